@@ -3,7 +3,7 @@
 //! # Financial Statement Converter CLI
 //!
 //! A command-line utility for converting financial statement files
-//! between standard formats such as **MT940**, **CAMT.053**, **XML**, and **CSV**.
+//! between standard formats such as **MT940**, **CAMT.053**, **XML**, **JSON**, **CSV**, **BAI2**, **CODA**, **OFX**, **QIF**, **CFONB 120**, **Norma 43**, **1C ClientBankExchange**, **MT950**, **MT900/MT910**, **CAMT.054**, **pain.001** and **XLSX**.
 //!
 //! ## Overview
 //!
@@ -14,8 +14,21 @@
 //!
 //! Supported conversions include:
 //! - MT940 ↔ CAMT.053
-//! - MT940 → XML
-//! - CAMT.053 → XML
+//! - MT940 ↔ XML
+//! - CAMT.053 ↔ XML
+//! - MT940 / CAMT.053 ↔ JSON, NDJSON
+//! - CAMT.053 ↔ CSV
+//! - CAMT.053 ↔ BAI2
+//! - CAMT.053 ↔ CODA
+//! - CAMT.053 ↔ OFX 1.x / 2.x
+//! - CAMT.053 ↔ QIF
+//! - CAMT.053 ↔ CFONB 120 / Norma 43
+//! - CAMT.053 ↔ 1C ClientBankExchange
+//! - MT950 ↔ CAMT.053
+//! - MT900/MT910 ↔ CAMT.054 ↔ CAMT.053
+//! - CAMT.053 ↔ pain.001 (.03 / .09)
+//! - CAMT.053 ↔ XLSX (one sheet per statement or per account)
+//! - any other pair, through CAMT.053
 //!
 //! ## Command-Line Usage
 //!
@@ -31,10 +44,35 @@
 //!
 //! | Flag | Description |
 //! |------|-------------|
-//! | `-i, --input <FILE>` | Input file (use `-` or omit for stdin). |
+//! | `-i, --input <FILE>` | Input file (use `-` or omit for stdin); repeatable with `--merge`. |
 //! | `-o, --output <FILE>` | Output file (use `-` or omit for stdout). |
-//! | `--in-format <FORMAT>` | Input format. One of: `mt940`, `camt053`, `xml`, `csv`. |
+//! | `--in-format <FORMAT>` | Input format. One of: `auto`, `mt940`, `camt053`, `xml`, `csv`, `json`, `ndjson`, `bai2`, `coda`, `ofx`, `qif`, `cfonb`, `norma43`, `1c`, `mt950`, `mt900`, `camt054`, `pain001`, `xlsx`, or a format added to the [`parser::registry`]. |
 //! | `--out-format <FORMAT>` | Output format (defaults to input format). |
+//! | `--xml-indent <N>` | Indent XML output (`camt053`, `camt054`, `pain001`, `xml`) by `N` spaces. |
+//! | `--xml-trim-text` | Trim whitespace around XML text, which indentation keeps. |
+//! | `--xml-declaration` | Prepend an XML declaration with UTF-8 encoding. |
+//! | `--xml-sort-attributes` | Order XML attributes by name. |
+//! | `--xml-c14n` | Write Exclusive XML Canonicalization, ready for signing. |
+//! | `--qif-dates <us\|eu>` | Order of day and month in QIF dates (default `us`). |
+//! | `--ofx-version <1\|2>` | OFX version to write: 1.02 in SGML or 2.2 in XML (default `2`). |
+//! | `--pain001-version <03\|09>` | pain.001 version to write (default `09`). |
+//! | `--xlsx-sheets <statement\|account>` | One XLSX sheet per statement or per account (default `statement`). |
+//! | `--lenient` | Skip entries, messages and fields that cannot be parsed, with a warning. |
+//! | `--from <DATE>`, `--to <DATE>` | Keep entries booked in this window (`YYYY-MM-DD`, inclusive). |
+//! | `--min-amount <AMOUNT>`, `--max-amount <AMOUNT>` | Keep entries whose amount is in this range. |
+//! | `--direction <credit\|debit>` | Keep only credits or only debits. |
+//! | `--currency <CCY>` | Keep entries in this currency (repeatable). |
+//! | `--tx-code <CODE>` | Keep entries with this bank transaction code (repeatable). |
+//! | `--counterparty <TEXT>` | Keep entries whose debtor or creditor name or account contains `TEXT`. |
+//! | `--narrative <REGEX>` | Keep entries whose remittance information matches `REGEX`. |
+//! | `--merge <day\|week\|month>` | Merge the statements of all inputs into one per account and period. |
+//! | `--split <account\|day>` | Write one file per account or calendar day, named after `-o`. |
+//! | `--diff <FILE>` | Compare the input with a corrected version in `FILE` and write the differences. |
+//! | `--diff-format <FORMAT>` | Format of the `--diff` file (defaults to `--in-format`). |
+//! | `--diff-json` | Write the differences as JSON instead of text. |
+//! | `--payment-status <FILE>` | Link the payment statuses of the pain.002 report in `FILE` to the input's entries. |
+//! | `--payment-status-json` | Write the linked payment statuses as JSON instead of text. |
+//! | `--flag-balances` | Keep the balances of filtered statements and report them as unreconciled instead of adjusting them. |
 //! | `-v, --verbose` | Enables detailed logging to stderr. |
 //!
 //! ## Behavior
@@ -51,21 +89,110 @@
 //! - I/O errors (file not found, permission denied, etc.)
 //! - Format parsing or conversion failures
 //!
+//! When the library reports where the input is broken, the offending input
+//! line is printed to stderr with a caret under the problem, e.g.
+//!
+//! ```text
+//! MT940 parsing error: Invalid field 61 at line 5, column 1 in field :61:
+//!  --> line 5, column 1, field :61:
+//!   |
+//! 5 | :61:2510XX1021D250,00NTRFNONREF//BKNTRX0001
+//!   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+//! ```
+//!
+//! With `--lenient` such problems are printed the same way as warnings,
+//! prefixed with what was left out, e.g. `warning (skipped entry): `, and
+//! the rest of the input is still converted.
+//!
+//! ## Filtering
+//!
+//! With any of the filter flags only the matching entries are written.
+//! The opening and closing balances of statements that lost entries are
+//! recomputed, or with `--flag-balances` left as they were; statements whose
+//! balances no longer match their entries are listed on stderr.
+//!
+//! ## Merging and splitting
+//!
+//! `--merge week` turns daily statements, from one or several `-i` inputs,
+//! into one statement per account and week (`month`: per month), opening
+//! with the first opening balance and closing with a recomputed balance.
+//!
+//! `--split account` writes one file per account, `--split day` one file per
+//! booking day; entries without a date go to the `undated` file. The files
+//! are named after `-o`: `{key}` in the name is replaced by the account or
+//! date, otherwise it is added before the extension, e.g. `-o out.xml` gives
+//! `out-DE89370400440532013000.xml`.
+//!
+//! ## Comparing statements
+//!
+//! `--diff new.xml` compares the input with `new.xml`, which may be in
+//! another format, and writes the added (`+`), removed (`-`) and modified
+//! (`~`) entries and the changed balances of every statement, e.g.
+//!
+//! ```text
+//! Statement STMT001 of account DE89370400440532013000: changed
+//!   balance CLBD: C 1500.50 EUR on 2023-10-05 -> C 1550.50 EUR on 2023-10-05
+//!   - 2023-10-04 D 50.00 EUR ref ACCTREF789
+//!   ~ 2023-10-05 C 100.00 EUR ref END2END123 "John Debtor"
+//!       amount: 100.00 -> 110.00
+//! ```
+//!
+//! ## Payment statuses
+//!
+//! `--payment-status status.xml` reads the pain.002 status report of the
+//! payments sent and finds each payment in the input statement by its
+//! end-to-end id. Rejected payments that were booked anyway and payments
+//! that never appeared are listed first, e.g.
+//!
+//! ```text
+//! Rejected but booked (1):
+//!   E2E-0002 50.00 EUR RJCT AC04 "Account closed"
+//!       statement STMT001 of account DE89370400440532013000: 2023-10-04 D 50.00 EUR ref E2E-0002
+//! Not booked (1):
+//!   E2E-0004 ACCP
+//! ```
+//!
 //! ## Notes
 //!
-//! - If input and output formats are identical, data is copied directly.
+//! - With `--in-format auto` the input format is detected from the first bytes of the input;
+//!   the output format then defaults to the detected one. QIF dates are read as `DD/MM/YYYY`
+//!   when one of them starts with a day above 12, unless `--qif-dates` is given.
+//! - If input and output formats are identical, data is copied directly, unless `--ofx-version`,
+//!   `--pain001-version` or `--xlsx-sheets` asks for a variant of the output.
 //! - The tool supports streaming I/O for large files.
+//! - Conversions without a direct converter keep each intermediate result in memory, up to
+//!   512 MiB.
 //! - Verbose mode (`-v`) prints progress messages to stderr.
 
 mod errors;
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::builder::{PossibleValue, PossibleValuesParser};
 use clap::{Arg, ArgAction, Command};
 use errors::CliError;
+use parser::FormatOptions;
+use parser::OfxVersion;
+use parser::Pain001Version;
+use parser::ParseMode;
+use parser::ParseWarning;
+use parser::ParserError;
+use parser::QifDateFormat;
+use parser::SheetLayout;
 use parser::SupportedFormats;
-use parser::converter::convert_streams::convert_streams;
+use parser::XmlWriteOptions;
+use parser::converter::convert_streams::{
+    convert_streams_filtered, convert_streams_with_mode, diff_streams, link_payment_statuses,
+    merge_streams, split_streams,
+};
+use parser::converter::graph::ConversionContext;
+use parser::detect_stream;
+use parser::filter::{BalancePolicy, Direction, EntryFilter};
+use parser::merge::{Period, SplitBy};
+use parser::registry;
 use std::fs::File;
-use std::io;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
 
 /// Entry point for the CLI application.
 ///
@@ -75,9 +202,30 @@ use std::io;
 /// # Command-line arguments
 ///
 /// - `-i, --input <FILE>`: Input file (use `-` or omit for stdin). Default: `-`.
+///   Repeatable with `--merge`.
 /// - `-o, --output <FILE>`: Output file (use `-` or omit for stdout). Default: `-`.
-/// - `--in-format <FORMAT>`: Input format (required). Options: `"mt940"`, `"camt053"`, `"xml"`, `"csv"`.
+/// - `--in-format <FORMAT>`: Input format (required). Options: `"auto"`, `"mt940"`, `"camt053"`, `"xml"`, `"csv"`, `"json"`, `"ndjson"`, `"bai2"`,
+///   `"coda"`, `"ofx"`, `"qif"`,
+///   `"cfonb"`, `"norma43"`, `"1c"`, `"mt950"`, `"mt900"`, `"camt054"`, `"pain001"`,
+///   `"xlsx"`
+///   and the names of registered formats, see [`format_values`].
+///   With `"auto"` the format is detected from the input, see [`detect_stream`].
 /// - `--out-format <FORMAT>`: Output format. Defaults to the same as input format.
+/// - `--xml-indent <N>`, `--xml-trim-text`, `--xml-declaration`, `--xml-sort-attributes`, `--xml-c14n`:
+///   Formatting of XML output, see [`XmlWriteOptions`].
+/// - `--qif-dates <us|eu>`, `--ofx-version <1|2>`, `--pain001-version <03|09>`,
+///   `--xlsx-sheets <statement|account>`: Variants of QIF, OFX, pain.001 and XLSX,
+///   see [`format_options`].
+/// - `--lenient`: Convert in [`ParseMode::Lenient`], printing warnings to stderr.
+/// - `--from`, `--to`, `--min-amount`, `--max-amount`, `--direction`, `--currency`,
+///   `--tx-code`, `--counterparty`, `--narrative`, `--flag-balances`:
+///   Keep only the matching entries, see [`entry_filter`].
+/// - `--merge <PERIOD>`: Merge statements into one per account and [`Period`].
+/// - `--split <BY>`: Write one file per account or day, see [`split_output_path`].
+/// - `--diff <FILE>`, `--diff-format <FORMAT>`, `--diff-json`: Compare the input
+///   with `FILE` instead of converting it, see [`write_diff`].
+/// - `--payment-status <FILE>`, `--payment-status-json`: Link the pain.002
+///   statuses in `FILE` to the input's entries, see [`write_payment_statuses`].
 /// - `-v, --verbose`: Enable verbose output.
 ///
 /// # Behavior
@@ -102,7 +250,8 @@ fn main() -> Result<(), CliError> {
                 .short('i')
                 .long("input")
                 .value_name("FILE")
-                .help("Input file (omit or use - for stdin)")
+                .help("Input file (omit or use - for stdin); repeat to merge several files")
+                .action(ArgAction::Append)
                 .default_value("-"),
         )
         .arg(
@@ -117,17 +266,195 @@ fn main() -> Result<(), CliError> {
             Arg::new("in-format")
                 .long("in-format")
                 .value_name("FORMAT")
-                .value_parser(["mt940", "camt053", "xml", "csv"])
+                .value_parser(PossibleValuesParser::new(format_values(true)))
                 .required(true)
-                .help("Input format"),
+                .help("Input format, or auto to detect it from the input"),
         )
         .arg(
             Arg::new("out-format")
                 .long("out-format")
                 .value_name("FORMAT")
-                .value_parser(["mt940", "camt053", "xml", "csv"])
+                .value_parser(PossibleValuesParser::new(format_values(false)))
                 .help("Output format (defaults to the same as input format)"),
         )
+        .arg(
+            Arg::new("xml-indent")
+                .long("xml-indent")
+                .value_name("N")
+                .value_parser(clap::value_parser!(usize))
+                .help("Indent XML output by N spaces"),
+        )
+        .arg(
+            Arg::new("xml-trim-text")
+                .long("xml-trim-text")
+                .action(ArgAction::SetTrue)
+                .help("Trim whitespace around XML text"),
+        )
+        .arg(
+            Arg::new("xml-declaration")
+                .long("xml-declaration")
+                .action(ArgAction::SetTrue)
+                .help("Prepend an XML declaration with UTF-8 encoding"),
+        )
+        .arg(
+            Arg::new("xml-sort-attributes")
+                .long("xml-sort-attributes")
+                .action(ArgAction::SetTrue)
+                .help("Order XML attributes by name"),
+        )
+        .arg(
+            Arg::new("xml-c14n")
+                .long("xml-c14n")
+                .action(ArgAction::SetTrue)
+                .help("Write Exclusive XML Canonicalization (C14N)"),
+        )
+        .arg(
+            Arg::new("qif-dates")
+                .long("qif-dates")
+                .value_parser(["us", "eu"])
+                .help("Order of day and month in QIF dates (default us)"),
+        )
+        .arg(
+            Arg::new("ofx-version")
+                .long("ofx-version")
+                .value_parser(["1", "2"])
+                .help("OFX version to write, 1 for SGML or 2 for XML (default 2)"),
+        )
+        .arg(
+            Arg::new("pain001-version")
+                .long("pain001-version")
+                .value_parser(["03", "09"])
+                .help("pain.001 version to write, pain.001.001.03 or .09 (default 09)"),
+        )
+        .arg(
+            Arg::new("xlsx-sheets")
+                .long("xlsx-sheets")
+                .value_parser(["statement", "account"])
+                .help("Write one XLSX sheet per statement or per account (default statement)"),
+        )
+        .arg(
+            Arg::new("lenient")
+                .long("lenient")
+                .action(ArgAction::SetTrue)
+                .help("Skip what cannot be parsed with a warning instead of failing"),
+        )
+        .arg(
+            Arg::new("from")
+                .long("from")
+                .value_name("DATE")
+                .value_parser(clap::value_parser!(NaiveDate))
+                .help("Keep entries booked on or after DATE (YYYY-MM-DD)"),
+        )
+        .arg(
+            Arg::new("to")
+                .long("to")
+                .value_name("DATE")
+                .value_parser(clap::value_parser!(NaiveDate))
+                .help("Keep entries booked on or before DATE (YYYY-MM-DD)"),
+        )
+        .arg(
+            Arg::new("min-amount")
+                .long("min-amount")
+                .value_name("AMOUNT")
+                .value_parser(clap::value_parser!(f64))
+                .help("Keep entries of at least AMOUNT"),
+        )
+        .arg(
+            Arg::new("max-amount")
+                .long("max-amount")
+                .value_name("AMOUNT")
+                .value_parser(clap::value_parser!(f64))
+                .help("Keep entries of at most AMOUNT"),
+        )
+        .arg(
+            Arg::new("direction")
+                .long("direction")
+                .value_parser(["credit", "debit"])
+                .help("Keep only credits or only debits"),
+        )
+        .arg(
+            Arg::new("currency")
+                .long("currency")
+                .value_name("CCY")
+                .action(ArgAction::Append)
+                .help("Keep entries in CCY (repeatable)"),
+        )
+        .arg(
+            Arg::new("tx-code")
+                .long("tx-code")
+                .value_name("CODE")
+                .action(ArgAction::Append)
+                .help("Keep entries with bank transaction code CODE, e.g. NTRF or PMNT (repeatable)"),
+        )
+        .arg(
+            Arg::new("counterparty")
+                .long("counterparty")
+                .value_name("TEXT")
+                .help("Keep entries whose debtor or creditor name or account contains TEXT"),
+        )
+        .arg(
+            Arg::new("narrative")
+                .long("narrative")
+                .value_name("REGEX")
+                .help("Keep entries whose remittance information matches REGEX"),
+        )
+        .arg(
+            Arg::new("merge")
+                .long("merge")
+                .value_name("PERIOD")
+                .value_parser(["day", "week", "month"])
+                .conflicts_with("split")
+                .help("Merge the statements of all inputs into one per account and period"),
+        )
+        .arg(
+            Arg::new("split")
+                .long("split")
+                .value_name("BY")
+                .value_parser(["account", "day"])
+                .help("Write one file per account or calendar day, named after the output file"),
+        )
+        .arg(
+            Arg::new("diff")
+                .long("diff")
+                .value_name("FILE")
+                .conflicts_with_all(["out-format", "merge", "split"])
+                .help("Compare the input with a corrected version in FILE and write the differences"),
+        )
+        .arg(
+            Arg::new("diff-format")
+                .long("diff-format")
+                .value_name("FORMAT")
+                .value_parser(PossibleValuesParser::new(format_values(true)))
+                .requires("diff")
+                .help("Format of the --diff file (defaults to --in-format)"),
+        )
+        .arg(
+            Arg::new("diff-json")
+                .long("diff-json")
+                .action(ArgAction::SetTrue)
+                .requires("diff")
+                .help("Write the differences as JSON instead of text"),
+        )
+        .arg(
+            Arg::new("payment-status")
+                .long("payment-status")
+                .value_name("FILE")
+                .conflicts_with_all(["out-format", "merge", "split", "diff"])
+                .help("Link the payment statuses of the pain.002 report in FILE to the input's entries"),
+        )
+        .arg(
+            Arg::new("payment-status-json")
+                .long("payment-status-json")
+                .action(ArgAction::SetTrue)
+                .requires("payment-status")
+                .help("Write the linked payment statuses as JSON instead of text"),
+        )
+        .arg(
+            Arg::new("flag-balances")
+                .long("flag-balances")
+                .action(ArgAction::SetTrue)
+                .help("Report the balances of filtered statements as unreconciled instead of adjusting them"),
+        )
         .arg(
             Arg::new("verbose")
                 .short('v')
@@ -137,27 +464,52 @@ fn main() -> Result<(), CliError> {
         )
         .get_matches();
 
-    let input_path = matches
-        .get_one::<String>("input")
+    let input_paths: Vec<&String> = matches
+        .get_many::<String>("input")
+        .into_iter()
+        .flatten()
+        .collect();
+    let input_path = input_paths
+        .first()
         .ok_or_else(|| CliError::ArgsError("Failed to parse 'input' argument".to_string()))?;
     let output_path = matches
         .get_one::<String>("output")
         .ok_or_else(|| CliError::ArgsError("Failed to parse 'output' argument".to_string()))?;
 
+    let verbose = matches.get_flag("verbose");
     let in_format_str = matches
         .get_one::<String>("in-format")
         .ok_or_else(|| CliError::ArgsError("Missing 'in-format' argument".to_string()))?;
-    let in_format: SupportedFormats = in_format_str
-        .parse()
-        .map_err(|e| CliError::ArgsError(format!("Invalid format: {}", e)))?;
-
-    let out_format_str = matches
-        .get_one::<String>("out-format")
-        .unwrap_or(&in_format_str);
-    let out_format: SupportedFormats = out_format_str
-        .parse()
-        .map_err(|e| CliError::ArgsError(format!("Invalid format: {}", e)))?;
-    let verbose = matches.get_flag("verbose");
+    let input_stream = create_reader(input_path)?;
+    let (in_format, detected_options, input_stream) = if in_format_str == "auto" {
+        detect_input_format(input_stream, verbose)?
+    } else {
+        let in_format: SupportedFormats = in_format_str
+            .parse()
+            .map_err(|e| CliError::ArgsError(format!("Invalid format: {}", e)))?;
+        (in_format, FormatOptions::default(), input_stream)
+    };
+
+    let out_format: SupportedFormats = match matches.get_one::<String>("out-format") {
+        Some(out_format_str) => out_format_str
+            .parse()
+            .map_err(|e| CliError::ArgsError(format!("Invalid format: {}", e)))?,
+        None => in_format,
+    };
+    let mode = if matches.get_flag("lenient") {
+        ParseMode::Lenient
+    } else {
+        ParseMode::Strict
+    };
+
+    let xml_options = XmlWriteOptions::new()
+        .indent(matches.get_one::<usize>("xml-indent").copied().unwrap_or(0))
+        .trim_text(matches.get_flag("xml-trim-text"))
+        .declaration(matches.get_flag("xml-declaration"))
+        .sort_attributes(matches.get_flag("xml-sort-attributes"))
+        .canonical(matches.get_flag("xml-c14n"));
+    let format_options = format_options(&matches, detected_options)?;
+    let context = ConversionContext::new(mode).with_options(format_options);
 
     if verbose {
         eprintln!("Reading from: {}", input_path);
@@ -166,11 +518,150 @@ fn main() -> Result<(), CliError> {
         eprintln!("Writing to: {}", output_path);
     }
 
-    let input_stream = create_reader(input_path)?;
-    let output_stream = create_writer(output_path)?;
+    let filter = entry_filter(&matches)?;
+    let merge = matches
+        .get_one::<String>("merge")
+        .map(|period| period.parse::<Period>())
+        .transpose()
+        .map_err(CliError::ArgsError)?;
+    let split = matches
+        .get_one::<String>("split")
+        .map(|by| by.parse::<SplitBy>())
+        .transpose()
+        .map_err(CliError::ArgsError)?;
+    if input_paths.len() > 1 && merge.is_none() {
+        return Err(CliError::ArgsError(
+            "Several input files can only be given with --merge".to_string(),
+        ));
+    }
+    let diff = matches.get_one::<String>("diff");
+    let payment_status = matches.get_one::<String>("payment-status");
+    if !filter.is_empty()
+        && (merge.is_some() || split.is_some() || diff.is_some() || payment_status.is_some())
+    {
+        return Err(CliError::ArgsError(
+            "Filters cannot be combined with --merge, --split, --diff or --payment-status"
+                .to_string(),
+        ));
+    }
+    if split.is_some() && output_path == "-" {
+        return Err(CliError::ArgsError(
+            "--split needs an output file (-o) to name the parts after".to_string(),
+        ));
+    }
+    if let Some(diff_path) = diff {
+        let diff_format = matches
+            .get_one::<String>("diff-format")
+            .unwrap_or(in_format_str);
+        return write_diff(
+            (input_stream, in_format),
+            diff_path,
+            diff_format,
+            output_path,
+            matches.get_flag("diff-json"),
+            context,
+            verbose,
+        );
+    }
+    if let Some(status_path) = payment_status {
+        return write_payment_statuses(
+            (input_stream, in_format),
+            status_path,
+            output_path,
+            matches.get_flag("payment-status-json"),
+            context,
+        );
+    }
+
+    // Input lines can be shown under errors only when there is one input
+    let error_path = if input_paths.len() > 1 {
+        "-"
+    } else {
+        input_path.as_str()
+    };
+
     // Process conversion
-    convert_streams(input_stream, in_format, output_stream, out_format)
-        .map_err(|e| CliError::ConversionError(e.to_string()))?;
+    let (warnings, report) = if let Some(period) = merge {
+        let mut input_streams = vec![input_stream];
+        for path in &input_paths[1..] {
+            input_streams.push(create_reader(path)?);
+        }
+        let warnings = merge_streams(
+            input_streams,
+            in_format,
+            create_writer(output_path)?,
+            out_format,
+            &xml_options,
+            context,
+            period,
+        )
+        .map_err(|e| conversion_error(error_path, e))?;
+        (warnings, None)
+    } else if let Some(by) = split {
+        let warnings = split_streams(
+            input_stream,
+            in_format,
+            out_format,
+            &xml_options,
+            context,
+            by,
+            |key| {
+                let path = split_output_path(output_path, key);
+                if verbose {
+                    eprintln!("Writing {} to: {}", key, path);
+                }
+                Ok(Box::new(File::create(path)?) as Box<dyn std::io::Write>)
+            },
+        )
+        .map_err(|e| conversion_error(error_path, e))?;
+        (warnings, None)
+    } else if filter.is_empty() {
+        let output_stream = create_writer(output_path)?;
+        let warnings = convert_streams_with_mode(
+            input_stream,
+            in_format,
+            output_stream,
+            out_format,
+            &xml_options,
+            context,
+        )
+        .map_err(|e| conversion_error(error_path, e))?;
+        (warnings, None)
+    } else {
+        let output_stream = create_writer(output_path)?;
+        let (warnings, report) = convert_streams_filtered(
+            input_stream,
+            in_format,
+            output_stream,
+            out_format,
+            &xml_options,
+            context,
+            &filter,
+        )
+        .map_err(|e| conversion_error(error_path, e))?;
+        (warnings, Some(report))
+    };
+
+    for warning in warnings {
+        print_warning(error_path, warning);
+    }
+
+    if verbose {
+        for stmt in report.iter().flat_map(|report| report.statements.iter()) {
+            eprintln!(
+                "Statement {}: {} entries kept, {} removed",
+                stmt.id.as_deref().unwrap_or("-"),
+                stmt.kept,
+                stmt.removed
+            );
+        }
+    }
+    for stmt in report.iter().flat_map(|report| report.unreconciled()) {
+        eprintln!(
+            "warning: balances of statement {} no longer match its entries",
+            stmt.id.as_deref().unwrap_or("-")
+        );
+    }
 
     if verbose {
         eprintln!("Conversion completed successfully");
@@ -179,6 +670,254 @@ fn main() -> Result<(), CliError> {
     Ok(())
 }
 
+/// Compares the input with the corrected version in `diff_path` and writes
+/// the differences to `output_path`, as text or with `json` as JSON.
+///
+/// With `diff_format` `"auto"` the format of the corrected version is
+/// detected from its content.
+///
+/// # Errors
+///
+/// Returns a [`CliError`] if either file cannot be read or parsed, or the
+/// output cannot be written.
+fn write_diff(
+    (input_stream, in_format): (Box<dyn std::io::Read>, SupportedFormats),
+    diff_path: &str,
+    diff_format: &str,
+    output_path: &str,
+    json: bool,
+    context: ConversionContext,
+    verbose: bool,
+) -> Result<(), CliError> {
+    let diff_stream = create_reader(diff_path)?;
+    let (diff_format, diff_stream) = if diff_format == "auto" {
+        let (format, _, diff_stream) = detect_input_format(diff_stream, verbose)?;
+        (format, diff_stream)
+    } else {
+        let format: SupportedFormats = diff_format
+            .parse()
+            .map_err(|e| CliError::ArgsError(format!("Invalid format: {}", e)))?;
+        (format, diff_stream)
+    };
+
+    // Errors may be in either file, so no input line is shown under them
+    let (changes, warnings) =
+        diff_streams(input_stream, in_format, diff_stream, diff_format, context)
+            .map_err(|e| conversion_error("-", e))?;
+    for warning in warnings {
+        print_warning("-", warning);
+    }
+
+    let mut output_stream = create_writer(output_path)?;
+    if json {
+        writeln!(output_stream, "{}", changes.to_json())?;
+    } else {
+        write!(output_stream, "{}", changes)?;
+    }
+    output_stream.flush()?;
+    Ok(())
+}
+
+/// Links the payment statuses of the pain.002 report in `status_path` to
+/// the entries of the input and writes them to `output_path`, as text or
+/// with `json` as JSON.
+///
+/// # Errors
+///
+/// Returns a [`CliError`] if either file cannot be read or parsed, or the
+/// output cannot be written.
+fn write_payment_statuses(
+    (input_stream, in_format): (Box<dyn std::io::Read>, SupportedFormats),
+    status_path: &str,
+    output_path: &str,
+    json: bool,
+    context: ConversionContext,
+) -> Result<(), CliError> {
+    let status_stream = create_reader(status_path)?;
+    let (links, warnings) = link_payment_statuses(status_stream, input_stream, in_format, context)
+        .map_err(|e| conversion_error("-", e))?;
+    for warning in warnings {
+        print_warning("-", warning);
+    }
+
+    let mut output_stream = create_writer(output_path)?;
+    if json {
+        writeln!(output_stream, "{}", links.to_json())?;
+    } else {
+        write!(output_stream, "{}", links)?;
+    }
+    output_stream.flush()?;
+    Ok(())
+}
+
+/// The file a part of `--split` is written to: `{key}` in `output_path`
+/// replaced by `key`, or `key` added before the extension.
+///
+/// Characters of the key other than letters, digits, `-` and `_` are
+/// replaced by `_`.
+fn split_output_path(output_path: &str, key: &str) -> String {
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if output_path.contains("{key}") {
+        return output_path.replace("{key}", &key);
+    }
+    let path = Path::new(output_path);
+    match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => path
+            .with_file_name(format!(
+                "{}-{}.{}",
+                stem.to_string_lossy(),
+                key,
+                extension.to_string_lossy()
+            ))
+            .to_string_lossy()
+            .to_string(),
+        _ => format!("{}-{}", output_path, key),
+    }
+}
+
+/// Turns an error of the conversion into a [`CliError`], printing where the
+/// input is broken with a caret under the problem.
+fn conversion_error(input_path: &str, error: ParserError) -> CliError {
+    match error {
+        ParserError::Located(mut diagnostic) => {
+            if let Some(line) = input_line(input_path, diagnostic.span.line) {
+                diagnostic.snippet = Some(line);
+            }
+            eprint!("{}", diagnostic.render(""));
+            CliError::ParserError(diagnostic.to_string())
+        }
+        e => CliError::ConversionError(e.to_string()),
+    }
+}
+
+/// Builds the [`EntryFilter`] of the filter flags; it is empty when none is given.
+///
+/// # Errors
+///
+/// Returns a [`CliError::ArgsError`] if `--narrative` is not a valid regular expression.
+fn entry_filter(matches: &clap::ArgMatches) -> Result<EntryFilter, CliError> {
+    let mut filter = EntryFilter::new();
+    if let Some(date) = matches.get_one::<NaiveDate>("from") {
+        filter = filter.from_date(*date);
+    }
+    if let Some(date) = matches.get_one::<NaiveDate>("to") {
+        filter = filter.to_date(*date);
+    }
+    if let Some(amount) = matches.get_one::<f64>("min-amount") {
+        filter = filter.min_amount(*amount);
+    }
+    if let Some(amount) = matches.get_one::<f64>("max-amount") {
+        filter = filter.max_amount(*amount);
+    }
+    if let Some(direction) = matches.get_one::<String>("direction") {
+        let direction: Direction = direction.parse().map_err(CliError::ArgsError)?;
+        filter = filter.direction(direction);
+    }
+    for currency in matches.get_many::<String>("currency").into_iter().flatten() {
+        filter = filter.currency(currency);
+    }
+    for code in matches.get_many::<String>("tx-code").into_iter().flatten() {
+        filter = filter.transaction_code(code);
+    }
+    if let Some(text) = matches.get_one::<String>("counterparty") {
+        filter = filter.counterparty(text);
+    }
+    if let Some(pattern) = matches.get_one::<String>("narrative") {
+        filter = filter
+            .narrative(pattern)
+            .map_err(|e| CliError::ArgsError(e.to_string()))?;
+    }
+    if matches.get_flag("flag-balances") {
+        filter = filter.balances(BalancePolicy::Flag);
+    }
+    Ok(filter)
+}
+
+/// The [`FormatOptions`] of the `--qif-dates`, `--ofx-version`,
+/// `--pain001-version` and `--xlsx-sheets` flags, replacing those `detected`
+/// from the input.
+///
+/// # Errors
+///
+/// Returns a [`CliError::ArgsError`] if a flag has an unknown value.
+fn format_options(
+    matches: &clap::ArgMatches,
+    detected: FormatOptions,
+) -> Result<FormatOptions, CliError> {
+    let mut options = detected;
+    if let Some(order) = matches.get_one::<String>("qif-dates") {
+        let order: QifDateFormat = order.parse().map_err(CliError::ArgsError)?;
+        options = options.qif_date_format(order);
+    }
+    if let Some(version) = matches.get_one::<String>("ofx-version") {
+        let version: OfxVersion = version.parse().map_err(CliError::ArgsError)?;
+        options = options.ofx_version(version);
+    }
+    if let Some(version) = matches.get_one::<String>("pain001-version") {
+        let version: Pain001Version = version.parse().map_err(CliError::ArgsError)?;
+        options = options.pain001_version(version);
+    }
+    if let Some(layout) = matches.get_one::<String>("xlsx-sheets") {
+        let layout: SheetLayout = layout.parse().map_err(CliError::ArgsError)?;
+        options = options.xlsx_layout(layout);
+    }
+    Ok(options)
+}
+
+/// Values accepted by `--in-format` (with `auto`) and `--out-format`: the names
+/// and aliases of all formats in the [`registry`], including registered ones.
+fn format_values(auto: bool) -> Vec<PossibleValue> {
+    let formats = registry::formats();
+    let names = formats
+        .iter()
+        .map(|format| PossibleValue::new(format.name()).aliases(format.aliases().iter().copied()));
+    let auto = auto.then(|| PossibleValue::new("auto").help("Detect the format from the input"));
+    auto.into_iter().chain(names).collect()
+}
+
+/// Detects the input format from the first bytes of the input.
+///
+/// Returns the format and the options to read it with, e.g. the order of
+/// QIF dates, together with a reader that still yields the whole input.
+/// A guess with low confidence is reported on stderr.
+///
+/// # Errors
+///
+/// Returns a [`CliError::ArgsError`] if the format is not recognised or cannot be converted.
+fn detect_input_format(
+    input_stream: Box<dyn std::io::Read>,
+    verbose: bool,
+) -> Result<(SupportedFormats, FormatOptions, Box<dyn std::io::Read>), CliError> {
+    let (detection, input_stream) =
+        detect_stream(input_stream).map_err(|e| CliError::ConversionError(e.to_string()))?;
+    let detection = detection.ok_or_else(|| {
+        CliError::ArgsError("Cannot detect the input format, use --in-format".to_string())
+    })?;
+    let in_format = detection.format.supported_format().ok_or_else(|| {
+        CliError::ArgsError(format!(
+            "Detected {} input, which cannot be converted",
+            detection.format
+        ))
+    })?;
+
+    if verbose || detection.confidence < 0.5 {
+        eprintln!(
+            "Detected input format: {} (confidence {:.2})",
+            detection.format, detection.confidence
+        );
+    }
+    Ok((in_format, detection.options, input_stream))
+}
+
 /// Creates a boxed reader from the specified input path.
 ///
 /// If the input path is `"-"`, returns a reader for stdin; otherwise, opens the file.
@@ -210,3 +949,32 @@ fn create_writer(output_path: &str) -> Result<Box<dyn std::io::Write>, CliError>
         Ok(Box::new(file))
     }
 }
+
+/// Prints a warning of a lenient conversion to stderr, with the input line it refers to.
+fn print_warning(input_path: &str, mut warning: ParseWarning) {
+    if let ParserError::Located(diagnostic) = &mut warning.error
+        && let Some(line) = input_line(input_path, diagnostic.span.line)
+    {
+        diagnostic.snippet = Some(line);
+    }
+    eprint!("{}", warning.render(""));
+}
+
+/// Reads line `number` (1-based) of the input file, to show it under a parsing error.
+///
+/// Returns `None` for stdin, which cannot be read again.
+fn input_line(input_path: &str, number: usize) -> Option<String> {
+    if input_path == "-" {
+        return None;
+    }
+    let file = File::open(input_path).ok()?;
+    let line = BufReader::new(file)
+        .split(b'\n')
+        .nth(number.checked_sub(1)?)?
+        .ok()?;
+    Some(
+        String::from_utf8_lossy(&line)
+            .trim_end_matches('\r')
+            .to_string(),
+    )
+}
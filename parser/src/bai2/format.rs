@@ -0,0 +1,185 @@
+/// A BAI2 cash management file: the `01` file header and its groups.
+///
+/// Trailers (`49`, `98`, `99`) are not kept: the reader checks their control
+/// totals and record counts, and the writer computes them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Bai2 {
+    pub header: FileHeader,
+    pub groups: Vec<Group>,
+}
+
+/// `01` record: sender, receiver, creation date and time, file id.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FileHeader {
+    pub sender_id: String,
+    pub receiver_id: String,
+    /// `YYMMDD`.
+    pub creation_date: String,
+    /// `HHMM`, may be empty.
+    pub creation_time: String,
+    pub file_id: String,
+    pub record_length: String,
+    pub block_size: String,
+    /// Always `2` for BAI2.
+    pub version: String,
+}
+
+/// `02` record with the accounts of one originator and as-of date.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Group {
+    pub ultimate_receiver_id: String,
+    pub originator_id: String,
+    /// `1` update, `2` deletion, `3` correction, `4` test only.
+    pub status: String,
+    /// `YYMMDD`.
+    pub as_of_date: String,
+    /// `HHMM`, may be empty.
+    pub as_of_time: String,
+    /// Currency of the accounts that do not name their own, USD when empty.
+    pub currency: String,
+    /// `1` … `4`: interim or final, previous-day or same-day data.
+    pub as_of_date_modifier: String,
+    pub accounts: Vec<Bai2Account>,
+}
+
+/// `03` record with its summaries, followed by the account's `16` records.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Bai2Account {
+    pub account_number: String,
+    /// Empty for the currency of the group.
+    pub currency: String,
+    pub summaries: Vec<Summary>,
+    pub transactions: Vec<Transaction>,
+}
+
+/// Status (balance) or summary amount of an `03` record, e.g. `010` opening ledger.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Summary {
+    pub type_code: String,
+    /// In minor units of the account currency, signed for balances.
+    pub amount: Option<i64>,
+    pub item_count: Option<u32>,
+    pub funds_type: Option<FundsType>,
+}
+
+/// `16` transaction detail record, with the text of its `88` continuations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Transaction {
+    pub type_code: String,
+    /// In minor units of the account currency.
+    pub amount: i64,
+    pub funds_type: Option<FundsType>,
+    pub bank_reference: String,
+    pub customer_reference: String,
+    /// The text of the `16` record, then one line per `88` record.
+    pub text: Vec<String>,
+}
+
+/// When the funds are available.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FundsType {
+    /// `0`
+    Immediate,
+    /// `1`
+    OneDay,
+    /// `2`
+    TwoOrMoreDays,
+    /// `Z`
+    Unknown,
+    /// `S`: amounts available immediately, in one day and in two or more days.
+    Distributed {
+        immediate: i64,
+        one_day: i64,
+        two_or_more_days: i64,
+    },
+    /// `V`: value date `YYMMDD` and optional time `HHMM`.
+    ValueDated { date: String, time: String },
+    /// `D`: amounts by number of days until they are available.
+    DistributedByDays(Vec<(u32, i64)>),
+}
+
+/// Type codes mapped to ISO 20022 bank transaction codes (domain, family,
+/// sub-family).
+///
+/// `100`–`399` are credits and `400`–`699` debits. Codes without an entry
+/// map to nothing; CAMT.053 codes without an entry map to `399` or `699`,
+/// miscellaneous credit or debit. Where several type codes share an ISO code
+/// the first one is written.
+pub(crate) const TYPE_CODES: [(&str, &str, &str, &str); 26] = [
+    // Lockbox deposit
+    ("115", "PMNT", "LBOX", "LBDP"),
+    // ACH credit received
+    ("142", "PMNT", "RCDT", "ACDT"),
+    // Preauthorized ACH credit
+    ("165", "PMNT", "RCDT", "ACDT"),
+    // Miscellaneous ACH credit
+    ("169", "PMNT", "RCDT", "ACDT"),
+    // Check deposit package
+    ("175", "PMNT", "RCHQ", "CCHQ"),
+    // Incoming money transfer
+    ("195", "PMNT", "RCDT", "DMCT"),
+    // Book transfer credit
+    ("206", "PMNT", "RCDT", "BOOK"),
+    // Incoming international money transfer
+    ("208", "PMNT", "RCDT", "XBCT"),
+    // ZBA credit
+    ("275", "CAMT", "ACCB", "ZABA"),
+    // Commercial deposit
+    ("301", "PMNT", "CNTR", "CDPT"),
+    // Interest credit
+    ("354", "ACMT", "MCOP", "INTR"),
+    // Credit adjustment
+    ("357", "ACMT", "MCOP", "ADJT"),
+    // Miscellaneous credit
+    ("399", "PMNT", "MCOP", "OTHR"),
+    // ACH debit received
+    ("451", "PMNT", "RDDT", "PMDD"),
+    // Preauthorized ACH debit
+    ("455", "PMNT", "RDDT", "PMDD"),
+    // Miscellaneous ACH debit
+    ("469", "PMNT", "RDDT", "PMDD"),
+    // Check paid
+    ("475", "PMNT", "ICHQ", "CCHQ"),
+    // Outgoing money transfer
+    ("495", "PMNT", "ICDT", "DMCT"),
+    // Book transfer debit
+    ("506", "PMNT", "ICDT", "BOOK"),
+    // Outgoing international money transfer
+    ("508", "PMNT", "ICDT", "XBCT"),
+    // Deposited item returned
+    ("555", "PMNT", "RCHQ", "UPCQ"),
+    // ZBA debit
+    ("575", "CAMT", "ACCB", "ZABA"),
+    // Interest debit
+    ("654", "ACMT", "MDOP", "INTR"),
+    // Debit adjustment
+    ("657", "ACMT", "MDOP", "ADJT"),
+    // Miscellaneous fees
+    ("698", "ACMT", "MDOP", "CHRG"),
+    // Miscellaneous debit
+    ("699", "PMNT", "MDOP", "OTHR"),
+];
+
+/// Status type codes mapped to CAMT.053 balance types.
+pub(crate) const BALANCE_CODES: [(&str, &str); 6] = [
+    ("010", "OPBD"),
+    ("015", "CLBD"),
+    ("030", "ITBD"),
+    ("040", "OPAV"),
+    ("045", "CLAV"),
+    ("060", "ITAV"),
+];
+
+/// Whether a detail type code is a credit (`Some(true)`) or a debit.
+pub(crate) fn is_credit(type_code: &str) -> Option<bool> {
+    match type_code.parse::<u16>().ok()? {
+        100..=399 => Some(true),
+        400..=699 => Some(false),
+        _ => None,
+    }
+}
+
+/// Status type codes, `001`–`099`, report balances rather than totals.
+pub(crate) fn is_status(type_code: &str) -> bool {
+    matches!(type_code.parse::<u16>(), Ok(1..=99))
+}
@@ -0,0 +1,591 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::bai2::format::*;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+
+/// A physical record with the `88` records continuing it.
+struct Record<'a> {
+    code: &'a str,
+    /// Everything after the record code and its comma.
+    body: &'a str,
+    continuations: Vec<&'a str>,
+    /// Byte offset and length of the first physical record in the input.
+    offset: usize,
+    len: usize,
+}
+
+impl<'a> Record<'a> {
+    fn physical_records(&self) -> u64 {
+        1 + self.continuations.len() as u64
+    }
+
+    /// The fields of the record and its continuations as one list.
+    fn joined(&self) -> String {
+        std::iter::once(self.body)
+            .chain(self.continuations.iter().copied())
+            .map(|part| part.strip_suffix('/').unwrap_or(part))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn error(&self, data: &str, message: impl Into<String>) -> ParserError {
+        ParserError::Bai2(message.into()).at_text(
+            data,
+            self.offset,
+            self.len,
+            &SourceSpan::default(),
+            None,
+        )
+    }
+}
+
+/// Splits the input into records, attaching `88` continuations to the record before them.
+fn records(data: &str) -> Result<Vec<Record<'_>>, ParserError> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut offset = 0;
+    for line in data.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end();
+        if line.is_empty() {
+            continue;
+        }
+        let (code, body) = line.split_once(',').unwrap_or((line, ""));
+        let code = code.trim_start_matches('\u{feff}').trim();
+        if code == "88" {
+            let Some(last) = records.last_mut() else {
+                return Err(ParserError::Bai2(
+                    "Continuation record without a record to continue".to_string(),
+                )
+                .at_text(data, start, line.len(), &SourceSpan::default(), None));
+            };
+            last.continuations.push(body);
+            continue;
+        }
+        records.push(Record {
+            code,
+            body,
+            continuations: Vec::new(),
+            offset: start,
+            len: line.len(),
+        });
+    }
+    Ok(records)
+}
+
+/// Comma-separated fields of a record, read one at a time.
+struct Fields<'a> {
+    rest: Option<&'a str>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(text: &'a str) -> Self {
+        Fields { rest: Some(text) }
+    }
+
+    /// The next field, empty when there are no more.
+    fn next(&mut self) -> &'a str {
+        match self.rest.take() {
+            None => "",
+            Some(rest) => match rest.split_once(',') {
+                Some((field, rest)) => {
+                    self.rest = Some(rest);
+                    field.trim()
+                }
+                None => rest.strip_suffix('/').unwrap_or(rest).trim(),
+            },
+        }
+    }
+
+    /// The field `n` places further on.
+    fn nth(&mut self, n: usize) -> &'a str {
+        for _ in 0..n {
+            self.next();
+        }
+        self.next()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rest.is_none()
+    }
+
+    /// Everything not read yet, commas included.
+    fn remainder(&mut self) -> &'a str {
+        self.rest.take().unwrap_or("")
+    }
+}
+
+fn amount(field: &str) -> Result<Option<i64>, String> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    let digits = field.strip_prefix(['+', '-']).unwrap_or(field);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid amount '{}'", field));
+    }
+    field
+        .trim_start_matches('+')
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("Amount '{}' is too large", field))
+}
+
+fn number<T: std::str::FromStr>(field: &str, what: &str) -> Result<Option<T>, String> {
+    if field.is_empty() {
+        return Ok(None);
+    }
+    field
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("Invalid {} '{}'", what, field))
+}
+
+fn date(field: &str, what: &str) -> Result<String, String> {
+    if field.len() == 6 && field.bytes().all(|b| b.is_ascii_digit()) {
+        Ok(field.to_string())
+    } else {
+        Err(format!("Invalid {} '{}', expected YYMMDD", what, field))
+    }
+}
+
+/// The funds type field and the fields that depend on it.
+fn funds_type(fields: &mut Fields) -> Result<Option<FundsType>, String> {
+    let funds_amount = |field: &str| amount(field).map(Option::unwrap_or_default);
+    Ok(Some(match fields.next() {
+        "" => return Ok(None),
+        "0" => FundsType::Immediate,
+        "1" => FundsType::OneDay,
+        "2" => FundsType::TwoOrMoreDays,
+        "Z" => FundsType::Unknown,
+        "S" => FundsType::Distributed {
+            immediate: funds_amount(fields.next())?,
+            one_day: funds_amount(fields.next())?,
+            two_or_more_days: funds_amount(fields.next())?,
+        },
+        "V" => FundsType::ValueDated {
+            date: date(fields.next(), "value date")?,
+            time: fields.next().to_string(),
+        },
+        "D" => {
+            let count: u32 = number(fields.next(), "distribution count")?.unwrap_or_default();
+            let mut distribution = Vec::new();
+            for _ in 0..count {
+                // The count comes from the input, the fields bound it
+                if fields.is_empty() {
+                    return Err(format!(
+                        "Distribution count {} exceeds the fields of the record",
+                        count
+                    ));
+                }
+                let days = number(fields.next(), "availability days")?.unwrap_or_default();
+                distribution.push((days, funds_amount(fields.next())?));
+            }
+            FundsType::DistributedByDays(distribution)
+        }
+        other => return Err(format!("Unknown funds type '{}'", other)),
+    }))
+}
+
+fn file_header(record: &Record) -> Result<FileHeader, String> {
+    let joined = record.joined();
+    let mut fields = Fields::new(&joined);
+    let header = FileHeader {
+        sender_id: fields.next().to_string(),
+        receiver_id: fields.next().to_string(),
+        creation_date: date(fields.next(), "file creation date")?,
+        creation_time: fields.next().to_string(),
+        file_id: fields.next().to_string(),
+        record_length: fields.next().to_string(),
+        block_size: fields.next().to_string(),
+        version: fields.next().to_string(),
+    };
+    if header.version != "2" {
+        return Err(format!("Unsupported BAI version '{}'", header.version));
+    }
+    Ok(header)
+}
+
+fn group_header(record: &Record) -> Result<Group, String> {
+    let joined = record.joined();
+    let mut fields = Fields::new(&joined);
+    Ok(Group {
+        ultimate_receiver_id: fields.next().to_string(),
+        originator_id: fields.next().to_string(),
+        status: fields.next().to_string(),
+        as_of_date: date(fields.next(), "as-of date")?,
+        as_of_time: fields.next().to_string(),
+        currency: fields.next().to_string(),
+        as_of_date_modifier: fields.next().to_string(),
+        accounts: Vec::new(),
+    })
+}
+
+fn account_identifier(record: &Record) -> Result<Bai2Account, String> {
+    let joined = record.joined();
+    let mut fields = Fields::new(&joined);
+    let account_number = fields.next().to_string();
+    if account_number.is_empty() {
+        return Err("Missing account number".to_string());
+    }
+    let currency = fields.next().to_string();
+    let mut summaries = Vec::new();
+    while !fields.is_empty() {
+        let type_code = fields.next();
+        if type_code.is_empty() {
+            continue;
+        }
+        summaries.push(Summary {
+            type_code: type_code.to_string(),
+            amount: amount(fields.next())?,
+            item_count: number(fields.next(), "item count")?,
+            funds_type: funds_type(&mut fields)?,
+        });
+    }
+    Ok(Bai2Account {
+        account_number,
+        currency,
+        summaries,
+        transactions: Vec::new(),
+    })
+}
+
+/// Text of a `16` or `88` record, where a trailing `/` ends the record.
+fn text(part: &str) -> String {
+    let part = part.trim_end();
+    part.strip_suffix('/')
+        .unwrap_or(part)
+        .trim_end()
+        .to_string()
+}
+
+fn transaction_detail(record: &Record) -> Result<Transaction, String> {
+    let mut fields = Fields::new(record.body);
+    let type_code = fields.next().to_string();
+    if is_credit(&type_code).is_none() {
+        return Err(format!(
+            "Type code '{}' is not a credit or debit detail code",
+            type_code
+        ));
+    }
+    let amount = match amount(fields.next())? {
+        Some(amount) if amount >= 0 => amount,
+        Some(_) => return Err("Negative transaction amount".to_string()),
+        None => return Err("Missing transaction amount".to_string()),
+    };
+    let funds_type = funds_type(&mut fields)?;
+    let bank_reference = fields.next().to_string();
+    let customer_reference = fields.next().to_string();
+    let text = std::iter::once(fields.remainder())
+        .chain(record.continuations.iter().copied())
+        .map(text)
+        .collect::<Vec<_>>();
+    Ok(Transaction {
+        type_code,
+        amount,
+        funds_type,
+        bank_reference,
+        customer_reference,
+        text: match text.as_slice() {
+            [first] if first.is_empty() => Vec::new(),
+            _ => text,
+        },
+    })
+}
+
+/// Control total and record count of an open file, group or account.
+#[derive(Default)]
+struct Totals {
+    amount: i64,
+    records: u64,
+    /// Groups of a file, accounts of a group.
+    children: u64,
+}
+
+/// Builds the file from its records and checks the trailers against them.
+struct Bai2Reader<'a, 'r> {
+    data: &'a str,
+    recovery: &'r mut Recovery,
+    file: Totals,
+    groups: Vec<Group>,
+    group: Option<(Group, Totals)>,
+    account: Option<(Bai2Account, Totals)>,
+}
+
+impl Bai2Reader<'_, '_> {
+    /// Counts `records` physical records and `amount` in every open level.
+    fn count(&mut self, records: u64, amount: i64) {
+        self.file.records += records;
+        self.file.amount += amount;
+        if let Some((_, totals)) = &mut self.group {
+            totals.records += records;
+            totals.amount += amount;
+        }
+        if let Some((_, totals)) = &mut self.account {
+            totals.records += records;
+            totals.amount += amount;
+        }
+    }
+
+    /// Compares a trailer field with the value computed from the records.
+    fn check(
+        &mut self,
+        record: &Record,
+        what: &str,
+        trailer: Result<Option<i64>, String>,
+        computed: i64,
+    ) -> Result<(), ParserError> {
+        let error = match trailer {
+            Ok(Some(value)) if value == computed => return Ok(()),
+            Ok(Some(value)) => format!(
+                "{} {} does not match the records, {}",
+                what, value, computed
+            ),
+            Ok(None) => format!("Missing {}", what.to_lowercase()),
+            Err(e) => e,
+        };
+        self.recovery
+            .recover(WarningKind::SkippedField, record.error(self.data, error))
+    }
+
+    /// Closes the open account, with its `49` trailer or, when it is missing, at `record`.
+    fn close_account(&mut self, record: &Record, trailer: bool) -> Result<(), ParserError> {
+        let Some((account, totals)) = self.account.take() else {
+            return Ok(());
+        };
+        if trailer {
+            let joined = record.joined();
+            let mut fields = Fields::new(&joined);
+            self.check(
+                record,
+                "Account control total",
+                amount(fields.next()),
+                totals.amount,
+            )?;
+            self.check(
+                record,
+                "Account record count",
+                amount(fields.next()),
+                totals.records as i64,
+            )?;
+        } else {
+            self.recovery.recover(
+                WarningKind::SkippedField,
+                record.error(self.data, "Missing 49 account trailer before this record"),
+            )?;
+        }
+        if let Some((group, group_totals)) = &mut self.group {
+            group.accounts.push(account);
+            group_totals.children += 1;
+        }
+        Ok(())
+    }
+
+    /// Closes the open group, with its `98` trailer or, when it is missing, at `record`.
+    fn close_group(&mut self, record: &Record, trailer: bool) -> Result<(), ParserError> {
+        self.close_account(record, false)?;
+        let Some((group, totals)) = self.group.take() else {
+            return Ok(());
+        };
+        if trailer {
+            let joined = record.joined();
+            let mut fields = Fields::new(&joined);
+            self.check(
+                record,
+                "Group control total",
+                amount(fields.next()),
+                totals.amount,
+            )?;
+            self.check(
+                record,
+                "Group account count",
+                amount(fields.next()),
+                totals.children as i64,
+            )?;
+            self.check(
+                record,
+                "Group record count",
+                amount(fields.next()),
+                totals.records as i64,
+            )?;
+        } else {
+            self.recovery.recover(
+                WarningKind::SkippedField,
+                record.error(self.data, "Missing 98 group trailer before this record"),
+            )?;
+        }
+        self.groups.push(group);
+        self.file.children += 1;
+        Ok(())
+    }
+
+    fn record(&mut self, record: &Record) -> Result<(), ParserError> {
+        let data = self.data;
+        match record.code {
+            "02" => {
+                self.close_group(record, false)?;
+                let group = group_header(record).map_err(|e| record.error(data, e))?;
+                self.group = Some((group, Totals::default()));
+                self.count(record.physical_records(), 0);
+            }
+            "03" => {
+                if self.group.is_none() {
+                    return Err(record.error(data, "03 account record outside a group"));
+                }
+                self.close_account(record, false)?;
+                let account = account_identifier(record).map_err(|e| record.error(data, e))?;
+                let amount = account.summaries.iter().filter_map(|s| s.amount).sum();
+                self.account = Some((account, Totals::default()));
+                self.count(record.physical_records(), amount);
+            }
+            "16" => {
+                if self.account.is_none() {
+                    return Err(record.error(data, "16 transaction record outside an account"));
+                }
+                match transaction_detail(record) {
+                    Ok(transaction) => {
+                        self.count(record.physical_records(), transaction.amount);
+                        if let Some((account, _)) = &mut self.account {
+                            account.transactions.push(transaction);
+                        }
+                    }
+                    Err(e) => {
+                        // The amount still counts towards the control totals
+                        let mut fields = Fields::new(record.body);
+                        let amount = amount(fields.nth(1)).ok().flatten().unwrap_or_default();
+                        self.count(record.physical_records(), amount);
+                        self.recovery
+                            .recover(WarningKind::SkippedEntry, record.error(data, e))?;
+                    }
+                }
+            }
+            "49" => {
+                if self.account.is_none() {
+                    return Err(record.error(data, "49 account trailer without an account"));
+                }
+                self.count(record.physical_records(), 0);
+                self.close_account(record, true)?;
+            }
+            "98" => {
+                if self.group.is_none() {
+                    return Err(record.error(data, "98 group trailer without a group"));
+                }
+                self.count(record.physical_records(), 0);
+                self.close_group(record, true)?;
+            }
+            "01" => return Err(record.error(data, "Second 01 file header")),
+            other => {
+                self.count(record.physical_records(), 0);
+                self.recovery.recover(
+                    WarningKind::SkippedField,
+                    record.error(data, format!("Unknown record type '{}'", other)),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Bai2 {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let records = records(data)?;
+        let Some((header, rest)) = records.split_first() else {
+            return Err(ParserError::Bai2("Empty file".to_string()));
+        };
+        if header.code != "01" {
+            return Err(header.error(data, "File does not start with an 01 file header"));
+        }
+        let header_fields = file_header(header).map_err(|e| header.error(data, e))?;
+
+        let mut reader = Bai2Reader {
+            data,
+            recovery,
+            file: Totals::default(),
+            groups: Vec::new(),
+            group: None,
+            account: None,
+        };
+        reader.count(header.physical_records(), 0);
+        let mut trailer = None;
+        for record in rest {
+            if trailer.is_some() {
+                return Err(record.error(data, "Record after the 99 file trailer"));
+            }
+            if record.code == "99" {
+                trailer = Some(record);
+            } else {
+                reader.record(record)?;
+            }
+        }
+
+        match trailer {
+            Some(record) => {
+                reader.count(record.physical_records(), 0);
+                if reader.group.is_some() {
+                    reader.close_group(record, false)?;
+                }
+                let joined = record.joined();
+                let mut fields = Fields::new(&joined);
+                let computed = (
+                    reader.file.amount,
+                    reader.file.children,
+                    reader.file.records,
+                );
+                reader.check(
+                    record,
+                    "File control total",
+                    amount(fields.next()),
+                    computed.0,
+                )?;
+                reader.check(
+                    record,
+                    "File group count",
+                    amount(fields.next()),
+                    computed.1 as i64,
+                )?;
+                reader.check(
+                    record,
+                    "File record count",
+                    amount(fields.next()),
+                    computed.2 as i64,
+                )?;
+            }
+            None => {
+                let last = rest.last().unwrap_or(header);
+                reader.recovery.recover(
+                    WarningKind::SkippedField,
+                    last.error(data, "Missing 99 file trailer after this record"),
+                )?;
+                if let Some((mut group, _)) = reader.group.take() {
+                    group
+                        .accounts
+                        .extend(reader.account.take().map(|(account, _)| account));
+                    reader.groups.push(group);
+                }
+            }
+        }
+
+        Ok(Bai2 {
+            header: header_fields,
+            groups: reader.groups,
+        })
+    }
+}
+
+impl FinancialDataRead for Bai2 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(bai2, _)| bai2)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Bai2(e.to_string()))?;
+        let mut recovery = Recovery::new(mode);
+        let bai2 = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((bai2, recovery.into_warnings()))
+    }
+}
@@ -0,0 +1,127 @@
+use crate::Bai2;
+use crate::ParserError;
+use crate::bai2::format::FundsType;
+use crate::errors::{ParseMode, WarningKind};
+use crate::test_utils::{read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let bai2 = Bai2::from_read(File::open(test_data_path("valid1.bai2")).unwrap()).unwrap();
+
+    assert_eq!(bai2.header.file_id, "1021001");
+    assert_eq!(bai2.groups.len(), 1);
+    let group = &bai2.groups[0];
+    assert_eq!(group.as_of_date, "251020");
+    assert_eq!(group.accounts.len(), 2);
+
+    let account = &group.accounts[0];
+    assert_eq!(account.summaries.len(), 4);
+    assert_eq!(account.summaries[2].item_count, Some(2));
+    assert_eq!(account.transactions.len(), 4);
+    let wire = &account.transactions[1];
+    assert_eq!(
+        wire.funds_type,
+        Some(FundsType::ValueDated {
+            date: "251021".to_string(),
+            time: String::new()
+        })
+    );
+    assert_eq!(wire.bank_reference, "WIRE7781");
+    assert_eq!(wire.text, ["INCOMING WIRE FROM GLOBEX", "REF ORDER 4471"]);
+    assert!(account.transactions[3].text.is_empty());
+    assert_eq!(group.accounts[1].summaries[0].amount, Some(-1500));
+}
+
+#[test]
+fn test_read_write() {
+    let data = read_test_file("valid1.bai2");
+    let bai2 = Bai2::from_read(data.as_bytes()).unwrap();
+
+    let mut written = Vec::new();
+    bai2.write_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), data);
+}
+
+#[test]
+fn test_continuation_and_funds_types() {
+    let data = "01,SENDER,RECEIVER,251021,,F1,80,,2/\n\
+        02,RECEIVER,SENDER,1,251020,,USD,/\n\
+        03,123,,010,+1000,,,015,1200,,/\n\
+        88,072,300,,S,100,100,100/\n\
+        16,115,150,D,2,0,100,1,50,LBX1,,LOCKBOX\n\
+        16,115,50,S,10,20,20,LBX2,,\n\
+        49,2700,5/\n\
+        98,2700,1,7/\n\
+        99,2700,1,9/\n";
+    let bai2 = Bai2::from_read(data.as_bytes()).unwrap();
+    let account = &bai2.groups[0].accounts[0];
+    assert_eq!(account.summaries.len(), 3);
+    assert_eq!(
+        account.summaries[2].funds_type,
+        Some(FundsType::Distributed {
+            immediate: 100,
+            one_day: 100,
+            two_or_more_days: 100
+        })
+    );
+    assert_eq!(
+        account.transactions[0].funds_type,
+        Some(FundsType::DistributedByDays(vec![(0, 100), (1, 50)]))
+    );
+    assert_eq!(account.transactions[0].text, ["LOCKBOX"]);
+    assert_eq!(account.transactions[1].bank_reference, "LBX2");
+    assert!(account.transactions[1].text.is_empty());
+
+    let mut written = Vec::new();
+    bai2.write_to(&mut written).unwrap();
+    assert_eq!(Bai2::from_read(written.as_slice()).unwrap(), bai2);
+}
+
+#[test]
+fn test_control_totals() {
+    let data = read_test_file("valid1.bai2").replace("49,1117500,7/", "49,1117000,7/");
+    let error = Bai2::from_read(data.as_bytes()).unwrap_err();
+    let ParserError::Located(diagnostic) = &error else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert_eq!(diagnostic.span.line, 9);
+    assert!(error.to_string().contains("Account control total 1117000"));
+
+    let (bai2, warnings) = Bai2::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(bai2.groups[0].accounts.len(), 2);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedField);
+
+    // A record count that leaves out the 88 continuation
+    let data = read_test_file("valid1.bai2").replace("98,1118500,2,12/", "98,1118500,2,11/");
+    assert!(Bai2::from_read(data.as_bytes()).is_err());
+}
+
+#[test]
+fn test_lenient_skips_invalid_transaction() {
+    let data = read_test_file("valid1.bai2").replace("16,475,12500,Z,", "16,475,12500,X,");
+    assert!(Bai2::from_read(data.as_bytes()).is_err());
+
+    let (bai2, warnings) = Bai2::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(bai2.groups[0].accounts[0].transactions.len(), 3);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 7);
+
+    // A distribution count beyond the fields of the record
+    let data = read_test_file("valid1.bai2").replace(
+        "16,142,15000,0,ACH123,INV-2025-17,ACME CORP PAYROLL",
+        "16,142,15000,D,400000000/",
+    );
+    let error = Bai2::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Distribution count 400000000"));
+
+    let data = read_test_file("valid1.bai2").replace("99,1118500,1,14/\n", "");
+    assert!(Bai2::from_read(data.as_bytes()).is_err());
+    let (bai2, warnings) = Bai2::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(bai2.groups.len(), 1);
+    assert_eq!(warnings.len(), 1);
+}
@@ -0,0 +1,142 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::bai2::format::*;
+use std::fmt::Write;
+
+fn amount(value: Option<i64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// The funds type field and the fields that depend on it.
+fn funds_type(funds_type: &Option<FundsType>) -> String {
+    match funds_type {
+        None => String::new(),
+        Some(FundsType::Immediate) => "0".to_string(),
+        Some(FundsType::OneDay) => "1".to_string(),
+        Some(FundsType::TwoOrMoreDays) => "2".to_string(),
+        Some(FundsType::Unknown) => "Z".to_string(),
+        Some(FundsType::Distributed {
+            immediate,
+            one_day,
+            two_or_more_days,
+        }) => format!("S,{},{},{}", immediate, one_day, two_or_more_days),
+        Some(FundsType::ValueDated { date, time }) => format!("V,{},{}", date, time),
+        Some(FundsType::DistributedByDays(distribution)) => {
+            let mut field = format!("D,{}", distribution.len());
+            for (days, amount) in distribution {
+                field.push_str(&format!(",{},{}", days, amount));
+            }
+            field
+        }
+    }
+}
+
+impl Bai2Account {
+    /// Writes the `03`, `16`, `88` and `49` records; returns the control total and record count.
+    fn write(&self, out: &mut String) -> Result<(i64, u64), ParserError> {
+        let mut total = 0;
+        let mut records = 2;
+        write!(out, "03,{},{}", self.account_number, self.currency)?;
+        for summary in &self.summaries {
+            total += summary.amount.unwrap_or_default();
+            write!(
+                out,
+                ",{},{},{},{}",
+                summary.type_code,
+                amount(summary.amount),
+                summary
+                    .item_count
+                    .map(|c| c.to_string())
+                    .unwrap_or_default(),
+                funds_type(&summary.funds_type)
+            )?;
+        }
+        out.push_str("/\n");
+
+        for transaction in &self.transactions {
+            total += transaction.amount;
+            records += transaction.text.len().max(1) as u64;
+            // The text runs to the end of the record; without one the record ends with `/`
+            let mut lines = transaction.text.iter();
+            writeln!(
+                out,
+                "16,{},{},{},{},{},{}",
+                transaction.type_code,
+                transaction.amount,
+                funds_type(&transaction.funds_type),
+                transaction.bank_reference,
+                transaction.customer_reference,
+                lines.next().map(String::as_str).unwrap_or("/")
+            )?;
+            for line in lines {
+                writeln!(out, "88,{}", line)?;
+            }
+        }
+
+        writeln!(out, "49,{},{}/", total, records)?;
+        Ok((total, records))
+    }
+}
+
+impl Group {
+    /// Writes the `02` record, the accounts and the `98` record; returns the
+    /// control total and record count.
+    fn write(&self, out: &mut String) -> Result<(i64, u64), ParserError> {
+        writeln!(
+            out,
+            "02,{},{},{},{},{},{},{}/",
+            self.ultimate_receiver_id,
+            self.originator_id,
+            self.status,
+            self.as_of_date,
+            self.as_of_time,
+            self.currency,
+            self.as_of_date_modifier
+        )?;
+        let mut total = 0;
+        let mut records = 2;
+        for account in &self.accounts {
+            let (account_total, account_records) = account.write(out)?;
+            total += account_total;
+            records += account_records;
+        }
+        writeln!(out, "98,{},{},{}/", total, self.accounts.len(), records)?;
+        Ok((total, records))
+    }
+}
+
+impl Bai2 {
+    pub(crate) fn to_string(&self) -> Result<String, ParserError> {
+        let header = &self.header;
+        let mut out = String::new();
+        writeln!(
+            out,
+            "01,{},{},{},{},{},{},{},{}/",
+            header.sender_id,
+            header.receiver_id,
+            header.creation_date,
+            header.creation_time,
+            header.file_id,
+            header.record_length,
+            header.block_size,
+            header.version
+        )?;
+        let mut total = 0;
+        let mut records = 2;
+        for group in &self.groups {
+            let (group_total, group_records) = group.write(&mut out)?;
+            total += group_total;
+            records += group_records;
+        }
+        writeln!(out, "99,{},{},{}/", total, self.groups.len(), records)?;
+        Ok(out)
+    }
+}
+
+impl FinancialDataWrite for Bai2 {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = self.to_string()?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use crate::camt053::format::*;
+
+use chrono::NaiveDate;
+
+/// The balance type code, e.g. `OPBD` or `CLBD`.
+pub(crate) fn balance_code(balance: &Balance) -> Option<&str> {
+    balance.tp.as_ref()?.cd_or_prtry.as_ref()?.cd.as_deref()
+}
+
+/// The first balance of type `code`.
+pub(crate) fn find_balance<'a>(stmt: &'a Statement, code: &str) -> Option<&'a Balance> {
+    stmt.bal.iter().find(|b| balance_code(b) == Some(code))
+}
+
+/// `None` for missing and blank values, which readers of different formats
+/// produce for the same absent field.
+pub(crate) fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// The IBAN of an account, or its other id.
+pub(crate) fn account_number(
+    iban: &Option<String>,
+    other: &Option<GenericAccountIdentification>,
+) -> Option<String> {
+    non_empty(iban).or_else(|| non_empty(&other.as_ref()?.id))
+}
+
+/// The IBAN or other id of the statement account.
+pub(crate) fn account_id(stmt: &Statement) -> Option<String> {
+    let id = stmt.acct.as_ref()?.id.as_ref()?;
+    account_number(&id.iban, &id.other)
+}
+
+/// The transactions of all entry details of an entry.
+pub(crate) fn transactions(entry: &Entry) -> impl Iterator<Item = &TransactionDetails> {
+    entry
+        .ntry_dtls
+        .iter()
+        .flat_map(|details| details.tx_dtls.iter())
+}
+
+/// The first day a statement covers: the start of its period, or the date
+/// of its opening balance or earliest entry.
+pub(crate) fn statement_first_day(stmt: &Statement) -> Option<NaiveDate> {
+    let from = stmt
+        .fr_to_dt
+        .as_ref()
+        .and_then(|period| period.fr_dt_tm.as_deref())
+        .and_then(|dt| NaiveDate::parse_from_str(dt.get(..10)?, "%Y-%m-%d").ok());
+    from.or_else(|| find_balance(stmt, "OPBD")?.dt.as_ref().and_then(date_of))
+        .or_else(|| stmt.ntry.iter().filter_map(entry_date).min())
+}
+
+/// The day a statement closes: the date of its closing balance or latest entry.
+pub(crate) fn statement_last_day(stmt: &Statement) -> Option<NaiveDate> {
+    find_balance(stmt, "CLBD")
+        .and_then(|b| b.dt.as_ref())
+        .and_then(date_of)
+        .or_else(|| stmt.ntry.iter().filter_map(entry_date).max())
+}
+
+/// The date of a date or date-time choice.
+pub(crate) fn date_of(date: &DateAndDateTimeChoice) -> Option<NaiveDate> {
+    let value = date.dt.as_deref().or(date.dt_tm.as_deref())?;
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// The booking date of an entry, or its value date when it has none.
+pub(crate) fn entry_date(entry: &Entry) -> Option<NaiveDate> {
+    entry
+        .bookg_dt
+        .as_ref()
+        .and_then(date_of)
+        .or_else(|| entry.val_dt.as_ref().and_then(date_of))
+}
+
+/// A balance of type `code`, with the sign of `amount` as its indicator.
+pub(crate) fn new_balance(
+    code: &str,
+    amount: SignedAmount,
+    currency: Option<String>,
+    dt: Option<DateAndDateTimeChoice>,
+) -> Balance {
+    Balance {
+        tp: Some(BalanceType {
+            cd_or_prtry: Some(CodeOrProprietary {
+                cd: Some(code.to_string()),
+                prtry: None,
+            }),
+        }),
+        amt: Some(Amount {
+            currency,
+            value: Some(amount.magnitude()),
+        }),
+        cdt_dbt_ind: Some(if amount.units < 0 { "DBIT" } else { "CRDT" }.to_string()),
+        dt,
+    }
+}
+
+/// An exact decimal amount, negative for debits.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct SignedAmount {
+    /// Amount in units of `10^-SCALE`.
+    pub(crate) units: i128,
+    /// Number of decimals to write, the most any added amount had.
+    pub(crate) decimals: u32,
+}
+
+impl SignedAmount {
+    /// ISO 20022 amounts have at most five decimals.
+    const SCALE: u32 = 5;
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (whole, fraction) = value.split_once(['.', ',']).unwrap_or((value, ""));
+        if whole.is_empty()
+            || !whole.bytes().all(|b| b.is_ascii_digit())
+            || !fraction.bytes().all(|b| b.is_ascii_digit())
+            || fraction.len() > Self::SCALE as usize
+        {
+            return None;
+        }
+        let digits = format!(
+            "{}{:0<width$}",
+            whole,
+            fraction,
+            width = Self::SCALE as usize
+        );
+        Some(SignedAmount {
+            units: digits.parse().ok()?,
+            decimals: fraction.len() as u32,
+        })
+    }
+
+    pub(crate) fn add(self, other: SignedAmount) -> SignedAmount {
+        SignedAmount {
+            units: self.units + other.units,
+            decimals: self.decimals.max(other.decimals),
+        }
+    }
+
+    pub(crate) fn negate(self) -> SignedAmount {
+        SignedAmount {
+            units: -self.units,
+            ..self
+        }
+    }
+
+    /// The amount in units of its `decimals`-th decimal, e.g. cents for
+    /// two; `None` when it has more decimals.
+    pub(crate) fn in_units_of(&self, decimals: u32) -> Option<i128> {
+        let scale = 10i128.pow(Self::SCALE - decimals.min(Self::SCALE));
+        (self.units % scale == 0).then_some(self.units / scale)
+    }
+
+    /// The absolute value, with at least two decimals.
+    pub(crate) fn magnitude(&self) -> String {
+        let scale = 10i128.pow(Self::SCALE);
+        let units = self.units.abs();
+        let decimals = self.decimals.max(2);
+        let fraction = format!("{:0width$}", units % scale, width = Self::SCALE as usize);
+        format!("{}.{}", units / scale, &fraction[..decimals as usize])
+    }
+}
+
+/// Number of decimals of the minor unit of `currency`.
+pub(crate) fn currency_decimals(currency: &str) -> u32 {
+    match currency {
+        "JPY" | "KRW" | "CLP" | "ISK" | "VND" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        _ => 2,
+    }
+}
+
+/// An amount with the sign of its credit/debit indicator.
+pub(crate) fn signed_amount(
+    amount: &Option<Amount>,
+    cdt_dbt_ind: &Option<String>,
+) -> Option<SignedAmount> {
+    let amount = SignedAmount::parse(amount.as_ref()?.value.as_deref()?)?;
+    match cdt_dbt_ind.as_deref()?.trim() {
+        "CRDT" | "C" => Some(amount),
+        "DBIT" | "D" => Some(amount.negate()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signed_amount() {
+        let a = SignedAmount::parse("1000.5").unwrap();
+        let b = SignedAmount::parse("0,25").unwrap();
+        assert_eq!(a.add(b).magnitude(), "1000.75");
+        assert_eq!(SignedAmount::parse("7").unwrap().magnitude(), "7.00");
+        assert_eq!(SignedAmount::default().add(b.negate()).magnitude(), "0.25");
+        assert_eq!(SignedAmount::parse("1e3"), None);
+        assert_eq!(SignedAmount::parse("-1"), None);
+        assert_eq!(a.negate().in_units_of(2), Some(-100_050));
+        assert_eq!(b.in_units_of(1), None);
+        assert_eq!(a.in_units_of(0), None);
+    }
+}
@@ -1,14 +1,14 @@
 use serde::{Deserialize, Serialize};
 
 // Root document
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename = "Document")]
 pub(crate) struct Camt053 {
     #[serde(rename = "BkToCstmrStmt")]
     pub bk_to_cstmr_stmt: BankToCustomerStatement,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct BankToCustomerStatement {
     #[serde(rename = "GrpHdr")]
     pub grp_hdr: GroupHeader,
@@ -16,7 +16,7 @@ pub(crate) struct BankToCustomerStatement {
     pub stmts: Vec<Statement>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct GroupHeader {
     #[serde(rename = "MsgId")]
     pub msg_id: Option<String>,
@@ -24,7 +24,7 @@ pub(crate) struct GroupHeader {
     pub cre_dt_tm: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Statement {
     #[serde(rename = "Id")]
     pub id: Option<String>,
@@ -44,7 +44,7 @@ pub(crate) struct Statement {
     pub ntry: Vec<Entry>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct FromToDate {
     #[serde(rename = "FrDtTm")]
     pub fr_dt_tm: Option<String>,
@@ -52,7 +52,7 @@ pub(crate) struct FromToDate {
     pub to_dt_tm: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Account {
     #[serde(rename = "Id")]
     pub id: Option<AccountId>,
@@ -60,9 +60,47 @@ pub(crate) struct Account {
     pub ccy: Option<String>,
     #[serde(rename = "Nm")]
     pub name: Option<String>,
+    #[serde(rename = "Svcr", skip_serializing_if = "Option::is_none")]
+    pub svcr: Option<BranchAndFinancialInstitutionIdentification>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct BranchAndFinancialInstitutionIdentification {
+    #[serde(rename = "FinInstnId")]
+    pub fin_instn_id: Option<FinancialInstitutionIdentification>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct FinancialInstitutionIdentification {
+    /// `BICFI` from the 2019 message versions on.
+    #[serde(rename = "BIC", alias = "BICFI")]
+    pub bic: Option<String>,
+    /// A national bank code, e.g. a Russian BIK, for banks without a BIC.
+    #[serde(
+        rename = "ClrSysMmbId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub clr_sys_mmb_id: Option<ClearingSystemMemberIdentification>,
+    #[serde(rename = "Nm", default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct ClearingSystemMemberIdentification {
+    #[serde(rename = "ClrSysId")]
+    pub clr_sys_id: Option<ClearingSystemIdentification>,
+    #[serde(rename = "MmbId")]
+    pub mmb_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct ClearingSystemIdentification {
+    #[serde(rename = "Cd")]
+    pub cd: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct AccountId {
     #[serde(rename = "IBAN")]
     pub iban: Option<String>,
@@ -70,13 +108,13 @@ pub(crate) struct AccountId {
     pub other: Option<GenericAccountIdentification>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct GenericAccountIdentification {
     #[serde(rename = "Id")]
     pub id: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Balance {
     #[serde(rename = "Tp")]
     pub tp: Option<BalanceType>,
@@ -88,13 +126,13 @@ pub(crate) struct Balance {
     pub dt: Option<DateAndDateTimeChoice>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct BalanceType {
     #[serde(rename = "CdOrPrtry")]
     pub cd_or_prtry: Option<CodeOrProprietary>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct CodeOrProprietary {
     #[serde(rename = "Cd")]
     pub cd: Option<String>,
@@ -102,7 +140,7 @@ pub(crate) struct CodeOrProprietary {
     pub prtry: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Amount {
     #[serde(rename = "@Ccy")]
     pub currency: Option<String>,
@@ -110,7 +148,7 @@ pub(crate) struct Amount {
     pub value: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct DateAndDateTimeChoice {
     #[serde(rename = "Dt")]
     pub dt: Option<String>,
@@ -118,7 +156,7 @@ pub(crate) struct DateAndDateTimeChoice {
     pub dt_tm: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Entry {
     #[serde(rename = "Amt")]
     pub amt: Option<Amount>,
@@ -136,7 +174,7 @@ pub(crate) struct Entry {
     pub ntry_dtls: Vec<EntryDetails>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct BankTransactionCode {
     #[serde(rename = "Domn")]
     pub domn: Option<BankTransactionCodeStructure>,
@@ -144,7 +182,7 @@ pub(crate) struct BankTransactionCode {
     pub prtry: Option<ProprietaryBankTransactionCode>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct BankTransactionCodeStructure {
     #[serde(rename = "Cd")]
     pub cd: Option<String>,
@@ -152,7 +190,7 @@ pub(crate) struct BankTransactionCodeStructure {
     pub fmly: Option<BankTransactionCodeFamily>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct BankTransactionCodeFamily {
     #[serde(rename = "Cd")]
     pub cd: Option<String>,
@@ -160,7 +198,7 @@ pub(crate) struct BankTransactionCodeFamily {
     pub sub_fmly_cd: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct ProprietaryBankTransactionCode {
     #[serde(rename = "Cd")]
     pub cd: Option<String>,
@@ -168,13 +206,13 @@ pub(crate) struct ProprietaryBankTransactionCode {
     pub issr: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct EntryDetails {
     #[serde(rename = "TxDtls", default)]
     pub tx_dtls: Vec<TransactionDetails>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct TransactionDetails {
     #[serde(rename = "Refs")]
     pub refs: Option<TransactionReferences>,
@@ -182,11 +220,13 @@ pub(crate) struct TransactionDetails {
     pub amt: Option<Amount>,
     #[serde(rename = "RltdPties")]
     pub rltd_pties: Option<RelatedParties>,
+    #[serde(rename = "RltdAgts", default, skip_serializing_if = "Option::is_none")]
+    pub rltd_agts: Option<RelatedAgents>,
     #[serde(rename = "RmtInf")]
     pub rmt_inf: Option<RemittanceInformation>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct TransactionReferences {
     #[serde(rename = "MsgId")]
     pub msg_id: Option<String>,
@@ -198,11 +238,14 @@ pub(crate) struct TransactionReferences {
     pub instr_id: Option<String>,
     #[serde(rename = "EndToEndId")]
     pub end_to_end_id: Option<String>,
+    // Unique end-to-end transaction reference, from camt.053.001.08 on
+    #[serde(rename = "UETR", default, skip_serializing_if = "Option::is_none")]
+    pub uetr: Option<String>,
     #[serde(rename = "TxId")]
     pub tx_id: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct RelatedParties {
     #[serde(rename = "Dbtr")]
     pub dbtr: Option<Party>,
@@ -214,13 +257,70 @@ pub(crate) struct RelatedParties {
     pub cdtr_acct: Option<AccountIdentification>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Party {
     #[serde(rename = "Nm")]
     pub name: Option<String>,
+    /// Tax and registration numbers of an organisation.
+    #[serde(rename = "Id", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<PartyIdentification>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct PartyIdentification {
+    #[serde(rename = "OrgId")]
+    pub org_id: Option<OrganisationIdentification>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct OrganisationIdentification {
+    #[serde(rename = "Othr", default)]
+    pub othr: Vec<GenericOrganisationIdentification>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct GenericOrganisationIdentification {
+    #[serde(rename = "Id")]
+    pub id: Option<String>,
+    #[serde(rename = "SchmeNm", default, skip_serializing_if = "Option::is_none")]
+    pub schme_nm: Option<OrganisationIdentificationSchemeName>,
+}
+
+/// An ISO code, e.g. `TXID` for a tax number, or a proprietary scheme.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct OrganisationIdentificationSchemeName {
+    #[serde(rename = "Cd", default, skip_serializing_if = "Option::is_none")]
+    pub cd: Option<String>,
+    #[serde(rename = "Prtry", default, skip_serializing_if = "Option::is_none")]
+    pub prtry: Option<String>,
+}
+
+impl Party {
+    /// The identification of the organisation under `scheme`, an ISO code
+    /// or a proprietary scheme name.
+    pub(crate) fn org_id(&self, scheme: &str) -> Option<&str> {
+        let org_id = self.id.as_ref()?.org_id.as_ref()?;
+        org_id
+            .othr
+            .iter()
+            .find(|other| {
+                other.schme_nm.as_ref().is_some_and(|name| {
+                    name.cd.as_deref() == Some(scheme) || name.prtry.as_deref() == Some(scheme)
+                })
+            })
+            .and_then(|other| other.id.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct RelatedAgents {
+    #[serde(rename = "DbtrAgt", default, skip_serializing_if = "Option::is_none")]
+    pub dbtr_agt: Option<BranchAndFinancialInstitutionIdentification>,
+    #[serde(rename = "CdtrAgt", default, skip_serializing_if = "Option::is_none")]
+    pub cdtr_agt: Option<BranchAndFinancialInstitutionIdentification>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct AccountIdentification {
     #[serde(rename = "IBAN")]
     pub iban: Option<String>,
@@ -228,7 +328,7 @@ pub(crate) struct AccountIdentification {
     pub other: Option<GenericAccountIdentification>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
 pub(crate) struct RemittanceInformation {
     #[serde(rename = "Ustrd", default)]
     pub ustrd: Vec<String>,
@@ -1,13 +1,64 @@
 use crate::Camt053;
 use crate::FinancialDataRead;
 use crate::ParserError;
+use crate::camt053::stream::{Camt053Item, Camt053StreamReader};
+use crate::errors::{ParseMode, ParseWarning};
 
 use quick_xml::de::from_str;
 
 impl FinancialDataRead for Camt053 {
     fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
         let data = Self::read_to_string(reader).map_err(|e| ParserError::Camt053(e.to_string()))?;
-        let result: Camt053 = from_str(&data).map_err(|e| ParserError::Camt053(e.to_string()))?;
+        let result: Camt053 = from_str(&data).map_err(|e| locate(&data, e.to_string()))?;
         Ok(result)
     }
+
+    /// In lenient mode the document is assembled from the streaming reader,
+    /// which skips entries and statements that fail to decode.
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        if mode == ParseMode::Strict {
+            return Self::from_read(reader).map(|camt053| (camt053, Vec::new()));
+        }
+
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Camt053(e.to_string()))?;
+        let mut items = Camt053StreamReader::with_mode(data.as_bytes(), mode);
+        let mut camt053 = Camt053::default();
+        let document = &mut camt053.bk_to_cstmr_stmt;
+        for item in items.by_ref() {
+            match item.map_err(|e| e.with_source(&data))? {
+                Camt053Item::GroupHeader(grp_hdr) => document.grp_hdr = grp_hdr,
+                Camt053Item::Statement(stmt) => document.stmts.push(stmt),
+                Camt053Item::Entry(entry) => {
+                    if let Some(stmt) = document.stmts.last_mut() {
+                        stmt.ntry.push(entry);
+                    }
+                }
+                Camt053Item::StatementEnd => {}
+            }
+        }
+        let warnings = items
+            .take_warnings()
+            .into_iter()
+            .map(|warning| ParseWarning {
+                error: warning.error.with_source(&data),
+                ..warning
+            })
+            .collect();
+        Ok((camt053, warnings))
+    }
+}
+
+/// Locates a document decoding error by reading `data` element by element.
+///
+/// The streaming reader pinpoints the element that fails to decode; errors it
+/// does not reproduce, e.g. a missing `BkToCstmrStmt`, are returned unlocated.
+fn locate(data: &str, message: String) -> ParserError {
+    Camt053StreamReader::new(data.as_bytes())
+        .find_map(Result::err)
+        .filter(|e| e.diagnostic().is_some())
+        .map(|e| e.with_source(data))
+        .unwrap_or(ParserError::Camt053(message))
 }
@@ -0,0 +1,471 @@
+use crate::ParserError;
+use crate::camt053::format::{Entry, GroupHeader, Statement};
+use crate::errors::{ErrorLocation, ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+
+use quick_xml::de::from_str;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::se::to_string_with_root;
+use quick_xml::{Reader, Writer};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::io::{BufRead, Read, Write};
+
+/// Buffered source that keeps the line and column of the next unread byte
+/// and of the last `<` read, where the last read tag starts.
+struct Tracked<R: BufRead> {
+    inner: R,
+    position: SourceSpan,
+    tag_start: SourceSpan,
+}
+
+impl<R: BufRead> Tracked<R> {
+    fn advance(position: &mut SourceSpan, tag_start: &mut SourceSpan, bytes: &[u8]) {
+        match bytes.iter().rposition(|&b| b == b'<') {
+            Some(i) => {
+                position.advance(&bytes[..i]);
+                *tag_start = *position;
+                position.advance(&bytes[i..]);
+            }
+            None => position.advance(bytes),
+        }
+    }
+}
+
+impl<R: BufRead> Read for Tracked<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        Self::advance(&mut self.position, &mut self.tag_start, &buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for Tracked<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        // The buffer is already filled, so this does not read from the source
+        if let Ok(buf) = self.inner.fill_buf() {
+            let consumed = &buf[..amt.min(buf.len())];
+            Self::advance(&mut self.position, &mut self.tag_start, consumed);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+/// One unit of a CAMT.053 document produced by [`Camt053StreamReader`].
+///
+/// Items arrive in document order: the group header, then for every
+/// statement its header, its entries and a closing marker.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Camt053Item {
+    /// `GrpHdr` of the document.
+    GroupHeader(GroupHeader),
+    /// Statement header (`Id`, `Acct`, `Bal`, ...) with an empty `ntry` list.
+    Statement(Statement),
+    /// One `Ntry` of the current statement.
+    Entry(Entry),
+    /// End of the current statement.
+    StatementEnd,
+}
+
+/// Pull-based CAMT.053 reader built on the quick-xml event reader.
+///
+/// Only the element currently being decoded — the group header, a statement
+/// header or a single entry — is held in memory, so documents of any size
+/// are read in bounded memory.
+///
+/// Errors are [located](ParserError::Located) at the start of the element
+/// that failed to decode, with its XML path, e.g.
+/// `/Document/BkToCstmrStmt/Stmt[2]/Ntry[15]`.
+///
+/// In lenient mode an entry or statement that fails to decode is skipped,
+/// and a group header that fails to decode is replaced by an empty one;
+/// see [`take_warnings`](Self::take_warnings). Malformed XML always fails.
+pub(crate) struct Camt053StreamReader<R: BufRead> {
+    reader: Reader<Tracked<R>>,
+    buf: Vec<u8>,
+    depth: usize,
+    // Names of the open Document, BkToCstmrStmt and Stmt elements, for error paths
+    path: Vec<String>,
+    statements: usize,
+    entries: usize,
+    // Start and path of the current statement and of the last entry read
+    statement_location: (SourceSpan, String),
+    entry_location: (SourceSpan, String),
+    // Serialized statement header children, until the first entry or the end of the statement
+    header: Option<Vec<u8>>,
+    // Set when the header of the current statement was skipped in lenient mode
+    skip_statement: bool,
+    pending: VecDeque<Camt053Item>,
+    done: bool,
+    recovery: Recovery,
+}
+
+impl<R: BufRead> Camt053StreamReader<R> {
+    /// Creates a strict reader over a buffered CAMT.053 source.
+    pub(crate) fn new(source: R) -> Self {
+        Self::with_mode(source, ParseMode::Strict)
+    }
+
+    /// Creates a reader over a buffered CAMT.053 source in the given mode.
+    pub(crate) fn with_mode(source: R, mode: ParseMode) -> Self {
+        Camt053StreamReader {
+            reader: Reader::from_reader(Tracked {
+                inner: source,
+                position: SourceSpan::default(),
+                tag_start: SourceSpan::default(),
+            }),
+            buf: Vec::new(),
+            depth: 0,
+            path: Vec::new(),
+            statements: 0,
+            entries: 0,
+            statement_location: Default::default(),
+            entry_location: Default::default(),
+            header: None,
+            skip_statement: false,
+            pending: VecDeque::new(),
+            done: false,
+            recovery: Recovery::new(mode),
+        }
+    }
+
+    /// Removes and returns the warnings for the items read so far.
+    pub(crate) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        self.recovery.take_warnings()
+    }
+
+    /// Locates a problem found with the last entry read at its `Ntry` element.
+    pub(crate) fn locate_entry(&self, error: ParserError) -> ParserError {
+        let (span, path) = &self.entry_location;
+        error.at_span(*span, Some(ErrorLocation::XmlPath(path.clone())))
+    }
+
+    /// Locates a problem found with the current statement at its `Stmt` element.
+    pub(crate) fn locate_statement(&self, error: ParserError) -> ParserError {
+        let (span, path) = &self.statement_location;
+        error.at_span(*span, Some(ErrorLocation::XmlPath(path.clone())))
+    }
+
+    fn next_item(&mut self) -> Result<Option<Camt053Item>, ParserError> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Ok(Some(item));
+            }
+            if self.done {
+                return Ok(None);
+            }
+
+            self.buf.clear();
+            let event = match self.reader.read_event_into(&mut self.buf) {
+                Ok(event) => event.into_owned(),
+                Err(e) => return Err(stream_error(e, self.reader.get_ref().tag_start, None)),
+            };
+
+            match event {
+                Event::Eof => {
+                    if self.depth > 0 {
+                        let end = self.reader.get_ref().position;
+                        return Err(stream_error("unexpected end of document", end, None));
+                    }
+                    self.done = true;
+                }
+                Event::Start(start) => self.on_element(start, false)?,
+                Event::Empty(start) => self.on_element(start, true)?,
+                Event::End(end) => {
+                    // Closing </Stmt>
+                    if self.depth == 3 {
+                        self.end_statement()?;
+                    }
+                    self.depth = self.depth.saturating_sub(1);
+                    self.path.truncate(self.depth);
+                    if self.depth == 0 && end.local_name().as_ref() == b"Document" {
+                        self.done = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // `depth` is the number of open ancestors: Document = 1, BkToCstmrStmt = 2, Stmt = 3
+    fn on_element(&mut self, start: BytesStart<'static>, empty: bool) -> Result<(), ParserError> {
+        let name = start.local_name().as_ref().to_vec();
+        let element_start = self.reader.get_ref().tag_start;
+        match (self.depth, name.as_slice()) {
+            (2, b"GrpHdr") => {
+                let path = self.child_path("GrpHdr");
+                let xml = self.capture(start, empty, element_start, &path)?;
+                let grp_hdr = match self.decode(&xml, element_start, &path) {
+                    Ok(grp_hdr) => grp_hdr,
+                    Err(e) => {
+                        self.recovery.recover(WarningKind::SkippedGroupHeader, e)?;
+                        GroupHeader::default()
+                    }
+                };
+                self.pending.push_back(Camt053Item::GroupHeader(grp_hdr));
+            }
+            (2, b"Stmt") => {
+                self.statements += 1;
+                self.entries = 0;
+                self.skip_statement = false;
+                self.header = Some(b"<Stmt>".to_vec());
+                self.path.push(format!("Stmt[{}]", self.statements));
+                self.statement_location = (element_start, format!("/{}", self.path.join("/")));
+                if empty {
+                    self.end_statement()?;
+                    self.path.pop();
+                } else {
+                    self.depth += 1;
+                }
+            }
+            (3, b"Ntry") => {
+                self.flush_header()?;
+                self.entries += 1;
+                let path = self.child_path(&format!("Ntry[{}]", self.entries));
+                let xml = self.capture(start, empty, element_start, &path)?;
+                if self.skip_statement {
+                    return Ok(());
+                }
+                match self.decode(&xml, element_start, &path) {
+                    Ok(entry) => self.pending.push_back(Camt053Item::Entry(entry)),
+                    Err(e) => self.recovery.recover(WarningKind::SkippedEntry, e)?,
+                }
+                self.entry_location = (element_start, path);
+            }
+            (3, _) => {
+                let path = self.child_path(&String::from_utf8_lossy(&name));
+                let xml = self.capture(start, empty, element_start, &path)?;
+                // Elements after the first entry (e.g. AddtlStmtInf) are not part of the model
+                if let Some(header) = self.header.as_mut() {
+                    header.extend_from_slice(&xml);
+                }
+            }
+            (0 | 1, _) if !empty => {
+                self.depth += 1;
+                self.path.push(String::from_utf8_lossy(&name).into_owned());
+            }
+            (0 | 1, _) => {}
+            // Group header siblings such as a signature are skipped
+            _ => {
+                let path = self.child_path(&String::from_utf8_lossy(&name));
+                self.capture(start, empty, element_start, &path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_header(&mut self) -> Result<(), ParserError> {
+        if let Some(mut header) = self.header.take() {
+            header.extend_from_slice(b"</Stmt>");
+            let (start, path) = &self.statement_location;
+            match self.decode(&header, *start, path) {
+                Ok(statement) => self.pending.push_back(Camt053Item::Statement(statement)),
+                Err(e) => {
+                    // The entries of a skipped statement are skipped with it
+                    self.recovery.recover(WarningKind::SkippedStatement, e)?;
+                    self.skip_statement = true;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn end_statement(&mut self) -> Result<(), ParserError> {
+        self.flush_header()?;
+        if !self.skip_statement {
+            self.pending.push_back(Camt053Item::StatementEnd);
+        }
+        Ok(())
+    }
+
+    /// Path of the child `name` of the innermost open element.
+    fn child_path(&self, name: &str) -> String {
+        let mut path = String::new();
+        for ancestor in &self.path {
+            path.push('/');
+            path.push_str(ancestor);
+        }
+        format!("{}/{}", path, name)
+    }
+
+    /// Re-serializes the element starting with `start` and all its content.
+    fn capture(
+        &mut self,
+        start: BytesStart<'static>,
+        empty: bool,
+        element_start: SourceSpan,
+        path: &str,
+    ) -> Result<Vec<u8>, ParserError> {
+        let mut writer = Writer::new(Vec::new());
+        if empty {
+            writer
+                .write_event(Event::Empty(start))
+                .map_err(|e| stream_error(e, element_start, Some(path)))?;
+            return Ok(writer.into_inner());
+        }
+
+        writer
+            .write_event(Event::Start(start))
+            .map_err(|e| stream_error(e, element_start, Some(path)))?;
+        let mut nesting = 1usize;
+        while nesting > 0 {
+            self.buf.clear();
+            let reader = &mut self.reader;
+            let event = match reader.read_event_into(&mut self.buf) {
+                Ok(event) => event,
+                Err(e) => return Err(stream_error(e, reader.get_ref().tag_start, Some(path))),
+            };
+            match &event {
+                Event::Start(_) => nesting += 1,
+                Event::End(_) => nesting -= 1,
+                Event::Eof => {
+                    let end = reader.get_ref().position;
+                    return Err(stream_error("unexpected end of document", end, Some(path)));
+                }
+                _ => {}
+            }
+            if let Err(e) = writer.write_event(event) {
+                return Err(stream_error(e, reader.get_ref().tag_start, Some(path)));
+            }
+        }
+        Ok(writer.into_inner())
+    }
+
+    fn decode<T: DeserializeOwned>(
+        &self,
+        xml: &[u8],
+        element_start: SourceSpan,
+        path: &str,
+    ) -> Result<T, ParserError> {
+        let xml =
+            std::str::from_utf8(xml).map_err(|e| stream_error(e, element_start, Some(path)))?;
+        from_str(xml).map_err(|e| stream_error(e, element_start, Some(path)))
+    }
+}
+
+/// A CAMT.053 error located at `span`, within the element at `path` if known.
+fn stream_error<E: std::fmt::Display>(
+    e: E,
+    mut span: SourceSpan,
+    path: Option<&str>,
+) -> ParserError {
+    span.len = 1;
+    let location = path.map(|path| ErrorLocation::XmlPath(path.to_string()));
+    ParserError::Camt053(e.to_string()).at_span(span, location)
+}
+
+impl<R: BufRead> Iterator for Camt053StreamReader<R> {
+    type Item = Result<Camt053Item, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(e) => {
+                self.done = true;
+                self.pending.clear();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Incremental CAMT.053 writer, the counterpart of [`Camt053StreamReader`].
+///
+/// Produces the same document as [`Camt053`](crate::Camt053)'s `write_to`,
+/// but writes every statement header and entry as soon as it is passed in.
+pub(crate) struct Camt053StreamWriter<W: Write> {
+    writer: W,
+    in_statement: bool,
+}
+
+impl<W: Write> Camt053StreamWriter<W> {
+    /// Writes the document prologue and the group header.
+    pub(crate) fn new(writer: W, grp_hdr: &GroupHeader) -> Result<Self, ParserError> {
+        let mut stream = Camt053StreamWriter {
+            writer,
+            in_statement: false,
+        };
+        stream.write_raw("<Document><BkToCstmrStmt>")?;
+        stream.write_element("GrpHdr", grp_hdr)?;
+        Ok(stream)
+    }
+
+    /// Opens a statement and writes its header. The `ntry` list of `statement`
+    /// is ignored, entries are passed to [`write_entry`](Self::write_entry).
+    ///
+    /// An open statement is closed first.
+    pub(crate) fn start_statement(&mut self, statement: &Statement) -> Result<(), ParserError> {
+        self.end_statement()?;
+        self.write_raw("<Stmt>")?;
+        self.in_statement = true;
+
+        self.write_optional("Id", &statement.id)?;
+        self.write_optional("ElctrncSeqNb", &statement.elctrnc_seq_nb)?;
+        self.write_optional("LglSeqNb", &statement.lgl_seq_nb)?;
+        self.write_optional("CreDtTm", &statement.cre_dt_tm)?;
+        self.write_optional("FrToDt", &statement.fr_to_dt)?;
+        self.write_optional("Acct", &statement.acct)?;
+        for balance in &statement.bal {
+            self.write_element("Bal", balance)?;
+        }
+        Ok(())
+    }
+
+    /// Writes one entry of the open statement.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError::Camt053`] if no statement is open.
+    pub(crate) fn write_entry(&mut self, entry: &Entry) -> Result<(), ParserError> {
+        if !self.in_statement {
+            return Err(ParserError::Camt053(
+                "Entry written outside of a statement".to_string(),
+            ));
+        }
+        self.write_element("Ntry", entry)
+    }
+
+    /// Closes the open statement, if any.
+    pub(crate) fn end_statement(&mut self) -> Result<(), ParserError> {
+        if self.in_statement {
+            self.write_raw("</Stmt>")?;
+            self.in_statement = false;
+        }
+        Ok(())
+    }
+
+    /// Closes the document and returns the underlying writer.
+    pub(crate) fn finish(mut self) -> Result<W, ParserError> {
+        self.end_statement()?;
+        self.write_raw("</BkToCstmrStmt></Document>")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    // Matches the struct serializer, which writes `None` fields as empty elements
+    fn write_optional<T: Serialize>(
+        &mut self,
+        name: &str,
+        value: &Option<T>,
+    ) -> Result<(), ParserError> {
+        match value {
+            Some(value) => self.write_element(name, value),
+            None => self.write_raw(&format!("<{}/>", name)),
+        }
+    }
+
+    fn write_element<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), ParserError> {
+        let xml =
+            to_string_with_root(name, value).map_err(|e| ParserError::Camt053(e.to_string()))?;
+        self.write_raw(&xml)
+    }
+
+    fn write_raw(&mut self, data: &str) -> Result<(), ParserError> {
+        self.writer.write_all(data.as_bytes())?;
+        Ok(())
+    }
+}
@@ -1,4 +1,8 @@
 use crate::Camt053;
+use crate::ParserError;
+use crate::camt053::format::{Entry, GroupHeader};
+use crate::camt053::stream::{Camt053Item, Camt053StreamReader, Camt053StreamWriter};
+use crate::errors::{ErrorLocation, ParseMode, WarningKind};
 use crate::traits::FinancialDataRead;
 use crate::traits::FinancialDataWrite;
 
@@ -204,3 +208,197 @@ fn test_read_write() {
     assert_eq!(first_amt1.value, first_amt2.value);
     assert_eq!(first_balance1.cdt_dbt_ind, first_balance2.cdt_dbt_ind);
 }
+
+fn read_by_stream(file: File) -> Camt053 {
+    let mut document = Camt053::default();
+    let reader = Camt053StreamReader::new(std::io::BufReader::new(file));
+    for item in reader {
+        match item.expect("Failed to stream CAMT.053 XML") {
+            Camt053Item::GroupHeader(grp_hdr) => document.bk_to_cstmr_stmt.grp_hdr = grp_hdr,
+            Camt053Item::Statement(stmt) => {
+                assert!(stmt.ntry.is_empty());
+                document.bk_to_cstmr_stmt.stmts.push(stmt);
+            }
+            Camt053Item::Entry(entry) => document
+                .bk_to_cstmr_stmt
+                .stmts
+                .last_mut()
+                .expect("Entry before statement header")
+                .ntry
+                .push(entry),
+            Camt053Item::StatementEnd => {}
+        }
+    }
+    document
+}
+
+#[test]
+fn test_stream_read_matches_document() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = PathBuf::from(manifest_dir).join("test_data");
+
+    for name in ["valid1.camt053", "valid2.camt053", "valid3.camt053"] {
+        let streamed = read_by_stream(File::open(path.join(name)).unwrap());
+        let document = Camt053::from_read(File::open(path.join(name)).unwrap()).unwrap();
+        assert_eq!(streamed, document, "{}", name);
+    }
+}
+
+#[test]
+fn test_stream_read_item_order() {
+    let xml = "<Document><BkToCstmrStmt>\
+        <GrpHdr><MsgId>M1</MsgId></GrpHdr>\
+        <Stmt><Id>S1</Id><Ntry><Sts>BOOK</Sts></Ntry><Ntry><Sts>PDNG</Sts></Ntry></Stmt>\
+        <Stmt><Id>S2</Id></Stmt>\
+        </BkToCstmrStmt></Document>";
+
+    let items: Vec<Camt053Item> = Camt053StreamReader::new(xml.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let kinds: Vec<&str> = items
+        .iter()
+        .map(|item| match item {
+            Camt053Item::GroupHeader(_) => "GrpHdr",
+            Camt053Item::Statement(_) => "Stmt",
+            Camt053Item::Entry(_) => "Ntry",
+            Camt053Item::StatementEnd => "/Stmt",
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        ["GrpHdr", "Stmt", "Ntry", "Ntry", "/Stmt", "Stmt", "/Stmt"]
+    );
+    assert_eq!(
+        items[3],
+        Camt053Item::Entry(Entry {
+            sts: Some("PDNG".to_string()),
+            ..Default::default()
+        })
+    );
+}
+
+#[test]
+fn test_stream_read_truncated_input() {
+    let xml = "<Document><BkToCstmrStmt><GrpHdr><MsgId>M1</MsgId></GrpHdr><Stmt><Ntry>";
+    let result: Result<Vec<Camt053Item>, ParserError> =
+        Camt053StreamReader::new(xml.as_bytes()).collect();
+    let error = result.unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert!(matches!(diagnostic.error, ParserError::Camt053(_)));
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::XmlPath(
+            "/Document/BkToCstmrStmt/Stmt[1]/Ntry[1]".to_string()
+        ))
+    );
+}
+
+const LOCATED_XML: &str = "<Document>\n<BkToCstmrStmt>\n\
+    <GrpHdr><MsgId>M1</MsgId></GrpHdr>\n\
+    <Stmt><Id>S1</Id></Stmt>\n\
+    <Stmt><Id>S2</Id>\n\
+    <Ntry><Sts>BOOK</Sts></Ntry>\n\
+    \t<Ntry><Sts>BOOK</Sts><Sts>PDNG</Sts></Ntry>\n\
+    </Stmt>\n</BkToCstmrStmt>\n</Document>";
+
+#[test]
+fn test_decode_error_location() {
+    let error = Camt053::from_read(LOCATED_XML.as_bytes()).unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::XmlPath(
+            "/Document/BkToCstmrStmt/Stmt[2]/Ntry[2]".to_string()
+        ))
+    );
+    assert_eq!(diagnostic.span.line, 7);
+    assert_eq!(diagnostic.span.column, 2);
+    assert_eq!(
+        diagnostic.span.offset,
+        LOCATED_XML.find("<Ntry><Sts>BOOK</Sts><Sts>").unwrap()
+    );
+    assert_eq!(
+        diagnostic.snippet.as_deref(),
+        Some("\t<Ntry><Sts>BOOK</Sts><Sts>PDNG</Sts></Ntry>")
+    );
+
+    let rendered = error.render(LOCATED_XML);
+    assert!(rendered.ends_with("7 | \t<Ntry><Sts>BOOK</Sts><Sts>PDNG</Sts></Ntry>\n  | \t^\n"));
+}
+
+#[test]
+fn test_lenient_skips_entry() {
+    let (camt053, warnings) =
+        Camt053::from_read_with_mode(LOCATED_XML.as_bytes(), ParseMode::Lenient).unwrap();
+    let stmts = &camt053.bk_to_cstmr_stmt.stmts;
+    assert_eq!(stmts.len(), 2);
+    assert_eq!(stmts[1].ntry.len(), 1);
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    let diagnostic = warnings[0].error.diagnostic().unwrap();
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::XmlPath(
+            "/Document/BkToCstmrStmt/Stmt[2]/Ntry[2]".to_string()
+        ))
+    );
+    assert_eq!(
+        diagnostic.snippet.as_deref(),
+        Some("\t<Ntry><Sts>BOOK</Sts><Sts>PDNG</Sts></Ntry>")
+    );
+
+    // Malformed XML cannot be skipped over
+    let xml = LOCATED_XML.replace("<Id>S2</Id>", "<Id>S2</Idd>");
+    assert!(Camt053::from_read_with_mode(xml.as_bytes(), ParseMode::Lenient).is_err());
+}
+
+#[test]
+fn test_syntax_error_location() {
+    let xml = LOCATED_XML.replace("<Id>S2</Id>", "<Id>S2</Idd>");
+    let source = std::io::BufReader::with_capacity(4, xml.as_bytes());
+    let result: Result<Vec<Camt053Item>, ParserError> = Camt053StreamReader::new(source).collect();
+
+    let error = result.unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert_eq!(diagnostic.span.line, 5);
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::XmlPath(
+            "/Document/BkToCstmrStmt/Stmt[2]/Id".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_stream_write_matches_document() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = PathBuf::from(manifest_dir).join("test_data");
+    let document = Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap();
+
+    let mut expected = Vec::new();
+    document.write_to(&mut expected).unwrap();
+
+    let statements = &document.bk_to_cstmr_stmt.stmts;
+    let mut writer =
+        Camt053StreamWriter::new(Vec::new(), &document.bk_to_cstmr_stmt.grp_hdr).unwrap();
+    for stmt in statements {
+        writer.start_statement(stmt).unwrap();
+        for entry in &stmt.ntry {
+            writer.write_entry(entry).unwrap();
+        }
+    }
+    let streamed = writer.finish().unwrap();
+
+    assert_eq!(
+        String::from_utf8(streamed).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+}
+
+#[test]
+fn test_stream_write_entry_outside_statement() {
+    let mut writer = Camt053StreamWriter::new(Vec::new(), &GroupHeader::default()).unwrap();
+    assert!(writer.write_entry(&Entry::default()).is_err());
+}
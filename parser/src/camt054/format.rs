@@ -0,0 +1,38 @@
+use crate::camt053::format::{Account, Entry, GroupHeader};
+use serde::{Deserialize, Serialize};
+
+/// A camt.054 bank-to-customer debit/credit notification, with the group
+/// header, account and entry types of [`Camt053`](crate::Camt053).
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename = "Document")]
+pub(crate) struct Camt054 {
+    #[serde(rename = "BkToCstmrDbtCdtNtfctn")]
+    pub bk_to_cstmr_dbt_cdt_ntfctn: BankToCustomerDebitCreditNotification,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct BankToCustomerDebitCreditNotification {
+    #[serde(rename = "GrpHdr")]
+    pub grp_hdr: GroupHeader,
+    #[serde(rename = "Ntfctn", default)]
+    pub ntfctns: Vec<Notification>,
+}
+
+/// The bookings on one account, like a statement without balances.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Notification {
+    #[serde(rename = "Id")]
+    pub id: Option<String>,
+    #[serde(
+        rename = "ElctrncSeqNb",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub elctrnc_seq_nb: Option<String>,
+    #[serde(rename = "CreDtTm", default, skip_serializing_if = "Option::is_none")]
+    pub cre_dt_tm: Option<String>,
+    #[serde(rename = "Acct")]
+    pub acct: Option<Account>,
+    #[serde(rename = "Ntry", default)]
+    pub ntry: Vec<Entry>,
+}
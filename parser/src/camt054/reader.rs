@@ -0,0 +1,12 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::camt054::format::Camt054;
+
+use quick_xml::de::from_str;
+
+impl FinancialDataRead for Camt054 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Camt054(e.to_string()))?;
+        from_str(&data).map_err(|e| ParserError::Camt054(e.to_string()))
+    }
+}
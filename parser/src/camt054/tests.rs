@@ -0,0 +1,60 @@
+use crate::camt054::format::Camt054;
+use crate::test_utils::read_test_file;
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+#[test]
+fn test_with_file() {
+    let camt054 = Camt054::from_read(read_test_file("valid1.camt054").as_bytes()).unwrap();
+
+    let notification = &camt054.bk_to_cstmr_dbt_cdt_ntfctn;
+    assert_eq!(
+        notification.grp_hdr.msg_id,
+        Some("NTF2510210001".to_string())
+    );
+    assert_eq!(notification.ntfctns.len(), 2);
+    let ntfctn = &notification.ntfctns[0];
+    assert_eq!(ntfctn.id, Some("NTF2510210001-1".to_string()));
+    assert_eq!(
+        ntfctn.acct.as_ref().unwrap().id.as_ref().unwrap().iban,
+        Some("DE89370400440532013000".to_string())
+    );
+    assert_eq!(ntfctn.ntry.len(), 2);
+    let entry = &ntfctn.ntry[1];
+    assert_eq!(entry.cdt_dbt_ind, Some("DBIT".to_string()));
+    let refs = entry.ntry_dtls[0].tx_dtls[0].refs.as_ref().unwrap();
+    assert_eq!(refs.end_to_end_id, Some("E2E-0002".to_string()));
+    assert!(notification.ntfctns[1].ntry.is_empty());
+}
+
+#[test]
+fn test_read_write() {
+    let camt054 = Camt054::from_read(read_test_file("valid1.camt054").as_bytes()).unwrap();
+
+    let mut data = Vec::new();
+    camt054.write_to(&mut data).unwrap();
+    let result = Camt054::from_read(data.as_slice()).unwrap();
+    let notification = &result.bk_to_cstmr_dbt_cdt_ntfctn;
+    assert_eq!(
+        notification.grp_hdr.msg_id,
+        Some("NTF2510210001".to_string())
+    );
+    assert_eq!(notification.ntfctns.len(), 2);
+    for (entry, original) in notification.ntfctns[0]
+        .ntry
+        .iter()
+        .zip(&camt054.bk_to_cstmr_dbt_cdt_ntfctn.ntfctns[0].ntry)
+    {
+        assert_eq!(entry.amt, original.amt);
+        assert_eq!(entry.cdt_dbt_ind, original.cdt_dbt_ind);
+        assert_eq!(
+            entry.ntry_dtls[0].tx_dtls[0].rmt_inf,
+            original.ntry_dtls[0].tx_dtls[0].rmt_inf
+        );
+    }
+}
+
+#[test]
+fn test_not_a_notification() {
+    let data = read_test_file("valid1.camt053");
+    assert!(Camt054::from_read(data.as_bytes()).is_err());
+}
@@ -0,0 +1,13 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::camt054::format::Camt054;
+
+use quick_xml::se;
+
+impl FinancialDataWrite for Camt054 {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = se::to_string(self).map_err(|e| ParserError::Camt054(e.to_string()))?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+/// A CFONB 120 file: one or more statements, each an old balance (`01`) to
+/// new balance (`07`) sequence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Cfonb {
+    pub statements: Vec<CfonbStatement>,
+}
+
+/// The records of one account from its old to its new balance.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CfonbStatement {
+    pub account: CfonbAccount,
+    pub old_balance: CfonbBalance,
+    pub new_balance: CfonbBalance,
+    pub movements: Vec<CfonbMovement>,
+}
+
+/// Bank, branch, currency and account number, repeated on every record.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CfonbAccount {
+    /// Code banque, 5 digits.
+    pub bank_code: String,
+    /// Code guichet, 5 digits.
+    pub branch_code: String,
+    pub currency: String,
+    /// Number of decimals of the amounts, `0` to `3`.
+    pub decimals: u32,
+    /// 11 characters, without the RIB key.
+    pub number: String,
+}
+
+/// Record `01` or `07`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CfonbBalance {
+    /// `DDMMYY`.
+    pub date: String,
+    /// In units of the last decimal, negative for a debit balance.
+    pub amount: i64,
+}
+
+/// Record `04` with the `05` complements that follow it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CfonbMovement {
+    /// The bank's own operation code, 4 characters.
+    pub internal_code: String,
+    /// Interbank operation code, 2 characters.
+    pub operation_code: String,
+    /// `DDMMYY`.
+    pub booking_date: String,
+    pub rejection_code: String,
+    /// `DDMMYY`.
+    pub value_date: String,
+    pub label: String,
+    pub entry_number: String,
+    pub exemption_code: String,
+    pub unavailability_code: String,
+    /// In units of the last decimal, negative for a debit.
+    pub amount: i64,
+    pub reference: String,
+    pub complements: Vec<Complement>,
+}
+
+/// Record `05`: a qualified text about the movement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Complement {
+    /// `LIB` label, `NPY` name of the payer, `NBE` name of the beneficiary,
+    /// `RCN` customer reference, and others.
+    pub qualifier: String,
+    pub text: String,
+}
+
+/// The last digit of a 14-character amount carries its sign: `{` and `A` …
+/// `I` for 0 … 9 of a credit, `}` and `J` … `R` for those of a debit.
+const POSITIVE: [char; 10] = ['{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I'];
+const NEGATIVE: [char; 10] = ['}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R'];
+
+/// Reads a 14-character signed amount such as `0000000012345{`.
+pub(crate) fn decode_amount(value: &str) -> Option<i64> {
+    let mut chars = value.chars();
+    let last = chars.next_back()?;
+    let digits = chars.as_str();
+    if digits.len() != 13 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let (negative, digit) = match POSITIVE.iter().position(|c| *c == last) {
+        Some(digit) => (false, digit),
+        None => (true, NEGATIVE.iter().position(|c| *c == last)?),
+    };
+    let amount = digits.parse::<i64>().ok()? * 10 + digit as i64;
+    Some(if negative { -amount } else { amount })
+}
+
+/// Writes an amount below 10^14 as 13 digits and a sign letter.
+pub(crate) fn encode_amount(amount: i64) -> String {
+    let magnitude = amount.unsigned_abs();
+    let letters = if amount < 0 { &NEGATIVE } else { &POSITIVE };
+    format!(
+        "{:013}{}",
+        magnitude / 10,
+        letters[(magnitude % 10) as usize]
+    )
+}
+
+/// Digits of the letters of a RIB, which are read as `A` = `J` = 1, `B` =
+/// `K` = `S` = 2 and so on.
+fn rib_digits(value: &str) -> Option<String> {
+    value
+        .chars()
+        .map(|c| match c {
+            '0'..='9' => Some(c),
+            'A'..='I' => char::from_digit(c as u32 - 'A' as u32 + 1, 10),
+            'J'..='R' => char::from_digit(c as u32 - 'J' as u32 + 1, 10),
+            'S'..='Z' => char::from_digit(c as u32 - 'S' as u32 + 2, 10),
+            _ => None,
+        })
+        .collect()
+}
+
+impl CfonbAccount {
+    /// The French BBAN of the account: bank, branch, number and the two
+    /// digits of the RIB key, which the records leave out.
+    pub(crate) fn bban(&self) -> Option<String> {
+        if self.bank_code.len() != 5 || self.branch_code.len() != 5 || self.number.len() != 11 {
+            return None;
+        }
+        let value = |part: &str| rib_digits(part)?.parse::<u64>().ok();
+        let remainder = (89 * value(&self.bank_code)?
+            + 15 * value(&self.branch_code)?
+            + 3 * value(&self.number)?)
+            % 97;
+        Some(format!(
+            "{}{}{}{:02}",
+            self.bank_code,
+            self.branch_code,
+            self.number,
+            97 - remainder
+        ))
+    }
+}
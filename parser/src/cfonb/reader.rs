@@ -0,0 +1,257 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::cfonb::format::*;
+use crate::errors::{ParseMode, ParseWarning, Recovery, WarningKind};
+use crate::fixed_width::{Line, RecordLayout, decode};
+
+/// CFONB 120 records, which some banks send without line breaks.
+pub(crate) static CFONB_RECORDS: RecordLayout = RecordLayout {
+    length: 120,
+    error: ParserError::Cfonb,
+    concatenated: true,
+    date_format: "DDMMYY",
+    blank_dates: true,
+};
+
+/// The signed amount at 91 … 104.
+fn amount(line: &Line) -> Result<i64, String> {
+    let value = line.raw(91, 14);
+    decode_amount(&value).ok_or_else(|| format!("Invalid amount '{}'", value))
+}
+
+/// Bank, branch, currency, decimals and account number at 3 … 32.
+fn account(line: &Line) -> Result<CfonbAccount, String> {
+    let decimals = line.raw(20, 1);
+    Ok(CfonbAccount {
+        bank_code: line.field(3, 5),
+        branch_code: line.field(12, 5),
+        currency: line.field(17, 3),
+        decimals: decimals
+            .parse()
+            .ok()
+            .filter(|d| *d <= 3)
+            .ok_or_else(|| format!("Invalid number of decimals '{}'", decimals))?,
+        number: line.field(22, 11),
+    })
+}
+
+fn balance(line: &Line) -> Result<CfonbBalance, String> {
+    Ok(CfonbBalance {
+        date: line.date(35, "balance date")?,
+        amount: amount(line)?,
+    })
+}
+
+fn movement(line: &Line) -> Result<CfonbMovement, String> {
+    Ok(CfonbMovement {
+        internal_code: line.field(8, 4),
+        operation_code: line.field(33, 2),
+        booking_date: line.date(35, "booking date")?,
+        rejection_code: line.field(41, 2),
+        value_date: line.date(43, "value date")?,
+        label: line.field(49, 31),
+        entry_number: line.field(82, 7),
+        exemption_code: line.field(89, 1),
+        unavailability_code: line.field(90, 1),
+        amount: amount(line)?,
+        reference: line.field(105, 16),
+        complements: Vec::new(),
+    })
+}
+
+fn total(statement: &CfonbStatement) -> i64 {
+    statement.movements.iter().map(|m| m.amount).sum()
+}
+
+/// Builds the statements from their records and checks each new balance
+/// against the old balance and the movements.
+struct CfonbReader<'a, 'r> {
+    data: &'a str,
+    recovery: &'r mut Recovery,
+    statements: Vec<CfonbStatement>,
+    statement: Option<CfonbStatement>,
+    /// Whether the complements of a movement that could not be read are being skipped.
+    skipping: bool,
+}
+
+impl CfonbReader<'_, '_> {
+    fn recover(
+        &mut self,
+        kind: WarningKind,
+        line: &Line,
+        message: impl Into<String>,
+    ) -> Result<(), ParserError> {
+        let error = line.error(self.data, message);
+        self.recovery.recover(kind, error)
+    }
+
+    /// The account of a record `04`, `05` or `07`, which must be that of the statement.
+    fn same_account(&self, line: &Line) -> Result<(), String> {
+        let account = account(line)?;
+        match &self.statement {
+            Some(statement) if statement.account == account => Ok(()),
+            Some(statement) => Err(format!(
+                "Record {} of account {} in the statement of account {}",
+                line.code(),
+                account.number,
+                statement.account.number
+            )),
+            None => Err(format!(
+                "Record {} before an old balance record 01",
+                line.code()
+            )),
+        }
+    }
+
+    fn record(&mut self, line: &Line) -> Result<(), ParserError> {
+        let data = self.data;
+        let code = line.code();
+        if code != "05" {
+            self.skipping = false;
+        }
+        match code.as_str() {
+            "01" => {
+                if self.statement.is_some() {
+                    self.recover(
+                        WarningKind::SkippedField,
+                        line,
+                        "Missing new balance record 07 before this record",
+                    )?;
+                    self.finish(false);
+                }
+                let account = account(line).map_err(|e| line.error(data, e))?;
+                let old_balance = balance(line).map_err(|e| line.error(data, e))?;
+                self.statement = Some(CfonbStatement {
+                    account,
+                    old_balance,
+                    ..Default::default()
+                });
+            }
+            "04" => {
+                if self.statement.is_none() {
+                    return Err(line.error(data, "Record 04 before an old balance record 01"));
+                }
+                match self.same_account(line).and_then(|_| movement(line)) {
+                    Ok(movement) => self.statement.as_mut().unwrap().movements.push(movement),
+                    Err(e) => {
+                        self.skipping = true;
+                        self.recover(WarningKind::SkippedEntry, line, e)?;
+                    }
+                }
+            }
+            "05" if self.skipping => {}
+            "05" => {
+                let complement = Complement {
+                    qualifier: line.field(46, 3),
+                    text: line.field(49, 70),
+                };
+                let movement = self
+                    .statement
+                    .as_mut()
+                    .and_then(|statement| statement.movements.last_mut());
+                match movement {
+                    Some(movement) => movement.complements.push(complement),
+                    None => {
+                        return Err(line.error(data, "Record 05 without a movement record 04"));
+                    }
+                }
+            }
+            "07" => {
+                self.same_account(line).map_err(|e| line.error(data, e))?;
+                let new_balance = balance(line).map_err(|e| line.error(data, e))?;
+                let statement = self.statement.as_mut().unwrap();
+                let computed = statement.old_balance.amount + total(statement);
+                statement.new_balance = new_balance;
+                if statement.new_balance.amount != computed {
+                    let message = format!(
+                        "New balance {} does not match the old balance and the movements, {}",
+                        statement.new_balance.amount, computed
+                    );
+                    self.recover(WarningKind::SkippedField, line, message)?;
+                }
+                self.finish(true);
+            }
+            other => {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!("Unknown record type '{}'", other),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the statement being read; `balanced` is false when its new
+    /// balance record is missing, and the balance its movements add up to
+    /// is taken instead.
+    fn finish(&mut self, balanced: bool) {
+        if let Some(mut statement) = self.statement.take() {
+            if !balanced {
+                statement.new_balance = CfonbBalance {
+                    date: statement
+                        .movements
+                        .last()
+                        .map_or(&statement.old_balance.date, |m| &m.booking_date)
+                        .clone(),
+                    amount: statement.old_balance.amount + total(&statement),
+                };
+            }
+            self.statements.push(statement);
+        }
+    }
+}
+
+impl Cfonb {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let lines = CFONB_RECORDS.lines(data)?;
+        if lines.is_empty() {
+            return Err(ParserError::Cfonb("Empty file".to_string()));
+        }
+        let mut reader = CfonbReader {
+            data,
+            recovery,
+            statements: Vec::new(),
+            statement: None,
+            skipping: false,
+        };
+        for line in &lines {
+            reader.record(line)?;
+        }
+        if reader.statement.is_some() {
+            let last = lines.last().unwrap();
+            reader.recover(
+                WarningKind::SkippedField,
+                last,
+                "Missing new balance record 07 after this record",
+            )?;
+            reader.finish(false);
+        }
+        Ok(Cfonb {
+            statements: reader.statements,
+        })
+    }
+}
+
+impl FinancialDataRead for Cfonb {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(cfonb, _)| cfonb)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        mut reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::Cfonb(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let cfonb = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((cfonb, recovery.into_warnings()))
+    }
+}
@@ -0,0 +1,125 @@
+use crate::Cfonb;
+use crate::cfonb::format::{CfonbAccount, decode_amount, encode_amount};
+use crate::errors::{ParseMode, WarningKind};
+use crate::test_utils::{patch, read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let cfonb = Cfonb::from_read(File::open(test_data_path("valid1.cfonb")).unwrap()).unwrap();
+
+    assert_eq!(cfonb.statements.len(), 1);
+    let statement = &cfonb.statements[0];
+    assert_eq!(statement.account.bank_code, "20041");
+    assert_eq!(statement.account.branch_code, "01005");
+    assert_eq!(statement.account.number, "0500013M026");
+    assert_eq!(statement.account.currency, "EUR");
+    assert_eq!(statement.account.decimals, 2);
+    assert_eq!(statement.old_balance.amount, 150_000);
+    assert_eq!(statement.old_balance.date, "011025");
+    assert_eq!(statement.new_balance.amount, 264_760);
+    assert_eq!(statement.movements.len(), 3);
+
+    let credit = &statement.movements[0];
+    assert_eq!(credit.amount, 125_000);
+    assert_eq!(credit.operation_code, "05");
+    assert_eq!(credit.label, "VIR SEPA RECU ACME SARL");
+    assert_eq!(credit.reference, "REF0001");
+    let qualifiers: Vec<&str> = credit
+        .complements
+        .iter()
+        .map(|c| c.qualifier.as_str())
+        .collect();
+    assert_eq!(qualifiers, ["NPY", "RCN", "LIB"]);
+    assert_eq!(credit.complements[1].text, "E2E-2025-0001");
+
+    let fee = &statement.movements[2];
+    assert_eq!(fee.amount, -1_250);
+    assert_eq!(fee.value_date, "041025");
+    assert_eq!(fee.exemption_code, "1");
+}
+
+#[test]
+fn test_read_write() {
+    let data = read_test_file("valid1.cfonb");
+    let cfonb = Cfonb::from_read(data.as_bytes()).unwrap();
+
+    let mut written = Vec::new();
+    cfonb.write_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), data);
+}
+
+#[test]
+fn test_signed_amounts() {
+    assert_eq!(decode_amount("0000000012500{"), Some(125_000));
+    assert_eq!(decode_amount("0000000000899}"), Some(-8_990));
+    assert_eq!(decode_amount("0000000000012C"), Some(123));
+    assert_eq!(decode_amount("0000000000012L"), Some(-123));
+    assert_eq!(decode_amount("00000000000123"), None);
+    assert_eq!(decode_amount("000000000012{"), None);
+    for amount in [0, 7, -7, 125_000, -8_990, 99_999_999_999_999] {
+        assert_eq!(decode_amount(&encode_amount(amount)), Some(amount));
+    }
+    assert_eq!(encode_amount(-1_250), "0000000000125}");
+}
+
+#[test]
+fn test_rib_key() {
+    let account = CfonbAccount {
+        bank_code: "20041".to_string(),
+        branch_code: "01005".to_string(),
+        number: "0500013M026".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(account.bban(), Some("20041010050500013M02606".to_string()));
+    let short = CfonbAccount {
+        number: "500013".to_string(),
+        ..account
+    };
+    assert_eq!(short.bban(), None);
+}
+
+#[test]
+fn test_without_line_breaks() {
+    let data = read_test_file("valid1.cfonb");
+    let expected = Cfonb::from_read(data.as_bytes()).unwrap();
+    let joined = data.replace('\n', "");
+    assert_eq!(Cfonb::from_read(joined.as_bytes()).unwrap(), expected);
+
+    let error = Cfonb::from_read(format!("{} ", joined).as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Record of 1201 characters"));
+}
+
+#[test]
+fn test_new_balance_check() {
+    let data = patch(&read_test_file("valid1.cfonb"), 10, 91, "0000000026477{");
+    let error = Cfonb::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("New balance 264770"));
+
+    let (cfonb, warnings) =
+        Cfonb::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(cfonb.statements[0].new_balance.amount, 264_770);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedField);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 10);
+}
+
+#[test]
+fn test_lenient_skips_invalid_movement() {
+    // An amount without its sign letter on the first movement
+    let data = patch(&read_test_file("valid1.cfonb"), 2, 104, "0");
+    assert!(Cfonb::from_read(data.as_bytes()).is_err());
+
+    let (cfonb, warnings) =
+        Cfonb::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    let movements = &cfonb.statements[0].movements;
+    assert_eq!(movements.len(), 2);
+    // Its complements are skipped with it
+    assert_eq!(movements[0].complements[0].qualifier, "NBE");
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 2);
+    // The new balance no longer adds up
+    assert_eq!(warnings.len(), 2);
+}
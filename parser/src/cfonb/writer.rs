@@ -0,0 +1,75 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::cfonb::format::*;
+use crate::cfonb::reader::CFONB_RECORDS;
+use crate::fixed_width::{Record, encode};
+
+/// A record with the account zone every record starts with.
+fn record(code: &str, account: &CfonbAccount) -> Record {
+    let mut record = Record::new(&CFONB_RECORDS, code);
+    record
+        .put(3, 5, &account.bank_code)
+        .put(12, 5, &account.branch_code)
+        .put(17, 3, &account.currency)
+        .put(20, 1, &account.decimals.to_string())
+        .put(22, 11, &account.number);
+    record
+}
+
+impl CfonbMovement {
+    /// Writes record `04` and its `05` complements.
+    fn write(&self, out: &mut String, account: &CfonbAccount) {
+        record("04", account)
+            .put(8, 4, &self.internal_code)
+            .put(33, 2, &self.operation_code)
+            .date(35, &self.booking_date)
+            .put(41, 2, &self.rejection_code)
+            .date(43, &self.value_date)
+            .put(49, 31, &self.label)
+            .put(82, 7, &self.entry_number)
+            .put(89, 1, &self.exemption_code)
+            .put(90, 1, &self.unavailability_code)
+            .put(91, 14, &encode_amount(self.amount))
+            .put(105, 16, &self.reference)
+            .end(out);
+        for complement in &self.complements {
+            record("05", account)
+                .put(8, 4, &self.internal_code)
+                .put(33, 2, &self.operation_code)
+                .date(35, &self.booking_date)
+                .put(46, 3, &complement.qualifier)
+                .put(49, 70, &complement.text)
+                .end(out);
+        }
+    }
+}
+
+impl Cfonb {
+    pub(crate) fn to_records(&self) -> String {
+        let mut out = String::new();
+        for statement in &self.statements {
+            let account = &statement.account;
+            record("01", account)
+                .date(35, &statement.old_balance.date)
+                .put(91, 14, &encode_amount(statement.old_balance.amount))
+                .end(&mut out);
+            for movement in &statement.movements {
+                movement.write(&mut out, account);
+            }
+            record("07", account)
+                .date(35, &statement.new_balance.date)
+                .put(91, 14, &encode_amount(statement.new_balance.amount))
+                .end(&mut out);
+        }
+        out
+    }
+}
+
+impl FinancialDataWrite for Cfonb {
+    /// Writes Latin-1, replacing characters outside it with `?`.
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), ParserError> {
+        writer.write_all(&encode(&self.to_records()))?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use crate::norma43::format::CURRENCIES;
+
+use chrono::NaiveDate;
+
+/// A 1C exchange file (`1CClientBankExchange`): the header, one section
+/// per account and period, and the payment documents.
+///
+/// Amounts are kept as written, decimals with a `.`; dates are
+/// `DD.MM.YYYY` in the file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ClientBankExchange {
+    /// `ВерсияФормата`, e.g. `1.03`.
+    pub version: String,
+    /// `Кодировка`, `Windows` for Windows-1251.
+    pub encoding: String,
+    /// `Отправитель`, the program or bank that wrote the file.
+    pub sender: String,
+    /// `Получатель`.
+    pub receiver: String,
+    /// `ДатаСоздания`.
+    pub creation_date: Option<NaiveDate>,
+    /// `ВремяСоздания`, `HH:MM:SS`.
+    pub creation_time: String,
+    /// `ДатаНачала` and `ДатаКонца` of the whole file.
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// `РасчСчет`, once per account of the file.
+    pub accounts: Vec<String>,
+    /// `Документ`, once per kind of document the file may hold.
+    pub document_kinds: Vec<String>,
+    pub sections: Vec<AccountSection>,
+    pub documents: Vec<PaymentDocument>,
+}
+
+/// `СекцияРасчСчет` to `КонецРасчСчет`: the balances and turnover of an
+/// account over a period, often a single day.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct AccountSection {
+    /// `ДатаНачала`.
+    pub start_date: NaiveDate,
+    /// `ДатаКонца`.
+    pub end_date: NaiveDate,
+    /// `РасчСчет`.
+    pub account: String,
+    /// `НачальныйОстаток`.
+    pub opening_balance: String,
+    /// `ВсегоПоступило`.
+    pub total_received: String,
+    /// `ВсегоСписано`.
+    pub total_written_off: String,
+    /// `КонечныйОстаток`.
+    pub closing_balance: String,
+}
+
+/// `СекцияДокумент` to `КонецДокумента`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PaymentDocument {
+    /// The value of `СекцияДокумент`, e.g. `Платежное поручение`.
+    pub kind: String,
+    /// `Номер`.
+    pub number: String,
+    /// `Дата`, the date of the document.
+    pub date: NaiveDate,
+    /// `Сумма`.
+    pub amount: String,
+    /// `ДатаСписано`, when the payer's account was debited.
+    pub written_off_date: Option<NaiveDate>,
+    /// `ДатаПоступило`, when the payee's account was credited.
+    pub received_date: Option<NaiveDate>,
+    /// The fields starting with `Плательщик`.
+    pub payer: Participant,
+    /// The fields starting with `Получатель`.
+    pub payee: Participant,
+    /// `ВидОплаты`, the code of the kind of document, e.g. `01`.
+    pub payment_code: String,
+    /// `Код`, the unique payment identifier (УИП).
+    pub code: String,
+    /// `Очередность`, the priority of the payment.
+    pub priority: String,
+    /// `НазначениеПлатежа`, or its older lines `НазначениеПлатежа1` to `6`
+    /// joined.
+    pub purpose: String,
+    /// The other fields, e.g. those of tax payments, in file order.
+    pub other: Vec<(String, String)>,
+}
+
+/// The fields of the payer or the payee, after their `Плательщик` or
+/// `Получатель` prefix.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Participant {
+    /// `Счет`.
+    pub account: String,
+    /// No suffix: the name, in older files preceded by `ИНН` and the number.
+    pub name: String,
+    /// `ИНН`, the tax number.
+    pub inn: String,
+    /// `КПП`, the registration reason code of an organisation.
+    pub kpp: String,
+    /// `1` to `4`: the name, or for payments through a correspondent bank
+    /// the name, settlement account, bank and city.
+    pub name_lines: Vec<String>,
+    /// `РасчСчет`, the settlement account of a payment through a
+    /// correspondent bank.
+    pub settlement_account: String,
+    /// `Банк1`, the name of the bank.
+    pub bank: String,
+    /// `Банк2`, the city of the bank.
+    pub bank_city: String,
+    /// `БИК`, the bank identification code of the Bank of Russia.
+    pub bik: String,
+    /// `Корсчет`, the correspondent account of the bank.
+    pub correspondent_account: String,
+}
+
+impl Participant {
+    /// The name without the `ИНН` prefix of older files, or the first name line.
+    pub(crate) fn display_name(&self) -> &str {
+        let name = self.name.trim();
+        let name = match name.strip_prefix("ИНН") {
+            Some(rest) => rest
+                .trim_start()
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .trim_start(),
+            None => name,
+        };
+        match (name, self.name_lines.first()) {
+            ("", Some(line)) => line.trim(),
+            (name, _) => name,
+        }
+    }
+}
+
+/// Whether `account` is a Russian account number of 20 digits.
+pub(crate) fn is_account_number(account: &str) -> bool {
+    account.len() == 20 && account.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The ISO currency of a Russian account number, whose digits 6 to 8 are
+/// the numeric currency code; `810` is the code of the rouble before 1998.
+pub(crate) fn account_currency(account: &str) -> Option<&'static str> {
+    match account.get(5..8)? {
+        "810" | "643" => Some("RUB"),
+        code => CURRENCIES
+            .iter()
+            .find(|(numeric, _)| *numeric == code)
+            .map(|(_, alpha)| *alpha),
+    }
+}
+
+/// Codes of `ВидОплаты` and the kinds of document they stand for.
+pub(crate) const DOCUMENT_KINDS: [(&str, &str); 6] = [
+    ("01", "Платежное поручение"),
+    ("02", "Платежное требование"),
+    ("06", "Инкассовое поручение"),
+    ("09", "Мемориальный ордер"),
+    ("16", "Платежный ордер"),
+    ("17", "Банковский ордер"),
+];
+
+/// Windows-1251 characters of bytes `0x80` to `0xBF`; `0xC0` to `0xFF`
+/// are `А` to `я` in order.
+pub(crate) const WINDOWS_1251: [char; 64] = [
+    '\u{0402}', '\u{0403}', '\u{201a}', '\u{0453}', '\u{201e}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{20ac}', '\u{2030}', '\u{0409}', '\u{2039}', '\u{040a}', '\u{040c}', '\u{040b}', '\u{040f}',
+    '\u{0452}', '\u{2018}', '\u{2019}', '\u{201c}', '\u{201d}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{fffd}', '\u{2122}', '\u{0459}', '\u{203a}', '\u{045a}', '\u{045c}', '\u{045b}', '\u{045f}',
+    '\u{00a0}', '\u{040e}', '\u{045e}', '\u{0408}', '\u{00a4}', '\u{0490}', '\u{00a6}', '\u{00a7}',
+    '\u{0401}', '\u{00a9}', '\u{0404}', '\u{00ab}', '\u{00ac}', '\u{00ad}', '\u{00ae}', '\u{0407}',
+    '\u{00b0}', '\u{00b1}', '\u{0406}', '\u{0456}', '\u{0491}', '\u{00b5}', '\u{00b6}', '\u{00b7}',
+    '\u{0451}', '\u{2116}', '\u{0454}', '\u{00bb}', '\u{0458}', '\u{0405}', '\u{0455}', '\u{0457}',
+];
+
+/// The Windows-1251 byte of a character, `None` for characters outside it.
+pub(crate) fn windows_1251_byte(c: char) -> Option<u8> {
+    match u32::from(c) {
+        code @ 0..=0x7f => Some(code as u8),
+        code @ 0x410..=0x44f => Some((code - 0x410 + 0xc0) as u8),
+        _ => WINDOWS_1251
+            .iter()
+            .position(|&other| other == c && c != '\u{fffd}')
+            .map(|at| 0x80 + at as u8),
+    }
+}
+
+/// The character of a Windows-1251 byte.
+pub(crate) fn windows_1251_char(byte: u8) -> char {
+    match byte {
+        0..=0x7f => byte as char,
+        0x80..=0xbf => WINDOWS_1251[usize::from(byte - 0x80)],
+        _ => char::from_u32(u32::from(byte) - 0xc0 + 0x410).unwrap(),
+    }
+}
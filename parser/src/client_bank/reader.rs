@@ -0,0 +1,473 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::camt053::balance::SignedAmount;
+use crate::client_bank::format::*;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+
+use chrono::NaiveDate;
+
+/// The first line of every file.
+pub(crate) const HEADER: &str = "1CClientBankExchange";
+
+/// 1C writes Windows-1251 (`Кодировка=Windows`); UTF-8 input is accepted
+/// as well.
+fn decode(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().into_iter().map(windows_1251_char).collect(),
+    }
+}
+
+/// One line, split at its first `=`; a line without one has an empty value.
+struct Line<'a> {
+    key: &'a str,
+    value: &'a str,
+    /// Byte offset and length of the line in the input.
+    offset: usize,
+    len: usize,
+}
+
+impl Line<'_> {
+    fn error(&self, data: &str, message: impl Into<String>) -> ParserError {
+        ParserError::ClientBank(message.into()).at_text(
+            data,
+            self.offset,
+            self.len,
+            &SourceSpan::default(),
+            None,
+        )
+    }
+}
+
+fn lines(data: &str) -> Vec<Line<'_>> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for text in data.split_inclusive('\n') {
+        let start = offset;
+        offset += text.len();
+        let text = text.trim_end_matches(['\r', '\n']);
+        let bom = text.len() - text.trim_start_matches('\u{feff}').len();
+        let text = &text[bom..];
+        if text.trim().is_empty() {
+            continue;
+        }
+        let (key, value) = text.split_once('=').unwrap_or((text, ""));
+        lines.push(Line {
+            key: key.trim(),
+            value: value.trim(),
+            offset: start + bom,
+            len: text.len(),
+        });
+    }
+    lines
+}
+
+/// A `DD.MM.YYYY` date.
+fn date(line: &Line) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(line.value, "%d.%m.%Y")
+        .map_err(|_| format!("Invalid {} '{}', expected DD.MM.YYYY", line.key, line.value))
+}
+
+/// An amount as a decimal with `.`; balances may be negative.
+fn amount(line: &Line) -> Result<String, String> {
+    let value = line.value.replace(',', ".");
+    SignedAmount::parse(value.strip_prefix('-').unwrap_or(&value))
+        .map(|_| value.clone())
+        .ok_or_else(|| format!("Invalid {} '{}'", line.key, line.value))
+}
+
+/// Sets the header field of `line`.
+fn header_field(file: &mut ClientBankExchange, line: &Line) -> Result<(), String> {
+    let value = line.value.to_string();
+    let optional_date = |line: &Line| match line.value {
+        "" => Ok(None),
+        _ => date(line).map(Some),
+    };
+    match line.key {
+        "ВерсияФормата" => file.version = value,
+        "Кодировка" => file.encoding = value,
+        "Отправитель" => file.sender = value,
+        "Получатель" => file.receiver = value,
+        "ДатаСоздания" => file.creation_date = optional_date(line)?,
+        "ВремяСоздания" => file.creation_time = value,
+        "ДатаНачала" => file.start_date = optional_date(line)?,
+        "ДатаКонца" => file.end_date = optional_date(line)?,
+        "РасчСчет" => file.accounts.push(value),
+        "Документ" => file.document_kinds.push(value),
+        key => return Err(format!("Unknown header field '{}'", key)),
+    }
+    Ok(())
+}
+
+/// Document fields read into [`PaymentDocument`]; the others are kept as they are.
+const DOCUMENT_FIELDS: [&str; 8] = [
+    "Номер",
+    "Дата",
+    "Сумма",
+    "ДатаСписано",
+    "ДатаПоступило",
+    "ВидОплаты",
+    "Код",
+    "Очередность",
+];
+
+/// Suffixes of the payer and payee fields read into [`Participant`].
+const PARTICIPANT_FIELDS: [&str; 13] = [
+    "",
+    "Счет",
+    "ИНН",
+    "КПП",
+    "1",
+    "2",
+    "3",
+    "4",
+    "РасчСчет",
+    "Банк1",
+    "Банк2",
+    "БИК",
+    "Корсчет",
+];
+
+/// The lines of a section, after the line that starts it.
+struct Block<'l, 'a> {
+    start: &'l Line<'a>,
+    lines: Vec<&'l Line<'a>>,
+}
+
+impl<'l, 'a> Block<'l, 'a> {
+    fn field(&self, key: &str) -> Option<&'l Line<'a>> {
+        self.lines
+            .iter()
+            .rev()
+            .find(|line| line.key == key)
+            .copied()
+    }
+
+    fn value(&self, key: &str) -> String {
+        self.field(key)
+            .map(|line| line.value.to_string())
+            .unwrap_or_default()
+    }
+
+    /// A required field, read with `read`; errors are located at the field,
+    /// or at the start of the section when it is missing.
+    fn required<T>(
+        &self,
+        data: &str,
+        key: &str,
+        read: impl Fn(&Line) -> Result<T, String>,
+    ) -> Result<T, ParserError> {
+        let line = self.field(key).ok_or_else(|| {
+            self.start
+                .error(data, format!("{} without {}", self.start.key, key))
+        })?;
+        read(line).map_err(|e| line.error(data, e))
+    }
+
+    fn optional_date(&self, data: &str, key: &str) -> Result<Option<NaiveDate>, ParserError> {
+        match self.field(key) {
+            Some(line) if !line.value.is_empty() => {
+                date(line).map(Some).map_err(|e| line.error(data, e))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn section(&self, data: &str) -> Result<AccountSection, ParserError> {
+        let balance = |key: &str| match self.field(key) {
+            Some(line) if !line.value.is_empty() => amount(line).map_err(|e| line.error(data, e)),
+            _ => Ok(String::new()),
+        };
+        Ok(AccountSection {
+            start_date: self.required(data, "ДатаНачала", date)?,
+            end_date: self.required(data, "ДатаКонца", date)?,
+            account: self.required(data, "РасчСчет", |line| Ok(line.value.to_string()))?,
+            opening_balance: balance("НачальныйОстаток")?,
+            total_received: balance("ВсегоПоступило")?,
+            total_written_off: balance("ВсегоСписано")?,
+            closing_balance: balance("КонечныйОстаток")?,
+        })
+    }
+
+    fn participant(&self, prefix: &str) -> Participant {
+        let value = |suffix: &str| self.value(&format!("{}{}", prefix, suffix));
+        Participant {
+            account: value("Счет"),
+            name: value(""),
+            inn: value("ИНН"),
+            kpp: value("КПП"),
+            name_lines: (1..=4)
+                .filter_map(|i| self.field(&format!("{}{}", prefix, i)))
+                .map(|line| line.value.to_string())
+                .collect(),
+            settlement_account: value("РасчСчет"),
+            bank: value("Банк1"),
+            bank_city: value("Банк2"),
+            bik: value("БИК"),
+            correspondent_account: value("Корсчет"),
+        }
+    }
+
+    fn document(&self, data: &str) -> Result<PaymentDocument, ParserError> {
+        let purpose = match self.field("НазначениеПлатежа") {
+            Some(line) => line.value.to_string(),
+            None => (1..=6)
+                .map(|i| self.value(&format!("НазначениеПлатежа{}", i)))
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        let known = |key: &str| {
+            let participant = key
+                .strip_prefix("Плательщик")
+                .or_else(|| key.strip_prefix("Получатель"))
+                .is_some_and(|suffix| PARTICIPANT_FIELDS.contains(&suffix));
+            let purpose = key
+                .strip_prefix("НазначениеПлатежа")
+                .is_some_and(|suffix| matches!(suffix, "" | "1" | "2" | "3" | "4" | "5" | "6"));
+            participant || purpose || DOCUMENT_FIELDS.contains(&key)
+        };
+        let mut other: Vec<(String, String)> = Vec::new();
+        for line in &self.lines {
+            if !known(line.key) {
+                other.push((line.key.to_string(), line.value.to_string()));
+            }
+        }
+        Ok(PaymentDocument {
+            kind: self.start.value.to_string(),
+            number: self.required(data, "Номер", |line| Ok(line.value.to_string()))?,
+            date: self.required(data, "Дата", date)?,
+            amount: self.required(data, "Сумма", amount)?,
+            written_off_date: self.optional_date(data, "ДатаСписано")?,
+            received_date: self.optional_date(data, "ДатаПоступило")?,
+            payer: self.participant("Плательщик"),
+            payee: self.participant("Получатель"),
+            payment_code: self.value("ВидОплаты"),
+            code: self.value("Код"),
+            priority: self.value("Очередность"),
+            purpose,
+            other,
+        })
+    }
+}
+
+/// What the lines being read belong to.
+enum State<'l, 'a> {
+    Header,
+    Section(Block<'l, 'a>),
+    Document(Block<'l, 'a>),
+    Ended,
+}
+
+/// Builds the file from its sections and checks the balances of the
+/// account sections.
+struct ClientBankReader<'a, 'r> {
+    data: &'a str,
+    recovery: &'r mut Recovery,
+    file: ClientBankExchange,
+}
+
+impl ClientBankReader<'_, '_> {
+    fn recover(
+        &mut self,
+        kind: WarningKind,
+        line: &Line,
+        message: impl Into<String>,
+    ) -> Result<(), ParserError> {
+        let error = line.error(self.data, message);
+        self.recovery.recover(kind, error)
+    }
+
+    fn header(&mut self, line: &Line) -> Result<(), ParserError> {
+        match header_field(&mut self.file, line) {
+            Ok(()) => Ok(()),
+            Err(e) => self.recover(WarningKind::SkippedField, line, e),
+        }
+    }
+
+    /// Adds an account section whose closing balance matches its opening
+    /// balance and turnover.
+    fn section(&mut self, block: &Block) -> Result<(), ParserError> {
+        let section = match block.section(self.data) {
+            Ok(section) => section,
+            Err(e) => return self.recovery.recover(WarningKind::SkippedStatement, e),
+        };
+        let amounts = [
+            &section.opening_balance,
+            &section.total_received,
+            &section.total_written_off,
+            &section.closing_balance,
+        ]
+        .map(|value| {
+            let negative = value.starts_with('-');
+            let amount = SignedAmount::parse(value.trim_start_matches('-'))?;
+            Some(if negative { amount.negate() } else { amount })
+        });
+        if let [
+            Some(opening),
+            Some(received),
+            Some(written_off),
+            Some(closing),
+        ] = amounts
+        {
+            let computed = opening.add(received).add(written_off.negate());
+            if computed.units != closing.units {
+                let line = block.field("КонечныйОстаток").unwrap();
+                let sign = if computed.units < 0 { "-" } else { "" };
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!(
+                        "КонечныйОстаток {} does not match the opening balance and the turnover, {}{}",
+                        section.closing_balance,
+                        sign,
+                        computed.magnitude()
+                    ),
+                )?;
+            }
+        }
+        self.file.sections.push(section);
+        Ok(())
+    }
+
+    fn document(&mut self, block: &Block) -> Result<(), ParserError> {
+        match block.document(self.data) {
+            Ok(document) => self.file.documents.push(document),
+            Err(e) => self.recovery.recover(WarningKind::SkippedEntry, e)?,
+        }
+        Ok(())
+    }
+
+    /// Closes the section being read at `line`, which should have ended it.
+    fn close<'l, 'a>(
+        &mut self,
+        state: State<'l, 'a>,
+        line: &Line,
+        end: &str,
+    ) -> Result<State<'l, 'a>, ParserError> {
+        match state {
+            State::Section(block) => {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!("Missing КонецРасчСчет {} this line", end),
+                )?;
+                self.section(&block)?;
+            }
+            State::Document(block) => {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!("Missing КонецДокумента {} this line", end),
+                )?;
+                self.document(&block)?;
+            }
+            state => return Ok(state),
+        }
+        Ok(State::Header)
+    }
+}
+
+impl ClientBankExchange {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let lines = lines(data);
+        let Some((first, lines)) = lines.split_first() else {
+            return Err(ParserError::ClientBank("Empty file".to_string()));
+        };
+        if first.key != HEADER {
+            return Err(first.error(data, format!("Missing {} header", HEADER)));
+        }
+        let mut reader = ClientBankReader {
+            data,
+            recovery,
+            file: ClientBankExchange::default(),
+        };
+        let mut state = State::Header;
+        for line in lines {
+            state = match (line.key, state) {
+                (_, State::Ended) => {
+                    return Err(line.error(data, "Line after КонецФайла"));
+                }
+                ("КонецРасчСчет", State::Section(block)) => {
+                    reader.section(&block)?;
+                    State::Header
+                }
+                ("КонецДокумента", State::Document(block)) => {
+                    reader.document(&block)?;
+                    State::Header
+                }
+                ("СекцияРасчСчет", state) => {
+                    reader.close(state, line, "before")?;
+                    State::Section(Block {
+                        start: line,
+                        lines: Vec::new(),
+                    })
+                }
+                ("СекцияДокумент", state) => {
+                    reader.close(state, line, "before")?;
+                    State::Document(Block {
+                        start: line,
+                        lines: Vec::new(),
+                    })
+                }
+                ("КонецФайла", state) => {
+                    reader.close(state, line, "before")?;
+                    State::Ended
+                }
+                ("КонецРасчСчет" | "КонецДокумента", state) => {
+                    reader.recover(
+                        WarningKind::SkippedField,
+                        line,
+                        format!("{} without a section", line.key),
+                    )?;
+                    state
+                }
+                (_, State::Header) => {
+                    reader.header(line)?;
+                    State::Header
+                }
+                (_, State::Section(mut block)) => {
+                    block.lines.push(line);
+                    State::Section(block)
+                }
+                (_, State::Document(mut block)) => {
+                    block.lines.push(line);
+                    State::Document(block)
+                }
+            };
+        }
+        let last = lines.last().unwrap_or(first);
+        if !matches!(state, State::Ended) {
+            reader.close(state, last, "after")?;
+            reader.recover(
+                WarningKind::SkippedField,
+                last,
+                "Missing КонецФайла after this line",
+            )?;
+        }
+        Ok(reader.file)
+    }
+}
+
+impl FinancialDataRead for ClientBankExchange {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(file, _)| file)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        mut reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::ClientBank(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let file = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((file, recovery.into_warnings()))
+    }
+}
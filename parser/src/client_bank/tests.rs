@@ -0,0 +1,159 @@
+use crate::ClientBankExchange;
+use crate::client_bank::format::{Participant, windows_1251_byte, windows_1251_char};
+use crate::errors::{ParseMode, WarningKind};
+use crate::test_utils::{read_test_bytes, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use chrono::NaiveDate;
+use std::fs::File;
+
+/// The text of a Windows-1251 test file, to be patched and read as UTF-8.
+fn read_windows_1251_file(name: &str) -> String {
+    read_test_bytes(name)
+        .into_iter()
+        .map(windows_1251_char)
+        .collect()
+}
+
+#[test]
+fn test_with_file() {
+    let file =
+        ClientBankExchange::from_read(File::open(test_data_path("valid1.1c")).unwrap()).unwrap();
+
+    assert_eq!(file.version, "1.03");
+    assert_eq!(file.sender, "СберБизнес");
+    assert_eq!(file.creation_date, NaiveDate::from_ymd_opt(2025, 10, 6));
+    assert_eq!(file.accounts, ["40702810900000012345"]);
+    assert_eq!(file.document_kinds, ["Платежное поручение"]);
+    assert_eq!(file.sections.len(), 2);
+    let section = &file.sections[1];
+    assert_eq!(section.opening_balance, "275000.00");
+    assert_eq!(section.total_written_off, "48350.00");
+    assert_eq!(section.closing_balance, "226650.00");
+    assert_eq!(file.documents.len(), 3);
+
+    let credit = &file.documents[0];
+    assert_eq!(credit.number, "118");
+    assert_eq!(credit.amount, "125000.00");
+    assert_eq!(credit.received_date, NaiveDate::from_ymd_opt(2025, 10, 1));
+    assert_eq!(credit.payer.inn, "7705123456");
+    assert_eq!(credit.payer.kpp, "770501001");
+    assert_eq!(credit.payer.bik, "044525593");
+    assert_eq!(credit.payer.name_lines, ["ООО \"Ромашка\""]);
+    assert_eq!(credit.payee.account, "40702810900000012345");
+    assert!(credit.purpose.starts_with("Оплата по счету № 118"));
+
+    let tax = &file.documents[2];
+    assert_eq!(tax.payee.kpp, "770801001");
+    assert_eq!(tax.other.len(), 7);
+    assert_eq!(
+        tax.other[1],
+        (
+            "ПоказательКБК".to_string(),
+            "18210301000011000110".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let data = read_test_bytes("valid1.1c");
+    let file = ClientBankExchange::from_read(data.as_slice()).unwrap();
+
+    let mut written = Vec::new();
+    file.write_to(&mut written).unwrap();
+    assert_eq!(written, data);
+
+    // UTF-8 input is read the same
+    let utf8 = read_windows_1251_file("valid1.1c");
+    assert_eq!(
+        ClientBankExchange::from_read(utf8.as_bytes()).unwrap(),
+        file
+    );
+}
+
+#[test]
+fn test_windows_1251() {
+    for byte in (0..=255u8).filter(|&byte| byte != 0x98) {
+        assert_eq!(windows_1251_byte(windows_1251_char(byte)), Some(byte));
+    }
+    assert_eq!(windows_1251_char(0xb9), '№');
+    assert_eq!(windows_1251_char(0xa8), 'Ё');
+    assert_eq!(windows_1251_byte('я'), Some(0xff));
+    assert_eq!(windows_1251_byte('€'), Some(0x88));
+    assert_eq!(windows_1251_byte('ü'), None);
+}
+
+#[test]
+fn test_display_name() {
+    let participant = Participant {
+        name: "ИНН 7705123456 ООО \"Ромашка\"".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(participant.display_name(), "ООО \"Ромашка\"");
+
+    let participant = Participant {
+        name: "ИНН 7705123456".to_string(),
+        name_lines: vec!["ООО \"Ромашка\"".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(participant.display_name(), "ООО \"Ромашка\"");
+}
+
+#[test]
+fn test_closing_balance() {
+    let data = read_windows_1251_file("valid1.1c")
+        .replace("КонечныйОстаток=275000.00", "КонечныйОстаток=275000.01");
+    let error = ClientBankExchange::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains(
+        "КонечныйОстаток 275000.01 does not match the opening balance and the turnover, 275000.00"
+    ));
+
+    let (file, warnings) =
+        ClientBankExchange::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(file.sections.len(), 2);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedField);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 19);
+}
+
+#[test]
+fn test_lenient_skips_invalid_document() {
+    let data =
+        read_windows_1251_file("valid1.1c").replace("\r\nДата=02.10.2025", "\r\nДата=32.10.2025");
+    let error = ClientBankExchange::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Invalid Дата '32.10.2025'"));
+
+    let (file, warnings) =
+        ClientBankExchange::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    let numbers: Vec<&str> = file.documents.iter().map(|d| d.number.as_str()).collect();
+    assert_eq!(numbers, ["118", "456"]);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 60);
+}
+
+#[test]
+fn test_missing_end_lines() {
+    let data = read_windows_1251_file("valid1.1c").replace("КонецФайла\r\n", "");
+    let error = ClientBankExchange::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Missing КонецФайла"));
+
+    // The document before the next one and the last one still end
+    let data = data.replace("КонецДокумента\r\n", "");
+    let (file, warnings) =
+        ClientBankExchange::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(file.documents.len(), 3);
+    let messages: Vec<String> = warnings.iter().map(|w| w.error.to_string()).collect();
+    assert_eq!(messages.len(), 4);
+    assert!(messages[0].contains("Missing КонецДокумента before this line"));
+    assert!(messages[2].contains("Missing КонецДокумента after this line"));
+    assert!(messages[3].contains("Missing КонецФайла after this line"));
+
+    let error = ClientBankExchange::from_read("ВерсияФормата=1.03\r\n".as_bytes()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Missing 1CClientBankExchange header")
+    );
+}
@@ -0,0 +1,144 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::client_bank::format::*;
+use crate::client_bank::reader::HEADER;
+
+use chrono::NaiveDate;
+
+/// Builds the `key=value` lines of a file, with Windows line ends.
+#[derive(Default)]
+struct Lines(String);
+
+impl Lines {
+    fn line(&mut self, text: &str) -> &mut Self {
+        self.0.push_str(text);
+        self.0.push_str("\r\n");
+        self
+    }
+
+    fn field(&mut self, key: &str, value: &str) -> &mut Self {
+        self.line(&format!("{}={}", key, value))
+    }
+
+    /// A field that is left out when empty.
+    fn optional(&mut self, key: &str, value: &str) -> &mut Self {
+        if !value.is_empty() {
+            self.field(key, value);
+        }
+        self
+    }
+
+    fn date(&mut self, key: &str, date: Option<NaiveDate>) -> &mut Self {
+        let value = date.map(|date| date.format("%d.%m.%Y").to_string());
+        self.field(key, value.as_deref().unwrap_or_default())
+    }
+}
+
+impl Participant {
+    /// Writes the account, name and bank fields after `prefix`; `date` is
+    /// `ДатаСписано` or `ДатаПоступило`, which 1C places after the account.
+    fn write(&self, lines: &mut Lines, prefix: &str, date: (&str, Option<NaiveDate>)) {
+        let key = |suffix: &str| format!("{}{}", prefix, suffix);
+        lines.field(&key("Счет"), &self.account);
+        if date.1.is_some() {
+            lines.date(date.0, date.1);
+        }
+        lines
+            .field(&key(""), &self.name)
+            .field(&key("ИНН"), &self.inn);
+        for (i, line) in self.name_lines.iter().enumerate() {
+            lines.field(&key(&(i + 1).to_string()), line);
+        }
+        lines
+            .optional(&key("РасчСчет"), &self.settlement_account)
+            .field(&key("Банк1"), &self.bank)
+            .optional(&key("Банк2"), &self.bank_city)
+            .field(&key("БИК"), &self.bik)
+            .field(&key("Корсчет"), &self.correspondent_account);
+    }
+}
+
+impl PaymentDocument {
+    fn write(&self, lines: &mut Lines) {
+        lines
+            .field("СекцияДокумент", &self.kind)
+            .field("Номер", &self.number)
+            .date("Дата", Some(self.date))
+            .field("Сумма", &self.amount);
+        self.payer
+            .write(lines, "Плательщик", ("ДатаСписано", self.written_off_date));
+        self.payee
+            .write(lines, "Получатель", ("ДатаПоступило", self.received_date));
+        lines
+            .field("ВидОплаты", &self.payment_code)
+            .optional("Код", &self.code)
+            .field("ПлательщикКПП", &self.payer.kpp)
+            .field("ПолучательКПП", &self.payee.kpp);
+        for (key, value) in &self.other {
+            lines.field(key, value);
+        }
+        lines
+            .field("Очередность", &self.priority)
+            .field("НазначениеПлатежа", &self.purpose)
+            .line("КонецДокумента");
+    }
+}
+
+impl AccountSection {
+    fn write(&self, lines: &mut Lines) {
+        lines
+            .line("СекцияРасчСчет")
+            .date("ДатаНачала", Some(self.start_date))
+            .date("ДатаКонца", Some(self.end_date))
+            .field("РасчСчет", &self.account)
+            .field("НачальныйОстаток", &self.opening_balance)
+            .field("ВсегоПоступило", &self.total_received)
+            .field("ВсегоСписано", &self.total_written_off)
+            .field("КонечныйОстаток", &self.closing_balance)
+            .line("КонецРасчСчет");
+    }
+}
+
+impl ClientBankExchange {
+    pub(crate) fn to_text(&self) -> String {
+        let mut lines = Lines::default();
+        lines
+            .line(HEADER)
+            .field("ВерсияФормата", &self.version)
+            .field("Кодировка", &self.encoding)
+            .field("Отправитель", &self.sender)
+            .field("Получатель", &self.receiver)
+            .date("ДатаСоздания", self.creation_date)
+            .field("ВремяСоздания", &self.creation_time)
+            .date("ДатаНачала", self.start_date)
+            .date("ДатаКонца", self.end_date);
+        for account in &self.accounts {
+            lines.field("РасчСчет", account);
+        }
+        for kind in &self.document_kinds {
+            lines.field("Документ", kind);
+        }
+        for section in &self.sections {
+            section.write(&mut lines);
+        }
+        for document in &self.documents {
+            document.write(&mut lines);
+        }
+        lines.line("КонецФайла");
+        lines.0
+    }
+}
+
+impl FinancialDataWrite for ClientBankExchange {
+    /// Writes Windows-1251, replacing characters outside it with `?`.
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), ParserError> {
+        let bytes: Vec<u8> = self
+            .to_text()
+            .chars()
+            .map(|c| windows_1251_byte(c).unwrap_or(b'?'))
+            .collect();
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+/// A CODA file: one or more statements, each a header (`0`) to trailer (`9`) sequence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Coda {
+    pub statements: Vec<CodaStatement>,
+}
+
+/// One account statement: records `0`, `1`, `2x`/`3x`, `4`, `8` and `9`.
+///
+/// The trailer is not kept: the reader checks its record count and totals,
+/// and the writer computes them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CodaStatement {
+    pub header: Header,
+    pub account: CodaAccount,
+    /// Sequence number of the paper statement, 3 digits.
+    pub paper_sequence: String,
+    /// Sequence number of the coded statement, 3 digits.
+    pub coded_sequence: String,
+    pub holder_name: String,
+    pub description: String,
+    pub old_balance: CodaBalance,
+    pub new_balance: CodaBalance,
+    pub movements: Vec<Movement>,
+    /// Texts of the `4` free communication records.
+    pub free_communications: Vec<String>,
+}
+
+/// Record `0`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Header {
+    /// `DDMMYY`.
+    pub creation_date: String,
+    /// Identification number of the bank, 3 digits.
+    pub bank_id: String,
+    pub duplicate: bool,
+    pub file_reference: String,
+    pub addressee_name: String,
+    pub bic: String,
+    /// Company number of the addressee.
+    pub addressee_id: String,
+    pub separate_application: String,
+    pub transaction_reference: String,
+    pub related_reference: String,
+}
+
+/// Account number and currency of records `1` and `8`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CodaAccount {
+    /// `0` Belgian account number, `1` foreign account number, `2` Belgian
+    /// IBAN, `3` foreign IBAN.
+    pub structure: char,
+    pub number: String,
+    pub currency: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CodaBalance {
+    pub debit: bool,
+    /// In thousandths of the currency unit.
+    pub amount: i64,
+    /// `DDMMYY`.
+    pub date: String,
+}
+
+/// A movement: record `21` with its `22` and `23` continuations and the
+/// `31` … `33` information records that follow it.
+///
+/// Movements with detail number `0` are what the account was booked with;
+/// those with the same sequence number and a higher detail number break a
+/// globalised one down.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Movement {
+    pub sequence: u32,
+    pub detail: u32,
+    pub bank_reference: String,
+    pub debit: bool,
+    /// In thousandths of the currency unit.
+    pub amount: i64,
+    /// `DDMMYY`, empty when unknown.
+    pub value_date: String,
+    /// Type, family, transaction and category: 8 digits.
+    pub transaction_code: String,
+    pub communication: Communication,
+    /// `DDMMYY`.
+    pub entry_date: String,
+    pub globalisation: char,
+    pub customer_reference: String,
+    pub counterparty_bic: String,
+    pub counterparty_account: String,
+    pub counterparty_currency: String,
+    pub counterparty_name: String,
+    pub information: Vec<Information>,
+}
+
+/// Records `31` … `33`: further details of a movement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Information {
+    pub bank_reference: String,
+    pub transaction_code: String,
+    pub communication: Communication,
+}
+
+/// The communication zone, continued over the records of a movement.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Communication {
+    Unstructured(String),
+    /// A structured communication of type `kind`, e.g. `101` for an OGM/VCS reference.
+    Structured {
+        kind: String,
+        text: String,
+    },
+}
+
+impl Default for Communication {
+    fn default() -> Self {
+        Communication::Unstructured(String::new())
+    }
+}
+
+/// A Belgian structured reference (OGM/VCS): 12 digits, the last two the
+/// remainder of the first ten divided by 97, or 97 for none.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Ogm(pub String);
+
+impl Ogm {
+    /// Reads the digits of `+++123/4567/89002+++`, `***…***` or a bare 12-digit reference.
+    pub(crate) fn parse(value: &str) -> Option<Ogm> {
+        let value = value.trim();
+        let inner = value
+            .strip_prefix("+++")
+            .and_then(|v| v.strip_suffix("+++"))
+            .or_else(|| value.strip_prefix("***")?.strip_suffix("***"))
+            .unwrap_or(value);
+        let digits: String = inner.chars().filter(|c| *c != '/').collect();
+        if digits.len() != 12 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let base: u64 = digits[..10].parse().ok()?;
+        let check: u64 = digits[10..].parse().ok()?;
+        let expected = match base % 97 {
+            0 => 97,
+            remainder => remainder,
+        };
+        (check == expected).then_some(Ogm(digits))
+    }
+}
+
+impl std::fmt::Display for Ogm {
+    /// `+++123/4567/89002+++`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "+++{}/{}/{}+++",
+            &self.0[..3],
+            &self.0[3..7],
+            &self.0[7..]
+        )
+    }
+}
+
+/// An ISO 20022 domain, family and sub-family code.
+pub(crate) type IsoCode = (&'static str, &'static str, &'static str);
+
+/// Transaction families mapped to ISO 20022 bank transaction codes (domain,
+/// family, sub-family), for credits and for debits.
+///
+/// Families without an entry map to nothing; CAMT.053 codes without an
+/// entry are written as family `30`, various transactions.
+pub(crate) const FAMILY_CODES: [(&str, [IsoCode; 2]); 6] = [
+    ("01", [("PMNT", "RCDT", "ESCT"), ("PMNT", "ICDT", "ESCT")]),
+    ("03", [("PMNT", "RCHQ", "CCHQ"), ("PMNT", "ICHQ", "CCHQ")]),
+    ("04", [("PMNT", "CCRD", "POSD"), ("PMNT", "CCRD", "POSD")]),
+    ("05", [("PMNT", "IDDT", "ESDD"), ("PMNT", "RDDT", "ESDD")]),
+    ("35", [("ACMT", "MCOP", "INTR"), ("ACMT", "MDOP", "CHRG")]),
+    ("41", [("PMNT", "RCDT", "XBCT"), ("PMNT", "ICDT", "XBCT")]),
+];
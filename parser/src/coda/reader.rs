@@ -0,0 +1,448 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::coda::format::*;
+use crate::errors::{ParseMode, ParseWarning, Recovery, WarningKind};
+use crate::fixed_width::{Line, RecordLayout, decode};
+
+/// CODA records are 128 characters with `DDMMYY` dates.
+pub(crate) static CODA_RECORDS: RecordLayout = RecordLayout {
+    length: 128,
+    error: ParserError::Coda,
+    concatenated: false,
+    date_format: "DDMMYY",
+    blank_dates: true,
+};
+
+/// `0`, `1`, `21` … `23`, `31` … `33`, `4`, `8` or `9`.
+fn id(line: &Line) -> String {
+    match line.char_at(1) {
+        '2' | '3' => line.raw(1, 2),
+        first => first.to_string(),
+    }
+}
+
+fn flag(line: &Line, position: usize) -> bool {
+    line.char_at(position) == '1'
+}
+
+/// A 15-digit amount with three decimals, in thousandths.
+fn amount(line: &Line, start: usize) -> Result<i64, String> {
+    line.number(start, 15, "amount")
+}
+
+fn header(line: &Line) -> Result<Header, String> {
+    if line.raw(15, 2) != "05" {
+        return Err(format!(
+            "Unsupported application code '{}'",
+            line.raw(15, 2)
+        ));
+    }
+    if line.raw(128, 1) != "2" {
+        return Err(format!("Unsupported CODA version '{}'", line.raw(128, 1)));
+    }
+    Ok(Header {
+        creation_date: line.date(6, "creation date")?,
+        bank_id: line.field(12, 3),
+        duplicate: line.raw(17, 1) == "D",
+        file_reference: line.field(25, 10),
+        addressee_name: line.field(35, 26),
+        bic: line.field(61, 11),
+        addressee_id: line.field(72, 11),
+        separate_application: line.field(84, 5),
+        transaction_reference: line.field(89, 16),
+        related_reference: line.field(105, 16),
+    })
+}
+
+/// The 37-character account number and currency zone of records `1` and `8`.
+fn account(structure: char, zone: &str) -> Result<CodaAccount, String> {
+    let zone: Vec<char> = zone.chars().collect();
+    let text =
+        |range: std::ops::Range<usize>| zone[range].iter().collect::<String>().trim().to_string();
+    let (number, currency) = match structure {
+        '0' => (text(0..12), text(13..16)),
+        '1' | '2' | '3' => (text(0..34), text(34..37)),
+        other => return Err(format!("Invalid account structure '{}'", other)),
+    };
+    Ok(CodaAccount {
+        structure,
+        number,
+        currency,
+    })
+}
+
+fn balance(line: &Line, sign: usize, date: usize, what: &str) -> Result<CodaBalance, String> {
+    Ok(CodaBalance {
+        debit: sign_of(line, sign)?,
+        amount: amount(line, sign + 1)?,
+        date: line.date(date, what)?,
+    })
+}
+
+fn sign_of(line: &Line, position: usize) -> Result<bool, String> {
+    match line.char_at(position) {
+        '0' => Ok(false),
+        '1' => Ok(true),
+        other => Err(format!("Invalid sign '{}'", other)),
+    }
+}
+
+fn old_balance(line: &Line, statement: &mut CodaStatement) -> Result<(), String> {
+    statement.account = account(line.char_at(2), &line.raw(6, 37))?;
+    statement.paper_sequence = line.field(3, 3);
+    statement.old_balance = balance(line, 43, 59, "old balance date")?;
+    statement.holder_name = line.field(65, 26);
+    statement.description = line.field(91, 35);
+    statement.coded_sequence = line.field(126, 3);
+    Ok(())
+}
+
+fn communication(kind: char, zone: String) -> Result<Communication, String> {
+    match kind {
+        '0' | ' ' => Ok(Communication::Unstructured(zone)),
+        '1' => {
+            let (kind, text) =
+                zone.split_at(zone.char_indices().nth(3).map_or(zone.len(), |(i, _)| i));
+            Ok(Communication::Structured {
+                kind: kind.to_string(),
+                text: text.to_string(),
+            })
+        }
+        other => Err(format!("Invalid communication type '{}'", other)),
+    }
+}
+
+/// Appends a continued part of the communication zone.
+fn continue_communication(communication: &mut Communication, part: String) {
+    match communication {
+        Communication::Unstructured(text) | Communication::Structured { text, .. } => {
+            text.push_str(&part)
+        }
+    }
+}
+
+/// Removes the blanks that pad the last record of a communication.
+fn trim_communication(communication: &mut Communication) {
+    match communication {
+        Communication::Unstructured(text) | Communication::Structured { text, .. } => {
+            text.truncate(text.trim_end().len())
+        }
+    }
+}
+
+fn transaction_code(line: &Line, start: usize) -> Result<String, String> {
+    let code = line.raw(start, 8);
+    if code.bytes().all(|b| b.is_ascii_digit()) || code.trim().is_empty() {
+        Ok(code.trim().to_string())
+    } else {
+        Err(format!("Invalid transaction code '{}'", code))
+    }
+}
+
+fn movement(line: &Line) -> Result<Movement, String> {
+    Ok(Movement {
+        sequence: line.number(3, 4, "sequence number")?,
+        detail: line.number(7, 4, "detail number")?,
+        bank_reference: line.field(11, 21),
+        debit: sign_of(line, 32)?,
+        amount: amount(line, 33)?,
+        value_date: line.date(48, "value date")?,
+        transaction_code: transaction_code(line, 54)?,
+        communication: communication(line.char_at(62), line.raw(63, 53))?,
+        entry_date: line.date(116, "entry date")?,
+        globalisation: line.char_at(125),
+        ..Default::default()
+    })
+}
+
+fn information(line: &Line) -> Result<Information, String> {
+    Ok(Information {
+        bank_reference: line.field(11, 21),
+        transaction_code: transaction_code(line, 32)?,
+        communication: communication(line.char_at(40), line.raw(41, 73))?,
+    })
+}
+
+/// Record count and movement totals of the statement being read.
+#[derive(Default)]
+struct Totals {
+    records: u64,
+    debit: i64,
+    credit: i64,
+}
+
+/// Builds the statements from their records and checks the continuation
+/// codes and trailers against them.
+struct CodaReader<'a, 'r> {
+    data: &'a str,
+    recovery: &'r mut Recovery,
+    statements: Vec<CodaStatement>,
+    statement: Option<(CodaStatement, Totals)>,
+    /// Records the previous one announced, by its continuation or link code.
+    expected: &'static [&'static str],
+    /// Whether the parts of a movement that could not be read are being skipped.
+    skipping: bool,
+}
+
+impl CodaReader<'_, '_> {
+    fn recover(
+        &mut self,
+        kind: WarningKind,
+        line: &Line,
+        message: impl Into<String>,
+    ) -> Result<(), ParserError> {
+        let error = line.error(self.data, message);
+        self.recovery.recover(kind, error)
+    }
+
+    fn last_movement(&mut self, line: &Line) -> Result<&mut Movement, ParserError> {
+        let data = self.data;
+        self.statement
+            .as_mut()
+            .and_then(|(statement, _)| statement.movements.last_mut())
+            .ok_or_else(|| line.error(data, format!("Record {} without a movement", id(line))))
+    }
+
+    /// What the continuation code at 126 and the link code at 128 of a record announce.
+    fn announce(line: &Line, continued: &'static [&'static str]) -> &'static [&'static str] {
+        if flag(line, 126) && !continued.is_empty() {
+            continued
+        } else if flag(line, 128) {
+            &["31"]
+        } else {
+            &[]
+        }
+    }
+
+    fn record(&mut self, line: &Line) -> Result<(), ParserError> {
+        let data = self.data;
+        let id = id(line);
+        if !self.expected.is_empty() && !self.expected.contains(&id.as_str()) {
+            let message = format!(
+                "Record {} follows a record announcing record {}",
+                id,
+                self.expected.join(" or ")
+            );
+            self.recover(WarningKind::SkippedField, line, message)?;
+        } else if self.expected.is_empty()
+            && matches!(id.as_str(), "22" | "23" | "32" | "33")
+            && !self.skipping
+        {
+            self.recover(
+                WarningKind::SkippedField,
+                line,
+                format!("Record {} without a record announcing it", id),
+            )?;
+        }
+        self.expected = &[];
+
+        if id == "0" {
+            if self.statement.is_some() {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    "Missing trailer record 9 before this record",
+                )?;
+                self.finish();
+            }
+            let header = header(line).map_err(|e| line.error(data, e))?;
+            self.statement = Some((
+                CodaStatement {
+                    header,
+                    ..Default::default()
+                },
+                Totals::default(),
+            ));
+            return Ok(());
+        }
+        let Some((_, totals)) = &mut self.statement else {
+            return Err(line.error(data, format!("Record {} before the header record 0", id)));
+        };
+        if matches!(
+            id.as_str(),
+            "1" | "21" | "22" | "23" | "31" | "32" | "33" | "8"
+        ) {
+            totals.records += 1;
+        }
+
+        match id.as_str() {
+            "1" => {
+                let (statement, _) = self.statement.as_mut().unwrap();
+                old_balance(line, statement).map_err(|e| line.error(data, e))?;
+            }
+            "21" => {
+                self.skipping = false;
+                match movement(line) {
+                    Ok(movement) => {
+                        self.expected = Self::announce(line, &["22", "23"]);
+                        let (statement, totals) = self.statement.as_mut().unwrap();
+                        if movement.detail == 0 {
+                            match movement.debit {
+                                true => totals.debit += movement.amount,
+                                false => totals.credit += movement.amount,
+                            }
+                        }
+                        statement.movements.push(movement);
+                    }
+                    Err(e) => {
+                        self.skipping = true;
+                        self.recover(WarningKind::SkippedEntry, line, e)?;
+                    }
+                }
+            }
+            "22" | "23" if self.skipping => {}
+            "22" => {
+                self.expected = Self::announce(line, &["23"]);
+                let movement = self.last_movement(line)?;
+                continue_communication(&mut movement.communication, line.raw(11, 53));
+                movement.customer_reference = line.field(64, 35);
+                movement.counterparty_bic = line.field(99, 11);
+            }
+            "23" => {
+                self.expected = Self::announce(line, &[]);
+                let movement = self.last_movement(line)?;
+                movement.counterparty_account = line.field(11, 34);
+                movement.counterparty_currency = line.field(45, 3);
+                movement.counterparty_name = line.field(48, 35);
+                continue_communication(&mut movement.communication, line.raw(83, 43));
+            }
+            "31" | "32" | "33" if self.skipping => {}
+            "31" => match information(line) {
+                Ok(information) => {
+                    self.expected = Self::announce(line, &["32", "33"]);
+                    self.last_movement(line)?.information.push(information);
+                }
+                Err(e) => self.recover(WarningKind::SkippedField, line, e)?,
+            },
+            "32" | "33" => {
+                self.expected = Self::announce(line, if id == "32" { &["33"] } else { &[] });
+                let part = if id == "32" {
+                    line.raw(11, 105)
+                } else {
+                    line.raw(11, 90)
+                };
+                if let Some(information) = self.last_movement(line)?.information.last_mut() {
+                    continue_communication(&mut information.communication, part);
+                }
+            }
+            "4" => {
+                let (statement, _) = self.statement.as_mut().unwrap();
+                statement.free_communications.push(line.field(33, 80));
+            }
+            "8" => {
+                let (statement, _) = self.statement.as_mut().unwrap();
+                statement.new_balance =
+                    balance(line, 42, 58, "new balance date").map_err(|e| line.error(data, e))?;
+                self.expected = if flag(line, 128) { &["4"] } else { &[] };
+            }
+            "9" => self.trailer(line)?,
+            other => {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!("Unknown record type '{}'", other),
+                )?;
+            }
+        }
+        if !id.starts_with(['2', '3']) {
+            self.skipping = false;
+        }
+        Ok(())
+    }
+
+    /// Checks the trailer against the records of the statement and closes it.
+    fn trailer(&mut self, line: &Line) -> Result<(), ParserError> {
+        let Some((_, totals)) = &self.statement else {
+            return Ok(());
+        };
+        let computed = [
+            (
+                "Record count",
+                totals.records as i64,
+                line.number::<i64>(17, 6, "record count"),
+            ),
+            ("Debit total", totals.debit, amount(line, 23)),
+            ("Credit total", totals.credit, amount(line, 38)),
+        ];
+        for (what, computed, trailer) in computed {
+            let error = match trailer {
+                Ok(value) if value == computed => continue,
+                Ok(value) => format!(
+                    "{} {} does not match the records, {}",
+                    what, value, computed
+                ),
+                Err(e) => e,
+            };
+            self.recover(WarningKind::SkippedField, line, error)?;
+        }
+        self.finish();
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        if let Some((mut statement, _)) = self.statement.take() {
+            for movement in &mut statement.movements {
+                trim_communication(&mut movement.communication);
+                for information in &mut movement.information {
+                    trim_communication(&mut information.communication);
+                }
+            }
+            self.statements.push(statement);
+        }
+    }
+}
+
+impl Coda {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let lines = CODA_RECORDS.lines(data)?;
+        if lines.is_empty() {
+            return Err(ParserError::Coda("Empty file".to_string()));
+        }
+        let mut reader = CodaReader {
+            data,
+            recovery,
+            statements: Vec::new(),
+            statement: None,
+            expected: &[],
+            skipping: false,
+        };
+        for line in &lines {
+            reader.record(line)?;
+        }
+        if reader.statement.is_some() {
+            let last = lines.last().unwrap();
+            reader.recover(
+                WarningKind::SkippedField,
+                last,
+                "Missing trailer record 9 after this record",
+            )?;
+            reader.finish();
+        }
+        Ok(Coda {
+            statements: reader.statements,
+        })
+    }
+}
+
+impl FinancialDataRead for Coda {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(coda, _)| coda)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        mut reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::Coda(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let coda = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((coda, recovery.into_warnings()))
+    }
+}
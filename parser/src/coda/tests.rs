@@ -0,0 +1,160 @@
+use crate::Coda;
+use crate::ParserError;
+use crate::coda::format::{Communication, Ogm};
+use crate::errors::{ParseMode, WarningKind};
+use crate::test_utils::{patch, read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let coda = Coda::from_read(File::open(test_data_path("valid1.coda")).unwrap()).unwrap();
+
+    assert_eq!(coda.statements.len(), 1);
+    let statement = &coda.statements[0];
+    assert_eq!(statement.header.bic, "KREDBEBB");
+    assert_eq!(statement.header.creation_date, "201025");
+    assert_eq!(statement.account.structure, '2');
+    assert_eq!(statement.account.number, "BE68539007547034");
+    assert_eq!(statement.account.currency, "EUR");
+    assert_eq!(statement.old_balance.amount, 10_000_000);
+    assert_eq!(statement.new_balance.amount, 11_247_500);
+    assert_eq!(statement.movements.len(), 5);
+    assert_eq!(
+        statement.free_communications,
+        ["THANK YOU FOR BANKING WITH US"]
+    );
+
+    let credit = &statement.movements[0];
+    assert_eq!(credit.transaction_code, "00150000");
+    assert_eq!(
+        credit.communication,
+        Communication::Structured {
+            kind: "101".to_string(),
+            text: "123456789002".to_string()
+        }
+    );
+    assert_eq!(credit.customer_reference, "E2E-0001");
+    assert_eq!(credit.counterparty_bic, "GEBABEBB");
+    assert_eq!(credit.counterparty_account, "BE71096123456769");
+    assert_eq!(credit.counterparty_name, "ACME NV");
+
+    let batch = &statement.movements[1];
+    assert!(batch.debit);
+    assert_eq!(batch.globalisation, '1');
+    assert_eq!(batch.information.len(), 1);
+    assert_eq!(
+        batch.information[0].communication,
+        Communication::Unstructured(
+            "INVOICES 2025-118 AND 2025-119 FOR THE OFFICE SUPPLIES DELIVERED IN SEPTEMBER 2025"
+                .to_string()
+        )
+    );
+    assert_eq!(
+        (
+            statement.movements[3].sequence,
+            statement.movements[3].detail
+        ),
+        (2, 2)
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let data = read_test_file("valid1.coda");
+    let coda = Coda::from_read(data.as_bytes()).unwrap();
+
+    let mut written = Vec::new();
+    coda.write_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), data);
+}
+
+#[test]
+fn test_latin1() {
+    let data = read_test_file("valid1.coda");
+    let mut bytes = patch(&data, 2, 65, "CAF# GLOBEX").into_bytes();
+    // `É` in Latin-1
+    let at = bytes.iter().position(|b| *b == b'#').unwrap();
+    bytes[at] = 0xc9;
+    let coda = Coda::from_read(bytes.as_slice()).unwrap();
+    assert_eq!(coda.statements[0].holder_name, "CAF\u{c9} GLOBEX");
+
+    let mut written = Vec::new();
+    coda.write_to(&mut written).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn test_ogm() {
+    let ogm = Ogm::parse("+++123/4567/89002+++").unwrap();
+    assert_eq!(ogm.0, "123456789002");
+    assert_eq!(ogm.to_string(), "+++123/4567/89002+++");
+    assert_eq!(Ogm::parse("***123/4567/89002***"), Some(ogm));
+    // A remainder of 0 gives check digits 97
+    assert!(Ogm::parse("000000009797").is_some());
+    assert_eq!(Ogm::parse("123456789003"), None);
+    assert_eq!(Ogm::parse("12345678900"), None);
+}
+
+#[test]
+fn test_continuation_codes() {
+    // Record 21 of the first movement no longer announces its 22 record
+    let data = patch(&read_test_file("valid1.coda"), 3, 126, "0");
+    let error = Coda::from_read(data.as_bytes()).unwrap_err();
+    let ParserError::Located(diagnostic) = &error else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert_eq!(diagnostic.span.line, 4);
+    assert!(
+        error
+            .to_string()
+            .contains("Record 22 without a record announcing it")
+    );
+
+    let (coda, warnings) = Coda::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(
+        coda.statements[0].movements[0].customer_reference,
+        "E2E-0001"
+    );
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedField);
+
+    // Record 31 announces a 32 record that does not follow
+    let data: String = read_test_file("valid1.coda")
+        .lines()
+        .filter(|line| !line.starts_with("32"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let error = Coda::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("announcing record 32 or 33"));
+}
+
+#[test]
+fn test_trailer_totals() {
+    let data = patch(&read_test_file("valid1.coda"), 14, 23, "000000000252000");
+    let error = Coda::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Debit total 252000"));
+
+    let (coda, warnings) = Coda::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(coda.statements[0].movements.len(), 5);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedField);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 14);
+}
+
+#[test]
+fn test_lenient_skips_invalid_movement() {
+    // An invalid value date on the batch movement
+    let data = patch(&read_test_file("valid1.coda"), 6, 48, "321325");
+    assert!(Coda::from_read(data.as_bytes()).is_err());
+
+    let (coda, warnings) = Coda::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    let movements = &coda.statements[0].movements;
+    assert_eq!(movements.len(), 4);
+    assert!(movements.iter().all(|m| m.information.is_empty()));
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 6);
+    // The trailer's debit total still counts the skipped movement
+    assert_eq!(warnings.len(), 2);
+}
@@ -0,0 +1,216 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::coda::format::*;
+use crate::coda::reader::CODA_RECORDS;
+use crate::fixed_width::{Record, encode};
+
+fn account_zone(account: &CodaAccount) -> String {
+    match account.structure {
+        '0' => format!("{:<12} {:<3}", account.number, account.currency),
+        _ => format!("{:<34}{:<3}", account.number, account.currency),
+    }
+}
+
+/// Splits a communication over parts of the given lengths, dropping what does not fit.
+fn split(text: &str, lengths: &[usize]) -> Vec<String> {
+    let mut chars = text.chars();
+    lengths
+        .iter()
+        .map(|len| chars.by_ref().take(*len).collect())
+        .collect()
+}
+
+/// The structure code and the communication zone text.
+fn communication(communication: &Communication) -> (char, String) {
+    match communication {
+        Communication::Unstructured(text) => ('0', text.clone()),
+        Communication::Structured { kind, text } => ('1', format!("{:0>3.3}{}", kind, text)),
+    }
+}
+
+impl Movement {
+    /// Writes records `21` … `23` and `31` … `33`; returns how many.
+    fn write(&self, out: &mut String, statement_sequence: &str) -> u64 {
+        let (structure, text) = communication(&self.communication);
+        let parts = split(&text, &[53, 53, 43]);
+        let has_22 = !parts[1].is_empty()
+            || !self.customer_reference.is_empty()
+            || !self.counterparty_bic.is_empty();
+        let has_23 = !parts[2].is_empty()
+            || !self.counterparty_account.is_empty()
+            || !self.counterparty_name.is_empty();
+        let linked = !self.information.is_empty();
+
+        Record::new(&CODA_RECORDS, "21")
+            .number(3, 4, self.sequence)
+            .number(7, 4, self.detail)
+            .put(11, 21, &self.bank_reference)
+            .flag(32, self.debit)
+            .number(33, 15, self.amount)
+            .date(48, &self.value_date)
+            .put(54, 8, &self.transaction_code)
+            .put(62, 1, &structure.to_string())
+            .put(63, 53, &parts[0])
+            .date(116, &self.entry_date)
+            .number(122, 3, statement_sequence)
+            .put(125, 1, &self.globalisation.to_string())
+            .flag(126, has_22 || has_23)
+            .flag(128, linked && !has_22 && !has_23)
+            .end(out);
+        let mut records = 1;
+
+        if has_22 {
+            Record::new(&CODA_RECORDS, "22")
+                .number(3, 4, self.sequence)
+                .number(7, 4, self.detail)
+                .put(11, 53, &parts[1])
+                .put(64, 35, &self.customer_reference)
+                .put(99, 11, &self.counterparty_bic)
+                .flag(126, has_23)
+                .flag(128, linked && !has_23)
+                .end(out);
+            records += 1;
+        }
+        if has_23 {
+            Record::new(&CODA_RECORDS, "23")
+                .number(3, 4, self.sequence)
+                .number(7, 4, self.detail)
+                .put(11, 34, &self.counterparty_account)
+                .put(45, 3, &self.counterparty_currency)
+                .put(48, 35, &self.counterparty_name)
+                .put(83, 43, &parts[2])
+                .flag(126, false)
+                .flag(128, linked)
+                .end(out);
+            records += 1;
+        }
+
+        for (i, information) in self.information.iter().enumerate() {
+            let linked = i + 1 < self.information.len();
+            let (structure, text) = communication(&information.communication);
+            let parts = split(&text, &[73, 105, 90]);
+            Record::new(&CODA_RECORDS, "31")
+                .number(3, 4, self.sequence)
+                .number(7, 4, self.detail)
+                .put(11, 21, &information.bank_reference)
+                .put(32, 8, &information.transaction_code)
+                .put(40, 1, &structure.to_string())
+                .put(41, 73, &parts[0])
+                .flag(126, !parts[1].is_empty())
+                .flag(128, linked && parts[1].is_empty())
+                .end(out);
+            records += 1;
+            if !parts[1].is_empty() {
+                Record::new(&CODA_RECORDS, "32")
+                    .number(3, 4, self.sequence)
+                    .number(7, 4, self.detail)
+                    .put(11, 105, &parts[1])
+                    .flag(126, !parts[2].is_empty())
+                    .flag(128, linked && parts[2].is_empty())
+                    .end(out);
+                records += 1;
+            }
+            if !parts[2].is_empty() {
+                Record::new(&CODA_RECORDS, "33")
+                    .number(3, 4, self.sequence)
+                    .number(7, 4, self.detail)
+                    .put(11, 90, &parts[2])
+                    .flag(126, false)
+                    .flag(128, linked)
+                    .end(out);
+                records += 1;
+            }
+        }
+        records
+    }
+}
+
+impl CodaStatement {
+    /// Writes records `0` to `9`; `last` tells the trailer whether another statement follows.
+    fn write(&self, out: &mut String, last: bool) {
+        let header = &self.header;
+        Record::new(&CODA_RECORDS, "0")
+            .number(2, 4, 0)
+            .date(6, &header.creation_date)
+            .put(12, 3, &header.bank_id)
+            .put(15, 2, "05")
+            .put(17, 1, if header.duplicate { "D" } else { " " })
+            .put(25, 10, &header.file_reference)
+            .put(35, 26, &header.addressee_name)
+            .put(61, 11, &header.bic)
+            .put(72, 11, &header.addressee_id)
+            .put(84, 5, &header.separate_application)
+            .put(89, 16, &header.transaction_reference)
+            .put(105, 16, &header.related_reference)
+            .put(128, 1, "2")
+            .end(out);
+
+        Record::new(&CODA_RECORDS, "1")
+            .put(2, 1, &self.account.structure.to_string())
+            .put(3, 3, &self.paper_sequence)
+            .put(6, 37, &account_zone(&self.account))
+            .flag(43, self.old_balance.debit)
+            .number(44, 15, self.old_balance.amount)
+            .date(59, &self.old_balance.date)
+            .put(65, 26, &self.holder_name)
+            .put(91, 35, &self.description)
+            .put(126, 3, &self.coded_sequence)
+            .end(out);
+
+        let mut records = 2;
+        let (mut debit, mut credit) = (0, 0);
+        for movement in &self.movements {
+            if movement.detail == 0 {
+                match movement.debit {
+                    true => debit += movement.amount,
+                    false => credit += movement.amount,
+                }
+            }
+            records += movement.write(out, &self.paper_sequence);
+        }
+
+        Record::new(&CODA_RECORDS, "8")
+            .put(2, 3, &self.paper_sequence)
+            .put(5, 37, &account_zone(&self.account))
+            .flag(42, self.new_balance.debit)
+            .number(43, 15, self.new_balance.amount)
+            .date(58, &self.new_balance.date)
+            .flag(128, !self.free_communications.is_empty())
+            .end(out);
+
+        for (i, text) in self.free_communications.iter().enumerate() {
+            Record::new(&CODA_RECORDS, "4")
+                .number(3, 4, i + 1)
+                .number(7, 4, 0)
+                .put(33, 80, text)
+                .flag(128, false)
+                .end(out);
+        }
+
+        Record::new(&CODA_RECORDS, "9")
+            .number(17, 6, records)
+            .number(23, 15, debit)
+            .number(38, 15, credit)
+            .put(128, 1, if last { "2" } else { "1" })
+            .end(out);
+    }
+}
+
+impl Coda {
+    pub(crate) fn to_records(&self) -> String {
+        let mut out = String::new();
+        for (i, statement) in self.statements.iter().enumerate() {
+            statement.write(&mut out, i + 1 == self.statements.len());
+        }
+        out
+    }
+}
+
+impl FinancialDataWrite for Coda {
+    /// Writes Latin-1, replacing characters outside it with `?`.
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), ParserError> {
+        writer.write_all(&encode(&self.to_records()))?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -1,11 +1,53 @@
+use crate::Bai2;
 use crate::Camt053;
+use crate::Camt054;
+use crate::Cfonb;
+use crate::ClientBankExchange;
+use crate::Coda;
+use crate::CsvWrapper;
+use crate::FormatOptions;
+use crate::JsonStatements;
+use crate::Mt900;
 use crate::Mt940;
+use crate::Mt950;
+use crate::Norma43;
+use crate::Ofx;
+use crate::Pain001;
 use crate::ParserError;
+use crate::Qif;
 use crate::SupportedFormats;
+use crate::Xlsx;
 use crate::XmlWrapper;
+use crate::XmlWriteOptions;
+use crate::camt053::format::{GroupHeader, Statement};
+use crate::camt053::stream::{Camt053Item, Camt053StreamReader, Camt053StreamWriter};
+use crate::converter::graph::{
+    ConversionContext, ConversionGraph, ConvertFn, Converter, FnConverter, OptionsConvertFn,
+    SharedBuffer, without_position,
+};
+use crate::converter::mt940xml_wrapper::Mt940XmlVersion;
+use crate::converter::to_camt053::{
+    camt_account, camt_entry, csv_to_camt053, mt940_entry, mt940_to_camt053, mt950_to_camt053,
+    xlsx_to_camt053,
+};
+use crate::converter::to_json::{json_entry, json_statement_account};
+use crate::converter::to_mt900::camt054_to_mt900;
+use crate::converter::to_mt940::{camt053_to_mt940, entry_error, mt940_statement, statement_line};
+use crate::converter::to_mt950::camt053_to_mt950;
+use crate::converter::to_pain001::{camt053_to_pain001, csv_to_pain001};
+use crate::converter::to_xlsx::camt053_to_xlsx;
+use crate::diff::{DocumentDiff, diff};
+use crate::errors::{ParseWarning, Recovery, WarningKind};
+use crate::filter::{EntryFilter, FilterReport};
+use crate::json::format::JsonEntryLine;
+use crate::json::reader::NdjsonLineReader;
+use crate::merge::{Period, SplitBy, merge, split};
+use crate::mt940::stream::{FinMessage, Mt940Item, Mt940ItemReader, SwiftStreamReader};
+use crate::payment_status::{PaymentLinks, PaymentStatusReport, link};
 use crate::traits::FinancialDataRead;
 use crate::traits::FinancialDataWrite;
 
+use std::cell::RefCell;
 use std::io::Write;
 
 /// Converts data between supported financial statement formats using input and output streams.
@@ -23,9 +65,9 @@ use std::io::Write;
 /// # Behavior
 ///
 /// - If the input and output formats are identical, the data is copied directly.
-/// - If a supported conversion path exists (e.g., MT940 → CAMT.053 or vice versa),
-///   the data is parsed and re-serialized.
-/// - For unsupported format combinations, an error of type [`ParserError::Converter`] is returned.
+/// - Otherwise the data is parsed and re-serialized along the route found by
+///   the default [`ConversionGraph`]: directly (e.g., MT940 → CAMT.053 or vice
+///   versa) or through CAMT.053 for pairs without a direct converter.
 ///
 /// # Errors
 ///
@@ -36,37 +78,479 @@ pub fn convert_streams(
     output_stream: Box<dyn std::io::Write>,
     output_format: SupportedFormats,
 ) -> Result<(), ParserError> {
-    // If formats are the same, just copy the data
-    if input_format == output_format {
-        copy_input_to_output(input_stream, output_stream)?;
-        return Ok(());
+    convert(
+        input_stream,
+        input_format,
+        output_stream,
+        output_format,
+        &mut ConversionContext::default(),
+    )
+}
+
+fn convert(
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    context: &mut ConversionContext,
+) -> Result<(), ParserError> {
+    let graph = ConversionGraph::default();
+    // A variant chosen by the options is written even when the formats are the same
+    if input_format == output_format
+        && context.options().rewrites(output_format)
+        && let Some(read) = builtin_converter(input_format.name(), "camt053")
+    {
+        return graph.convert_through(
+            input_stream,
+            input_format.name(),
+            read.as_ref(),
+            output_stream,
+            output_format.name(),
+            context,
+        );
     }
+    graph.convert(
+        input_stream,
+        input_format.name(),
+        output_stream,
+        output_format.name(),
+        context,
+    )
+}
 
-    match (input_format, output_format) {
-        (SupportedFormats::Mt940, SupportedFormats::Camt053) => {
-            convert_mt940_to_camt053(input_stream, output_stream)
-        }
-        (SupportedFormats::Camt053, SupportedFormats::Mt940) => {
-            convert_camt053_to_mt940(input_stream, output_stream)
-        }
-        (SupportedFormats::Mt940, SupportedFormats::Xml) => {
-            convert_mt940_to_xml(input_stream, output_stream)
-        }
-        (SupportedFormats::Camt053, SupportedFormats::Xml) => {
-            convert_camt053_to_xml(input_stream, output_stream)
-        }
-        _ => Err(ParserError::Converter(format!(
-            "Unsupported format conversion: {} to {}",
-            input_format.to_string(),
-            output_format.to_string(),
-        ))),
+/// The built-in converters of [`ConversionGraph::default`].
+///
+/// Every format converts to and from CAMT.053; the other pairs listed here
+/// convert directly, most of them streaming.
+pub(crate) fn builtin_converters() -> Vec<Box<dyn Converter>> {
+    let converters: [(&'static str, &'static str, ConvertFn); 34] = [
+        ("mt940", "camt053", mt940_to_camt053_stream),
+        ("camt053", "mt940", camt053_to_mt940_stream),
+        ("mt940", "xml", mt940_to_xml_stream),
+        ("camt053", "xml", camt053_to_xml_stream),
+        ("xml", "camt053", xml_to_camt053_stream),
+        ("xml", "mt940", xml_to_mt940_stream),
+        ("mt940", "json", mt940_to_json_stream),
+        ("camt053", "json", camt053_to_json_stream),
+        ("json", "camt053", json_to_camt053_stream),
+        ("json", "mt940", json_to_mt940_stream),
+        ("mt940", "ndjson", mt940_to_ndjson_stream),
+        ("camt053", "ndjson", camt053_to_ndjson_stream),
+        ("ndjson", "camt053", ndjson_to_camt053_stream),
+        ("camt053", "csv", camt053_to_csv_stream),
+        ("csv", "camt053", csv_to_camt053_stream),
+        ("camt053", "bai2", camt053_to_bai2_stream),
+        ("bai2", "camt053", bai2_to_camt053_stream),
+        ("camt053", "coda", camt053_to_coda_stream),
+        ("coda", "camt053", coda_to_camt053_stream),
+        ("ofx", "camt053", ofx_to_camt053_stream),
+        ("camt053", "cfonb", camt053_to_cfonb_stream),
+        ("cfonb", "camt053", cfonb_to_camt053_stream),
+        ("camt053", "norma43", camt053_to_norma43_stream),
+        ("norma43", "camt053", norma43_to_camt053_stream),
+        ("camt053", "1c", camt053_to_client_bank_stream),
+        ("1c", "camt053", client_bank_to_camt053_stream),
+        ("mt950", "camt053", mt950_to_camt053_stream),
+        ("camt053", "mt950", camt053_to_mt950_stream),
+        ("mt900", "camt054", mt900_to_camt054_stream),
+        ("camt054", "mt900", camt054_to_mt900_stream),
+        ("camt054", "camt053", camt054_to_camt053_stream),
+        ("camt053", "camt054", camt053_to_camt054_stream),
+        ("pain001", "camt053", pain001_to_camt053_stream),
+        ("xlsx", "camt053", xlsx_to_camt053_stream),
+    ];
+    let with_options: [(&'static str, &'static str, OptionsConvertFn); 6] = [
+        ("camt053", "pain001", camt053_to_pain001_stream),
+        ("csv", "pain001", csv_to_pain001_stream),
+        ("camt053", "ofx", camt053_to_ofx_stream),
+        ("camt053", "qif", camt053_to_qif_stream),
+        ("qif", "camt053", qif_to_camt053_stream),
+        ("camt053", "xlsx", camt053_to_xlsx_stream),
+    ];
+    let converters = converters
+        .into_iter()
+        .map(|(source, target, convert)| FnConverter::new(source, target, convert));
+    let with_options = with_options
+        .into_iter()
+        .map(|(source, target, convert)| FnConverter::with_options(source, target, convert));
+    converters
+        .chain(with_options)
+        .map(|converter| Box::new(converter) as Box<dyn Converter>)
+        .collect()
+}
+
+/// The built-in converter from `source` to `target`, if there is one.
+pub(crate) fn builtin_converter(source: &str, target: &str) -> Option<Box<dyn Converter>> {
+    builtin_converters()
+        .into_iter()
+        .find(|c| c.source() == source && c.target() == target)
+}
+
+/// Converts data between supported formats and formats XML output with `options`.
+///
+/// Behaves like [`convert_streams`], but when `output_format` produces XML
+/// (**CAMT.053** or **XML**) the result is reformatted according to
+/// [`XmlWriteOptions`] — indentation, XML declaration, attribute ordering
+/// or Exclusive XML Canonicalization. This also applies when input and
+/// output formats are identical, so an existing file can be pretty-printed
+/// or canonicalized.
+///
+/// # Behavior
+///
+/// - With default options, or for non-XML output formats, this is the same as [`convert_streams`].
+/// - Otherwise the converted document is buffered in memory before formatting.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if any parsing, I/O, conversion or XML formatting error occurs.
+pub fn convert_streams_with_options(
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+) -> Result<(), ParserError> {
+    convert_streams_with_mode(
+        input_stream,
+        input_format,
+        output_stream,
+        output_format,
+        options,
+        ConversionContext::default(),
+    )
+    .map(|_| ())
+}
+
+/// Converts data between supported formats in the
+/// [`ParseMode`](crate::ParseMode) of `context`, reading and writing the
+/// variants of the formats chosen by its [`FormatOptions`].
+///
+/// Behaves like [`convert_streams_with_options`]. In
+/// [`ParseMode::Lenient`](crate::ParseMode::Lenient) recoverable problems — an invalid MT940 `:61:` line, a CAMT.053 entry
+/// that fails to decode or has an unknown `CdtDbtInd`, a malformed NDJSON
+/// line, an MT940 message of an archive that cannot be parsed — leave the
+/// affected part out of the output and are returned as warnings, in input
+/// order.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] for problems that cannot be recovered from,
+/// e.g. malformed XML, and in [`ParseMode::Strict`](crate::ParseMode::Strict)
+/// for every problem.
+pub fn convert_streams_with_mode(
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    mut context: ConversionContext,
+) -> Result<Vec<ParseWarning>, ParserError> {
+    write_formatted(output_stream, output_format, options, |output| {
+        convert(
+            input_stream,
+            input_format,
+            output,
+            output_format,
+            &mut context,
+        )
+    })?;
+    Ok(context.into_warnings())
+}
+
+/// Converts data between supported formats, keeping only the statement
+/// entries selected by `filter`.
+///
+/// Behaves like [`convert_streams_with_mode`], with the input converted to
+/// CAMT.053, filtered and converted on to `output_format`. The balances of
+/// statements that lost entries are adjusted or flagged according to the
+/// filter's [`BalancePolicy`](crate::filter::BalancePolicy); the returned
+/// [`FilterReport`] tells which.
+///
+/// # Behavior
+///
+/// - The whole document is held in memory while it is filtered.
+/// - The data passes through CAMT.053 even when input and output formats
+///   are the same.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] under the same conditions as [`convert_streams_with_mode`].
+pub fn convert_streams_filtered(
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    mut context: ConversionContext,
+    filter: &EntryFilter,
+) -> Result<(Vec<ParseWarning>, FilterReport), ParserError> {
+    let step = FilterStep {
+        filter,
+        report: RefCell::default(),
+    };
+    write_formatted(output_stream, output_format, options, |output| {
+        ConversionGraph::default().convert_through(
+            input_stream,
+            input_format.name(),
+            &step,
+            output,
+            output_format.name(),
+            &mut context,
+        )
+    })?;
+    Ok((context.into_warnings(), step.report.take()))
+}
+
+/// Merges the statements of `input_streams` into one statement per account
+/// and [`Period`], e.g. daily statements into weekly or monthly ones.
+///
+/// All inputs are in `input_format`; the merged document is written to
+/// `output_stream` in `output_format`, with XML output formatted by
+/// `options`. Each merged statement opens with the opening balance of the
+/// earliest statement of its period and its closing balance is recomputed
+/// from that balance and all entries of the period.
+///
+/// # Behavior
+///
+/// - Every input is converted to CAMT.053 and held in memory.
+/// - Statements of one period keep the account, identification and
+///   sequence numbers of the earliest one and are ordered by date.
+/// - The group header is that of the first input.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] under the same conditions as [`convert_streams_with_mode`].
+pub fn merge_streams(
+    input_streams: Vec<Box<dyn std::io::Read>>,
+    input_format: SupportedFormats,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    mut context: ConversionContext,
+    period: Period,
+) -> Result<Vec<ParseWarning>, ParserError> {
+    let graph = ConversionGraph::default();
+    let documents = input_streams
+        .into_iter()
+        .map(|input| read_camt053(&graph, input, input_format, &mut context))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let merged = merge(documents, period);
+    write_camt053(
+        &graph,
+        &merged,
+        output_stream,
+        output_format,
+        options,
+        &mut context,
+    )?;
+    Ok(context.into_warnings())
+}
+
+/// Splits the document in `input_stream` by account or by calendar day.
+///
+/// Each part is written in `output_format` to the stream `create_output`
+/// returns for its key: the IBAN or other account id, or the date as
+/// `YYYY-MM-DD`. Parts are created in order of their first statement.
+/// When splitting by day, statements that span several days are cut into
+/// daily statements whose balances are carried from one day to the next.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] under the same conditions as
+/// [`convert_streams_with_mode`], and any error of `create_output`.
+pub fn split_streams(
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    mut context: ConversionContext,
+    by: SplitBy,
+    mut create_output: impl FnMut(&str) -> Result<Box<dyn std::io::Write>, ParserError>,
+) -> Result<Vec<ParseWarning>, ParserError> {
+    let graph = ConversionGraph::default();
+    let document = read_camt053(&graph, input_stream, input_format, &mut context)?;
+
+    for (key, part) in split(document, by) {
+        let output_stream = create_output(&key)?;
+        write_camt053(
+            &graph,
+            &part,
+            output_stream,
+            output_format,
+            options,
+            &mut context,
+        )?;
+    }
+    Ok(context.into_warnings())
+}
+
+/// Compares two versions of a statement document, e.g. a statement and
+/// the corrected one the bank resent.
+///
+/// The documents may be in different formats, e.g. MT940 and CAMT.053;
+/// both are read into the CAMT.053 model and compared there. See
+/// [`DocumentDiff`] for how statements and entries are matched.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if either document cannot be read, under the
+/// same conditions as [`convert_streams_with_mode`].
+pub fn diff_streams(
+    old_stream: Box<dyn std::io::Read>,
+    old_format: SupportedFormats,
+    new_stream: Box<dyn std::io::Read>,
+    new_format: SupportedFormats,
+    mut context: ConversionContext,
+) -> Result<(DocumentDiff, Vec<ParseWarning>), ParserError> {
+    let graph = ConversionGraph::default();
+    let old = read_camt053(&graph, old_stream, old_format, &mut context)?;
+    let new = read_camt053(&graph, new_stream, new_format, &mut context)?;
+    Ok((diff(&old, &new), context.into_warnings()))
+}
+
+/// Links the payment statuses of a pain.002 report to the entries of a
+/// statement document by end-to-end id, e.g. to find rejected payments that
+/// were booked anyway and accepted ones that never appeared.
+///
+/// The statement document may be in any format; it is read into the
+/// CAMT.053 model first. See [`PaymentLinks`] for the result.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the status report is not a pain.002
+/// document, or the statement document cannot be read under the same
+/// conditions as [`convert_streams_with_mode`].
+pub fn link_payment_statuses(
+    status_stream: Box<dyn std::io::Read>,
+    statement_stream: Box<dyn std::io::Read>,
+    statement_format: SupportedFormats,
+    mut context: ConversionContext,
+) -> Result<(PaymentLinks, Vec<ParseWarning>), ParserError> {
+    let report = PaymentStatusReport::from_read(status_stream)?;
+    let graph = ConversionGraph::default();
+    let camt053 = read_camt053(&graph, statement_stream, statement_format, &mut context)?;
+    Ok((link(&report, &camt053), context.into_warnings()))
+}
+
+/// Reads `input_stream` into the CAMT.053 model, converting it from `input_format` first.
+fn read_camt053(
+    graph: &ConversionGraph,
+    input_stream: Box<dyn std::io::Read>,
+    input_format: SupportedFormats,
+    context: &mut ConversionContext,
+) -> Result<Camt053, ParserError> {
+    let buffer = SharedBuffer::default();
+    graph.convert(
+        input_stream,
+        input_format.name(),
+        Box::new(buffer.clone()),
+        SupportedFormats::Camt053.name(),
+        context,
+    )?;
+
+    // Positions refer to the user's input only when it was CAMT.053 already
+    let locate = |error| match input_format {
+        SupportedFormats::Camt053 => error,
+        _ => without_position(error),
+    };
+    let (camt053, warnings) =
+        Camt053::from_read_with_mode(buffer.take().as_slice(), context.mode()).map_err(locate)?;
+    let warnings = warnings
+        .into_iter()
+        .map(|warning| ParseWarning {
+            kind: warning.kind,
+            error: locate(warning.error),
+        })
+        .collect();
+    context.recovery().extend(warnings);
+    Ok(camt053)
+}
+
+/// Writes `camt053` to `output_stream` in `output_format`.
+fn write_camt053(
+    graph: &ConversionGraph,
+    camt053: &Camt053,
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    context: &mut ConversionContext,
+) -> Result<(), ParserError> {
+    let mut data = Vec::new();
+    camt053.write_to(&mut data)?;
+    write_formatted(output_stream, output_format, options, |output| {
+        graph.convert_intermediate(
+            data,
+            SupportedFormats::Camt053.name(),
+            output,
+            output_format.name(),
+            context,
+        )
+    })
+}
+
+/// Runs `convert` into `output_stream`, or into a buffer that is then
+/// formatted with `options` when they apply to `output_format`.
+fn write_formatted(
+    output_stream: Box<dyn std::io::Write>,
+    output_format: SupportedFormats,
+    options: &XmlWriteOptions,
+    convert: impl FnOnce(Box<dyn std::io::Write>) -> Result<(), ParserError>,
+) -> Result<(), ParserError> {
+    if options.is_default() || !output_format.is_xml() {
+        return convert(output_stream);
+    }
+
+    let buffer = SharedBuffer::default();
+    convert(Box::new(buffer.clone()))?;
+
+    let xml = String::from_utf8(buffer.take()).map_err(|e| ParserError::Xml(e.to_string()))?;
+    let formatted = options.apply(&xml)?;
+
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+    buffered_writer.write_all(formatted.as_bytes())?;
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// CAMT.053 to CAMT.053 step of [`convert_streams_filtered`] that applies the filter.
+struct FilterStep<'a> {
+    filter: &'a EntryFilter,
+    report: RefCell<FilterReport>,
+}
+
+impl Converter for FilterStep<'_> {
+    fn source(&self) -> &str {
+        SupportedFormats::Camt053.name()
+    }
+
+    fn target(&self) -> &str {
+        SupportedFormats::Camt053.name()
+    }
+
+    fn convert(
+        &self,
+        input: Box<dyn std::io::Read>,
+        output: Box<dyn std::io::Write>,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError> {
+        let (mut camt053, warnings) = Camt053::from_read_with_mode(input, context.mode())?;
+        context.recovery().extend(warnings);
+        *self.report.borrow_mut() = self.filter.apply(&mut camt053);
+        camt053.write_to(output)
     }
 }
 
 /// Converts a stream of **MT940** data into **CAMT.053** format.
 ///
-/// Parses MT940 data from the input stream, converts it to a [`Camt053`] structure,
-/// and writes the resulting XML to the output stream.
+/// Reads the MT940 input one SWIFT message at a time, converts each message to a
+/// CAMT.053 statement and writes it before the next message is read.
+///
+/// # Behavior
+///
+/// - Every message of a multi-message archive becomes one `Stmt` of a single document.
+/// - The group header is taken from the first message.
+/// - Memory use is bounded by the largest single message, not by the file size.
 ///
 /// # Errors
 ///
@@ -75,23 +559,84 @@ pub fn convert_mt940_to_camt053(
     input_stream: Box<dyn std::io::Read>,
     output_stream: Box<dyn std::io::Write>,
 ) -> Result<(), ParserError> {
-    let mt940 = Mt940::from_read(input_stream)?;
-    let camt053_result: Result<Camt053, ParserError> = TryFrom::try_from(&mt940);
-    let camt053 = camt053_result?;
+    mt940_to_camt053_stream(input_stream, output_stream, &mut Recovery::default())
+}
 
-    camt053.write_to(output_stream)?;
+fn mt940_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    swift_to_camt053_stream::<Mt940>(
+        input_stream,
+        output_stream,
+        recovery,
+        mt940_to_camt053,
+        ParserError::Mt940("No MT940 message found".to_string()),
+    )
+}
+
+/// Converts the SWIFT messages of a stream one at a time and writes their
+/// statements into a single CAMT.053 document, with the group header of the
+/// first message. `missing` is returned for a stream without messages.
+fn swift_to_camt053_stream<M: FinMessage>(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    convert: fn(&M, &mut Recovery) -> Result<Camt053, ParserError>,
+    missing: ParserError,
+) -> Result<(), ParserError> {
+    let mut messages = SwiftStreamReader::<_, M>::with_mode(
+        std::io::BufReader::new(input_stream),
+        recovery.mode(),
+    );
+    let first = messages.next().transpose()?;
+    recovery.extend(messages.take_warnings());
+    let first = first.ok_or(missing)?;
+    let camt053 = convert(&first, recovery)?;
+
+    let mut writer = Camt053StreamWriter::new(
+        std::io::BufWriter::new(output_stream),
+        &camt053.bk_to_cstmr_stmt.grp_hdr,
+    )?;
+    write_statements(&mut writer, &camt053)?;
+
+    while let Some(message) = messages.next() {
+        let message = message?;
+        recovery.extend(messages.take_warnings());
+        let camt053 = convert(&message, recovery)?;
+        write_statements(&mut writer, &camt053)?;
+    }
+    recovery.extend(messages.take_warnings());
+
+    writer.finish()?;
+    Ok(())
+}
+
+/// Writes all statements of a [`Camt053`] document through a streaming writer.
+fn write_statements<W: Write>(
+    writer: &mut Camt053StreamWriter<W>,
+    camt053: &Camt053,
+) -> Result<(), ParserError> {
+    for stmt in &camt053.bk_to_cstmr_stmt.stmts {
+        writer.start_statement(stmt)?;
+        for entry in &stmt.ntry {
+            writer.write_entry(entry)?;
+        }
+    }
     Ok(())
 }
 
 /// Converts a stream of **CAMT.053** data into **MT940** format.
 ///
-/// Parses CAMT.053 XML from the input stream, converts it to one or more [`Mt940`] records,
-/// and writes them to the output stream.
+/// Reads the CAMT.053 XML one statement at a time, converts each statement to an
+/// [`Mt940`] record and writes it to the output stream before reading the next one.
 ///
 /// # Behavior
 ///
 /// - Multiple MT940 statements may be generated from a single CAMT.053 file.
 /// - Each MT940 record is separated by two newline characters for readability.
+/// - Memory use is bounded by the largest single statement, not by the file size.
 ///
 /// # Errors
 ///
@@ -100,9 +645,80 @@ pub fn convert_camt053_to_mt940(
     input_stream: Box<dyn std::io::Read>,
     output_stream: Box<dyn std::io::Write>,
 ) -> Result<(), ParserError> {
-    let camt053 = Camt053::from_read(input_stream)?;
-    let mt940_vec_result: Result<Vec<Mt940>, ParserError> = TryFrom::try_from(&camt053);
-    let mt940_vec = mt940_vec_result?;
+    camt053_to_mt940_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn camt053_to_mt940_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut reader =
+        Camt053StreamReader::with_mode(std::io::BufReader::new(input_stream), recovery.mode());
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+
+    let mut msg_id = String::new();
+    // The statement header and the MT940 lines of its entries converted so far
+    let mut current = None;
+    let mut entries = 0usize;
+    let mut written = 0usize;
+
+    while let Some(item) = reader.next() {
+        let item = item?;
+        recovery.extend(reader.take_warnings());
+        match item {
+            Camt053Item::GroupHeader(header) => msg_id = header.msg_id.unwrap_or_default(),
+            Camt053Item::Statement(stmt) => {
+                current = Some((stmt, Vec::new()));
+                entries = 0;
+            }
+            Camt053Item::Entry(entry) => {
+                let Some((stmt, lines)) = current.as_mut() else {
+                    continue;
+                };
+                entries += 1;
+                match statement_line(&entry) {
+                    Ok(line) => lines.push(line),
+                    Err(e) => {
+                        let error = reader.locate_entry(entry_error(stmt, entries - 1, e));
+                        recovery.recover(WarningKind::SkippedEntry, error)?;
+                    }
+                }
+            }
+            Camt053Item::StatementEnd => {
+                let Some((stmt, lines)) = current.take() else {
+                    continue;
+                };
+                let mt940 = match mt940_statement(&msg_id, &stmt, lines) {
+                    Ok(mt940) => mt940,
+                    Err(e) => {
+                        let error = reader.locate_statement(e);
+                        recovery.recover(WarningKind::SkippedStatement, error)?;
+                        continue;
+                    }
+                };
+                if written > 0 {
+                    buffered_writer.write_all(b"\n\n")?;
+                }
+                mt940.write_to(&mut buffered_writer)?;
+                written += 1;
+            }
+        }
+    }
+    recovery.extend(reader.take_warnings());
+
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// Converts a [`Camt053`] structure into MT940 records and writes them,
+/// separated by two newline characters, to the output stream.
+fn write_camt053_as_mt940(
+    camt053: &Camt053,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mt940_vec = camt053_to_mt940(camt053, recovery)?;
 
     let mut buffered_writer = std::io::BufWriter::new(output_stream);
 
@@ -134,7 +750,16 @@ pub fn convert_mt940_to_xml(
     input_stream: Box<dyn std::io::Read>,
     output_stream: Box<dyn std::io::Write>,
 ) -> Result<(), ParserError> {
-    let mt940 = Mt940::from_read(input_stream)?;
+    mt940_to_xml_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn mt940_to_xml_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (mt940, warnings) = Mt940::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
     let xml_result: Result<XmlWrapper, ParserError> = TryFrom::try_from(&mt940);
     let xml = xml_result?;
 
@@ -159,7 +784,16 @@ pub fn convert_camt053_to_xml(
     input_stream: Box<dyn std::io::Read>,
     output_stream: Box<dyn std::io::Write>,
 ) -> Result<(), ParserError> {
-    let camt053 = Camt053::from_read(input_stream)?;
+    camt053_to_xml_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn camt053_to_xml_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
     let xml_result: Result<XmlWrapper, ParserError> = TryFrom::try_from(&camt053);
     let xml = xml_result?;
 
@@ -167,22 +801,822 @@ pub fn convert_camt053_to_xml(
     Ok(())
 }
 
-/// Copies raw data from the input stream to the output stream.
+/// Converts an **XML** document into **CAMT.053** format.
+///
+/// Accepts both documents written by the XML conversions: the MT940 XML
+/// vocabulary, whose statement is converted like MT940 input, and CAMT.053 XML.
+fn xml_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let xml = XmlWrapper::from_read(input_stream)?;
+    let camt053 = if Mt940XmlVersion::detect(&xml.0).is_ok() {
+        let mt940: Mt940 = TryFrom::try_from(&xml)?;
+        mt940_to_camt053(&mt940, recovery)?
+    } else {
+        let (camt053, warnings) = Camt053::from_read_with_mode(xml.0.as_bytes(), recovery.mode())?;
+        recovery.extend(warnings);
+        camt053
+    };
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts an **XML** document into **MT940** format.
+///
+/// MT940 XML is mapped back field by field; CAMT.053 XML is converted like
+/// CAMT.053 input.
+fn xml_to_mt940_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let xml = XmlWrapper::from_read(input_stream)?;
+    if Mt940XmlVersion::detect(&xml.0).is_err() {
+        return camt053_to_mt940_stream(
+            Box::new(std::io::Cursor::new(xml.0)),
+            output_stream,
+            recovery,
+        );
+    }
+    let mt940: Mt940 = TryFrom::try_from(&xml)?;
+
+    mt940.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a stream of **MT940** data into the **JSON** statement model.
+///
+/// Every MT940 message is mapped to [`Camt053`], which the JSON model
+/// mirrors, and their statements are serialized as a single JSON document
+/// with the message id of the first message.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the MT940 data cannot be parsed, converted, or written.
+pub fn convert_mt940_to_json(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    mt940_to_json_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn mt940_to_json_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut messages = SwiftStreamReader::<_, Mt940>::with_mode(
+        std::io::BufReader::new(input_stream),
+        recovery.mode(),
+    );
+    let first = messages.next().transpose()?;
+    recovery.extend(messages.take_warnings());
+    let first = first.ok_or(ParserError::Mt940("No MT940 message found".to_string()))?;
+    let mut camt053 = mt940_to_camt053(&first, recovery)?;
+    while let Some(message) = messages.next() {
+        let message = message?;
+        recovery.extend(messages.take_warnings());
+        let stmts = mt940_to_camt053(&message, recovery)?.bk_to_cstmr_stmt.stmts;
+        camt053.bk_to_cstmr_stmt.stmts.extend(stmts);
+    }
+    recovery.extend(messages.take_warnings());
+    let json: JsonStatements = TryFrom::try_from(&camt053)?;
+
+    json.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a stream of **CAMT.053** data into the **JSON** statement model.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the CAMT.053 data cannot be parsed, converted, or written.
+pub fn convert_camt053_to_json(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    camt053_to_json_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn camt053_to_json_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let json: JsonStatements = TryFrom::try_from(&camt053)?;
+
+    json.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **JSON** statement document into **CAMT.053** format.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the JSON data cannot be parsed, has an unsupported
+/// version, or if writing fails.
+pub fn convert_json_to_camt053(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    json_to_camt053_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn json_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    _recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let json = JsonStatements::from_read(input_stream)?;
+    let camt053: Camt053 = TryFrom::try_from(&json)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **JSON** statement document into **MT940** format.
+///
+/// # Behavior
 ///
-/// This function is used when no conversion between formats is necessary.
+/// - One MT940 record is produced per statement in the document.
+/// - Each MT940 record is separated by two newline characters for readability.
 ///
 /// # Errors
 ///
-/// Returns a [`ParserError`] if an I/O error occurs during reading or writing.
-fn copy_input_to_output(
+/// Returns a [`ParserError`] if the JSON data cannot be parsed, converted, or written.
+pub fn convert_json_to_mt940(
     input_stream: Box<dyn std::io::Read>,
     output_stream: Box<dyn std::io::Write>,
 ) -> Result<(), ParserError> {
-    let mut input_buf = std::io::BufReader::new(input_stream);
-    let mut output_buf = std::io::BufWriter::new(output_stream);
+    json_to_mt940_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn json_to_mt940_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let json = JsonStatements::from_read(input_stream)?;
+    let camt053: Camt053 = TryFrom::try_from(&json)?;
+
+    write_camt053_as_mt940(&camt053, output_stream, recovery)
+}
 
-    std::io::copy(&mut input_buf, &mut output_buf)?;
-    output_buf.flush()?;
+/// Converts a stream of **MT940** data into **NDJSON**, one entry per line.
+///
+/// Messages are read one at a time and every line is written as soon as its
+/// statement line is converted, so long multi-message archives are
+/// converted in constant memory.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the MT940 data cannot be parsed, converted, or written.
+pub fn convert_mt940_to_ndjson(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    mt940_to_ndjson_stream(input_stream, output_stream, &mut Recovery::default())
+}
 
+fn mt940_to_ndjson_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut reader =
+        Mt940ItemReader::with_mode(std::io::BufReader::new(input_stream), recovery.mode());
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+
+    // The message header with the NDJSON fields of its statement
+    let mut current = None;
+    let mut index = 0;
+
+    while let Some(item) = reader.next() {
+        let item = item?;
+        recovery.extend(reader.take_warnings());
+        match item {
+            Mt940Item::Statement(mt940) => {
+                let camt053 = mt940_to_camt053(&mt940, recovery)?;
+                let message_id = camt053.bk_to_cstmr_stmt.grp_hdr.msg_id;
+                let stmt = camt053.bk_to_cstmr_stmt.stmts.into_iter().next();
+                let statement_id = stmt.as_ref().and_then(|stmt| stmt.id.clone());
+                let account = stmt
+                    .as_ref()
+                    .and_then(|stmt| stmt.acct.as_ref())
+                    .map(json_statement_account);
+                current = Some((mt940, message_id, statement_id, account));
+                index = 0;
+            }
+            Mt940Item::StatementLine(line) => {
+                let Some((mt940, message_id, statement_id, account)) = current.as_ref() else {
+                    continue;
+                };
+                index += 1;
+                let Some(entry) = mt940_entry(mt940, index - 1, &line, recovery)? else {
+                    continue;
+                };
+                let line = JsonEntryLine {
+                    message_id: message_id.clone(),
+                    statement_id: statement_id.clone(),
+                    account: account.clone(),
+                    entry: json_entry(&entry),
+                };
+                serde_json::to_writer(&mut buffered_writer, &line)
+                    .map_err(|e| ParserError::Json(e.to_string()))?;
+                buffered_writer.write_all(b"\n")?;
+            }
+            Mt940Item::StatementEnd => current = None,
+        }
+    }
+    recovery.extend(reader.take_warnings());
+
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// Converts a stream of **CAMT.053** data into **NDJSON**, one entry per line.
+///
+/// The CAMT.053 input is read entry by entry and every line is written as soon
+/// as its entry is parsed, so files of any size are converted in constant memory.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if the CAMT.053 data cannot be parsed, converted, or written.
+pub fn convert_camt053_to_ndjson(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    camt053_to_ndjson_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn camt053_to_ndjson_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut reader =
+        Camt053StreamReader::with_mode(std::io::BufReader::new(input_stream), recovery.mode());
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+
+    let mut message_id = None;
+    let mut statement_id = None;
+    let mut account = None;
+
+    for item in reader.by_ref() {
+        match item? {
+            Camt053Item::GroupHeader(header) => message_id = header.msg_id,
+            Camt053Item::Statement(stmt) => {
+                statement_id = stmt.id;
+                account = stmt.acct.as_ref().map(json_statement_account);
+            }
+            Camt053Item::Entry(entry) => {
+                let line = JsonEntryLine {
+                    message_id: message_id.clone(),
+                    statement_id: statement_id.clone(),
+                    account: account.clone(),
+                    entry: json_entry(&entry),
+                };
+                serde_json::to_writer(&mut buffered_writer, &line)
+                    .map_err(|e| ParserError::Json(e.to_string()))?;
+                buffered_writer.write_all(b"\n")?;
+            }
+            Camt053Item::StatementEnd => {}
+        }
+    }
+    recovery.extend(reader.take_warnings());
+
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// Converts **NDJSON** entry lines back into **CAMT.053** format.
+///
+/// # Behavior
+///
+/// - Consecutive lines with the same statement id and account form one statement.
+/// - Balances are not carried by NDJSON, so the resulting statements have none.
+/// - Lines are read and written one at a time, so memory use does not grow with the input.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if a line cannot be parsed or if writing fails.
+pub fn convert_ndjson_to_camt053(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+) -> Result<(), ParserError> {
+    ndjson_to_camt053_stream(input_stream, output_stream, &mut Recovery::default())
+}
+
+fn ndjson_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut lines = NdjsonLineReader::new(std::io::BufReader::new(input_stream), recovery.mode());
+
+    let first = lines.next().transpose()?;
+    let grp_hdr = GroupHeader {
+        msg_id: first.as_ref().and_then(|line| line.message_id.clone()),
+        ..Default::default()
+    };
+    let mut writer = Camt053StreamWriter::new(std::io::BufWriter::new(output_stream), &grp_hdr)?;
+
+    let mut current_key = None;
+    for line in first.into_iter().map(Ok).chain(lines.by_ref()) {
+        let line = line?;
+        let key = (line.statement_id.clone(), line.account.clone());
+        if current_key.as_ref() != Some(&key) {
+            writer.start_statement(&Statement {
+                id: line.statement_id.clone(),
+                acct: line.account.as_ref().map(camt_account),
+                ..Default::default()
+            })?;
+            current_key = Some(key);
+        }
+        writer.write_entry(&camt_entry(&line.entry))?;
+    }
+    recovery.extend(lines.take_warnings());
+
+    writer.finish()?;
+    Ok(())
+}
+
+/// Converts **CAMT.053** data into **CSV**, one row per entry.
+fn camt053_to_csv_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let csv: CsvWrapper = TryFrom::try_from(&camt053)?;
+
+    csv.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts **CSV** rows back into **CAMT.053** format.
+///
+/// Consecutive rows with the same statement id and account form one statement.
+fn csv_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let csv = CsvWrapper::from_read(input_stream)?;
+    let camt053 = csv_to_camt053(&csv, recovery)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **BAI2** file, one account per statement.
+fn camt053_to_bai2_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let bai2: Bai2 = TryFrom::try_from(&camt053)?;
+
+    bai2.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **BAI2** file into **CAMT.053** format, one statement per account.
+///
+/// In lenient mode transaction records that cannot be read and trailers
+/// whose control totals do not match are reported as warnings.
+fn bai2_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (bai2, warnings) = Bai2::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&bai2)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **CODA** file, one CODA statement per statement.
+fn camt053_to_coda_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let coda: Coda = TryFrom::try_from(&camt053)?;
+
+    coda.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CODA** file into **CAMT.053** format, one statement per CODA statement.
+///
+/// In lenient mode movements that cannot be read, continuation records
+/// that were not announced and trailers whose totals do not match are
+/// reported as warnings.
+fn coda_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (coda, warnings) = Coda::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&coda)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into an **OFX** file of the version of
+/// `options`, one bank statement per statement.
+fn camt053_to_ofx_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let mut ofx: Ofx = TryFrom::try_from(&camt053)?;
+    let version = options.ofx_version.unwrap_or_default();
+    ofx.version = version.header_version().to_string();
+
+    ofx.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts an **OFX** file, SGML or XML, into **CAMT.053** format, one
+/// statement per bank or credit card statement response.
+///
+/// In lenient mode transactions that cannot be read and responses without
+/// a statement are reported as warnings.
+fn ofx_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (ofx, warnings) = Ofx::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&ofx)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **QIF** file with the date
+/// format of `options`, one `!Type:Bank` list per account.
+fn camt053_to_qif_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let mut qif: Qif = TryFrom::try_from(&camt053)?;
+    qif.date_format = options.qif_date_format;
+
+    qif.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **QIF** file whose dates are in the date format of `options`
+/// into **CAMT.053** format, one statement per account list.
+///
+/// In lenient mode transactions that cannot be read and lists other than
+/// transaction lists are reported as warnings.
+fn qif_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let (qif, warnings) =
+        Qif::from_read_with_format(input_stream, options.qif_date_format, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&qif)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **CFONB 120** file, one old to
+/// new balance sequence per statement.
+fn camt053_to_cfonb_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let cfonb: Cfonb = TryFrom::try_from(&camt053)?;
+
+    cfonb.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CFONB 120** file into **CAMT.053** format, one statement per account sequence.
+///
+/// In lenient mode movements that cannot be read and new balances that
+/// do not match the movements are reported as warnings.
+fn cfonb_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (cfonb, warnings) = Cfonb::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&cfonb)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **Norma 43** file, one account
+/// sequence per statement.
+fn camt053_to_norma43_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let norma43: Norma43 = TryFrom::try_from(&camt053)?;
+
+    norma43.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **Norma 43** file into **CAMT.053** format, one statement per account.
+///
+/// In lenient mode movements that cannot be read and totals or record
+/// counts that do not match are reported as warnings.
+fn norma43_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (norma43, warnings) = Norma43::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&norma43)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **1C ClientBankExchange** file in
+/// Windows-1251, one account section per statement.
+fn camt053_to_client_bank_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let file: ClientBankExchange = TryFrom::try_from(&camt053)?;
+
+    file.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **1C ClientBankExchange** file into **CAMT.053** format, one
+/// statement per account section.
+///
+/// In lenient mode documents that cannot be read, closing balances that do
+/// not match the turnover and missing end lines are reported as warnings.
+fn client_bank_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (file, warnings) = ClientBankExchange::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&file)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a stream of **MT950** messages into one **CAMT.053** document,
+/// one statement per message, reading one message at a time.
+fn mt950_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    swift_to_camt053_stream::<Mt950>(
+        input_stream,
+        output_stream,
+        recovery,
+        mt950_to_camt053,
+        ParserError::Mt950("No MT950 message found".to_string()),
+    )
+}
+
+/// Converts a **CAMT.053** document into **MT950** messages, one per
+/// statement, separated by an empty line like MT940 output.
+fn camt053_to_mt950_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let messages = camt053_to_mt950(&camt053, recovery)?;
+
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+    for (i, mt950) in messages.iter().enumerate() {
+        if i > 0 {
+            buffered_writer.write_all(b"\n\n")?;
+        }
+        mt950.write_to(&mut buffered_writer)?;
+    }
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// Converts a stream of **MT900** and **MT910** confirmations into one
+/// **CAMT.054** document, one notification per message, with the group
+/// header of the first message.
+fn mt900_to_camt054_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let mut messages = SwiftStreamReader::<_, Mt900>::with_mode(
+        std::io::BufReader::new(input_stream),
+        recovery.mode(),
+    );
+    let mut camt054: Option<Camt054> = None;
+    while let Some(mt900) = messages.next() {
+        let mt900 = mt900?;
+        recovery.extend(messages.take_warnings());
+        let notification = Camt054::try_from(&mt900)?;
+        match camt054.as_mut() {
+            Some(camt054) => camt054
+                .bk_to_cstmr_dbt_cdt_ntfctn
+                .ntfctns
+                .extend(notification.bk_to_cstmr_dbt_cdt_ntfctn.ntfctns),
+            None => camt054 = Some(notification),
+        }
+    }
+    recovery.extend(messages.take_warnings());
+    let camt054 = camt054.ok_or(ParserError::Mt900(
+        "No MT900 or MT910 message found".to_string(),
+    ))?;
+
+    camt054.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.054** document into **MT900** and **MT910**
+/// confirmations, one per entry, separated by an empty line.
+fn camt054_to_mt900_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt054, warnings) = Camt054::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let messages = camt054_to_mt900(&camt054, recovery)?;
+
+    let mut buffered_writer = std::io::BufWriter::new(output_stream);
+    for (i, mt900) in messages.iter().enumerate() {
+        if i > 0 {
+            buffered_writer.write_all(b"\n\n")?;
+        }
+        mt900.write_to(&mut buffered_writer)?;
+    }
+    buffered_writer.flush()?;
+    Ok(())
+}
+
+/// Converts a **CAMT.054** document into **CAMT.053** format, one statement
+/// without balances per notification.
+fn camt054_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt054, warnings) = Camt054::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&camt054)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **CAMT.053** document into a **CAMT.054** notification per
+/// statement, leaving out the balances.
+fn camt053_to_camt054_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt054: Camt054 = TryFrom::try_from(&camt053)?;
+
+    camt054.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts the debits of a **CAMT.053** document into a **pain.001**
+/// credit transfer initiation of the version of `options`, one payment
+/// information block per statement and requested execution date.
+///
+/// In lenient mode entries that cannot be initiated, such as credits or
+/// payments without a creditor account, are left out and reported as warnings.
+fn camt053_to_pain001_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let mut pain001 = camt053_to_pain001(&camt053, recovery)?;
+    pain001.version = options.pain001_version.unwrap_or_default();
+
+    pain001.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts the debit rows of a **CSV** payment run directly into a
+/// **pain.001** credit transfer initiation of the version of `options`,
+/// one payment information block per account and requested execution date.
+///
+/// In lenient mode rows that cannot be initiated are left out and reported
+/// as warnings.
+fn csv_to_pain001_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let csv = CsvWrapper::from_read(input_stream)?;
+    let mut pain001 = csv_to_pain001(&csv, recovery)?;
+    pain001.version = options.pain001_version.unwrap_or_default();
+
+    pain001.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts a **pain.001** credit transfer initiation, of any version, into
+/// **CAMT.053** format, one statement of pending debits per payment
+/// information block.
+fn pain001_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let (pain001, warnings) = Pain001::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let camt053: Camt053 = TryFrom::try_from(&pain001)?;
+
+    camt053.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Writes a **CAMT.053** document as an **XLSX** workbook, one sheet per
+/// statement or per account as set by [`FormatOptions::xlsx_layout`].
+fn camt053_to_xlsx_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+    options: &FormatOptions,
+) -> Result<(), ParserError> {
+    let (camt053, warnings) = Camt053::from_read_with_mode(input_stream, recovery.mode())?;
+    recovery.extend(warnings);
+    let xlsx = camt053_to_xlsx(&camt053, options.xlsx_layout.unwrap_or_default())?;
+
+    xlsx.write_to(output_stream)?;
+    Ok(())
+}
+
+/// Converts the sheets of an **XLSX** workbook with the columns of the
+/// statement CSV layout into **CAMT.053** format.
+///
+/// In lenient mode rows that cannot be converted are reported as warnings.
+fn xlsx_to_camt053_stream(
+    input_stream: Box<dyn std::io::Read>,
+    output_stream: Box<dyn std::io::Write>,
+    recovery: &mut Recovery,
+) -> Result<(), ParserError> {
+    let xlsx = Xlsx::from_read(input_stream)?;
+    let camt053 = xlsx_to_camt053(&xlsx, recovery)?;
+
+    camt053.write_to(output_stream)?;
     Ok(())
 }
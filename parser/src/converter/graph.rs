@@ -0,0 +1,696 @@
+use crate::FormatOptions;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, WarningKind};
+use crate::registry;
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::io::{Read, Write};
+use std::rc::Rc;
+
+/// The default of [`ConversionContext::with_intermediate_limit`], 512 MiB.
+const INTERMEDIATE_LIMIT: usize = 512 * 1024 * 1024;
+
+/// State shared by the steps of one conversion: the [`ParseMode`], the
+/// [`FormatOptions`], the limit of intermediate results and the warnings
+/// collected in lenient mode.
+#[derive(Debug)]
+pub struct ConversionContext {
+    recovery: Recovery,
+    options: FormatOptions,
+    intermediate_limit: usize,
+}
+
+impl Default for ConversionContext {
+    fn default() -> Self {
+        Self::new(ParseMode::default())
+    }
+}
+
+impl ConversionContext {
+    /// Creates a context for a conversion in `mode`.
+    pub fn new(mode: ParseMode) -> Self {
+        ConversionContext {
+            recovery: Recovery::new(mode),
+            options: FormatOptions::default(),
+            intermediate_limit: INTERMEDIATE_LIMIT,
+        }
+    }
+
+    /// Reads and writes the formats of the conversion with `options`.
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Fails the conversion once one of its intermediate results, which
+    /// are kept in memory, grows beyond `bytes`; 512 MiB unless set. See
+    /// [`ConversionGraph`].
+    pub fn with_intermediate_limit(mut self, bytes: usize) -> Self {
+        self.intermediate_limit = bytes;
+        self
+    }
+
+    /// The mode the conversion runs in.
+    pub fn mode(&self) -> ParseMode {
+        self.recovery.mode()
+    }
+
+    /// The options of the formats the conversion reads and writes.
+    pub fn options(&self) -> &FormatOptions {
+        &self.options
+    }
+
+    /// The size in bytes up to which an intermediate result may grow.
+    pub fn intermediate_limit(&self) -> usize {
+        self.intermediate_limit
+    }
+
+    /// Reports a problem that affects only part of the input.
+    ///
+    /// Returns `error` in [`ParseMode::Strict`], so the converter can stop
+    /// with `?`; in [`ParseMode::Lenient`] records it as a warning, and the
+    /// converter should leave out what `kind` describes and go on.
+    pub fn recover(&mut self, kind: WarningKind, error: ParserError) -> Result<(), ParserError> {
+        self.recovery.recover(kind, error)
+    }
+
+    /// Returns the warnings collected so far.
+    pub fn into_warnings(self) -> Vec<ParseWarning> {
+        self.recovery.into_warnings()
+    }
+
+    pub(crate) fn recovery(&mut self) -> &mut Recovery {
+        &mut self.recovery
+    }
+}
+
+/// A conversion from one format to another, an edge of a [`ConversionGraph`].
+///
+/// Formats are identified by lowercase names such as `"mt940"` or
+/// `"camt053"`. A crate adding its own format implements converters between
+/// it and any of the built-in formats — usually `"camt053"`, the canonical
+/// model — and registers them with [`ConversionGraph::register`]; every
+/// other format is then reached through the built-in converters.
+pub trait Converter {
+    /// Name of the format read from `input`.
+    fn source(&self) -> &str;
+
+    /// Name of the format written to `output`.
+    fn target(&self) -> &str;
+
+    /// Reads `input` in the [`source`](Self::source) format and writes it to
+    /// `output` in the [`target`](Self::target) format.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParserError`] if the input cannot be parsed, converted or written.
+    fn convert(
+        &self,
+        input: Box<dyn Read>,
+        output: Box<dyn Write>,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError>;
+}
+
+/// Signature of the crate's own conversion functions.
+pub(crate) type ConvertFn =
+    fn(Box<dyn Read>, Box<dyn Write>, &mut Recovery) -> Result<(), ParserError>;
+
+/// Signature of the conversion functions of formats with [`FormatOptions`].
+pub(crate) type OptionsConvertFn =
+    fn(Box<dyn Read>, Box<dyn Write>, &mut Recovery, &FormatOptions) -> Result<(), ParserError>;
+
+/// A [`Converter`] backed by one of the crate's conversion functions.
+pub(crate) struct FnConverter {
+    source: &'static str,
+    target: &'static str,
+    convert: Function,
+}
+
+enum Function {
+    Plain(ConvertFn),
+    WithOptions(OptionsConvertFn),
+}
+
+impl FnConverter {
+    pub(crate) fn new(source: &'static str, target: &'static str, convert: ConvertFn) -> Self {
+        FnConverter {
+            source,
+            target,
+            convert: Function::Plain(convert),
+        }
+    }
+
+    /// A converter whose function is passed the [`FormatOptions`] of the conversion.
+    pub(crate) fn with_options(
+        source: &'static str,
+        target: &'static str,
+        convert: OptionsConvertFn,
+    ) -> Self {
+        FnConverter {
+            source,
+            target,
+            convert: Function::WithOptions(convert),
+        }
+    }
+}
+
+impl Converter for FnConverter {
+    fn source(&self) -> &str {
+        self.source
+    }
+
+    fn target(&self) -> &str {
+        self.target
+    }
+
+    fn convert(
+        &self,
+        input: Box<dyn Read>,
+        output: Box<dyn Write>,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError> {
+        match self.convert {
+            Function::Plain(convert) => convert(input, output, context.recovery()),
+            Function::WithOptions(convert) => {
+                let options = context.options;
+                convert(input, output, context.recovery(), &options)
+            }
+        }
+    }
+}
+
+/// Converters between formats, and the routes between formats they form.
+///
+/// Formats that have no direct converter between them are converted in
+/// several steps along the shortest chain of converters, normally through
+/// CAMT.053, the canonical model every built-in format converts to and from.
+///
+/// The result of each step is kept in memory whole until the next step has
+/// read it, even between converters that stream their input and output, so
+/// a conversion of several steps needs memory for its largest intermediate
+/// result besides what the steps themselves hold. A step whose result grows
+/// beyond the [`intermediate_limit`](ConversionContext::intermediate_limit)
+/// fails the conversion instead of exhausting memory.
+///
+/// [`ConversionGraph::default`] holds the converters of all formats listed
+/// by [`registry::formats`]: the built-in ones and those added with
+/// [`register_format`](crate::register_format).
+/// [`formats`](ConversionGraph::formats) names the formats of a graph.
+pub struct ConversionGraph {
+    converters: Vec<Box<dyn Converter>>,
+}
+
+impl Default for ConversionGraph {
+    fn default() -> Self {
+        let mut graph = ConversionGraph::new();
+        let builtin = super::convert_streams::builtin_converters();
+        for converter in builtin.into_iter().chain(registry::registered_converters()) {
+            graph.register(converter);
+        }
+        graph
+    }
+}
+
+impl ConversionGraph {
+    /// Creates a graph without any converters.
+    pub fn new() -> Self {
+        ConversionGraph {
+            converters: Vec::new(),
+        }
+    }
+
+    /// Adds a converter, replacing a registered one between the same formats.
+    pub fn register(&mut self, converter: Box<dyn Converter>) -> &mut Self {
+        self.converters
+            .retain(|c| c.source() != converter.source() || c.target() != converter.target());
+        self.converters.push(converter);
+        self
+    }
+
+    /// Names of all formats that can be read or written.
+    pub fn formats(&self) -> Vec<&str> {
+        let mut formats = Vec::new();
+        for converter in &self.converters {
+            for format in [converter.source(), converter.target()] {
+                if !formats.contains(&format) {
+                    formats.push(format);
+                }
+            }
+        }
+        formats
+    }
+
+    /// The formats a conversion from `source` to `target` goes through,
+    /// both included, or `None` if `target` cannot be reached.
+    pub fn route<'a>(&'a self, source: &'a str, target: &'a str) -> Option<Vec<&'a str>> {
+        if source == target {
+            return Some(vec![source]);
+        }
+        let steps = self.shortest_path(source, target)?;
+        let mut formats = vec![source];
+        formats.extend(steps.iter().map(|c| c.target()));
+        Some(formats)
+    }
+
+    /// Converts `input` from the `source` format to the `target` format.
+    ///
+    /// Data is copied unchanged when the formats are the same.
+    ///
+    /// Problems found by a later step of a multi-step conversion refer to
+    /// intermediate data, not to `input`, so they are reported without
+    /// their position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError::Converter`] if there is no route between the
+    /// formats, and any error of the converters along the route.
+    pub fn convert(
+        &self,
+        input: Box<dyn Read>,
+        source: &str,
+        output: Box<dyn Write>,
+        target: &str,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError> {
+        if source == target {
+            return copy_input_to_output(input, output);
+        }
+        let steps = self.steps(source, target)?;
+        run_steps(&steps, input, output, context)
+    }
+
+    /// Converts `input` from the `source` format to the `target` format
+    /// with `step` in between: to its source format, through it, and from
+    /// its target format on.
+    ///
+    /// Unlike [`convert`](Self::convert), data is passed through `step`
+    /// even when all formats are the same.
+    pub(crate) fn convert_through(
+        &self,
+        input: Box<dyn Read>,
+        source: &str,
+        step: &dyn Converter,
+        output: Box<dyn Write>,
+        target: &str,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError> {
+        let mut steps = self.steps(source, step.source())?;
+        steps.push(step);
+        steps.extend(self.steps(step.target(), target)?);
+        run_steps(&steps, input, output, context)
+    }
+
+    /// Converts `data` produced by the crate itself, rather than read from
+    /// the user's input, from the `source` format to the `target` format.
+    ///
+    /// Like later steps of [`convert`](Self::convert), problems are
+    /// reported without their position.
+    pub(crate) fn convert_intermediate(
+        &self,
+        data: Vec<u8>,
+        source: &str,
+        mut output: Box<dyn Write>,
+        target: &str,
+        context: &mut ConversionContext,
+    ) -> Result<(), ParserError> {
+        if source == target {
+            output.write_all(&data)?;
+            output.flush()?;
+            return Ok(());
+        }
+        let steps = self.steps(source, target)?;
+        run_intermediate_steps(&steps, data, output, context)
+    }
+
+    fn steps(&self, source: &str, target: &str) -> Result<Vec<&dyn Converter>, ParserError> {
+        self.shortest_path(source, target).ok_or_else(|| {
+            ParserError::Converter(format!(
+                "Unsupported format conversion: {} to {}",
+                source, target
+            ))
+        })
+    }
+
+    /// Breadth-first search for the fewest converters leading from `source`
+    /// to `target`. Converters registered earlier are tried first.
+    fn shortest_path(&self, source: &str, target: &str) -> Option<Vec<&dyn Converter>> {
+        let mut reached: HashMap<&str, Option<&dyn Converter>> = HashMap::new();
+        reached.insert(source, None);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(format) = queue.pop_front() {
+            if format == target {
+                break;
+            }
+            for converter in self.converters.iter().filter(|c| c.source() == format) {
+                if !reached.contains_key(converter.target()) {
+                    reached.insert(converter.target(), Some(converter.as_ref()));
+                    queue.push_back(converter.target());
+                }
+            }
+        }
+
+        let mut steps = Vec::new();
+        let mut format = target;
+        while let Some(converter) = reached.get(format)? {
+            steps.push(*converter);
+            format = converter.source();
+        }
+        steps.reverse();
+        Some(steps)
+    }
+}
+
+/// Runs `steps` one after the other, each reading what the previous one wrote.
+fn run_steps(
+    steps: &[&dyn Converter],
+    input: Box<dyn Read>,
+    output: Box<dyn Write>,
+    context: &mut ConversionContext,
+) -> Result<(), ParserError> {
+    let Some((last, first)) = steps.split_last() else {
+        return Ok(());
+    };
+    if first.is_empty() {
+        return last.convert(input, output, context);
+    }
+
+    let buffer = SharedBuffer::default();
+    let intermediate = Intermediate::new(&buffer, context.intermediate_limit());
+    first[0].convert(input, Box::new(intermediate), context)?;
+    run_intermediate_steps(&steps[1..], buffer.take(), output, context)
+}
+
+/// Runs `steps` on intermediate data, one after the other.
+fn run_intermediate_steps(
+    steps: &[&dyn Converter],
+    mut data: Vec<u8>,
+    output: Box<dyn Write>,
+    context: &mut ConversionContext,
+) -> Result<(), ParserError> {
+    let Some((last, first)) = steps.split_last() else {
+        return Ok(());
+    };
+    for step in first {
+        let next = SharedBuffer::default();
+        let intermediate = Intermediate::new(&next, context.intermediate_limit());
+        convert_intermediate(*step, data, Box::new(intermediate), context)?;
+        data = next.take();
+    }
+    convert_intermediate(*last, data, output, context)
+}
+
+/// Runs a step that reads intermediate data, dropping positions from its
+/// errors and warnings.
+fn convert_intermediate(
+    step: &dyn Converter,
+    input: Vec<u8>,
+    output: Box<dyn Write>,
+    context: &mut ConversionContext,
+) -> Result<(), ParserError> {
+    let mut step_context = ConversionContext::new(context.mode())
+        .with_options(context.options)
+        .with_intermediate_limit(context.intermediate_limit);
+    step.convert(
+        Box::new(std::io::Cursor::new(input)),
+        output,
+        &mut step_context,
+    )
+    .map_err(without_position)?;
+
+    let warnings = step_context
+        .into_warnings()
+        .into_iter()
+        .map(|warning| ParseWarning {
+            kind: warning.kind,
+            error: without_position(warning.error),
+        })
+        .collect();
+    context.recovery().extend(warnings);
+    Ok(())
+}
+
+pub(crate) fn without_position(error: ParserError) -> ParserError {
+    match error {
+        ParserError::Located(diagnostic) => diagnostic.error,
+        error => error,
+    }
+}
+
+/// In-memory writer whose contents stay reachable after it was boxed and handed
+/// to a conversion function.
+#[derive(Clone, Default)]
+pub(crate) struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Removes and returns everything written so far.
+    pub(crate) fn take(&self) -> Vec<u8> {
+        self.0.take()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes the result of a step into a [`SharedBuffer`], failing once it
+/// would hold more than `limit` bytes.
+struct Intermediate {
+    buffer: SharedBuffer,
+    limit: usize,
+    written: usize,
+}
+
+impl Intermediate {
+    fn new(buffer: &SharedBuffer, limit: usize) -> Self {
+        Intermediate {
+            buffer: buffer.clone(),
+            limit,
+            written: 0,
+        }
+    }
+}
+
+impl Write for Intermediate {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.len() > self.limit - self.written {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::OutOfMemory,
+                format!(
+                    "Intermediate result of the conversion exceeds the limit of {} bytes",
+                    self.limit
+                ),
+            ));
+        }
+        self.written += buf.len();
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Copies raw data from the input stream to the output stream.
+///
+/// This function is used when no conversion between formats is necessary.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if an I/O error occurs during reading or writing.
+fn copy_input_to_output(input: Box<dyn Read>, output: Box<dyn Write>) -> Result<(), ParserError> {
+    let mut input_buf = std::io::BufReader::new(input);
+    let mut output_buf = std::io::BufWriter::new(output);
+
+    std::io::copy(&mut input_buf, &mut output_buf)?;
+    output_buf.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::read_test_bytes;
+    use crate::traits::FinancialDataRead;
+    use crate::{Camt053, CsvWrapper};
+
+    fn convert(graph: &ConversionGraph, input: Vec<u8>, source: &str, target: &str) -> Vec<u8> {
+        let output = SharedBuffer::default();
+        graph
+            .convert(
+                Box::new(std::io::Cursor::new(input)),
+                source,
+                Box::new(output.clone()),
+                target,
+                &mut ConversionContext::default(),
+            )
+            .unwrap();
+        output.take()
+    }
+
+    /// A bank format of one `amount;D|C` line per entry.
+    struct MyBankConverter;
+
+    impl Converter for MyBankConverter {
+        fn source(&self) -> &str {
+            "mybank"
+        }
+
+        fn target(&self) -> &str {
+            "csv"
+        }
+
+        fn convert(
+            &self,
+            mut input: Box<dyn Read>,
+            mut output: Box<dyn Write>,
+            context: &mut ConversionContext,
+        ) -> Result<(), ParserError> {
+            let mut data = String::new();
+            input.read_to_string(&mut data)?;
+            writeln!(output, "amount,credit_debit")?;
+            for (i, line) in data.lines().enumerate() {
+                match line.split_once(';') {
+                    Some((amount, "C")) => writeln!(output, "{},CRDT", amount)?,
+                    Some((amount, "D")) => writeln!(output, "{},DBIT", amount)?,
+                    _ => context.recover(
+                        WarningKind::SkippedEntry,
+                        ParserError::InvalidInput(format!("Invalid line {}", i + 1)),
+                    )?,
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_builtin_routes() {
+        let graph = ConversionGraph::default();
+
+        assert_eq!(
+            graph.route("mt940", "camt053"),
+            Some(vec!["mt940", "camt053"])
+        );
+        assert_eq!(
+            graph.route("xml", "csv"),
+            Some(vec!["xml", "camt053", "csv"])
+        );
+        assert_eq!(
+            graph.route("ndjson", "mt940"),
+            Some(vec!["ndjson", "camt053", "mt940"])
+        );
+        assert_eq!(graph.route("json", "json"), Some(vec!["json"]));
+        assert_eq!(graph.route("json", "mybank"), None);
+        for source in graph.formats() {
+            for target in graph.formats() {
+                assert!(graph.route(source, target).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_xml_to_csv() {
+        let graph = ConversionGraph::default();
+        let csv = convert(&graph, read_test_bytes("valid1.camt053"), "xml", "csv");
+
+        let csv = CsvWrapper::from_read(csv.as_slice()).unwrap();
+        assert_eq!(csv.0.len(), 3);
+        assert_eq!(&csv.0[1][15], "END2END123");
+    }
+
+    #[test]
+    fn test_third_party_format() {
+        let mut graph = ConversionGraph::default();
+        graph.register(Box::new(MyBankConverter));
+
+        assert_eq!(
+            graph.route("mybank", "mt940"),
+            Some(vec!["mybank", "csv", "camt053", "mt940"])
+        );
+        let camt = convert(&graph, b"10.50;C\n3.00;D\n".to_vec(), "mybank", "camt053");
+        let camt = Camt053::from_read(camt.as_slice()).unwrap();
+        let entries = &camt.bk_to_cstmr_stmt.stmts[0].ntry;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].cdt_dbt_ind, Some("DBIT".to_string()));
+    }
+
+    #[test]
+    fn test_lenient_warnings_of_every_step() {
+        let mut graph = ConversionGraph::default();
+        graph.register(Box::new(MyBankConverter));
+
+        let mut context = ConversionContext::new(ParseMode::Lenient);
+        let output = SharedBuffer::default();
+        graph
+            .convert(
+                Box::new(&b"10.50;C\nbroken\n"[..]),
+                "mybank",
+                Box::new(output.clone()),
+                "json",
+                &mut context,
+            )
+            .unwrap();
+
+        let warnings = context.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+        assert!(!output.take().is_empty());
+    }
+
+    #[test]
+    fn test_format_options_of_every_step() {
+        let graph = ConversionGraph::default();
+        let options = FormatOptions::new().qif_date_format(crate::QifDateFormat::Eu);
+
+        let mut context = ConversionContext::default().with_options(options);
+        let output = SharedBuffer::default();
+        graph
+            .convert(
+                Box::new(std::io::Cursor::new(read_test_bytes("valid1.camt053"))),
+                "xml",
+                Box::new(output.clone()),
+                "qif",
+                &mut context,
+            )
+            .unwrap();
+
+        let qif = String::from_utf8(output.take()).unwrap();
+        assert!(qif.contains("\nD05/10/2023\n"), "{}", qif);
+        assert!(qif.contains("\nD04/10/2023\n"), "{}", qif);
+    }
+
+    #[test]
+    fn test_intermediate_limit() {
+        let graph = ConversionGraph::default();
+        let input = read_test_bytes("valid1.camt053");
+        let convert = |limit| {
+            let mut context = ConversionContext::default().with_intermediate_limit(limit);
+            graph.convert(
+                Box::new(std::io::Cursor::new(input.clone())),
+                "xml",
+                Box::new(SharedBuffer::default()),
+                "csv",
+                &mut context,
+            )
+        };
+
+        // The CAMT.053 document between XML and CSV is larger than 100 bytes
+        let error = convert(100).unwrap_err();
+        assert!(
+            error.to_string().contains("exceeds the limit of 100 bytes"),
+            "{}",
+            error
+        );
+        assert!(convert(input.len()).is_ok());
+    }
+}
@@ -4,6 +4,13 @@
 /// directly from input/output streams without requiring intermediate files.
 pub mod convert_streams;
 
+/// Module routing conversions through a graph of format converters.
+///
+/// Defines the [`graph::Converter`] trait, through which other crates can
+/// add their own formats, and the [`graph::ConversionGraph`] that finds the
+/// shortest chain of converters between two formats.
+pub mod graph;
+
 /// Module for converting data to the **CAMT.053** format.
 ///
 /// Contains utilities and implementations that transform supported input formats
@@ -22,5 +29,91 @@ pub mod to_mt940;
 /// into xml document.
 pub mod to_xml;
 
+/// Module for converting data to the **JSON** statement model.
+///
+/// Includes logic for transforming CAMT.053 structures into the versioned
+/// JSON document and its line-per-entry NDJSON form.
+pub mod to_json;
+
+/// Module for converting data to the **CSV** statement layout.
+///
+/// Includes logic for transforming CAMT.053 structures into one CSV row
+/// per entry.
+pub mod to_csv;
+
+/// Module for converting data to the **BAI2** cash management format.
+///
+/// Includes logic for transforming CAMT.053 structures into BAI2 groups and
+/// accounts, mapping ISO bank transaction codes to BAI type codes.
+pub mod to_bai2;
+
+/// Module for converting data to the Belgian **CODA** statement format.
+///
+/// Includes logic for transforming CAMT.053 structures into CODA statements
+/// and movements, mapping ISO bank transaction codes to Febelfin transaction families.
+pub mod to_coda;
+
+/// Module for converting data to the **OFX** statement download format.
+///
+/// Includes logic for transforming CAMT.053 statements into OFX bank
+/// statement responses, deriving `FITID`s from the bank references.
+pub mod to_ofx;
+
+/// Module for converting data to the **QIF** format of personal finance tools.
+///
+/// Includes logic for transforming CAMT.053 statements into `!Type:Bank`
+/// lists, with the counterparty as payee and the remittance information as memo.
+pub mod to_qif;
+
+/// Module for converting data to the French **CFONB 120** statement format.
+///
+/// Includes logic for transforming CAMT.053 statements into old balance,
+/// movement and new balance records, with the signed amounts of the format.
+pub mod to_cfonb;
+
+/// Module for converting data to the Spanish **Norma 43** statement format.
+///
+/// Includes logic for transforming CAMT.053 statements into account records,
+/// mapping ISO bank transaction codes to common concepts.
+pub mod to_norma43;
+
+/// Module for converting data to the **1C ClientBankExchange** format.
+///
+/// Includes logic for transforming CAMT.053 statements into account sections
+/// and payment documents, with the tax numbers and bank codes of the parties.
+pub mod to_client_bank;
+
+/// Module for converting data to the **MT950** statement message.
+///
+/// Includes logic for transforming CAMT.053 statements into MT950 messages,
+/// with intermediate balances for statements sent in several messages.
+pub mod to_mt950;
+
+/// Module for converting data to the **CAMT.054** debit/credit notification.
+///
+/// Includes logic for transforming CAMT.053 statements and MT900/MT910
+/// confirmations into CAMT.054 notifications.
+pub mod to_camt054;
+
+/// Module for converting data to **MT900/MT910** confirmations.
+///
+/// Includes logic for transforming CAMT.054 notifications into one
+/// confirmation of debit or credit per entry.
+pub mod to_mt900;
+
+/// Module for converting data to **pain.001** credit transfer initiations.
+///
+/// Includes logic for transforming the debits of CAMT.053 statements into
+/// payment information blocks per debtor account and execution date.
+pub mod to_pain001;
+
+/// Module for converting data to **XLSX** workbooks.
+///
+/// Includes logic for transforming CAMT.053 statements into sheets with
+/// the account, period and balances, the entries in the CSV columns and totals.
+pub mod to_xlsx;
+
 /// Helper wrapper for mt940 - xml conversions
 mod mt940xml_wrapper;
+
+pub use mt940xml_wrapper::{MT940_XML_NAMESPACE, MT940_XML_SCHEMA, MT940_XML_VERSION};
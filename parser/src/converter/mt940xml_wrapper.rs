@@ -1,9 +1,404 @@
+use crate::ParserError;
 use crate::mt940::format::*;
+use chrono::NaiveDate;
+use quick_xml::Reader;
+use quick_xml::events::Event;
 use serde::{Deserialize, Serialize};
+use swift_mt_message::MT940StatementLine;
+use swift_mt_message::SwiftField;
 use swift_mt_message::fields::*;
+use swift_mt_message::messages;
 
-/// This wrapper is needed beceause XML tags are incompatible with CAMT940 fields names
-#[derive(Deserialize, Serialize)]
+/// Namespace of the MT940 XML vocabulary produced by this crate.
+pub const MT940_XML_NAMESPACE: &str = "urn:financial-parser:xsd:mt940.xml.1";
+
+/// Current version of the MT940 XML vocabulary, written to the `version` attribute.
+pub const MT940_XML_VERSION: &str = "1.0";
+
+/// XSD describing version [`MT940_XML_VERSION`] of the MT940 XML vocabulary.
+pub const MT940_XML_SCHEMA: &str = include_str!("../../schema/mt940-xml-1.0.xsd");
+
+/// Root element of the first, unversioned layout, which mirrored `swift_mt_message` field names.
+const LEGACY_ROOT: &str = "Mt940Xml";
+const DOCUMENT_ROOT: &str = "Mt940Document";
+
+/// Layouts of MT940 XML documents that can be read back.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Mt940XmlVersion {
+    /// Unversioned `<Mt940Xml>` documents written before the vocabulary was fixed.
+    Legacy,
+    /// `<Mt940Document version="1.0">`.
+    V1,
+}
+
+impl Mt940XmlVersion {
+    /// Inspects the root element of `xml` and picks the matching layout.
+    pub(crate) fn detect(xml: &str) -> Result<Self, ParserError> {
+        let mut reader = Reader::from_str(xml);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+                    let root = e.local_name();
+                    if root.as_ref() == LEGACY_ROOT.as_bytes() {
+                        return Ok(Mt940XmlVersion::Legacy);
+                    }
+                    if root.as_ref() != DOCUMENT_ROOT.as_bytes() {
+                        return Err(ParserError::Converter(format!(
+                            "Unexpected MT940 XML root element: {}",
+                            String::from_utf8_lossy(root.as_ref())
+                        )));
+                    }
+
+                    let version = e
+                        .try_get_attribute("version")
+                        .map_err(|e| ParserError::Xml(e.to_string()))?
+                        .map(|a| String::from_utf8_lossy(&a.value).into_owned());
+                    return match version.as_deref() {
+                        Some(MT940_XML_VERSION) => Ok(Mt940XmlVersion::V1),
+                        Some(other) => Err(ParserError::Converter(format!(
+                            "Unsupported MT940 XML version: {}",
+                            other
+                        ))),
+                        None => Err(ParserError::Converter(
+                            "Missing MT940 XML version attribute".to_string(),
+                        )),
+                    };
+                }
+                Ok(Event::Eof) => {
+                    return Err(ParserError::Converter(
+                        "Empty MT940 XML document".to_string(),
+                    ));
+                }
+                Ok(_) => continue,
+                Err(e) => return Err(ParserError::Xml(e.to_string())),
+            }
+        }
+    }
+}
+
+// --- Version 1.0 ---
+// Element names are fixed here and in schema/mt940-xml-1.0.xsd;
+// they must not follow renames in swift_mt_message.
+
+/// Root of a version 1.0 MT940 XML document.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename = "Mt940Document")]
+pub(crate) struct Mt940XmlDocument {
+    #[serde(rename = "@xmlns", default)]
+    pub(crate) xmlns: Option<String>,
+    #[serde(rename = "@version")]
+    pub(crate) version: String,
+    #[serde(rename = "BasicHeader")]
+    pub(crate) basic_header: String,
+    #[serde(rename = "ApplicationHeader", default)]
+    pub(crate) application_header: String,
+    #[serde(rename = "UserHeader", skip_serializing_if = "Option::is_none")]
+    pub(crate) user_header: Option<String>,
+    #[serde(rename = "Statement")]
+    pub(crate) statement: Mt940XmlStatementV1,
+    #[serde(rename = "Trailer", skip_serializing_if = "Option::is_none")]
+    pub(crate) footer: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Mt940XmlStatementV1 {
+    #[serde(rename = "TransactionReference")]
+    pub(crate) transaction_reference: String,
+    #[serde(rename = "RelatedReference", skip_serializing_if = "Option::is_none")]
+    pub(crate) related_reference: Option<String>,
+    #[serde(rename = "AccountIdentification")]
+    pub(crate) account_identification: String,
+    #[serde(rename = "StatementNumber")]
+    pub(crate) statement_number: String,
+    #[serde(rename = "SequenceNumber", skip_serializing_if = "Option::is_none")]
+    pub(crate) sequence_number: Option<String>,
+    #[serde(rename = "OpeningBalance")]
+    pub(crate) opening_balance: Mt940XmlBalance,
+    #[serde(rename = "Entry", default)]
+    pub(crate) entries: Vec<Mt940XmlEntry>,
+    #[serde(rename = "ClosingBalance")]
+    pub(crate) closing_balance: Mt940XmlBalance,
+    #[serde(
+        rename = "ClosingAvailableBalance",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) closing_available_balance: Option<Mt940XmlBalance>,
+    #[serde(rename = "ForwardAvailableBalance", default)]
+    pub(crate) forward_available_balances: Vec<Mt940XmlBalance>,
+}
+
+/// Balance fields shared by `:60F:`, `:62F:`, `:64:` and `:65:`.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Mt940XmlBalance {
+    #[serde(rename = "DebitCreditMark")]
+    pub(crate) debit_credit_mark: String,
+    #[serde(rename = "Date")]
+    pub(crate) date: NaiveDate,
+    #[serde(rename = "Currency")]
+    pub(crate) currency: String,
+    #[serde(rename = "Amount")]
+    pub(crate) amount: String,
+}
+
+/// One `:61:` statement line with its optional `:86:` information.
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Mt940XmlEntry {
+    #[serde(rename = "ValueDate")]
+    pub(crate) value_date: NaiveDate,
+    /// `xs:gMonthDay`, e.g. `--10-21`.
+    #[serde(rename = "EntryDate", skip_serializing_if = "Option::is_none")]
+    pub(crate) entry_date: Option<String>,
+    #[serde(rename = "DebitCreditMark")]
+    pub(crate) debit_credit_mark: String,
+    #[serde(rename = "FundsCode", skip_serializing_if = "Option::is_none")]
+    pub(crate) funds_code: Option<String>,
+    #[serde(rename = "Amount")]
+    pub(crate) amount: String,
+    #[serde(rename = "TransactionType")]
+    pub(crate) transaction_type: String,
+    #[serde(rename = "CustomerReference")]
+    pub(crate) customer_reference: String,
+    #[serde(rename = "BankReference", skip_serializing_if = "Option::is_none")]
+    pub(crate) bank_reference: Option<String>,
+    #[serde(
+        rename = "SupplementaryDetails",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) supplementary_details: Option<String>,
+    #[serde(rename = "Information", skip_serializing_if = "Option::is_none")]
+    pub(crate) information: Option<Mt940XmlInformation>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Mt940XmlInformation {
+    #[serde(rename = "Line", default)]
+    pub(crate) lines: Vec<String>,
+}
+
+impl Mt940XmlBalance {
+    fn new(mark: &str, date: &NaiveDate, currency: &str, amount: f64) -> Self {
+        Mt940XmlBalance {
+            debit_credit_mark: mark.to_string(),
+            date: *date,
+            currency: currency.to_string(),
+            amount: amount.to_string(),
+        }
+    }
+
+    /// Builds the SWIFT representation of the balance without the tag, e.g. `C251020EUR10000,00`.
+    fn to_swift_line(&self) -> String {
+        format!(
+            "{}{}{}{}",
+            self.debit_credit_mark,
+            self.date.format("%y%m%d"),
+            self.currency,
+            self.amount.replace('.', ",")
+        )
+    }
+}
+
+impl Mt940XmlEntry {
+    fn to_statement_line(&self) -> Result<MT940StatementLine, ParserError> {
+        let entry_date = match &self.entry_date {
+            Some(date) => gmonthday_to_mmdd(date)?,
+            None => String::new(),
+        };
+
+        let mut line = format!(
+            "{}{}{}{}{}{}{}",
+            self.value_date.format("%y%m%d"),
+            entry_date,
+            self.debit_credit_mark,
+            self.funds_code.as_deref().unwrap_or_default(),
+            self.amount.replace('.', ","),
+            self.transaction_type,
+            self.customer_reference
+        );
+        if let Some(ref bank_reference) = self.bank_reference {
+            line.push_str("//");
+            line.push_str(bank_reference);
+        }
+        if let Some(ref details) = self.supplementary_details {
+            line.push('\n');
+            line.push_str(details);
+        }
+
+        let field_61 = Field61::parse(&line).map_err(parse_error)?;
+        let field_86 = match &self.information {
+            Some(info) => Some(Field86::parse(&info.lines.join("\n")).map_err(parse_error)?),
+            None => None,
+        };
+
+        Ok(MT940StatementLine { field_61, field_86 })
+    }
+}
+
+fn parse_error<E: std::fmt::Display>(e: E) -> ParserError {
+    ParserError::Converter(e.to_string())
+}
+
+// "1021" -> "--10-21"
+fn mmdd_to_gmonthday(mmdd: &str) -> String {
+    match (mmdd.get(0..2), mmdd.get(2..4)) {
+        (Some(month), Some(day)) => format!("--{}-{}", month, day),
+        _ => mmdd.to_string(),
+    }
+}
+
+// "--10-21" -> "1021"
+fn gmonthday_to_mmdd(gmonthday: &str) -> Result<String, ParserError> {
+    let digits: String = gmonthday.chars().filter(|c| c.is_ascii_digit()).collect();
+    if digits.len() != 4 {
+        return Err(ParserError::Converter(format!(
+            "Invalid entry date: '{}'",
+            gmonthday
+        )));
+    }
+    Ok(digits)
+}
+
+impl From<&Mt940> for Mt940XmlDocument {
+    fn from(mt940: &Mt940) -> Self {
+        let stmt = &mt940.statement;
+
+        let entries = stmt
+            .statement_lines
+            .iter()
+            .map(|line| {
+                let f61 = &line.field_61;
+                Mt940XmlEntry {
+                    value_date: f61.value_date,
+                    entry_date: f61.entry_date.as_deref().map(mmdd_to_gmonthday),
+                    debit_credit_mark: f61.debit_credit_mark.clone(),
+                    funds_code: f61.funds_code.map(|c| c.to_string()),
+                    amount: f61.amount.to_string(),
+                    transaction_type: f61.transaction_type.clone(),
+                    customer_reference: f61.customer_reference.clone(),
+                    bank_reference: f61.bank_reference.clone(),
+                    supplementary_details: f61.supplementary_details.clone(),
+                    information: line.field_86.as_ref().map(|f86| Mt940XmlInformation {
+                        lines: f86.narrative.clone(),
+                    }),
+                }
+            })
+            .collect();
+
+        let statement = Mt940XmlStatementV1 {
+            transaction_reference: stmt.field_20.reference.clone(),
+            related_reference: stmt.field_21.as_ref().map(|f| f.reference.clone()),
+            account_identification: stmt.field_25.authorisation.clone(),
+            statement_number: stmt.field_28c.statement_number.to_string(),
+            sequence_number: stmt.field_28c.sequence_number.map(|n| n.to_string()),
+            opening_balance: Mt940XmlBalance::new(
+                &stmt.field_60f.debit_credit_mark,
+                &stmt.field_60f.value_date,
+                &stmt.field_60f.currency,
+                stmt.field_60f.amount,
+            ),
+            entries,
+            closing_balance: Mt940XmlBalance::new(
+                &stmt.field_62f.debit_credit_mark,
+                &stmt.field_62f.value_date,
+                &stmt.field_62f.currency,
+                stmt.field_62f.amount,
+            ),
+            closing_available_balance: stmt.field_64.as_ref().map(|f| {
+                Mt940XmlBalance::new(&f.debit_credit_mark, &f.value_date, &f.currency, f.amount)
+            }),
+            forward_available_balances: stmt
+                .field_65
+                .iter()
+                .flatten()
+                .map(|f| {
+                    Mt940XmlBalance::new(&f.debit_credit_mark, &f.value_date, &f.currency, f.amount)
+                })
+                .collect(),
+        };
+
+        Mt940XmlDocument {
+            xmlns: Some(MT940_XML_NAMESPACE.to_string()),
+            version: MT940_XML_VERSION.to_string(),
+            basic_header: mt940.basic_header.to_string(),
+            application_header: mt940.application_header.to_string(),
+            user_header: mt940.user_header.as_ref().map(UserHeaderBlock::to_string),
+            statement,
+            footer: mt940.footer.as_ref().map(TrailerBlock::to_string),
+        }
+    }
+}
+
+impl TryFrom<Mt940XmlDocument> for Mt940 {
+    type Error = ParserError;
+
+    fn try_from(doc: Mt940XmlDocument) -> Result<Self, Self::Error> {
+        let stmt = doc.statement;
+
+        let field_28c = match &stmt.sequence_number {
+            Some(seq) => format!("{}/{}", stmt.statement_number, seq),
+            None => stmt.statement_number.clone(),
+        };
+
+        let field_65 = stmt
+            .forward_available_balances
+            .iter()
+            .map(|b| Field65::parse(&b.to_swift_line()).map_err(parse_error))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let statement = messages::MT940 {
+            field_20: Field20::parse(&stmt.transaction_reference).map_err(parse_error)?,
+            field_21: stmt
+                .related_reference
+                .as_deref()
+                .map(Field21NoOption::parse)
+                .transpose()
+                .map_err(parse_error)?,
+            field_25: Field25NoOption::parse(&stmt.account_identification).map_err(parse_error)?,
+            field_28c: Field28C::parse(&field_28c).map_err(parse_error)?,
+            field_60f: Field60F::parse(&stmt.opening_balance.to_swift_line())
+                .map_err(parse_error)?,
+            statement_lines: stmt
+                .entries
+                .iter()
+                .map(|entry| entry.to_statement_line())
+                .collect::<Result<_, _>>()?,
+            field_62f: Field62F::parse(&stmt.closing_balance.to_swift_line())
+                .map_err(parse_error)?,
+            field_64: stmt
+                .closing_available_balance
+                .as_ref()
+                .map(|b| Field64::parse(&b.to_swift_line()))
+                .transpose()
+                .map_err(parse_error)?,
+            field_65: if field_65.is_empty() {
+                None
+            } else {
+                Some(field_65)
+            },
+        };
+
+        Ok(Mt940 {
+            basic_header: BasicHeaderBlock::from_string(&doc.basic_header)?,
+            application_header: ApplicationHeaderBlock::from_string(&doc.application_header)?,
+            user_header: doc
+                .user_header
+                .as_deref()
+                .map(UserHeaderBlock::from_string)
+                .transpose()?,
+            statement,
+            footer: doc
+                .footer
+                .as_deref()
+                .map(TrailerBlock::from_string)
+                .transpose()?,
+        })
+    }
+}
+
+// --- Legacy layout ---
+// Kept for reading documents written before the vocabulary was versioned.
+// Element names are the serde names of swift_mt_message fields at that time.
+
+#[derive(Deserialize)]
 pub(crate) struct Mt940Xml {
     pub(crate) basic_header: BasicHeaderBlock,
     pub(crate) application_header: String,
@@ -12,7 +407,7 @@ pub(crate) struct Mt940Xml {
     pub(crate) footer: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Deserialize)]
 pub(crate) struct MT940XmlStatement {
     #[serde(rename = "field20")]
     pub(crate) field_20: Field20,
@@ -29,7 +424,7 @@ pub(crate) struct MT940XmlStatement {
     #[serde(rename = "field60F")]
     pub(crate) field_60f: Field60F,
 
-    #[serde(rename = "transactions")]
+    #[serde(rename = "transactions", default)]
     pub(crate) statement_lines: Vec<MT940StatementLineXml>,
 
     #[serde(rename = "field62F")]
@@ -42,7 +437,7 @@ pub(crate) struct MT940XmlStatement {
     pub(crate) field_65: Option<Vec<Field65>>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Deserialize)]
 pub(crate) struct MT940StatementLineXml {
     #[serde(rename = "field61")]
     pub(crate) field_61: Field61,
@@ -0,0 +1,402 @@
+use crate::ParserError;
+use crate::bai2::format::*;
+use crate::camt053::balance::{balance_code, currency_decimals, date_of, entry_date, find_balance};
+use crate::camt053::format::*;
+use chrono::NaiveDate;
+
+/// A decimal amount in minor units of `currency`.
+fn minor_units(amount: &Option<Amount>, currency: &str) -> Result<i64, ParserError> {
+    let value = amount
+        .as_ref()
+        .and_then(|a| a.value.as_deref())
+        .ok_or_else(|| ParserError::Converter("Missing amount".to_string()))?
+        .trim();
+    let decimals = currency_decimals(currency) as usize;
+    let (whole, fraction) = value.split_once(['.', ',']).unwrap_or((value, ""));
+    let fraction = fraction.trim_end_matches('0');
+    if whole.is_empty()
+        || fraction.len() > decimals
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(ParserError::Converter(format!(
+            "Amount '{}' is not a whole number of {} minor units",
+            value, currency
+        )));
+    }
+    format!("{}{:0<decimals$}", whole, fraction)
+        .parse()
+        .map_err(|_| ParserError::Converter(format!("Amount '{}' is too large", value)))
+}
+
+fn is_debit(cdt_dbt_ind: &Option<String>) -> Result<bool, ParserError> {
+    match cdt_dbt_ind.as_deref().map(str::trim) {
+        Some("CRDT" | "C") => Ok(false),
+        Some("DBIT" | "D") => Ok(true),
+        other => Err(ParserError::Converter(format!(
+            "Invalid credit/debit indicator: {:?}",
+            other
+        ))),
+    }
+}
+
+fn yymmdd(date: NaiveDate) -> String {
+    date.format("%y%m%d").to_string()
+}
+
+/// Record fields cannot contain the `,` and `/` delimiters.
+fn field(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, ',' | '/'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// The type code of an entry: a BAI type code it already has, the one
+/// mapped to its ISO bank transaction code, or miscellaneous credit or debit.
+fn type_code(entry: &Entry, credit: bool) -> String {
+    let code = entry.bk_tx_cd.as_ref();
+    let proprietary = code
+        .and_then(|c| c.prtry.as_ref()?.cd.as_deref())
+        .filter(|cd| cd.len() == 3 && is_credit(cd) == Some(credit));
+    if let Some(cd) = proprietary {
+        return cd.to_string();
+    }
+    let iso = code.and_then(|c| {
+        let domain = c.domn.as_ref()?;
+        let family = domain.fmly.as_ref()?;
+        Some((
+            domain.cd.as_deref()?,
+            family.cd.as_deref()?,
+            family.sub_fmly_cd.as_deref()?,
+        ))
+    });
+    TYPE_CODES
+        .iter()
+        .find(|(type_code, domain, family, sub_family)| {
+            iso == Some((*domain, *family, *sub_family)) && is_credit(type_code) == Some(credit)
+        })
+        .map_or(if credit { "399" } else { "699" }, |(type_code, ..)| {
+            type_code
+        })
+        .to_string()
+}
+
+fn transaction(
+    entry: &Entry,
+    currency: &str,
+    as_of_date: NaiveDate,
+) -> Result<Transaction, ParserError> {
+    let credit = !is_debit(&entry.cdt_dbt_ind)?;
+    // The booking date of every entry is the as-of date of its group
+    let value_date = entry
+        .val_dt
+        .as_ref()
+        .and_then(date_of)
+        .or(entry_date(entry));
+    let funds_type = match value_date {
+        Some(date) if date != as_of_date => FundsType::ValueDated {
+            date: yymmdd(date),
+            time: String::new(),
+        },
+        _ => FundsType::Unknown,
+    };
+    let details = entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls);
+    let refs = details.clone().find_map(|d| d.refs.as_ref());
+    Ok(Transaction {
+        type_code: type_code(entry, credit),
+        amount: minor_units(&entry.amt, currency)?,
+        funds_type: Some(funds_type),
+        bank_reference: field(refs.and_then(|r| r.acct_svcr_ref.as_deref()).unwrap_or("")),
+        customer_reference: field(
+            refs.and_then(|r| r.end_to_end_id.as_deref())
+                .filter(|id| *id != "NOTPROVIDED")
+                .unwrap_or(""),
+        ),
+        text: details
+            .filter_map(|d| d.rmt_inf.as_ref())
+            .flat_map(|r| &r.ustrd)
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+    })
+}
+
+/// The balances as status summaries, followed by the total credits (`100`)
+/// and total debits (`400`) with their item counts.
+fn summaries(
+    stmt: &Statement,
+    currency: &str,
+    transactions: &[Transaction],
+) -> Result<Vec<Summary>, ParserError> {
+    let has_opening = stmt.bal.iter().any(|b| balance_code(b) == Some("OPBD"));
+    let mut summaries: Vec<Summary> = Vec::new();
+    for balance in &stmt.bal {
+        let proprietary = balance
+            .tp
+            .as_ref()
+            .and_then(|tp| tp.cd_or_prtry.as_ref()?.prtry.as_deref());
+        let type_code = match balance_code(balance) {
+            Some("PRCD") if !has_opening => Some("010"),
+            Some(code) => BALANCE_CODES
+                .iter()
+                .find(|(_, balance_code)| *balance_code == code)
+                .map(|(type_code, _)| *type_code),
+            None => proprietary.filter(|code| code.len() == 3 && is_status(code)),
+        };
+        let Some(type_code) = type_code else {
+            continue;
+        };
+        if summaries.iter().any(|s| s.type_code == type_code) {
+            continue;
+        }
+        let amount = minor_units(&balance.amt, currency)?;
+        let debit = is_debit(&balance.cdt_dbt_ind)?;
+        summaries.push(Summary {
+            type_code: type_code.to_string(),
+            amount: Some(if debit { -amount } else { amount }),
+            item_count: None,
+            funds_type: None,
+        });
+    }
+    for (type_code, credit) in [("100", true), ("400", false)] {
+        let amounts = transactions
+            .iter()
+            .filter(|t| is_credit(&t.type_code) == Some(credit))
+            .map(|t| t.amount);
+        summaries.push(Summary {
+            type_code: type_code.to_string(),
+            amount: Some(amounts.clone().sum()),
+            item_count: Some(amounts.count() as u32),
+            funds_type: None,
+        });
+    }
+    Ok(summaries)
+}
+
+/// The as-of date of a statement: that of its closing or other balances,
+/// its last entry or its creation.
+fn as_of_date(stmt: &Statement) -> Option<NaiveDate> {
+    let closing = find_balance(stmt, "CLBD");
+    closing
+        .into_iter()
+        .chain(&stmt.bal)
+        .find_map(|b| b.dt.as_ref().and_then(date_of))
+        .or_else(|| stmt.ntry.iter().rev().find_map(entry_date))
+        .or_else(|| {
+            let to = stmt.fr_to_dt.as_ref()?.to_dt_tm.as_ref()?;
+            NaiveDate::parse_from_str(to.get(..10)?, "%Y-%m-%d").ok()
+        })
+        .or_else(|| NaiveDate::parse_from_str(stmt.cre_dt_tm.as_ref()?.get(..10)?, "%Y-%m-%d").ok())
+}
+
+fn servicer_bic(stmt: &Statement) -> String {
+    stmt.acct
+        .as_ref()
+        .and_then(|a| a.svcr.as_ref()?.fin_instn_id.as_ref()?.bic.clone())
+        .unwrap_or_default()
+}
+
+/// Statements with the same as-of date, servicer and currency, one after
+/// another, form a group; each statement becomes an account.
+impl TryFrom<&Camt053> for Bai2 {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let mut groups: Vec<Group> = Vec::new();
+        for stmt in &camt.bk_to_cstmr_stmt.stmts {
+            let name = stmt.id.as_deref().unwrap_or_default();
+            let acct = stmt.acct.as_ref();
+            let account_number = acct
+                .and_then(|a| a.id.as_ref())
+                .and_then(|id| id.iban.clone().or_else(|| id.other.as_ref()?.id.clone()))
+                .filter(|id| !id.is_empty())
+                .ok_or_else(|| {
+                    ParserError::Converter(format!("Statement '{}' has no account", name))
+                })?;
+            let currency = acct
+                .and_then(|a| a.ccy.clone())
+                .or_else(|| {
+                    stmt.ntry
+                        .iter()
+                        .find_map(|e| e.amt.as_ref()?.currency.clone())
+                })
+                .unwrap_or_else(|| "USD".to_string());
+            let as_of_date = as_of_date(stmt).ok_or_else(|| {
+                ParserError::Converter(format!("Statement '{}' has no dates", name))
+            })?;
+
+            let transactions = stmt
+                .ntry
+                .iter()
+                .map(|entry| transaction(entry, &currency, as_of_date))
+                .collect::<Result<Vec<_>, _>>()?;
+            let account = Bai2Account {
+                account_number: field(&account_number),
+                currency: currency.clone(),
+                summaries: summaries(stmt, &currency, &transactions)?,
+                transactions,
+            };
+
+            let originator = servicer_bic(stmt);
+            match groups.last_mut() {
+                Some(group)
+                    if group.as_of_date == yymmdd(as_of_date)
+                        && group.originator_id == originator
+                        && group.currency == currency =>
+                {
+                    group.accounts.push(account)
+                }
+                _ => groups.push(Group {
+                    ultimate_receiver_id: String::new(),
+                    originator_id: originator,
+                    status: "1".to_string(),
+                    as_of_date: yymmdd(as_of_date),
+                    as_of_time: String::new(),
+                    currency,
+                    as_of_date_modifier: String::new(),
+                    accounts: vec![account],
+                }),
+            }
+        }
+
+        let grp_hdr = &camt.bk_to_cstmr_stmt.grp_hdr;
+        let created = grp_hdr.cre_dt_tm.as_deref().unwrap_or_default();
+        let creation_date =
+            NaiveDate::parse_from_str(created.get(..10).unwrap_or_default(), "%Y-%m-%d")
+                .ok()
+                .map(yymmdd)
+                .or_else(|| Some(groups.first()?.as_of_date.clone()))
+                .ok_or_else(|| ParserError::Converter("Document has no statements".to_string()))?;
+        let creation_time = created
+            .get(11..16)
+            .map(|time| time.replace(':', ""))
+            .filter(|time| time.len() == 4)
+            .unwrap_or_default();
+        let file_id = grp_hdr
+            .msg_id
+            .as_deref()
+            .map(field)
+            .filter(|id| !id.is_empty())
+            .unwrap_or_else(|| "1".to_string());
+
+        Ok(Bai2 {
+            header: FileHeader {
+                sender_id: groups
+                    .first()
+                    .map(|g| g.originator_id.clone())
+                    .unwrap_or_default(),
+                receiver_id: String::new(),
+                creation_date,
+                creation_time,
+                file_id,
+                record_length: String::new(),
+                block_size: String::new(),
+                version: "2".to_string(),
+            },
+            groups,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_camt053_to_bai2() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let camt053 = Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap();
+
+        let bai2 = Bai2::try_from(&camt053).unwrap();
+        assert_eq!(bai2.groups.len(), 1);
+        let account = &bai2.groups[0].accounts[0];
+        assert_eq!(account.account_number, "DE89370400440532013000");
+        assert_eq!(account.currency, "EUR");
+        let codes: Vec<&str> = account
+            .summaries
+            .iter()
+            .map(|s| s.type_code.as_str())
+            .collect();
+        assert_eq!(codes, ["010", "045", "015", "100", "400"]);
+        assert_eq!(account.transactions.len(), 2);
+        assert!(
+            account.transactions[0]
+                .type_code
+                .starts_with(['1', '2', '3'])
+        );
+        assert!(
+            account.transactions[1]
+                .type_code
+                .starts_with(['4', '5', '6'])
+        );
+
+        // The written file passes the reader's control total checks
+        let mut written = Vec::new();
+        bai2.write_to(&mut written).unwrap();
+        assert_eq!(Bai2::from_read(written.as_slice()).unwrap(), bai2);
+    }
+
+    #[test]
+    fn test_bai2_round_trip_keeps_type_codes() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let bai2 = Bai2::from_read(File::open(path.join("valid1.bai2")).unwrap()).unwrap();
+
+        let camt053 = Camt053::try_from(&bai2).unwrap();
+        let result = Bai2::try_from(&camt053).unwrap();
+        let codes = |bai2: &Bai2| -> Vec<(String, i64)> {
+            bai2.groups
+                .iter()
+                .flat_map(|g| &g.accounts)
+                .flat_map(|a| &a.transactions)
+                .map(|t| (t.type_code.clone(), t.amount))
+                .collect()
+        };
+        assert_eq!(codes(&result), codes(&bai2));
+        let account = &result.groups[0].accounts[0];
+        assert_eq!(
+            account.transactions[1].funds_type,
+            bai2.groups[0].accounts[0].transactions[1].funds_type
+        );
+        assert_eq!(account.summaries[1].amount, Some(482500));
+
+        // A debit with an ISO code and no BAI code gets the mapped type code
+        let mut entry = camt053.bk_to_cstmr_stmt.stmts[0].ntry[2].clone();
+        entry.bk_tx_cd.as_mut().unwrap().prtry = None;
+        assert_eq!(type_code(&entry, false), "475");
+        entry.bk_tx_cd = None;
+        assert_eq!(type_code(&entry, false), "699");
+        entry.bk_tx_cd = Some(BankTransactionCode {
+            domn: Some(BankTransactionCodeStructure {
+                cd: Some("CAMT".to_string()),
+                fmly: Some(BankTransactionCodeFamily {
+                    cd: Some("ACCB".to_string()),
+                    sub_fmly_cd: Some("ZABA".to_string()),
+                }),
+            }),
+            prtry: None,
+        });
+        assert_eq!(type_code(&entry, false), "575");
+        assert_eq!(type_code(&entry, true), "275");
+
+        let amount = |value: &str| {
+            Some(Amount {
+                currency: None,
+                value: Some(value.to_string()),
+            })
+        };
+        assert_eq!(minor_units(&amount("12.3"), "USD").unwrap(), 1230);
+        assert_eq!(minor_units(&amount("1500"), "JPY").unwrap(), 1500);
+        assert!(minor_units(&amount("1.005"), "USD").is_err());
+    }
+}
@@ -1,20 +1,43 @@
 use crate::ParserError;
+use crate::bai2::format::*;
+use crate::camt053::balance::{SignedAmount, currency_decimals, new_balance};
 use crate::camt053::format::*;
+use crate::camt054::format::*;
+use crate::cfonb::format::*;
+use crate::client_bank::format::*;
+use crate::coda::format::*;
+use crate::converter::to_camt054::bic_agent;
+use crate::csv::format::*;
+use crate::errors::{Recovery, SourceSpan, WarningKind};
+use crate::json::format::*;
 use crate::mt940::format::*;
+use crate::mt950::format::*;
+use crate::norma43::format::*;
+use crate::ofx::format::*;
+use crate::ofx::reader::ofx_date;
+use crate::pain001::format::*;
+use crate::qif::format::*;
+use crate::xlsx::format::{Cell, Sheet, Xlsx};
 use crate::xml::format::*;
 use chrono::NaiveDate;
+use csv::StringRecord;
 use swift_mt_message::MT940StatementLine;
 use swift_mt_message::SwiftField;
+use swift_mt_message::fields::{Field60, Field61, Field62};
 
 use quick_xml::de;
 
 // --- Helper: parse balance ---
 // It's easier to convert balance to swift string and parse it
 fn parse_balance(field_swift: &str, tag: &str) -> Result<Balance, ParserError> {
-    // Remove tags :60F:, :62F:, etc.
-    let body = field_swift.get(5..).ok_or_else(|| {
-        ParserError::Converter("Failed to parse balance string: field too short".to_string())
-    })?;
+    // Remove tags :60F:, :62F:, :64:, etc.
+    let body = field_swift
+        .strip_prefix(':')
+        .and_then(|field| field.split_once(':'))
+        .map(|(_, body)| body)
+        .ok_or_else(|| {
+            ParserError::Converter("Failed to parse balance string: field too short".to_string())
+        })?;
 
     // Validate minimum length for required fields
     if body.len() < 10 {
@@ -96,231 +119,3257 @@ fn parse_balance(field_swift: &str, tag: &str) -> Result<Balance, ParserError> {
 }
 
 // --- Helper: parse entry line ---
-fn parse_entry(line: &MT940StatementLine) -> Result<Entry, ParserError> {
-    let field61 = &line.field_61;
+// The `:61:` line maps like one of an MT950, in the currency of the opening
+// balance. The `:86:` narrative, when present, replaces the supplementary
+// details as remittance information.
+fn parse_entry(
+    line: &MT940StatementLine,
+    currency: &str,
+    uetr: Option<&str>,
+) -> Result<Entry, ParserError> {
+    let mut entry = mt950_entry(&line.field_61, currency, uetr)?;
+    let amt = entry.amt.clone();
+    for tx_details in entry
+        .ntry_dtls
+        .iter_mut()
+        .flat_map(|details| details.tx_dtls.iter_mut())
+    {
+        tx_details.amt = amt.clone();
+        if let Some(field_86) = &line.field_86 {
+            tx_details.rmt_inf = Some(RemittanceInformation {
+                ustrd: field_86.narrative.clone(),
+            });
+        }
+    }
+    Ok(entry)
+}
 
-    let cdt_dbt = match field61.debit_credit_mark.as_str() {
-        "C" => "CRDT",
-        "D" => "DBIT",
-        _ => "CRDT",
-    };
+/// Converts statement line `index` of `mt940`. In lenient mode a line that
+/// cannot be mapped is left out with a warning.
+pub(crate) fn mt940_entry(
+    mt940: &Mt940,
+    index: usize,
+    line: &MT940StatementLine,
+    recovery: &mut Recovery,
+) -> Result<Option<Entry>, ParserError> {
+    let uetr = mt940
+        .user_header
+        .as_ref()
+        .and_then(|header| header.uetr.as_deref());
+    let currency = &mt940.statement.field_60f.currency;
+    match parse_entry(line, currency, uetr) {
+        Ok(entry) => Ok(Some(entry)),
+        Err(ParserError::Converter(message)) => {
+            let error = ParserError::Converter(format!(
+                "statement line {} of message {}: {}",
+                index + 1,
+                mt940.statement.field_20.reference,
+                message
+            ));
+            recovery.recover(WarningKind::SkippedEntry, error)?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
 
-    let amt_value = format!("{:.2}", field61.amount);
-    let currency = "EUR".to_string();
+impl TryFrom<&Mt940> for Camt053 {
+    type Error = ParserError;
 
-    let rmt_info = line
-        .field_86
-        .as_ref()
-        .map(|field_86| RemittanceInformation {
-            ustrd: field_86.narrative.clone(),
-            ..Default::default()
-        });
+    fn try_from(mt940: &Mt940) -> Result<Self, Self::Error> {
+        mt940_to_camt053(mt940, &mut Recovery::default())
+    }
+}
 
-    let tx_details = TransactionDetails {
-        amt: Some(Amount {
-            currency: Some(currency.clone()),
-            value: Some(amt_value.clone()),
-        }),
-        rmt_inf: rmt_info,
+/// Converts one MT940 message. In lenient mode statement lines that cannot
+/// be mapped are left out with a warning.
+pub(crate) fn mt940_to_camt053(
+    mt940: &Mt940,
+    recovery: &mut Recovery,
+) -> Result<Camt053, ParserError> {
+    let stmt = &mt940.statement;
+
+    // --- GroupHeader ---
+    let msg_id = stmt.field_20.reference.clone();
+    let grp_hdr = GroupHeader {
+        msg_id: Some(msg_id),
         ..Default::default()
     };
 
-    Ok(Entry {
-        amt: Some(Amount {
-            currency: Some(currency),
-            value: Some(amt_value),
+    // --- Account ---
+    let acct_iban = stmt.field_25.authorisation.clone();
+    let acct = Account {
+        id: Some(AccountId {
+            iban: Some(acct_iban),
+            other: None,
         }),
-        cdt_dbt_ind: Some(cdt_dbt.into()),
-        ntry_dtls: vec![EntryDetails {
-            tx_dtls: vec![tx_details],
-            ..Default::default()
-        }],
+        ccy: Some(stmt.field_60f.currency.clone()),
+        svcr: mt940
+            .sender_bic()
+            .map(|bic| BranchAndFinancialInstitutionIdentification {
+                fin_instn_id: Some(FinancialInstitutionIdentification {
+                    bic: Some(bic),
+                    ..Default::default()
+                }),
+            }),
+        ..Default::default()
+    };
+
+    // --- Balances ---
+    let mut balances = vec![];
+    balances.push(parse_balance(&stmt.field_60f.to_swift_string(), "OPBD")?);
+    balances.push(parse_balance(&stmt.field_62f.to_swift_string(), "CLBD")?);
+    if let Some(f64bal) = &stmt.field_64 {
+        balances.push(parse_balance(&f64bal.to_swift_string(), "CLAV")?);
+    }
+    if let Some(f65bal_list) = &stmt.field_65 {
+        for f65bal in f65bal_list {
+            balances.push(parse_balance(&f65bal.to_swift_string(), "FWAV")?);
+        }
+    }
+
+    // --- Entries ---
+    let mut entries: Vec<Entry> = Vec::new();
+    for (index, line) in stmt.statement_lines.iter().enumerate() {
+        entries.extend(mt940_entry(mt940, index, line, recovery)?);
+    }
+
+    // --- Statement ---
+    let statement = Statement {
+        // Without a related reference the statement goes by its own `:20:`
+        id: Some(
+            stmt.field_21
+                .as_ref()
+                .map_or(&stmt.field_20.reference, |f| &f.reference)
+                .clone(),
+        ),
+        acct: Some(acct),
+        elctrnc_seq_nb: Some(
+            stmt.field_28c
+                .to_swift_string()
+                .trim_start_matches(":28C:")
+                .to_string(),
+        ),
+        bal: balances,
+        ntry: entries,
         ..Default::default()
+    };
+    // --- Camt053 ---
+    Ok(Camt053 {
+        bk_to_cstmr_stmt: BankToCustomerStatement {
+            grp_hdr,
+            stmts: vec![statement],
+        },
     })
 }
 
-impl TryFrom<&Mt940> for Camt053 {
+impl TryFrom<&XmlWrapper> for Camt053 {
     type Error = ParserError;
 
-    fn try_from(mt940: &Mt940) -> Result<Self, Self::Error> {
-        let stmt = &mt940.statement;
+    fn try_from(value: &XmlWrapper) -> Result<Self, Self::Error> {
+        let camt = de::from_str(&value.0).map_err(|e| ParserError::Converter(e.to_string()))?;
+        Ok(camt)
+    }
+}
 
-        // --- GroupHeader ---
-        let msg_id = stmt.field_20.reference.clone();
-        let grp_hdr = GroupHeader {
-            msg_id: Some(msg_id),
-            ..Default::default()
-        };
+fn camt_amount(amount: &Option<JsonAmount>) -> Option<Amount> {
+    amount.as_ref().map(|a| Amount {
+        currency: a.currency.clone(),
+        value: Some(a.value.clone()),
+    })
+}
 
-        // --- Account ---
-        let acct_iban = stmt.field_25.authorisation.clone();
-        let acct = Account {
-            id: Some(AccountId {
-                iban: Some(acct_iban),
-                other: None,
+fn camt_date(dt: &Option<String>, dt_tm: &Option<String>) -> Option<DateAndDateTimeChoice> {
+    if dt.is_none() && dt_tm.is_none() {
+        return None;
+    }
+    Some(DateAndDateTimeChoice {
+        dt: dt.clone(),
+        dt_tm: dt_tm.clone(),
+    })
+}
+
+fn camt_other_id(other_id: &Option<String>) -> Option<GenericAccountIdentification> {
+    other_id.as_ref().map(|id| GenericAccountIdentification {
+        id: Some(id.clone()),
+    })
+}
+
+pub(crate) fn camt_account(account: &JsonAccount) -> Account {
+    let id = if account.iban.is_some() || account.other_id.is_some() {
+        Some(AccountId {
+            iban: account.iban.clone(),
+            other: camt_other_id(&account.other_id),
+        })
+    } else {
+        None
+    };
+    Account {
+        id,
+        ccy: account.currency.clone(),
+        name: account.name.clone(),
+        ..Default::default()
+    }
+}
+
+fn camt_party_account(account: &Option<JsonAccount>) -> Option<AccountIdentification> {
+    account.as_ref().map(|a| AccountIdentification {
+        iban: a.iban.clone(),
+        other: camt_other_id(&a.other_id),
+    })
+}
+
+fn camt_balance(balance: &JsonBalance) -> Balance {
+    let tp = if balance.tp.is_some() || balance.proprietary_type.is_some() {
+        Some(BalanceType {
+            cd_or_prtry: Some(CodeOrProprietary {
+                cd: balance.tp.clone(),
+                prtry: balance.proprietary_type.clone(),
             }),
-            ccy: Some("EUR".into()),
-            ..Default::default()
-        };
+        })
+    } else {
+        None
+    };
+    Balance {
+        tp,
+        amt: camt_amount(&balance.amount),
+        cdt_dbt_ind: balance.credit_debit.clone(),
+        dt: camt_date(&balance.date, &balance.date_time),
+    }
+}
 
-        // --- Balances ---
-        let mut balances = vec![];
-        balances.push(parse_balance(&stmt.field_60f.to_swift_string(), "OPBD")?);
-        balances.push(parse_balance(&stmt.field_62f.to_swift_string(), "CLBD")?);
-        if let Some(f64bal) = &stmt.field_64 {
-            balances.push(parse_balance(&f64bal.to_swift_string(), "CLAV")?);
-        }
-        if let Some(f65bal_list) = &stmt.field_65 {
-            for f65bal in f65bal_list {
-                balances.push(parse_balance(&f65bal.to_swift_string(), "FWAV")?);
-            }
+fn camt_transaction_details(details: &JsonTransactionDetails) -> TransactionDetails {
+    let has_parties = details.debtor.is_some()
+        || details.creditor.is_some()
+        || details.debtor_account.is_some()
+        || details.creditor_account.is_some();
+
+    TransactionDetails {
+        refs: details.references.as_ref().map(|r| TransactionReferences {
+            msg_id: r.message_id.clone(),
+            acct_svcr_ref: r.account_servicer_reference.clone(),
+            pmt_inf_id: r.payment_information_id.clone(),
+            instr_id: r.instruction_id.clone(),
+            end_to_end_id: r.end_to_end_id.clone(),
+            uetr: r.uetr.clone(),
+            tx_id: r.transaction_id.clone(),
+        }),
+        amt: camt_amount(&details.amount),
+        rltd_pties: has_parties.then(|| RelatedParties {
+            dbtr: details.debtor.as_ref().map(|name| Party {
+                name: Some(name.clone()),
+                ..Default::default()
+            }),
+            cdtr: details.creditor.as_ref().map(|name| Party {
+                name: Some(name.clone()),
+                ..Default::default()
+            }),
+            dbtr_acct: camt_party_account(&details.debtor_account),
+            cdtr_acct: camt_party_account(&details.creditor_account),
+        }),
+        rltd_agts: (details.debtor_agent.is_some() || details.creditor_agent.is_some()).then(
+            || RelatedAgents {
+                dbtr_agt: details.debtor_agent.as_deref().map(bic_agent),
+                cdtr_agt: details.creditor_agent.as_deref().map(bic_agent),
+            },
+        ),
+        rmt_inf: (!details.remittance_information.is_empty()).then(|| RemittanceInformation {
+            ustrd: details.remittance_information.clone(),
+        }),
+    }
+}
+
+pub(crate) fn camt_entry(entry: &JsonEntry) -> Entry {
+    Entry {
+        amt: camt_amount(&entry.amount),
+        cdt_dbt_ind: entry.credit_debit.clone(),
+        sts: entry.status.clone(),
+        bookg_dt: camt_date(&entry.booking_date, &entry.booking_date_time),
+        val_dt: camt_date(&entry.value_date, &entry.value_date_time),
+        bk_tx_cd: entry
+            .bank_transaction_code
+            .as_ref()
+            .map(|code| BankTransactionCode {
+                domn: code
+                    .domain
+                    .as_ref()
+                    .map(|domain| BankTransactionCodeStructure {
+                        cd: Some(domain.clone()),
+                        fmly: code
+                            .family
+                            .as_ref()
+                            .map(|family| BankTransactionCodeFamily {
+                                cd: Some(family.clone()),
+                                sub_fmly_cd: code.sub_family.clone(),
+                            }),
+                    }),
+                prtry: code
+                    .proprietary
+                    .as_ref()
+                    .map(|cd| ProprietaryBankTransactionCode {
+                        cd: Some(cd.clone()),
+                        issr: code.issuer.clone(),
+                    }),
+            }),
+        ntry_dtls: if entry.details.is_empty() {
+            vec![]
+        } else {
+            vec![EntryDetails {
+                tx_dtls: entry.details.iter().map(camt_transaction_details).collect(),
+            }]
+        },
+    }
+}
+
+impl TryFrom<&JsonStatements> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(json: &JsonStatements) -> Result<Self, Self::Error> {
+        if json.version != STATEMENT_JSON_VERSION {
+            return Err(ParserError::Converter(format!(
+                "Unsupported statement JSON version: {}",
+                json.version
+            )));
         }
 
-        // --- Entries ---
-        let entries: Vec<Entry> = stmt
-            .statement_lines
+        let stmts = json
+            .statements
             .iter()
-            .map(|line| parse_entry(line))
-            .collect::<Result<_, _>>()?;
-
-        // --- Statement ---
-        let statement = Statement {
-            id: stmt.field_21.as_ref().map(|f| f.reference.clone()),
-            acct: Some(acct),
-            elctrnc_seq_nb: Some(
-                stmt.field_28c
-                    .to_swift_string()
-                    .trim_start_matches(":28C:")
-                    .to_string(),
-            ),
-            bal: balances,
-            ntry: entries,
-            ..Default::default()
-        };
-        // --- Camt053 ---
+            .map(|stmt| Statement {
+                id: stmt.id.clone(),
+                elctrnc_seq_nb: stmt.electronic_sequence_number.clone(),
+                lgl_seq_nb: stmt.legal_sequence_number.clone(),
+                cre_dt_tm: stmt.created_at.clone(),
+                fr_to_dt: if stmt.from_date_time.is_some() || stmt.to_date_time.is_some() {
+                    Some(FromToDate {
+                        fr_dt_tm: stmt.from_date_time.clone(),
+                        to_dt_tm: stmt.to_date_time.clone(),
+                    })
+                } else {
+                    None
+                },
+                acct: stmt.account.as_ref().map(camt_account),
+                bal: stmt.balances.iter().map(camt_balance).collect(),
+                ntry: stmt.entries.iter().map(camt_entry).collect(),
+            })
+            .collect();
+
         Ok(Camt053 {
             bk_to_cstmr_stmt: BankToCustomerStatement {
-                grp_hdr,
-                stmts: vec![statement],
+                grp_hdr: GroupHeader {
+                    msg_id: json.message_id.clone(),
+                    cre_dt_tm: json.created_at.clone(),
+                },
+                stmts,
             },
         })
     }
 }
 
-impl TryFrom<&XmlWrapper> for Camt053 {
+impl TryFrom<&NdjsonEntries> for Camt053 {
     type Error = ParserError;
 
-    fn try_from(value: &XmlWrapper) -> Result<Self, Self::Error> {
-        let camt = de::from_str(&value.0).map_err(|e| ParserError::Converter(e.to_string()))?;
-        Ok(camt)
+    fn try_from(ndjson: &NdjsonEntries) -> Result<Self, Self::Error> {
+        // Consecutive lines of the same statement are grouped back together.
+        // Balances are not part of the NDJSON stream and stay empty.
+        let mut stmts: Vec<Statement> = vec![];
+        let mut current_key: Option<(&Option<String>, &Option<JsonAccount>)> = None;
+
+        for line in &ndjson.0 {
+            let key = (&line.statement_id, &line.account);
+            if current_key != Some(key) {
+                stmts.push(Statement {
+                    id: line.statement_id.clone(),
+                    acct: line.account.as_ref().map(camt_account),
+                    ..Default::default()
+                });
+                current_key = Some(key);
+            }
+            if let Some(stmt) = stmts.last_mut() {
+                stmt.ntry.push(camt_entry(&line.entry));
+            }
+        }
+
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: ndjson.0.first().and_then(|line| line.message_id.clone()),
+                    ..Default::default()
+                },
+                stmts,
+            },
+        })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::traits::FinancialDataRead;
-    use std::env;
-    use std::fs::File;
-    use std::path::PathBuf;
+/// Positions of the [`CSV_COLUMNS`] in a CSV header, `None` for missing columns.
+pub(crate) struct CsvColumns(Vec<Option<usize>>);
 
-    #[test]
-    fn test_convert_mt940_to_camt053() {
-        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
-        let path = PathBuf::from(manifest_dir).join("test_data");
-        let target_file_path = path.join("simple.mt940");
-        let target_file = File::open(target_file_path).unwrap();
-        let mt940_valid = Mt940::from_read(target_file).unwrap();
+impl CsvColumns {
+    pub(crate) fn new(header: &StringRecord) -> Result<Self, ParserError> {
+        let columns = CsvColumns(
+            CSV_COLUMNS
+                .iter()
+                .map(|name| header.iter().position(|h| h.trim() == *name))
+                .collect(),
+        );
+        for required in ["amount", "credit_debit"] {
+            if columns.index(required).is_none() {
+                return Err(ParserError::Csv(format!("Missing column: {}", required)));
+            }
+        }
+        Ok(columns)
+    }
 
-        let result: Result<Camt053, ParserError> = (&mt940_valid).try_into();
-        let result = result.unwrap();
+    fn index(&self, name: &str) -> Option<usize> {
+        let column = CSV_COLUMNS.iter().position(|c| *c == name)?;
+        self.0[column]
+    }
 
-        // GroupHeader
-        assert_eq!(
-            result.bk_to_cstmr_stmt.grp_hdr.msg_id,
-            Some("STAT202510210001".to_string()) // :20:STAT202510210001
-        );
+    /// The non-empty value of column `name` in `record`.
+    fn get(&self, record: &StringRecord, name: &str) -> Option<String> {
+        let value = record.get(self.index(name)?)?.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    }
+}
 
-        // Statement
-        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
-        assert_eq!(stmt.elctrnc_seq_nb, Some("1/1".to_string())); // :28C:00001/001
+/// Splits a date column into a date or, when it has a time, a date and time.
+fn csv_date(value: Option<String>) -> (Option<String>, Option<String>) {
+    match value {
+        Some(value) if value.contains('T') => (None, Some(value)),
+        value => (value, None),
+    }
+}
 
-        // Account
-        let acct = stmt.acct.as_ref().unwrap();
-        let acct_id = acct.id.as_ref().unwrap();
-        assert_eq!(acct_id.iban, Some("NL91ABNA0417164300".to_string())); // :25:NL91ABNA0417164300
-        assert_eq!(acct.ccy, Some("EUR".to_string()));
+/// The entry of one row of the statement CSV layout, see [`CSV_COLUMNS`].
+pub(crate) fn csv_entry_line(
+    columns: &CsvColumns,
+    record: &StringRecord,
+) -> Result<JsonEntryLine, ParserError> {
+    let get = |name| columns.get(record, name);
+    let value = get("amount").ok_or(ParserError::Csv("Missing amount".to_string()))?;
+    if value.parse::<f64>().is_err() {
+        return Err(ParserError::Csv(format!("Invalid amount: '{}'", value)));
+    }
+    let credit_debit = get("credit_debit");
+    if !matches!(credit_debit.as_deref(), Some("CRDT" | "DBIT")) {
+        return Err(ParserError::Csv(format!(
+            "Invalid credit/debit indicator: '{}'",
+            credit_debit.unwrap_or_default()
+        )));
+    }
 
-        // Balances
-        let bal1 = &stmt.bal[0]; // :60F:C251020EUR10000,00
-        assert_eq!(
-            bal1.tp.as_ref().unwrap().cd_or_prtry.as_ref().unwrap().cd,
-            Some("OPBD".to_string())
-        );
-        assert_eq!(bal1.amt.as_ref().unwrap().currency, Some("EUR".to_string()));
-        assert_eq!(
-            bal1.amt.as_ref().unwrap().value,
-            Some("10000,00".to_string())
-        );
-        assert_eq!(bal1.cdt_dbt_ind, Some("CRDT".to_string()));
-        assert_eq!(bal1.dt.as_ref().unwrap().dt, Some("2025-10-20".to_string()));
+    let account = JsonAccount {
+        iban: get("iban"),
+        other_id: get("account_id"),
+        currency: get("account_currency"),
+        name: None,
+    };
+    let code = JsonBankTransactionCode {
+        domain: get("domain"),
+        family: get("family"),
+        sub_family: get("sub_family"),
+        proprietary: get("proprietary_code"),
+        issuer: None,
+    };
+    let references = JsonReferences {
+        end_to_end_id: get("end_to_end_id"),
+        account_servicer_reference: get("account_servicer_reference"),
+        ..Default::default()
+    };
+    let party_account = |name| {
+        get(name).map(|id| JsonAccount {
+            iban: is_iban(&id).then(|| id.clone()),
+            other_id: (!is_iban(&id)).then_some(id),
+            ..Default::default()
+        })
+    };
+    let details = JsonTransactionDetails {
+        references: (references != JsonReferences::default()).then_some(references),
+        debtor: get("debtor"),
+        creditor: get("creditor"),
+        debtor_account: party_account("debtor_account"),
+        creditor_account: party_account("creditor_account"),
+        debtor_agent: get("debtor_agent"),
+        creditor_agent: get("creditor_agent"),
+        remittance_information: get("remittance_information")
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let (booking_date, booking_date_time) = csv_date(get("booking_date"));
+    let (value_date, value_date_time) = csv_date(get("value_date"));
 
-        let bal2 = &stmt.bal[1]; // :62F:C251021EUR11239,50
-        assert_eq!(
-            bal2.tp.as_ref().unwrap().cd_or_prtry.as_ref().unwrap().cd,
-            Some("CLBD".to_string())
-        );
-        assert_eq!(bal2.amt.as_ref().unwrap().currency, Some("EUR".to_string()));
-        assert_eq!(
-            bal2.amt.as_ref().unwrap().value,
-            Some("11239,50".to_string())
-        );
-        assert_eq!(bal2.cdt_dbt_ind, Some("CRDT".to_string()));
-        assert_eq!(bal2.dt.as_ref().unwrap().dt, Some("2025-10-21".to_string()));
+    Ok(JsonEntryLine {
+        message_id: get("message_id"),
+        statement_id: get("statement_id"),
+        account: (account != JsonAccount::default()).then_some(account),
+        entry: JsonEntry {
+            amount: Some(JsonAmount {
+                value,
+                currency: get("currency"),
+            }),
+            credit_debit,
+            status: get("status"),
+            booking_date,
+            booking_date_time,
+            value_date,
+            value_date_time,
+            bank_transaction_code: (code != JsonBankTransactionCode::default()).then_some(code),
+            details: if details == JsonTransactionDetails::default() {
+                vec![]
+            } else {
+                vec![details]
+            },
+        },
+    })
+}
 
-        // Entry
-        let entry = &stmt.ntry[0]; // :61:2510211021D250,00NTRFNONREF//BKNTRX0001
-        assert_eq!(
-            entry.amt.as_ref().unwrap().currency,
-            Some("EUR".to_string())
-        );
-        assert_eq!(
-            entry.amt.as_ref().unwrap().value,
-            Some("250.00".to_string())
-        );
-        assert_eq!(entry.cdt_dbt_ind, Some("DBIT".to_string()));
+/// Attaches the line of a CSV row to an error.
+pub(crate) fn locate_record(record: &StringRecord, error: ParserError) -> ParserError {
+    match record.position() {
+        Some(position) => error.at_span(
+            SourceSpan {
+                offset: position.byte() as usize,
+                line: position.line() as usize,
+                column: 1,
+                len: 0,
+            },
+            None,
+        ),
+        None => error,
+    }
+}
 
-        // TransactionDetails
-        let tx_details = &entry.ntry_dtls[0].tx_dtls[0];
-        assert_eq!(
-            tx_details.amt.as_ref().unwrap().currency,
-            Some("EUR".to_string())
-        );
-        assert_eq!(
-            tx_details.amt.as_ref().unwrap().value,
-            Some("250.00".to_string())
-        );
-        assert_eq!(
-            tx_details.rmt_inf.as_ref().unwrap().ustrd[0],
-            "Payment to supplier Roga i Kopyta Inv 1001".to_string() // :86:Payment to supplier Roga i Kopyta Inv 1001
-        );
+/// Converts the rows of the statement CSV layout, see [`CSV_COLUMNS`].
+///
+/// Rows that cannot be converted are skipped in lenient mode.
+pub(crate) fn csv_to_camt053(
+    csv: &CsvWrapper,
+    recovery: &mut Recovery,
+) -> Result<Camt053, ParserError> {
+    let Some((header, rows)) = csv.0.split_first() else {
+        return Err(ParserError::Csv("Missing header row".to_string()));
+    };
+    let columns = CsvColumns::new(header)?;
+
+    let mut lines = Vec::with_capacity(rows.len());
+    for record in rows {
+        match csv_entry_line(&columns, record) {
+            Ok(line) => lines.push(line),
+            Err(e) => recovery.recover(WarningKind::SkippedEntry, locate_record(record, e))?,
+        }
     }
+    Camt053::try_from(&NdjsonEntries(lines))
+}
 
-    #[test]
-    fn test_convert_xmlwrapper_to_camt053() {
-        let xml_data = r#"
-            <Document>
-                <BkToCstmrStmt>
-                    <GrpHdr>
-                        <MsgId>12345</MsgId>
-                    </GrpHdr>
-                </BkToCstmrStmt>
-            </Document>
-        "#;
+impl TryFrom<&CsvWrapper> for Camt053 {
+    type Error = ParserError;
 
-        let xml_wrapper = XmlWrapper(xml_data.to_string());
-        let result = Camt053::try_from(&xml_wrapper).unwrap();
+    fn try_from(csv: &CsvWrapper) -> Result<Self, Self::Error> {
+        csv_to_camt053(csv, &mut Recovery::default())
+    }
+}
 
-        assert_eq!(result.bk_to_cstmr_stmt.grp_hdr.msg_id.unwrap(), "12345");
+/// Gives a CSV column error the row and sheet of the workbook it is in.
+fn locate_cell_row(error: ParserError, row: usize, sheet: &Sheet) -> ParserError {
+    match error {
+        ParserError::Csv(message) => ParserError::Xlsx(format!(
+            "{} in row {} of sheet '{}'",
+            message,
+            row + 1,
+            sheet.name
+        )),
+        other => other,
+    }
+}
+
+/// Converts the entries of every sheet with a row of [`CSV_COLUMNS`]
+/// headers: the rows below it, up to the first empty one, are read as
+/// the rows of a CSV file. Other sheets, e.g. of notes, are left out.
+///
+/// The account and balances above the headers are not read, as CSV has
+/// no balances. Rows that cannot be converted are skipped in lenient mode.
+pub(crate) fn xlsx_to_camt053(
+    xlsx: &Xlsx,
+    recovery: &mut Recovery,
+) -> Result<Camt053, ParserError> {
+    let mut lines = Vec::new();
+    let mut found = false;
+    for sheet in &xlsx.sheets {
+        let records: Vec<StringRecord> = sheet
+            .rows
+            .iter()
+            .map(|row| row.iter().map(Cell::csv_value).collect())
+            .collect();
+        let is_header = |record: &StringRecord| record.iter().any(|value| value.trim() == "amount");
+        let Some(start) = records.iter().position(is_header) else {
+            continue;
+        };
+        found = true;
+        let columns =
+            CsvColumns::new(&records[start]).map_err(|e| locate_cell_row(e, start, sheet))?;
+
+        for (index, record) in records.iter().enumerate().skip(start + 1) {
+            if record.iter().all(|value| value.trim().is_empty()) {
+                break;
+            }
+            match csv_entry_line(&columns, record) {
+                Ok(line) => lines.push(line),
+                Err(e) => {
+                    recovery.recover(WarningKind::SkippedEntry, locate_cell_row(e, index, sheet))?
+                }
+            }
+        }
+    }
+    if !found {
+        return Err(ParserError::Xlsx(
+            "No sheet has a row of statement column headers".to_string(),
+        ));
+    }
+    Camt053::try_from(&NdjsonEntries(lines))
+}
+
+impl TryFrom<&Xlsx> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(xlsx: &Xlsx) -> Result<Self, Self::Error> {
+        xlsx_to_camt053(xlsx, &mut Recovery::default())
+    }
+}
+
+/// A BAI2 `YYMMDD` date, in this century.
+pub(crate) fn bai2_date(date: &str) -> Result<NaiveDate, ParserError> {
+    NaiveDate::parse_from_str(&format!("20{}", date), "%Y%m%d")
+        .map_err(|_| ParserError::Converter(format!("Invalid BAI2 date '{}'", date)))
+}
+
+/// A BAI2 date and `HHMM` time; end of day (`2400`, `9999`) and missing
+/// times give the date alone.
+fn bai2_date_time(date: &str, time: &str) -> Result<DateAndDateTimeChoice, ParserError> {
+    let date = bai2_date(date)?;
+    let time = chrono::NaiveTime::parse_from_str(time, "%H%M").ok();
+    Ok(match time {
+        Some(time) => DateAndDateTimeChoice {
+            dt: None,
+            dt_tm: Some(date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string()),
+        },
+        None => DateAndDateTimeChoice {
+            dt: Some(date.format("%Y-%m-%d").to_string()),
+            dt_tm: None,
+        },
+    })
+}
+
+/// An amount in minor units as a decimal, without its sign.
+fn bai2_amount(units: i64, currency: &str) -> Amount {
+    let decimals = currency_decimals(currency);
+    let units = units.unsigned_abs();
+    let value = match decimals {
+        0 => units.to_string(),
+        _ => {
+            let scale = 10u64.pow(decimals);
+            format!(
+                "{}.{:0width$}",
+                units / scale,
+                units % scale,
+                width = decimals as usize
+            )
+        }
+    };
+    Amount {
+        currency: Some(currency.to_string()),
+        value: Some(value),
+    }
+}
+
+/// A status summary of an `03` record as a balance: the common balances
+/// by their ISO code, the others by their type code.
+fn bai2_balance(summary: &Summary, currency: &str, dt: &DateAndDateTimeChoice) -> Option<Balance> {
+    let amount = summary.amount?;
+    let code = BALANCE_CODES
+        .iter()
+        .find(|(type_code, _)| *type_code == summary.type_code)
+        .map(|(_, code)| code.to_string());
+    Some(Balance {
+        tp: Some(BalanceType {
+            cd_or_prtry: Some(CodeOrProprietary {
+                prtry: code.is_none().then(|| summary.type_code.clone()),
+                cd: code,
+            }),
+        }),
+        amt: Some(bai2_amount(amount, currency)),
+        cdt_dbt_ind: Some(if amount < 0 { "DBIT" } else { "CRDT" }.to_string()),
+        dt: Some(dt.clone()),
+    })
+}
+
+fn bai2_entry(
+    transaction: &Transaction,
+    currency: &str,
+    as_of_date: &DateAndDateTimeChoice,
+) -> Result<Entry, ParserError> {
+    let credit = is_credit(&transaction.type_code).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Type code '{}' is not a credit or debit detail code",
+            transaction.type_code
+        ))
+    })?;
+    let val_dt = match &transaction.funds_type {
+        Some(FundsType::ValueDated { date, .. }) => Some(DateAndDateTimeChoice {
+            dt: Some(bai2_date(date)?.format("%Y-%m-%d").to_string()),
+            dt_tm: None,
+        }),
+        _ => None,
+    };
+    let domain = TYPE_CODES
+        .iter()
+        .find(|(type_code, ..)| *type_code == transaction.type_code)
+        .map(
+            |(_, domain, family, sub_family)| BankTransactionCodeStructure {
+                cd: Some(domain.to_string()),
+                fmly: Some(BankTransactionCodeFamily {
+                    cd: Some(family.to_string()),
+                    sub_fmly_cd: Some(sub_family.to_string()),
+                }),
+            },
+        );
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let refs = TransactionReferences {
+        acct_svcr_ref: non_empty(&transaction.bank_reference),
+        end_to_end_id: non_empty(&transaction.customer_reference),
+        ..Default::default()
+    };
+    let has_refs = refs.acct_svcr_ref.is_some() || refs.end_to_end_id.is_some();
+    let details = TransactionDetails {
+        refs: has_refs.then_some(refs),
+        rmt_inf: (!transaction.text.is_empty()).then(|| RemittanceInformation {
+            ustrd: transaction.text.clone(),
+        }),
+        ..Default::default()
+    };
+    Ok(Entry {
+        amt: Some(bai2_amount(transaction.amount, currency)),
+        cdt_dbt_ind: Some(if credit { "CRDT" } else { "DBIT" }.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt: Some(as_of_date.clone()),
+        val_dt,
+        bk_tx_cd: Some(BankTransactionCode {
+            domn: domain,
+            prtry: Some(ProprietaryBankTransactionCode {
+                cd: Some(transaction.type_code.clone()),
+                issr: Some("BAI".to_string()),
+            }),
+        }),
+        ntry_dtls: if has_refs || details.rmt_inf.is_some() {
+            vec![EntryDetails {
+                tx_dtls: vec![details],
+            }]
+        } else {
+            vec![]
+        },
+    })
+}
+
+/// Whether a BAI2 originator identification is a BIC rather than, as
+/// usually, an ABA routing number.
+fn is_bic(id: &str) -> bool {
+    matches!(id.len(), 8 | 11)
+        && id.bytes().take(6).all(|b| b.is_ascii_uppercase())
+        && id.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+/// One statement per account, with the status summaries as balances and
+/// the transaction details as booked entries on the as-of date.
+///
+/// The `100` and `400` total summaries are left out; the writer recomputes them.
+impl TryFrom<&Bai2> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(bai2: &Bai2) -> Result<Self, Self::Error> {
+        let header = &bai2.header;
+        let created = bai2_date_time(&header.creation_date, &header.creation_time)?;
+        let created = created.dt_tm.or(created.dt);
+        let mut stmts = Vec::new();
+        for group in &bai2.groups {
+            let as_of_date = bai2_date(&group.as_of_date)?;
+            let as_of_date = DateAndDateTimeChoice {
+                dt: Some(as_of_date.format("%Y-%m-%d").to_string()),
+                dt_tm: None,
+            };
+            let svcr =
+                is_bic(&group.originator_id).then(|| BranchAndFinancialInstitutionIdentification {
+                    fin_instn_id: Some(FinancialInstitutionIdentification {
+                        bic: Some(group.originator_id.clone()),
+                        ..Default::default()
+                    }),
+                });
+            for account in &group.accounts {
+                let currency = [&account.currency, &group.currency]
+                    .into_iter()
+                    .find(|currency| !currency.is_empty())
+                    .map_or("USD", |currency| currency.as_str());
+                let entries = account
+                    .transactions
+                    .iter()
+                    .map(|transaction| bai2_entry(transaction, currency, &as_of_date))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let sequence = (stmts.len() + 1).to_string();
+                stmts.push(Statement {
+                    id: Some(format!("{}-{}", header.file_id, sequence)),
+                    elctrnc_seq_nb: Some(sequence),
+                    cre_dt_tm: created.clone(),
+                    acct: Some(Account {
+                        id: Some(AccountId {
+                            iban: None,
+                            other: Some(GenericAccountIdentification {
+                                id: Some(account.account_number.clone()),
+                            }),
+                        }),
+                        ccy: Some(currency.to_string()),
+                        name: None,
+                        svcr: svcr.clone(),
+                    }),
+                    bal: account
+                        .summaries
+                        .iter()
+                        .filter(|summary| is_status(&summary.type_code))
+                        .filter_map(|summary| bai2_balance(summary, currency, &as_of_date))
+                        .collect(),
+                    ntry: entries,
+                    ..Default::default()
+                });
+            }
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: Some(header.file_id.clone()),
+                    cre_dt_tm: created,
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// A CODA `DDMMYY` date as `YYYY-MM-DD`; empty for none.
+pub(crate) fn coda_date(date: &str) -> Result<Option<String>, ParserError> {
+    if date.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(date, "%d%m%y")
+        .map(|date| Some(date.format("%Y-%m-%d").to_string()))
+        .map_err(|_| ParserError::Converter(format!("Invalid CODA date '{}'", date)))
+}
+
+fn coda_day(date: &str) -> Result<Option<DateAndDateTimeChoice>, ParserError> {
+    Ok(coda_date(date)?.map(|dt| DateAndDateTimeChoice {
+        dt: Some(dt),
+        dt_tm: None,
+    }))
+}
+
+/// An amount in thousandths, with three decimals only when it needs them.
+fn coda_amount(thousandths: i64, debit: bool) -> SignedAmount {
+    let amount = SignedAmount {
+        units: i128::from(thousandths) * 100,
+        decimals: if thousandths % 10 == 0 { 2 } else { 3 },
+    };
+    if debit { amount.negate() } else { amount }
+}
+
+fn coda_balance(code: &str, balance: &CodaBalance, currency: &str) -> Result<Balance, ParserError> {
+    Ok(new_balance(
+        code,
+        coda_amount(balance.amount, balance.debit),
+        Some(currency.to_string()),
+        coda_day(&balance.date)?,
+    ))
+}
+
+/// A communication as remittance text, structured OGM/VCS references as `+++…+++`.
+fn coda_communication(communication: &Communication) -> String {
+    match communication {
+        Communication::Structured { kind, text } if kind == "101" || kind == "102" => {
+            match Ogm::parse(text.get(..12).unwrap_or(text)) {
+                Some(ogm) => ogm.to_string(),
+                None => text.trim().to_string(),
+            }
+        }
+        Communication::Structured { text, .. } | Communication::Unstructured(text) => {
+            text.trim().to_string()
+        }
+    }
+}
+
+/// The ISO code of the family in digits 2 and 3 of a transaction code.
+fn coda_domain(transaction_code: &str, debit: bool) -> Option<BankTransactionCodeStructure> {
+    let family = transaction_code.get(1..3)?;
+    let (_, codes) = FAMILY_CODES.iter().find(|(code, _)| *code == family)?;
+    let (domain, family, sub_family) = codes[usize::from(debit)];
+    Some(BankTransactionCodeStructure {
+        cd: Some(domain.to_string()),
+        fmly: Some(BankTransactionCodeFamily {
+            cd: Some(family.to_string()),
+            sub_fmly_cd: Some(sub_family.to_string()),
+        }),
+    })
+}
+
+fn coda_account_id(number: &str, iban: bool) -> AccountIdentification {
+    AccountIdentification {
+        iban: iban.then(|| number.to_string()),
+        other: (!iban).then(|| GenericAccountIdentification {
+            id: Some(number.to_string()),
+        }),
+    }
+}
+
+fn coda_transaction_details(movement: &Movement, currency: &str) -> TransactionDetails {
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let refs = TransactionReferences {
+        acct_svcr_ref: non_empty(&movement.bank_reference),
+        end_to_end_id: non_empty(&movement.customer_reference),
+        ..Default::default()
+    };
+    let has_refs = refs.acct_svcr_ref.is_some() || refs.end_to_end_id.is_some();
+
+    let counterparty = Party {
+        name: non_empty(&movement.counterparty_name),
+        ..Default::default()
+    };
+    let counterparty_account = non_empty(&movement.counterparty_account).map(|number| {
+        let iban = number.len() > 4
+            && number.bytes().take(2).all(|b| b.is_ascii_uppercase())
+            && number.bytes().skip(2).take(2).all(|b| b.is_ascii_digit());
+        coda_account_id(&number, iban)
+    });
+    let rltd_pties = (counterparty.name.is_some() || counterparty_account.is_some()).then(|| {
+        if movement.debit {
+            RelatedParties {
+                cdtr: counterparty.name.is_some().then_some(counterparty),
+                cdtr_acct: counterparty_account,
+                ..Default::default()
+            }
+        } else {
+            RelatedParties {
+                dbtr: counterparty.name.is_some().then_some(counterparty),
+                dbtr_acct: counterparty_account,
+                ..Default::default()
+            }
+        }
+    });
+
+    let ustrd: Vec<String> = std::iter::once(&movement.communication)
+        .chain(movement.information.iter().map(|i| &i.communication))
+        .map(coda_communication)
+        .filter(|line| !line.is_empty())
+        .collect();
+    TransactionDetails {
+        refs: has_refs.then_some(refs),
+        // Only the breakdown of a globalised movement has amounts of its own
+        amt: (movement.detail > 0).then(|| Amount {
+            currency: Some(currency.to_string()),
+            value: Some(coda_amount(movement.amount, false).magnitude()),
+        }),
+        rltd_pties,
+        rltd_agts: None,
+        rmt_inf: (!ustrd.is_empty()).then_some(RemittanceInformation { ustrd }),
+    }
+}
+
+fn coda_entry(movement: &Movement, currency: &str) -> Result<Entry, ParserError> {
+    let details = coda_transaction_details(movement, currency);
+    let has_details =
+        details.refs.is_some() || details.rltd_pties.is_some() || details.rmt_inf.is_some();
+    Ok(Entry {
+        amt: Some(Amount {
+            currency: Some(currency.to_string()),
+            value: Some(coda_amount(movement.amount, false).magnitude()),
+        }),
+        cdt_dbt_ind: Some(if movement.debit { "DBIT" } else { "CRDT" }.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt: coda_day(&movement.entry_date)?,
+        val_dt: coda_day(&movement.value_date)?,
+        bk_tx_cd: Some(BankTransactionCode {
+            domn: coda_domain(&movement.transaction_code, movement.debit),
+            prtry: Some(ProprietaryBankTransactionCode {
+                cd: Some(movement.transaction_code.clone()),
+                issr: Some("FEBELFIN".to_string()),
+            }),
+        }),
+        ntry_dtls: if has_details {
+            vec![EntryDetails {
+                tx_dtls: vec![details],
+            }]
+        } else {
+            vec![]
+        },
+    })
+}
+
+/// One statement per CODA statement, with the old and new balances as
+/// opening and closing balances.
+///
+/// Each movement with detail number `0` becomes an entry; the detail
+/// movements that break a globalised one down become further transaction
+/// details of that entry.
+impl TryFrom<&Coda> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(coda: &Coda) -> Result<Self, Self::Error> {
+        let first = coda
+            .statements
+            .first()
+            .ok_or_else(|| ParserError::Converter("CODA file has no statements".to_string()))?;
+        let mut stmts = Vec::new();
+        for statement in &coda.statements {
+            let header = &statement.header;
+            let currency = statement.account.currency.as_str();
+            let created = coda_date(&header.creation_date)?;
+            let mut entries: Vec<Entry> = Vec::new();
+            for movement in &statement.movements {
+                if movement.detail == 0 {
+                    entries.push(coda_entry(movement, currency)?);
+                    continue;
+                }
+                let entry = entries.last_mut().ok_or_else(|| {
+                    ParserError::Converter(format!(
+                        "Detail {} of movement {} without the movement",
+                        movement.detail, movement.sequence
+                    ))
+                })?;
+                let details = coda_transaction_details(movement, currency);
+                match entry.ntry_dtls.first_mut() {
+                    Some(entry_details) => entry_details.tx_dtls.push(details),
+                    None => entry.ntry_dtls.push(EntryDetails {
+                        tx_dtls: vec![TransactionDetails::default(), details],
+                    }),
+                }
+            }
+            let id = match header.file_reference.as_str() {
+                "" => format!(
+                    "{}-{}",
+                    created.as_deref().unwrap_or_default().replace('-', ""),
+                    statement.coded_sequence
+                ),
+                reference => reference.to_string(),
+            };
+            let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+            let account_id = coda_account_id(
+                &statement.account.number,
+                matches!(statement.account.structure, '2' | '3'),
+            );
+            stmts.push(Statement {
+                id: Some(id),
+                elctrnc_seq_nb: non_empty(&statement.coded_sequence),
+                lgl_seq_nb: non_empty(&statement.paper_sequence),
+                cre_dt_tm: created,
+                fr_to_dt: None,
+                acct: Some(Account {
+                    id: Some(AccountId {
+                        iban: account_id.iban,
+                        other: account_id.other,
+                    }),
+                    ccy: non_empty(&statement.account.currency),
+                    name: non_empty(&statement.holder_name),
+                    svcr: non_empty(&header.bic).map(|bic| {
+                        BranchAndFinancialInstitutionIdentification {
+                            fin_instn_id: Some(FinancialInstitutionIdentification {
+                                bic: Some(bic),
+                                ..Default::default()
+                            }),
+                        }
+                    }),
+                }),
+                bal: vec![
+                    coda_balance("OPBD", &statement.old_balance, currency)?,
+                    coda_balance("CLBD", &statement.new_balance, currency)?,
+                ],
+                ntry: entries,
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts[0].id.clone(),
+                    cre_dt_tm: coda_date(&first.header.creation_date)?,
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// An OFX date-time as `YYYY-MM-DDTHH:MM:SS`, the time zone left out, or
+/// a date alone as `YYYY-MM-DD`.
+fn ofx_date_time(value: &str) -> Option<String> {
+    let date = ofx_date(value)?;
+    let time = value
+        .get(8..14)
+        .and_then(|time| chrono::NaiveTime::parse_from_str(time, "%H%M%S").ok());
+    Some(match time {
+        Some(time) => date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string(),
+        None => date.format("%Y-%m-%d").to_string(),
+    })
+}
+
+fn ofx_day(value: &str) -> Option<DateAndDateTimeChoice> {
+    Some(DateAndDateTimeChoice {
+        dt: Some(ofx_date(value)?.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    })
+}
+
+/// A signed OFX amount.
+fn ofx_amount(value: &str) -> Result<SignedAmount, ParserError> {
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, value),
+    };
+    // `.5` is a valid OFX amount
+    let digits = if digits.starts_with('.') {
+        format!("0{}", digits)
+    } else {
+        digits.to_string()
+    };
+    let amount = SignedAmount::parse(&digits)
+        .ok_or_else(|| ParserError::Converter(format!("Invalid OFX amount '{}'", value)))?;
+    Ok(if negative { amount.negate() } else { amount })
+}
+
+/// Whether an account number is an IBAN: country code, check digits and
+/// an alphanumeric account.
+pub(crate) fn is_iban(id: &str) -> bool {
+    (15..=34).contains(&id.len())
+        && id.bytes().take(2).all(|b| b.is_ascii_uppercase())
+        && id.bytes().skip(2).take(2).all(|b| b.is_ascii_digit())
+        && id.bytes().all(|b| b.is_ascii_alphanumeric())
+}
+
+fn ofx_entry(transaction: &OfxTransaction, currency: &str) -> Result<Entry, ParserError> {
+    let amount = ofx_amount(&transaction.amount)?;
+    let debit = amount.units < 0;
+    let domain = TRN_TYPES
+        .iter()
+        .find(|(trn_type, ..)| *trn_type == transaction.trn_type)
+        .map(
+            |(_, domain, family, sub_family)| BankTransactionCodeStructure {
+                cd: Some(domain.to_string()),
+                fmly: Some(BankTransactionCodeFamily {
+                    cd: Some(family.to_string()),
+                    sub_fmly_cd: Some(sub_family.to_string()),
+                }),
+            },
+        );
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let counterparty = non_empty(&transaction.name).map(|name| Party {
+        name: Some(name),
+        ..Default::default()
+    });
+    let details = TransactionDetails {
+        refs: Some(TransactionReferences {
+            acct_svcr_ref: non_empty(&transaction.fit_id),
+            end_to_end_id: non_empty(&transaction.ref_num),
+            ..Default::default()
+        }),
+        rltd_pties: counterparty.map(|party| match debit {
+            true => RelatedParties {
+                cdtr: Some(party),
+                ..Default::default()
+            },
+            false => RelatedParties {
+                dbtr: Some(party),
+                ..Default::default()
+            },
+        }),
+        rmt_inf: non_empty(&transaction.memo)
+            .map(|memo| RemittanceInformation { ustrd: vec![memo] }),
+        ..Default::default()
+    };
+    Ok(Entry {
+        amt: Some(Amount {
+            currency: Some(currency.to_string()),
+            value: Some(amount.magnitude()),
+        }),
+        cdt_dbt_ind: Some(if debit { "DBIT" } else { "CRDT" }.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt: ofx_day(&transaction.posted),
+        val_dt: ofx_day(&transaction.available),
+        bk_tx_cd: Some(BankTransactionCode {
+            domn: domain,
+            prtry: Some(ProprietaryBankTransactionCode {
+                cd: Some(transaction.trn_type.clone()),
+                issr: Some("OFX".to_string()),
+            }),
+        }),
+        ntry_dtls: vec![EntryDetails {
+            tx_dtls: vec![details],
+        }],
+    })
+}
+
+/// One statement per bank or credit card statement. The ledger balance
+/// becomes the closing balance and the available balance the closing
+/// available balance; OFX has no opening balance, so it is worked out
+/// from the ledger balance and the transactions.
+impl TryFrom<&Ofx> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(ofx: &Ofx) -> Result<Self, Self::Error> {
+        let mut stmts = Vec::new();
+        for statement in &ofx.statements {
+            let currency = statement.currency.as_str();
+            let ntry = statement
+                .transactions
+                .iter()
+                .map(|transaction| ofx_entry(transaction, currency))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut bal = Vec::new();
+            if let Some(ledger) = &statement.ledger_balance {
+                let closing = ofx_amount(&ledger.amount)?;
+                let mut opening = closing;
+                for transaction in &statement.transactions {
+                    opening = opening.add(ofx_amount(&transaction.amount)?.negate());
+                }
+                let opening_date = [&statement.start, &ledger.date]
+                    .into_iter()
+                    .find_map(|date| ofx_day(date));
+                bal.push(new_balance(
+                    "OPBD",
+                    opening,
+                    Some(currency.to_string()),
+                    opening_date,
+                ));
+                bal.push(new_balance(
+                    "CLBD",
+                    closing,
+                    Some(currency.to_string()),
+                    ofx_day(&ledger.date),
+                ));
+            }
+            if let Some(available) = &statement.available_balance {
+                bal.push(new_balance(
+                    "CLAV",
+                    ofx_amount(&available.amount)?,
+                    Some(currency.to_string()),
+                    ofx_day(&available.date),
+                ));
+            }
+
+            let account = &statement.account;
+            let id = match statement.trn_uid.trim_start_matches('0') {
+                "" => format!(
+                    "{}-{}",
+                    account.acct_id,
+                    ofx_date(&statement.end)
+                        .map(|date| date.format("%Y%m%d").to_string())
+                        .unwrap_or_default()
+                ),
+                _ => statement.trn_uid.clone(),
+            };
+            // A period of dates alone runs from the start of the first day to the end of the last
+            let period = |value: &str, time: &str| {
+                ofx_date_time(value).map(|value| match value.len() {
+                    10 => format!("{}T{}", value, time),
+                    _ => value,
+                })
+            };
+            let from = period(&statement.start, "00:00:00");
+            let to = period(&statement.end, "23:59:59");
+            stmts.push(Statement {
+                id: Some(id),
+                elctrnc_seq_nb: Some((stmts.len() + 1).to_string()),
+                cre_dt_tm: ofx_date_time(&ofx.signon.dt_server),
+                fr_to_dt: (from.is_some() || to.is_some()).then_some(FromToDate {
+                    fr_dt_tm: from,
+                    to_dt_tm: to,
+                }),
+                acct: Some(Account {
+                    id: Some(AccountId {
+                        iban: is_iban(&account.acct_id).then(|| account.acct_id.clone()),
+                        other: (!is_iban(&account.acct_id)).then(|| GenericAccountIdentification {
+                            id: Some(account.acct_id.clone()),
+                        }),
+                    }),
+                    ccy: Some(currency.to_string()),
+                    name: None,
+                    svcr: is_bic(&account.bank_id).then(|| {
+                        BranchAndFinancialInstitutionIdentification {
+                            fin_instn_id: Some(FinancialInstitutionIdentification {
+                                bic: Some(account.bank_id.clone()),
+                                ..Default::default()
+                            }),
+                        }
+                    }),
+                }),
+                bal,
+                ntry,
+                ..Default::default()
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts.first().and_then(|stmt| stmt.id.clone()),
+                    cre_dt_tm: ofx_date_time(&ofx.signon.dt_server),
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+fn qif_entry(transaction: &QifTransaction) -> Result<Entry, ParserError> {
+    let amount = ofx_amount(&transaction.amount).map_err(|_| {
+        ParserError::Converter(format!("Invalid QIF amount '{}'", transaction.amount))
+    })?;
+    let debit = amount.units < 0;
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let payee = non_empty(&transaction.payee).map(|name| Party {
+        name: Some(name),
+        ..Default::default()
+    });
+    let date = DateAndDateTimeChoice {
+        dt: Some(transaction.date.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    };
+    let details = TransactionDetails {
+        refs: non_empty(&transaction.number).map(|number| TransactionReferences {
+            acct_svcr_ref: Some(number),
+            ..Default::default()
+        }),
+        rltd_pties: payee.map(|party| match debit {
+            true => RelatedParties {
+                cdtr: Some(party),
+                ..Default::default()
+            },
+            false => RelatedParties {
+                dbtr: Some(party),
+                ..Default::default()
+            },
+        }),
+        rmt_inf: non_empty(&transaction.memo)
+            .map(|memo| RemittanceInformation { ustrd: vec![memo] }),
+        ..Default::default()
+    };
+    Ok(Entry {
+        amt: Some(Amount {
+            currency: None,
+            value: Some(amount.magnitude()),
+        }),
+        cdt_dbt_ind: Some(if debit { "DBIT" } else { "CRDT" }.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt: Some(date),
+        ntry_dtls: vec![EntryDetails {
+            tx_dtls: vec![details],
+        }],
+        ..Default::default()
+    })
+}
+
+/// One statement per account list, covering the dates of its transactions.
+/// QIF has no currency and no balances, so neither is set; the account is
+/// the name of the list's `!Account` block.
+impl TryFrom<&Qif> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(qif: &Qif) -> Result<Self, Self::Error> {
+        let mut stmts = Vec::new();
+        for account in &qif.accounts {
+            let ntry = account
+                .transactions
+                .iter()
+                .map(qif_entry)
+                .collect::<Result<Vec<_>, _>>()?;
+            let dates = account.transactions.iter().map(|t| t.date);
+            let fr_to_dt = dates
+                .clone()
+                .min()
+                .zip(dates.max())
+                .map(|(from, to)| FromToDate {
+                    fr_dt_tm: Some(from.format("%Y-%m-%dT00:00:00").to_string()),
+                    to_dt_tm: Some(to.format("%Y-%m-%dT23:59:59").to_string()),
+                });
+            let name = &account.name;
+            stmts.push(Statement {
+                id: (!name.is_empty()).then(|| name.clone()),
+                elctrnc_seq_nb: Some((stmts.len() + 1).to_string()),
+                fr_to_dt,
+                acct: (!name.is_empty()).then(|| Account {
+                    id: Some(AccountId {
+                        iban: is_iban(name).then(|| name.clone()),
+                        other: (!is_iban(name)).then(|| GenericAccountIdentification {
+                            id: Some(name.clone()),
+                        }),
+                    }),
+                    ..Default::default()
+                }),
+                ntry,
+                ..Default::default()
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts.first().and_then(|stmt| stmt.id.clone()),
+                    ..Default::default()
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// The IBAN of a domestic account number: the country code, the ISO 13616
+/// check digits and the BBAN.
+fn national_iban(country: &str, bban: &str) -> Option<String> {
+    let digits = format!("{}{}00", bban, country)
+        .chars()
+        .map(|c| c.to_digit(36))
+        .collect::<Option<Vec<u32>>>()?;
+    let remainder = digits.iter().fold(0, |remainder, digit| match digit {
+        0..=9 => (remainder * 10 + digit) % 97,
+        _ => (remainder * 100 + digit) % 97,
+    });
+    Some(format!("{}{:02}{}", country, 98 - remainder, bban))
+}
+
+/// A domestic account as IBAN when its BBAN is known, otherwise by number.
+fn domestic_account_id(country: &str, bban: Option<String>, number: &str) -> AccountId {
+    match bban.and_then(|bban| national_iban(country, &bban)) {
+        Some(iban) => AccountId {
+            iban: Some(iban),
+            other: None,
+        },
+        None => AccountId {
+            iban: None,
+            other: Some(GenericAccountIdentification {
+                id: Some(number.to_string()),
+            }),
+        },
+    }
+}
+
+/// A `DDMMYY` or `YYMMDD` date as `YYYY-MM-DD`.
+fn fixed_width_day(date: &str, format: &str) -> Option<DateAndDateTimeChoice> {
+    let date = NaiveDate::parse_from_str(date, format).ok()?;
+    Some(DateAndDateTimeChoice {
+        dt: Some(date.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    })
+}
+
+/// An amount in units of its last decimal.
+fn minor_amount(units: i64, decimals: u32) -> SignedAmount {
+    SignedAmount {
+        units: i128::from(units) * 10i128.pow(5 - decimals.min(5)),
+        decimals,
+    }
+}
+
+/// An entry for a movement; its transaction details, when it has any, are
+/// given by the caller.
+fn fixed_width_entry(
+    amount: SignedAmount,
+    currency: Option<&str>,
+    dates: (Option<DateAndDateTimeChoice>, Option<DateAndDateTimeChoice>),
+    bk_tx_cd: BankTransactionCode,
+    details: TransactionDetails,
+) -> Entry {
+    let has_details = details.refs.is_some()
+        || details.rltd_pties.is_some()
+        || details.rltd_agts.is_some()
+        || details.rmt_inf.is_some();
+    let (bookg_dt, val_dt) = dates;
+    Entry {
+        amt: Some(Amount {
+            currency: currency.map(str::to_string),
+            value: Some(amount.magnitude()),
+        }),
+        cdt_dbt_ind: Some(if amount.units < 0 { "DBIT" } else { "CRDT" }.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt,
+        val_dt,
+        bk_tx_cd: Some(bk_tx_cd),
+        ntry_dtls: if has_details {
+            vec![EntryDetails {
+                tx_dtls: vec![details],
+            }]
+        } else {
+            vec![]
+        },
+    }
+}
+
+fn cfonb_entry(movement: &CfonbMovement, account: &CfonbAccount) -> Entry {
+    let complement = |qualifier: &str| {
+        movement
+            .complements
+            .iter()
+            .find(|c| c.qualifier == qualifier && !c.text.is_empty())
+            .map(|c| c.text.clone())
+    };
+    let party = |qualifier: &str| {
+        complement(qualifier).map(|name| Party {
+            name: Some(name),
+            ..Default::default()
+        })
+    };
+    let (dbtr, cdtr) = (party("NPY"), party("NBE"));
+    let refs = TransactionReferences {
+        acct_svcr_ref: (!movement.reference.is_empty()).then(|| movement.reference.clone()),
+        end_to_end_id: complement("RCN"),
+        ..Default::default()
+    };
+    let has_refs = refs.acct_svcr_ref.is_some() || refs.end_to_end_id.is_some();
+    // The label, then the texts of the complements not mapped above
+    let ustrd: Vec<String> = std::iter::once(&movement.label)
+        .chain(
+            movement
+                .complements
+                .iter()
+                .filter(|c| !matches!(c.qualifier.as_str(), "NPY" | "NBE" | "RCN"))
+                .map(|c| &c.text),
+        )
+        .filter(|text| !text.is_empty())
+        .cloned()
+        .collect();
+    let details = TransactionDetails {
+        refs: has_refs.then_some(refs),
+        rltd_pties: (dbtr.is_some() || cdtr.is_some()).then(|| RelatedParties {
+            dbtr,
+            cdtr,
+            ..Default::default()
+        }),
+        rmt_inf: (!ustrd.is_empty()).then_some(RemittanceInformation { ustrd }),
+        ..Default::default()
+    };
+    fixed_width_entry(
+        minor_amount(movement.amount, account.decimals),
+        Some(&account.currency),
+        (
+            fixed_width_day(&movement.booking_date, "%d%m%y"),
+            fixed_width_day(&movement.value_date, "%d%m%y"),
+        ),
+        BankTransactionCode {
+            domn: None,
+            prtry: (!movement.operation_code.is_empty()).then(|| ProprietaryBankTransactionCode {
+                cd: Some(movement.operation_code.clone()),
+                issr: Some("CFONB".to_string()),
+            }),
+        },
+        details,
+    )
+}
+
+/// One statement per old balance to new balance sequence, with those
+/// balances as opening and closing balances. French accounts are
+/// identified by IBAN, the RIB key worked out from the account number.
+///
+/// The interbank operation code is kept as a CFONB proprietary code; the
+/// `NPY` and `NBE` complements name the debtor and creditor, `RCN` is the
+/// end-to-end id, and the label and other complements are remittance lines.
+impl TryFrom<&Cfonb> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(cfonb: &Cfonb) -> Result<Self, Self::Error> {
+        let mut stmts = Vec::new();
+        for (i, statement) in cfonb.statements.iter().enumerate() {
+            let account = &statement.account;
+            let currency = Some(account.currency.clone()).filter(|c| !c.is_empty());
+            let balance = |code: &str, balance: &CfonbBalance| {
+                new_balance(
+                    code,
+                    minor_amount(balance.amount, account.decimals),
+                    currency.clone(),
+                    fixed_width_day(&balance.date, "%d%m%y"),
+                )
+            };
+            let closing = fixed_width_day(&statement.new_balance.date, "%d%m%y")
+                .and_then(|day| day.dt)
+                .unwrap_or_default();
+            stmts.push(Statement {
+                id: Some(format!("{}-{}", account.number, closing.replace('-', ""))),
+                elctrnc_seq_nb: Some((i + 1).to_string()),
+                acct: Some(Account {
+                    id: Some(domestic_account_id("FR", account.bban(), &account.number)),
+                    ccy: currency.clone(),
+                    ..Default::default()
+                }),
+                bal: vec![
+                    balance("OPBD", &statement.old_balance),
+                    balance("CLBD", &statement.new_balance),
+                ],
+                ntry: statement
+                    .movements
+                    .iter()
+                    .map(|movement| cfonb_entry(movement, account))
+                    .collect(),
+                ..Default::default()
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts.first().and_then(|stmt| stmt.id.clone()),
+                    ..Default::default()
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// The ISO code of a common concept.
+fn norma43_domain(common_concept: &str, debit: bool) -> Option<BankTransactionCodeStructure> {
+    let (_, codes) = COMMON_CONCEPTS
+        .iter()
+        .find(|(concept, _)| *concept == common_concept)?;
+    let (domain, family, sub_family) = codes[usize::from(debit)];
+    Some(BankTransactionCodeStructure {
+        cd: Some(domain.to_string()),
+        fmly: Some(BankTransactionCodeFamily {
+            cd: Some(family.to_string()),
+            sub_fmly_cd: Some(sub_family.to_string()),
+        }),
+    })
+}
+
+fn norma43_entry(movement: &Norma43Movement, currency: &str) -> Entry {
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    let refs = TransactionReferences {
+        acct_svcr_ref: non_empty(&movement.document_number),
+        end_to_end_id: non_empty(&movement.reference_1),
+        instr_id: non_empty(&movement.reference_2),
+        ..Default::default()
+    };
+    let has_refs =
+        refs.acct_svcr_ref.is_some() || refs.end_to_end_id.is_some() || refs.instr_id.is_some();
+    let ustrd: Vec<String> = movement
+        .concepts
+        .iter()
+        .filter(|text| !text.is_empty())
+        .cloned()
+        .collect();
+    let details = TransactionDetails {
+        refs: has_refs.then_some(refs),
+        rmt_inf: (!ustrd.is_empty()).then_some(RemittanceInformation { ustrd }),
+        ..Default::default()
+    };
+    fixed_width_entry(
+        minor_amount(movement.amount, 2),
+        Some(currency),
+        (
+            fixed_width_day(&movement.operation_date, "%y%m%d"),
+            fixed_width_day(&movement.value_date, "%y%m%d"),
+        ),
+        BankTransactionCode {
+            domn: norma43_domain(&movement.common_concept, movement.amount < 0),
+            prtry: Some(ProprietaryBankTransactionCode {
+                cd: Some(format!(
+                    "{}{}",
+                    movement.common_concept, movement.own_concept
+                )),
+                issr: Some("AEB".to_string()),
+            }),
+        },
+        details,
+    )
+}
+
+/// One statement per account, covering its start to end date, with the
+/// initial and final balances as opening and closing balances. Spanish
+/// accounts are identified by IBAN, the CCC check digits worked out from
+/// the entity, office and number.
+///
+/// Common and own concepts are kept as an AEB proprietary code, and common
+/// concepts are mapped to ISO bank transaction codes. The document number
+/// becomes the bank reference, the two references the end-to-end and
+/// instruction ids, and the concept records the remittance lines.
+impl TryFrom<&Norma43> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(norma43: &Norma43) -> Result<Self, Self::Error> {
+        let mut stmts = Vec::new();
+        for (i, account) in norma43.accounts.iter().enumerate() {
+            let currency = CURRENCIES
+                .iter()
+                .find(|(numeric, _)| *numeric == account.currency)
+                .map(|(_, alpha)| alpha.to_string())
+                .ok_or_else(|| {
+                    ParserError::Converter(format!(
+                        "Unknown Norma 43 currency code '{}'",
+                        account.currency
+                    ))
+                })?;
+            let start = fixed_width_day(&account.start_date, "%y%m%d").and_then(|day| day.dt);
+            let end = fixed_width_day(&account.end_date, "%y%m%d").and_then(|day| day.dt);
+            let balance = |code: &str, amount: i64, date: &Option<String>| {
+                new_balance(
+                    code,
+                    minor_amount(amount, 2),
+                    Some(currency.clone()),
+                    date.clone().map(|dt| DateAndDateTimeChoice {
+                        dt: Some(dt),
+                        dt_tm: None,
+                    }),
+                )
+            };
+            stmts.push(Statement {
+                id: Some(format!(
+                    "{}-{}",
+                    account.number,
+                    end.as_deref().unwrap_or_default().replace('-', "")
+                )),
+                elctrnc_seq_nb: Some((i + 1).to_string()),
+                fr_to_dt: Some(FromToDate {
+                    fr_dt_tm: start.as_ref().map(|dt| format!("{}T00:00:00", dt)),
+                    to_dt_tm: end.as_ref().map(|dt| format!("{}T23:59:59", dt)),
+                }),
+                acct: Some(Account {
+                    id: Some(domestic_account_id("ES", account.ccc(), &account.number)),
+                    ccy: Some(currency.clone()),
+                    name: (!account.name.is_empty()).then(|| account.name.clone()),
+                    ..Default::default()
+                }),
+                bal: vec![
+                    balance("OPBD", account.initial_balance, &start),
+                    balance("CLBD", account.final_balance, &end),
+                ],
+                ntry: account
+                    .movements
+                    .iter()
+                    .map(|movement| norma43_entry(movement, &currency))
+                    .collect(),
+                ..Default::default()
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts.first().and_then(|stmt| stmt.id.clone()),
+                    ..Default::default()
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// A 1C amount, which may be negative for balances.
+fn client_bank_amount(value: &str) -> Result<SignedAmount, ParserError> {
+    let amount = SignedAmount::parse(value.trim_start_matches('-'))
+        .ok_or_else(|| ParserError::Converter(format!("Invalid 1C amount '{}'", value)))?;
+    Ok(if value.starts_with('-') {
+        amount.negate()
+    } else {
+        amount
+    })
+}
+
+fn client_bank_day(date: NaiveDate) -> DateAndDateTimeChoice {
+    DateAndDateTimeChoice {
+        dt: Some(date.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    }
+}
+
+/// The name of a payer or payee, with the ИНН as tax identification and
+/// the КПП under the proprietary scheme `KPP`.
+fn client_bank_party(participant: &Participant) -> Option<Party> {
+    let name = participant.display_name();
+    let ids: Vec<GenericOrganisationIdentification> = [
+        (&participant.inn, ("TXID", None)),
+        (&participant.kpp, ("", Some("KPP"))),
+    ]
+    .into_iter()
+    .filter(|(id, _)| !id.is_empty() && id.as_str() != "0")
+    .map(|(id, (cd, prtry))| GenericOrganisationIdentification {
+        id: Some(id.clone()),
+        schme_nm: Some(OrganisationIdentificationSchemeName {
+            cd: (!cd.is_empty()).then(|| cd.to_string()),
+            prtry: prtry.map(str::to_string),
+        }),
+    })
+    .collect();
+    if name.is_empty() && ids.is_empty() {
+        return None;
+    }
+    Some(Party {
+        name: (!name.is_empty()).then(|| name.to_string()),
+        id: (!ids.is_empty()).then_some(PartyIdentification {
+            org_id: Some(OrganisationIdentification { othr: ids }),
+        }),
+    })
+}
+
+/// The bank of a payer or payee, by its БИК as a member of the Bank of
+/// Russia clearing system.
+fn client_bank_agent(
+    participant: &Participant,
+) -> Option<BranchAndFinancialInstitutionIdentification> {
+    if participant.bik.is_empty() {
+        return None;
+    }
+    Some(BranchAndFinancialInstitutionIdentification {
+        fin_instn_id: Some(FinancialInstitutionIdentification {
+            bic: None,
+            clr_sys_mmb_id: Some(ClearingSystemMemberIdentification {
+                clr_sys_id: Some(ClearingSystemIdentification {
+                    cd: Some("RUCBC".to_string()),
+                }),
+                mmb_id: Some(participant.bik.clone()),
+            }),
+            name: (!participant.bank.is_empty()).then(|| participant.bank.clone()),
+        }),
+    })
+}
+
+/// The entry of a document on the payer's account, a debit, or on the
+/// payee's, a credit.
+fn client_bank_entry(
+    document: &PaymentDocument,
+    debit: bool,
+    currency: Option<&str>,
+) -> Result<Entry, ParserError> {
+    let amount = client_bank_amount(&document.amount)?;
+    let amount = if debit { amount.negate() } else { amount };
+    let booked = match debit {
+        true => document.written_off_date,
+        false => document.received_date,
+    }
+    .unwrap_or(document.date);
+    let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+    // `Код` is `0` when the payment has no identifier
+    let refs = TransactionReferences {
+        instr_id: non_empty(&document.number),
+        end_to_end_id: non_empty(&document.code).filter(|code| code != "0"),
+        ..Default::default()
+    };
+    let has_refs = refs.instr_id.is_some() || refs.end_to_end_id.is_some();
+    let account = |participant: &Participant| {
+        non_empty(&participant.account).map(|id| AccountIdentification {
+            iban: None,
+            other: Some(GenericAccountIdentification { id: Some(id) }),
+        })
+    };
+    let (payer, payee) = (&document.payer, &document.payee);
+    let agents = RelatedAgents {
+        dbtr_agt: client_bank_agent(payer),
+        cdtr_agt: client_bank_agent(payee),
+    };
+    let details = TransactionDetails {
+        refs: has_refs.then_some(refs),
+        rltd_pties: Some(RelatedParties {
+            dbtr: client_bank_party(payer),
+            cdtr: client_bank_party(payee),
+            dbtr_acct: account(payer),
+            cdtr_acct: account(payee),
+        }),
+        rltd_agts: (agents.dbtr_agt.is_some() || agents.cdtr_agt.is_some()).then_some(agents),
+        rmt_inf: non_empty(&document.purpose).map(|purpose| RemittanceInformation {
+            ustrd: vec![purpose],
+        }),
+        ..Default::default()
+    };
+    // A payment order is a domestic credit transfer
+    let domain = (document.payment_code == "01").then(|| BankTransactionCodeStructure {
+        cd: Some("PMNT".to_string()),
+        fmly: Some(BankTransactionCodeFamily {
+            cd: Some(if debit { "ICDT" } else { "RCDT" }.to_string()),
+            sub_fmly_cd: Some("DMCT".to_string()),
+        }),
+    });
+    Ok(fixed_width_entry(
+        amount,
+        currency,
+        (Some(client_bank_day(booked)), None),
+        BankTransactionCode {
+            domn: domain,
+            prtry: non_empty(&document.payment_code).map(|code| ProprietaryBankTransactionCode {
+                cd: Some(code),
+                issr: Some("CBR".to_string()),
+            }),
+        },
+        details,
+    ))
+}
+
+/// A statement of one account of a 1C file: its balances, when it has an
+/// account section, and the documents it paid or received between `start`
+/// and `end`.
+struct ClientBankStatement<'a> {
+    account: &'a str,
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    section: Option<&'a AccountSection>,
+    /// The documents and whether each is a debit.
+    documents: Vec<(&'a PaymentDocument, bool)>,
+}
+
+impl<'a> ClientBankStatement<'a> {
+    /// Takes `document` when it is paid from or to the account in the period.
+    fn take(&mut self, document: &'a PaymentDocument) -> bool {
+        let side = |participant: &Participant, date: Option<NaiveDate>| {
+            let date = date.unwrap_or(document.date);
+            participant.account == self.account
+                && self.start.is_none_or(|start| date >= start)
+                && self.end.is_none_or(|end| date <= end)
+        };
+        let debit = side(&document.payer, document.written_off_date);
+        if debit || side(&document.payee, document.received_date) {
+            self.documents.push((document, debit));
+            return true;
+        }
+        false
+    }
+}
+
+/// One statement per account section, with its opening and closing
+/// balances, holding the documents paid from or to the account in the
+/// section's period. Documents outside every section are put in a
+/// statement without balances for each account of the file header.
+///
+/// The document number becomes the instruction id, `Код` the end-to-end id
+/// and `НазначениеПлатежа` the remittance information. Payers and payees
+/// keep their ИНН and КПП as organisation identifications, their account
+/// numbers and their banks' БИК as Bank of Russia clearing codes; the
+/// `ВидОплаты` code is kept as a `CBR` proprietary code. Accounts are
+/// identified by number, the currency taken from its digits 6 to 8.
+impl TryFrom<&ClientBankExchange> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(file: &ClientBankExchange) -> Result<Self, Self::Error> {
+        let mut statements: Vec<ClientBankStatement> = file
+            .sections
+            .iter()
+            .map(|section| ClientBankStatement {
+                account: &section.account,
+                start: Some(section.start_date),
+                end: Some(section.end_date),
+                section: Some(section),
+                documents: Vec::new(),
+            })
+            .collect();
+        let sections = statements.len();
+        for document in &file.documents {
+            if statements[..sections]
+                .iter_mut()
+                .any(|statement| statement.take(document))
+            {
+                continue;
+            }
+            let placed = file.accounts.iter().any(|account| {
+                let statement = match statements[sections..]
+                    .iter_mut()
+                    .find(|statement| statement.account == account)
+                {
+                    Some(statement) => statement,
+                    None => {
+                        statements.push(ClientBankStatement {
+                            account,
+                            start: None,
+                            end: None,
+                            section: None,
+                            documents: Vec::new(),
+                        });
+                        statements.last_mut().unwrap()
+                    }
+                };
+                statement.take(document)
+            });
+            if !placed {
+                return Err(ParserError::Converter(format!(
+                    "Document {} of {} is not paid from or to an account of the file",
+                    document.number,
+                    document.date.format("%d.%m.%Y")
+                )));
+            }
+        }
+        statements
+            .retain(|statement| statement.section.is_some() || !statement.documents.is_empty());
+
+        let mut stmts = Vec::new();
+        for (i, statement) in statements.iter().enumerate() {
+            let currency = account_currency(statement.account);
+            let start = statement.start.or(file.start_date);
+            let end = statement.end.or(file.end_date);
+            let mut bal = Vec::new();
+            if let Some(section) = statement.section {
+                for (code, value, date) in [
+                    ("OPBD", &section.opening_balance, section.start_date),
+                    ("CLBD", &section.closing_balance, section.end_date),
+                ] {
+                    if !value.is_empty() {
+                        bal.push(new_balance(
+                            code,
+                            client_bank_amount(value)?,
+                            currency.map(str::to_string),
+                            Some(client_bank_day(date)),
+                        ));
+                    }
+                }
+            }
+            // The account holder and its bank, from the first document
+            let holder = statement
+                .documents
+                .first()
+                .map(|(document, debit)| match debit {
+                    true => &document.payer,
+                    false => &document.payee,
+                });
+            let ntry = statement
+                .documents
+                .iter()
+                .map(|(document, debit)| client_bank_entry(document, *debit, currency))
+                .collect::<Result<Vec<_>, _>>()?;
+            // Short enough for the 16 characters of an MT940 reference
+            let id = match end {
+                Some(end) => format!("{}-{}", end.format("%Y%m%d"), i + 1),
+                None => (i + 1).to_string(),
+            };
+            stmts.push(Statement {
+                id: Some(id),
+                elctrnc_seq_nb: Some((i + 1).to_string()),
+                cre_dt_tm: file.creation_date.map(|date| {
+                    let time = Some(file.creation_time.as_str()).filter(|time| time.len() == 8);
+                    format!("{}T{}", date.format("%Y-%m-%d"), time.unwrap_or("00:00:00"))
+                }),
+                fr_to_dt: (start.is_some() || end.is_some()).then(|| FromToDate {
+                    fr_dt_tm: start.map(|start| start.format("%Y-%m-%dT00:00:00").to_string()),
+                    to_dt_tm: end.map(|end| end.format("%Y-%m-%dT23:59:59").to_string()),
+                }),
+                acct: Some(Account {
+                    id: Some(AccountId {
+                        iban: None,
+                        other: Some(GenericAccountIdentification {
+                            id: Some(statement.account.to_string()),
+                        }),
+                    }),
+                    ccy: currency.map(str::to_string),
+                    name: holder
+                        .map(|holder| holder.display_name().to_string())
+                        .filter(|name| !name.is_empty()),
+                    svcr: holder.and_then(client_bank_agent),
+                }),
+                bal,
+                ntry,
+                ..Default::default()
+            });
+        }
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: stmts.first().and_then(|stmt| stmt.id.clone()),
+                    cre_dt_tm: stmts.first().and_then(|stmt| stmt.cre_dt_tm.clone()),
+                },
+                stmts,
+            },
+        })
+    }
+}
+
+/// The date of an MMDD entry date, in the year of the value date or the
+/// one next to it when the booking crosses the turn of the year.
+fn mt950_entry_date(value_date: NaiveDate, entry_date: &str) -> Option<NaiveDate> {
+    use chrono::Datelike;
+    let month: u32 = entry_date.get(..2)?.parse().ok()?;
+    let day: u32 = entry_date.get(2..4)?.parse().ok()?;
+    let year = match (value_date.month(), month) {
+        (12, 1) => value_date.year() + 1,
+        (1, 12) => value_date.year() - 1,
+        _ => value_date.year(),
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// One `:61:` line of an MT950. Without `:86:` the references and the
+/// supplementary details are all there is to keep: the customer reference
+/// becomes the end-to-end id, the bank reference the account servicer
+/// reference and the supplementary details the remittance information. The
+/// UETR of the message becomes the UETR reference.
+fn mt950_entry(line: &Field61, currency: &str, uetr: Option<&str>) -> Result<Entry, ParserError> {
+    // A reversal of a credit (RC) is booked as a debit, and vice versa
+    let cdt_dbt = match line.debit_credit_mark.as_str() {
+        "C" | "RD" => "CRDT",
+        "D" | "RC" => "DBIT",
+        other => {
+            return Err(ParserError::Converter(format!(
+                "Invalid debit/credit mark: '{}'",
+                other
+            )));
+        }
+    };
+    let day = |date: NaiveDate| DateAndDateTimeChoice {
+        dt: Some(date.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    };
+    let booking_date = match line.entry_date.as_deref() {
+        Some(entry_date) => mt950_entry_date(line.value_date, entry_date).ok_or_else(|| {
+            ParserError::Converter(format!("Invalid entry date '{}'", entry_date))
+        })?,
+        None => line.value_date,
+    };
+    let non_empty = |value: &str| (!value.is_empty()).then(|| value.to_string());
+    let details = TransactionDetails {
+        refs: Some(TransactionReferences {
+            acct_svcr_ref: line.bank_reference.as_deref().and_then(non_empty),
+            end_to_end_id: Some(line.customer_reference.as_str())
+                .filter(|reference| *reference != "NONREF")
+                .and_then(non_empty),
+            uetr: uetr.map(str::to_string),
+            ..Default::default()
+        }),
+        rmt_inf: line
+            .supplementary_details
+            .as_deref()
+            .and_then(non_empty)
+            .map(|details| RemittanceInformation {
+                ustrd: vec![details],
+            }),
+        ..Default::default()
+    };
+    Ok(Entry {
+        amt: Some(Amount {
+            currency: Some(currency.to_string()),
+            value: Some(format!("{:.2}", line.amount)),
+        }),
+        cdt_dbt_ind: Some(cdt_dbt.to_string()),
+        sts: Some("BOOK".to_string()),
+        bookg_dt: Some(day(booking_date)),
+        val_dt: Some(day(line.value_date)),
+        bk_tx_cd: non_empty(&line.transaction_type).map(|code| BankTransactionCode {
+            prtry: Some(ProprietaryBankTransactionCode {
+                cd: Some(code),
+                issr: Some("SWIFT".to_string()),
+            }),
+            ..Default::default()
+        }),
+        ntry_dtls: vec![EntryDetails {
+            tx_dtls: vec![details],
+        }],
+    })
+}
+
+impl TryFrom<&Mt950> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(mt950: &Mt950) -> Result<Self, Self::Error> {
+        mt950_to_camt053(mt950, &mut Recovery::default())
+    }
+}
+
+/// Converts one MT950 message to a statement identified by `:20:`, in the
+/// currency of the opening balance. Final balances become `OPBD` and
+/// `CLBD`, intermediate ones (`:60M:`, `:62M:`) `ITBD`. In lenient mode
+/// statement lines that cannot be mapped are left out with a warning.
+pub(crate) fn mt950_to_camt053(
+    mt950: &Mt950,
+    recovery: &mut Recovery,
+) -> Result<Camt053, ParserError> {
+    let stmt = &mt950.statement;
+    let reference = stmt.field_20.reference.clone();
+    let grp_hdr = GroupHeader {
+        msg_id: Some(reference.clone()),
+        ..Default::default()
+    };
+
+    let (opening, opening_code) = match &stmt.field_60 {
+        Field60::F(field) => (field.to_swift_string(), "OPBD"),
+        Field60::M(field) => (field.to_swift_string(), "ITBD"),
+    };
+    let (closing, closing_code) = match &stmt.field_62 {
+        Field62::F(field) => (field.to_swift_string(), "CLBD"),
+        Field62::M(field) => (field.to_swift_string(), "ITBD"),
+    };
+    let mut balances = vec![
+        parse_balance(&opening, opening_code)?,
+        parse_balance(&closing, closing_code)?,
+    ];
+    if let Some(field_64) = &stmt.field_64 {
+        balances.push(parse_balance(&field_64.to_swift_string(), "CLAV")?);
+    }
+    let currency = balances[0]
+        .amt
+        .as_ref()
+        .and_then(|amount| amount.currency.clone())
+        .unwrap_or_default();
+
+    let account = stmt.field_25.authorisation.clone();
+    let acct = Account {
+        id: Some(if is_iban(&account) {
+            AccountId {
+                iban: Some(account),
+                other: None,
+            }
+        } else {
+            AccountId {
+                iban: None,
+                other: Some(GenericAccountIdentification { id: Some(account) }),
+            }
+        }),
+        ccy: Some(currency.clone()),
+        svcr: mt950
+            .sender_bic()
+            .map(|bic| BranchAndFinancialInstitutionIdentification {
+                fin_instn_id: Some(FinancialInstitutionIdentification {
+                    bic: Some(bic),
+                    ..Default::default()
+                }),
+            }),
+        ..Default::default()
+    };
+
+    let uetr = mt950
+        .user_header
+        .as_ref()
+        .and_then(|header| header.uetr.as_deref());
+    let mut entries = Vec::new();
+    for (index, line) in stmt.field_61.iter().flatten().enumerate() {
+        match mt950_entry(line, &currency, uetr) {
+            Ok(entry) => entries.push(entry),
+            Err(ParserError::Converter(message)) => {
+                let error = ParserError::Converter(format!(
+                    "statement line {} of message {}: {}",
+                    index + 1,
+                    reference,
+                    message
+                ));
+                recovery.recover(WarningKind::SkippedEntry, error)?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let statement = Statement {
+        id: Some(reference),
+        acct: Some(acct),
+        elctrnc_seq_nb: Some(
+            stmt.field_28c
+                .to_swift_string()
+                .trim_start_matches(":28C:")
+                .to_string(),
+        ),
+        bal: balances,
+        ntry: entries,
+        ..Default::default()
+    };
+    Ok(Camt053 {
+        bk_to_cstmr_stmt: BankToCustomerStatement {
+            grp_hdr,
+            stmts: vec![statement],
+        },
+    })
+}
+
+/// One statement without balances per notification, with its id,
+/// account and entries.
+impl TryFrom<&Camt054> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(camt054: &Camt054) -> Result<Self, Self::Error> {
+        let notification = &camt054.bk_to_cstmr_dbt_cdt_ntfctn;
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: notification.grp_hdr.clone(),
+                stmts: notification
+                    .ntfctns
+                    .iter()
+                    .map(|ntfctn| Statement {
+                        id: ntfctn.id.clone(),
+                        elctrnc_seq_nb: ntfctn.elctrnc_seq_nb.clone(),
+                        cre_dt_tm: ntfctn.cre_dt_tm.clone(),
+                        acct: ntfctn.acct.clone(),
+                        ntry: ntfctn.ntry.clone(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            },
+        })
+    }
+}
+
+fn pain001_party(party: &Pain001Party) -> Party {
+    Party {
+        name: party.name.clone(),
+        id: None,
+    }
+}
+
+fn pain001_account_id(account: &Pain001Account) -> Option<AccountId> {
+    let id = account.id.as_ref()?;
+    Some(AccountId {
+        iban: id.iban.clone(),
+        other: id.other.as_ref().map(|other| GenericAccountIdentification {
+            id: other.id.clone(),
+        }),
+    })
+}
+
+fn pain001_agent(agent: &Pain001Agent) -> BranchAndFinancialInstitutionIdentification {
+    BranchAndFinancialInstitutionIdentification {
+        fin_instn_id: agent
+            .fin_instn_id
+            .as_ref()
+            .map(|id| FinancialInstitutionIdentification {
+                bic: id.bic.clone(),
+                ..Default::default()
+            }),
+    }
+}
+
+/// A credit transfer as a pending debit of the debtor account, valued on
+/// the requested execution date, with the ISO code of an issued SEPA
+/// credit transfer.
+fn pain001_entry(
+    msg_id: Option<&String>,
+    block: &PaymentInformation,
+    transaction: &CreditTransferTransaction,
+) -> Entry {
+    let pmt_id = transaction.pmt_id.as_ref();
+    let account_identification = |account: &Pain001Account| {
+        let id = pain001_account_id(account)?;
+        Some(AccountIdentification {
+            iban: id.iban,
+            other: id.other,
+        })
+    };
+    Entry {
+        amt: transaction
+            .amt
+            .as_ref()
+            .and_then(|amount| amount.instd_amt.as_ref())
+            .map(|amount| Amount {
+                currency: amount.currency.clone(),
+                value: amount.value.clone(),
+            }),
+        cdt_dbt_ind: Some("DBIT".to_string()),
+        sts: Some("PDNG".to_string()),
+        val_dt: block
+            .reqd_exctn_dt
+            .as_ref()
+            .and_then(|date| date.value())
+            .map(|date| DateAndDateTimeChoice {
+                dt: Some(date.to_string()),
+                dt_tm: None,
+            }),
+        bk_tx_cd: Some(BankTransactionCode {
+            domn: Some(BankTransactionCodeStructure {
+                cd: Some("PMNT".to_string()),
+                fmly: Some(BankTransactionCodeFamily {
+                    cd: Some("ICDT".to_string()),
+                    sub_fmly_cd: Some("ESCT".to_string()),
+                }),
+            }),
+            ..Default::default()
+        }),
+        ntry_dtls: vec![EntryDetails {
+            tx_dtls: vec![TransactionDetails {
+                refs: Some(TransactionReferences {
+                    msg_id: msg_id.cloned(),
+                    pmt_inf_id: block.pmt_inf_id.clone(),
+                    instr_id: pmt_id.and_then(|id| id.instr_id.clone()),
+                    end_to_end_id: pmt_id.and_then(|id| id.end_to_end_id.clone()),
+                    ..Default::default()
+                }),
+                amt: None,
+                rltd_pties: Some(RelatedParties {
+                    dbtr: block.dbtr.as_ref().map(pain001_party),
+                    cdtr: transaction.cdtr.as_ref().map(pain001_party),
+                    dbtr_acct: block.dbtr_acct.as_ref().and_then(account_identification),
+                    cdtr_acct: transaction
+                        .cdtr_acct
+                        .as_ref()
+                        .and_then(account_identification),
+                }),
+                rltd_agts: Some(RelatedAgents {
+                    dbtr_agt: block.dbtr_agt.as_ref().map(pain001_agent),
+                    cdtr_agt: transaction.cdtr_agt.as_ref().map(pain001_agent),
+                }),
+                rmt_inf: transaction
+                    .rmt_inf
+                    .as_ref()
+                    .map(|rmt| RemittanceInformation {
+                        ustrd: rmt.ustrd.clone(),
+                    }),
+            }],
+        }],
+        ..Default::default()
+    }
+}
+
+/// One statement without balances per payment information block,
+/// identified by the block id, for the debtor account named after the
+/// debtor and serviced by the debtor agent.
+impl TryFrom<&Pain001> for Camt053 {
+    type Error = ParserError;
+
+    fn try_from(pain001: &Pain001) -> Result<Self, Self::Error> {
+        let initiation = &pain001.cstmr_cdt_trf_initn;
+        let msg_id = initiation.grp_hdr.msg_id.as_ref();
+        Ok(Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: msg_id.cloned(),
+                    cre_dt_tm: initiation.grp_hdr.cre_dt_tm.clone(),
+                },
+                stmts: initiation
+                    .pmt_inf
+                    .iter()
+                    .map(|block| Statement {
+                        id: block.pmt_inf_id.clone(),
+                        acct: block.dbtr_acct.as_ref().map(|account| Account {
+                            id: pain001_account_id(account),
+                            ccy: account.ccy.clone(),
+                            name: block.dbtr.as_ref().and_then(|dbtr| dbtr.name.clone()),
+                            svcr: block.dbtr_agt.as_ref().map(pain001_agent),
+                        }),
+                        ntry: block
+                            .cdt_trf_tx_inf
+                            .iter()
+                            .map(|transaction| pain001_entry(msg_id, block, transaction))
+                            .collect(),
+                        ..Default::default()
+                    })
+                    .collect(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camt053::balance::balance_code;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_mt940_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file_path = path.join("simple.mt940");
+        let target_file = File::open(target_file_path).unwrap();
+        let mt940_valid = Mt940::from_read(target_file).unwrap();
+
+        let result: Result<Camt053, ParserError> = (&mt940_valid).try_into();
+        let result = result.unwrap();
+
+        // GroupHeader
+        assert_eq!(
+            result.bk_to_cstmr_stmt.grp_hdr.msg_id,
+            Some("STAT202510210001".to_string()) // :20:STAT202510210001
+        );
+
+        // Statement
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.elctrnc_seq_nb, Some("1/1".to_string())); // :28C:00001/001
+
+        // Account
+        let acct = stmt.acct.as_ref().unwrap();
+        let acct_id = acct.id.as_ref().unwrap();
+        assert_eq!(acct_id.iban, Some("NL91ABNA0417164300".to_string())); // :25:NL91ABNA0417164300
+        assert_eq!(acct.ccy, Some("EUR".to_string()));
+
+        // Balances
+        let bal1 = &stmt.bal[0]; // :60F:C251020EUR10000,00
+        assert_eq!(
+            bal1.tp.as_ref().unwrap().cd_or_prtry.as_ref().unwrap().cd,
+            Some("OPBD".to_string())
+        );
+        assert_eq!(bal1.amt.as_ref().unwrap().currency, Some("EUR".to_string()));
+        assert_eq!(
+            bal1.amt.as_ref().unwrap().value,
+            Some("10000,00".to_string())
+        );
+        assert_eq!(bal1.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(bal1.dt.as_ref().unwrap().dt, Some("2025-10-20".to_string()));
+
+        let bal2 = &stmt.bal[1]; // :62F:C251021EUR11239,50
+        assert_eq!(
+            bal2.tp.as_ref().unwrap().cd_or_prtry.as_ref().unwrap().cd,
+            Some("CLBD".to_string())
+        );
+        assert_eq!(bal2.amt.as_ref().unwrap().currency, Some("EUR".to_string()));
+        assert_eq!(
+            bal2.amt.as_ref().unwrap().value,
+            Some("11239,50".to_string())
+        );
+        assert_eq!(bal2.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(bal2.dt.as_ref().unwrap().dt, Some("2025-10-21".to_string()));
+
+        // Entry
+        let entry = &stmt.ntry[0]; // :61:2510211021D250,00NTRFNONREF//BKNTRX0001
+        assert_eq!(
+            entry.amt.as_ref().unwrap().currency,
+            Some("EUR".to_string())
+        );
+        assert_eq!(
+            entry.amt.as_ref().unwrap().value,
+            Some("250.00".to_string())
+        );
+        assert_eq!(entry.cdt_dbt_ind, Some("DBIT".to_string()));
+
+        // TransactionDetails
+        let tx_details = &entry.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            tx_details.amt.as_ref().unwrap().currency,
+            Some("EUR".to_string())
+        );
+        assert_eq!(
+            tx_details.amt.as_ref().unwrap().value,
+            Some("250.00".to_string())
+        );
+        assert_eq!(
+            tx_details.rmt_inf.as_ref().unwrap().ustrd[0],
+            "Payment to supplier Roga i Kopyta Inv 1001".to_string() // :86:Payment to supplier Roga i Kopyta Inv 1001
+        );
+    }
+
+    #[test]
+    fn test_convert_mt940_headers_to_camt053() {
+        let data = "{1:F01BANKNL2AXXXX0000000000}{2:O9400100230920DEUTDEFFAXXX00000000002309201000N}\
+            {3:{121:e0b5b8c6-3a4b-4c4e-9d5f-6a7b8c9d0e1f}}{4:\n:20:REF\n:25:DE89370400440532013000\n\
+            :28C:1/1\n:60F:C230920EUR0,00\n:61:2309200920C5,00NTRFNONREF\n:62F:C230920EUR5,00\n-}";
+        let mt940 = Mt940::from_string(data).unwrap();
+
+        let result: Camt053 = (&mt940).try_into().unwrap();
+        let refs = result.bk_to_cstmr_stmt.stmts[0].ntry[0].ntry_dtls[0].tx_dtls[0]
+            .refs
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            refs.uetr.as_deref(),
+            Some("e0b5b8c6-3a4b-4c4e-9d5f-6a7b8c9d0e1f")
+        );
+        let svcr = result.bk_to_cstmr_stmt.stmts[0]
+            .acct
+            .as_ref()
+            .and_then(|acct| acct.svcr.as_ref())
+            .and_then(|svcr| svcr.fin_instn_id.as_ref())
+            .unwrap();
+        assert_eq!(svcr.bic.as_deref(), Some("DEUTDEFFXXX"));
+    }
+
+    #[test]
+    fn test_convert_xmlwrapper_to_camt053() {
+        let xml_data = r#"
+            <Document>
+                <BkToCstmrStmt>
+                    <GrpHdr>
+                        <MsgId>12345</MsgId>
+                    </GrpHdr>
+                </BkToCstmrStmt>
+            </Document>
+        "#;
+
+        let xml_wrapper = XmlWrapper(xml_data.to_string());
+        let result = Camt053::try_from(&xml_wrapper).unwrap();
+
+        assert_eq!(result.bk_to_cstmr_stmt.grp_hdr.msg_id.unwrap(), "12345");
+    }
+
+    #[test]
+    fn test_convert_json_to_camt053_round_trip() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let camt053_valid = Camt053::from_read(target_file).unwrap();
+
+        let json: JsonStatements = (&camt053_valid).try_into().unwrap();
+        let result: Result<Camt053, ParserError> = (&json).try_into();
+        assert_eq!(result.unwrap(), camt053_valid);
+    }
+
+    #[test]
+    fn test_convert_ndjson_to_camt053() {
+        let data = r#"{"statementId":"S1","account":{"iban":"DE89370400440532013000"},"amount":{"value":"100.00","currency":"EUR"},"creditDebit":"CRDT"}
+{"statementId":"S1","account":{"iban":"DE89370400440532013000"},"amount":{"value":"50.00","currency":"EUR"},"creditDebit":"DBIT"}
+{"statementId":"S2","account":{"iban":"NL91ABNA0417164300"},"amount":{"value":"7.00","currency":"EUR"},"creditDebit":"DBIT"}
+"#;
+        let ndjson = NdjsonEntries::from_string(data).unwrap();
+        let result = Camt053::try_from(&ndjson).unwrap();
+
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts[0].ntry.len(), 2);
+        assert_eq!(stmts[1].id, Some("S2".to_string()));
+        assert_eq!(stmts[1].ntry[0].cdt_dbt_ind, Some("DBIT".to_string()));
+    }
+
+    #[test]
+    fn test_convert_csv_to_camt053() {
+        let data =
+            "credit_debit,amount,currency,statement_id,iban,booking_date,remittance_information
+CRDT,100.00,EUR,S1,DE89370400440532013000,2025-10-21,\"Invoice 1\nCustomer 7\"
+DBIT,50.00,EUR,S1,DE89370400440532013000,2025-10-22T09:30:00,
+DBIT,7.00,EUR,S2,NL91ABNA0417164300,,
+";
+        let csv = CsvWrapper::from_string(data).unwrap();
+        let result = Camt053::try_from(&csv).unwrap();
+
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts[0].ntry.len(), 2);
+        let first = &stmts[0].ntry[0];
+        assert_eq!(
+            first.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-21".to_string())
+        );
+        let ustrd = &first.ntry_dtls[0].tx_dtls[0]
+            .rmt_inf
+            .as_ref()
+            .unwrap()
+            .ustrd;
+        assert_eq!(
+            ustrd,
+            &vec!["Invoice 1".to_string(), "Customer 7".to_string()]
+        );
+        assert_eq!(
+            stmts[0].ntry[1].bookg_dt.as_ref().unwrap().dt_tm,
+            Some("2025-10-22T09:30:00".to_string())
+        );
+        assert_eq!(stmts[1].id, Some("S2".to_string()));
+    }
+
+    #[test]
+    fn test_convert_csv_round_trip_and_lenient_rows() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let camt053_valid = Camt053::from_read(target_file).unwrap();
+
+        let csv: CsvWrapper = (&camt053_valid).try_into().unwrap();
+        let result = Camt053::try_from(&csv).unwrap();
+        let json = JsonStatements::try_from(&result).unwrap();
+        assert_eq!(json.statements[0].entries.len(), 2);
+        assert_eq!(
+            json.statements[0].entries[0].details[0].debtor,
+            Some("John Debtor".to_string())
+        );
+
+        let data = "amount,credit_debit\n1.00,CRDT\nabc,DBIT\n2.00,XX\n";
+        let csv = CsvWrapper::from_string(data).unwrap();
+        assert!(Camt053::try_from(&csv).is_err());
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        let result = csv_to_camt053(&csv, &mut recovery).unwrap();
+        assert_eq!(result.bk_to_cstmr_stmt.stmts[0].ntry.len(), 1);
+        let warnings = recovery.into_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].error.diagnostic().unwrap().span.line, 4);
+    }
+
+    #[test]
+    fn test_convert_xlsx_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let xlsx = Xlsx::from_read(File::open(path.join("valid1.xlsx")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&xlsx).unwrap();
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(stmts[0].id, Some("STMT-2025-10".to_string()));
+        let entries: Vec<_> = stmts[0]
+            .ntry
+            .iter()
+            .map(|entry| {
+                (
+                    entry.bookg_dt.clone().unwrap().dt,
+                    entry.amt.clone().unwrap().value,
+                    entry.cdt_dbt_ind.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    Some("2025-10-21".to_string()),
+                    Some("1500".to_string()),
+                    Some("CRDT".to_string())
+                ),
+                (
+                    Some("2025-10-22".to_string()),
+                    Some("850.3".to_string()),
+                    Some("DBIT".to_string())
+                ),
+            ]
+        );
+
+        let xlsx = Xlsx {
+            sheets: vec![Sheet {
+                name: "Notes".to_string(),
+                rows: vec![vec![Cell::Text("booking_date".to_string())]],
+            }],
+        };
+        assert!(matches!(
+            Camt053::try_from(&xlsx),
+            Err(ParserError::Xlsx(_))
+        ));
+    }
+
+    #[test]
+    fn test_convert_bai2_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let bai2 = Bai2::from_read(File::open(path.join("valid1.bai2")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&bai2).unwrap();
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts[0].id, Some("1021001-1".to_string()));
+        let balances: Vec<_> = stmts[0]
+            .bal
+            .iter()
+            .map(|b| {
+                (
+                    b.tp.clone().unwrap().cd_or_prtry.unwrap().cd,
+                    b.amt.clone().unwrap().value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            balances,
+            vec![
+                (Some("OPBD".to_string()), Some("5000.00".to_string())),
+                (Some("CLBD".to_string()), Some("4825.00".to_string())),
+            ]
+        );
+        assert_eq!(stmts[1].bal[0].cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(stmts[1].acct.as_ref().unwrap().ccy, Some("CAD".to_string()));
+
+        let wire = &stmts[0].ntry[1];
+        assert_eq!(wire.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            wire.val_dt.as_ref().unwrap().dt,
+            Some("2025-10-21".to_string())
+        );
+        let code = wire.bk_tx_cd.as_ref().unwrap();
+        assert_eq!(
+            code.domn
+                .as_ref()
+                .unwrap()
+                .fmly
+                .as_ref()
+                .unwrap()
+                .sub_fmly_cd,
+            Some("DMCT".to_string())
+        );
+        assert_eq!(code.prtry.as_ref().unwrap().cd, Some("195".to_string()));
+        let details = &wire.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            details.refs.as_ref().unwrap().acct_svcr_ref,
+            Some("WIRE7781".to_string())
+        );
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec![
+                "INCOMING WIRE FROM GLOBEX".to_string(),
+                "REF ORDER 4471".to_string()
+            ]
+        );
+        assert_eq!(stmts[0].ntry[3].cdt_dbt_ind, Some("DBIT".to_string()));
+        assert!(stmts[0].ntry[3].ntry_dtls[0].tx_dtls[0].rmt_inf.is_none());
+    }
+
+    #[test]
+    fn test_convert_coda_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let coda = Coda::from_read(File::open(path.join("valid1.coda")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&coda).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("CODA1020".to_string()));
+        assert_eq!(stmt.elctrnc_seq_nb, Some("155".to_string()));
+        let acct = stmt.acct.as_ref().unwrap();
+        assert_eq!(
+            acct.id.as_ref().unwrap().iban,
+            Some("BE68539007547034".to_string())
+        );
+        assert_eq!(
+            acct.svcr
+                .as_ref()
+                .unwrap()
+                .fin_instn_id
+                .as_ref()
+                .unwrap()
+                .bic,
+            Some("KREDBEBB".to_string())
+        );
+        assert_eq!(
+            stmt.bal[1].amt.as_ref().unwrap().value,
+            Some("11247.50".to_string())
+        );
+        assert_eq!(stmt.ntry.len(), 3);
+
+        let credit = &stmt.ntry[0];
+        let code = credit.bk_tx_cd.as_ref().unwrap();
+        assert_eq!(
+            code.domn
+                .as_ref()
+                .unwrap()
+                .fmly
+                .as_ref()
+                .unwrap()
+                .sub_fmly_cd,
+            Some("ESCT".to_string())
+        );
+        assert_eq!(
+            code.prtry.as_ref().unwrap().cd,
+            Some("00150000".to_string())
+        );
+        let details = &credit.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec!["+++123/4567/89002+++".to_string()]
+        );
+        let parties = details.rltd_pties.as_ref().unwrap();
+        assert_eq!(
+            parties.dbtr.as_ref().unwrap().name,
+            Some("ACME NV".to_string())
+        );
+        assert_eq!(
+            parties.dbtr_acct.as_ref().unwrap().iban,
+            Some("BE71096123456769".to_string())
+        );
+
+        // The batch carries its breakdown as further transaction details
+        let batch = &stmt.ntry[1].ntry_dtls[0].tx_dtls;
+        assert_eq!(batch.len(), 3);
+        assert!(batch[0].amt.is_none());
+        assert_eq!(batch[0].rmt_inf.as_ref().unwrap().ustrd.len(), 2);
+        assert_eq!(
+            batch[2].amt.as_ref().unwrap().value,
+            Some("150.00".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_ofx_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let ofx = Ofx::from_read(File::open(path.join("valid1.ofx")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&ofx).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("1001".to_string()));
+        let period = stmt.fr_to_dt.as_ref().unwrap();
+        assert_eq!(period.fr_dt_tm, Some("2025-10-01T00:00:00".to_string()));
+        assert_eq!(period.to_dt_tm, Some("2025-10-20T23:59:59".to_string()));
+        assert_eq!(stmt.acct.as_ref().unwrap().id.as_ref().unwrap().iban, None);
+
+        // The opening balance is the ledger balance before the transactions
+        let balances: Vec<_> = stmt
+            .bal
+            .iter()
+            .map(|bal| (balance_code(bal), bal.amt.as_ref().unwrap().value.clone()))
+            .collect();
+        assert_eq!(
+            balances,
+            vec![
+                (Some("OPBD"), Some("1500.00".to_string())),
+                (Some("CLBD"), Some("3874.55".to_string())),
+                (Some("CLAV"), Some("3800.00".to_string())),
+            ]
+        );
+
+        assert_eq!(stmt.ntry.len(), 3);
+        let salary = &stmt.ntry[0];
+        assert_eq!(salary.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            salary.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-03".to_string())
+        );
+        let code = salary.bk_tx_cd.as_ref().unwrap();
+        assert_eq!(
+            code.domn
+                .as_ref()
+                .unwrap()
+                .fmly
+                .as_ref()
+                .unwrap()
+                .sub_fmly_cd,
+            Some("ESCT".to_string())
+        );
+        assert_eq!(
+            code.prtry.as_ref().unwrap().cd,
+            Some("DIRECTDEP".to_string())
+        );
+        let details = &salary.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            details.refs.as_ref().unwrap().acct_svcr_ref,
+            Some("202510030001".to_string())
+        );
+        assert_eq!(
+            details
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .dbtr
+                .as_ref()
+                .unwrap()
+                .name,
+            Some("ACME PAYROLL".to_string())
+        );
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec!["SALARY OCTOBER".to_string()]
+        );
+
+        let check = &stmt.ntry[1];
+        assert_eq!(check.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(
+            check.amt.as_ref().unwrap().value,
+            Some("120.50".to_string())
+        );
+        assert_eq!(
+            check.ntry_dtls[0].tx_dtls[0]
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .cdtr
+                .as_ref()
+                .unwrap()
+                .name,
+            Some("SMITH & SONS".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_qif_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let qif = Qif::from_read(File::open(path.join("valid1.qif")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&qif).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("Checking 4455".to_string()));
+        assert_eq!(
+            stmt.acct.as_ref().unwrap().id.as_ref().unwrap().other,
+            Some(GenericAccountIdentification {
+                id: Some("Checking 4455".to_string())
+            })
+        );
+        let period = stmt.fr_to_dt.as_ref().unwrap();
+        assert_eq!(period.fr_dt_tm, Some("2025-10-03T00:00:00".to_string()));
+        assert_eq!(period.to_dt_tm, Some("2025-10-20T23:59:59".to_string()));
+        assert!(stmt.bal.is_empty());
+
+        assert_eq!(stmt.ntry.len(), 3);
+        let salary = &stmt.ntry[0];
+        assert_eq!(salary.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            salary.amt.as_ref().unwrap().value,
+            Some("2500.00".to_string())
+        );
+        let details = &salary.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            details
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .dbtr
+                .as_ref()
+                .unwrap()
+                .name,
+            Some("ACME PAYROLL".to_string())
+        );
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec!["SALARY OCTOBER".to_string()]
+        );
+        assert_eq!(
+            details.refs.as_ref().unwrap().acct_svcr_ref,
+            Some("DEP".to_string())
+        );
+
+        let check = &stmt.ntry[1];
+        assert_eq!(check.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(
+            check.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-08".to_string())
+        );
+        assert_eq!(
+            check.ntry_dtls[0].tx_dtls[0]
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .cdtr
+                .as_ref()
+                .unwrap()
+                .name,
+            Some("SMITH & SONS".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_cfonb_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let cfonb = Cfonb::from_read(File::open(path.join("valid1.cfonb")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&cfonb).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("0500013M026-20251005".to_string()));
+        let acct = stmt.acct.as_ref().unwrap();
+        // The RIB key 06 is worked out from the account number
+        assert_eq!(
+            acct.id.as_ref().unwrap().iban,
+            Some("FR1420041010050500013M02606".to_string())
+        );
+        assert_eq!(balance_code(&stmt.bal[0]), Some("OPBD"));
+        assert_eq!(
+            stmt.bal[0].amt.as_ref().unwrap().value,
+            Some("1500.00".to_string())
+        );
+        assert_eq!(
+            stmt.bal[0].dt.as_ref().unwrap().dt,
+            Some("2025-10-01".to_string())
+        );
+        assert_eq!(
+            stmt.bal[1].amt.as_ref().unwrap().value,
+            Some("2647.60".to_string())
+        );
+        assert_eq!(stmt.ntry.len(), 3);
+
+        let credit = &stmt.ntry[0];
+        assert_eq!(credit.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            credit.amt.as_ref().unwrap().value,
+            Some("1250.00".to_string())
+        );
+        let code = credit.bk_tx_cd.as_ref().unwrap().prtry.as_ref().unwrap();
+        assert_eq!(code.cd, Some("05".to_string()));
+        assert_eq!(code.issr, Some("CFONB".to_string()));
+        let details = &credit.ntry_dtls[0].tx_dtls[0];
+        let refs = details.refs.as_ref().unwrap();
+        assert_eq!(refs.acct_svcr_ref, Some("REF0001".to_string()));
+        assert_eq!(refs.end_to_end_id, Some("E2E-2025-0001".to_string()));
+        assert_eq!(
+            details
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .dbtr
+                .as_ref()
+                .unwrap()
+                .name,
+            Some("ACME SARL".to_string())
+        );
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec![
+                "VIR SEPA RECU ACME SARL".to_string(),
+                "FACTURE 2025-118".to_string()
+            ]
+        );
+
+        let fee = &stmt.ntry[2];
+        assert_eq!(fee.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(fee.amt.as_ref().unwrap().value, Some("12.50".to_string()));
+        assert_eq!(
+            fee.val_dt.as_ref().unwrap().dt,
+            Some("2025-10-04".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_norma43_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let norma43 = Norma43::from_read(File::open(path.join("valid1.n43")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&norma43).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("0200051332-20251031".to_string()));
+        let acct = stmt.acct.as_ref().unwrap();
+        // The CCC check digits 45 are worked out from the entity, office and number
+        assert_eq!(
+            acct.id.as_ref().unwrap().iban,
+            Some("ES9121000418450200051332".to_string())
+        );
+        assert_eq!(acct.ccy, Some("EUR".to_string()));
+        assert_eq!(acct.name, Some("GLOBEX IBERICA SL".to_string()));
+        assert_eq!(
+            stmt.fr_to_dt.as_ref().unwrap().fr_dt_tm,
+            Some("2025-10-01T00:00:00".to_string())
+        );
+        assert_eq!(
+            stmt.bal[1].amt.as_ref().unwrap().value,
+            Some("3778.45".to_string())
+        );
+        assert_eq!(stmt.bal[1].cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(stmt.ntry.len(), 4);
+
+        let direct_debit = &stmt.ntry[1];
+        assert_eq!(direct_debit.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(
+            direct_debit.amt.as_ref().unwrap().value,
+            Some("145.20".to_string())
+        );
+        let code = direct_debit.bk_tx_cd.as_ref().unwrap();
+        assert_eq!(
+            code.domn.as_ref().unwrap().fmly.as_ref().unwrap().cd,
+            Some("RDDT".to_string())
+        );
+        assert_eq!(code.prtry.as_ref().unwrap().cd, Some("03101".to_string()));
+        assert_eq!(
+            direct_debit.val_dt.as_ref().unwrap().dt,
+            Some("2025-10-05".to_string())
+        );
+        let details = &stmt.ntry[0].ntry_dtls[0].tx_dtls[0];
+        let refs = details.refs.as_ref().unwrap();
+        assert_eq!(refs.end_to_end_id, Some("E2E000000001".to_string()));
+        assert_eq!(refs.instr_id, Some("FRA-2025-118".to_string()));
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            vec![
+                "TRANSF DE ACME SA".to_string(),
+                "FACTURA 2025-118".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_convert_client_bank_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let file =
+            ClientBankExchange::from_read(File::open(path.join("valid1.1c")).unwrap()).unwrap();
+
+        let result = Camt053::try_from(&file).unwrap();
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 2);
+        assert_eq!(stmts[1].id, Some("20251003-2".to_string()));
+        assert_eq!(stmts[1].cre_dt_tm, Some("2025-10-06T09:15:00".to_string()));
+        let acct = stmts[1].acct.as_ref().unwrap();
+        assert_eq!(acct.ccy, Some("RUB".to_string()));
+        assert_eq!(acct.name, Some("ООО \"Вектор\"".to_string()));
+        assert_eq!(
+            stmts[1].bal[1].amt.as_ref().unwrap().value,
+            Some("226650.00".to_string())
+        );
+        assert_eq!(stmts[0].ntry.len(), 1);
+        assert_eq!(stmts[1].ntry.len(), 2);
+
+        // Received on 01.10, in the first section
+        let credit = &stmts[0].ntry[0];
+        assert_eq!(credit.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            credit.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-01".to_string())
+        );
+        let details = &credit.ntry_dtls[0].tx_dtls[0];
+        let refs = details.refs.as_ref().unwrap();
+        assert_eq!(refs.instr_id, Some("118".to_string()));
+        assert_eq!(refs.end_to_end_id, None);
+        let parties = details.rltd_pties.as_ref().unwrap();
+        let payer = parties.dbtr.as_ref().unwrap();
+        assert_eq!(payer.name, Some("ООО \"Ромашка\"".to_string()));
+        assert_eq!(payer.org_id("TXID"), Some("7705123456"));
+        assert_eq!(payer.org_id("KPP"), Some("770501001"));
+        let agent = details
+            .rltd_agts
+            .as_ref()
+            .unwrap()
+            .dbtr_agt
+            .as_ref()
+            .unwrap();
+        let member = agent
+            .fin_instn_id
+            .as_ref()
+            .unwrap()
+            .clr_sys_mmb_id
+            .as_ref()
+            .unwrap();
+        assert_eq!(member.mmb_id, Some("044525593".to_string()));
+
+        let tax = &stmts[1].ntry[1];
+        assert_eq!(tax.cdt_dbt_ind, Some("DBIT".to_string()));
+        let code = tax.bk_tx_cd.as_ref().unwrap();
+        assert_eq!(code.prtry.as_ref().unwrap().cd, Some("01".to_string()));
+        assert_eq!(
+            code.domn.as_ref().unwrap().fmly.as_ref().unwrap().cd,
+            Some("ICDT".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_mt950_to_camt053() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let data = std::fs::read_to_string(path.join("valid1.mt950")).unwrap();
+        let (first, second) = data.split_at(data.find("\n{1:").unwrap() + 1);
+
+        let first = Mt950::from_read(first.as_bytes()).unwrap();
+        let result = Camt053::try_from(&first).unwrap();
+        assert_eq!(
+            result.bk_to_cstmr_stmt.grp_hdr.msg_id,
+            Some("NOSTRO2510210001".to_string())
+        );
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.id, Some("NOSTRO2510210001".to_string()));
+        let acct = stmt.acct.as_ref().unwrap();
+        let acct_id = acct.id.as_ref().unwrap();
+        assert_eq!(acct_id.iban, None);
+        assert_eq!(
+            acct_id.other.as_ref().unwrap().id,
+            Some("0012345678".to_string())
+        );
+        assert_eq!(acct.ccy, Some("EUR".to_string()));
+        let bic = acct.svcr.as_ref().unwrap().fin_instn_id.as_ref().unwrap();
+        assert_eq!(bic.bic, Some("BANKGB2LXXX".to_string()));
+        let codes: Vec<_> = stmt.bal.iter().map(|bal| balance_code(bal)).collect();
+        assert_eq!(codes, [Some("OPBD"), Some("ITBD")]);
+
+        assert_eq!(stmt.ntry.len(), 2);
+        let debit = &stmt.ntry[0];
+        assert_eq!(debit.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(
+            debit.amt.as_ref().unwrap().value,
+            Some("15000.00".to_string())
+        );
+        assert_eq!(
+            debit.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-21".to_string())
+        );
+        let details = &debit.ntry_dtls[0].tx_dtls[0];
+        let refs = details.refs.as_ref().unwrap();
+        assert_eq!(refs.end_to_end_id, Some("PAY0001".to_string()));
+        assert_eq!(refs.acct_svcr_ref, Some("BK0001".to_string()));
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            ["SUPPLIER SETTLEMENT"]
+        );
+
+        let second = Mt950::from_read(second.as_bytes()).unwrap();
+        let result = Camt053::try_from(&second).unwrap();
+        let stmt = &result.bk_to_cstmr_stmt.stmts[0];
+        let codes: Vec<_> = stmt.bal.iter().map(|bal| balance_code(bal)).collect();
+        assert_eq!(codes, [Some("ITBD"), Some("CLBD"), Some("CLAV")]);
+        assert_eq!(stmt.bal[2].cdt_dbt_ind, Some("CRDT".to_string()));
+        let refs = stmt.ntry[0].ntry_dtls[0].tx_dtls[0].refs.as_ref().unwrap();
+        assert_eq!(refs.end_to_end_id, None);
+    }
+
+    #[test]
+    fn test_mt950_entry_date_across_year_end() {
+        let value_date = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        assert_eq!(
+            mt950_entry_date(value_date, "0102"),
+            NaiveDate::from_ymd_opt(2026, 1, 2)
+        );
+        let value_date = NaiveDate::from_ymd_opt(2026, 1, 2).unwrap();
+        assert_eq!(
+            mt950_entry_date(value_date, "1231"),
+            NaiveDate::from_ymd_opt(2025, 12, 31)
+        );
+        assert_eq!(mt950_entry_date(value_date, "1340"), None);
     }
 }
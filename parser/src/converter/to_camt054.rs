@@ -0,0 +1,315 @@
+use crate::ParserError;
+use crate::camt053::format::*;
+use crate::camt054::format::*;
+use crate::converter::to_camt053::is_iban;
+use crate::mt900::format::*;
+
+use swift_mt_message::fields::{
+    Field25AccountIdentification, Field50OrderingCustomerAFK, Field52OrderingInstitution,
+};
+
+/// One notification per statement, with its id, account and entries; the
+/// balances are left out.
+impl TryFrom<&Camt053> for Camt054 {
+    type Error = ParserError;
+
+    fn try_from(camt053: &Camt053) -> Result<Self, Self::Error> {
+        let statement = &camt053.bk_to_cstmr_stmt;
+        Ok(Camt054 {
+            bk_to_cstmr_dbt_cdt_ntfctn: BankToCustomerDebitCreditNotification {
+                grp_hdr: statement.grp_hdr.clone(),
+                ntfctns: statement
+                    .stmts
+                    .iter()
+                    .map(|stmt| Notification {
+                        id: stmt.id.clone(),
+                        elctrnc_seq_nb: stmt.elctrnc_seq_nb.clone(),
+                        cre_dt_tm: stmt.cre_dt_tm.clone(),
+                        acct: stmt.acct.clone(),
+                        ntry: stmt.ntry.clone(),
+                    })
+                    .collect(),
+            },
+        })
+    }
+}
+
+/// An account identified by IBAN when the number is one.
+fn account_id(account: &str) -> AccountId {
+    if is_iban(account) {
+        AccountId {
+            iban: Some(account.to_string()),
+            other: None,
+        }
+    } else {
+        AccountId {
+            iban: None,
+            other: Some(GenericAccountIdentification {
+                id: Some(account.to_string()),
+            }),
+        }
+    }
+}
+
+/// An agent identified by its BIC.
+pub(crate) fn bic_agent(bic: &str) -> BranchAndFinancialInstitutionIdentification {
+    BranchAndFinancialInstitutionIdentification {
+        fin_instn_id: Some(FinancialInstitutionIdentification {
+            bic: Some(bic.to_string()),
+            ..Default::default()
+        }),
+    }
+}
+
+/// The ordering customer of an MT910 as the debtor, named by the first
+/// line of its name and address, and its account.
+fn ordering_customer(customer: &Field50OrderingCustomerAFK) -> RelatedParties {
+    let (account, name) = match customer {
+        Field50OrderingCustomerAFK::A(field) => (field.party_identifier.as_deref(), None),
+        Field50OrderingCustomerAFK::F(field) => (
+            Some(field.party_identifier.as_str()),
+            field.name_and_address.first(),
+        ),
+        Field50OrderingCustomerAFK::K(field) => {
+            (field.account.as_deref(), field.name_and_address.first())
+        }
+    };
+    RelatedParties {
+        dbtr: name.map(|name| Party {
+            name: Some(name.clone()),
+            ..Default::default()
+        }),
+        dbtr_acct: account.map(|account| {
+            let id = account_id(account);
+            AccountIdentification {
+                iban: id.iban,
+                other: id.other,
+            }
+        }),
+        ..Default::default()
+    }
+}
+
+/// One notification with a single booked entry, identified by `:20:`.
+///
+/// `:21:` becomes the end-to-end id of the entry, unless it is `NONREF`,
+/// and `:20:` its account servicer reference. `:13D:` is the booking time
+/// and `:32A:` the value date, currency and amount; an MT900 books a debit,
+/// an MT910 a credit. The BIC of `:52A:` is kept as the debtor agent, the
+/// ordering customer of an MT910 as the debtor and `:72:` as remittance
+/// information. The account servicer is the BIC of `:25P:` or else the
+/// sender of the message.
+impl TryFrom<&Mt900> for Camt054 {
+    type Error = ParserError;
+
+    fn try_from(mt900: &Mt900) -> Result<Self, Self::Error> {
+        let confirmation = &mt900.confirmation;
+        let reference = confirmation.reference().to_string();
+        let amount = confirmation.amount();
+
+        let (account, servicer) = match confirmation.account() {
+            Field25AccountIdentification::NoOption(field) => {
+                (field.authorisation.clone(), mt900.sender_bic())
+            }
+            Field25AccountIdentification::P(field) => {
+                (field.account.clone(), Some(field.bic.clone()))
+            }
+        };
+        let acct = Account {
+            id: Some(account_id(&account)),
+            ccy: Some(amount.currency.clone()),
+            svcr: servicer.as_deref().map(bic_agent),
+            ..Default::default()
+        };
+
+        let value_date = amount.value_date.format("%Y-%m-%d").to_string();
+        let booking_date = match confirmation.date_time() {
+            Some(field) => DateAndDateTimeChoice {
+                dt: None,
+                dt_tm: Some(format!(
+                    "{}T{}{}{}:{}",
+                    field.date.format("%Y-%m-%d"),
+                    field.time.format("%H:%M:00"),
+                    field.offset_sign,
+                    field.offset.get(..2).unwrap_or_default(),
+                    field.offset.get(2..).unwrap_or_default()
+                )),
+            },
+            None => DateAndDateTimeChoice {
+                dt: Some(value_date.clone()),
+                dt_tm: None,
+            },
+        };
+
+        let debtor_agent = match confirmation.ordering_institution() {
+            Some(Field52OrderingInstitution::A(field)) => Some(bic_agent(&field.bic)),
+            _ => None,
+        };
+        let rltd_pties = match confirmation {
+            Confirmation::Credit(message) => message.field_50.as_ref().map(ordering_customer),
+            Confirmation::Debit(_) => None,
+        };
+        let related_reference = confirmation.related_reference();
+        let details = TransactionDetails {
+            refs: Some(TransactionReferences {
+                acct_svcr_ref: Some(reference.clone()),
+                end_to_end_id: (related_reference != "NONREF")
+                    .then(|| related_reference.to_string()),
+                ..Default::default()
+            }),
+            rltd_pties,
+            rltd_agts: debtor_agent.map(|agent| RelatedAgents {
+                dbtr_agt: Some(agent),
+                ..Default::default()
+            }),
+            rmt_inf: confirmation
+                .information()
+                .map(|field| RemittanceInformation {
+                    ustrd: field.information.clone(),
+                }),
+            ..Default::default()
+        };
+
+        let entry = Entry {
+            amt: Some(Amount {
+                currency: Some(amount.currency.clone()),
+                value: Some(format!("{:.2}", amount.amount)),
+            }),
+            cdt_dbt_ind: Some(
+                match confirmation {
+                    Confirmation::Debit(_) => "DBIT",
+                    Confirmation::Credit(_) => "CRDT",
+                }
+                .to_string(),
+            ),
+            sts: Some("BOOK".to_string()),
+            bookg_dt: Some(booking_date),
+            val_dt: Some(DateAndDateTimeChoice {
+                dt: Some(value_date),
+                dt_tm: None,
+            }),
+            ntry_dtls: vec![EntryDetails {
+                tx_dtls: vec![details],
+            }],
+            ..Default::default()
+        };
+
+        Ok(Camt054 {
+            bk_to_cstmr_dbt_cdt_ntfctn: BankToCustomerDebitCreditNotification {
+                grp_hdr: GroupHeader {
+                    msg_id: Some(reference.clone()),
+                    ..Default::default()
+                },
+                ntfctns: vec![Notification {
+                    id: Some(reference),
+                    acct: Some(acct),
+                    ntry: vec![entry],
+                    ..Default::default()
+                }],
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn test_confirmations() -> Vec<Mt900> {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let data = std::fs::read_to_string(path.join("valid1.mt900")).unwrap();
+        let (debit, credit) = data.split_at(data.find("\n{1:").unwrap() + 1);
+        vec![
+            Mt900::from_read(debit.as_bytes()).unwrap(),
+            Mt900::from_read(credit.as_bytes()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_convert_mt900_to_camt054() {
+        let confirmations = test_confirmations();
+        let camt054 = Camt054::try_from(&confirmations[0]).unwrap();
+        let notification = &camt054.bk_to_cstmr_dbt_cdt_ntfctn;
+        assert_eq!(
+            notification.grp_hdr.msg_id,
+            Some("DBT2510210001".to_string())
+        );
+        let ntfctn = &notification.ntfctns[0];
+        let acct = ntfctn.acct.as_ref().unwrap();
+        assert_eq!(
+            acct.id.as_ref().unwrap().other.as_ref().unwrap().id,
+            Some("0012345678".to_string())
+        );
+        assert_eq!(acct.ccy, Some("EUR".to_string()));
+        let servicer = acct.svcr.as_ref().unwrap().fin_instn_id.as_ref().unwrap();
+        assert_eq!(servicer.bic, Some("BANKGB2LXXX".to_string()));
+
+        let entry = &ntfctn.ntry[0];
+        assert_eq!(entry.cdt_dbt_ind, Some("DBIT".to_string()));
+        assert_eq!(
+            entry.amt.as_ref().unwrap().value,
+            Some("15000.00".to_string())
+        );
+        assert_eq!(
+            entry.bookg_dt.as_ref().unwrap().dt_tm,
+            Some("2025-10-21T14:10:00+01:00".to_string())
+        );
+        assert_eq!(
+            entry.val_dt.as_ref().unwrap().dt,
+            Some("2025-10-21".to_string())
+        );
+        let details = &entry.ntry_dtls[0].tx_dtls[0];
+        let refs = details.refs.as_ref().unwrap();
+        assert_eq!(refs.end_to_end_id, Some("PAY0001".to_string()));
+        assert_eq!(refs.acct_svcr_ref, Some("DBT2510210001".to_string()));
+        let agent = details
+            .rltd_agts
+            .as_ref()
+            .unwrap()
+            .dbtr_agt
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            agent.fin_instn_id.as_ref().unwrap().bic,
+            Some("BANKFRPPXXX".to_string())
+        );
+        assert_eq!(
+            details.rmt_inf.as_ref().unwrap().ustrd,
+            ["/BNF/SUPPLIER SETTLEMENT"]
+        );
+
+        let camt054 = Camt054::try_from(&confirmations[1]).unwrap();
+        let entry = &camt054.bk_to_cstmr_dbt_cdt_ntfctn.ntfctns[0].ntry[0];
+        assert_eq!(entry.cdt_dbt_ind, Some("CRDT".to_string()));
+        assert_eq!(
+            entry.bookg_dt.as_ref().unwrap().dt,
+            Some("2025-10-21".to_string())
+        );
+        let parties = entry.ntry_dtls[0].tx_dtls[0].rltd_pties.as_ref().unwrap();
+        assert_eq!(
+            parties.dbtr.as_ref().unwrap().name,
+            Some("ACME SA".to_string())
+        );
+        assert_eq!(
+            parties.dbtr_acct.as_ref().unwrap().iban,
+            Some("FR7630006000011234567890189".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_camt053_to_camt054() {
+        let confirmations = test_confirmations();
+        let camt054 = Camt054::try_from(&confirmations[1]).unwrap();
+        let camt053 = Camt053::try_from(&camt054).unwrap();
+        assert_eq!(
+            camt053.bk_to_cstmr_stmt.stmts[0].id,
+            Some("CDT2510210001".to_string())
+        );
+        assert!(camt053.bk_to_cstmr_stmt.stmts[0].bal.is_empty());
+        assert_eq!(Camt054::try_from(&camt053).unwrap(), camt054);
+    }
+}
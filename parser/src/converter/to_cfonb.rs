@@ -0,0 +1,311 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, balance_code, currency_decimals, date_of, entry_date, find_balance, signed_amount,
+};
+use crate::camt053::format::*;
+use crate::cfonb::format::*;
+use crate::fixed_width::cut;
+use chrono::NaiveDate;
+
+/// Amounts have 14 digits, the last one carrying the sign.
+const AMOUNT_LIMIT: i128 = 100_000_000_000_000;
+
+/// A signed amount in units of the account's last decimal.
+fn units(amount: SignedAmount, decimals: u32) -> Result<i64, ParserError> {
+    let units = amount.in_units_of(decimals).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Amount {} has more than {} decimals",
+            amount.magnitude(),
+            decimals
+        ))
+    })?;
+    if units.abs() >= AMOUNT_LIMIT {
+        return Err(ParserError::Converter(format!(
+            "Amount {} is too large",
+            amount.magnitude()
+        )));
+    }
+    Ok(units as i64)
+}
+
+fn ddmmyy(date: NaiveDate) -> String {
+    date.format("%d%m%y").to_string()
+}
+
+/// Splits a text into parts of at most `len` characters.
+fn chunks(text: &str, len: usize) -> Vec<String> {
+    let chars: Vec<char> = text.trim().chars().collect();
+    chars
+        .chunks(len)
+        .map(|part| part.iter().collect())
+        .collect()
+}
+
+/// Bank, branch and number of a French or Monegasque IBAN, of a 23-character
+/// RIB, or an account number of at most 11 characters.
+fn account(acct: Option<&Account>, currency: &str) -> Result<CfonbAccount, ParserError> {
+    let id = acct.and_then(|a| a.id.as_ref());
+    let number = id
+        .and_then(|id| {
+            let iban = id.iban.clone().filter(|iban| !iban.is_empty());
+            iban.or_else(|| id.other.as_ref()?.id.clone())
+        })
+        .filter(|number| !number.is_empty())
+        .ok_or_else(|| ParserError::Converter("Statement has no account".to_string()))?;
+    let rib = match number.len() {
+        27 if number.starts_with("FR") || number.starts_with("MC") => &number[4..],
+        23 => &number,
+        len if len <= 11 => "",
+        _ => {
+            return Err(ParserError::Converter(format!(
+                "Account '{}' is not a French IBAN or RIB",
+                number
+            )));
+        }
+    };
+    let (bank_code, branch_code, number) = match rib.get(..21) {
+        Some(rib) => (&rib[..5], &rib[5..10], &rib[10..]),
+        None => ("", "", number.as_str()),
+    };
+    Ok(CfonbAccount {
+        bank_code: bank_code.to_string(),
+        branch_code: branch_code.to_string(),
+        currency: currency.to_string(),
+        decimals: currency_decimals(currency),
+        number: number.to_string(),
+    })
+}
+
+/// A movement for an entry: the interbank code of a CFONB proprietary
+/// code, the bank reference, and complements for the counterparties, the
+/// customer reference and the remittance lines after the first, which is
+/// the label.
+fn movement(
+    entry: &Entry,
+    account: &CfonbAccount,
+    statement_date: NaiveDate,
+) -> Result<CfonbMovement, ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter("Entry has no valid amount or credit/debit indicator".to_string())
+    })?;
+    let booked = entry_date(entry).unwrap_or(statement_date);
+    let operation_code = entry
+        .bk_tx_cd
+        .as_ref()
+        .and_then(|c| c.prtry.as_ref())
+        .filter(|p| p.issr.as_deref() == Some("CFONB"))
+        .and_then(|p| p.cd.clone())
+        .unwrap_or_default();
+    let mut movement = CfonbMovement {
+        operation_code: cut(&operation_code, 2),
+        booking_date: ddmmyy(booked),
+        value_date: ddmmyy(entry.val_dt.as_ref().and_then(date_of).unwrap_or(booked)),
+        amount: units(amount, account.decimals)?,
+        ..Default::default()
+    };
+
+    let details: Vec<&TransactionDetails> =
+        entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls).collect();
+    let refs = details.iter().find_map(|d| d.refs.as_ref());
+    movement.reference = cut(
+        refs.and_then(|r| r.acct_svcr_ref.as_deref())
+            .unwrap_or_default(),
+        16,
+    );
+    let mut complement = |qualifier: &str, text: &str| {
+        movement.complements.push(Complement {
+            qualifier: qualifier.to_string(),
+            text: cut(text, 70),
+        })
+    };
+    if let Some(parties) = details.iter().find_map(|d| d.rltd_pties.as_ref()) {
+        for (qualifier, party) in [("NPY", &parties.dbtr), ("NBE", &parties.cdtr)] {
+            if let Some(name) = party.as_ref().and_then(|p| p.name.as_deref()) {
+                complement(qualifier, name);
+            }
+        }
+    }
+    let end_to_end_id = refs
+        .and_then(|r| r.end_to_end_id.as_deref())
+        .filter(|id| !id.is_empty() && *id != "NOTPROVIDED");
+    if let Some(id) = end_to_end_id {
+        complement("RCN", id);
+    }
+    let mut lines = details
+        .iter()
+        .filter_map(|d| d.rmt_inf.as_ref())
+        .flat_map(|r| &r.ustrd)
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty());
+    let first = lines.next().unwrap_or_default();
+    let overflow: String = first.chars().skip(31).collect();
+    for line in std::iter::once(overflow.as_str()).chain(lines) {
+        for part in chunks(line, 70) {
+            complement("LIB", &part);
+        }
+    }
+    movement.label = cut(first, 31);
+    Ok(movement)
+}
+
+/// The old and new balance of a statement; one that is missing is worked
+/// out from the other and the entries.
+fn balances(
+    stmt: &Statement,
+    movements: &[CfonbMovement],
+    decimals: u32,
+    date: NaiveDate,
+) -> Result<(CfonbBalance, CfonbBalance), ParserError> {
+    let find = |codes: &[&str]| -> Result<Option<CfonbBalance>, ParserError> {
+        let Some(balance) = codes.iter().find_map(|code| find_balance(stmt, code)) else {
+            return Ok(None);
+        };
+        let amount = signed_amount(&balance.amt, &balance.cdt_dbt_ind)
+            .ok_or_else(|| ParserError::Converter("Balance has no valid amount".to_string()))?;
+        Ok(Some(CfonbBalance {
+            date: ddmmyy(balance.dt.as_ref().and_then(date_of).unwrap_or(date)),
+            amount: units(amount, decimals)?,
+        }))
+    };
+    let total: i64 = movements.iter().map(|m| m.amount).sum();
+    match (find(&["OPBD", "PRCD"])?, find(&["CLBD"])?) {
+        (Some(old), Some(new)) => Ok((old, new)),
+        (Some(old), None) => {
+            let new = CfonbBalance {
+                date: ddmmyy(date),
+                amount: old.amount + total,
+            };
+            Ok((old, new))
+        }
+        (None, Some(new)) => {
+            let old = CfonbBalance {
+                date: ddmmyy(date),
+                amount: new.amount - total,
+            };
+            Ok((old, new))
+        }
+        (None, None) => Err(ParserError::Converter(format!(
+            "Statement '{}' has no opening or closing balance",
+            stmt.id.as_deref().unwrap_or_default()
+        ))),
+    }
+}
+
+/// One old balance to new balance sequence per statement, for French
+/// accounts. Entries become movements with their complements; interbank
+/// operation codes are kept only from CFONB proprietary codes.
+impl TryFrom<&Camt053> for Cfonb {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let mut statements = Vec::new();
+        for stmt in &camt.bk_to_cstmr_stmt.stmts {
+            let acct = stmt.acct.as_ref();
+            let currency = acct
+                .and_then(|a| a.ccy.clone())
+                .or_else(|| {
+                    stmt.ntry
+                        .iter()
+                        .find_map(|e| e.amt.as_ref()?.currency.clone())
+                })
+                .unwrap_or_else(|| "EUR".to_string());
+            let account = account(acct, &currency)?;
+            let date = stmt
+                .bal
+                .iter()
+                .filter(|b| balance_code(b) == Some("CLBD"))
+                .find_map(|b| b.dt.as_ref().and_then(date_of))
+                .or_else(|| stmt.ntry.iter().rev().find_map(entry_date))
+                .ok_or_else(|| {
+                    ParserError::Converter(format!(
+                        "Statement '{}' has no dates",
+                        stmt.id.as_deref().unwrap_or_default()
+                    ))
+                })?;
+            let movements = stmt
+                .ntry
+                .iter()
+                .map(|entry| movement(entry, &account, date))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (old_balance, new_balance) = balances(stmt, &movements, account.decimals, date)?;
+            statements.push(CfonbStatement {
+                account,
+                old_balance,
+                new_balance,
+                movements,
+            });
+        }
+        if statements.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        Ok(Cfonb { statements })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> File {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        File::open(PathBuf::from(manifest_dir).join("test_data").join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_cfonb_round_trip() {
+        let cfonb = Cfonb::from_read(test_file("valid1.cfonb")).unwrap();
+
+        let camt053 = Camt053::try_from(&cfonb).unwrap();
+        let result = Cfonb::try_from(&camt053).unwrap();
+        let statement = &result.statements[0];
+        assert_eq!(statement.account, cfonb.statements[0].account);
+        assert_eq!(statement.old_balance, cfonb.statements[0].old_balance);
+        assert_eq!(statement.new_balance, cfonb.statements[0].new_balance);
+        let summary = |cfonb: &Cfonb| -> Vec<(i64, String, String, String, Vec<Complement>)> {
+            cfonb.statements[0]
+                .movements
+                .iter()
+                .map(|m| {
+                    (
+                        m.amount,
+                        m.operation_code.clone(),
+                        m.value_date.clone(),
+                        m.label.clone(),
+                        m.complements.clone(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(summary(&result), summary(&cfonb));
+
+        // The written file passes the reader's balance check
+        let mut written = Vec::new();
+        result.write_to(&mut written).unwrap();
+        assert_eq!(Cfonb::from_read(written.as_slice()).unwrap(), result);
+    }
+
+    #[test]
+    fn test_long_label_and_foreign_account() {
+        let cfonb = Cfonb::from_read(test_file("valid1.cfonb")).unwrap();
+        let mut camt053 = Camt053::try_from(&cfonb).unwrap();
+        let fee = &mut camt053.bk_to_cstmr_stmt.stmts[0].ntry[2];
+        fee.ntry_dtls[0].tx_dtls[0].rmt_inf = Some(RemittanceInformation {
+            ustrd: vec!["FRAIS DE TENUE DE COMPTE DU MOIS D'OCTOBRE".to_string()],
+        });
+        let result = Cfonb::try_from(&camt053).unwrap();
+        let fee = &result.statements[0].movements[2];
+        assert_eq!(fee.label, "FRAIS DE TENUE DE COMPTE DU MOI");
+        assert_eq!(fee.complements[0].qualifier, "LIB");
+        assert_eq!(fee.complements[0].text, "S D'OCTOBRE");
+
+        let camt053 = Camt053::from_read(test_file("valid1.camt053")).unwrap();
+        let error = Cfonb::try_from(&camt053).unwrap_err();
+        assert!(error.to_string().contains("is not a French IBAN or RIB"));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, balance_code, date_of, entry_date, find_balance, signed_amount,
+};
+use crate::camt053::format::*;
+use crate::client_bank::format::*;
+use chrono::NaiveDate;
+
+/// A signed amount with exactly two decimals, as 1C writes them.
+fn rubles(amount: SignedAmount) -> Result<String, ParserError> {
+    let kopecks = amount.in_units_of(2).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Amount {} has more than two decimals",
+            amount.magnitude()
+        ))
+    })?;
+    let sign = if kopecks < 0 { "-" } else { "" };
+    let kopecks = kopecks.abs();
+    Ok(format!("{}{}.{:02}", sign, kopecks / 100, kopecks % 100))
+}
+
+/// The 20-digit number of the statement's account.
+fn account(acct: Option<&Account>) -> Result<String, ParserError> {
+    let id = acct.and_then(|a| a.id.as_ref());
+    let number = id
+        .and_then(|id| {
+            let other = id.other.as_ref().and_then(|other| other.id.clone());
+            other.or_else(|| id.iban.clone())
+        })
+        .filter(|number| !number.is_empty())
+        .ok_or_else(|| ParserError::Converter("Statement has no account".to_string()))?;
+    if !is_account_number(&number) {
+        return Err(ParserError::Converter(format!(
+            "Account '{}' is not a Russian account number",
+            number
+        )));
+    }
+    Ok(number)
+}
+
+/// The dates of the period, the balances or the entries.
+fn period(stmt: &Statement) -> Option<(NaiveDate, NaiveDate)> {
+    let balance_date = |code: &str| {
+        stmt.bal
+            .iter()
+            .filter(|b| balance_code(b) == Some(code))
+            .find_map(|b| b.dt.as_ref().and_then(date_of))
+    };
+    let period_date =
+        |value: Option<&String>| NaiveDate::parse_from_str(value?.get(..10)?, "%Y-%m-%d").ok();
+    let fr_to_dt = stmt.fr_to_dt.as_ref();
+    let dates = stmt.ntry.iter().filter_map(entry_date);
+    let start = period_date(fr_to_dt.and_then(|p| p.fr_dt_tm.as_ref()))
+        .or_else(|| balance_date("OPBD"))
+        .or_else(|| dates.clone().min())?;
+    let end = period_date(fr_to_dt.and_then(|p| p.to_dt_tm.as_ref()))
+        .or_else(|| balance_date("CLBD"))
+        .or_else(|| dates.max())
+        .unwrap_or(start);
+    Some((start, end))
+}
+
+/// The opening and closing balance; one that is missing is worked out from
+/// the other and the entries.
+fn balances(
+    stmt: &Statement,
+    total: SignedAmount,
+) -> Result<(SignedAmount, SignedAmount), ParserError> {
+    let find = |codes: &[&str]| -> Result<Option<SignedAmount>, ParserError> {
+        let Some(balance) = codes.iter().find_map(|code| find_balance(stmt, code)) else {
+            return Ok(None);
+        };
+        signed_amount(&balance.amt, &balance.cdt_dbt_ind)
+            .map(Some)
+            .ok_or_else(|| ParserError::Converter("Balance has no valid amount".to_string()))
+    };
+    match (find(&["OPBD", "PRCD"])?, find(&["CLBD"])?) {
+        (Some(opening), Some(closing)) => Ok((opening, closing)),
+        (Some(opening), None) => Ok((opening, opening.add(total))),
+        (None, Some(closing)) => Ok((closing.add(total.negate()), closing)),
+        (None, None) => Err(ParserError::Converter(format!(
+            "Statement '{}' has no opening or closing balance",
+            stmt.id.as_deref().unwrap_or_default()
+        ))),
+    }
+}
+
+/// The payer or payee: the name, ИНН and КПП of `party`, the account and
+/// the bank's БИК and name.
+fn participant(
+    party: Option<&Party>,
+    account: Option<&AccountIdentification>,
+    agent: Option<&BranchAndFinancialInstitutionIdentification>,
+) -> Participant {
+    let name = party
+        .and_then(|p| p.name.clone())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let org_id = |scheme: &str| {
+        party
+            .and_then(|p| p.org_id(scheme))
+            .unwrap_or_default()
+            .to_string()
+    };
+    let institution = agent.and_then(|a| a.fin_instn_id.as_ref());
+    let bik = institution
+        .and_then(|i| i.clr_sys_mmb_id.as_ref())
+        .and_then(|m| m.mmb_id.clone())
+        .unwrap_or_default();
+    Participant {
+        account: account
+            .and_then(|a| {
+                let other = a.other.as_ref().and_then(|other| other.id.clone());
+                other.or_else(|| a.iban.clone())
+            })
+            .unwrap_or_default(),
+        name_lines: (!name.is_empty())
+            .then(|| name.clone())
+            .into_iter()
+            .collect(),
+        name,
+        inn: org_id("TXID"),
+        kpp: org_id("KPP"),
+        bank: institution.and_then(|i| i.name.clone()).unwrap_or_default(),
+        bik,
+        ..Default::default()
+    }
+}
+
+/// A document for an entry, paid from the account for a debit and to it
+/// for a credit; the statement's account, name and servicer stand in for
+/// the account holder's side where the entry has none.
+fn document(
+    entry: &Entry,
+    stmt: &Statement,
+    number: &str,
+    statement_date: NaiveDate,
+) -> Result<(PaymentDocument, SignedAmount), ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter("Entry has no valid amount or credit/debit indicator".to_string())
+    })?;
+    let debit = amount.units < 0;
+    let booked = entry_date(entry).unwrap_or(statement_date);
+    let details = entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls);
+    let refs = details.clone().find_map(|d| d.refs.as_ref());
+    let parties = details.clone().find_map(|d| d.rltd_pties.as_ref());
+    let agents = details.clone().find_map(|d| d.rltd_agts.as_ref());
+    let purpose: Vec<&str> = details
+        .filter_map(|d| d.rmt_inf.as_ref())
+        .flat_map(|r| &r.ustrd)
+        .map(|line| line.trim())
+        .collect();
+
+    let acct = stmt.acct.as_ref();
+    let own_party = Party {
+        name: acct.and_then(|a| a.name.clone()),
+        ..Default::default()
+    };
+    let own_account = AccountIdentification {
+        iban: None,
+        other: Some(GenericAccountIdentification {
+            id: Some(number.to_string()),
+        }),
+    };
+    let (dbtr, cdtr) = (
+        parties.and_then(|p| p.dbtr.as_ref()),
+        parties.and_then(|p| p.cdtr.as_ref()),
+    );
+    let (dbtr_acct, cdtr_acct) = (
+        parties.and_then(|p| p.dbtr_acct.as_ref()),
+        parties.and_then(|p| p.cdtr_acct.as_ref()),
+    );
+    let (dbtr_agt, cdtr_agt) = (
+        agents.and_then(|a| a.dbtr_agt.as_ref()),
+        agents.and_then(|a| a.cdtr_agt.as_ref()),
+    );
+    let svcr = acct.and_then(|a| a.svcr.as_ref());
+    let (payer, payee) = match debit {
+        true => (
+            participant(
+                dbtr.or(Some(&own_party)),
+                Some(&own_account),
+                dbtr_agt.or(svcr),
+            ),
+            participant(cdtr, cdtr_acct, cdtr_agt),
+        ),
+        false => (
+            participant(dbtr, dbtr_acct, dbtr_agt),
+            participant(
+                cdtr.or(Some(&own_party)),
+                Some(&own_account),
+                cdtr_agt.or(svcr),
+            ),
+        ),
+    };
+
+    let (payment_code, kind) = entry
+        .bk_tx_cd
+        .as_ref()
+        .and_then(|c| c.prtry.as_ref())
+        .filter(|p| p.issr.as_deref() == Some("CBR"))
+        .and_then(|p| {
+            let cd = p.cd.as_deref()?;
+            DOCUMENT_KINDS.iter().find(|(code, _)| *code == cd)
+        })
+        .copied()
+        .unwrap_or(DOCUMENT_KINDS[0]);
+    let reference = |id: Option<&str>| match id {
+        Some("NOTPROVIDED") | None => String::new(),
+        Some(id) => id.trim().to_string(),
+    };
+    let document_number = reference(refs.and_then(|r| r.instr_id.as_deref()));
+    let document_number = match document_number.is_empty() {
+        true => reference(refs.and_then(|r| r.acct_svcr_ref.as_deref())),
+        false => document_number,
+    };
+    let document = PaymentDocument {
+        kind: kind.to_string(),
+        number: document_number,
+        date: booked,
+        amount: rubles(if debit { amount.negate() } else { amount })?,
+        written_off_date: debit.then_some(booked),
+        received_date: (!debit).then_some(booked),
+        payer,
+        payee,
+        payment_code: payment_code.to_string(),
+        code: reference(refs.and_then(|r| r.end_to_end_id.as_deref())),
+        priority: "5".to_string(),
+        purpose: purpose.join(" "),
+        other: Vec::new(),
+    };
+    Ok((document, amount))
+}
+
+/// One account section per statement, with its documents, for accounts
+/// with a 20-digit Russian number. The document number is taken from the
+/// instruction id or the bank reference, `Код` from the end-to-end id and
+/// the kind of document from a `CBR` proprietary code, a payment order
+/// otherwise. Payers and payees get the ИНН and КПП of the parties and
+/// the БИК of their agents; documents are dated on their booking date.
+impl TryFrom<&Camt053> for ClientBankExchange {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let mut file = ClientBankExchange {
+            version: "1.03".to_string(),
+            encoding: "Windows".to_string(),
+            ..Default::default()
+        };
+        for stmt in &camt.bk_to_cstmr_stmt.stmts {
+            let number = account(stmt.acct.as_ref())?;
+            let (start, end) = period(stmt).ok_or_else(|| {
+                ParserError::Converter(format!(
+                    "Statement '{}' has no dates",
+                    stmt.id.as_deref().unwrap_or_default()
+                ))
+            })?;
+            let mut received = SignedAmount::default();
+            let mut written_off = SignedAmount::default();
+            for entry in &stmt.ntry {
+                let (document, amount) = document(entry, stmt, &number, end)?;
+                match amount.units < 0 {
+                    true => written_off = written_off.add(amount.negate()),
+                    false => received = received.add(amount),
+                }
+                if !file.document_kinds.contains(&document.kind) {
+                    file.document_kinds.push(document.kind.clone());
+                }
+                file.documents.push(document);
+            }
+            let (opening, closing) = balances(stmt, received.add(written_off.negate()))?;
+            if !file.accounts.contains(&number) {
+                file.accounts.push(number.clone());
+            }
+            file.start_date = Some(file.start_date.map_or(start, |date| date.min(start)));
+            file.end_date = Some(file.end_date.map_or(end, |date| date.max(end)));
+            file.sections.push(AccountSection {
+                start_date: start,
+                end_date: end,
+                account: number,
+                opening_balance: rubles(opening)?,
+                total_received: rubles(received)?,
+                total_written_off: rubles(written_off)?,
+                closing_balance: rubles(closing)?,
+            });
+        }
+        if file.sections.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        if let Some(created) = camt.bk_to_cstmr_stmt.grp_hdr.cre_dt_tm.as_deref() {
+            file.creation_date = created
+                .get(..10)
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+            file.creation_time = created.get(11..19).unwrap_or_default().to_string();
+        }
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> File {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        File::open(PathBuf::from(manifest_dir).join("test_data").join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_client_bank_round_trip() {
+        let file = ClientBankExchange::from_read(test_file("valid1.1c")).unwrap();
+
+        let camt053 = Camt053::try_from(&file).unwrap();
+        assert_eq!(camt053.bk_to_cstmr_stmt.stmts.len(), 2);
+        let result = ClientBankExchange::try_from(&camt053).unwrap();
+        assert_eq!(result.sections, file.sections);
+        assert_eq!(
+            (result.start_date, result.end_date),
+            (file.start_date, file.end_date)
+        );
+        assert_eq!(result.accounts, file.accounts);
+        assert_eq!(result.creation_date, file.creation_date);
+        assert_eq!(result.creation_time, file.creation_time);
+
+        for (document, original) in result.documents.iter().zip(&file.documents) {
+            assert_eq!(
+                (&document.kind, &document.number, &document.amount),
+                (&original.kind, &original.number, &original.amount)
+            );
+            assert_eq!(
+                (&document.payment_code, &document.purpose),
+                (&original.payment_code, &original.purpose)
+            );
+            for (participant, original) in [
+                (&document.payer, &original.payer),
+                (&document.payee, &original.payee),
+            ] {
+                assert_eq!(participant.account, original.account);
+                assert_eq!(participant.name, original.display_name());
+                assert_eq!(
+                    (&participant.inn, &participant.kpp),
+                    (&original.inn, &original.kpp)
+                );
+                assert_eq!(
+                    (&participant.bik, &participant.bank),
+                    (&original.bik, &original.bank)
+                );
+            }
+        }
+        // `Код=0` stands for no identifier
+        assert_eq!(result.documents[0].code, "");
+
+        // The written file passes the reader's balance checks
+        let mut written = Vec::new();
+        result.write_to(&mut written).unwrap();
+        assert_eq!(
+            ClientBankExchange::from_read(written.as_slice()).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_non_russian_account() {
+        let camt053 = Camt053::from_read(test_file("valid1.camt053")).unwrap();
+        let error = ClientBankExchange::try_from(&camt053).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("is not a Russian account number")
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, balance_code, date_of, entry_date, find_balance, signed_amount,
+};
+use crate::camt053::format::*;
+use crate::coda::format::*;
+use crate::fixed_width::cut;
+use chrono::NaiveDate;
+
+/// A signed amount in thousandths.
+fn thousandths(amount: SignedAmount) -> Result<i64, ParserError> {
+    // `SignedAmount` keeps five decimals
+    if amount.units % 100 != 0 {
+        return Err(ParserError::Converter(format!(
+            "Amount {} has more than three decimals",
+            amount.magnitude()
+        )));
+    }
+    i64::try_from(amount.units / 100)
+        .map_err(|_| ParserError::Converter(format!("Amount {} is too large", amount.magnitude())))
+}
+
+fn ddmmyy(date: NaiveDate) -> String {
+    date.format("%d%m%y").to_string()
+}
+
+/// A 3-digit sequence number from the last digits of a statement number.
+fn sequence(value: Option<&str>, default: usize) -> String {
+    let digits: String = value
+        .unwrap_or_default()
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect();
+    let number = digits
+        .get(digits.len().saturating_sub(3)..)
+        .and_then(|digits| digits.parse::<usize>().ok())
+        .unwrap_or(default);
+    format!("{:03}", number % 1000)
+}
+
+fn account(acct: Option<&Account>, currency: &str) -> Result<CodaAccount, ParserError> {
+    let id = acct.and_then(|a| a.id.as_ref());
+    let (structure, number) = match id {
+        Some(AccountId {
+            iban: Some(iban), ..
+        }) => (if iban.starts_with("BE") { '2' } else { '3' }, iban.clone()),
+        _ => {
+            let number = id
+                .and_then(|id| id.other.as_ref()?.id.clone())
+                .ok_or_else(|| ParserError::Converter("Statement has no account".to_string()))?;
+            let belgian = number.len() == 12 && number.bytes().all(|b| b.is_ascii_digit());
+            (if belgian { '0' } else { '1' }, number)
+        }
+    };
+    Ok(CodaAccount {
+        structure,
+        number: cut(&number, 34),
+        currency: currency.to_string(),
+    })
+}
+
+/// The 8-digit code of a movement: the Febelfin code it already has, one of
+/// the family mapped to its ISO bank transaction code, or of family `30`.
+///
+/// `kind` is the first digit: `0` for a simple movement, `1` for a
+/// globalised one and `5` for its details.
+fn transaction_code(entry: &Entry, debit: bool, kind: char) -> String {
+    let code = entry.bk_tx_cd.as_ref();
+    let proprietary = code
+        .and_then(|c| c.prtry.as_ref()?.cd.as_deref())
+        .filter(|cd| cd.len() == 8 && cd.bytes().all(|b| b.is_ascii_digit()));
+    if let Some(cd) = proprietary {
+        return cd.to_string();
+    }
+    let iso = code.and_then(|c| {
+        let domain = c.domn.as_ref()?;
+        let family = domain.fmly.as_ref()?;
+        Some((
+            domain.cd.as_deref()?,
+            family.cd.as_deref()?,
+            family.sub_fmly_cd.as_deref()?,
+        ))
+    });
+    let family = FAMILY_CODES
+        .iter()
+        .find(|(_, codes)| iso == Some(codes[usize::from(debit)]))
+        .map_or("30", |(family, _)| family);
+    // Transactions 01 to 49 of a family are debits, 50 to 99 credits
+    format!("{}{}{}000", kind, family, if debit { "01" } else { "50" })
+}
+
+fn communication(line: &str) -> Communication {
+    match Ogm::parse(line) {
+        Some(ogm) => Communication::Structured {
+            kind: "101".to_string(),
+            text: ogm.0,
+        },
+        None => Communication::Unstructured(line.to_string()),
+    }
+}
+
+/// Fills in a movement from transaction details: references, counterparty
+/// and the first remittance line as communication; further lines become
+/// information records.
+fn describe(movement: &mut Movement, details: &TransactionDetails) {
+    let refs = details.refs.as_ref();
+    movement.bank_reference = cut(
+        refs.and_then(|r| r.acct_svcr_ref.as_deref()).unwrap_or(""),
+        21,
+    );
+    movement.customer_reference = cut(
+        refs.and_then(|r| r.end_to_end_id.as_deref())
+            .filter(|id| *id != "NOTPROVIDED")
+            .unwrap_or(""),
+        35,
+    );
+    if let Some(parties) = &details.rltd_pties {
+        let (party, account) = match movement.debit {
+            true => (&parties.cdtr, &parties.cdtr_acct),
+            false => (&parties.dbtr, &parties.dbtr_acct),
+        };
+        movement.counterparty_name = cut(
+            party.as_ref().and_then(|p| p.name.as_deref()).unwrap_or(""),
+            35,
+        );
+        movement.counterparty_account = cut(
+            account
+                .as_ref()
+                .and_then(|a| a.iban.clone().or_else(|| a.other.as_ref()?.id.clone()))
+                .as_deref()
+                .unwrap_or(""),
+            34,
+        );
+    }
+    let mut lines = details
+        .rmt_inf
+        .iter()
+        .flat_map(|r| &r.ustrd)
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty());
+    movement.communication = lines.next().map(communication).unwrap_or_default();
+    movement.information = lines
+        .map(|line| Information {
+            bank_reference: movement.bank_reference.clone(),
+            transaction_code: movement.transaction_code.clone(),
+            communication: communication(line),
+        })
+        .collect();
+}
+
+/// A movement for an entry, followed by detail movements when its
+/// transaction details have amounts of their own.
+fn movements(
+    entry: &Entry,
+    sequence: u32,
+    statement_date: NaiveDate,
+) -> Result<Vec<Movement>, ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Entry {} has no valid amount or credit/debit indicator",
+            sequence
+        ))
+    })?;
+    let debit = amount.units < 0;
+    let details: Vec<&TransactionDetails> =
+        entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls).collect();
+    // Details without an amount of their own describe the movement itself
+    let (own, breakdown) = match details.as_slice() {
+        [] => (None, &[][..]),
+        [only] => (Some(*only), &[][..]),
+        [first, rest @ ..] if first.amt.is_none() => (Some(*first), rest),
+        all => (None, all),
+    };
+
+    let booked = entry_date(entry).unwrap_or(statement_date);
+    let mut movement = Movement {
+        sequence,
+        detail: 0,
+        debit,
+        amount: thousandths(amount)?.abs(),
+        value_date: ddmmyy(entry.val_dt.as_ref().and_then(date_of).unwrap_or(booked)),
+        transaction_code: transaction_code(
+            entry,
+            debit,
+            if breakdown.is_empty() { '0' } else { '1' },
+        ),
+        entry_date: ddmmyy(booked),
+        globalisation: if breakdown.is_empty() { '0' } else { '1' },
+        ..Default::default()
+    };
+    if let Some(details) = own {
+        describe(&mut movement, details);
+    }
+
+    let mut movements = vec![movement];
+    for (i, details) in breakdown.iter().enumerate() {
+        let amount = details
+            .amt
+            .as_ref()
+            .and_then(|a| SignedAmount::parse(a.value.as_deref()?))
+            .ok_or_else(|| {
+                ParserError::Converter(format!(
+                    "Transaction details {} of entry {} have no amount",
+                    i + 1,
+                    sequence
+                ))
+            })?;
+        let first = &movements[0];
+        let mut detail = Movement {
+            sequence,
+            detail: i as u32 + 1,
+            debit,
+            amount: thousandths(amount)?,
+            value_date: first.value_date.clone(),
+            transaction_code: format!("5{}", &first.transaction_code[1..]),
+            entry_date: first.entry_date.clone(),
+            globalisation: '0',
+            ..Default::default()
+        };
+        describe(&mut detail, details);
+        movements.push(detail);
+    }
+    Ok(movements)
+}
+
+/// The old and new balance of a statement; one that is missing is worked
+/// out from the other and the entries.
+fn balances(stmt: &Statement, date: NaiveDate) -> Result<(CodaBalance, CodaBalance), ParserError> {
+    let find = |codes: &[&str]| -> Result<Option<(i64, String)>, ParserError> {
+        let Some(balance) = codes.iter().find_map(|code| find_balance(stmt, code)) else {
+            return Ok(None);
+        };
+        let amount = signed_amount(&balance.amt, &balance.cdt_dbt_ind)
+            .ok_or_else(|| ParserError::Converter("Balance has no valid amount".to_string()))?;
+        let date = balance.dt.as_ref().and_then(date_of).unwrap_or(date);
+        Ok(Some((thousandths(amount)?, ddmmyy(date))))
+    };
+    let movements = stmt
+        .ntry
+        .iter()
+        .map(|entry| {
+            signed_amount(&entry.amt, &entry.cdt_dbt_ind)
+                .ok_or_else(|| ParserError::Converter("Entry has no valid amount".to_string()))
+                .and_then(thousandths)
+        })
+        .sum::<Result<i64, _>>()?;
+    let (old, new) = match (find(&["OPBD", "PRCD"])?, find(&["CLBD"])?) {
+        (Some(old), Some(new)) => (old, new),
+        (Some(old), None) => ((old.0, old.1), (old.0 + movements, ddmmyy(date))),
+        (None, Some(new)) => ((new.0 - movements, ddmmyy(date)), new),
+        (None, None) => {
+            return Err(ParserError::Converter(format!(
+                "Statement '{}' has no opening or closing balance",
+                stmt.id.as_deref().unwrap_or_default()
+            )));
+        }
+    };
+    let balance = |(amount, date): (i64, String)| CodaBalance {
+        debit: amount < 0,
+        amount: amount.abs(),
+        date,
+    };
+    Ok((balance(old), balance(new)))
+}
+
+/// One CODA statement per statement. Entries become movements, numbered
+/// in order; the transaction details of batch entries become their detail
+/// movements.
+impl TryFrom<&Camt053> for Coda {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let grp_hdr = &camt.bk_to_cstmr_stmt.grp_hdr;
+        let mut statements = Vec::new();
+        for (i, stmt) in camt.bk_to_cstmr_stmt.stmts.iter().enumerate() {
+            let acct = stmt.acct.as_ref();
+            let currency = acct
+                .and_then(|a| a.ccy.clone())
+                .or_else(|| {
+                    stmt.ntry
+                        .iter()
+                        .find_map(|e| e.amt.as_ref()?.currency.clone())
+                })
+                .unwrap_or_else(|| "EUR".to_string());
+            let created = [&stmt.cre_dt_tm, &grp_hdr.cre_dt_tm]
+                .into_iter()
+                .find_map(|dt| {
+                    NaiveDate::parse_from_str(dt.as_deref()?.get(..10)?, "%Y-%m-%d").ok()
+                });
+            let date = stmt
+                .bal
+                .iter()
+                .filter(|b| balance_code(b) == Some("CLBD"))
+                .find_map(|b| b.dt.as_ref().and_then(date_of))
+                .or_else(|| stmt.ntry.iter().rev().find_map(entry_date))
+                .or(created)
+                .ok_or_else(|| {
+                    ParserError::Converter(format!(
+                        "Statement '{}' has no dates",
+                        stmt.id.as_deref().unwrap_or_default()
+                    ))
+                })?;
+            let (old_balance, new_balance) = balances(stmt, date)?;
+
+            let mut movements = Vec::new();
+            for (n, entry) in stmt.ntry.iter().enumerate() {
+                movements.extend(self::movements(entry, n as u32 + 1, date)?);
+            }
+            let name = acct.and_then(|a| a.name.as_deref()).unwrap_or_default();
+            let paper_sequence = sequence(
+                stmt.lgl_seq_nb
+                    .as_deref()
+                    .or(stmt.elctrnc_seq_nb.as_deref()),
+                i + 1,
+            );
+            statements.push(CodaStatement {
+                header: Header {
+                    creation_date: ddmmyy(created.unwrap_or(date)),
+                    bank_id: "000".to_string(),
+                    file_reference: cut(stmt.id.as_deref().unwrap_or_default(), 10),
+                    addressee_name: cut(name, 26),
+                    bic: cut(
+                        &acct
+                            .and_then(|a| a.svcr.as_ref()?.fin_instn_id.as_ref()?.bic.clone())
+                            .unwrap_or_default(),
+                        11,
+                    ),
+                    ..Default::default()
+                },
+                account: account(acct, &currency)?,
+                coded_sequence: sequence(stmt.elctrnc_seq_nb.as_deref(), i + 1),
+                paper_sequence,
+                holder_name: cut(name, 26),
+                description: String::new(),
+                old_balance,
+                new_balance,
+                movements,
+                free_communications: Vec::new(),
+            });
+        }
+        if statements.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        Ok(Coda { statements })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_camt053_to_coda() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let camt053 = Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap();
+
+        let coda = Coda::try_from(&camt053).unwrap();
+        assert_eq!(coda.statements.len(), 1);
+        let statement = &coda.statements[0];
+        assert_eq!(statement.account.number, "DE89370400440532013000");
+        assert_eq!(statement.account.structure, '3');
+        assert_eq!(statement.movements.len(), 2);
+        assert!(!statement.movements[0].debit);
+        assert!(statement.movements[1].debit);
+        assert!(
+            statement
+                .movements
+                .iter()
+                .all(|m| m.transaction_code.len() == 8)
+        );
+
+        // The written file passes the reader's trailer checks
+        let mut written = Vec::new();
+        coda.write_to(&mut written).unwrap();
+        assert_eq!(Coda::from_read(written.as_slice()).unwrap(), coda);
+    }
+
+    #[test]
+    fn test_coda_round_trip() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let coda = Coda::from_read(File::open(path.join("valid1.coda")).unwrap()).unwrap();
+
+        let camt053 = Camt053::try_from(&coda).unwrap();
+        let result = Coda::try_from(&camt053).unwrap();
+        let movements = |coda: &Coda| -> Vec<(u32, u32, String, i64, Communication)> {
+            coda.statements
+                .iter()
+                .flat_map(|s| &s.movements)
+                .map(|m| {
+                    (
+                        m.sequence,
+                        m.detail,
+                        m.transaction_code.clone(),
+                        m.amount,
+                        m.communication.clone(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(movements(&result), movements(&coda));
+        let (original, result) = (&coda.statements[0], &result.statements[0]);
+        assert_eq!(result.old_balance, original.old_balance);
+        assert_eq!(result.new_balance, original.new_balance);
+        assert_eq!(result.account, original.account);
+
+        let mut entry = camt053.bk_to_cstmr_stmt.stmts[0].ntry[0].clone();
+        entry.bk_tx_cd.as_mut().unwrap().prtry = None;
+        assert_eq!(transaction_code(&entry, false, '0'), "00150000");
+        entry.bk_tx_cd = None;
+        assert_eq!(transaction_code(&entry, true, '0'), "03001000");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::ParserError;
+use crate::camt053::format::*;
+use crate::csv::format::*;
+use crate::json::format::*;
+use csv::StringRecord;
+
+/// Date column value: the date, or the date and time when only that is known.
+fn csv_date(dt: &Option<String>, dt_tm: &Option<String>) -> String {
+    dt.clone().or_else(|| dt_tm.clone()).unwrap_or_default()
+}
+
+/// Account column value: the IBAN, or else the other account id.
+fn csv_account(account: &Option<JsonAccount>) -> Option<String> {
+    let account = account.as_ref()?;
+    account.iban.clone().or_else(|| account.other_id.clone())
+}
+
+/// Flattens an entry line into a row in [`CSV_COLUMNS`] order.
+///
+/// Only the first transaction details of the entry are kept, which is what
+/// single-transaction statements, the common case, carry.
+pub(crate) fn csv_record(line: &JsonEntryLine) -> StringRecord {
+    let account = line.account.clone().unwrap_or_default();
+    let entry = &line.entry;
+    let amount = entry.amount.as_ref();
+    let code = entry.bank_transaction_code.as_ref();
+    let details = entry.details.first();
+    let references = details.and_then(|d| d.references.as_ref());
+
+    let fields = [
+        line.message_id.clone(),
+        line.statement_id.clone(),
+        account.iban,
+        account.other_id,
+        account.currency,
+        Some(csv_date(&entry.booking_date, &entry.booking_date_time)),
+        Some(csv_date(&entry.value_date, &entry.value_date_time)),
+        amount.map(|a| a.value.clone()),
+        amount.and_then(|a| a.currency.clone()),
+        entry.credit_debit.clone(),
+        entry.status.clone(),
+        code.and_then(|c| c.domain.clone()),
+        code.and_then(|c| c.family.clone()),
+        code.and_then(|c| c.sub_family.clone()),
+        code.and_then(|c| c.proprietary.clone()),
+        references.and_then(|r| r.end_to_end_id.clone()),
+        references.and_then(|r| r.account_servicer_reference.clone()),
+        details.and_then(|d| d.debtor.clone()),
+        details.and_then(|d| d.creditor.clone()),
+        details.map(|d| d.remittance_information.join("\n")),
+        details.and_then(|d| csv_account(&d.debtor_account)),
+        details.and_then(|d| csv_account(&d.creditor_account)),
+        details.and_then(|d| d.debtor_agent.clone()),
+        details.and_then(|d| d.creditor_agent.clone()),
+    ];
+    fields.into_iter().map(Option::unwrap_or_default).collect()
+}
+
+impl TryFrom<&Camt053> for CsvWrapper {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let ndjson = NdjsonEntries::try_from(camt)?;
+        let mut records = vec![StringRecord::from(CSV_COLUMNS.to_vec())];
+        records.extend(ndjson.0.iter().map(csv_record));
+        Ok(CsvWrapper(records))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_camt053_to_csv() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let camt053_valid = Camt053::from_read(target_file).unwrap();
+
+        let result: Result<CsvWrapper, ParserError> = (&camt053_valid).try_into();
+        let result = result.unwrap();
+
+        assert_eq!(result.0.len(), 3);
+        assert_eq!(&result.0[0], &StringRecord::from(CSV_COLUMNS.to_vec()));
+        let row = &result.0[1];
+        assert_eq!(&row[1], "STMT001");
+        assert_eq!(&row[2], "DE89370400440532013000");
+        assert_eq!(&row[15], "END2END123");
+        assert_eq!(&row[17], "John Debtor");
+        assert_eq!(&result.0[2][9], "DBIT");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::ParserError;
+use crate::camt053::format::*;
+use crate::json::format::*;
+
+fn json_amount(amt: &Option<Amount>) -> Option<JsonAmount> {
+    amt.as_ref().map(|a| JsonAmount {
+        value: a.value.clone().unwrap_or_default(),
+        currency: a.currency.clone(),
+    })
+}
+
+fn json_account(
+    iban: &Option<String>,
+    other: &Option<GenericAccountIdentification>,
+) -> JsonAccount {
+    JsonAccount {
+        iban: iban.clone(),
+        other_id: other.as_ref().and_then(|o| o.id.clone()),
+        ..Default::default()
+    }
+}
+
+pub(crate) fn json_statement_account(acct: &Account) -> JsonAccount {
+    let mut account = match &acct.id {
+        Some(id) => json_account(&id.iban, &id.other),
+        None => JsonAccount::default(),
+    };
+    account.currency = acct.ccy.clone();
+    account.name = acct.name.clone();
+    account
+}
+
+fn json_balance(bal: &Balance) -> JsonBalance {
+    let cd_or_prtry = bal.tp.as_ref().and_then(|tp| tp.cd_or_prtry.as_ref());
+    JsonBalance {
+        tp: cd_or_prtry.and_then(|c| c.cd.clone()),
+        proprietary_type: cd_or_prtry.and_then(|c| c.prtry.clone()),
+        amount: json_amount(&bal.amt),
+        credit_debit: bal.cdt_dbt_ind.clone(),
+        date: bal.dt.as_ref().and_then(|d| d.dt.clone()),
+        date_time: bal.dt.as_ref().and_then(|d| d.dt_tm.clone()),
+    }
+}
+
+fn json_transaction_details(tx: &TransactionDetails) -> JsonTransactionDetails {
+    let parties = tx.rltd_pties.as_ref();
+    let agents = tx.rltd_agts.as_ref();
+    let bic = |agent: Option<&BranchAndFinancialInstitutionIdentification>| {
+        agent?.fin_instn_id.as_ref()?.bic.clone()
+    };
+    JsonTransactionDetails {
+        references: tx.refs.as_ref().map(|r| JsonReferences {
+            message_id: r.msg_id.clone(),
+            account_servicer_reference: r.acct_svcr_ref.clone(),
+            payment_information_id: r.pmt_inf_id.clone(),
+            instruction_id: r.instr_id.clone(),
+            end_to_end_id: r.end_to_end_id.clone(),
+            uetr: r.uetr.clone(),
+            transaction_id: r.tx_id.clone(),
+        }),
+        amount: json_amount(&tx.amt),
+        debtor: parties.and_then(|p| p.dbtr.as_ref()?.name.clone()),
+        creditor: parties.and_then(|p| p.cdtr.as_ref()?.name.clone()),
+        debtor_account: parties
+            .and_then(|p| p.dbtr_acct.as_ref())
+            .map(|a| json_account(&a.iban, &a.other)),
+        creditor_account: parties
+            .and_then(|p| p.cdtr_acct.as_ref())
+            .map(|a| json_account(&a.iban, &a.other)),
+        debtor_agent: bic(agents.and_then(|a| a.dbtr_agt.as_ref())),
+        creditor_agent: bic(agents.and_then(|a| a.cdtr_agt.as_ref())),
+        remittance_information: tx
+            .rmt_inf
+            .as_ref()
+            .map(|r| r.ustrd.clone())
+            .unwrap_or_default(),
+    }
+}
+
+pub(crate) fn json_entry(entry: &Entry) -> JsonEntry {
+    JsonEntry {
+        amount: json_amount(&entry.amt),
+        credit_debit: entry.cdt_dbt_ind.clone(),
+        status: entry.sts.clone(),
+        booking_date: entry.bookg_dt.as_ref().and_then(|d| d.dt.clone()),
+        booking_date_time: entry.bookg_dt.as_ref().and_then(|d| d.dt_tm.clone()),
+        value_date: entry.val_dt.as_ref().and_then(|d| d.dt.clone()),
+        value_date_time: entry.val_dt.as_ref().and_then(|d| d.dt_tm.clone()),
+        bank_transaction_code: entry.bk_tx_cd.as_ref().map(|code| {
+            let domain = code.domn.as_ref();
+            let family = domain.and_then(|d| d.fmly.as_ref());
+            JsonBankTransactionCode {
+                domain: domain.and_then(|d| d.cd.clone()),
+                family: family.and_then(|f| f.cd.clone()),
+                sub_family: family.and_then(|f| f.sub_fmly_cd.clone()),
+                proprietary: code.prtry.as_ref().and_then(|p| p.cd.clone()),
+                issuer: code.prtry.as_ref().and_then(|p| p.issr.clone()),
+            }
+        }),
+        details: entry
+            .ntry_dtls
+            .iter()
+            .flat_map(|d| d.tx_dtls.iter())
+            .map(json_transaction_details)
+            .collect(),
+    }
+}
+
+fn json_statement(stmt: &Statement) -> JsonStatement {
+    JsonStatement {
+        id: stmt.id.clone(),
+        electronic_sequence_number: stmt.elctrnc_seq_nb.clone(),
+        legal_sequence_number: stmt.lgl_seq_nb.clone(),
+        created_at: stmt.cre_dt_tm.clone(),
+        from_date_time: stmt.fr_to_dt.as_ref().and_then(|d| d.fr_dt_tm.clone()),
+        to_date_time: stmt.fr_to_dt.as_ref().and_then(|d| d.to_dt_tm.clone()),
+        account: stmt.acct.as_ref().map(json_statement_account),
+        balances: stmt.bal.iter().map(json_balance).collect(),
+        entries: stmt.ntry.iter().map(json_entry).collect(),
+    }
+}
+
+impl TryFrom<&Camt053> for JsonStatements {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let grp_hdr = &camt.bk_to_cstmr_stmt.grp_hdr;
+        Ok(JsonStatements {
+            version: STATEMENT_JSON_VERSION.to_string(),
+            message_id: grp_hdr.msg_id.clone(),
+            created_at: grp_hdr.cre_dt_tm.clone(),
+            statements: camt
+                .bk_to_cstmr_stmt
+                .stmts
+                .iter()
+                .map(json_statement)
+                .collect(),
+        })
+    }
+}
+
+impl TryFrom<&Camt053> for NdjsonEntries {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let msg_id = &camt.bk_to_cstmr_stmt.grp_hdr.msg_id;
+        let lines = camt
+            .bk_to_cstmr_stmt
+            .stmts
+            .iter()
+            .flat_map(|stmt| {
+                let account = stmt.acct.as_ref().map(json_statement_account);
+                stmt.ntry.iter().map(move |entry| JsonEntryLine {
+                    message_id: msg_id.clone(),
+                    statement_id: stmt.id.clone(),
+                    account: account.clone(),
+                    entry: json_entry(entry),
+                })
+            })
+            .collect();
+        Ok(NdjsonEntries(lines))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_camt053_to_json() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let camt053_valid = Camt053::from_read(target_file).unwrap();
+
+        let result: Result<JsonStatements, ParserError> = (&camt053_valid).try_into();
+        let result = result.unwrap();
+
+        assert_eq!(result.version, STATEMENT_JSON_VERSION);
+        assert_eq!(result.message_id, Some("MSG123456789".to_string()));
+        let stmt = &result.statements[0];
+        assert_eq!(
+            stmt.account.as_ref().unwrap().iban,
+            Some("DE89370400440532013000".to_string())
+        );
+        assert_eq!(stmt.balances.len(), 4);
+        assert_eq!(stmt.entries.len(), 2);
+        let details = &stmt.entries[0].details[0];
+        assert_eq!(
+            details.references.as_ref().unwrap().end_to_end_id,
+            Some("END2END123".to_string())
+        );
+        assert_eq!(details.debtor, Some("John Debtor".to_string()));
+    }
+
+    #[test]
+    fn test_convert_camt053_to_ndjson() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let camt053_valid = Camt053::from_read(target_file).unwrap();
+
+        let result: Result<NdjsonEntries, ParserError> = (&camt053_valid).try_into();
+        let result = result.unwrap();
+
+        assert_eq!(result.0.len(), 2);
+        assert!(
+            result
+                .0
+                .iter()
+                .all(|line| line.statement_id == Some("STMT001".to_string()))
+        );
+        assert_eq!(result.0[1].entry.credit_debit, Some("DBIT".to_string()));
+    }
+
+    #[test]
+    fn test_convert_mt940_to_json_all_messages() {
+        use crate::converter::convert_streams::convert_mt940_to_json;
+        use crate::converter::graph::SharedBuffer;
+
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let output = SharedBuffer::default();
+        convert_mt940_to_json(
+            Box::new(File::open(path.join("valid2.mt940")).unwrap()),
+            Box::new(output.clone()),
+        )
+        .unwrap();
+
+        let json = JsonStatements::from_read(output.take().as_slice()).unwrap();
+        assert_eq!(json.statements.len(), 31);
+        let entries: usize = json.statements.iter().map(|s| s.entries.len()).sum();
+        assert_eq!(entries, 8);
+        assert_eq!(
+            json.statements[1].electronic_sequence_number.as_deref(),
+            Some("2/1")
+        );
+    }
+}
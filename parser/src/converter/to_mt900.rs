@@ -0,0 +1,297 @@
+use crate::ParserError;
+use crate::camt053::balance::date_of;
+use crate::camt053::format::*;
+use crate::camt054::format::*;
+use crate::converter::to_mt940::statement_account;
+use crate::errors::{Recovery, WarningKind};
+use crate::mt900::format::*;
+use crate::mt940::format::*;
+
+use swift_mt_message::SwiftField;
+use swift_mt_message::fields::{
+    Field13D, Field20, Field21NoOption, Field25AccountIdentification, Field32A, Field50K,
+    Field50OrderingCustomerAFK, Field52A, Field52OrderingInstitution, Field72,
+};
+use swift_mt_message::messages;
+
+fn converter_error(e: swift_mt_message::ParseError) -> ParserError {
+    ParserError::Converter(e.to_string())
+}
+
+/// At most 16 characters, as `:20:` and `:21:` allow.
+fn reference(value: &str) -> String {
+    value.chars().take(16).collect()
+}
+
+/// `:13D:` from a booking date-time with its UTC offset.
+fn booking_time(date: &DateAndDateTimeChoice) -> Option<Field13D> {
+    let date_time = chrono::DateTime::parse_from_rfc3339(date.dt_tm.as_deref()?).ok()?;
+    Field13D::parse(&date_time.format("%y%m%d%H%M%z").to_string()).ok()
+}
+
+/// `:32A:`, the value date or else the booking date, the currency and the amount.
+fn value_date_amount(entry: &Entry, currency: Option<&str>) -> Result<Field32A, ParserError> {
+    let date = entry
+        .val_dt
+        .as_ref()
+        .and_then(date_of)
+        .or_else(|| entry.bookg_dt.as_ref().and_then(date_of))
+        .ok_or_else(|| {
+            ParserError::Converter("Missing entry value and booking date".to_string())
+        })?;
+    let amount = entry.amt.as_ref();
+    let currency = amount
+        .and_then(|a| a.currency.as_deref())
+        .or(currency)
+        .ok_or_else(|| ParserError::Converter("Missing entry currency".to_string()))?;
+    let value = amount
+        .and_then(|a| a.value.as_deref())
+        .ok_or_else(|| ParserError::Converter("Missing entry amount".to_string()))?;
+    let value = match value.split_once('.') {
+        Some((units, cents)) => format!("{},{}", units, cents),
+        None => format!("{},", value),
+    };
+    Field32A::parse(&format!("{}{}{}", date.format("%y%m%d"), currency, value))
+        .map_err(converter_error)
+}
+
+/// `:50K:`, the debtor's account and name, when there is either.
+fn ordering_customer(parties: Option<&RelatedParties>) -> Option<Field50OrderingCustomerAFK> {
+    let parties = parties?;
+    let name = parties
+        .dbtr
+        .as_ref()
+        .and_then(|party| party.name.clone())
+        .filter(|name| !name.is_empty());
+    let account = parties
+        .dbtr_acct
+        .as_ref()
+        .and_then(|account| {
+            let iban = account.iban.clone().filter(|iban| !iban.is_empty());
+            iban.or_else(|| account.other.as_ref()?.id.clone())
+        })
+        .filter(|account| !account.is_empty());
+    if name.is_none() && account.is_none() {
+        return None;
+    }
+    Some(Field50OrderingCustomerAFK::K(Field50K {
+        account,
+        name_and_address: name
+            .map(|name| vec![name.chars().take(35).collect()])
+            .unwrap_or_default(),
+    }))
+}
+
+/// The MT900 or MT910 confirming one entry of a notification.
+///
+/// `:20:` is the account servicer reference of the entry or else the
+/// notification id, `:21:` its end-to-end id or `NONREF`. The debtor agent
+/// becomes `:52A:` and, for a credit, the debtor `:50K:`; the remittance
+/// information is sent as `:72:`, six lines of 35 characters at most.
+fn confirmation(notification: &Notification, entry: &Entry) -> Result<Mt900, ParserError> {
+    let details = entry.ntry_dtls.first().and_then(|d| d.tx_dtls.first());
+    let refs = details.and_then(|tx| tx.refs.as_ref());
+    let field_20 = refs
+        .and_then(|r| r.acct_svcr_ref.as_deref())
+        .or(notification.id.as_deref())
+        .filter(|reference| !reference.is_empty())
+        .ok_or_else(|| ParserError::Converter("Missing entry reference".to_string()))?;
+    let field_20 = Field20::parse(&reference(field_20)).map_err(converter_error)?;
+    let field_21 = refs
+        .and_then(|r| r.end_to_end_id.as_deref())
+        .filter(|id| !id.is_empty() && *id != "NOTPROVIDED")
+        .unwrap_or("NONREF");
+    let field_21 = Field21NoOption::parse(&reference(field_21)).map_err(converter_error)?;
+
+    let acct = notification.acct.as_ref();
+    let field_25 = Field25AccountIdentification::NoOption(statement_account(acct)?);
+    let field_13d = entry.bookg_dt.as_ref().and_then(booking_time);
+    let field_32a = value_date_amount(entry, acct.and_then(|a| a.ccy.as_deref()))?;
+    let field_52 = details
+        .and_then(|tx| {
+            tx.rltd_agts
+                .as_ref()?
+                .dbtr_agt
+                .as_ref()?
+                .fin_instn_id
+                .as_ref()
+        })
+        .and_then(|institution| institution.bic.as_deref())
+        .filter(|bic| !bic.is_empty())
+        .map(|bic| Field52A::parse(bic).map(Field52OrderingInstitution::A))
+        .transpose()
+        .map_err(converter_error)?;
+    let information: Vec<String> = details
+        .and_then(|tx| tx.rmt_inf.as_ref())
+        .map(|rmt| rmt.ustrd.iter())
+        .into_iter()
+        .flatten()
+        .filter(|line| !line.is_empty())
+        .take(6)
+        .map(|line| line.chars().take(35).collect())
+        .collect();
+    let field_72 = (!information.is_empty())
+        .then(|| Field72::parse(&information.join("\n")))
+        .transpose()
+        .map_err(converter_error)?;
+
+    let confirmation = match entry.cdt_dbt_ind.as_deref() {
+        Some("DBIT") => Confirmation::Debit(messages::MT900 {
+            field_20,
+            field_21,
+            field_25,
+            field_13d,
+            field_32a,
+            field_52,
+            field_72,
+        }),
+        Some("CRDT") => Confirmation::Credit(messages::MT910 {
+            field_20,
+            field_21,
+            field_25,
+            field_13d,
+            field_32a,
+            field_50: ordering_customer(details.and_then(|tx| tx.rltd_pties.as_ref())),
+            field_52,
+            field_56: None,
+            field_72,
+        }),
+        Some(other) => {
+            return Err(ParserError::Converter(format!(
+                "Unknown credit/debit indicator: '{}'",
+                other
+            )));
+        }
+        None => {
+            return Err(ParserError::Converter(
+                "Missing credit/debit indicator".to_string(),
+            ));
+        }
+    };
+
+    Ok(Mt900 {
+        basic_header: BasicHeaderBlock::default(),
+        // Without block 2 a reader could not tell the two message types apart
+        application_header: ApplicationHeaderBlock::Unstructured(format!(
+            "I{}",
+            confirmation.message_type()
+        )),
+        user_header: None,
+        confirmation,
+        footer: None,
+    })
+}
+
+impl TryFrom<&Camt054> for Vec<Mt900> {
+    type Error = ParserError;
+
+    fn try_from(camt054: &Camt054) -> Result<Self, Self::Error> {
+        camt054_to_mt900(camt054, &mut Recovery::default())
+    }
+}
+
+/// Converts every entry to an MT900 for a debit or an MT910 for a credit.
+/// In lenient mode entries that cannot be mapped are left out with a warning.
+pub(crate) fn camt054_to_mt900(
+    camt054: &Camt054,
+    recovery: &mut Recovery,
+) -> Result<Vec<Mt900>, ParserError> {
+    let mut result = vec![];
+    for notification in &camt054.bk_to_cstmr_dbt_cdt_ntfctn.ntfctns {
+        for (index, entry) in notification.ntry.iter().enumerate() {
+            match confirmation(notification, entry) {
+                Ok(mt900) => result.push(mt900),
+                Err(e) => {
+                    let error = ParserError::Converter(format!(
+                        "entry {} of notification {}: {}",
+                        index + 1,
+                        notification.id.as_deref().unwrap_or_default(),
+                        match e {
+                            ParserError::Converter(message) => message,
+                            other => other.to_string(),
+                        }
+                    ));
+                    recovery.recover(WarningKind::SkippedEntry, error)?;
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn test_confirmations() -> Vec<Mt900> {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let data = std::fs::read_to_string(path.join("valid1.mt900")).unwrap();
+        let (debit, credit) = data.split_at(data.find("\n{1:").unwrap() + 1);
+        vec![
+            Mt900::from_read(debit.as_bytes()).unwrap(),
+            Mt900::from_read(credit.as_bytes()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_mt900_round_trip() {
+        let round_trip = |mt900: &Mt900| {
+            let camt054 = Camt054::try_from(mt900).unwrap();
+            let mut result = Vec::<Mt900>::try_from(&camt054).unwrap();
+            assert_eq!(result.len(), 1);
+            result.remove(0)
+        };
+        let confirmations = test_confirmations();
+
+        let debit = round_trip(&confirmations[0]);
+        assert_eq!(debit.confirmation, confirmations[0].confirmation);
+        assert_eq!(debit.application_header.message_type(), Some("900"));
+
+        let credit = round_trip(&confirmations[1]);
+        assert_eq!(credit.application_header.message_type(), Some("910"));
+        let (Confirmation::Credit(result), Confirmation::Credit(original)) =
+            (&credit.confirmation, &confirmations[1].confirmation)
+        else {
+            panic!("expected MT910 messages");
+        };
+        assert_eq!(result.field_20, original.field_20);
+        assert_eq!(result.field_21, original.field_21);
+        assert_eq!(result.field_25, original.field_25);
+        assert_eq!(result.field_32a, original.field_32a);
+        assert_eq!(result.field_52, original.field_52);
+        // The ordering customer keeps its account and name, not its address
+        let Some(Field50OrderingCustomerAFK::K(customer)) = &result.field_50 else {
+            panic!("expected :50K:");
+        };
+        assert_eq!(
+            customer.account,
+            Some("FR7630006000011234567890189".to_string())
+        );
+        assert_eq!(customer.name_and_address, ["ACME SA"]);
+    }
+
+    #[test]
+    fn test_convert_camt054_to_mt900_unknown_credit_debit() {
+        let mut camt054 = Camt054::try_from(&test_confirmations()[0]).unwrap();
+        camt054.bk_to_cstmr_dbt_cdt_ntfctn.ntfctns[0].ntry[0].cdt_dbt_ind =
+            Some("XXXX".to_string());
+
+        let error = Vec::<Mt900>::try_from(&camt054).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("entry 1 of notification DBT2510210001")
+        );
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        assert!(
+            camt054_to_mt900(&camt054, &mut recovery)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(recovery.into_warnings()[0].kind, WarningKind::SkippedEntry);
+    }
+}
@@ -1,5 +1,7 @@
 use crate::ParserError;
+use crate::camt053::balance::find_balance;
 use crate::camt053::format::*;
+use crate::errors::{Recovery, WarningKind};
 use crate::mt940::format::*;
 use crate::xml::format::*;
 use chrono::Datelike;
@@ -7,6 +9,7 @@ use chrono::Datelike;
 use swift_mt_message::MT940StatementLine;
 
 use quick_xml::de::from_str;
+use regex::Regex;
 
 use super::mt940xml_wrapper::*;
 use swift_mt_message::messages;
@@ -25,23 +28,17 @@ field_64 — Bal with CLAV (Closing Available Balance).
 field_65 — Bal with FWAV (Forward Available Balance).
 */
 
-fn find_balance<'a>(stmt: &'a Statement, tag: &'a str) -> Result<&'a Balance, ParserError> {
-    Ok(stmt
-        .bal
-        .iter()
-        .find(|b| {
-            b.tp.as_ref()
-                .and_then(|tp| tp.cd_or_prtry.as_ref())
-                .and_then(|cd_prtry| cd_prtry.cd.as_deref())
-                == Some(tag)
-        })
-        .ok_or(ParserError::Converter(format!(
-            "Failed to parse Balance: {}",
-            tag
-        )))?)
+/// The balance of type `tag`, which the statement must have.
+pub(crate) fn required_balance<'a>(
+    stmt: &'a Statement,
+    tag: &str,
+) -> Result<&'a Balance, ParserError> {
+    find_balance(stmt, tag)
+        .ok_or_else(|| ParserError::Converter(format!("Failed to parse Balance: {}", tag)))
 }
 
-fn format_mt940_balance_line(bal: &Balance) -> String {
+/// Formats a balance as the value of a `:60F:`, `:62F:`, `:64:` or `:65:` line.
+pub(crate) fn format_mt940_balance_line(bal: &Balance) -> String {
     let cdt_dbt = match bal.cdt_dbt_ind.as_deref().unwrap_or("C") {
         "C" | "CRDT" => "C",
         "D" | "DBIT" => "D",
@@ -80,170 +77,351 @@ fn format_mt940_balance_line(bal: &Balance) -> String {
     format!("{}{}{}{}", cdt_dbt, date_formatted, currency, amount)
 }
 
+/// Date of an entry from `Dt` or the date part of `DtTm`.
+fn entry_date(date: &DateAndDateTimeChoice) -> Option<chrono::NaiveDate> {
+    let value = date.dt.as_deref().or(date.dt_tm.as_deref())?;
+    chrono::NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+/// Builds the `:61:` line and `:86:` narrative of one entry.
+pub(crate) fn statement_line(entry: &Entry) -> Result<MT940StatementLine, ParserError> {
+    let mark = match entry.cdt_dbt_ind.as_deref() {
+        Some("CRDT") => "C",
+        Some("DBIT") => "D",
+        Some(other) => {
+            return Err(ParserError::Converter(format!(
+                "Unknown credit/debit indicator: '{}'",
+                other
+            )));
+        }
+        None => {
+            return Err(ParserError::Converter(
+                "Missing credit/debit indicator".to_string(),
+            ));
+        }
+    };
+
+    let amount = entry
+        .amt
+        .as_ref()
+        .and_then(|a| a.value.as_deref())
+        .ok_or(ParserError::Converter("Missing entry amount".to_string()))?;
+    let amount = match amount.split_once('.') {
+        Some((units, cents)) => format!("{},{}", units, cents),
+        None => format!("{},", amount),
+    };
+
+    let value_date = entry.val_dt.as_ref().and_then(entry_date);
+    let booking_date = entry.bookg_dt.as_ref().and_then(entry_date);
+    let value_date = value_date.or(booking_date).ok_or(ParserError::Converter(
+        "Missing entry value and booking date".to_string(),
+    ))?;
+    let booking_date = booking_date
+        .map(|d| format!("{:02}{:02}", d.month(), d.day()))
+        .unwrap_or_default();
+
+    // Only SWIFT transaction type identification codes (e.g. NTRF) fit into :61:
+    let transaction_type = entry
+        .bk_tx_cd
+        .as_ref()
+        .and_then(|code| code.prtry.as_ref()?.cd.as_deref())
+        .filter(|cd| {
+            cd.len() == 4
+                && matches!(cd.as_bytes()[0], b'N' | b'F' | b'S')
+                && cd.bytes().all(|b| b.is_ascii_alphanumeric())
+        })
+        .unwrap_or("NMSC");
+
+    let details = entry.ntry_dtls.first().and_then(|d| d.tx_dtls.first());
+    let refs = details.and_then(|tx| tx.refs.as_ref());
+    let reference: String = refs
+        .and_then(|r| r.end_to_end_id.as_deref())
+        .filter(|id| !id.is_empty() && *id != "NOTPROVIDED")
+        .unwrap_or("NONREF")
+        .chars()
+        .take(16)
+        .collect();
+    let bank_reference = refs
+        .and_then(|r| r.acct_svcr_ref.as_deref())
+        .map(|r| format!("//{}", r.chars().take(16).collect::<String>()))
+        .unwrap_or_default();
+
+    let line_61 = format!(
+        "{:02}{:02}{:02}{}{}{}{}{}{}",
+        value_date.year() % 100,
+        value_date.month(),
+        value_date.day(),
+        booking_date,
+        mark,
+        amount,
+        transaction_type,
+        reference,
+        bank_reference
+    );
+    let field_61 = swift_mt_message::fields::Field61::parse(&line_61)
+        .map_err(|e| ParserError::Converter(format!("{}: {}", e, line_61)))?;
+
+    // :86: holds up to 6 lines of 65 characters, longer text is cut
+    let narrative: Vec<String> = details
+        .and_then(|tx| tx.rmt_inf.as_ref())
+        .into_iter()
+        .flat_map(|rmt| rmt.ustrd.iter().flat_map(|text| text.lines()))
+        .flat_map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            chars
+                .chunks(65)
+                .map(|chunk| chunk.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .take(6)
+        .collect();
+    let field_86 = (!narrative.is_empty())
+        .then(|| swift_mt_message::fields::Field86::parse(&narrative.join("\n")))
+        .transpose()
+        .map_err(|e| ParserError::Converter(e.to_string()))?;
+
+    Ok(MT940StatementLine { field_61, field_86 })
+}
+
 impl TryFrom<&Camt053> for Vec<Mt940> {
     type Error = ParserError;
 
     fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
-        let mut result = vec![];
-
-        let msg_id = camt
-            .bk_to_cstmr_stmt
-            .grp_hdr
-            .msg_id
-            .clone()
-            .unwrap_or_default();
-
-        for stmt in &camt.bk_to_cstmr_stmt.stmts {
-            let field_20 = swift_mt_message::fields::Field20::parse(&msg_id)
-                .map_err(|e| ParserError::Converter(e.to_string()))?;
-
-            let field_21 = stmt.id.clone().unwrap_or_default();
-            let field_21 = Some(
-                swift_mt_message::fields::Field21NoOption::parse(&field_21)
-                    .map_err(|e| ParserError::Converter(e.to_string()))?,
-            );
-
-            let field_25 = stmt
-                .acct
-                .as_ref()
-                .and_then(|a| a.id.as_ref())
-                .and_then(|id| id.iban.clone().or(id.other.as_ref()?.id.clone()))
-                .unwrap_or_default();
-            let field_25 = swift_mt_message::fields::Field25NoOption::parse(&field_25)
-                .map_err(|e| ParserError::Converter(e.to_string()))?;
-
-            let field_28c = {
-                let seq = stmt
-                    .elctrnc_seq_nb
-                    .clone()
-                    .or(stmt.lgl_seq_nb.clone())
-                    .unwrap_or_default();
-
-                let digits: String = seq.chars().filter(|c| c.is_ascii_digit()).collect();
-                let statement_number = if digits.len() > 5 {
-                    digits[digits.len() - 5..].to_string()
-                } else {
-                    digits
-                };
-
-                let line = format!("{}/1", statement_number);
-                swift_mt_message::fields::Field28C::parse(&line)
-                    .map_err(|e| ParserError::Converter(e.to_string()))?
-            };
-
-            let line_60f = format_mt940_balance_line(find_balance(stmt, "OPBD")?);
-            let field_60f = swift_mt_message::fields::Field60F::parse(&line_60f)
-                .map_err(|e| ParserError::Converter(e.to_string()))?;
-
-            let line_62f = format_mt940_balance_line(find_balance(stmt, "CLBD")?);
-            let field_62f = swift_mt_message::fields::Field62F::parse(&line_62f)
-                .map_err(|e| ParserError::Converter(e.to_string()))?;
-
-            let statement_lines: Vec<MT940StatementLine> = stmt
-                .ntry
-                .iter()
-                .filter_map(|entry| {
-                    let amt = entry.amt.as_ref()?.value.as_ref()?;
-                    let line_61_str = format!("{}", amt);
-
-                    let field_61 = swift_mt_message::fields::Field61::parse(&line_61_str).ok()?;
-
-                    let field_86 =
-                        Some(swift_mt_message::fields::Field86::parse("TRANSACTION DETAIL").ok()?);
-
-                    Some(MT940StatementLine { field_61, field_86 })
-                })
-                .collect();
-
-            let line_64 = format_mt940_balance_line(find_balance(stmt, "CLAV")?);
-            let field_64 = Some(
-                swift_mt_message::fields::Field64::parse(&line_64)
-                    .map_err(|e| ParserError::Converter(e.to_string()))?,
-            );
-
-            let field_65: Option<Vec<swift_mt_message::fields::Field65>> = {
-                let list: Vec<_> = stmt
-                    .bal
-                    .iter()
-                    .filter(|b| {
-                        b.tp.as_ref()
-                            .and_then(|tp| tp.cd_or_prtry.as_ref()?.cd.as_ref())
-                            .map(|cd| cd == "FWAV")
-                            .unwrap_or(false)
-                    })
-                    .filter_map(|bal| {
-                        let line = format_mt940_balance_line(&bal);
-                        swift_mt_message::fields::Field65::parse(&line).ok()
-                    })
-                    .collect();
-
-                if list.is_empty() { None } else { Some(list) }
-            };
-
-            let statement = swift_mt_message::MT940 {
-                field_20,
-                field_21,
-                field_25,
-                field_28c,
-                field_60f,
-                statement_lines,
-                field_62f,
-                field_64,
-                field_65,
-            };
-
-            result.push(Mt940 {
-                basic_header: BasicHeaderBlock::default(),
-                application_header: String::default(),
-                user_header: None,
-                statement: statement,
-                footer: None,
-            });
-        }
+        camt053_to_mt940(camt, &mut Recovery::default())
+    }
+}
 
-        Ok(result)
+/// Converts every statement to an MT940 message. In lenient mode statements
+/// and entries that cannot be mapped are left out with a warning.
+pub(crate) fn camt053_to_mt940(
+    camt: &Camt053,
+    recovery: &mut Recovery,
+) -> Result<Vec<Mt940>, ParserError> {
+    let msg_id = camt
+        .bk_to_cstmr_stmt
+        .grp_hdr
+        .msg_id
+        .clone()
+        .unwrap_or_default();
+
+    let mut result = vec![];
+    for stmt in &camt.bk_to_cstmr_stmt.stmts {
+        let mut statement_lines = vec![];
+        for (index, entry) in stmt.ntry.iter().enumerate() {
+            match statement_line(entry) {
+                Ok(line) => statement_lines.push(line),
+                Err(e) => {
+                    let error = entry_error(stmt, index, e);
+                    recovery.recover(WarningKind::SkippedEntry, error)?;
+                }
+            }
+        }
+        match mt940_statement(&msg_id, stmt, statement_lines) {
+            Ok(mt940) => result.push(mt940),
+            Err(e) => {
+                let error = ParserError::Converter(format!(
+                    "statement {}: {}",
+                    stmt.id.as_deref().unwrap_or_default(),
+                    e
+                ));
+                recovery.recover(WarningKind::SkippedStatement, error)?;
+            }
+        }
     }
+
+    Ok(result)
 }
 
-impl TryFrom<&XmlWrapper> for Mt940 {
-    type Error = ParserError;
+/// Names the entry in an error of [`statement_line`].
+pub(crate) fn entry_error(stmt: &Statement, index: usize, error: ParserError) -> ParserError {
+    ParserError::Converter(format!(
+        "entry {} of statement {}: {}",
+        index + 1,
+        stmt.id.as_deref().unwrap_or_default(),
+        match error {
+            ParserError::Converter(message) => message,
+            other => other.to_string(),
+        }
+    ))
+}
 
-    fn try_from(xml_wrapper: &XmlWrapper) -> Result<Self, Self::Error> {
-        let mt940_xml: Mt940Xml = from_str(&xml_wrapper.0)
-            .map_err(|e| ParserError::Converter(format!("XML deserialization error: {}", e)))?;
+/// `:25:`, the IBAN of the account or its other identification. As in the
+/// original converter, an account without `Othr` is written as `/`.
+pub(crate) fn statement_account(
+    acct: Option<&Account>,
+) -> Result<swift_mt_message::fields::Field25NoOption, ParserError> {
+    let account = acct
+        .and_then(|a| a.id.as_ref())
+        .and_then(|id| {
+            let iban = id.iban.clone().filter(|iban| !iban.is_empty());
+            iban.or(id.other.as_ref()?.id.clone())
+        })
+        .unwrap_or_default();
+    swift_mt_message::fields::Field25NoOption::parse(&account)
+        .map_err(|e| ParserError::Converter(e.to_string()))
+}
 
-        let statement_lines = mt940_xml
-            .statement
-            .statement_lines
+/// `:28C:`, the last five digits of the statement's sequence number.
+pub(crate) fn statement_number(
+    stmt: &Statement,
+) -> Result<swift_mt_message::fields::Field28C, ParserError> {
+    let seq = stmt
+        .elctrnc_seq_nb
+        .clone()
+        .or(stmt.lgl_seq_nb.clone())
+        .unwrap_or_default();
+
+    let digits: String = seq.chars().filter(|c| c.is_ascii_digit()).collect();
+    let statement_number = if digits.len() > 5 {
+        digits[digits.len() - 5..].to_string()
+    } else {
+        digits
+    };
+
+    let line = format!("{}/1", statement_number);
+    swift_mt_message::fields::Field28C::parse(&line)
+        .map_err(|e| ParserError::Converter(e.to_string()))
+}
+
+/// Builds the MT940 message of one statement around its converted entries.
+pub(crate) fn mt940_statement(
+    msg_id: &str,
+    stmt: &Statement,
+    statement_lines: Vec<MT940StatementLine>,
+) -> Result<Mt940, ParserError> {
+    let field_20 = swift_mt_message::fields::Field20::parse(msg_id)
+        .map_err(|e| ParserError::Converter(e.to_string()))?;
+
+    let field_21 = stmt.id.clone().unwrap_or_default();
+    let field_21 = Some(
+        swift_mt_message::fields::Field21NoOption::parse(&field_21)
+            .map_err(|e| ParserError::Converter(e.to_string()))?,
+    );
+
+    let field_25 = statement_account(stmt.acct.as_ref())?;
+    let field_28c = statement_number(stmt)?;
+
+    let line_60f = format_mt940_balance_line(required_balance(stmt, "OPBD")?);
+    let field_60f = swift_mt_message::fields::Field60F::parse(&line_60f)
+        .map_err(|e| ParserError::Converter(e.to_string()))?;
+
+    let line_62f = format_mt940_balance_line(required_balance(stmt, "CLBD")?);
+    let field_62f = swift_mt_message::fields::Field62F::parse(&line_62f)
+        .map_err(|e| ParserError::Converter(e.to_string()))?;
+
+    // :64: is optional, statements without a closing available balance have none
+    let field_64 = find_balance(stmt, "CLAV")
+        .map(|balance| {
+            swift_mt_message::fields::Field64::parse(&format_mt940_balance_line(balance))
+                .map_err(|e| ParserError::Converter(e.to_string()))
+        })
+        .transpose()?;
+
+    let field_65: Option<Vec<swift_mt_message::fields::Field65>> = {
+        let list: Vec<_> = stmt
+            .bal
             .iter()
-            .map(|line| MT940StatementLine {
-                field_61: line.field_61.clone(),
-                field_86: line.field_86.clone(),
+            .filter(|b| {
+                b.tp.as_ref()
+                    .and_then(|tp| tp.cd_or_prtry.as_ref()?.cd.as_ref())
+                    .map(|cd| cd == "FWAV")
+                    .unwrap_or(false)
+            })
+            .filter_map(|bal| {
+                let line = format_mt940_balance_line(bal);
+                swift_mt_message::fields::Field65::parse(&line).ok()
             })
             .collect();
 
-        let mt940 = Mt940 {
-            basic_header: mt940_xml.basic_header.clone(),
-            application_header: mt940_xml.application_header.clone(),
-            user_header: mt940_xml.user_header.clone(),
-            statement: messages::MT940 {
-                field_20: mt940_xml.statement.field_20,
-                field_21: mt940_xml.statement.field_21,
-                field_25: mt940_xml.statement.field_25,
-                field_28c: mt940_xml.statement.field_28c,
-                field_60f: mt940_xml.statement.field_60f,
-                statement_lines,
-                field_62f: mt940_xml.statement.field_62f,
-                field_64: mt940_xml.statement.field_64,
-                field_65: mt940_xml.statement.field_65.map(|v| v.to_vec()),
-            },
-            footer: mt940_xml.footer.clone(),
-        };
-
-        Ok(mt940)
+        if list.is_empty() { None } else { Some(list) }
+    };
+
+    let statement = swift_mt_message::MT940 {
+        field_20,
+        field_21,
+        field_25,
+        field_28c,
+        field_60f,
+        statement_lines,
+        field_62f,
+        field_64,
+        field_65,
+    };
+
+    Ok(Mt940 {
+        basic_header: BasicHeaderBlock::default(),
+        application_header: ApplicationHeaderBlock::default(),
+        user_header: None,
+        statement,
+        footer: None,
+    })
+}
+
+impl TryFrom<&XmlWrapper> for Mt940 {
+    type Error = ParserError;
+
+    fn try_from(xml_wrapper: &XmlWrapper) -> Result<Self, Self::Error> {
+        match Mt940XmlVersion::detect(&xml_wrapper.0)? {
+            Mt940XmlVersion::V1 => {
+                let document: Mt940XmlDocument = from_str(&xml_wrapper.0).map_err(|e| {
+                    ParserError::Converter(format!("XML deserialization error: {}", e))
+                })?;
+                Mt940::try_from(document)
+            }
+            Mt940XmlVersion::Legacy => from_legacy_xml(&xml_wrapper.0),
+        }
     }
 }
 
+fn from_legacy_xml(xml: &str) -> Result<Mt940, ParserError> {
+    // The legacy writer emitted `<field21/>` for missing optional fields,
+    // which serde would otherwise read as present-but-empty structs
+    let empty_element =
+        Regex::new(r"<[A-Za-z0-9_]+/>").map_err(|e| ParserError::Converter(e.to_string()))?;
+    let xml = empty_element.replace_all(xml, "");
+
+    let mt940_xml: Mt940Xml = from_str(&xml)
+        .map_err(|e| ParserError::Converter(format!("XML deserialization error: {}", e)))?;
+
+    let statement_lines = mt940_xml
+        .statement
+        .statement_lines
+        .iter()
+        .map(|line| MT940StatementLine {
+            field_61: line.field_61.clone(),
+            field_86: line.field_86.clone(),
+        })
+        .collect();
+
+    let mt940 = Mt940 {
+        basic_header: mt940_xml.basic_header.clone(),
+        application_header: ApplicationHeaderBlock::from_string(&mt940_xml.application_header)?,
+        user_header: mt940_xml
+            .user_header
+            .as_deref()
+            .map(UserHeaderBlock::from_string)
+            .transpose()?,
+        statement: messages::MT940 {
+            field_20: mt940_xml.statement.field_20,
+            field_21: mt940_xml.statement.field_21,
+            field_25: mt940_xml.statement.field_25,
+            field_28c: mt940_xml.statement.field_28c,
+            field_60f: mt940_xml.statement.field_60f,
+            statement_lines,
+            field_62f: mt940_xml.statement.field_62f,
+            field_64: mt940_xml.statement.field_64,
+            field_65: mt940_xml.statement.field_65.map(|v| v.to_vec()),
+        },
+        footer: mt940_xml
+            .footer
+            .as_deref()
+            .map(TrailerBlock::from_string)
+            .transpose()?,
+    };
+
+    Ok(mt940)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,10 +438,117 @@ mod tests {
         let target_file = File::open(target_file_path).unwrap();
         let camt053_valid = Camt053::from_read(target_file).unwrap();
 
-        let expected_string = "{1:}{2:}\r\n{4::20:MSG123456789\r\n:21:STMT001\r\n:25:/\r\n:28C:1/1\r\n:60F:C231005EUR1000,00\r\n:62F:C231005EUR1500,50\r\n:64:C251026EUR1150,00\r\n-}\r\n".to_string();
+        let expected_string = "{1:}{2:}\r\n{4::20:MSG123456789\r\n:21:STMT001\r\n:25:/\r\n:28C:1/1\r\n:60F:C231005EUR1000,00\r\n\
+            :61:2310051005C100,00NMSCEND2END123\r\n:86:Invoice 12345\r\nPayment for services\r\n\
+            :61:2310041004D50,00NMSCNONREF//ACCTREF789\r\n:86:Service fee\r\n\
+            :62F:C231005EUR1500,50\r\n:64:C251026EUR1150,00\r\n-}\r\n".to_string();
         let result: Result<Vec<Mt940>, ParserError> = (&camt053_valid).try_into();
         let result = result.unwrap();
         let mt940_str = result[0].to_string();
         assert_eq!(mt940_str.unwrap(), expected_string);
     }
+
+    #[test]
+    fn test_convert_camt053_to_mt940_long_narrative() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let mut camt053 = Camt053::from_read(target_file).unwrap();
+        let text = "0123456789".repeat(15);
+        camt053.bk_to_cstmr_stmt.stmts[0].ntry[0].ntry_dtls[0].tx_dtls[0].rmt_inf =
+            Some(RemittanceInformation {
+                ustrd: vec![text.clone(), "Second line".to_string()],
+            });
+
+        let result: Vec<Mt940> = (&camt053).try_into().unwrap();
+        let narrative = &result[0].statement.statement_lines[0]
+            .field_86
+            .as_ref()
+            .unwrap()
+            .narrative;
+        assert_eq!(
+            narrative,
+            &vec![
+                text[..65].to_string(),
+                text[65..130].to_string(),
+                text[130..].to_string(),
+                "Second line".to_string()
+            ]
+        );
+        let written = result[0].to_string().unwrap();
+        assert!(written.contains(&format!(":86:{}\r\n{}\r\n", &text[..65], &text[65..130])));
+        assert!(!written.replace("\r\n", "").contains('\n'));
+    }
+
+    #[test]
+    fn test_convert_camt053_to_mt940_unknown_credit_debit() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        let mut camt053 = Camt053::from_read(target_file).unwrap();
+        camt053.bk_to_cstmr_stmt.stmts[0].ntry[0].cdt_dbt_ind = Some("CRDIT".to_string());
+
+        let result: Result<Vec<Mt940>, ParserError> = (&camt053).try_into();
+        assert!(result.is_err());
+
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        let result = camt053_to_mt940(&camt053, &mut recovery).unwrap();
+        let lines = &result[0].statement.statement_lines;
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].field_61.debit_credit_mark, "D");
+
+        let warnings = recovery.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+        assert!(warnings[0].error.to_string().contains("'CRDIT'"));
+    }
+
+    #[test]
+    fn test_convert_xml_to_mt940_round_trip() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.mt940")).unwrap();
+        let mt940_valid = Mt940::from_read(target_file).unwrap();
+
+        let xml: XmlWrapper = (&mt940_valid).try_into().unwrap();
+        let result: Result<Mt940, ParserError> = (&xml).try_into();
+        assert_eq!(result.unwrap(), mt940_valid);
+    }
+
+    #[test]
+    fn test_convert_legacy_xml_to_mt940() {
+        // Layout written before the MT940 XML vocabulary was versioned
+        let legacy = XmlWrapper(
+            "<Mt940Xml><basic_header>F01BANKDEFFAXXX0000000000</basic_header>\
+            <application_header>I940BANKNL2AXXXXN</application_header><user_header/><statement>\
+            <field20><reference>STAT202510210001</reference></field20><field21/>\
+            <field25><authorisation>NL91ABNA0417164300</authorisation></field25>\
+            <field28C><statement_number>1</statement_number><sequence_number>1</sequence_number></field28C>\
+            <field60F><debit_credit_mark>C</debit_credit_mark><value_date>2025-10-20</value_date>\
+            <currency>EUR</currency><amount>10000</amount></field60F><transactions/>\
+            <field62F><debit_credit_mark>C</debit_credit_mark><value_date>2025-10-21</value_date>\
+            <currency>EUR</currency><amount>11239.5</amount></field62F><field64/><field65/></statement>\
+            <footer/></Mt940Xml>"
+                .to_string(),
+        );
+
+        let result: Result<Mt940, ParserError> = (&legacy).try_into();
+        let result = result.unwrap();
+        assert_eq!(result.statement.field_20.reference, "STAT202510210001");
+        assert_eq!(
+            result.statement.field_25.authorisation,
+            "NL91ABNA0417164300"
+        );
+        assert!(result.statement.statement_lines.is_empty());
+    }
+
+    #[test]
+    fn test_convert_xml_to_mt940_unknown_version() {
+        let xml = XmlWrapper(format!(
+            "<Mt940Document xmlns=\"{}\" version=\"2.0\"/>",
+            crate::converter::MT940_XML_NAMESPACE
+        ));
+        let result: Result<Mt940, ParserError> = (&xml).try_into();
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,205 @@
+use crate::ParserError;
+use crate::camt053::balance::{balance_code, find_balance};
+use crate::camt053::format::*;
+use crate::converter::to_mt940::{
+    entry_error, format_mt940_balance_line, required_balance, statement_account, statement_line,
+    statement_number,
+};
+use crate::errors::{Recovery, WarningKind};
+use crate::mt940::format::*;
+use crate::mt950::format::*;
+
+use swift_mt_message::SwiftField;
+use swift_mt_message::fields::{
+    Field20, Field60, Field60F, Field60M, Field61, Field62, Field62F, Field62M, Field64,
+};
+use swift_mt_message::messages;
+
+/// The `:61:` line of one entry. MT950 has no `:86:`, so the first line of
+/// the remittance information is kept as supplementary details instead.
+fn mt950_line(entry: &Entry) -> Result<Field61, ParserError> {
+    let line = statement_line(entry)?;
+    let mut field_61 = line.field_61;
+    if field_61.supplementary_details.is_none() {
+        field_61.supplementary_details = line
+            .field_86
+            .and_then(|field_86| field_86.narrative.into_iter().next())
+            .map(|narrative| narrative.chars().take(34).collect());
+    }
+    Ok(field_61)
+}
+
+impl TryFrom<&Camt053> for Vec<Mt950> {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        camt053_to_mt950(camt, &mut Recovery::default())
+    }
+}
+
+/// Converts every statement to an MT950 message. In lenient mode statements
+/// and entries that cannot be mapped are left out with a warning.
+pub(crate) fn camt053_to_mt950(
+    camt: &Camt053,
+    recovery: &mut Recovery,
+) -> Result<Vec<Mt950>, ParserError> {
+    let msg_id = camt
+        .bk_to_cstmr_stmt
+        .grp_hdr
+        .msg_id
+        .clone()
+        .unwrap_or_default();
+
+    let mut result = vec![];
+    for stmt in &camt.bk_to_cstmr_stmt.stmts {
+        let mut lines = vec![];
+        for (index, entry) in stmt.ntry.iter().enumerate() {
+            match mt950_line(entry) {
+                Ok(line) => lines.push(line),
+                Err(e) => {
+                    let error = entry_error(stmt, index, e);
+                    recovery.recover(WarningKind::SkippedEntry, error)?;
+                }
+            }
+        }
+        match mt950_statement(&msg_id, stmt, lines) {
+            Ok(mt950) => result.push(mt950),
+            Err(e) => {
+                let error = ParserError::Converter(format!(
+                    "statement {}: {}",
+                    stmt.id.as_deref().unwrap_or_default(),
+                    e
+                ));
+                recovery.recover(WarningKind::SkippedStatement, error)?;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Builds the MT950 message of one statement, identified by the statement
+/// id or else the message id. Without an `OPBD` or `CLBD` balance the
+/// first or last `ITBD` balance is written as `:60M:` or `:62M:`.
+fn mt950_statement(
+    msg_id: &str,
+    stmt: &Statement,
+    lines: Vec<Field61>,
+) -> Result<Mt950, ParserError> {
+    let converter_error = |e: swift_mt_message::ParseError| ParserError::Converter(e.to_string());
+    let reference = stmt
+        .id
+        .as_deref()
+        .filter(|id| !id.is_empty())
+        .unwrap_or(msg_id);
+    let field_20 = Field20::parse(reference).map_err(converter_error)?;
+
+    let mut intermediate = stmt
+        .bal
+        .iter()
+        .filter(|balance| balance_code(balance) == Some("ITBD"));
+    let field_60 = match required_balance(stmt, "OPBD") {
+        Ok(balance) => Field60::F(
+            Field60F::parse(&format_mt940_balance_line(balance)).map_err(converter_error)?,
+        ),
+        Err(e) => {
+            let balance = intermediate.next().ok_or(e)?;
+            Field60::M(
+                Field60M::parse(&format_mt940_balance_line(balance)).map_err(converter_error)?,
+            )
+        }
+    };
+    let field_62 = match required_balance(stmt, "CLBD") {
+        Ok(balance) => Field62::F(
+            Field62F::parse(&format_mt940_balance_line(balance)).map_err(converter_error)?,
+        ),
+        Err(e) => {
+            let balance = intermediate.next_back().ok_or(e)?;
+            Field62::M(
+                Field62M::parse(&format_mt940_balance_line(balance)).map_err(converter_error)?,
+            )
+        }
+    };
+    let field_64 = find_balance(stmt, "CLAV")
+        .map(|balance| Field64::parse(&format_mt940_balance_line(balance)))
+        .transpose()
+        .map_err(converter_error)?;
+
+    let statement = messages::MT950 {
+        field_20,
+        field_25: statement_account(stmt.acct.as_ref())?,
+        field_28c: statement_number(stmt)?,
+        field_60,
+        field_61: (!lines.is_empty()).then_some(lines),
+        field_62,
+        field_64,
+    };
+
+    Ok(Mt950 {
+        basic_header: BasicHeaderBlock::default(),
+        application_header: ApplicationHeaderBlock::default(),
+        user_header: None,
+        statement,
+        footer: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::path::PathBuf;
+
+    fn test_messages() -> Vec<Mt950> {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let data = std::fs::read_to_string(path.join("valid1.mt950")).unwrap();
+        let (first, second) = data.split_at(data.find("\n{1:").unwrap() + 1);
+        vec![
+            Mt950::from_read(first.as_bytes()).unwrap(),
+            Mt950::from_read(second.as_bytes()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_mt950_round_trip() {
+        for mt950 in test_messages() {
+            let camt053 = Camt053::try_from(&mt950).unwrap();
+            let result = Vec::<Mt950>::try_from(&camt053).unwrap();
+            assert_eq!(result.len(), 1);
+            let statement = &result[0].statement;
+            assert_eq!(statement.field_20, mt950.statement.field_20);
+            assert_eq!(statement.field_25, mt950.statement.field_25);
+            assert_eq!(statement.field_60, mt950.statement.field_60);
+            assert_eq!(statement.field_62, mt950.statement.field_62);
+            assert_eq!(statement.field_64, mt950.statement.field_64);
+
+            let lines = statement.field_61.iter().flatten();
+            let original = mt950.statement.field_61.iter().flatten();
+            assert_eq!(lines.clone().count(), original.clone().count());
+            for (line, original) in lines.zip(original) {
+                assert_eq!(line.amount, original.amount);
+                assert_eq!(line.debit_credit_mark, original.debit_credit_mark);
+                assert_eq!(line.transaction_type, original.transaction_type);
+                assert_eq!(line.customer_reference, original.customer_reference);
+                assert_eq!(line.bank_reference, original.bank_reference);
+                assert_eq!(line.supplementary_details, original.supplementary_details);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convert_camt053_to_mt950_without_balances() {
+        let mut camt053 = Camt053::try_from(&test_messages()[0]).unwrap();
+        camt053.bk_to_cstmr_stmt.stmts[0].bal.clear();
+
+        assert!(Vec::<Mt950>::try_from(&camt053).is_err());
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        let result = camt053_to_mt950(&camt053, &mut recovery).unwrap();
+        assert!(result.is_empty());
+        let warnings = recovery.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, WarningKind::SkippedStatement);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, balance_code, date_of, entry_date, find_balance, signed_amount,
+};
+use crate::camt053::format::*;
+use crate::fixed_width::cut;
+use crate::norma43::format::*;
+use chrono::NaiveDate;
+
+/// Amounts have 14 digits, in cents.
+const AMOUNT_LIMIT: i128 = 100_000_000_000_000;
+
+fn cents(amount: SignedAmount) -> Result<i64, ParserError> {
+    let cents = amount.in_units_of(2).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Amount {} has more than two decimals",
+            amount.magnitude()
+        ))
+    })?;
+    if cents.abs() >= AMOUNT_LIMIT {
+        return Err(ParserError::Converter(format!(
+            "Amount {} is too large",
+            amount.magnitude()
+        )));
+    }
+    Ok(cents as i64)
+}
+
+fn yymmdd(date: NaiveDate) -> String {
+    date.format("%y%m%d").to_string()
+}
+
+/// Entity, office and number of a Spanish IBAN or a 20-digit CCC; the
+/// check digits are left out.
+fn account(acct: Option<&Account>) -> Result<(String, String, String), ParserError> {
+    let id = acct.and_then(|a| a.id.as_ref());
+    let number = id
+        .and_then(|id| {
+            let iban = id.iban.clone().filter(|iban| !iban.is_empty());
+            iban.or_else(|| id.other.as_ref()?.id.clone())
+        })
+        .filter(|number| !number.is_empty())
+        .ok_or_else(|| ParserError::Converter("Statement has no account".to_string()))?;
+    let ccc = match number.len() {
+        24 if number.starts_with("ES") => &number[4..],
+        _ => &number,
+    };
+    if ccc.len() != 20 || !ccc.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParserError::Converter(format!(
+            "Account '{}' is not a Spanish IBAN or CCC",
+            number
+        )));
+    }
+    Ok((
+        ccc[..4].to_string(),
+        ccc[4..8].to_string(),
+        ccc[10..].to_string(),
+    ))
+}
+
+/// The common and own concept of an entry: those of an AEB proprietary
+/// code, or the common concept mapped to its ISO bank transaction code, or
+/// `99`, various.
+fn concepts(entry: &Entry, debit: bool) -> (String, String) {
+    let code = entry.bk_tx_cd.as_ref();
+    let proprietary = code
+        .and_then(|c| c.prtry.as_ref())
+        .filter(|p| p.issr.as_deref() == Some("AEB"))
+        .and_then(|p| p.cd.as_deref())
+        .filter(|cd| cd.len() == 5 && cd.bytes().all(|b| b.is_ascii_digit()));
+    if let Some(cd) = proprietary {
+        return (cd[..2].to_string(), cd[2..].to_string());
+    }
+    let iso = code.and_then(|c| {
+        let domain = c.domn.as_ref()?;
+        let family = domain.fmly.as_ref()?;
+        Some((
+            domain.cd.as_deref()?,
+            family.cd.as_deref()?,
+            family.sub_fmly_cd.as_deref()?,
+        ))
+    });
+    let common = COMMON_CONCEPTS
+        .iter()
+        .find(|(_, codes)| iso == Some(codes[usize::from(debit)]))
+        .map_or("99", |(concept, _)| concept);
+    (common.to_string(), "000".to_string())
+}
+
+/// A movement for an entry: the bank reference as document number, the
+/// end-to-end and instruction ids as references and the remittance lines
+/// as concepts.
+fn movement(entry: &Entry, statement_date: NaiveDate) -> Result<Norma43Movement, ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter("Entry has no valid amount or credit/debit indicator".to_string())
+    })?;
+    let booked = entry_date(entry).unwrap_or(statement_date);
+    let (common_concept, own_concept) = concepts(entry, amount.units < 0);
+    let details: Vec<&TransactionDetails> =
+        entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls).collect();
+    let refs = details.iter().find_map(|d| d.refs.as_ref());
+    let reference = |id: Option<&Option<String>>, len: usize| {
+        let id = id.and_then(|id| id.as_deref()).unwrap_or_default();
+        match id {
+            "NOTPROVIDED" => String::new(),
+            id => cut(id, len),
+        }
+    };
+    // Five concept records of two texts each
+    let concepts: Vec<String> = details
+        .iter()
+        .filter_map(|d| d.rmt_inf.as_ref())
+        .flat_map(|r| &r.ustrd)
+        .flat_map(|line| {
+            let chars: Vec<char> = line.trim().chars().collect();
+            chars
+                .chunks(38)
+                .map(|part| part.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .take(10)
+        .collect();
+    Ok(Norma43Movement {
+        branch_code: String::new(),
+        operation_date: yymmdd(booked),
+        value_date: yymmdd(entry.val_dt.as_ref().and_then(date_of).unwrap_or(booked)),
+        common_concept,
+        own_concept,
+        amount: cents(amount)?,
+        document_number: reference(refs.map(|r| &r.acct_svcr_ref), 10),
+        reference_1: reference(refs.map(|r| &r.end_to_end_id), 12),
+        reference_2: reference(refs.map(|r| &r.instr_id), 16),
+        concepts,
+        equivalence: None,
+    })
+}
+
+/// The dates of a balance type, the period or the entries.
+fn period(stmt: &Statement) -> Option<(NaiveDate, NaiveDate)> {
+    let balance_date = |code: &str| {
+        stmt.bal
+            .iter()
+            .filter(|b| balance_code(b) == Some(code))
+            .find_map(|b| b.dt.as_ref().and_then(date_of))
+    };
+    let period_date =
+        |value: Option<&String>| NaiveDate::parse_from_str(value?.get(..10)?, "%Y-%m-%d").ok();
+    let fr_to_dt = stmt.fr_to_dt.as_ref();
+    let dates = stmt.ntry.iter().filter_map(entry_date);
+    let start = period_date(fr_to_dt.and_then(|p| p.fr_dt_tm.as_ref()))
+        .or_else(|| balance_date("OPBD"))
+        .or_else(|| dates.clone().min())?;
+    let end = period_date(fr_to_dt.and_then(|p| p.to_dt_tm.as_ref()))
+        .or_else(|| balance_date("CLBD"))
+        .or_else(|| dates.max())
+        .unwrap_or(start);
+    Some((start, end))
+}
+
+/// The initial and final balance of a statement; one that is missing is
+/// worked out from the other and the movements.
+fn balances(stmt: &Statement, movements: &[Norma43Movement]) -> Result<(i64, i64), ParserError> {
+    let find = |codes: &[&str]| -> Result<Option<i64>, ParserError> {
+        let Some(balance) = codes.iter().find_map(|code| find_balance(stmt, code)) else {
+            return Ok(None);
+        };
+        let amount = signed_amount(&balance.amt, &balance.cdt_dbt_ind)
+            .ok_or_else(|| ParserError::Converter("Balance has no valid amount".to_string()))?;
+        cents(amount).map(Some)
+    };
+    let total: i64 = movements.iter().map(|m| m.amount).sum();
+    match (find(&["OPBD", "PRCD"])?, find(&["CLBD"])?) {
+        (Some(initial), Some(closing)) => Ok((initial, closing)),
+        (Some(initial), None) => Ok((initial, initial + total)),
+        (None, Some(closing)) => Ok((closing - total, closing)),
+        (None, None) => Err(ParserError::Converter(format!(
+            "Statement '{}' has no opening or closing balance",
+            stmt.id.as_deref().unwrap_or_default()
+        ))),
+    }
+}
+
+/// One account sequence per statement, for Spanish accounts, in
+/// information mode `3`. The ISO 4217 currency is written as its numeric code.
+impl TryFrom<&Camt053> for Norma43 {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let mut accounts = Vec::new();
+        for stmt in &camt.bk_to_cstmr_stmt.stmts {
+            let acct = stmt.acct.as_ref();
+            let (bank_code, branch_code, number) = account(acct)?;
+            let currency = acct
+                .and_then(|a| a.ccy.clone())
+                .or_else(|| {
+                    stmt.ntry
+                        .iter()
+                        .find_map(|e| e.amt.as_ref()?.currency.clone())
+                })
+                .unwrap_or_else(|| "EUR".to_string());
+            let currency = CURRENCIES
+                .iter()
+                .find(|(_, alpha)| *alpha == currency)
+                .map(|(numeric, _)| numeric.to_string())
+                .ok_or_else(|| {
+                    ParserError::Converter(format!("Unsupported currency '{}'", currency))
+                })?;
+            let (start, end) = period(stmt).ok_or_else(|| {
+                ParserError::Converter(format!(
+                    "Statement '{}' has no dates",
+                    stmt.id.as_deref().unwrap_or_default()
+                ))
+            })?;
+            let movements = stmt
+                .ntry
+                .iter()
+                .map(|entry| movement(entry, end))
+                .collect::<Result<Vec<_>, _>>()?;
+            let (initial_balance, final_balance) = balances(stmt, &movements)?;
+            accounts.push(Norma43Account {
+                bank_code,
+                branch_code,
+                number,
+                start_date: yymmdd(start),
+                end_date: yymmdd(end),
+                initial_balance,
+                final_balance,
+                currency,
+                information_mode: "3".to_string(),
+                name: cut(acct.and_then(|a| a.name.as_deref()).unwrap_or_default(), 26),
+                movements,
+            });
+        }
+        if accounts.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        Ok(Norma43 { accounts })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> File {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        File::open(PathBuf::from(manifest_dir).join("test_data").join(name)).unwrap()
+    }
+
+    #[test]
+    fn test_norma43_round_trip() {
+        let norma43 = Norma43::from_read(test_file("valid1.n43")).unwrap();
+
+        let camt053 = Camt053::try_from(&norma43).unwrap();
+        let result = Norma43::try_from(&camt053).unwrap();
+        let account = &result.accounts[0];
+        let original = &norma43.accounts[0];
+        assert_eq!(
+            (&account.bank_code, &account.branch_code, &account.number),
+            (&original.bank_code, &original.branch_code, &original.number)
+        );
+        assert_eq!(
+            (&account.start_date, &account.end_date, &account.currency),
+            (&original.start_date, &original.end_date, &original.currency)
+        );
+        assert_eq!(account.initial_balance, original.initial_balance);
+        assert_eq!(account.final_balance, original.final_balance);
+        for (movement, original) in account.movements.iter().zip(&original.movements) {
+            assert_eq!(
+                Norma43Movement {
+                    branch_code: original.branch_code.clone(),
+                    equivalence: original.equivalence.clone(),
+                    ..movement.clone()
+                },
+                *original
+            );
+        }
+
+        // The written file passes the reader's totals checks
+        let mut written = Vec::new();
+        result.write_to(&mut written).unwrap();
+        assert_eq!(Norma43::from_read(written.as_slice()).unwrap(), result);
+    }
+
+    #[test]
+    fn test_concepts_from_iso_codes() {
+        let norma43 = Norma43::from_read(test_file("valid1.n43")).unwrap();
+        let mut camt053 = Camt053::try_from(&norma43).unwrap();
+        let stmt = &mut camt053.bk_to_cstmr_stmt.stmts[0];
+        for entry in &mut stmt.ntry {
+            entry.bk_tx_cd.as_mut().unwrap().prtry = None;
+        }
+        let result = Norma43::try_from(&camt053).unwrap();
+        let concepts: Vec<(&str, &str)> = result.accounts[0]
+            .movements
+            .iter()
+            .map(|m| (m.common_concept.as_str(), m.own_concept.as_str()))
+            .collect();
+        assert_eq!(
+            concepts,
+            [("04", "000"), ("03", "000"), ("12", "000"), ("17", "000")]
+        );
+
+        let camt053 = Camt053::from_read(test_file("valid1.camt053")).unwrap();
+        let error = Norma43::try_from(&camt053).unwrap_err();
+        assert!(error.to_string().contains("is not a Spanish IBAN or CCC"));
+    }
+}
@@ -0,0 +1,406 @@
+use crate::ParserError;
+use crate::camt053::balance::{SignedAmount, date_of, entry_date, find_balance, signed_amount};
+use crate::camt053::format::*;
+use crate::ofx::format::*;
+use chrono::{NaiveDate, NaiveDateTime};
+use std::collections::HashSet;
+
+/// OFX 1.x limits `NAME` to 32 characters and `MEMO` to 255.
+const NAME_LENGTH: usize = 32;
+const MEMO_LENGTH: usize = 255;
+
+fn yyyymmdd(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// An ISO date or date-time as an OFX date-time, the time zone left out.
+fn ofx_date_time(value: &str) -> Option<String> {
+    let date_time = value
+        .get(..19)
+        .and_then(|v| NaiveDateTime::parse_from_str(v, "%Y-%m-%dT%H:%M:%S").ok());
+    if let Some(date_time) = date_time {
+        return Some(date_time.format("%Y%m%d%H%M%S").to_string());
+    }
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d")
+        .ok()
+        .map(yyyymmdd)
+}
+
+/// A signed amount as `TRNAMT` or `BALAMT`.
+fn ofx_amount(amount: SignedAmount) -> String {
+    match amount.units < 0 {
+        true => format!("-{}", amount.magnitude()),
+        false => amount.magnitude(),
+    }
+}
+
+fn cut(value: &str, len: usize) -> String {
+    value.trim().chars().take(len).collect()
+}
+
+/// The transaction type of an entry: an OFX type it already has, the one
+/// mapped to its ISO bank transaction code, or `CREDIT` or `DEBIT`.
+fn trn_type(entry: &Entry, debit: bool) -> String {
+    let code = entry.bk_tx_cd.as_ref();
+    let proprietary = code
+        .and_then(|c| c.prtry.as_ref())
+        .filter(|p| p.issr.as_deref() == Some("OFX"))
+        .and_then(|p| p.cd.clone());
+    if let Some(cd) = proprietary {
+        return cd;
+    }
+    let iso = code.and_then(|c| {
+        let domain = c.domn.as_ref()?;
+        let family = domain.fmly.as_ref()?;
+        Some((
+            domain.cd.as_deref()?,
+            family.cd.as_deref()?,
+            family.sub_fmly_cd.as_deref()?,
+        ))
+    });
+    TRN_TYPES
+        .iter()
+        .find(|(_, domain, family, sub_family)| iso == Some((*domain, *family, *sub_family)))
+        .map_or(if debit { "DEBIT" } else { "CREDIT" }, |(trn_type, ..)| {
+            trn_type
+        })
+        .to_string()
+}
+
+/// The `FITID` of an entry, from the first of its bank's reference, its
+/// transaction id and its end-to-end id; otherwise from the statement id
+/// and the position of the entry. A suffix keeps it unique in the statement.
+fn fit_id(
+    entry: &Entry,
+    statement_id: &str,
+    position: usize,
+    used: &mut HashSet<String>,
+) -> String {
+    let refs = entry
+        .ntry_dtls
+        .iter()
+        .flat_map(|d| &d.tx_dtls)
+        .find_map(|d| d.refs.as_ref());
+    let reference = refs
+        .and_then(|r| {
+            [&r.acct_svcr_ref, &r.tx_id, &r.end_to_end_id]
+                .into_iter()
+                .flatten()
+                .map(|id| id.trim())
+                .find(|id| !id.is_empty() && *id != "NOTPROVIDED")
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("{}-{}", statement_id, position));
+    let mut fit_id = reference.clone();
+    let mut n = 1;
+    while !used.insert(fit_id.clone()) {
+        n += 1;
+        fit_id = format!("{}-{}", reference, n);
+    }
+    fit_id
+}
+
+fn transaction(
+    entry: &Entry,
+    statement_date: NaiveDate,
+    fit_id: String,
+) -> Result<OfxTransaction, ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter(format!(
+            "Entry '{}' has no valid amount or credit/debit indicator",
+            fit_id
+        ))
+    })?;
+    let debit = amount.units < 0;
+    let details: Vec<&TransactionDetails> =
+        entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls).collect();
+    let refs = details.iter().find_map(|d| d.refs.as_ref());
+    let parties = details.iter().find_map(|d| d.rltd_pties.as_ref());
+    let counterparty = parties.and_then(|p| match debit {
+        true => p.cdtr.as_ref(),
+        false => p.dbtr.as_ref(),
+    });
+    let memo = details
+        .iter()
+        .filter_map(|d| d.rmt_inf.as_ref())
+        .flat_map(|r| &r.ustrd)
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let posted = entry_date(entry).unwrap_or(statement_date);
+    let available = entry
+        .val_dt
+        .as_ref()
+        .and_then(date_of)
+        .filter(|date| *date != posted);
+    Ok(OfxTransaction {
+        trn_type: trn_type(entry, debit),
+        posted: yyyymmdd(posted),
+        available: available.map(yyyymmdd).unwrap_or_default(),
+        amount: ofx_amount(amount),
+        fit_id,
+        check_num: String::new(),
+        ref_num: refs
+            .and_then(|r| r.end_to_end_id.as_deref())
+            .filter(|id| *id != "NOTPROVIDED")
+            .unwrap_or_default()
+            .to_string(),
+        name: cut(
+            counterparty
+                .and_then(|p| p.name.as_deref())
+                .unwrap_or_default(),
+            NAME_LENGTH,
+        ),
+        memo: cut(&memo, MEMO_LENGTH),
+    })
+}
+
+fn balance(
+    stmt: &Statement,
+    code: &str,
+    date: NaiveDate,
+) -> Result<Option<OfxBalance>, ParserError> {
+    let Some(balance) = find_balance(stmt, code) else {
+        return Ok(None);
+    };
+    let amount = signed_amount(&balance.amt, &balance.cdt_dbt_ind)
+        .ok_or_else(|| ParserError::Converter(format!("Balance {} has no valid amount", code)))?;
+    Ok(Some(OfxBalance {
+        amount: ofx_amount(amount),
+        date: yyyymmdd(balance.dt.as_ref().and_then(date_of).unwrap_or(date)),
+    }))
+}
+
+/// One bank statement per statement, in OFX 2.2; entries become `STMTTRN`s
+/// with a `FITID` from their references.
+impl TryFrom<&Camt053> for Ofx {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let grp_hdr = &camt.bk_to_cstmr_stmt.grp_hdr;
+        let mut statements = Vec::new();
+        for (i, stmt) in camt.bk_to_cstmr_stmt.stmts.iter().enumerate() {
+            // MT940 input has its reference in the group header, not the statement
+            let name = stmt
+                .id
+                .clone()
+                .filter(|id| !id.is_empty())
+                .or_else(|| {
+                    let msg_id = grp_hdr.msg_id.as_deref().filter(|id| !id.is_empty())?;
+                    Some(format!("{}-{}", msg_id, i + 1))
+                })
+                .unwrap_or_else(|| (i + 1).to_string());
+            let acct = stmt.acct.as_ref();
+            let acct_id = acct
+                .and_then(|a| a.id.as_ref())
+                .and_then(|id| {
+                    let iban = id.iban.clone().filter(|iban| !iban.is_empty());
+                    iban.or_else(|| id.other.as_ref()?.id.clone())
+                })
+                .filter(|id| !id.is_empty())
+                .ok_or_else(|| {
+                    ParserError::Converter(format!("Statement '{}' has no account", name))
+                })?;
+            let currency = acct
+                .and_then(|a| a.ccy.clone())
+                .or_else(|| {
+                    stmt.ntry
+                        .iter()
+                        .find_map(|e| e.amt.as_ref()?.currency.clone())
+                })
+                .filter(|currency| !currency.is_empty())
+                .ok_or_else(|| {
+                    ParserError::Converter(format!("Statement '{}' has no currency", name))
+                })?;
+
+            let entry_dates: Vec<NaiveDate> = stmt.ntry.iter().filter_map(entry_date).collect();
+            let period = stmt.fr_to_dt.as_ref();
+            let start = period
+                .and_then(|p| ofx_date_time(p.fr_dt_tm.as_deref()?))
+                .or_else(|| entry_dates.iter().min().copied().map(yyyymmdd));
+            let end = period
+                .and_then(|p| ofx_date_time(p.to_dt_tm.as_deref()?))
+                .or_else(|| entry_dates.iter().max().copied().map(yyyymmdd));
+            let date = end
+                .as_deref()
+                .and_then(|end| NaiveDate::parse_from_str(end.get(..8)?, "%Y%m%d").ok())
+                .or_else(|| {
+                    stmt.bal
+                        .iter()
+                        .find_map(|b| b.dt.as_ref().and_then(date_of))
+                })
+                .or_else(|| {
+                    NaiveDate::parse_from_str(stmt.cre_dt_tm.as_deref()?.get(..10)?, "%Y-%m-%d")
+                        .ok()
+                })
+                .ok_or_else(|| {
+                    ParserError::Converter(format!("Statement '{}' has no dates", name))
+                })?;
+
+            let mut used = HashSet::new();
+            let transactions = stmt
+                .ntry
+                .iter()
+                .enumerate()
+                .map(|(n, entry)| transaction(entry, date, fit_id(entry, &name, n + 1, &mut used)))
+                .collect::<Result<Vec<_>, _>>()?;
+            statements.push(OfxStatement {
+                kind: StatementKind::Bank,
+                trn_uid: name,
+                currency,
+                account: OfxAccount {
+                    bank_id: acct
+                        .and_then(|a| a.svcr.as_ref()?.fin_instn_id.as_ref()?.bic.clone())
+                        .unwrap_or_default(),
+                    branch_id: String::new(),
+                    acct_id,
+                    acct_type: "CHECKING".to_string(),
+                },
+                start: start.unwrap_or_else(|| yyyymmdd(date)),
+                end: end.unwrap_or_else(|| yyyymmdd(date)),
+                transactions,
+                ledger_balance: balance(stmt, "CLBD", date)?,
+                available_balance: balance(stmt, "CLAV", date)?,
+            });
+        }
+        if statements.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        let dt_server = grp_hdr
+            .cre_dt_tm
+            .as_deref()
+            .and_then(ofx_date_time)
+            .unwrap_or_else(|| statements[0].end.clone());
+        Ok(Ofx {
+            version: OfxVersion::default().header_version().to_string(),
+            signon: SignOn {
+                dt_server,
+                language: "ENG".to_string(),
+                ..Default::default()
+            },
+            statements,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_convert_camt053_to_ofx() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let camt053 = Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap();
+
+        let ofx = Ofx::try_from(&camt053).unwrap();
+        let statement = &ofx.statements[0];
+        assert_eq!(statement.account.acct_id, "DE89370400440532013000");
+        assert_eq!(statement.currency, "EUR");
+        assert_eq!(statement.transactions.len(), 2);
+        let credit = &statement.transactions[0];
+        assert!(!credit.amount.starts_with('-'));
+        assert_eq!(credit.fit_id, "TXN123456");
+        assert!(statement.transactions[1].amount.starts_with('-'));
+        assert_eq!(statement.transactions[1].fit_id, "ACCTREF789");
+        assert!(statement.ledger_balance.is_some());
+
+        // The written file, XML or SGML, reads back the same
+        let mut written = Vec::new();
+        ofx.write_to(&mut written).unwrap();
+        assert_eq!(Ofx::from_read(written.as_slice()).unwrap(), ofx);
+        let sgml = Ofx {
+            version: "102".to_string(),
+            ..ofx
+        };
+        let mut written = Vec::new();
+        sgml.write_to(&mut written).unwrap();
+        assert_eq!(Ofx::from_read(written.as_slice()).unwrap(), sgml);
+    }
+
+    #[test]
+    fn test_ofx_round_trip_keeps_fit_ids_and_types() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let ofx = Ofx::from_read(File::open(path.join("valid1.ofx")).unwrap()).unwrap();
+
+        let camt053 = Camt053::try_from(&ofx).unwrap();
+        let result = Ofx::try_from(&camt053).unwrap();
+        let transactions = |ofx: &Ofx| -> Vec<(String, String, String, String)> {
+            ofx.statements
+                .iter()
+                .flat_map(|s| &s.transactions)
+                .map(|t| {
+                    (
+                        t.trn_type.clone(),
+                        t.amount.clone(),
+                        t.fit_id.clone(),
+                        t.name.clone(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(transactions(&result), transactions(&ofx));
+        assert_eq!(
+            result.statements[0].ledger_balance,
+            ofx.statements[0].ledger_balance
+        );
+
+        // Entries without references get unique ids from the statement id
+        let mut used = HashSet::new();
+        let entry = Entry::default();
+        assert_eq!(fit_id(&entry, "S1", 1, &mut used), "S1-1");
+        used.insert("S1-2".to_string());
+        assert_eq!(fit_id(&entry, "S1", 2, &mut used), "S1-2-2");
+    }
+
+    #[test]
+    fn test_convert_streams_to_ofx_version() {
+        use crate::converter::convert_streams::convert_streams_with_mode;
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{FormatOptions, OfxVersion, SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let convert = |name: &str, options: FormatOptions| {
+            let output = SharedBuffer::default();
+            convert_streams_with_mode(
+                Box::new(File::open(path.join(name)).unwrap()),
+                SupportedFormats::Ofx,
+                Box::new(output.clone()),
+                SupportedFormats::Ofx,
+                &XmlWriteOptions::default(),
+                ConversionContext::default().with_options(options),
+            )
+            .unwrap();
+            String::from_utf8(output.take()).unwrap()
+        };
+
+        // Without a version OFX is copied, with one it is rewritten
+        let xml = fs::read_to_string(path.join("valid2.ofx")).unwrap();
+        assert_eq!(convert("valid2.ofx", FormatOptions::new()), xml);
+        let sgml = convert(
+            "valid2.ofx",
+            FormatOptions::new().ofx_version(OfxVersion::V1),
+        );
+        assert!(sgml.starts_with("OFXHEADER:100\n"), "{}", sgml);
+        let xml = convert(
+            "valid1.ofx",
+            FormatOptions::new().ofx_version(OfxVersion::V2),
+        );
+        assert!(xml.contains("VERSION=\"220\""), "{}", xml);
+        let read = Ofx::from_read(sgml.as_bytes()).unwrap();
+        let original = Ofx::from_read(File::open(path.join("valid2.ofx")).unwrap()).unwrap();
+        assert_eq!(
+            read.statements[0].transactions,
+            original.statements[0].transactions
+        );
+    }
+}
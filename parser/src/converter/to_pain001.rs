@@ -0,0 +1,692 @@
+use crate::ParserError;
+use crate::camt053::balance::{SignedAmount, date_of, transactions};
+use crate::camt053::format::*;
+use crate::converter::to_camt053::{CsvColumns, csv_entry_line, locate_record};
+use crate::converter::to_mt940::entry_error;
+use crate::csv::format::CsvWrapper;
+use crate::errors::{Recovery, WarningKind};
+use crate::json::format::{JsonAccount, JsonEntryLine};
+use crate::pain001::format::*;
+
+use chrono::NaiveDate;
+
+/// SEPA allows a single unstructured remittance line of 140 characters.
+const REMITTANCE_LENGTH: usize = 140;
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// An account identified by its IBAN or else its other id.
+fn party_account(iban: Option<&str>, other: Option<&str>) -> Option<Pain001Account> {
+    let iban = non_empty(iban);
+    let other = non_empty(other).filter(|_| iban.is_none());
+    (iban.is_some() || other.is_some()).then(|| Pain001Account {
+        id: Some(Pain001AccountId {
+            iban: iban.map(str::to_string),
+            other: other.map(|id| Pain001OtherAccountId {
+                id: Some(id.to_string()),
+            }),
+        }),
+        ccy: None,
+    })
+}
+
+/// The agent of a BIC, `None` without one.
+fn bic_agent(bic: Option<&str>) -> Option<Pain001Agent> {
+    non_empty(bic).map(|bic| Pain001Agent {
+        fin_instn_id: Some(Pain001FinancialInstitution {
+            bic: Some(bic.to_string()),
+        }),
+    })
+}
+
+/// The amount of a debit, which is what a credit transfer pays.
+fn debit_amount(
+    value: Option<&str>,
+    cdt_dbt_ind: Option<&str>,
+) -> Result<SignedAmount, ParserError> {
+    if !matches!(cdt_dbt_ind.map(str::trim), Some("DBIT" | "D")) {
+        return Err(ParserError::Converter(
+            "Only debits can be initiated as credit transfers".to_string(),
+        ));
+    }
+    value
+        .and_then(SignedAmount::parse)
+        .ok_or_else(|| ParserError::Converter("Missing or invalid amount".to_string()))
+}
+
+/// The remittance lines joined into one, cut at [`REMITTANCE_LENGTH`].
+fn remittance<'a>(lines: impl Iterator<Item = &'a str>) -> Option<Pain001RemittanceInformation> {
+    let lines: Vec<&str> = lines
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let line: String = lines.join(" ").chars().take(REMITTANCE_LENGTH).collect();
+    (!line.is_empty()).then(|| Pain001RemittanceInformation { ustrd: vec![line] })
+}
+
+/// The debtor of the payments from one account: its owner, the account
+/// and the bank servicing it, as far as the input names them.
+#[derive(Debug, Default)]
+struct Debtor {
+    name: Option<String>,
+    account: Option<Pain001Account>,
+    agent: Option<Pain001Agent>,
+}
+
+/// One credit transfer, with the debtor name and agent its entry names.
+#[derive(Debug)]
+struct Transfer {
+    date: NaiveDate,
+    transaction: CreditTransferTransaction,
+    value: SignedAmount,
+    debtor: Option<String>,
+    debtor_agent: Option<Pain001Agent>,
+}
+
+/// The credit transfers from one debtor account, e.g. of one statement.
+#[derive(Debug)]
+struct Payments {
+    /// Identifies the payment information blocks.
+    id: String,
+    debtor: Debtor,
+    transfers: Vec<Transfer>,
+}
+
+/// The payment information block of the transfers from one account on one
+/// execution date. The debtor is named by the account owner or else the
+/// first transfer; the debtor agent is the first transfer's or else the
+/// account servicer.
+fn payment_information(
+    debtor: &Debtor,
+    pmt_inf_id: String,
+    date: NaiveDate,
+    transfers: Vec<Transfer>,
+) -> Result<PaymentInformation, ParserError> {
+    let name = debtor
+        .name
+        .clone()
+        .or_else(|| transfers.iter().find_map(|t| t.debtor.clone()))
+        .ok_or_else(|| ParserError::Converter("Missing debtor name".to_string()))?;
+    let account = debtor
+        .account
+        .clone()
+        .ok_or_else(|| ParserError::Converter("Missing debtor account".to_string()))?;
+    let agent = transfers
+        .iter()
+        .find_map(|t| t.debtor_agent.clone())
+        .or_else(|| debtor.agent.clone())
+        .ok_or_else(|| ParserError::Converter("Missing debtor agent BIC".to_string()))?;
+
+    let sepa = transfers.iter().all(|t| {
+        let amount = t
+            .transaction
+            .amt
+            .as_ref()
+            .and_then(|a| a.instd_amt.as_ref());
+        amount.and_then(|a| a.currency.as_deref()) == Some("EUR")
+    });
+    let total = transfers
+        .iter()
+        .fold(SignedAmount::default(), |sum, t| sum.add(t.value));
+
+    Ok(PaymentInformation {
+        pmt_inf_id: Some(pmt_inf_id),
+        pmt_mtd: Some("TRF".to_string()),
+        nb_of_txs: Some(transfers.len().to_string()),
+        ctrl_sum: Some(total.magnitude()),
+        pmt_tp_inf: sepa.then(|| PaymentTypeInformation {
+            svc_lvl: Some(ServiceLevel {
+                cd: Some("SEPA".to_string()),
+            }),
+        }),
+        reqd_exctn_dt: Some(RequestedExecutionDate {
+            date: Some(date.format("%Y-%m-%d").to_string()),
+            ..Default::default()
+        }),
+        dbtr: Some(Pain001Party { name: Some(name) }),
+        dbtr_acct: Some(account),
+        dbtr_agt: Some(agent),
+        chrg_br: sepa.then(|| "SLEV".to_string()),
+        cdt_trf_tx_inf: transfers.into_iter().map(|t| t.transaction).collect(),
+    })
+}
+
+/// The initiation of all `payments`, one payment information block per
+/// debtor account and requested execution date.
+///
+/// Blocks are identified by the id of their payments, followed by the date
+/// when they are on several dates. In lenient mode payments without a
+/// debtor are left out with a warning.
+fn initiation(
+    msg_id: &str,
+    created: Option<&str>,
+    payments: Vec<Payments>,
+    recovery: &mut Recovery,
+) -> Result<Pain001, ParserError> {
+    let mut pmt_inf = Vec::new();
+    for payments in payments {
+        let mut groups: Vec<(NaiveDate, Vec<Transfer>)> = Vec::new();
+        for transfer in payments.transfers {
+            match groups.iter_mut().find(|(date, _)| *date == transfer.date) {
+                Some((_, transfers)) => transfers.push(transfer),
+                None => groups.push((transfer.date, vec![transfer])),
+            }
+        }
+
+        let several = groups.len() > 1;
+        for (date, transfers) in groups {
+            let pmt_inf_id = if several {
+                format!("{}-{}", payments.id, date.format("%Y%m%d"))
+            } else {
+                payments.id.clone()
+            };
+            match payment_information(&payments.debtor, pmt_inf_id, date, transfers) {
+                Ok(block) => pmt_inf.push(block),
+                Err(e) => {
+                    let error = ParserError::Converter(format!("statement {}: {}", payments.id, e));
+                    recovery.recover(WarningKind::SkippedStatement, error)?;
+                }
+            }
+        }
+    }
+
+    let amounts = pmt_inf.iter().filter_map(|block| block.ctrl_sum.as_deref());
+    let total = amounts
+        .filter_map(SignedAmount::parse)
+        .fold(SignedAmount::default(), SignedAmount::add);
+    let count: usize = pmt_inf.iter().map(|block| block.cdt_trf_tx_inf.len()).sum();
+    let initiating_party = pmt_inf.first().and_then(|block| block.dbtr.clone());
+
+    Ok(Pain001 {
+        xmlns: None,
+        cstmr_cdt_trf_initn: CustomerCreditTransferInitiation {
+            grp_hdr: Pain001GroupHeader {
+                msg_id: Some(msg_id.to_string()),
+                cre_dt_tm: Some(match non_empty(created) {
+                    Some(created) => created.to_string(),
+                    None => chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+                }),
+                nb_of_txs: Some(count.to_string()),
+                ctrl_sum: Some(total.magnitude()),
+                initg_pty: initiating_party,
+            },
+            pmt_inf,
+        },
+        version: Pain001Version::default(),
+    })
+}
+
+/// The requested execution date of a payment: the value date of the entry
+/// or else its booking date.
+fn execution_date(entry: &Entry) -> Result<NaiveDate, ParserError> {
+    entry
+        .val_dt
+        .as_ref()
+        .and_then(date_of)
+        .or_else(|| entry.bookg_dt.as_ref().and_then(date_of))
+        .ok_or_else(|| ParserError::Converter("Missing value and booking date".to_string()))
+}
+
+fn agent_of(agent: &BranchAndFinancialInstitutionIdentification) -> Option<Pain001Agent> {
+    bic_agent(agent.fin_instn_id.as_ref()?.bic.as_deref())
+}
+
+/// The credit transfer of a debit entry, with its amount in `currency`
+/// unless the entry names its own.
+fn credit_transfer(entry: &Entry, currency: Option<&str>) -> Result<Transfer, ParserError> {
+    let amt = entry.amt.as_ref();
+    let value = debit_amount(
+        amt.and_then(|a| a.value.as_deref()),
+        entry.cdt_dbt_ind.as_deref(),
+    )?;
+    let currency = non_empty(amt.and_then(|a| a.currency.as_deref()))
+        .or(currency)
+        .ok_or_else(|| ParserError::Converter("Missing currency".to_string()))?;
+    let date = execution_date(entry)?;
+
+    let refs = transactions(entry).find_map(|tx| tx.refs.as_ref());
+    let parties = transactions(entry).find_map(|tx| tx.rltd_pties.as_ref());
+    let agents = || transactions(entry).filter_map(|tx| tx.rltd_agts.as_ref());
+    let creditor = parties
+        .and_then(|p| non_empty(p.cdtr.as_ref()?.name.as_deref()))
+        .ok_or_else(|| ParserError::Converter("Missing creditor name".to_string()))?;
+    let creditor_account = parties
+        .and_then(|p| p.cdtr_acct.as_ref())
+        .and_then(|account| {
+            let other = account.other.as_ref().and_then(|other| other.id.as_deref());
+            party_account(account.iban.as_deref(), other)
+        })
+        .ok_or_else(|| ParserError::Converter("Missing creditor account".to_string()))?;
+
+    let transaction = CreditTransferTransaction {
+        pmt_id: Some(PaymentIdentification {
+            instr_id: non_empty(refs.and_then(|r| r.instr_id.as_deref())).map(str::to_string),
+            end_to_end_id: Some(
+                non_empty(refs.and_then(|r| r.end_to_end_id.as_deref()))
+                    .unwrap_or("NOTPROVIDED")
+                    .to_string(),
+            ),
+        }),
+        amt: Some(AmountType {
+            instd_amt: Some(Pain001Amount {
+                currency: Some(currency.to_string()),
+                value: Some(value.magnitude()),
+            }),
+        }),
+        cdtr_agt: agents().find_map(|a| agent_of(a.cdtr_agt.as_ref()?)),
+        cdtr: Some(Pain001Party {
+            name: Some(creditor.to_string()),
+        }),
+        cdtr_acct: Some(creditor_account),
+        rmt_inf: remittance(
+            transactions(entry)
+                .filter_map(|tx| tx.rmt_inf.as_ref())
+                .flat_map(|rmt| rmt.ustrd.iter().map(String::as_str)),
+        ),
+    };
+    Ok(Transfer {
+        date,
+        transaction,
+        value,
+        debtor: parties
+            .and_then(|p| non_empty(p.dbtr.as_ref()?.name.as_deref()))
+            .map(str::to_string),
+        debtor_agent: agents().find_map(|a| agent_of(a.dbtr_agt.as_ref()?)),
+    })
+}
+
+/// The statement account as debtor, named by its owner and serviced by
+/// its bank.
+fn statement_debtor(stmt: &Statement) -> Debtor {
+    let acct = stmt.acct.as_ref();
+    Debtor {
+        name: non_empty(acct.and_then(|a| a.name.as_deref())).map(str::to_string),
+        account: acct.and_then(|a| {
+            let id = a.id.as_ref()?;
+            Some(Pain001Account {
+                id: Some(Pain001AccountId {
+                    iban: id.iban.clone(),
+                    other: id.other.as_ref().map(|other| Pain001OtherAccountId {
+                        id: other.id.clone(),
+                    }),
+                }),
+                ccy: a.ccy.clone(),
+            })
+        }),
+        agent: acct.and_then(|a| agent_of(a.svcr.as_ref()?)),
+    }
+}
+
+impl TryFrom<&Camt053> for Pain001 {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        camt053_to_pain001(camt, &mut Recovery::default())
+    }
+}
+
+/// Converts the debit entries of every statement into credit transfers
+/// from the statement account, one payment information block per
+/// requested execution date.
+///
+/// Blocks are identified by the statement id, followed by the date when a
+/// statement has payments on several dates. In lenient mode entries and
+/// statements that cannot be initiated are left out with a warning.
+pub(crate) fn camt053_to_pain001(
+    camt: &Camt053,
+    recovery: &mut Recovery,
+) -> Result<Pain001, ParserError> {
+    let grp_hdr = &camt.bk_to_cstmr_stmt.grp_hdr;
+    let stmts = &camt.bk_to_cstmr_stmt.stmts;
+    let msg_id = non_empty(grp_hdr.msg_id.as_deref())
+        .or_else(|| stmts.iter().find_map(|stmt| non_empty(stmt.id.as_deref())))
+        .ok_or_else(|| ParserError::Converter("Missing message id".to_string()))?;
+
+    let mut payments = Vec::new();
+    for (stmt_index, stmt) in stmts.iter().enumerate() {
+        let currency = stmt.acct.as_ref().and_then(|a| a.ccy.as_deref());
+        let mut transfers = Vec::new();
+        for (index, entry) in stmt.ntry.iter().enumerate() {
+            match credit_transfer(entry, currency) {
+                Ok(transfer) => transfers.push(transfer),
+                Err(e) => {
+                    recovery.recover(WarningKind::SkippedEntry, entry_error(stmt, index, e))?
+                }
+            }
+        }
+        payments.push(Payments {
+            id: non_empty(stmt.id.as_deref())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}-{}", msg_id, stmt_index + 1)),
+            debtor: statement_debtor(stmt),
+            transfers,
+        });
+    }
+
+    initiation(msg_id, grp_hdr.cre_dt_tm.as_deref(), payments, recovery)
+}
+
+/// The credit transfer of a debit row of the statement CSV layout, with
+/// its amount in the account currency unless the row names its own.
+fn csv_credit_transfer(line: &JsonEntryLine) -> Result<Transfer, ParserError> {
+    let entry = &line.entry;
+    let amount = entry.amount.as_ref();
+    let value = debit_amount(
+        amount.map(|a| a.value.as_str()),
+        entry.credit_debit.as_deref(),
+    )?;
+    let currency = non_empty(amount.and_then(|a| a.currency.as_deref()))
+        .or_else(|| non_empty(line.account.as_ref()?.currency.as_deref()))
+        .ok_or_else(|| ParserError::Converter("Missing currency".to_string()))?;
+    let dates = [
+        &entry.value_date,
+        &entry.value_date_time,
+        &entry.booking_date,
+        &entry.booking_date_time,
+    ];
+    let date = dates
+        .into_iter()
+        .find_map(|date| NaiveDate::parse_from_str(date.as_deref()?.get(..10)?, "%Y-%m-%d").ok())
+        .ok_or_else(|| ParserError::Converter("Missing value and booking date".to_string()))?;
+
+    let details = entry.details.first();
+    let creditor = non_empty(details.and_then(|d| d.creditor.as_deref()))
+        .ok_or_else(|| ParserError::Converter("Missing creditor name".to_string()))?;
+    let creditor_account = details
+        .and_then(|d| d.creditor_account.as_ref())
+        .and_then(|a| party_account(a.iban.as_deref(), a.other_id.as_deref()))
+        .ok_or_else(|| ParserError::Converter("Missing creditor account".to_string()))?;
+    let end_to_end_id = details
+        .and_then(|d| d.references.as_ref())
+        .and_then(|r| non_empty(r.end_to_end_id.as_deref()));
+
+    let transaction = CreditTransferTransaction {
+        pmt_id: Some(PaymentIdentification {
+            instr_id: None,
+            end_to_end_id: Some(end_to_end_id.unwrap_or("NOTPROVIDED").to_string()),
+        }),
+        amt: Some(AmountType {
+            instd_amt: Some(Pain001Amount {
+                currency: Some(currency.to_string()),
+                value: Some(value.magnitude()),
+            }),
+        }),
+        cdtr_agt: bic_agent(details.and_then(|d| d.creditor_agent.as_deref())),
+        cdtr: Some(Pain001Party {
+            name: Some(creditor.to_string()),
+        }),
+        cdtr_acct: Some(creditor_account),
+        rmt_inf: remittance(
+            details
+                .iter()
+                .flat_map(|d| d.remittance_information.iter().map(String::as_str)),
+        ),
+    };
+    Ok(Transfer {
+        date,
+        transaction,
+        value,
+        debtor: non_empty(details.and_then(|d| d.debtor.as_deref())).map(str::to_string),
+        debtor_agent: bic_agent(details.and_then(|d| d.debtor_agent.as_deref())),
+    })
+}
+
+/// The account of CSV rows as debtor, named by the `debtor` and serviced
+/// by the `debtor_agent` of the first of its `transfers` with one.
+fn csv_debtor(account: &Option<JsonAccount>, transfers: &[Transfer]) -> Debtor {
+    let account = account.as_ref();
+    Debtor {
+        name: transfers.iter().find_map(|t| t.debtor.clone()),
+        account: account
+            .and_then(|a| party_account(a.iban.as_deref(), a.other_id.as_deref()))
+            .map(|debtor_account| Pain001Account {
+                ccy: account.and_then(|a| non_empty(a.currency.as_deref()).map(str::to_string)),
+                ..debtor_account
+            }),
+        agent: transfers.iter().find_map(|t| t.debtor_agent.clone()),
+    }
+}
+
+/// Converts the debit rows of the statement CSV layout, see
+/// [`CSV_COLUMNS`](crate::csv::format::CSV_COLUMNS), into credit transfers
+/// without going through a statement.
+///
+/// The rows of one statement id and account are paid from that account,
+/// one payment information block per requested execution date: the value
+/// date of the row or else its booking date. The debtor and its bank are
+/// given by the `debtor` and `debtor_agent` columns of any row of the
+/// account. In lenient mode rows and accounts that cannot be initiated are
+/// left out with a warning.
+pub(crate) fn csv_to_pain001(
+    csv: &CsvWrapper,
+    recovery: &mut Recovery,
+) -> Result<Pain001, ParserError> {
+    let Some((header, rows)) = csv.0.split_first() else {
+        return Err(ParserError::Csv("Missing header row".to_string()));
+    };
+    let columns = CsvColumns::new(header)?;
+
+    let mut msg_id = None;
+    let mut payments: Vec<(Option<String>, Option<JsonAccount>, Vec<Transfer>)> = Vec::new();
+    for record in rows {
+        let transfer = csv_entry_line(&columns, record)
+            .and_then(|line| Ok((csv_credit_transfer(&line)?, line)));
+        let (transfer, line) = match transfer {
+            Ok(transfer) => transfer,
+            Err(e) => {
+                recovery.recover(WarningKind::SkippedEntry, locate_record(record, e))?;
+                continue;
+            }
+        };
+        msg_id = msg_id.or(line.message_id).or(line.statement_id.clone());
+        let key = (line.statement_id, line.account);
+        match payments
+            .iter_mut()
+            .find(|(id, account, _)| (id, account) == (&key.0, &key.1))
+        {
+            Some((_, _, transfers)) => transfers.push(transfer),
+            None => payments.push((key.0, key.1, vec![transfer])),
+        }
+    }
+    let msg_id = msg_id.ok_or_else(|| ParserError::Converter("Missing message id".to_string()))?;
+
+    let payments = payments
+        .into_iter()
+        .enumerate()
+        .map(|(index, (id, account, transfers))| Payments {
+            id: id.unwrap_or_else(|| format!("{}-{}", msg_id, index + 1)),
+            debtor: csv_debtor(&account, &transfers),
+            transfers,
+        })
+        .collect();
+    initiation(&msg_id, None, payments, recovery)
+}
+
+impl TryFrom<&CsvWrapper> for Pain001 {
+    type Error = ParserError;
+
+    fn try_from(csv: &CsvWrapper) -> Result<Self, Self::Error> {
+        csv_to_pain001(csv, &mut Recovery::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::path::PathBuf;
+
+    fn test_pain001() -> Pain001 {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let data = std::fs::read_to_string(path.join("valid1.pain001")).unwrap();
+        Pain001::from_read(data.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_pain001_round_trip() {
+        let pain001 = test_pain001();
+        let camt053 = Camt053::try_from(&pain001).unwrap();
+        let result = Pain001::try_from(&camt053).unwrap();
+
+        let (result, original) = (&result.cstmr_cdt_trf_initn, &pain001.cstmr_cdt_trf_initn);
+        assert_eq!(result.grp_hdr.msg_id, original.grp_hdr.msg_id);
+        assert_eq!(result.grp_hdr.cre_dt_tm, original.grp_hdr.cre_dt_tm);
+        assert_eq!(result.grp_hdr.nb_of_txs, original.grp_hdr.nb_of_txs);
+        assert_eq!(result.grp_hdr.ctrl_sum, original.grp_hdr.ctrl_sum);
+        assert_eq!(result.pmt_inf.len(), original.pmt_inf.len());
+        for (block, original) in result.pmt_inf.iter().zip(&original.pmt_inf) {
+            assert_eq!(block.pmt_inf_id, original.pmt_inf_id);
+            assert_eq!(block.ctrl_sum, original.ctrl_sum);
+            assert_eq!(
+                block.reqd_exctn_dt.as_ref().unwrap().value(),
+                original.reqd_exctn_dt.as_ref().unwrap().value()
+            );
+            assert_eq!(block.dbtr, original.dbtr);
+            assert_eq!(block.dbtr_acct, original.dbtr_acct);
+            assert_eq!(block.dbtr_agt, original.dbtr_agt);
+            assert_eq!(block.pmt_tp_inf, original.pmt_tp_inf);
+            assert_eq!(block.cdt_trf_tx_inf, original.cdt_trf_tx_inf);
+        }
+    }
+
+    #[test]
+    fn test_write_versions() {
+        let mut pain001 = test_pain001();
+
+        let mut data = Vec::new();
+        pain001.write_to(&mut data).unwrap();
+        let xml = String::from_utf8(data).unwrap();
+        assert!(xml.starts_with(
+            "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.001.001.09\"><CstmrCdtTrfInitn>"
+        ));
+        assert!(xml.contains("<ReqdExctnDt><Dt>2025-10-22</Dt></ReqdExctnDt>"));
+        assert!(xml.contains("<DbtrAgt><FinInstnId><BICFI>COBADEFFXXX</BICFI></FinInstnId>"));
+        // Missing values are left out rather than written as empty elements
+        assert!(!xml.contains("/>"));
+
+        pain001.version = Pain001Version::V03;
+        let mut data = Vec::new();
+        pain001.write_to(&mut data).unwrap();
+        let xml = String::from_utf8(data).unwrap();
+        assert!(xml.contains("pain.001.001.03"));
+        assert!(xml.contains("<ReqdExctnDt>2025-10-22</ReqdExctnDt>"));
+        assert!(xml.contains("<BIC>COBADEFFXXX</BIC>"));
+        let result = Pain001::from_read(xml.as_bytes()).unwrap();
+        assert_eq!(result.version, Pain001Version::V03);
+        assert_eq!(
+            result.cstmr_cdt_trf_initn.pmt_inf[0].cdt_trf_tx_inf,
+            pain001.cstmr_cdt_trf_initn.pmt_inf[0].cdt_trf_tx_inf
+        );
+    }
+
+    #[test]
+    fn test_convert_camt053_to_pain001_credit() {
+        let mut camt053 = Camt053::try_from(&test_pain001()).unwrap();
+        camt053.bk_to_cstmr_stmt.stmts[0].ntry[0].cdt_dbt_ind = Some("CRDT".to_string());
+
+        let error = Pain001::try_from(&camt053).unwrap_err();
+        assert!(error.to_string().contains("Only debits"));
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        let result = camt053_to_pain001(&camt053, &mut recovery).unwrap();
+        assert_eq!(
+            result.cstmr_cdt_trf_initn.grp_hdr.nb_of_txs.as_deref(),
+            Some("2")
+        );
+        assert_eq!(recovery.into_warnings()[0].kind, WarningKind::SkippedEntry);
+    }
+
+    #[test]
+    fn test_convert_streams_to_pain001_version() {
+        use crate::converter::convert_streams::convert_streams_with_mode;
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{FormatOptions, SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = std::path::PathBuf::from(manifest_dir).join("test_data/valid1.pain001");
+        let output = SharedBuffer::default();
+        convert_streams_with_mode(
+            Box::new(std::fs::File::open(&path).unwrap()),
+            SupportedFormats::Pain001,
+            Box::new(output.clone()),
+            SupportedFormats::Pain001,
+            &XmlWriteOptions::default(),
+            ConversionContext::default()
+                .with_options(FormatOptions::new().pain001_version(Pain001Version::V03)),
+        )
+        .unwrap();
+
+        let result = Pain001::from_read(output.take().as_slice()).unwrap();
+        assert_eq!(result.version, Pain001Version::V03);
+        assert_eq!(
+            result.cstmr_cdt_trf_initn.grp_hdr.ctrl_sum,
+            test_pain001().cstmr_cdt_trf_initn.grp_hdr.ctrl_sum
+        );
+    }
+
+    #[test]
+    fn test_convert_csv_to_pain001() {
+        let data = "statement_id,iban,account_currency,value_date,amount,currency,credit_debit,\
+                    end_to_end_id,debtor,creditor,remittance_information,creditor_account,\
+                    debtor_agent,creditor_agent\n\
+                    RUN-1,DE89370400440532013000,EUR,2025-10-22,1000.00,,DBIT,E2E-1,\
+                    Example GmbH,ACME SA,Invoice 1,FR7630006000011234567890189,\
+                    COBADEFFXXX,BNPAFRPPXXX\n\
+                    RUN-1,DE89370400440532013000,EUR,2025-10-24,175.5,EUR,DBIT,,,\
+                    Stadtwerke,,DE02120300000000202051,,\n\
+                    RUN-1,DE89370400440532013000,EUR,2025-10-24,20.00,EUR,CRDT,,,,,,,\n";
+        let csv = CsvWrapper::from_string(data).unwrap();
+
+        let error = Pain001::try_from(&csv).unwrap_err();
+        assert!(error.to_string().contains("Only debits"));
+        let mut recovery = Recovery::new(crate::ParseMode::Lenient);
+        let result = csv_to_pain001(&csv, &mut recovery).unwrap();
+        assert_eq!(recovery.into_warnings().len(), 1);
+
+        let initiation = &result.cstmr_cdt_trf_initn;
+        assert_eq!(initiation.grp_hdr.msg_id.as_deref(), Some("RUN-1"));
+        assert_eq!(initiation.grp_hdr.nb_of_txs.as_deref(), Some("2"));
+        assert_eq!(initiation.grp_hdr.ctrl_sum.as_deref(), Some("1175.50"));
+        let ids: Vec<_> = initiation
+            .pmt_inf
+            .iter()
+            .map(|block| block.pmt_inf_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["RUN-1-20251022", "RUN-1-20251024"]);
+
+        // The debtor and its bank of the first row pay the second one too
+        let block = &initiation.pmt_inf[1];
+        assert_eq!(
+            block.dbtr.as_ref().unwrap().name.as_deref(),
+            Some("Example GmbH")
+        );
+        assert_eq!(block.dbtr_agt, bic_agent(Some("COBADEFFXXX")));
+        let account = block.dbtr_acct.as_ref().unwrap();
+        assert_eq!(account.ccy.as_deref(), Some("EUR"));
+        let transaction = &block.cdt_trf_tx_inf[0];
+        assert_eq!(
+            transaction
+                .pmt_id
+                .as_ref()
+                .unwrap()
+                .end_to_end_id
+                .as_deref(),
+            Some("NOTPROVIDED")
+        );
+        assert_eq!(
+            transaction.amt.as_ref().unwrap().instd_amt,
+            Some(Pain001Amount {
+                currency: Some("EUR".to_string()),
+                value: Some("175.50".to_string()),
+            })
+        );
+        assert!(transaction.cdtr_agt.is_none());
+
+        let transaction = &initiation.pmt_inf[0].cdt_trf_tx_inf[0];
+        assert_eq!(transaction.cdtr_agt, bic_agent(Some("BNPAFRPPXXX")));
+        assert_eq!(transaction.rmt_inf.as_ref().unwrap().ustrd, ["Invoice 1"]);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::ParserError;
+use crate::camt053::balance::{entry_date, signed_amount};
+use crate::camt053::format::*;
+use crate::qif::format::*;
+use chrono::NaiveDate;
+
+/// The account a statement is filed under: its IBAN or other id, or the
+/// statement id when it names no account.
+fn account_name(stmt: &Statement) -> String {
+    stmt.acct
+        .as_ref()
+        .and_then(|a| a.id.as_ref())
+        .and_then(|id| {
+            let iban = id.iban.clone().filter(|iban| !iban.is_empty());
+            iban.or_else(|| id.other.as_ref()?.id.clone())
+        })
+        .filter(|name| !name.is_empty())
+        .or_else(|| stmt.id.clone())
+        .unwrap_or_default()
+}
+
+/// The date of entries without one: the end of the statement period or the
+/// creation date. A balance date says nothing about when an entry was
+/// booked and is not used.
+fn statement_date(stmt: &Statement) -> Option<NaiveDate> {
+    let period_end = stmt.fr_to_dt.as_ref().and_then(|p| p.to_dt_tm.as_deref());
+    period_end
+        .into_iter()
+        .chain(stmt.cre_dt_tm.as_deref())
+        .find_map(|value| NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok())
+}
+
+/// A transaction with the counterparty as payee, the remittance information
+/// as memo and the bank's reference as number.
+fn transaction(entry: &Entry, date: Option<NaiveDate>) -> Result<QifTransaction, ParserError> {
+    let amount = signed_amount(&entry.amt, &entry.cdt_dbt_ind).ok_or_else(|| {
+        ParserError::Converter("Entry has no valid amount or credit/debit indicator".to_string())
+    })?;
+    let debit = amount.units < 0;
+    let date = entry_date(entry)
+        .or(date)
+        .ok_or_else(|| ParserError::Converter("Entry has no booking or value date".to_string()))?;
+    let details: Vec<&TransactionDetails> =
+        entry.ntry_dtls.iter().flat_map(|d| &d.tx_dtls).collect();
+    let payee = details
+        .iter()
+        .filter_map(|d| d.rltd_pties.as_ref())
+        .find_map(|p| match debit {
+            true => p.cdtr.as_ref()?.name.clone(),
+            false => p.dbtr.as_ref()?.name.clone(),
+        })
+        .unwrap_or_default();
+    let memo = details
+        .iter()
+        .filter_map(|d| d.rmt_inf.as_ref())
+        .flat_map(|r| &r.ustrd)
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let number = details
+        .iter()
+        .filter_map(|d| d.refs.as_ref())
+        .find_map(|r| {
+            [&r.acct_svcr_ref, &r.tx_id, &r.end_to_end_id]
+                .into_iter()
+                .flatten()
+                .map(|id| id.trim())
+                .find(|id| !id.is_empty() && *id != "NOTPROVIDED")
+        })
+        .unwrap_or_default()
+        .to_string();
+    Ok(QifTransaction {
+        date,
+        amount: match debit {
+            true => format!("-{}", amount.magnitude()),
+            false => amount.magnitude(),
+        },
+        payee,
+        memo,
+        number,
+    })
+}
+
+/// One `!Type:Bank` list per account, with the entries of all its
+/// statements in order. Balances have no place in QIF and are left out.
+impl TryFrom<&Camt053> for Qif {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        let mut accounts: Vec<QifAccount> = Vec::new();
+        for stmt in &camt.bk_to_cstmr_stmt.stmts {
+            let name = account_name(stmt);
+            let date = statement_date(stmt);
+            let transactions = stmt
+                .ntry
+                .iter()
+                .map(|entry| transaction(entry, date))
+                .collect::<Result<Vec<_>, _>>()?;
+            match accounts.iter_mut().find(|account| account.name == name) {
+                Some(account) => account.transactions.extend(transactions),
+                None => accounts.push(QifAccount {
+                    name,
+                    list_type: "Bank".to_string(),
+                    transactions,
+                }),
+            }
+        }
+        if accounts.is_empty() {
+            return Err(ParserError::Converter(
+                "Document has no statements".to_string(),
+            ));
+        }
+        Ok(Qif {
+            date_format: QifDateFormat::Us,
+            accounts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{FinancialDataRead, FinancialDataWrite};
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn read_camt053(name: &str) -> Camt053 {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data").join(name);
+        Camt053::from_read(File::open(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_convert_camt053_to_qif() {
+        let camt053 = read_camt053("valid1.camt053");
+
+        let qif = Qif::try_from(&camt053).unwrap();
+        assert_eq!(qif.accounts.len(), 1);
+        let account = &qif.accounts[0];
+        assert_eq!(account.name, "DE89370400440532013000");
+        assert_eq!(account.list_type, "Bank");
+
+        let transactions = &account.transactions;
+        assert_eq!(transactions.len(), 2);
+        assert!(!transactions[0].amount.starts_with('-'));
+        assert_eq!(transactions[0].number, "TXN123456");
+        assert!(transactions[1].amount.starts_with('-'));
+        assert_eq!(transactions[1].number, "ACCTREF789");
+
+        // A single list has no `!Account` block
+        let mut written = Vec::new();
+        qif.write_to(&mut written).unwrap();
+        let text = String::from_utf8(written).unwrap();
+        assert!(text.starts_with("!Type:Bank\nD"));
+        assert_eq!(text.matches("\n^\n").count(), 2);
+    }
+
+    #[test]
+    fn test_entry_dates() {
+        let mt940 =
+            crate::Mt940::from_read(crate::test_utils::read_test_file("valid1.mt940").as_bytes())
+                .unwrap();
+        let mut camt053 = Camt053::try_from(&mt940).unwrap();
+        let qif = Qif::try_from(&camt053).unwrap();
+        let mut written = Vec::new();
+        qif.write_to(&mut written).unwrap();
+        let text = String::from_utf8(written).unwrap();
+        assert_eq!(text.matches("\nD10/21/2025\n").count(), 3);
+
+        // The opening balance date is no date for an entry
+        let entry = &mut camt053.bk_to_cstmr_stmt.stmts[0].ntry[0];
+        entry.bookg_dt = None;
+        entry.val_dt = None;
+        assert!(Qif::try_from(&camt053).is_err());
+    }
+
+    #[test]
+    fn test_payee_memo_and_date_format() {
+        let mut camt053 = read_camt053("valid1.camt053");
+        let mut second = camt053.bk_to_cstmr_stmt.stmts[0].clone();
+        second.acct = None;
+        second.id = Some("SECOND".to_string());
+        camt053.bk_to_cstmr_stmt.stmts.push(second);
+
+        let mut qif = Qif::try_from(&camt053).unwrap();
+        assert_eq!(qif.accounts[1].name, "SECOND");
+        let credit = &qif.accounts[0].transactions[0];
+        let entry = &camt053.bk_to_cstmr_stmt.stmts[0].ntry[0];
+        let details = &entry.ntry_dtls[0].tx_dtls[0];
+        assert_eq!(
+            Some(credit.payee.clone()),
+            details
+                .rltd_pties
+                .as_ref()
+                .unwrap()
+                .dbtr
+                .as_ref()
+                .unwrap()
+                .name
+        );
+        assert_eq!(
+            credit.memo,
+            details.rmt_inf.as_ref().unwrap().ustrd.join(" ")
+        );
+
+        qif.date_format = QifDateFormat::Eu;
+        let mut written = Vec::new();
+        qif.write_to(&mut written).unwrap();
+        let text = String::from_utf8(written).unwrap();
+        assert!(text.starts_with("!Account\nNDE89370400440532013000\nTBank\n^\n!Type:Bank\n"));
+        assert!(text.contains("!Account\nNSECOND\nTBank\n^\n"));
+        let date = credit.date.format("%d/%m/%Y").to_string();
+        assert!(text.contains(&format!("\nD{}\n", date)));
+
+        let (read, _) =
+            Qif::from_read_with_format(text.as_bytes(), QifDateFormat::Eu, Default::default())
+                .unwrap();
+        assert_eq!(read, qif);
+    }
+}
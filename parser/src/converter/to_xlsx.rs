@@ -0,0 +1,540 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, account_id, balance_code, date_of, entry_date, find_balance, signed_amount,
+};
+use crate::camt053::format::*;
+use crate::converter::to_csv::csv_record;
+use crate::converter::to_json::{json_entry, json_statement_account};
+use crate::csv::format::*;
+use crate::json::format::JsonEntryLine;
+use crate::xlsx::format::*;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// Index of column `name` of [`CSV_COLUMNS`].
+fn column(name: &str) -> usize {
+    CSV_COLUMNS
+        .iter()
+        .position(|c| *c == name)
+        .unwrap_or_default()
+}
+
+/// A row without its trailing empty cells.
+fn row(mut cells: Vec<Cell>) -> Vec<Cell> {
+    while cells.last() == Some(&Cell::Empty) {
+        cells.pop();
+    }
+    cells
+}
+
+/// A date column value as a date cell; date-times with an offset stay text.
+fn date_cell(value: &str) -> Cell {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Cell::Date(date);
+    }
+    match NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f") {
+        Ok(date_time) => Cell::DateTime(date_time),
+        Err(_) => Cell::text(value),
+    }
+}
+
+fn signed_value(amount: SignedAmount) -> String {
+    let sign = if amount.units < 0 { "-" } else { "" };
+    format!("{}{}", sign, amount.magnitude())
+}
+
+/// A CSV row as typed cells: dates, and amounts in the number format of
+/// their currency.
+fn entry_row(line: &JsonEntryLine, account_currency: &Option<String>) -> Vec<Cell> {
+    let record = csv_record(line);
+    let currency = Some(&record[column("currency")])
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .or_else(|| account_currency.clone());
+    let cells = CSV_COLUMNS
+        .iter()
+        .zip(record.iter())
+        .map(|(name, value)| match *name {
+            _ if value.is_empty() => Cell::Empty,
+            "booking_date" | "value_date" => date_cell(value),
+            "amount" => Cell::Number {
+                value: value.to_string(),
+                currency: currency.clone(),
+            },
+            _ => Cell::text(value),
+        })
+        .collect();
+    row(cells)
+}
+
+/// The first date of a date-time such as `2025-10-01T00:00:00+02:00`.
+fn date_of_text(value: &Option<String>) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.as_deref()?.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn balance_row(label: &str, balance: Option<&Balance>, currency: &Option<String>) -> Vec<Cell> {
+    let Some(balance) = balance else {
+        return vec![Cell::text(label)];
+    };
+    let amount = signed_amount(&balance.amt, &balance.cdt_dbt_ind).map(signed_value);
+    let currency = balance
+        .amt
+        .as_ref()
+        .and_then(|amt| amt.currency.clone())
+        .or_else(|| currency.clone());
+    let date = balance.dt.as_ref().and_then(date_of);
+    row(vec![
+        Cell::text(label),
+        Cell::amount(amount, currency),
+        date.map_or(Cell::Empty, Cell::Date),
+    ])
+}
+
+/// The statements of one sheet: one statement, or all of an account.
+struct SheetStatements<'a> {
+    msg_id: &'a Option<String>,
+    stmts: Vec<&'a Statement>,
+}
+
+impl SheetStatements<'_> {
+    fn currency(&self) -> Option<String> {
+        self.stmts
+            .iter()
+            .find_map(|stmt| stmt.acct.as_ref()?.ccy.clone())
+    }
+
+    /// The period of the statements, or else the dates of their entries.
+    fn period(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        let entry_dates = || {
+            self.stmts
+                .iter()
+                .flat_map(|stmt| &stmt.ntry)
+                .filter_map(entry_date)
+        };
+        let from = self
+            .stmts
+            .first()
+            .and_then(|stmt| date_of_text(&stmt.fr_to_dt.as_ref()?.fr_dt_tm))
+            .or_else(|| entry_dates().min());
+        let to = self
+            .stmts
+            .last()
+            .and_then(|stmt| date_of_text(&stmt.fr_to_dt.as_ref()?.to_dt_tm))
+            .or_else(|| entry_dates().max());
+        (from, to)
+    }
+
+    /// Account, period and balances above the entries.
+    fn header(&self) -> Vec<Vec<Cell>> {
+        let currency = self.currency();
+        let ids: Vec<&str> = self
+            .stmts
+            .iter()
+            .filter_map(|stmt| stmt.id.as_deref())
+            .collect();
+        let label = if self.stmts.len() > 1 {
+            "Statements"
+        } else {
+            "Statement"
+        };
+        let first = self.stmts.first();
+        let account = first.and_then(|stmt| account_id(stmt)).unwrap_or_default();
+        let name = first
+            .and_then(|stmt| stmt.acct.as_ref()?.name.clone())
+            .unwrap_or_default();
+        let (from, to) = self.period();
+
+        let opening = first.and_then(|stmt| {
+            stmt.bal
+                .iter()
+                .find(|b| matches!(balance_code(b), Some("OPBD" | "PRCD")))
+        });
+        let closing = self
+            .stmts
+            .last()
+            .and_then(|stmt| find_balance(stmt, "CLBD"));
+
+        vec![
+            row(vec![Cell::text(label), Cell::text(ids.join(", "))]),
+            row(vec![
+                Cell::text("Account"),
+                Cell::text(account),
+                Cell::text(name),
+            ]),
+            row(vec![
+                Cell::text("Currency"),
+                Cell::text(currency.clone().unwrap_or_default()),
+            ]),
+            row(vec![
+                Cell::text("Period"),
+                from.map_or(Cell::Empty, Cell::Date),
+                to.map_or(Cell::Empty, Cell::Date),
+            ]),
+            balance_row("Opening balance", opening, &currency),
+            balance_row("Closing balance", closing, &currency),
+        ]
+    }
+
+    fn lines(&self) -> Vec<JsonEntryLine> {
+        self.stmts
+            .iter()
+            .flat_map(|stmt| {
+                let account = stmt.acct.as_ref().map(json_statement_account);
+                stmt.ntry.iter().map(move |entry| JsonEntryLine {
+                    message_id: self.msg_id.clone(),
+                    statement_id: stmt.id.clone(),
+                    account: account.clone(),
+                    entry: json_entry(entry),
+                })
+            })
+            .collect()
+    }
+
+    /// Total credits and debits per currency, under the entries from row
+    /// `first` to `last` (1-based), as formulas with their values.
+    fn totals(&self, lines: &[JsonEntryLine], first: usize, last: usize) -> Vec<Vec<Cell>> {
+        let account_currency = self.currency();
+        let mut currencies: Vec<(Option<String>, SignedAmount, SignedAmount)> = Vec::new();
+        for line in lines {
+            let amount = line.entry.amount.as_ref();
+            let currency = amount
+                .and_then(|a| a.currency.clone())
+                .or_else(|| account_currency.clone());
+            let Some(value) = amount.and_then(|a| SignedAmount::parse(&a.value)) else {
+                continue;
+            };
+            let index = match currencies.iter().position(|(c, _, _)| *c == currency) {
+                Some(index) => index,
+                None => {
+                    currencies.push((currency, SignedAmount::default(), SignedAmount::default()));
+                    currencies.len() - 1
+                }
+            };
+            let (_, credits, debits) = &mut currencies[index];
+            match line.entry.credit_debit.as_deref() {
+                Some("CRDT") => *credits = credits.add(value),
+                Some("DBIT") => *debits = debits.add(value),
+                _ => {}
+            }
+        }
+
+        let range = |name: &str| {
+            let letters = column_name(column(name));
+            format!("{}{}:{}{}", letters, first, letters, last)
+        };
+        // Amounts in different currencies are only added up with their own
+        let single = currencies.len() == 1;
+        let mut rows = Vec::new();
+        for (currency, credits, debits) in currencies {
+            for (label, indicator, total) in [
+                ("Total credits", "CRDT", credits),
+                ("Total debits", "DBIT", debits),
+            ] {
+                let formula = if single {
+                    format!(
+                        "SUMIF({},\"{}\",{})",
+                        range("credit_debit"),
+                        indicator,
+                        range("amount")
+                    )
+                } else {
+                    format!(
+                        "SUMIFS({},{},\"{}\",{},\"{}\")",
+                        range("amount"),
+                        range("credit_debit"),
+                        indicator,
+                        range("currency"),
+                        currency.as_deref().unwrap_or_default()
+                    )
+                };
+                let mut cells = vec![Cell::Empty; column("currency") + 1];
+                cells[0] = Cell::text(label);
+                cells[column("amount")] = Cell::Formula {
+                    formula,
+                    value: total.magnitude(),
+                    currency: currency.clone(),
+                };
+                cells[column("currency")] = Cell::text(currency.clone().unwrap_or_default());
+                rows.push(row(cells));
+            }
+        }
+        rows
+    }
+
+    fn sheet(&self, name: String) -> Sheet {
+        let mut rows = self.header();
+        rows.push(Vec::new());
+        rows.push(CSV_COLUMNS.iter().map(|name| Cell::text(*name)).collect());
+
+        let lines = self.lines();
+        let currency = self.currency();
+        let first = rows.len() + 1;
+        rows.extend(lines.iter().map(|line| entry_row(line, &currency)));
+        if !lines.is_empty() {
+            let last = rows.len();
+            rows.push(Vec::new());
+            rows.extend(self.totals(&lines, first, last));
+        }
+        Sheet { name, rows }
+    }
+}
+
+/// A valid sheet name, unique among `taken`: at most 31 characters,
+/// without `[]:*?/\` and not starting or ending with an apostrophe.
+fn sheet_name(name: &str, taken: &[String]) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if "[]:*?/\\".contains(c) { '_' } else { c })
+        .collect();
+    let name = name.trim().trim_matches('\'');
+    let name = if name.is_empty() { "Statement" } else { name };
+    let is_taken = |candidate: &str| {
+        taken
+            .iter()
+            .any(|t| t.to_lowercase() == candidate.to_lowercase())
+    };
+
+    let mut number = 1;
+    loop {
+        let suffix = if number == 1 {
+            String::new()
+        } else {
+            format!(" ({})", number)
+        };
+        let kept = 31 - suffix.chars().count();
+        let candidate = format!("{}{}", name.chars().take(kept).collect::<String>(), suffix);
+        if !is_taken(&candidate) {
+            return candidate;
+        }
+        number += 1;
+    }
+}
+
+/// Converts the statements into a workbook, one sheet per statement or per
+/// account, see [`SheetLayout`].
+///
+/// Each sheet has the account, period and opening and closing balances on
+/// top, then the entries in the [`CSV_COLUMNS`], with typed dates and
+/// amounts, and the total credits and debits per currency.
+pub(crate) fn camt053_to_xlsx(camt: &Camt053, layout: SheetLayout) -> Result<Xlsx, ParserError> {
+    let msg_id = &camt.bk_to_cstmr_stmt.grp_hdr.msg_id;
+    let mut groups: Vec<(String, SheetStatements)> = Vec::new();
+    for (index, stmt) in camt.bk_to_cstmr_stmt.stmts.iter().enumerate() {
+        match layout {
+            SheetLayout::Statement => {
+                let name = stmt
+                    .id
+                    .clone()
+                    .unwrap_or_else(|| format!("Statement {}", index + 1));
+                groups.push((
+                    name,
+                    SheetStatements {
+                        msg_id,
+                        stmts: vec![stmt],
+                    },
+                ));
+            }
+            SheetLayout::Account => {
+                let name = account_id(stmt).unwrap_or_else(|| "Account".to_string());
+                match groups.iter_mut().find(|(account, _)| *account == name) {
+                    Some((_, group)) => group.stmts.push(stmt),
+                    None => groups.push((
+                        name,
+                        SheetStatements {
+                            msg_id,
+                            stmts: vec![stmt],
+                        },
+                    )),
+                }
+            }
+        }
+    }
+
+    let mut sheets: Vec<Sheet> = Vec::new();
+    for (name, group) in &groups {
+        let taken: Vec<String> = sheets.iter().map(|sheet| sheet.name.clone()).collect();
+        sheets.push(group.sheet(sheet_name(name, &taken)));
+    }
+    if sheets.is_empty() {
+        // Like an empty CSV file, only the column headers
+        sheets.push(Sheet {
+            name: "Statement".to_string(),
+            rows: vec![CSV_COLUMNS.iter().map(|name| Cell::text(*name)).collect()],
+        });
+    }
+    Ok(Xlsx { sheets })
+}
+
+impl TryFrom<&Camt053> for Xlsx {
+    type Error = ParserError;
+
+    fn try_from(camt: &Camt053) -> Result<Self, Self::Error> {
+        camt053_to_xlsx(camt, SheetLayout::Statement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn read_camt053() -> Camt053 {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let target_file = File::open(path.join("valid1.camt053")).unwrap();
+        Camt053::from_read(target_file).unwrap()
+    }
+
+    fn text(value: &str) -> Cell {
+        Cell::Text(value.to_string())
+    }
+
+    #[test]
+    fn test_convert_camt053_to_xlsx() {
+        let camt053_valid = read_camt053();
+
+        let result: Result<Xlsx, ParserError> = (&camt053_valid).try_into();
+        let result = result.unwrap();
+
+        assert_eq!(result.sheets.len(), 1);
+        let sheet = &result.sheets[0];
+        assert_eq!(sheet.name, "STMT001");
+        let rows = &sheet.rows;
+        assert_eq!(rows[0], vec![text("Statement"), text("STMT001")]);
+        assert_eq!(rows[1][1], text("DE89370400440532013000"));
+        assert_eq!(rows[2], vec![text("Currency"), text("EUR")]);
+        let date = |day| Cell::Date(NaiveDate::from_ymd_opt(2023, 10, day).unwrap());
+        assert_eq!(rows[3], vec![text("Period"), date(1), date(5)]);
+        assert_eq!(rows[5][0], text("Closing balance"));
+        assert_eq!(
+            rows[5][1],
+            Cell::Number {
+                value: "1500.50".to_string(),
+                currency: Some("EUR".to_string()),
+            }
+        );
+        assert!(rows[6].is_empty());
+        assert_eq!(rows[7].len(), CSV_COLUMNS.len());
+        assert_eq!(rows[8][column("booking_date")], date(5));
+        assert_eq!(
+            rows[8][column("amount")],
+            Cell::Number {
+                value: "100.00".to_string(),
+                currency: Some("EUR".to_string()),
+            }
+        );
+        assert_eq!(rows[9][column("credit_debit")], text("DBIT"));
+        assert!(rows[10].is_empty());
+        assert_eq!(rows[11][0], text("Total credits"));
+        assert_eq!(
+            rows[11][column("amount")],
+            Cell::Formula {
+                formula: "SUMIF(J9:J10,\"CRDT\",H9:H10)".to_string(),
+                value: "100.00".to_string(),
+                currency: Some("EUR".to_string()),
+            }
+        );
+        assert_eq!(rows[12][0], text("Total debits"));
+        assert_eq!(rows.len(), 13);
+    }
+
+    #[test]
+    fn test_convert_camt053_to_xlsx_per_account() {
+        let mut camt053 = read_camt053();
+        let mut second = camt053.bk_to_cstmr_stmt.stmts[0].clone();
+        second.id = Some("STMT001".to_string());
+        let entry = &mut second.ntry[0];
+        entry.amt.as_mut().unwrap().currency = Some("USD".to_string());
+        camt053.bk_to_cstmr_stmt.stmts.push(second);
+
+        let by_statement = camt053_to_xlsx(&camt053, SheetLayout::Statement).unwrap();
+        let names: Vec<&str> = by_statement
+            .sheets
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(names, ["STMT001", "STMT001 (2)"]);
+
+        let by_account = camt053_to_xlsx(&camt053, SheetLayout::Account).unwrap();
+        assert_eq!(by_account.sheets.len(), 1);
+        let rows = &by_account.sheets[0].rows;
+        assert_eq!(rows[0], vec![text("Statements"), text("STMT001, STMT001")]);
+        let totals: Vec<&Vec<Cell>> = rows.iter().skip(13).collect();
+        assert_eq!(totals.len(), 4);
+        assert_eq!(totals[2][column("currency")], text("USD"));
+        assert!(matches!(
+            &totals[2][column("amount")],
+            Cell::Formula { formula, value, .. }
+                if formula.starts_with("SUMIFS(H9:H12,J9:J12,\"CRDT\",I9:I12,\"USD\")")
+                    && value == "100.00"
+        ));
+    }
+
+    #[test]
+    fn test_convert_streams_to_xlsx_layout() {
+        use crate::converter::convert_streams::convert_streams_with_mode;
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{FormatOptions, SheetLayout, SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data/valid1.xlsx");
+        let output = SharedBuffer::default();
+        convert_streams_with_mode(
+            Box::new(File::open(&path).unwrap()),
+            SupportedFormats::Xlsx,
+            Box::new(output.clone()),
+            SupportedFormats::Xlsx,
+            &XmlWriteOptions::default(),
+            ConversionContext::default()
+                .with_options(FormatOptions::new().xlsx_layout(SheetLayout::Account)),
+        )
+        .unwrap();
+
+        let result = Xlsx::from_read(output.take().as_slice()).unwrap();
+        assert_eq!(result.sheets.len(), 1);
+        assert_eq!(result.sheets[0].name, "DE89370400440532013000");
+    }
+
+    #[test]
+    fn test_sheet_name() {
+        let taken = vec!["Statement".to_string()];
+        assert_eq!(sheet_name("statement", &taken), "statement (2)");
+        assert_eq!(sheet_name("2025/10 [EUR]", &[]), "2025_10 _EUR_");
+        assert_eq!(sheet_name("'", &[]), "Statement");
+        let long = "A".repeat(40);
+        assert_eq!(sheet_name(&long, &[]).chars().count(), 31);
+        let taken = vec![sheet_name(&long, &[])];
+        assert!(sheet_name(&long, &taken).ends_with("A (2)"));
+    }
+
+    #[test]
+    fn test_convert_camt053_to_xlsx_and_back() {
+        let camt053_valid = read_camt053();
+        let xlsx = camt053_to_xlsx(&camt053_valid, SheetLayout::Statement).unwrap();
+
+        let result = Camt053::try_from(&xlsx).unwrap();
+        let stmts = &result.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(stmts[0].id, Some("STMT001".to_string()));
+        let original = &camt053_valid.bk_to_cstmr_stmt.stmts[0].ntry;
+        assert_eq!(stmts[0].ntry.len(), original.len());
+        for (entry, original) in stmts[0].ntry.iter().zip(original) {
+            assert_eq!(entry.amt, original.amt);
+            assert_eq!(entry.cdt_dbt_ind, original.cdt_dbt_ind);
+            assert_eq!(entry.bookg_dt, original.bookg_dt);
+        }
+    }
+
+    #[test]
+    fn test_convert_empty_camt053_to_xlsx() {
+        let mut camt053 = read_camt053();
+        camt053.bk_to_cstmr_stmt.stmts.clear();
+
+        let xlsx = camt053_to_xlsx(&camt053, SheetLayout::Account).unwrap();
+        assert_eq!(xlsx.sheets.len(), 1);
+        assert_eq!(xlsx.sheets[0].rows.len(), 1);
+    }
+}
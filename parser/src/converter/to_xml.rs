@@ -20,37 +20,9 @@ impl TryFrom<&Mt940> for XmlWrapper {
     type Error = ParserError;
 
     fn try_from(mt940: &Mt940) -> Result<Self, Self::Error> {
-        let statement_lines_xml: Vec<MT940StatementLineXml> = mt940
-            .statement
-            .statement_lines
-            .iter()
-            .map(|line| MT940StatementLineXml {
-                field_61: line.field_61.clone(),
-                field_86: line.field_86.clone(),
-            })
-            .collect();
+        let document = Mt940XmlDocument::from(mt940);
 
-        let statement_wrapper = MT940XmlStatement {
-            field_20: mt940.statement.field_20.clone(),
-            field_21: mt940.statement.field_21.clone(),
-            field_25: mt940.statement.field_25.clone(),
-            field_28c: mt940.statement.field_28c.clone(),
-            field_60f: mt940.statement.field_60f.clone(),
-            statement_lines: statement_lines_xml,
-            field_62f: mt940.statement.field_62f.clone(),
-            field_64: mt940.statement.field_64.clone(),
-            field_65: mt940.statement.field_65.clone(),
-        };
-
-        let wrapper = Mt940Xml {
-            basic_header: mt940.basic_header.clone(),
-            application_header: mt940.application_header.clone(),
-            user_header: mt940.user_header.clone(),
-            statement: statement_wrapper,
-            footer: mt940.footer.clone(),
-        };
-
-        to_string(&wrapper)
+        to_string(&document)
             .map(XmlWrapper)
             .map_err(|e| ParserError::Converter(format!("XML conversion error: {}", e)))
     }
@@ -88,17 +60,16 @@ mod tests {
         let result = result.unwrap();
 
         // Expected XML representation
-        assert_eq!(result.0.clone(), "<Mt940Xml><basic_header>\
-        F01BANKDEFFAXXX0000000000</basic_header><application_header>I940BANKNL2AXXXXN\
-        </application_header><user_header/><statement><field20><reference>STAT202510210001</reference>\
-        </field20><field21/><field25><authorisation>NL91ABNA0417164300</authorisation></field25><field28C>\
-        <statement_number>1</statement_number><sequence_number>1</sequence_number></field28C><field60F><debit_credit_mark>C</debit_credit_mark>\
-        <value_date>2025-10-20</value_date><currency>EUR</currency><amount>10000</amount>\
-        </field60F><transactions><field61><value_date>2025-10-21</value_date><entry_date>1021</entry_date>\
-        <debit_credit_mark>D</debit_credit_mark><funds_code/><amount>250</amount><transaction_type>NTRF</transaction_type>\
-        <customer_reference>NONREF</customer_reference><bank_reference>BKNTRX0001</bank_reference><supplementary_details/>\
-        </field61><field86><narrative>Payment to supplier Roga i Kopyta Inv 1001</narrative></field86></transactions>\
-        <field62F><debit_credit_mark>C</debit_credit_mark><value_date>2025-10-21</value_date><currency>EUR</currency>\
-        <amount>11239.5</amount></field62F><field64/><field65/></statement><footer/></Mt940Xml>".to_string());
+        assert_eq!(result.0.clone(), "<Mt940Document xmlns=\"urn:financial-parser:xsd:mt940.xml.1\" version=\"1.0\">\
+        <BasicHeader>F01BANKDEFFAXXX0000000000</BasicHeader><ApplicationHeader>I940BANKNL2AXXXXN</ApplicationHeader>\
+        <Statement><TransactionReference>STAT202510210001</TransactionReference>\
+        <AccountIdentification>NL91ABNA0417164300</AccountIdentification><StatementNumber>1</StatementNumber>\
+        <SequenceNumber>1</SequenceNumber><OpeningBalance><DebitCreditMark>C</DebitCreditMark><Date>2025-10-20</Date>\
+        <Currency>EUR</Currency><Amount>10000</Amount></OpeningBalance><Entry><ValueDate>2025-10-21</ValueDate>\
+        <EntryDate>--10-21</EntryDate><DebitCreditMark>D</DebitCreditMark><Amount>250</Amount>\
+        <TransactionType>NTRF</TransactionType><CustomerReference>NONREF</CustomerReference>\
+        <BankReference>BKNTRX0001</BankReference><Information><Line>Payment to supplier Roga i Kopyta Inv 1001</Line>\
+        </Information></Entry><ClosingBalance><DebitCreditMark>C</DebitCreditMark><Date>2025-10-21</Date>\
+        <Currency>EUR</Currency><Amount>11239.5</Amount></ClosingBalance></Statement></Mt940Document>".to_string());
     }
 }
@@ -2,3 +2,36 @@ use csv::StringRecord;
 
 #[derive(Debug, PartialEq)]
 pub(crate) struct CsvWrapper(pub(crate) Vec<StringRecord>);
+
+/// Columns of the statement CSV layout: one row per entry, with the
+/// statement it belongs to repeated on every row.
+///
+/// Only `amount` and `credit_debit` are required when reading; the other
+/// columns may be missing or empty, and columns in any order are accepted.
+/// Accounts are an IBAN or another account id, agents a BIC.
+pub(crate) const CSV_COLUMNS: [&str; 24] = [
+    "message_id",
+    "statement_id",
+    "iban",
+    "account_id",
+    "account_currency",
+    "booking_date",
+    "value_date",
+    "amount",
+    "currency",
+    "credit_debit",
+    "status",
+    "domain",
+    "family",
+    "sub_family",
+    "proprietary_code",
+    "end_to_end_id",
+    "account_servicer_reference",
+    "debtor",
+    "creditor",
+    "remittance_information",
+    "debtor_account",
+    "creditor_account",
+    "debtor_agent",
+    "creditor_agent",
+];
@@ -0,0 +1,734 @@
+use crate::FormatOptions;
+use crate::ParserError;
+use crate::QifDateFormat;
+use crate::SupportedFormats;
+use crate::converter::MT940_XML_NAMESPACE;
+use crate::registry;
+use std::fmt;
+use std::io::Read;
+
+/// Number of bytes at the start of a stream that [`detect_stream`] looks at.
+pub const DETECTION_WINDOW: usize = 8 * 1024;
+
+/// A statement format recognised from the first bytes of its data.
+///
+/// Besides the formats this crate converts, detection recognises related
+/// formats (MT942, camt.052, pain.002) so that callers can report them
+/// instead of failing with a parsing error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectedFormat {
+    /// SWIFT MT940 customer statement, with or without `{1:}...{5:}` envelope.
+    Mt940,
+    /// SWIFT MT942 interim transaction report.
+    Mt942,
+    /// SWIFT MT950 statement message.
+    Mt950,
+    /// SWIFT MT900 confirmation of debit.
+    Mt900,
+    /// SWIFT MT910 confirmation of credit.
+    Mt910,
+    /// ISO 20022 camt.052 account report.
+    Camt052,
+    /// ISO 20022 camt.053 bank-to-customer statement.
+    Camt053,
+    /// ISO 20022 camt.054 debit/credit notification.
+    Camt054,
+    /// ISO 20022 pain.001 customer credit transfer initiation.
+    Pain001,
+    /// ISO 20022 pain.002 customer payment status report.
+    Pain002,
+    /// XML that is not an ISO 20022 cash management message, e.g. the MT940 XML representation.
+    Xml,
+    /// A single JSON document.
+    Json,
+    /// Newline-delimited JSON, one object per line.
+    Ndjson,
+    /// Delimiter-separated values.
+    Csv,
+    /// BAI2 cash management balance report.
+    Bai2,
+    /// Belgian CODA statement of account.
+    Coda,
+    /// OFX statement download, 1.x in SGML with a header of `NAME:VALUE`
+    /// lines or 2.x in XML.
+    Ofx,
+    /// QIF transaction list.
+    Qif,
+    /// French CFONB 120 bank statement.
+    Cfonb,
+    /// Spanish Norma 43 (AEB/CSB 43) bank statement.
+    Norma43,
+    /// 1C ClientBankExchange file of Russian accounting systems.
+    ClientBank,
+    /// Office Open XML spreadsheet (Excel workbook).
+    Xlsx,
+    /// A format added with [`register_format`](crate::register_format), by name.
+    Registered(&'static str),
+}
+
+impl DetectedFormat {
+    /// The format to read the data as, if this crate can convert it.
+    pub fn supported_format(&self) -> Option<SupportedFormats> {
+        match self {
+            DetectedFormat::Mt940 => Some(SupportedFormats::Mt940),
+            DetectedFormat::Camt053 => Some(SupportedFormats::Camt053),
+            DetectedFormat::Xml => Some(SupportedFormats::Xml),
+            DetectedFormat::Json => Some(SupportedFormats::Json),
+            DetectedFormat::Ndjson => Some(SupportedFormats::Ndjson),
+            DetectedFormat::Csv => Some(SupportedFormats::Csv),
+            DetectedFormat::Bai2 => Some(SupportedFormats::Bai2),
+            DetectedFormat::Coda => Some(SupportedFormats::Coda),
+            DetectedFormat::Ofx => Some(SupportedFormats::Ofx),
+            DetectedFormat::Qif => Some(SupportedFormats::Qif),
+            DetectedFormat::Cfonb => Some(SupportedFormats::Cfonb),
+            DetectedFormat::Norma43 => Some(SupportedFormats::Norma43),
+            DetectedFormat::ClientBank => Some(SupportedFormats::ClientBank),
+            DetectedFormat::Xlsx => Some(SupportedFormats::Xlsx),
+            DetectedFormat::Mt950 => Some(SupportedFormats::Mt950),
+            DetectedFormat::Mt900 | DetectedFormat::Mt910 => Some(SupportedFormats::Mt900),
+            DetectedFormat::Camt054 => Some(SupportedFormats::Camt054),
+            DetectedFormat::Pain001 => Some(SupportedFormats::Pain001),
+            DetectedFormat::Registered(name) => Some(SupportedFormats::Other(name)),
+            DetectedFormat::Mt942 | DetectedFormat::Camt052 | DetectedFormat::Pain002 => None,
+        }
+    }
+}
+
+impl fmt::Display for DetectedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DetectedFormat::Mt940 => "mt940",
+            DetectedFormat::Mt942 => "mt942",
+            DetectedFormat::Mt950 => "mt950",
+            DetectedFormat::Mt900 => "mt900",
+            DetectedFormat::Mt910 => "mt910",
+            DetectedFormat::Camt052 => "camt052",
+            DetectedFormat::Camt053 => "camt053",
+            DetectedFormat::Camt054 => "camt054",
+            DetectedFormat::Pain001 => "pain001",
+            DetectedFormat::Pain002 => "pain002",
+            DetectedFormat::Xml => "xml",
+            DetectedFormat::Json => "json",
+            DetectedFormat::Ndjson => "ndjson",
+            DetectedFormat::Csv => "csv",
+            DetectedFormat::Bai2 => "bai2",
+            DetectedFormat::Coda => "coda",
+            DetectedFormat::Ofx => "ofx",
+            DetectedFormat::Qif => "qif",
+            DetectedFormat::Cfonb => "cfonb",
+            DetectedFormat::Norma43 => "norma43",
+            DetectedFormat::ClientBank => "1c",
+            DetectedFormat::Xlsx => "xlsx",
+            DetectedFormat::Registered(name) => name,
+        })
+    }
+}
+
+/// Result of format detection.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Detection {
+    /// The most likely format.
+    pub format: DetectedFormat,
+    /// How certain the detection is, from `0.0` to `1.0`.
+    ///
+    /// `1.0` means the data declares its format, e.g. by the message type of
+    /// a SWIFT application header or an ISO 20022 namespace; values below
+    /// `0.5` are guesses from the general shape of the data.
+    pub confidence: f32,
+    /// Options to read the data with, e.g. the order of day and month of
+    /// QIF dates that do not fit the default one.
+    pub options: FormatOptions,
+}
+
+impl Detection {
+    fn new(format: DetectedFormat, confidence: f32) -> Self {
+        Detection {
+            format,
+            confidence,
+            options: FormatOptions::default(),
+        }
+    }
+}
+
+/// Detects the format of statement data from its first bytes.
+///
+/// `head` is the start of the data, typically the first
+/// [`DETECTION_WINDOW`] bytes; it may end anywhere, even inside a line or
+/// a multi-byte character. Returns `None` if the data looks like none of
+/// the [`DetectedFormat`]s.
+///
+/// Formats added with [`register_format`](crate::register_format) are
+/// asked as well; the most confident detection wins, the built-in one on a tie.
+pub fn detect_format(head: &[u8]) -> Option<Detection> {
+    let registered = registry::registered_formats()
+        .into_iter()
+        .filter_map(|format| {
+            let confidence = format.detect(head)?;
+            Some(Detection::new(
+                DetectedFormat::Registered(format.name()),
+                confidence,
+            ))
+        });
+    detect_builtin_format(head)
+        .into_iter()
+        .chain(registered)
+        .reduce(|best, detection| {
+            if detection.confidence > best.confidence {
+                detection
+            } else {
+                best
+            }
+        })
+}
+
+/// [`detect_format`] for the formats this crate implements and recognises.
+pub(crate) fn detect_builtin_format(head: &[u8]) -> Option<Detection> {
+    if head.starts_with(b"PK\x03\x04") {
+        return detect_zip(head);
+    }
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    match text.chars().next()? {
+        '<' => Some(detect_xml(text)),
+        '{' if text.starts_with("{1:") || text.starts_with("{2:") => detect_swift(text),
+        '{' | '[' => Some(detect_json(text)),
+        ':' => detect_swift(text),
+        '0' if text.starts_with("01,") => Some(detect_bai2(text)),
+        '0' if text.starts_with("00000") => Some(detect_coda(text)),
+        '0' if text.starts_with("01") => Some(detect_cfonb(text)),
+        '1' if text.starts_with("1CClientBankExchange") => {
+            Some(Detection::new(DetectedFormat::ClientBank, 1.0))
+        }
+        '1' if text.starts_with("11") => Some(detect_norma43(text)),
+        'O' if text.starts_with("OFXHEADER:") => Some(Detection::new(DetectedFormat::Ofx, 1.0)),
+        '!' => detect_qif(text),
+        _ => detect_csv(text),
+    }
+}
+
+/// Detects the format of a stream, see [`detect_format`].
+///
+/// Reads up to [`DETECTION_WINDOW`] bytes and returns, along with the
+/// detection, a reader that yields the complete stream including those bytes.
+///
+/// # Errors
+///
+/// Returns a [`ParserError`] if reading the stream fails.
+pub fn detect_stream(
+    mut reader: Box<dyn Read>,
+) -> Result<(Option<Detection>, Box<dyn Read>), ParserError> {
+    let mut head = Vec::with_capacity(DETECTION_WINDOW);
+    reader
+        .by_ref()
+        .take(DETECTION_WINDOW as u64)
+        .read_to_end(&mut head)?;
+    let detection = detect_format(&head);
+    Ok((
+        detection,
+        Box::new(std::io::Cursor::new(head).chain(reader)),
+    ))
+}
+
+/// A ZIP archive is a workbook if its entries include the `xl/` folder;
+/// other Office Open XML packages only have `[Content_Types].xml` in common.
+fn detect_zip(head: &[u8]) -> Option<Detection> {
+    let contains = |name: &[u8]| head.windows(name.len()).any(|window| window == name);
+    if contains(b"xl/") {
+        Some(Detection::new(DetectedFormat::Xlsx, 1.0))
+    } else if contains(b"[Content_Types].xml") {
+        Some(Detection::new(DetectedFormat::Xlsx, 0.5))
+    } else {
+        None
+    }
+}
+
+fn detect_xml(text: &str) -> Detection {
+    // ISO 20022 messages declare their type in the namespace, e.g. camt.053.001.02
+    for (marker, format) in [
+        ("xsd:camt.052.", DetectedFormat::Camt052),
+        ("xsd:camt.053.", DetectedFormat::Camt053),
+        ("xsd:camt.054.", DetectedFormat::Camt054),
+        ("xsd:pain.001.", DetectedFormat::Pain001),
+        ("xsd:pain.002.", DetectedFormat::Pain002),
+    ] {
+        if text.contains(marker) {
+            return Detection::new(format, 1.0);
+        }
+    }
+    if text.contains(MT940_XML_NAMESPACE) {
+        return Detection::new(DetectedFormat::Xml, 1.0);
+    }
+    // OFX 2.x declares itself in a processing instruction after the XML declaration
+    if text.contains("<?OFX ") {
+        return Detection::new(DetectedFormat::Ofx, 1.0);
+    }
+
+    // Without a namespace, the message element under <Document> names the type
+    let root = root_element(text).unwrap_or_default();
+    for (element, format) in [
+        ("BkToCstmrAcctRpt", DetectedFormat::Camt052),
+        ("BkToCstmrStmt", DetectedFormat::Camt053),
+        ("BkToCstmrDbtCdtNtfctn", DetectedFormat::Camt054),
+        ("CstmrCdtTrfInitn", DetectedFormat::Pain001),
+        ("CstmrPmtStsRpt", DetectedFormat::Pain002),
+    ] {
+        if has_element(text, element) {
+            let confidence = if root == "Document" { 0.9 } else { 0.7 };
+            return Detection::new(format, confidence);
+        }
+    }
+    match root {
+        "Mt940Document" | "Mt940Xml" => Detection::new(DetectedFormat::Xml, 0.9),
+        "OFX" => Detection::new(DetectedFormat::Ofx, 0.7),
+        _ => Detection::new(DetectedFormat::Xml, 0.3),
+    }
+}
+
+/// Local name of the first element, after the XML declaration, comments and DOCTYPE.
+fn root_element(text: &str) -> Option<&str> {
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("<?") {
+            rest = &after[after.find("?>")? + 2..];
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            rest = &after[after.find("-->")? + 3..];
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = &after[after.find('>')? + 1..];
+        } else {
+            let name = rest.strip_prefix('<')?;
+            let end = name
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+                .unwrap_or(name.len());
+            let name = &name[..end];
+            return Some(name.rsplit(':').next().unwrap_or(name));
+        }
+    }
+}
+
+/// Whether `text` has a start tag named `name`, with or without namespace prefix.
+fn has_element(text: &str, name: &str) -> bool {
+    text.match_indices(name).any(|(i, _)| {
+        let before = &text[..i];
+        let after = text[i + name.len()..].chars().next();
+        let prefixed = before
+            .rfind('<')
+            .map(|lt| &before[lt + 1..])
+            .is_some_and(|prefix| {
+                prefix.is_empty()
+                    || (prefix.ends_with(':')
+                        && prefix[..prefix.len() - 1]
+                            .chars()
+                            .all(|c| c.is_alphanumeric() || c == '_' || c == '-'))
+            });
+        prefixed && matches!(after, None | Some('>' | '/' | ' ' | '\t' | '\r' | '\n'))
+    })
+}
+
+fn detect_swift(text: &str) -> Option<Detection> {
+    // {2:I940...} or {2:O940...} names the message type
+    if let Some(start) = text.find("{2:") {
+        let header = &text[start + 3..];
+        let format = match header.get(1..4) {
+            _ if !header.starts_with(['I', 'O']) => None,
+            Some("940") => Some(DetectedFormat::Mt940),
+            Some("942") => Some(DetectedFormat::Mt942),
+            Some("950") => Some(DetectedFormat::Mt950),
+            Some("900") => Some(DetectedFormat::Mt900),
+            Some("910") => Some(DetectedFormat::Mt910),
+            _ => None,
+        };
+        if let Some(format) = format {
+            return Some(Detection::new(format, 1.0));
+        }
+    }
+
+    // Otherwise the text block tags tell the message types apart
+    let tags: Vec<&str> = text
+        .lines()
+        .filter_map(|line| {
+            let line = line.rsplit("{4:").next().unwrap_or(line);
+            let (tag, _) = line.strip_prefix(':')?.split_once(':')?;
+            let valid =
+                !tag.is_empty() && tag.len() <= 3 && tag.bytes().all(|b| b.is_ascii_alphanumeric());
+            valid.then_some(tag)
+        })
+        .collect();
+    let has = |tag: &str| tags.contains(&tag);
+    if !has("20") {
+        return None;
+    }
+    let has_balances = has("60F") || has("60M") || has("62F") || has("62M");
+    // A confirmation books one amount; only MT910 names an ordering customer or intermediary
+    if has("32A") && !has_balances {
+        let credit = tags
+            .iter()
+            .any(|tag| tag.starts_with("50") || tag.starts_with("56"));
+        return Some(if credit {
+            Detection::new(DetectedFormat::Mt910, 0.8)
+        } else {
+            Detection::new(DetectedFormat::Mt900, 0.5)
+        });
+    }
+    // MT942 has a floor limit and a date/time indication instead of balances
+    if has("34F") || has("13D") {
+        return Some(Detection::new(DetectedFormat::Mt942, 0.9));
+    }
+    if has_balances {
+        return Some(Detection::new(DetectedFormat::Mt940, 0.9));
+    }
+    if has("25") || has("28C") {
+        return Some(Detection::new(DetectedFormat::Mt940, 0.6));
+    }
+    Some(Detection::new(DetectedFormat::Mt940, 0.3))
+}
+
+fn detect_json(text: &str) -> Detection {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let first = lines.next().unwrap_or_default();
+
+    // A complete object on the first line: a one-line document or NDJSON
+    if let Ok(serde_json::Value::Object(object)) = serde_json::from_str(first) {
+        if object.contains_key("statements") || object.contains_key("version") {
+            return Detection::new(DetectedFormat::Json, 1.0);
+        }
+        let next_is_object = lines.next().is_some_and(|line| line.starts_with('{'));
+        let is_entry = object.contains_key("amount") || object.contains_key("creditDebit");
+        return match (next_is_object, is_entry) {
+            (true, true) => Detection::new(DetectedFormat::Ndjson, 1.0),
+            (true, false) | (false, true) => Detection::new(DetectedFormat::Ndjson, 0.7),
+            (false, false) => Detection::new(DetectedFormat::Json, 0.5),
+        };
+    }
+
+    if text.contains("\"statements\"") {
+        Detection::new(DetectedFormat::Json, 0.9)
+    } else {
+        Detection::new(DetectedFormat::Json, 0.5)
+    }
+}
+
+fn detect_bai2(text: &str) -> Detection {
+    // A file header followed by a group header; a lone "01," line may be CSV
+    let second = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("01,"));
+    match second {
+        Some(line) if line.starts_with("02,") => Detection::new(DetectedFormat::Bai2, 1.0),
+        _ => detect_csv(text).unwrap_or(Detection::new(DetectedFormat::Bai2, 0.4)),
+    }
+}
+
+fn detect_coda(text: &str) -> Detection {
+    // A 128-character header record with application code 05
+    let header = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+    let application = header.get(14..16);
+    match (header.chars().count(), application) {
+        (128, Some("05")) => Detection::new(DetectedFormat::Coda, 1.0),
+        _ => detect_csv(text).unwrap_or(Detection::new(DetectedFormat::Coda, 0.4)),
+    }
+}
+
+fn detect_cfonb(text: &str) -> Detection {
+    // 120-character records, possibly without line breaks, whose old
+    // balance ends in a sign letter
+    let line = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+    let chars: Vec<char> = line.chars().collect();
+    let signed = chars
+        .get(103)
+        .is_some_and(|c| matches!(c, 'A'..='R' | '{' | '}'));
+    match (chars.len() % 120, chars.is_empty(), signed) {
+        (0, false, true) => Detection::new(DetectedFormat::Cfonb, 1.0),
+        _ => detect_csv(text).unwrap_or(Detection::new(DetectedFormat::Cfonb, 0.4)),
+    }
+}
+
+fn detect_norma43(text: &str) -> Detection {
+    // An 80-character account header followed by a movement or totals record
+    let mut lines = text.lines().map(|line| line.trim_end_matches('\r'));
+    let header = lines.next().unwrap_or_default();
+    let next = lines.next().unwrap_or_default();
+    match (header.chars().count(), next.get(..2)) {
+        (80, Some("22" | "33")) => Detection::new(DetectedFormat::Norma43, 1.0),
+        _ => detect_csv(text).unwrap_or(Detection::new(DetectedFormat::Norma43, 0.4)),
+    }
+}
+
+fn detect_qif(text: &str) -> Option<Detection> {
+    let header = text.lines().next()?.trim().to_ascii_lowercase();
+    if !["!type:", "!account", "!option:"]
+        .iter()
+        .any(|prefix| header.starts_with(prefix))
+    {
+        return None;
+    }
+    // A first number above 12 in a `D` line puts the day first
+    let day_first = text
+        .lines()
+        .filter_map(|line| line.strip_prefix('D'))
+        .filter_map(|date| {
+            date.split(['/', '.', '-'])
+                .next()?
+                .trim()
+                .parse::<u32>()
+                .ok()
+        })
+        .any(|first| (13..=31).contains(&first));
+    let mut detection = Detection::new(DetectedFormat::Qif, 0.9);
+    if day_first {
+        detection.options = detection.options.qif_date_format(QifDateFormat::Eu);
+    }
+    Some(detection)
+}
+
+fn detect_csv(text: &str) -> Option<Detection> {
+    // Only complete lines count: the window may cut the last one
+    let mut lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.len() > 1 && !text.ends_with('\n') {
+        lines.pop();
+    }
+    let first = lines.first()?;
+    if first.chars().any(|c| c.is_control() && c != '\t') {
+        return None;
+    }
+
+    let (delimiter, columns) = [',', ';', '\t', '|']
+        .into_iter()
+        .map(|d| (d, first.matches(d).count()))
+        .max_by_key(|(_, count)| *count)?;
+    if columns == 0 {
+        return None;
+    }
+
+    let rows = lines.iter().take(20).skip(1);
+    let consistent = rows
+        .clone()
+        .all(|line| line.matches(delimiter).count() == columns);
+    let confidence = match (rows.count(), consistent) {
+        (0, _) => 0.3,
+        (_, false) => 0.4,
+        (1, true) => 0.6,
+        (_, true) => 0.8,
+    };
+    Some(Detection::new(DetectedFormat::Csv, confidence))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn detect_file(name: &str) -> Option<Detection> {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data").join(name);
+        detect_stream(Box::new(File::open(path).unwrap()))
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn test_detect_test_data() {
+        for name in ["valid1.mt940", "valid2.mt940", "valid3.mt940"] {
+            let detection = detect_file(name).unwrap();
+            assert_eq!(detection.format, DetectedFormat::Mt940, "{}", name);
+        }
+        for name in ["valid1.camt053", "valid2.camt053", "valid3.camt053"] {
+            let detection = detect_file(name).unwrap();
+            assert_eq!(detection.format, DetectedFormat::Camt053, "{}", name);
+            assert!(detection.confidence >= 0.9, "{}", name);
+        }
+        assert_eq!(
+            detect_file("valid1.bai2"),
+            Some(Detection::new(DetectedFormat::Bai2, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.coda"),
+            Some(Detection::new(DetectedFormat::Coda, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.ofx"),
+            Some(Detection::new(DetectedFormat::Ofx, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid2.ofx"),
+            Some(Detection::new(DetectedFormat::Ofx, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.qif"),
+            Some(Detection::new(DetectedFormat::Qif, 0.9))
+        );
+        assert_eq!(
+            detect_file("valid1.cfonb"),
+            Some(Detection::new(DetectedFormat::Cfonb, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.n43"),
+            Some(Detection::new(DetectedFormat::Norma43, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.1c"),
+            Some(Detection::new(DetectedFormat::ClientBank, 1.0))
+        );
+        assert_eq!(
+            detect_file("valid1.xlsx"),
+            Some(Detection::new(DetectedFormat::Xlsx, 1.0))
+        );
+        let eu = "!Type:Bank\nD03/10/2025\nT-1.00\n^\nD20/10/2025\nT2.00\n^\n";
+        let detection = detect_format(eu.as_bytes()).unwrap();
+        assert_eq!(detection.format, DetectedFormat::Qif);
+        assert_eq!(
+            detection.options,
+            FormatOptions::new().qif_date_format(QifDateFormat::Eu)
+        );
+    }
+
+    #[test]
+    fn test_detect_swift() {
+        let envelope = "{1:F01BANKDEFFAXXX0000000000}{2:I942BANKNL2AXXXXN}{4:\r\n:20:X\r\n-}";
+        assert_eq!(
+            detect_format(envelope.as_bytes()),
+            Some(Detection::new(DetectedFormat::Mt942, 1.0))
+        );
+
+        let bare = ":20:STAT1\r\n:25:NL91ABNA0417164300\r\n:28C:1/1\r\n:60F:C251020EUR1,00\r\n";
+        assert_eq!(
+            detect_format(bare.as_bytes()).unwrap().format,
+            DetectedFormat::Mt940
+        );
+
+        let interim = ":20:STAT1\r\n:25:NL91ABNA0417164300\r\n:28C:1/1\r\n:34F:EUR0,\r\n:13D:2510211200+0100\r\n";
+        assert_eq!(
+            detect_format(interim.as_bytes()).unwrap().format,
+            DetectedFormat::Mt942
+        );
+
+        let mt950 =
+            "{1:F01BANKDEFFAXXX0000000000}{2:O9501130251021BANKGB2LAXXX00000000002510211130N}";
+        let detection = detect_format(mt950.as_bytes()).unwrap();
+        assert_eq!(detection, Detection::new(DetectedFormat::Mt950, 1.0));
+        assert_eq!(
+            detection.format.supported_format(),
+            Some(SupportedFormats::Mt950)
+        );
+
+        let credit = ":20:C1\r\n:21:REF\r\n:25:123\r\n:13D:2510211200+0100\r\n:32A:251021EUR1,\r\n:50K:ACME\r\n";
+        assert_eq!(
+            detect_format(credit.as_bytes()),
+            Some(Detection::new(DetectedFormat::Mt910, 0.8))
+        );
+        assert_eq!(
+            DetectedFormat::Mt910.supported_format(),
+            Some(SupportedFormats::Mt900)
+        );
+    }
+
+    #[test]
+    fn test_detect_xml() {
+        let camt054 = "<?xml version=\"1.0\"?>\n<!-- bank export -->\n\
+            <Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.054.001.08\"><BkToCstmrDbtCdtNtfctn/>";
+        assert_eq!(
+            detect_format(camt054.as_bytes()),
+            Some(Detection::new(DetectedFormat::Camt054, 1.0))
+        );
+
+        let pain002 = "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pain.002.001.10\">";
+        let detection = detect_format(pain002.as_bytes()).unwrap();
+        assert_eq!(detection.format, DetectedFormat::Pain002);
+        assert_eq!(detection.format.supported_format(), None);
+
+        let pain001 = "<Document><CstmrCdtTrfInitn><GrpHdr>";
+        let detection = detect_format(pain001.as_bytes()).unwrap();
+        assert_eq!(detection, Detection::new(DetectedFormat::Pain001, 0.9));
+        assert_eq!(
+            detection.format.supported_format(),
+            Some(SupportedFormats::Pain001)
+        );
+
+        let prefixed = "<ns:Document><ns:BkToCstmrAcctRpt><ns:GrpHdr>";
+        assert_eq!(
+            detect_format(prefixed.as_bytes()),
+            Some(Detection::new(DetectedFormat::Camt052, 0.9))
+        );
+
+        let mt940_xml = format!(
+            "<Mt940Document xmlns=\"{}\" version=\"1.0\">",
+            MT940_XML_NAMESPACE
+        );
+        assert_eq!(
+            detect_format(mt940_xml.as_bytes()),
+            Some(Detection::new(DetectedFormat::Xml, 1.0))
+        );
+
+        let other = "<catalog><book id=\"1\"/></catalog>";
+        let detection = detect_format(other.as_bytes()).unwrap();
+        assert_eq!(detection.format, DetectedFormat::Xml);
+        assert!(detection.confidence < 0.5);
+    }
+
+    #[test]
+    fn test_detect_json_and_ndjson() {
+        let document = "{\n  \"version\": \"1.0\",\n  \"statements\": []\n}";
+        assert_eq!(
+            detect_format(document.as_bytes()).unwrap().format,
+            DetectedFormat::Json
+        );
+
+        let lines = "{\"statementId\":\"S1\",\"amount\":{\"value\":\"1.00\"},\"creditDebit\":\"CRDT\"}\n\
+            {\"statementId\":\"S1\",\"amount\":{\"value\":\"2.00\"},\"creditDebit\":\"DBIT\"}\n";
+        assert_eq!(
+            detect_format(lines.as_bytes()),
+            Some(Detection::new(DetectedFormat::Ndjson, 1.0))
+        );
+    }
+
+    #[test]
+    fn test_detect_csv() {
+        let csv = "\u{feff}date;amount;currency\n2025-10-21;250,00;EUR\n2025-10-22;10,00;EUR\n";
+        assert_eq!(
+            detect_format(csv.as_bytes()),
+            Some(Detection::new(DetectedFormat::Csv, 0.8))
+        );
+        assert_eq!(detect_format(b"plain text"), None);
+        assert_eq!(detect_format(b"  \n"), None);
+    }
+
+    #[test]
+    fn test_detect_zip() {
+        let docx = b"PK\x03\x04\x14\x00\x00\x00[Content_Types].xml...PK\x03\x04word/document.xml";
+        assert_eq!(
+            detect_format(docx),
+            Some(Detection::new(DetectedFormat::Xlsx, 0.5))
+        );
+        assert_eq!(detect_format(b"PK\x03\x04\x14\x00\x00\x00report.pdf"), None);
+    }
+
+    #[test]
+    fn test_detect_stream_keeps_data() {
+        let data = format!(
+            ":20:STAT1\r\n:60F:C251020EUR1,00\r\n{}",
+            "x".repeat(DETECTION_WINDOW)
+        );
+        let (detection, mut reader) =
+            detect_stream(Box::new(std::io::Cursor::new(data.clone()))).unwrap();
+        assert_eq!(detection.unwrap().format, DetectedFormat::Mt940);
+
+        let mut read = String::new();
+        reader.read_to_string(&mut read).unwrap();
+        assert_eq!(read, data);
+    }
+}
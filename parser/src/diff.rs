@@ -0,0 +1,704 @@
+use crate::camt053::balance::{
+    SignedAmount, account_id, account_number, balance_code, date_of, find_balance, non_empty,
+    statement_last_day, transactions,
+};
+use crate::camt053::format::*;
+
+use serde::Serialize;
+use std::fmt;
+
+/// Differences between two versions of a statement document, e.g. a
+/// statement and the corrected one the bank sent later.
+///
+/// Only statements with differences are listed. Rendered as text by
+/// [`Display`](fmt::Display) and as JSON by [`to_json`](Self::to_json).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocumentDiff {
+    /// The statements that were added, removed or changed.
+    pub statements: Vec<StatementDiff>,
+}
+
+/// What happened to a statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementStatus {
+    /// Only in the new document; its entries are listed as added.
+    Added,
+    /// Only in the old document; its entries are listed as removed.
+    Removed,
+    /// In both documents, with different balances or entries.
+    Changed,
+}
+
+/// Differences of one statement.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatementDiff {
+    /// The statement `Id` or `:20:` reference, of the new version if it has one.
+    pub statement_id: Option<String>,
+    /// The IBAN or other id of the account.
+    pub account: Option<String>,
+    /// Whether the statement was added, removed or changed.
+    pub status: StatementStatus,
+    /// Balances that differ, by type.
+    pub balances: Vec<BalanceChange>,
+    /// Entries only in the new statement.
+    pub added: Vec<EntrySummary>,
+    /// Entries only in the old statement.
+    pub removed: Vec<EntrySummary>,
+    /// Entries in both statements that differ.
+    pub modified: Vec<EntryChange>,
+}
+
+/// A balance as compared: amount, direction, currency and date.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceSummary {
+    /// Amount with two or more decimals and a `.` separator.
+    pub amount: Option<String>,
+    /// `CRDT` or `DBIT`.
+    pub credit_debit: Option<String>,
+    /// ISO 4217 currency code.
+    pub currency: Option<String>,
+    /// Date as `YYYY-MM-DD`.
+    pub date: Option<String>,
+}
+
+/// A balance that differs between the versions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceChange {
+    /// Balance type code, e.g. `OPBD` or `CLBD`.
+    pub code: String,
+    /// The balance in the old statement, if it had one of this type.
+    pub old: Option<BalanceSummary>,
+    /// The balance in the new statement, if it has one of this type.
+    pub new: Option<BalanceSummary>,
+}
+
+/// An entry as compared, with its values normalised so that the same entry
+/// read from MT940 and from CAMT.053 compares equal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntrySummary {
+    /// Bank reference, end-to-end id or transaction id, the first one present.
+    pub reference: Option<String>,
+    /// Booking date as `YYYY-MM-DD`.
+    pub booking_date: Option<String>,
+    /// Value date as `YYYY-MM-DD`.
+    pub value_date: Option<String>,
+    /// Amount with two or more decimals and a `.` separator.
+    pub amount: Option<String>,
+    /// ISO 4217 currency code.
+    pub currency: Option<String>,
+    /// `CRDT` or `DBIT`.
+    pub credit_debit: Option<String>,
+    /// Entry status, e.g. `BOOK` or `PDNG`.
+    pub status: Option<String>,
+    /// Proprietary bank transaction code, or the ISO domain, family and
+    /// sub-family codes joined with `/`.
+    pub transaction_code: Option<String>,
+    /// Name or account of the debtor of a credit or the creditor of a debit.
+    pub counterparty: Option<String>,
+    /// Remittance information, lines joined with spaces.
+    pub narrative: Option<String>,
+}
+
+/// How two entries were recognised as versions of one another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    /// They share a reference.
+    Reference,
+    /// They have the same amount, direction, booking date and counterparty.
+    AmountDateCounterparty,
+}
+
+/// A field of an entry that differs between the versions.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Name of the field, as in [`EntrySummary`].
+    pub field: &'static str,
+    /// The old value.
+    pub old: Option<String>,
+    /// The new value.
+    pub new: Option<String>,
+}
+
+/// An entry in both versions that differs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EntryChange {
+    /// How the entries were matched.
+    pub matched_by: MatchedBy,
+    /// The old entry.
+    pub old: EntrySummary,
+    /// The new entry.
+    pub new: EntrySummary,
+    /// The fields that differ.
+    pub changes: Vec<FieldChange>,
+}
+
+impl DocumentDiff {
+    /// Returns `true` if the documents have the same statements, balances and entries.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// The differences as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        // Plain data of strings and enums always serializes
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Compares `old` and `new`.
+///
+/// Statements are paired by account and statement id, then by account and
+/// date, then by account in order. Entries of a pair of statements are
+/// matched by a shared reference, then by amount, direction, booking date
+/// and counterparty; matched entries that differ in any other compared
+/// field are modified, the rest added or removed.
+pub(crate) fn diff(old: &Camt053, new: &Camt053) -> DocumentDiff {
+    let old_stmts = &old.bk_to_cstmr_stmt.stmts;
+    let new_stmts = &new.bk_to_cstmr_stmt.stmts;
+    let mut unmatched: Vec<Option<&Statement>> = old_stmts.iter().map(Some).collect();
+
+    let pairing: [fn(&Statement, &Statement) -> bool; 3] = [
+        |old, new| non_empty(&old.id).is_some() && non_empty(&old.id) == non_empty(&new.id),
+        |old, new| {
+            statement_last_day(old).is_some() && statement_last_day(old) == statement_last_day(new)
+        },
+        |_, _| true,
+    ];
+    let mut pairs: Vec<(Option<&Statement>, &Statement)> =
+        new_stmts.iter().map(|stmt| (None, stmt)).collect();
+    for same in pairing {
+        for (old_stmt, new_stmt) in pairs.iter_mut().filter(|pair| pair.0.is_none()) {
+            *old_stmt = take_first(&mut unmatched, |old| {
+                account_id(old) == account_id(new_stmt) && same(old, new_stmt)
+            });
+        }
+    }
+
+    let mut statements: Vec<StatementDiff> = pairs
+        .into_iter()
+        .filter_map(|(old_stmt, new_stmt)| match old_stmt {
+            Some(old_stmt) => diff_statements(old_stmt, new_stmt),
+            None => Some(whole_statement(new_stmt, StatementStatus::Added)),
+        })
+        .collect();
+    statements.extend(
+        unmatched
+            .into_iter()
+            .flatten()
+            .map(|stmt| whole_statement(stmt, StatementStatus::Removed)),
+    );
+    DocumentDiff { statements }
+}
+
+/// Removes and returns the first item that matches `predicate`.
+fn take_first<'a, T>(items: &mut [Option<&'a T>], predicate: impl Fn(&T) -> bool) -> Option<&'a T> {
+    items
+        .iter_mut()
+        .find(|item| item.is_some_and(&predicate))
+        .and_then(Option::take)
+}
+
+fn whole_statement(stmt: &Statement, status: StatementStatus) -> StatementDiff {
+    let entries = stmt.ntry.iter().map(summarize).collect();
+    let (added, removed) = match status {
+        StatementStatus::Removed => (Vec::new(), entries),
+        _ => (entries, Vec::new()),
+    };
+    StatementDiff {
+        statement_id: non_empty(&stmt.id),
+        account: account_id(stmt),
+        status,
+        balances: Vec::new(),
+        added,
+        removed,
+        modified: Vec::new(),
+    }
+}
+
+fn diff_statements(old: &Statement, new: &Statement) -> Option<StatementDiff> {
+    let balances = diff_balances(old, new);
+
+    let old_entries: Vec<EntrySummary> = old.ntry.iter().map(summarize).collect();
+    let new_entries: Vec<EntrySummary> = new.ntry.iter().map(summarize).collect();
+    let old_refs: Vec<Vec<String>> = old.ntry.iter().map(references).collect();
+    let mut unmatched: Vec<Option<usize>> = (0..old_entries.len()).map(Some).collect();
+    let mut matches: Vec<Option<(usize, MatchedBy)>> = vec![None; new_entries.len()];
+
+    for (i, entry) in new.ntry.iter().enumerate() {
+        let refs = references(entry);
+        let found = unmatched
+            .iter_mut()
+            .find(|old| old.is_some_and(|old| old_refs[old].iter().any(|r| refs.contains(r))));
+        matches[i] = found
+            .and_then(Option::take)
+            .map(|old| (old, MatchedBy::Reference));
+    }
+    for (i, entry) in new_entries.iter().enumerate() {
+        if matches[i].is_some() {
+            continue;
+        }
+        let found = unmatched
+            .iter_mut()
+            .find(|old| old.is_some_and(|old| same_payment(&old_entries[old], entry)));
+        matches[i] = found
+            .and_then(Option::take)
+            .map(|old| (old, MatchedBy::AmountDateCounterparty));
+    }
+
+    let mut added = Vec::new();
+    let mut modified = Vec::new();
+    for (new_entry, matched) in new_entries.into_iter().zip(matches) {
+        match matched {
+            None => added.push(new_entry),
+            Some((old, matched_by)) => {
+                let changes = entry_changes(&old_entries[old], &new_entry);
+                if !changes.is_empty() {
+                    modified.push(EntryChange {
+                        matched_by,
+                        old: old_entries[old].clone(),
+                        new: new_entry,
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    let removed: Vec<EntrySummary> = unmatched
+        .into_iter()
+        .flatten()
+        .map(|old| old_entries[old].clone())
+        .collect();
+
+    if balances.is_empty() && added.is_empty() && removed.is_empty() && modified.is_empty() {
+        return None;
+    }
+    Some(StatementDiff {
+        statement_id: non_empty(&new.id).or_else(|| non_empty(&old.id)),
+        account: account_id(new),
+        status: StatementStatus::Changed,
+        balances,
+        added,
+        removed,
+        modified,
+    })
+}
+
+fn diff_balances(old: &Statement, new: &Statement) -> Vec<BalanceChange> {
+    let mut codes: Vec<&str> = Vec::new();
+    for code in old.bal.iter().chain(&new.bal).filter_map(balance_code) {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    let summary = |stmt: &Statement, code: &str| {
+        let balance = find_balance(stmt, code)?;
+        Some(BalanceSummary {
+            amount: amount(&balance.amt),
+            credit_debit: credit_debit(&balance.cdt_dbt_ind),
+            currency: balance.amt.as_ref().and_then(|a| non_empty(&a.currency)),
+            date: balance
+                .dt
+                .as_ref()
+                .and_then(date_of)
+                .map(|date| date.to_string()),
+        })
+    };
+    codes
+        .into_iter()
+        .filter_map(|code| {
+            let (old, new) = (summary(old, code), summary(new, code));
+            (old != new).then(|| BalanceChange {
+                code: code.to_string(),
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+/// Same amount, direction, booking date and counterparty.
+fn same_payment(old: &EntrySummary, new: &EntrySummary) -> bool {
+    old.amount == new.amount
+        && old.credit_debit == new.credit_debit
+        && old.booking_date == new.booking_date
+        && old.counterparty.as_deref().map(str::to_lowercase)
+            == new.counterparty.as_deref().map(str::to_lowercase)
+}
+
+fn entry_changes(old: &EntrySummary, new: &EntrySummary) -> Vec<FieldChange> {
+    let fields: [(&'static str, &Option<String>, &Option<String>); 10] = [
+        ("reference", &old.reference, &new.reference),
+        ("booking_date", &old.booking_date, &new.booking_date),
+        ("value_date", &old.value_date, &new.value_date),
+        ("amount", &old.amount, &new.amount),
+        ("currency", &old.currency, &new.currency),
+        ("credit_debit", &old.credit_debit, &new.credit_debit),
+        ("status", &old.status, &new.status),
+        (
+            "transaction_code",
+            &old.transaction_code,
+            &new.transaction_code,
+        ),
+        ("counterparty", &old.counterparty, &new.counterparty),
+        ("narrative", &old.narrative, &new.narrative),
+    ];
+    fields
+        .into_iter()
+        .filter(|(field, old, new)| match *field {
+            // MT940 wraps :86: into lines of 65 characters, words included
+            "narrative" => without_whitespace(old) != without_whitespace(new),
+            "counterparty" => {
+                old.as_deref().map(str::to_lowercase) != new.as_deref().map(str::to_lowercase)
+            }
+            _ => old != new,
+        })
+        .map(|(field, old, new)| FieldChange {
+            field,
+            old: old.clone(),
+            new: new.clone(),
+        })
+        .collect()
+}
+
+fn without_whitespace(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|v| v.chars().filter(|c| !c.is_whitespace()).collect())
+}
+
+fn amount(amount: &Option<Amount>) -> Option<String> {
+    let value = amount.as_ref()?.value.as_deref()?;
+    SignedAmount::parse(value).map(|a| a.magnitude())
+}
+
+fn credit_debit(indicator: &Option<String>) -> Option<String> {
+    match indicator.as_deref()?.trim() {
+        "CRDT" | "C" => Some("CRDT".to_string()),
+        "DBIT" | "D" => Some("DBIT".to_string()),
+        other => Some(other.to_string()).filter(|v| !v.is_empty()),
+    }
+}
+
+/// References of an entry that identify it, without the MT940 and ISO
+/// placeholders for a missing reference.
+fn references(entry: &Entry) -> Vec<String> {
+    transactions(entry)
+        .filter_map(|tx| tx.refs.as_ref())
+        .flat_map(|refs| [&refs.acct_svcr_ref, &refs.end_to_end_id, &refs.tx_id])
+        .filter_map(non_empty)
+        .filter(|r| r != "NONREF" && r != "NOTPROVIDED")
+        .collect()
+}
+
+pub(crate) fn summarize(entry: &Entry) -> EntrySummary {
+    let code = entry.bk_tx_cd.as_ref();
+    let proprietary = code.and_then(|c| non_empty(&c.prtry.as_ref()?.cd));
+    let domain = code.and_then(|c| c.domn.as_ref()).map(|domain| {
+        let family = domain.fmly.as_ref();
+        [
+            non_empty(&domain.cd),
+            family.and_then(|f| non_empty(&f.cd)),
+            family.and_then(|f| non_empty(&f.sub_fmly_cd)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("/")
+    });
+
+    let credit_debit = credit_debit(&entry.cdt_dbt_ind);
+    let parties = transactions(entry).find_map(|tx| tx.rltd_pties.as_ref());
+    let party = |name: &Option<Party>, account: &Option<AccountIdentification>| {
+        non_empty(&name.as_ref()?.name).or_else(|| {
+            let account = account.as_ref()?;
+            account_number(&account.iban, &account.other)
+        })
+    };
+    let counterparty = parties.and_then(|p| {
+        let debtor = || party(&p.dbtr, &p.dbtr_acct);
+        let creditor = || party(&p.cdtr, &p.cdtr_acct);
+        match credit_debit.as_deref() {
+            Some("DBIT") => creditor().or_else(debtor),
+            _ => debtor().or_else(creditor),
+        }
+    });
+    let narrative: Vec<&str> = transactions(entry)
+        .filter_map(|tx| tx.rmt_inf.as_ref())
+        .flat_map(|rmt| rmt.ustrd.iter().map(|line| line.trim()))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    EntrySummary {
+        reference: references(entry).into_iter().next(),
+        booking_date: entry
+            .bookg_dt
+            .as_ref()
+            .and_then(date_of)
+            .map(|d| d.to_string()),
+        value_date: entry
+            .val_dt
+            .as_ref()
+            .and_then(date_of)
+            .map(|d| d.to_string()),
+        amount: amount(&entry.amt),
+        currency: entry.amt.as_ref().and_then(|a| non_empty(&a.currency)),
+        credit_debit,
+        status: non_empty(&entry.sts),
+        transaction_code: proprietary.or(domain.filter(|d| !d.is_empty())),
+        counterparty,
+        narrative: Some(narrative.join(" ")).filter(|n| !n.is_empty()),
+    }
+}
+
+fn show(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("-")
+}
+
+impl fmt::Display for EntrySummary {
+    /// One line: date, direction, amount, currency, reference and counterparty.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.credit_debit.as_deref() {
+            Some("CRDT") => "C",
+            Some("DBIT") => "D",
+            _ => "?",
+        };
+        write!(
+            f,
+            "{} {} {} {}",
+            show(&self.booking_date),
+            direction,
+            show(&self.amount),
+            show(&self.currency)
+        )?;
+        if let Some(reference) = &self.reference {
+            write!(f, " ref {}", reference)?;
+        }
+        if let Some(counterparty) = &self.counterparty {
+            write!(f, " \"{}\"", counterparty)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for BalanceSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.credit_debit.as_deref() {
+            Some("DBIT") => "D",
+            _ => "C",
+        };
+        write!(
+            f,
+            "{} {} {} on {}",
+            direction,
+            show(&self.amount),
+            show(&self.currency),
+            show(&self.date)
+        )
+    }
+}
+
+impl fmt::Display for DocumentDiff {
+    /// A report with one section per statement; entries are prefixed with
+    /// `+` (added), `-` (removed) or `~` (modified).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+        for stmt in &self.statements {
+            let status = match stmt.status {
+                StatementStatus::Added => "added",
+                StatementStatus::Removed => "removed",
+                StatementStatus::Changed => "changed",
+            };
+            writeln!(
+                f,
+                "Statement {} of account {}: {}",
+                show(&stmt.statement_id),
+                show(&stmt.account),
+                status
+            )?;
+            for balance in &stmt.balances {
+                let side = |b: &Option<BalanceSummary>| {
+                    b.as_ref().map_or("none".to_string(), |b| b.to_string())
+                };
+                writeln!(
+                    f,
+                    "  balance {}: {} -> {}",
+                    balance.code,
+                    side(&balance.old),
+                    side(&balance.new)
+                )?;
+            }
+            for entry in &stmt.added {
+                writeln!(f, "  + {}", entry)?;
+            }
+            for entry in &stmt.removed {
+                writeln!(f, "  - {}", entry)?;
+            }
+            for change in &stmt.modified {
+                writeln!(f, "  ~ {}", change.old)?;
+                for field in &change.changes {
+                    writeln!(
+                        f,
+                        "      {}: {} -> {}",
+                        field.field,
+                        show(&field.old),
+                        show(&field.new)
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn valid_camt053() -> Camt053 {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_identical_documents() {
+        let changes = diff(&valid_camt053(), &valid_camt053());
+        assert!(changes.is_empty());
+        assert_eq!(changes.to_string(), "No differences\n");
+    }
+
+    #[test]
+    fn test_corrected_statement() {
+        let old = valid_camt053();
+        let mut new = valid_camt053();
+        let stmt = &mut new.bk_to_cstmr_stmt.stmts[0];
+        // Amount of the referenced credit corrected, the fee dropped, a
+        // payment without reference added
+        stmt.ntry[0].amt.as_mut().unwrap().value = Some("110".to_string());
+        stmt.ntry.remove(1);
+        let mut added = Entry {
+            amt: Some(Amount {
+                currency: Some("EUR".to_string()),
+                value: Some("25.5".to_string()),
+            }),
+            cdt_dbt_ind: Some("C".to_string()),
+            ..Entry::default()
+        };
+        stmt.ntry.push(added.clone());
+        stmt.bal[3].amt.as_mut().unwrap().value = Some("1550.50".to_string());
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.statements.len(), 1);
+        let stmt = &changes.statements[0];
+        assert_eq!(stmt.status, StatementStatus::Changed);
+        assert_eq!(stmt.statement_id.as_deref(), Some("STMT001"));
+        assert_eq!(stmt.balances.len(), 1);
+        assert_eq!(stmt.balances[0].code, "CLBD");
+        assert_eq!(stmt.added.len(), 1);
+        assert_eq!(stmt.added[0].amount.as_deref(), Some("25.50"));
+        assert_eq!(stmt.removed[0].reference.as_deref(), Some("ACCTREF789"));
+        assert_eq!(stmt.modified.len(), 1);
+        assert_eq!(stmt.modified[0].matched_by, MatchedBy::Reference);
+        assert_eq!(
+            stmt.modified[0].changes,
+            vec![FieldChange {
+                field: "amount",
+                old: Some("100.00".to_string()),
+                new: Some("110.00".to_string()),
+            }]
+        );
+
+        let text = changes.to_string();
+        assert!(
+            text.contains(
+                "balance CLBD: C 1500.50 EUR on 2023-10-05 -> C 1550.50 EUR on 2023-10-05"
+            )
+        );
+        assert!(text.contains("  - 2023-10-04 D 50.00 EUR ref ACCTREF789"));
+        assert!(text.contains("      amount: 100.00 -> 110.00"));
+        let json: serde_json::Value = serde_json::from_str(&changes.to_json()).unwrap();
+        assert_eq!(
+            json["statements"][0]["modified"][0]["matched_by"],
+            "reference"
+        );
+
+        // Without references entries are matched by amount, date and counterparty
+        added.cdt_dbt_ind = Some("CRDT".to_string());
+        let mut old = valid_camt053();
+        old.bk_to_cstmr_stmt.stmts[0].ntry = vec![added.clone()];
+        let mut new = valid_camt053();
+        added.sts = Some("BOOK".to_string());
+        new.bk_to_cstmr_stmt.stmts[0].ntry = vec![added];
+        let changes = diff(&old, &new);
+        let modified = &changes.statements[0].modified[0];
+        assert_eq!(modified.matched_by, MatchedBy::AmountDateCounterparty);
+        assert_eq!(modified.changes[0].field, "status");
+    }
+
+    #[test]
+    fn test_corrected_mt940_statement() {
+        use crate::Mt940;
+        use crate::test_utils::read_test_file;
+
+        let camt053 =
+            |data: &str| Camt053::try_from(&Mt940::from_read(data.as_bytes()).unwrap()).unwrap();
+        let data = read_test_file("valid1.mt940");
+        let old = camt053(&data);
+        let new = camt053(
+            &data
+                .replace(":61:2510211021D250,00", ":61:2510211021D260,00")
+                .replace(":62F:C251021EUR11239,50", ":62F:C251021EUR11229,50"),
+        );
+
+        let changes = diff(&old, &new);
+        assert_eq!(changes.statements.len(), 1);
+        let stmt = &changes.statements[0];
+        assert_eq!(stmt.status, StatementStatus::Changed);
+        assert_eq!(stmt.statement_id.as_deref(), Some("STAT202510210001"));
+        assert!(stmt.added.is_empty());
+        assert!(stmt.removed.is_empty());
+        assert_eq!(stmt.modified.len(), 1);
+        assert_eq!(stmt.modified[0].matched_by, MatchedBy::Reference);
+        assert_eq!(
+            stmt.modified[0].changes,
+            vec![FieldChange {
+                field: "amount",
+                old: Some("250.00".to_string()),
+                new: Some("260.00".to_string()),
+            }]
+        );
+        assert!(
+            changes
+                .to_string()
+                .starts_with("Statement STAT202510210001 of account NL91ABNA0417164300: changed\n")
+        );
+    }
+
+    #[test]
+    fn test_added_and_removed_statements() {
+        let old = valid_camt053();
+        let mut new = valid_camt053();
+        new.bk_to_cstmr_stmt.stmts[0]
+            .acct
+            .as_mut()
+            .unwrap()
+            .id
+            .as_mut()
+            .unwrap()
+            .iban = Some("NL91ABNA0417164300".to_string());
+
+        let changes = diff(&old, &new);
+        let status: Vec<_> = changes.statements.iter().map(|s| s.status).collect();
+        assert_eq!(status, [StatementStatus::Added, StatementStatus::Removed]);
+        assert_eq!(changes.statements[0].added.len(), 2);
+        assert_eq!(changes.statements[1].removed.len(), 2);
+    }
+}
@@ -0,0 +1,243 @@
+use super::ParserError;
+use std::fmt;
+
+/// Position of a problem in the input.
+///
+/// `line` and `column` are 1-based, `column` counts characters rather than
+/// bytes. `len` is the number of bytes the problem spans, at least the byte
+/// at `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    /// Byte offset from the start of the input.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+    /// Length of the span in bytes.
+    pub len: usize,
+}
+
+impl Default for SourceSpan {
+    /// The first byte of the input.
+    fn default() -> Self {
+        SourceSpan {
+            offset: 0,
+            line: 1,
+            column: 1,
+            len: 0,
+        }
+    }
+}
+
+impl SourceSpan {
+    /// Moves the position past `bytes`, which follow it in the input.
+    pub(crate) fn advance(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // Every byte except UTF-8 continuation bytes starts a character
+                self.column += 1;
+            }
+        }
+        self.offset += bytes.len();
+    }
+
+    /// Span of `len` bytes at byte `index` of `text`, where `text` starts at `self`.
+    pub(crate) fn within(&self, text: &str, index: usize, len: usize) -> SourceSpan {
+        let index = index.min(text.len());
+        let mut span = SourceSpan { len, ..*self };
+        span.advance(&text.as_bytes()[..index]);
+        span
+    }
+}
+
+/// What the problem refers to: a SWIFT block, a field tag or an XML element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorLocation {
+    /// A SWIFT FIN block, by its identifier (`1` … `5`).
+    Block(String),
+    /// A tag of the MT940 text block, e.g. `61` or `62F`.
+    Field(String),
+    /// Path of an XML element, e.g. `/Document/BkToCstmrStmt/Stmt[1]/Ntry[3]`.
+    XmlPath(String),
+}
+
+impl fmt::Display for ErrorLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorLocation::Block(id) => write!(f, "block {}", id),
+            ErrorLocation::Field(tag) => write!(f, "field :{}:", tag),
+            ErrorLocation::XmlPath(path) => f.write_str(path),
+        }
+    }
+}
+
+/// A parsing error together with where it occurred in the input.
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// The underlying error.
+    pub error: ParserError,
+    /// Where the problem is.
+    pub span: SourceSpan,
+    /// The block, field or element the problem belongs to, if known.
+    pub location: Option<ErrorLocation>,
+    /// The input line containing the problem, if the input was available.
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    /// Renders the error with the offending input line and a caret under the problem.
+    ///
+    /// The line is taken from `source`, the complete input, or from the
+    /// captured [`snippet`](Self::snippet) when `source` is empty or does not
+    /// reach it (e.g. for input read from stdin).
+    ///
+    /// ```text
+    /// MT940 parsing error: Invalid amount at line 5, column 1 in field :61:
+    ///  --> line 5, column 1, field :61:
+    ///   |
+    /// 5 | :61:2510211021D25O,00NTRFNONREF
+    ///   | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!(
+            "{}\n --> line {}, column {}",
+            self, self.span.line, self.span.column
+        );
+        if let Some(location) = &self.location {
+            out.push_str(&format!(", {}", location));
+        }
+        out.push('\n');
+
+        let line = Some(source)
+            .filter(|source| !source.is_empty())
+            .and_then(|source| source.split('\n').nth(self.span.line - 1))
+            .map(|line| line.trim_end_matches('\r'))
+            .or(self.snippet.as_deref());
+        let Some(line) = line else {
+            return out;
+        };
+
+        let number = self.span.line.to_string();
+        let gutter = " ".repeat(number.len());
+        // Tabs are kept so the caret lines up with the text above it
+        let padding: String = line
+            .chars()
+            .take(self.span.column - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let start = line
+            .char_indices()
+            .nth(self.span.column - 1)
+            .map_or(line.len(), |(i, _)| i);
+        let end = line.len().min(start + self.span.len);
+        let width = line[start..end].chars().count().max(1);
+
+        out.push_str(&format!("{} |\n", gutter));
+        out.push_str(&format!("{} | {}\n", number, line));
+        out.push_str(&format!("{} | {}{}\n", gutter, padding, "^".repeat(width)));
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.error, self.span.line, self.span.column
+        )?;
+        if let Some(location) = &self.location {
+            write!(f, " in {}", location)?;
+        }
+        Ok(())
+    }
+}
+
+impl ParserError {
+    /// Returns the position information of a [`ParserError::Located`] error.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            ParserError::Located(diagnostic) => Some(diagnostic),
+            _ => None,
+        }
+    }
+
+    /// Renders the error for display to a user, see [`Diagnostic::render`].
+    ///
+    /// Errors without a position are rendered as their message.
+    pub fn render(&self, source: &str) -> String {
+        match self {
+            ParserError::Located(diagnostic) => diagnostic.render(source),
+            other => format!("{}\n", other),
+        }
+    }
+
+    /// Attaches a position, unless the error already has one.
+    pub(crate) fn at_span(self, span: SourceSpan, location: Option<ErrorLocation>) -> ParserError {
+        match self {
+            ParserError::Located(_) => self,
+            error => ParserError::Located(Box::new(Diagnostic {
+                error,
+                span,
+                location,
+                snippet: None,
+            })),
+        }
+    }
+
+    /// Attaches the position of `len` bytes at byte `index` of `text`, which
+    /// starts at `origin` in the input, along with the line containing it.
+    pub(crate) fn at_text(
+        self,
+        text: &str,
+        index: usize,
+        len: usize,
+        origin: &SourceSpan,
+        location: Option<ErrorLocation>,
+    ) -> ParserError {
+        let span = origin.within(text, index, len);
+        let snippet = line_at(text, index);
+        match self.at_span(span, location) {
+            ParserError::Located(mut diagnostic) => {
+                diagnostic.snippet.get_or_insert(snippet);
+                ParserError::Located(diagnostic)
+            }
+            other => other,
+        }
+    }
+
+    /// Fills in the snippet of a located error from the complete input.
+    pub(crate) fn with_source(self, source: &str) -> ParserError {
+        match self {
+            ParserError::Located(mut diagnostic) => {
+                if diagnostic.snippet.is_none() {
+                    let index = diagnostic.span.offset.min(source.len());
+                    diagnostic.snippet = Some(line_at(source, index));
+                }
+                ParserError::Located(diagnostic)
+            }
+            other => other,
+        }
+    }
+}
+
+/// The line of `text` containing byte `index`, without its line end.
+fn line_at(text: &str, index: usize) -> String {
+    let bytes = text.as_bytes();
+    let index = index.min(bytes.len());
+    let start = bytes[..index]
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    let end = bytes[index..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |i| index + i);
+    String::from_utf8_lossy(&bytes[start..end])
+        .trim_end_matches('\r')
+        .to_string()
+}
@@ -1,5 +1,12 @@
 use thiserror::Error;
 
+mod diagnostic;
+mod warning;
+
+pub use diagnostic::{Diagnostic, ErrorLocation, SourceSpan};
+pub(crate) use warning::Recovery;
+pub use warning::{ParseMode, ParseWarning, WarningKind};
+
 /// Represents all possible errors that can occur during parsing and data conversion.
 ///
 /// This enum consolidates various error types produced by parsers for supported formats
@@ -39,9 +46,86 @@ pub enum ParserError {
     #[error("XML parsing error: {0}")]
     Xml(String),
 
+    /// An error that occurred while parsing a **JSON** or **NDJSON** file.
+    #[error("JSON parsing error: {0}")]
+    Json(String),
+
+    /// An error that occurred while parsing a **BAI2** file.
+    ///
+    /// Contains a description of the parsing issue, e.g. a control total
+    /// that does not match the records.
+    #[error("BAI2 parsing error: {0}")]
+    Bai2(String),
+
+    /// An error that occurred while parsing a **CODA** file.
+    ///
+    /// Contains a description of the parsing issue, e.g. a continuation
+    /// record that does not follow the record announcing it.
+    #[error("CODA parsing error: {0}")]
+    Coda(String),
+
+    /// An error that occurred while parsing an **OFX** file, SGML or XML.
+    #[error("OFX parsing error: {0}")]
+    Ofx(String),
+
+    /// An error that occurred while parsing a **QIF** file, e.g. a
+    /// transaction without a date.
+    #[error("QIF parsing error: {0}")]
+    Qif(String),
+
+    /// An error that occurred while parsing a **CFONB 120** file, e.g. an
+    /// amount without its sign letter.
+    #[error("CFONB parsing error: {0}")]
+    Cfonb(String),
+
+    /// An error that occurred while parsing a **Norma 43** file, e.g. a
+    /// totals record that does not match the movements.
+    #[error("Norma 43 parsing error: {0}")]
+    Norma43(String),
+
+    /// An error that occurred while parsing a **1C ClientBankExchange**
+    /// file, e.g. a document without `Сумма`.
+    #[error("1C parsing error: {0}")]
+    ClientBank(String),
+
+    /// An error that occurred while parsing an **MT950** statement message.
+    #[error("MT950 parsing error: {0}")]
+    Mt950(String),
+
+    /// An error that occurred while parsing an **MT900** or **MT910**
+    /// confirmation, e.g. a message whose type cannot be told.
+    #[error("MT900/MT910 parsing error: {0}")]
+    Mt900(String),
+
+    /// An error that occurred while parsing a **CAMT.054** notification.
+    #[error("Camt054 parsing error: {0}")]
+    Camt054(String),
+
+    /// An error that occurred while reading or writing a **pain.001**
+    /// credit transfer initiation.
+    #[error("Pain001 error: {0}")]
+    Pain001(String),
+
+    /// An error that occurred while parsing a **pain.002** payment status report.
+    #[error("Pain002 parsing error: {0}")]
+    Pain002(String),
+
+    /// An error that occurred while reading or writing an **XLSX** workbook,
+    /// e.g. a sheet without the statement columns.
+    #[error("XLSX error: {0}")]
+    Xlsx(String),
+
     /// An error that occurred while **converting data between formats**.
     #[error("Format conversion error: {0}")]
     Converter(String),
+
+    /// Any of the errors above, together with its position in the input.
+    ///
+    /// Carries the line, column and byte offset, the SWIFT field tag or XML
+    /// path and the offending input line. Use [`ParserError::render`] to show
+    /// it with a caret under the problem.
+    #[error("{0}")]
+    Located(Box<Diagnostic>),
 }
 
 impl From<std::io::Error> for ParserError {
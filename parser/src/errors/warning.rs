@@ -0,0 +1,119 @@
+use super::ParserError;
+use std::fmt;
+
+/// How readers and converters handle problems that affect only part of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Every problem aborts with a [`ParserError`].
+    #[default]
+    Strict,
+    /// Recoverable problems are skipped over and reported as [`ParseWarning`]s;
+    /// the rest of the input is still converted.
+    Lenient,
+}
+
+/// What was left out of the output to recover from a problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A whole MT940 message of an archive.
+    SkippedMessage,
+    /// A whole statement, e.g. one with an unusable balance.
+    SkippedStatement,
+    /// One entry: an MT940 `:61:` line with its `:86:`, a CAMT.053 `Ntry` or an NDJSON line.
+    SkippedEntry,
+    /// An optional field, e.g. `:86:` or `:64:`.
+    SkippedField,
+    /// The CAMT.053 group header, replaced by an empty one.
+    SkippedGroupHeader,
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WarningKind::SkippedMessage => "skipped message",
+            WarningKind::SkippedStatement => "skipped statement",
+            WarningKind::SkippedEntry => "skipped entry",
+            WarningKind::SkippedField => "skipped field",
+            WarningKind::SkippedGroupHeader => "skipped group header",
+        })
+    }
+}
+
+/// A recoverable problem found in [`ParseMode::Lenient`].
+///
+/// `error` is what strict mode would have failed with, including its
+/// position in the input when known (see [`ParserError::diagnostic`]).
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// What was left out.
+    pub kind: WarningKind,
+    /// The problem.
+    pub error: ParserError,
+}
+
+impl ParseWarning {
+    /// Renders the warning like [`ParserError::render`].
+    pub fn render(&self, source: &str) -> String {
+        format!("warning ({}): {}", self.kind, self.error.render(source))
+    }
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.error)
+    }
+}
+
+/// Applies a [`ParseMode`]: fails on problems in strict mode, collects them
+/// as warnings in lenient mode.
+#[derive(Debug, Default)]
+pub(crate) struct Recovery {
+    mode: ParseMode,
+    warnings: Vec<ParseWarning>,
+}
+
+impl Recovery {
+    pub(crate) fn new(mode: ParseMode) -> Self {
+        Recovery {
+            mode,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub(crate) fn mode(&self) -> ParseMode {
+        self.mode
+    }
+
+    pub(crate) fn is_lenient(&self) -> bool {
+        self.mode == ParseMode::Lenient
+    }
+
+    /// Returns `error` in strict mode; records it as a warning in lenient mode.
+    pub(crate) fn recover(
+        &mut self,
+        kind: WarningKind,
+        error: ParserError,
+    ) -> Result<(), ParserError> {
+        match self.mode {
+            ParseMode::Strict => Err(error),
+            ParseMode::Lenient => {
+                self.warnings.push(ParseWarning { kind, error });
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds warnings collected by a nested reader or converter.
+    pub(crate) fn extend(&mut self, warnings: Vec<ParseWarning>) {
+        self.warnings.extend(warnings);
+    }
+
+    /// Removes and returns the warnings collected so far.
+    pub(crate) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    pub(crate) fn into_warnings(self) -> Vec<ParseWarning> {
+        self.warnings
+    }
+}
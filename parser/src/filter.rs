@@ -0,0 +1,625 @@
+use crate::ParserError;
+use crate::camt053::balance::{
+    SignedAmount, account_number, balance_code, date_of, entry_date, new_balance, signed_amount,
+    transactions,
+};
+use crate::camt053::format::*;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+/// Credit or debit side of an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Money received, `CRDT`.
+    Credit,
+    /// Money paid, `DBIT`.
+    Debit,
+}
+
+impl Direction {
+    /// Parses a credit/debit indicator, `CRDT`/`DBIT` or the short `C`/`D`.
+    fn of(indicator: Option<&str>) -> Option<Direction> {
+        match indicator?.trim() {
+            "CRDT" | "C" => Some(Direction::Credit),
+            "DBIT" | "D" => Some(Direction::Debit),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "credit" | "crdt" | "c" => Ok(Direction::Credit),
+            "debit" | "dbit" | "d" => Ok(Direction::Debit),
+            _ => Err(format!("Unknown direction: {}. Use 'credit' or 'debit'", s)),
+        }
+    }
+}
+
+/// What happens to the balances of a statement that lost entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalancePolicy {
+    /// The opening balance is moved past the entries removed before the
+    /// date window, the closing balance is recomputed from the opening
+    /// balance and the remaining entries, and balances that cannot be
+    /// recomputed (e.g. available balances) are dropped.
+    #[default]
+    Adjust,
+    /// Balances are kept as they are and the statement is reported as
+    /// [`BalanceStatus::Unreconciled`].
+    Flag,
+}
+
+/// State of the balances of a filtered statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceStatus {
+    /// No entry was removed.
+    Unchanged,
+    /// The balances were recomputed and match the remaining entries.
+    Adjusted,
+    /// The balances no longer match the remaining entries, because of
+    /// [`BalancePolicy::Flag`] or because they could not be recomputed.
+    Unreconciled,
+}
+
+/// Outcome of filtering one statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementReport {
+    /// The statement `Id`. For MT940 input this is the `:21:` related
+    /// reference, or the `:20:` reference when there is none.
+    pub id: Option<String>,
+    /// Number of entries that matched the filter.
+    pub kept: usize,
+    /// Number of entries that were removed.
+    pub removed: usize,
+    /// State of the balances.
+    pub balances: BalanceStatus,
+}
+
+/// Outcome of filtering, one report per statement in input order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilterReport {
+    /// The statements of the document.
+    pub statements: Vec<StatementReport>,
+}
+
+impl FilterReport {
+    /// The statements whose balances no longer match their entries.
+    pub fn unreconciled(&self) -> impl Iterator<Item = &StatementReport> {
+        self.statements
+            .iter()
+            .filter(|stmt| stmt.balances == BalanceStatus::Unreconciled)
+    }
+}
+
+/// Selects statement entries; entries must match every criterion that is set.
+///
+/// # Example
+///
+/// Debits over 10 000 booked in October:
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use parser::filter::{Direction, EntryFilter};
+///
+/// let filter = EntryFilter::new()
+///     .from_date(NaiveDate::from_ymd_opt(2025, 10, 1).unwrap())
+///     .to_date(NaiveDate::from_ymd_opt(2025, 10, 31).unwrap())
+///     .min_amount(10_000.0)
+///     .direction(Direction::Debit);
+/// assert!(!filter.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EntryFilter {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    min_amount: Option<f64>,
+    max_amount: Option<f64>,
+    direction: Option<Direction>,
+    currencies: Vec<String>,
+    transaction_codes: Vec<String>,
+    counterparty: Option<String>,
+    narrative: Option<Regex>,
+    balances: BalancePolicy,
+}
+
+impl EntryFilter {
+    /// Creates a filter that keeps every entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps entries booked on or after `date`.
+    ///
+    /// Entries are dated by their booking date, or value date when they
+    /// have none; entries without either are removed by a date window.
+    pub fn from_date(mut self, date: NaiveDate) -> Self {
+        self.from = Some(date);
+        self
+    }
+
+    /// Keeps entries booked on or before `date`.
+    pub fn to_date(mut self, date: NaiveDate) -> Self {
+        self.to = Some(date);
+        self
+    }
+
+    /// Keeps entries whose amount is at least `amount`, regardless of direction.
+    pub fn min_amount(mut self, amount: f64) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// Keeps entries whose amount is at most `amount`, regardless of direction.
+    pub fn max_amount(mut self, amount: f64) -> Self {
+        self.max_amount = Some(amount);
+        self
+    }
+
+    /// Keeps only credits or only debits.
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Keeps entries in `currency`. Called repeatedly, any of the currencies matches.
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currencies.push(currency.to_string());
+        self
+    }
+
+    /// Keeps entries with the bank transaction code `code`: the proprietary
+    /// code (e.g. the MT940 `NTRF`) or the ISO domain, family or sub-family
+    /// code (e.g. `PMNT`, `RCDT`, `ESCT`). Called repeatedly, any of the
+    /// codes matches.
+    pub fn transaction_code(mut self, code: &str) -> Self {
+        self.transaction_codes.push(code.to_string());
+        self
+    }
+
+    /// Keeps entries whose debtor or creditor name or account contains
+    /// `text`, ignoring case.
+    pub fn counterparty(mut self, text: &str) -> Self {
+        self.counterparty = Some(text.to_lowercase());
+        self
+    }
+
+    /// Keeps entries whose remittance information matches `pattern`.
+    ///
+    /// The unstructured remittance lines of an entry (the MT940 `:86:`
+    /// narrative) are matched joined with `\n`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError::InvalidInput`] if `pattern` is not a valid regular expression.
+    pub fn narrative(mut self, pattern: &str) -> Result<Self, ParserError> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| ParserError::InvalidInput(format!("Invalid narrative pattern: {}", e)))?;
+        self.narrative = Some(regex);
+        Ok(self)
+    }
+
+    /// Sets what happens to the balances of statements that lost entries.
+    pub fn balances(mut self, policy: BalancePolicy) -> Self {
+        self.balances = policy;
+        self
+    }
+
+    /// Returns `true` if the filter keeps every entry.
+    pub fn is_empty(&self) -> bool {
+        self.from.is_none()
+            && self.to.is_none()
+            && self.min_amount.is_none()
+            && self.max_amount.is_none()
+            && self.direction.is_none()
+            && self.currencies.is_empty()
+            && self.transaction_codes.is_empty()
+            && self.counterparty.is_none()
+            && self.narrative.is_none()
+    }
+
+    /// Returns `true` if `entry` matches every criterion.
+    pub(crate) fn matches(&self, entry: &Entry) -> bool {
+        self.matches_date(entry)
+            && self.matches_amount(entry)
+            && self
+                .direction
+                .is_none_or(|d| Direction::of(entry.cdt_dbt_ind.as_deref()) == Some(d))
+            && self.matches_currency(entry)
+            && self.matches_transaction_code(entry)
+            && self.matches_counterparty(entry)
+            && self.matches_narrative(entry)
+    }
+
+    fn matches_date(&self, entry: &Entry) -> bool {
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+        let Some(date) = entry_date(entry) else {
+            return false;
+        };
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+
+    fn matches_amount(&self, entry: &Entry) -> bool {
+        if self.min_amount.is_none() && self.max_amount.is_none() {
+            return true;
+        }
+        let Some(amount) = entry
+            .amt
+            .as_ref()
+            .and_then(|a| a.value.as_deref())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+        else {
+            return false;
+        };
+        self.min_amount.is_none_or(|min| amount >= min)
+            && self.max_amount.is_none_or(|max| amount <= max)
+    }
+
+    fn matches_currency(&self, entry: &Entry) -> bool {
+        if self.currencies.is_empty() {
+            return true;
+        }
+        let currency = entry.amt.as_ref().and_then(|a| a.currency.as_deref());
+        currency.is_some_and(|currency| {
+            self.currencies
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(currency))
+        })
+    }
+
+    fn matches_transaction_code(&self, entry: &Entry) -> bool {
+        if self.transaction_codes.is_empty() {
+            return true;
+        }
+        let Some(code) = entry.bk_tx_cd.as_ref() else {
+            return false;
+        };
+        let domain = code.domn.as_ref();
+        let family = domain.and_then(|d| d.fmly.as_ref());
+        let codes = [
+            code.prtry.as_ref().and_then(|p| p.cd.as_deref()),
+            domain.and_then(|d| d.cd.as_deref()),
+            family.and_then(|f| f.cd.as_deref()),
+            family.and_then(|f| f.sub_fmly_cd.as_deref()),
+        ];
+        codes.into_iter().flatten().any(|code| {
+            self.transaction_codes
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(code))
+        })
+    }
+
+    fn matches_counterparty(&self, entry: &Entry) -> bool {
+        let Some(text) = &self.counterparty else {
+            return true;
+        };
+        transactions(entry)
+            .filter_map(|tx| tx.rltd_pties.as_ref())
+            .flat_map(|parties| {
+                let account = |account: &Option<AccountIdentification>| {
+                    let account = account.as_ref()?;
+                    account_number(&account.iban, &account.other)
+                };
+                [
+                    parties.dbtr.as_ref().and_then(|p| p.name.clone()),
+                    parties.cdtr.as_ref().and_then(|p| p.name.clone()),
+                    account(&parties.dbtr_acct),
+                    account(&parties.cdtr_acct),
+                ]
+            })
+            .flatten()
+            .any(|value| value.to_lowercase().contains(text.as_str()))
+    }
+
+    fn matches_narrative(&self, entry: &Entry) -> bool {
+        let Some(regex) = &self.narrative else {
+            return true;
+        };
+        let narrative: Vec<&str> = transactions(entry)
+            .filter_map(|tx| tx.rmt_inf.as_ref())
+            .flat_map(|rmt| rmt.ustrd.iter().map(String::as_str))
+            .collect();
+        regex.is_match(&narrative.join("\n"))
+    }
+
+    /// Removes the entries that do not match from every statement and
+    /// applies the [`BalancePolicy`] to the statements that lost entries.
+    pub(crate) fn apply(&self, camt053: &mut Camt053) -> FilterReport {
+        let statements = camt053
+            .bk_to_cstmr_stmt
+            .stmts
+            .iter_mut()
+            .map(|stmt| self.apply_to_statement(stmt))
+            .collect();
+        FilterReport { statements }
+    }
+
+    fn apply_to_statement(&self, stmt: &mut Statement) -> StatementReport {
+        let (kept, removed): (Vec<Entry>, Vec<Entry>) = std::mem::take(&mut stmt.ntry)
+            .into_iter()
+            .partition(|entry| self.matches(entry));
+        stmt.ntry = kept;
+
+        let balances = if removed.is_empty() {
+            BalanceStatus::Unchanged
+        } else if self.balances == BalancePolicy::Adjust && self.adjust_balances(stmt, &removed) {
+            BalanceStatus::Adjusted
+        } else {
+            BalanceStatus::Unreconciled
+        };
+        StatementReport {
+            id: stmt.id.clone(),
+            kept: stmt.ntry.len(),
+            removed: removed.len(),
+            balances,
+        }
+    }
+
+    /// Recomputes the opening and closing balances of `stmt` after `removed`
+    /// were taken out. Returns `false`, leaving the balances untouched, if
+    /// an amount needed for that is missing or invalid.
+    fn adjust_balances(&self, stmt: &mut Statement, removed: &[Entry]) -> bool {
+        let Some(opening) = stmt
+            .bal
+            .iter()
+            .position(|b| balance_code(b) == Some("OPBD"))
+        else {
+            return false;
+        };
+        let Some(opening_amount) =
+            signed_amount(&stmt.bal[opening].amt, &stmt.bal[opening].cdt_dbt_ind)
+        else {
+            return false;
+        };
+
+        // Entries removed before the window moved the balance the window starts with
+        let mut shift = SignedAmount::default();
+        if let Some(from) = self.from {
+            for entry in removed {
+                if entry_date(entry).is_some_and(|date| date < from) {
+                    let Some(amount) = signed_amount(&entry.amt, &entry.cdt_dbt_ind) else {
+                        return false;
+                    };
+                    shift = shift.add(amount);
+                }
+            }
+        }
+        let mut movement = SignedAmount::default();
+        for entry in &stmt.ntry {
+            let Some(amount) = signed_amount(&entry.amt, &entry.cdt_dbt_ind) else {
+                return false;
+            };
+            movement = movement.add(amount);
+        }
+
+        let new_opening = opening_amount.add(shift);
+        let new_closing = new_opening.add(movement);
+        let currency = stmt.bal[opening]
+            .amt
+            .as_ref()
+            .and_then(|a| a.currency.clone());
+        let opening_date = stmt.bal[opening].dt.take();
+        let closing = stmt
+            .bal
+            .iter_mut()
+            .find(|b| balance_code(b) == Some("CLBD"))
+            .and_then(|b| b.dt.take());
+
+        stmt.bal = vec![
+            new_balance(
+                "OPBD",
+                new_opening,
+                currency.clone(),
+                window_date(opening_date, self.from, |original, from| original.max(from)),
+            ),
+            new_balance(
+                "CLBD",
+                new_closing,
+                currency,
+                window_date(closing, self.to, |original, to| original.min(to)),
+            ),
+        ];
+        true
+    }
+}
+
+/// Moves a balance date into the date window, keeping dates that are
+/// already inside and dates with a time.
+fn window_date(
+    date: Option<DateAndDateTimeChoice>,
+    bound: Option<NaiveDate>,
+    clamp: fn(NaiveDate, NaiveDate) -> NaiveDate,
+) -> Option<DateAndDateTimeChoice> {
+    let (Some(date), Some(bound)) = (&date, bound) else {
+        return date;
+    };
+    match (date.dt_tm.is_none()).then(|| date_of(date)).flatten() {
+        Some(original) => Some(DateAndDateTimeChoice {
+            dt: Some(clamp(original, bound).format("%Y-%m-%d").to_string()),
+            dt_tm: None,
+        }),
+        None => Some(DateAndDateTimeChoice {
+            dt: date.dt.clone(),
+            dt_tm: date.dt_tm.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn valid_camt053() -> Camt053 {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        Camt053::from_read(File::open(path.join("valid1.camt053")).unwrap()).unwrap()
+    }
+
+    fn balance_of(stmt: &Statement, code: &str) -> (String, String) {
+        let balance = stmt
+            .bal
+            .iter()
+            .find(|b| balance_code(b) == Some(code))
+            .unwrap();
+        (
+            balance.amt.as_ref().unwrap().value.clone().unwrap(),
+            balance.cdt_dbt_ind.clone().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_filter_criteria() {
+        let camt053 = valid_camt053();
+        let entries = &camt053.bk_to_cstmr_stmt.stmts[0].ntry;
+        let kept = |filter: EntryFilter| entries.iter().filter(|e| filter.matches(e)).count();
+
+        assert_eq!(kept(EntryFilter::new()), 2);
+        assert_eq!(kept(EntryFilter::new().direction(Direction::Debit)), 1);
+        assert_eq!(kept(EntryFilter::new().min_amount(100.0)), 1);
+        assert_eq!(kept(EntryFilter::new().currency("eur")), 2);
+        assert_eq!(kept(EntryFilter::new().currency("USD")), 0);
+        assert_eq!(kept(EntryFilter::new().transaction_code("RCDT")), 1);
+        assert_eq!(kept(EntryFilter::new().counterparty("john")), 1);
+        assert_eq!(
+            kept(EntryFilter::new().narrative(r"Invoice \d+").unwrap()),
+            1
+        );
+        assert!(EntryFilter::new().narrative("(").is_err());
+        let date = NaiveDate::from_ymd_opt(2023, 10, 4).unwrap();
+        assert_eq!(kept(EntryFilter::new().from_date(date)), 2);
+        assert_eq!(kept(EntryFilter::new().to_date(date)), 1);
+    }
+
+    #[test]
+    fn test_filter_mt940_criteria() {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let mt940 =
+            crate::Mt940::from_read(File::open(path.join("valid1.mt940")).unwrap()).unwrap();
+        let camt053 = Camt053::try_from(&mt940).unwrap();
+        let entries = &camt053.bk_to_cstmr_stmt.stmts[0].ntry;
+        let kept = |filter: EntryFilter| entries.iter().filter(|e| filter.matches(e)).count();
+
+        let from = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+        assert_eq!(kept(EntryFilter::new().from_date(from).to_date(to)), 3);
+        assert_eq!(kept(EntryFilter::new().to_date(from)), 0);
+        assert_eq!(kept(EntryFilter::new().transaction_code("NTRF")), 2);
+        assert_eq!(kept(EntryFilter::new().transaction_code("NCHG")), 1);
+        assert_eq!(kept(EntryFilter::new().currency("EUR")), 3);
+        assert_eq!(kept(EntryFilter::new().direction(Direction::Debit)), 2);
+    }
+
+    #[test]
+    fn test_adjust_balances() {
+        let mut camt053 = valid_camt053();
+        let report = EntryFilter::new()
+            .direction(Direction::Debit)
+            .apply(&mut camt053);
+        assert_eq!(report.statements[0].kept, 1);
+        assert_eq!(report.statements[0].removed, 1);
+        assert_eq!(report.statements[0].balances, BalanceStatus::Adjusted);
+
+        let stmt = &camt053.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(stmt.bal.len(), 2);
+        assert_eq!(balance_of(stmt, "OPBD"), ("1000.00".into(), "CRDT".into()));
+        assert_eq!(balance_of(stmt, "CLBD"), ("950.00".into(), "CRDT".into()));
+
+        // The debit of 2023-10-04 is before the window and moves the opening balance
+        let mut camt053 = valid_camt053();
+        let from = NaiveDate::from_ymd_opt(2023, 10, 5).unwrap();
+        EntryFilter::new().from_date(from).apply(&mut camt053);
+        let stmt = &camt053.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(balance_of(stmt, "OPBD"), ("950.00".into(), "CRDT".into()));
+        assert_eq!(balance_of(stmt, "CLBD"), ("1050.00".into(), "CRDT".into()));
+        assert_eq!(
+            stmt.bal[0].dt.as_ref().unwrap().dt.as_deref(),
+            Some("2023-10-05")
+        );
+    }
+
+    #[test]
+    fn test_flag_balances() {
+        let mut camt053 = valid_camt053();
+        let balances = camt053.bk_to_cstmr_stmt.stmts[0].bal.len();
+
+        let report = EntryFilter::new()
+            .currency("USD")
+            .balances(BalancePolicy::Flag)
+            .apply(&mut camt053);
+        assert_eq!(report.unreconciled().count(), 1);
+        assert!(camt053.bk_to_cstmr_stmt.stmts[0].ntry.is_empty());
+        assert_eq!(camt053.bk_to_cstmr_stmt.stmts[0].bal.len(), balances);
+
+        let report = EntryFilter::new().apply(&mut valid_camt053());
+        assert_eq!(report.statements[0].balances, BalanceStatus::Unchanged);
+    }
+
+    #[test]
+    fn test_convert_streams_filtered() {
+        use crate::converter::convert_streams::convert_streams_filtered;
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let output = SharedBuffer::default();
+        let (warnings, report) = convert_streams_filtered(
+            Box::new(File::open(path.join("valid1.camt053")).unwrap()),
+            SupportedFormats::Camt053,
+            Box::new(output.clone()),
+            SupportedFormats::Csv,
+            &XmlWriteOptions::default(),
+            ConversionContext::default(),
+            &EntryFilter::new().counterparty("JANE"),
+        )
+        .unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(report.statements[0].kept, 1);
+        let csv = String::from_utf8(output.take()).unwrap();
+        assert!(csv.contains("END2END123"));
+        assert!(!csv.contains("ACCTREF789"));
+    }
+
+    #[test]
+    fn test_convert_streams_filtered_mt940() {
+        use crate::converter::convert_streams::convert_streams_filtered;
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        let output = SharedBuffer::default();
+        let (warnings, report) = convert_streams_filtered(
+            Box::new(File::open(path.join("valid1.mt940")).unwrap()),
+            SupportedFormats::Mt940,
+            Box::new(output.clone()),
+            SupportedFormats::Mt940,
+            &XmlWriteOptions::default(),
+            ConversionContext::default(),
+            &EntryFilter::new().direction(Direction::Debit),
+        )
+        .unwrap();
+
+        assert!(warnings.is_empty());
+        assert_eq!(report.statements[0].id.as_deref(), Some("STAT202510210001"));
+        assert_eq!(report.statements[0].kept, 2);
+        assert_eq!(report.statements[0].balances, BalanceStatus::Adjusted);
+        let mt940 = String::from_utf8(output.take()).unwrap();
+        assert!(mt940.contains(":61:2510211021D250,00NTRFNONREF//BKNTRX0001"));
+        assert!(mt940.contains(":61:2510211021D10,50NCHGNONREF//BKNFEE01"));
+        assert!(!mt940.contains("BKNTRX0002"));
+        assert!(mt940.contains(":62F:C251021EUR9739,50"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Records of the fixed-width statement formats, CODA, CFONB 120 and Norma 43.
+
+use crate::ParserError;
+use crate::errors::SourceSpan;
+
+use chrono::NaiveDate;
+
+/// The record length and conventions of a fixed-width format.
+#[derive(Debug)]
+pub(crate) struct RecordLayout {
+    /// Length of every record.
+    pub(crate) length: usize,
+    /// Error variant of the format.
+    pub(crate) error: fn(String) -> ParserError,
+    /// Whether a line of several records' length is cut into records, for
+    /// banks that send them without line breaks.
+    pub(crate) concatenated: bool,
+    /// Six-digit date format, `DDMMYY` or `YYMMDD`.
+    pub(crate) date_format: &'static str,
+    /// Whether a blank or zero date stands for none.
+    pub(crate) blank_dates: bool,
+}
+
+/// Fixed-width files are Latin-1; UTF-8 input is accepted as well.
+pub(crate) fn decode(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// Latin-1 bytes of `text`, with `?` for characters outside it.
+pub(crate) fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+        .collect()
+}
+
+/// The first `len` characters of a field.
+pub(crate) fn cut(value: &str, len: usize) -> String {
+    value.trim().chars().take(len).collect()
+}
+
+/// One record, padded with blanks to the record length.
+pub(crate) struct Line {
+    chars: Vec<char>,
+    layout: &'static RecordLayout,
+    /// Byte offset and length of the record in the input.
+    offset: usize,
+    len: usize,
+}
+
+impl Line {
+    /// The characters at 1-based position `start`, as in the specifications.
+    pub(crate) fn raw(&self, start: usize, len: usize) -> String {
+        self.chars[start - 1..start - 1 + len].iter().collect()
+    }
+
+    /// [`raw`](Self::raw) without surrounding blanks.
+    pub(crate) fn field(&self, start: usize, len: usize) -> String {
+        self.raw(start, len).trim().to_string()
+    }
+
+    /// The character at 1-based `position`.
+    pub(crate) fn char_at(&self, position: usize) -> char {
+        self.chars[position - 1]
+    }
+
+    /// The two-character record code.
+    pub(crate) fn code(&self) -> String {
+        self.raw(1, 2)
+    }
+
+    pub(crate) fn number<T: std::str::FromStr>(
+        &self,
+        start: usize,
+        len: usize,
+        what: &str,
+    ) -> Result<T, String> {
+        let value = self.raw(start, len);
+        if !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid {} '{}'", what, value));
+        }
+        value
+            .parse()
+            .map_err(|_| format!("Invalid {} '{}'", what, value))
+    }
+
+    /// A date in the format of the layout, kept as written.
+    pub(crate) fn date(&self, start: usize, what: &str) -> Result<String, String> {
+        let value = self.raw(start, 6);
+        if self.layout.blank_dates && (value.trim().is_empty() || value == "000000") {
+            return Ok(String::new());
+        }
+        let pattern = match self.layout.date_format {
+            "YYMMDD" => "%y%m%d",
+            _ => "%d%m%y",
+        };
+        NaiveDate::parse_from_str(&value, pattern)
+            .map(|_| value.clone())
+            .map_err(|_| {
+                format!(
+                    "Invalid {} '{}', expected {}",
+                    what, value, self.layout.date_format
+                )
+            })
+    }
+
+    pub(crate) fn error(&self, data: &str, message: impl Into<String>) -> ParserError {
+        (self.layout.error)(message.into()).at_text(
+            data,
+            self.offset,
+            self.len,
+            &SourceSpan::default(),
+            None,
+        )
+    }
+}
+
+impl RecordLayout {
+    /// The records of the input.
+    pub(crate) fn lines(&'static self, data: &str) -> Result<Vec<Line>, ParserError> {
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        for text in data.split_inclusive('\n') {
+            let start = offset;
+            offset += text.len();
+            let text = text.trim_end_matches(['\r', '\n']);
+            if text.trim().is_empty() {
+                continue;
+            }
+            let chars: Vec<char> = text.trim_start_matches('\u{feff}').chars().collect();
+            let bom = text.len() - text.trim_start_matches('\u{feff}').len();
+            if chars.len() > self.length
+                && !(self.concatenated && chars.len().is_multiple_of(self.length))
+            {
+                return Err((self.error)(format!(
+                    "Record of {} characters, expected {}",
+                    chars.len(),
+                    self.length
+                ))
+                .at_text(data, start, text.len(), &SourceSpan::default(), None));
+            }
+            let mut record_offset = start + bom;
+            for record in chars.chunks(self.length) {
+                let len: usize = record.iter().map(|c| c.len_utf8()).sum();
+                let mut chars = record.to_vec();
+                chars.resize(self.length, ' ');
+                lines.push(Line {
+                    chars,
+                    layout: self,
+                    offset: record_offset,
+                    len,
+                });
+                record_offset += len;
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Builds one record: fields are placed at their 1-based positions and the
+/// rest is blank.
+pub(crate) struct Record(Vec<char>);
+
+impl Record {
+    /// An empty record of `layout` starting with `code`.
+    pub(crate) fn new(layout: &RecordLayout, code: &str) -> Self {
+        let mut record = Record(vec![' '; layout.length]);
+        record.put(1, code.chars().count(), code);
+        record
+    }
+
+    /// Left-aligned, cut at `len` characters.
+    pub(crate) fn put(&mut self, start: usize, len: usize, value: &str) -> &mut Self {
+        for (slot, c) in self.0[start - 1..start - 1 + len]
+            .iter_mut()
+            .zip(value.chars())
+        {
+            *slot = c;
+        }
+        self
+    }
+
+    /// Right-aligned and zero-padded.
+    pub(crate) fn number(
+        &mut self,
+        start: usize,
+        len: usize,
+        value: impl std::fmt::Display,
+    ) -> &mut Self {
+        self.put(
+            start,
+            len,
+            &format!("{:0>len$}", value.to_string(), len = len),
+        )
+    }
+
+    /// A six-digit date, zeros for none.
+    pub(crate) fn date(&mut self, start: usize, date: &str) -> &mut Self {
+        self.put(start, 6, if date.is_empty() { "000000" } else { date })
+    }
+
+    /// `1` when set, `0` otherwise.
+    pub(crate) fn flag(&mut self, position: usize, set: bool) -> &mut Self {
+        self.put(position, 1, if set { "1" } else { "0" })
+    }
+
+    pub(crate) fn end(&self, out: &mut String) {
+        out.extend(self.0.iter());
+        out.push('\n');
+    }
+}
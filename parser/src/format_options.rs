@@ -0,0 +1,85 @@
+use crate::SupportedFormats;
+use crate::ofx::format::OfxVersion;
+use crate::pain001::format::Pain001Version;
+use crate::qif::format::QifDateFormat;
+use crate::xlsx::format::SheetLayout;
+
+/// Options of the readers and writers of formats that come in several
+/// variants, e.g. QIF with US or European dates.
+///
+/// A conversion applies the options of a format wherever it reads or
+/// writes it. The default is the variant most tools expect.
+///
+/// # Example
+///
+/// ```
+/// use parser::{FormatOptions, OfxVersion, Pain001Version, QifDateFormat, SheetLayout};
+///
+/// let options = FormatOptions::new()
+///     .qif_date_format(QifDateFormat::Eu)
+///     .ofx_version(OfxVersion::V1)
+///     .pain001_version(Pain001Version::V03)
+///     .xlsx_layout(SheetLayout::Account);
+/// assert_ne!(options, FormatOptions::default());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FormatOptions {
+    pub(crate) qif_date_format: QifDateFormat,
+    pub(crate) ofx_version: Option<OfxVersion>,
+    pub(crate) pain001_version: Option<Pain001Version>,
+    pub(crate) xlsx_layout: Option<SheetLayout>,
+}
+
+impl FormatOptions {
+    /// Creates options for the default variant of every format.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the order of day and month in QIF dates: how they are written,
+    /// and read when they fit either order.
+    pub fn qif_date_format(mut self, format: QifDateFormat) -> Self {
+        self.qif_date_format = format;
+        self
+    }
+
+    /// Sets the version of written OFX files, 2.x unless set.
+    ///
+    /// OFX input is converted to this version even when the output format
+    /// is OFX as well, instead of being copied.
+    pub fn ofx_version(mut self, version: OfxVersion) -> Self {
+        self.ofx_version = Some(version);
+        self
+    }
+
+    /// Sets the version of written pain.001 messages, `pain.001.001.09`
+    /// unless set.
+    ///
+    /// pain.001 input is converted to this version even when the output
+    /// format is pain.001 as well, instead of being copied.
+    pub fn pain001_version(mut self, version: Pain001Version) -> Self {
+        self.pain001_version = Some(version);
+        self
+    }
+
+    /// Sets what each sheet of written XLSX workbooks holds, one statement
+    /// unless set.
+    ///
+    /// XLSX input is converted to this layout even when the output format
+    /// is XLSX as well, instead of being copied.
+    pub fn xlsx_layout(mut self, layout: SheetLayout) -> Self {
+        self.xlsx_layout = Some(layout);
+        self
+    }
+
+    /// Whether data of `format` is written differently from how it may
+    /// have been read, so that it is converted rather than copied.
+    pub(crate) fn rewrites(&self, format: SupportedFormats) -> bool {
+        match format {
+            SupportedFormats::Ofx => self.ofx_version.is_some(),
+            SupportedFormats::Pain001 => self.pain001_version.is_some(),
+            SupportedFormats::Xlsx => self.xlsx_layout.is_some(),
+            _ => false,
+        }
+    }
+}
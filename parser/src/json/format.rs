@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+/// Current version of the JSON statement model, written to the `version` property.
+pub const STATEMENT_JSON_VERSION: &str = "1.0";
+
+/// JSON Schema describing version [`STATEMENT_JSON_VERSION`] of the statement model.
+pub const STATEMENT_JSON_SCHEMA: &str = include_str!("../../schema/statement-1.0.schema.json");
+
+// Root document, mirrors Camt053 / BkToCstmrStmt
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonStatements {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub statements: Vec<JsonStatement>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonStatement {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub electronic_sequence_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_sequence_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_date_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_date_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<JsonAccount>,
+    #[serde(default)]
+    pub balances: Vec<JsonBalance>,
+    #[serde(default)]
+    pub entries: Vec<JsonEntry>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonAccount {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iban: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub other_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonAmount {
+    /// Decimal kept as text so that no precision is lost, e.g. `"1000.00"`.
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonBalance {
+    /// ISO 20022 balance type code, e.g. `OPBD` or `CLBD`.
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub tp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proprietary_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<JsonAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_debit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_time: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<JsonAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credit_debit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub booking_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub booking_date_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_date_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bank_transaction_code: Option<JsonBankTransactionCode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<JsonTransactionDetails>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonBankTransactionCode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proprietary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonTransactionDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub references: Option<JsonReferences>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<JsonAmount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debtor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creditor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debtor_account: Option<JsonAccount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creditor_account: Option<JsonAccount>,
+    /// BIC of the debtor's bank.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debtor_agent: Option<String>,
+    /// BIC of the creditor's bank.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creditor_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remittance_information: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonReferences {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account_servicer_reference: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_information_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_to_end_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uetr: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+}
+
+/// NDJSON form: one [`JsonEntryLine`] per line, suited for log pipelines.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct NdjsonEntries(pub(crate) Vec<JsonEntryLine>);
+
+/// A single entry together with the statement it belongs to.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JsonEntryLine {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<JsonAccount>,
+    #[serde(flatten)]
+    pub entry: JsonEntry,
+}
@@ -0,0 +1,122 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+use crate::json::format::{JsonEntryLine, JsonStatements, NdjsonEntries, STATEMENT_JSON_VERSION};
+use std::io::BufRead;
+
+impl JsonStatements {
+    pub(crate) fn from_string(s: &str) -> Result<Self, ParserError> {
+        let result: JsonStatements =
+            serde_json::from_str(s).map_err(|e| ParserError::Json(e.to_string()))?;
+
+        if result.version != STATEMENT_JSON_VERSION {
+            return Err(ParserError::Json(format!(
+                "Unsupported statement JSON version: {}",
+                result.version
+            )));
+        }
+
+        Ok(result)
+    }
+}
+
+impl FinancialDataRead for JsonStatements {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Json(e.to_string()))?;
+        Self::from_string(&data)
+    }
+}
+
+impl NdjsonEntries {
+    pub(crate) fn from_string(s: &str) -> Result<Self, ParserError> {
+        let lines = NdjsonLineReader::new(s.as_bytes(), ParseMode::Strict);
+        Ok(NdjsonEntries(lines.collect::<Result<_, _>>()?))
+    }
+}
+
+impl FinancialDataRead for NdjsonEntries {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Json(e.to_string()))?;
+        Self::from_string(&data)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut lines = NdjsonLineReader::new(std::io::BufReader::new(reader), mode);
+        let entries = lines.by_ref().collect::<Result<_, _>>()?;
+        Ok((NdjsonEntries(entries), lines.take_warnings()))
+    }
+}
+
+/// Reads NDJSON entry lines one at a time, skipping blank lines.
+///
+/// Errors are located at the line and column reported by the JSON parser.
+/// In lenient mode lines that cannot be parsed are skipped; see
+/// [`take_warnings`](Self::take_warnings).
+pub(crate) struct NdjsonLineReader<R: BufRead> {
+    source: R,
+    position: SourceSpan,
+    buf: Vec<u8>,
+    recovery: Recovery,
+}
+
+impl<R: BufRead> NdjsonLineReader<R> {
+    pub(crate) fn new(source: R, mode: ParseMode) -> Self {
+        NdjsonLineReader {
+            source,
+            position: SourceSpan::default(),
+            buf: Vec::new(),
+            recovery: Recovery::new(mode),
+        }
+    }
+
+    /// Removes and returns the warnings for the lines read so far.
+    pub(crate) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        self.recovery.take_warnings()
+    }
+
+    fn next_line(&mut self) -> Result<Option<JsonEntryLine>, ParserError> {
+        loop {
+            self.buf.clear();
+            if self.source.read_until(b'\n', &mut self.buf)? == 0 {
+                return Ok(None);
+            }
+            let origin = self.position;
+            self.position.advance(&self.buf);
+
+            let line = String::from_utf8_lossy(&self.buf);
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_entry_line(&line, &origin) {
+                Ok(entry) => return Ok(Some(entry)),
+                Err(e) => self.recovery.recover(WarningKind::SkippedEntry, e)?,
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for NdjsonLineReader<R> {
+    type Item = Result<JsonEntryLine, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_line().transpose()
+    }
+}
+
+/// Parses one NDJSON line that starts at `origin` in the input.
+fn parse_entry_line(line: &str, origin: &SourceSpan) -> Result<JsonEntryLine, ParserError> {
+    serde_json::from_str(line).map_err(|e| {
+        // The position is reported by the diagnostic instead
+        let message = e.to_string();
+        let suffix = format!(" at line {} column {}", e.line(), e.column());
+        let message = message
+            .strip_suffix(&suffix)
+            .unwrap_or(&message)
+            .to_string();
+        let index = e.column().saturating_sub(1).min(line.trim_end().len());
+        ParserError::Json(message).at_text(line, index, 1, origin, None)
+    })
+}
@@ -0,0 +1,87 @@
+use crate::json::format::NdjsonEntries;
+use crate::*;
+
+use std::env;
+use std::fs::File;
+use std::path::PathBuf;
+
+#[test]
+fn test_read_write() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = PathBuf::from(manifest_dir).join("test_data");
+    let new_file_path = path.join("new_file.json");
+
+    let json_data = r#"{
+        "version": "1.0",
+        "messageId": "MSG1",
+        "statements": [{
+            "id": "STMT1",
+            "account": { "iban": "DE89370400440532013000", "currency": "EUR" },
+            "balances": [{
+                "type": "OPBD",
+                "amount": { "value": "1000.00", "currency": "EUR" },
+                "creditDebit": "CRDT",
+                "date": "2023-10-05"
+            }],
+            "entries": [{
+                "amount": { "value": "100.00", "currency": "EUR" },
+                "creditDebit": "CRDT",
+                "details": [{ "remittanceInformation": ["Invoice 12345"] }]
+            }]
+        }]
+    }"#;
+    let json = JsonStatements::from_string(json_data).unwrap();
+
+    let write_json_file = File::create(&new_file_path).unwrap();
+    json.write_to(write_json_file).unwrap();
+
+    let read_json_file = File::open(&new_file_path).unwrap();
+    let read_json = JsonStatements::from_read(read_json_file).unwrap();
+    std::fs::remove_file(&new_file_path).unwrap();
+    assert_eq!(json, read_json);
+}
+
+#[test]
+fn test_unsupported_version() {
+    let json_data = r#"{ "version": "2.0", "statements": [] }"#;
+    assert!(JsonStatements::from_string(json_data).is_err());
+}
+
+#[test]
+fn test_ndjson_read_write() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = PathBuf::from(manifest_dir).join("test_data");
+    let new_file_path = path.join("new_file.ndjson");
+
+    let ndjson_data = "{\"statementId\":\"S1\",\"amount\":{\"value\":\"1.00\"},\"creditDebit\":\"CRDT\"}\n\
+        \n\
+        {\"statementId\":\"S1\",\"amount\":{\"value\":\"2.00\"},\"creditDebit\":\"DBIT\"}\n";
+    let ndjson = NdjsonEntries::from_string(ndjson_data).unwrap();
+    assert_eq!(ndjson.0.len(), 2);
+
+    let write_file = File::create(&new_file_path).unwrap();
+    ndjson.write_to(write_file).unwrap();
+
+    let written = std::fs::read_to_string(&new_file_path).unwrap();
+    let read_ndjson = NdjsonEntries::from_read(File::open(&new_file_path).unwrap()).unwrap();
+    std::fs::remove_file(&new_file_path).unwrap();
+    assert_eq!(written.lines().count(), 2);
+    assert_eq!(ndjson, read_ndjson);
+}
+
+#[test]
+fn test_ndjson_lenient_skips_invalid_line() {
+    let ndjson_data = "{\"statementId\":\"S1\",\"amount\":{\"value\":\"1.00\"},\"creditDebit\":\"CRDT\"}\n\
+        {\"statementId\":\"S1\",\"amount\":{\"value\":\"2.00\"},\"creditDebit\":\n\
+        {\"statementId\":\"S1\",\"amount\":{\"value\":\"3.00\"},\"creditDebit\":\"DBIT\"}\n";
+
+    let error = NdjsonEntries::from_read(ndjson_data.as_bytes()).unwrap_err();
+    assert_eq!(error.diagnostic().unwrap().span.line, 2);
+
+    let (ndjson, warnings) =
+        NdjsonEntries::from_read_with_mode(ndjson_data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(ndjson.0.len(), 2);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 2);
+}
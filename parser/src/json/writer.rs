@@ -0,0 +1,38 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::json::format::{JsonStatements, NdjsonEntries};
+
+impl JsonStatements {
+    fn to_string(&self) -> Result<String, ParserError> {
+        serde_json::to_string_pretty(self).map_err(|e| ParserError::Json(e.to_string()))
+    }
+}
+
+impl FinancialDataWrite for JsonStatements {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = self.to_string()?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
+
+impl NdjsonEntries {
+    fn to_string(&self) -> Result<String, ParserError> {
+        let mut data = String::new();
+        for line in &self.0 {
+            data.push_str(
+                &serde_json::to_string(line).map_err(|e| ParserError::Json(e.to_string()))?,
+            );
+            data.push('\n');
+        }
+        Ok(data)
+    }
+}
+
+impl FinancialDataWrite for NdjsonEntries {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = self.to_string()?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
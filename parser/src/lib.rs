@@ -4,7 +4,10 @@
 //! # Financial Statement Format Converter
 //!
 //! This crate provides tools for **reading, writing, and converting** financial
-//! statement data between multiple formats, including **MT940**, **CAMT.053**, **XML**, and **CSV**.
+//! statement data between multiple formats, including **MT940**, **MT950**, **CAMT.053**, **XML**, **JSON**, **CSV**,
+//! **BAI2**, **CODA**, **OFX**, **QIF**, **CFONB 120**, **Norma 43** and **1C ClientBankExchange**,
+//! as well as **MT900** / **MT910** confirmations, **CAMT.054** notifications and
+//! **pain.001** credit transfer initiations and **XLSX** workbooks.
 //!
 //! ## Overview
 //!
@@ -14,17 +17,42 @@
 //! Supported conversions include:
 //!
 //! - MT940 ↔ CAMT.053
-//! - MT940 → XML
-//! - CAMT.053 → XML
+//! - MT940 ↔ XML
+//! - CAMT.053 ↔ XML
+//! - MT940 / CAMT.053 ↔ JSON, NDJSON
+//! - CAMT.053 ↔ CSV
+//! - CAMT.053 ↔ XLSX, one sheet per statement or account with typed dates and amounts,
+//!   read back through the CSV columns
+//! - CAMT.053 ↔ BAI2, with BAI type codes mapped to ISO bank transaction codes
+//! - CAMT.053 ↔ CODA, with Febelfin transaction families mapped to ISO bank transaction codes
+//! - CAMT.053 ↔ OFX 1.x (SGML) and 2.x (XML), bank and credit card statements
+//! - CAMT.053 ↔ QIF, with US or European dates
+//! - CAMT.053 ↔ CFONB 120 and Norma 43, the French and Spanish fixed-width statements
+//! - CAMT.053 ↔ 1C ClientBankExchange, the Windows-1251 exchange files of Russian accounting systems
+//! - MT950 ↔ CAMT.053, including intermediate `:60M:` / `:62M:` balances
+//! - MT900 / MT910 ↔ CAMT.054, one notification per confirmation, and CAMT.054 ↔ CAMT.053
+//! - CAMT.053 ↔ pain.001 (versions .03 and .09), one payment information block per
+//!   debtor account and execution date, and CSV → pain.001 directly, so payment runs
+//!   prepared in CSV can be initiated
+//! - any other pair, through CAMT.053
 //!
 //! Each format is implemented in its own module and provides parsing and
 //! serialization through shared traits.
 //!
+//! CAMT.053 also has a pull-based streaming reader and an incremental writer,
+//! used by the CAMT.053 → MT940 / NDJSON and NDJSON → CAMT.053 conversions
+//! to process multi-gigabyte files in bounded memory. MT940 input is read one
+//! SWIFT message at a time by the MT940 → CAMT.053 / NDJSON conversions, and
+//! MT950 input by the MT950 → CAMT.053 conversion, so long multi-message
+//! archives are converted in constant memory.
+//!
 //! ## Architecture
 //!
 //! The crate is organized around three main layers:
 //!
-//! - **Format modules** (`mt940`, `camt053`, `xml`, `csv`)
+//! - **Format modules** (`mt940`, `mt950`, `mt900`, `camt053`, `camt054`, `xml`,
+//!   `json`, `csv`, `bai2`, `coda`, `ofx`, `qif`, `cfonb`, `norma43`, `client_bank`,
+//!   `pain001`, `xlsx`)
 //!   Each defines a format-specific struct implementing
 //!   [`FinancialDataRead`] and [`FinancialDataWrite`].
 //!
@@ -36,18 +64,66 @@
 //!   with input/output streams.
 //!
 //! Errors across all modules are represented by the unified [`ParserError`] type.
+//! Errors in MT940 and CAMT.053 input carry their line, column and field tag
+//! or XML path as a [`Diagnostic`], which renders the offending line with a
+//! caret under the problem.
+//!
+//! Readers and conversions are strict by default. In [`ParseMode::Lenient`]
+//! recoverable problems, such as a malformed `:61:` line or CAMT.053 entry,
+//! leave the affected part out and are returned as [`ParseWarning`]s.
+//!
+//! Variants of a format, such as the order of day and month in QIF dates
+//! or the OFX and pain.001 versions, are chosen with [`FormatOptions`]
+//! rather than by a format of their own.
+//!
+//! Entries can be selected between reading and writing with an
+//! [`EntryFilter`](filter::EntryFilter), which keeps the balances of the
+//! filtered statements consistent or flags them as no longer reconciled.
+//!
+//! Statuses of sent payments from a pain.002 report are linked to the
+//! statement entries with their end-to-end id by the [`payment_status`]
+//! module.
+//!
+//! Conversions are routed by a [`ConversionGraph`](converter::graph::ConversionGraph)
+//! of format converters. Pairs without a direct converter are converted
+//! along the shortest chain, normally through CAMT.053, the canonical model.
 //!
 //! ## Extending the crate
 //!
 //! To add support for a new format:
 //!
 //! 1. Create a new module implementing [`FinancialDataRead`] and [`FinancialDataWrite`].
-//! 2. Add conversions (`TryFrom` implementations) to or from existing formats.
-//! 3. Register the format in [`SupportedFormats`] and extend the converter logic.
+//! 2. Add conversions (`TryFrom` implementations) to and from `Camt053`.
+//! 3. Register the format in [`SupportedFormats`] and add its converters to
+//!    `converter::convert_streams::builtin_converters`.
+//!
+//! Other crates add formats at runtime by implementing [`Format`] — name,
+//! aliases, detection and [`Converter`](converter::graph::Converter)s to
+//! read and write it — and calling [`register_format`]. Registered formats
+//! are parsed by [`SupportedFormats`]'s `FromStr` as [`SupportedFormats::Other`]
+//! and converted by `convert_streams` like the built-in ones.
 
+pub(crate) mod bai2;
 pub(crate) mod camt053;
+pub(crate) mod camt054;
+pub(crate) mod cfonb;
+pub(crate) mod client_bank;
+pub(crate) mod coda;
 pub(crate) mod csv;
+pub(crate) mod fixed_width;
+pub(crate) mod format_options;
+pub(crate) mod json;
+pub(crate) mod mt900;
 pub(crate) mod mt940;
+pub(crate) mod mt950;
+pub(crate) mod norma43;
+pub(crate) mod ofx;
+pub(crate) mod pain001;
+pub(crate) mod pain002;
+pub(crate) mod qif;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub(crate) mod xlsx;
 pub(crate) mod xml;
 
 /// Core conversion utilities and logic shared by format converters.
@@ -56,12 +132,54 @@ pub(crate) mod xml;
 /// different format handlers.
 pub mod converter;
 
+/// Detection of the input format from the first bytes of a stream.
+///
+/// Recognises the formats this crate converts as well as related ones
+/// (MT942, camt.052, pain.002), each with a confidence score.
+pub mod detect;
+
+/// Semantic comparison of two versions of a statement document.
+///
+/// Used by [`diff_streams`](converter::convert_streams::diff_streams) to
+/// report added, removed and modified entries and changed balances, as
+/// text or JSON, also between documents in different formats.
+pub mod diff;
+
 /// Module defining all error types used throughout the library.
 ///
 /// Contains the [`ParserError`] enum and related conversions for handling
 /// parsing, I/O, and format conversion errors in a consistent way.
 pub mod errors;
 
+/// Selection of statement entries by date, amount, direction, currency,
+/// transaction code, counterparty and narrative.
+///
+/// Used by [`convert_streams_filtered`](converter::convert_streams::convert_streams_filtered)
+/// between reading and writing; adjusts or flags the balances of the
+/// statements that lost entries.
+pub mod filter;
+
+/// Merging of statements into weekly or monthly ones, and splitting of
+/// documents by account or day.
+///
+/// Used by [`merge_streams`](converter::convert_streams::merge_streams) and
+/// [`split_streams`](converter::convert_streams::split_streams), which
+/// recompute the balances of the statements they produce.
+pub mod merge;
+
+/// Payment status reports (pain.002) and their link to statement entries.
+///
+/// Used by [`link_payment_statuses`](converter::convert_streams::link_payment_statuses)
+/// to show which rejected payments were booked anyway and which accepted
+/// ones never appeared in the statement.
+pub mod payment_status;
+
+/// Registry of statement formats, extensible at runtime.
+///
+/// Lists the built-in formats and lets other crates add their own through
+/// the [`Format`] trait, without changing [`SupportedFormats`].
+pub mod registry;
+
 /// Traits defining the core read/write interfaces for financial data formats.
 ///
 /// Provides the [`FinancialDataRead`] and [`FinancialDataWrite`] traits used
@@ -69,13 +187,37 @@ pub mod errors;
 pub mod traits;
 
 // Structs for internal use
+pub(crate) use bai2::format::Bai2;
 pub(crate) use camt053::format::Camt053;
+pub(crate) use camt054::format::Camt054;
+pub(crate) use cfonb::format::Cfonb;
+pub(crate) use client_bank::format::ClientBankExchange;
+pub(crate) use coda::format::Coda;
 pub(crate) use csv::format::CsvWrapper;
+pub(crate) use json::format::JsonStatements;
+pub(crate) use mt900::format::Mt900;
 pub(crate) use mt940::format::Mt940;
+pub(crate) use mt950::format::Mt950;
+pub(crate) use norma43::format::Norma43;
+pub(crate) use ofx::format::Ofx;
+pub(crate) use pain001::format::Pain001;
+pub(crate) use qif::format::Qif;
+pub(crate) use xlsx::format::Xlsx;
 pub(crate) use xml::format::XmlWrapper;
 
-pub use errors::ParserError;
+pub use detect::{DetectedFormat, Detection, detect_format, detect_stream};
+pub use errors::{
+    Diagnostic, ErrorLocation, ParseMode, ParseWarning, ParserError, SourceSpan, WarningKind,
+};
+pub use format_options::FormatOptions;
+pub use json::format::{STATEMENT_JSON_SCHEMA, STATEMENT_JSON_VERSION};
+pub use ofx::format::OfxVersion;
+pub use pain001::format::Pain001Version;
+pub use qif::format::QifDateFormat;
+pub use registry::{Format, register_format};
 pub use traits::{FinancialDataRead, FinancialDataWrite};
+pub use xlsx::format::SheetLayout;
+pub use xml::options::XmlWriteOptions;
 
 /// Enumeration of supported statement formats.
 ///
@@ -94,32 +236,139 @@ pub enum SupportedFormats {
 
     /// **MT940** format — a SWIFT standard format for bank statements widely used in international banking.
     Mt940,
+
+    /// **JSON** format — the statement model as a single versioned JSON document.
+    Json,
+
+    /// **NDJSON** format — newline-delimited JSON with one statement entry per line.
+    Ndjson,
+
+    /// **BAI2** format — the BAI cash management balance reporting format used by US banks.
+    Bai2,
+
+    /// **CODA** format — the Belgian coded statement of account, in fixed-width 128-character records.
+    Coda,
+
+    /// **OFX** format — the Open Financial Exchange statement download, read in SGML (1.x) or
+    /// XML (2.x) and written in the version set by [`FormatOptions::ofx_version`].
+    Ofx,
+
+    /// **QIF** format — the Quicken Interchange Format of personal finance tools, with US or
+    /// European dates as set by [`FormatOptions::qif_date_format`].
+    Qif,
+
+    /// **CFONB 120** format — the French bank statement, in fixed-width 120-character records.
+    Cfonb,
+
+    /// **Norma 43** format — the Spanish AEB/CSB 43 bank statement, in fixed-width 80-character records.
+    Norma43,
+
+    /// **1C ClientBankExchange** format — the `key=value` statement exchange file of Russian accounting systems, in Windows-1251.
+    ClientBank,
+
+    /// **MT950** format — the SWIFT statement message of nostro and vostro accounts, MT940 without narratives.
+    Mt950,
+
+    /// **MT900** / **MT910** format — SWIFT confirmations of a single debit or credit to an account.
+    Mt900,
+
+    /// **CAMT.054** format — the ISO 20022 XML-based bank-to-customer debit/credit notification.
+    Camt054,
+
+    /// **pain.001** format — the ISO 20022 customer credit transfer initiation, read in versions
+    /// `.03` and `.09` and written in the version set by [`FormatOptions::pain001_version`].
+    Pain001,
+
+    /// **XLSX** format — an Excel workbook with typed date and amount cells, one sheet per
+    /// statement or per account as set by [`FormatOptions::xlsx_layout`].
+    Xlsx,
+
+    /// A format added with [`register_format`], by name.
+    Other(&'static str),
 }
 
 impl std::str::FromStr for SupportedFormats {
     type Err = String;
 
+    /// Parses the name or an alias of a built-in or registered [`Format`], ignoring case.
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "mt940" => Ok(SupportedFormats::Mt940),
-            "camt053" => Ok(SupportedFormats::Camt053),
-            "xml" => Ok(SupportedFormats::Xml),
-            "csv" => Ok(SupportedFormats::Csv),
-            _ => Err(format!(
-                "Unknown format: {}. Use 'mt940', 'camt053', 'xml' or 'csv'",
-                s
-            )),
-        }
+        let Some(format) = registry::find_format(s) else {
+            let names: Vec<String> = registry::formats()
+                .iter()
+                .map(|format| format!("'{}'", format.name()))
+                .collect();
+            let names = match names.split_last() {
+                Some((last, rest)) if !rest.is_empty() => {
+                    format!("{} or {}", rest.join(", "), last)
+                }
+                _ => names.concat(),
+            };
+            return Err(format!("Unknown format: {}. Use {}", s, names));
+        };
+        Ok(SupportedFormats::BUILTIN
+            .into_iter()
+            .find(|builtin| builtin.name() == format.name())
+            .unwrap_or(SupportedFormats::Other(format.name())))
     }
 }
 
 impl SupportedFormats {
-    fn to_string(&self) -> String {
+    /// The formats implemented by this crate.
+    const BUILTIN: [SupportedFormats; 18] = [
+        SupportedFormats::Mt940,
+        SupportedFormats::Camt053,
+        SupportedFormats::Xml,
+        SupportedFormats::Csv,
+        SupportedFormats::Json,
+        SupportedFormats::Ndjson,
+        SupportedFormats::Bai2,
+        SupportedFormats::Coda,
+        SupportedFormats::Ofx,
+        SupportedFormats::Qif,
+        SupportedFormats::Cfonb,
+        SupportedFormats::Norma43,
+        SupportedFormats::ClientBank,
+        SupportedFormats::Mt950,
+        SupportedFormats::Mt900,
+        SupportedFormats::Camt054,
+        SupportedFormats::Pain001,
+        SupportedFormats::Xlsx,
+    ];
+
+    /// Returns `true` for formats whose output is an XML document.
+    pub(crate) fn is_xml(&self) -> bool {
+        matches!(
+            self,
+            SupportedFormats::Xml
+                | SupportedFormats::Camt053
+                | SupportedFormats::Camt054
+                | SupportedFormats::Pain001
+        )
+    }
+
+    /// The lowercase name of the format, as accepted by [`std::str::FromStr`]
+    /// and used by [`converter::graph::ConversionGraph`].
+    pub fn name(&self) -> &'static str {
         match self {
-            SupportedFormats::Mt940 => "mt940".to_string(),
-            SupportedFormats::Camt053 => "camt053".to_string(),
-            SupportedFormats::Xml => "xml".to_string(),
-            SupportedFormats::Csv => "csv".to_string(),
+            SupportedFormats::Mt940 => "mt940",
+            SupportedFormats::Camt053 => "camt053",
+            SupportedFormats::Xml => "xml",
+            SupportedFormats::Csv => "csv",
+            SupportedFormats::Json => "json",
+            SupportedFormats::Ndjson => "ndjson",
+            SupportedFormats::Bai2 => "bai2",
+            SupportedFormats::Coda => "coda",
+            SupportedFormats::Ofx => "ofx",
+            SupportedFormats::Qif => "qif",
+            SupportedFormats::Cfonb => "cfonb",
+            SupportedFormats::Norma43 => "norma43",
+            SupportedFormats::ClientBank => "1c",
+            SupportedFormats::Mt950 => "mt950",
+            SupportedFormats::Mt900 => "mt900",
+            SupportedFormats::Camt054 => "camt054",
+            SupportedFormats::Pain001 => "pain001",
+            SupportedFormats::Xlsx => "xlsx",
+            SupportedFormats::Other(name) => name,
         }
     }
 }
@@ -0,0 +1,532 @@
+use crate::camt053::balance::{
+    SignedAmount, account_id, balance_code, entry_date, find_balance, new_balance, signed_amount,
+    statement_first_day,
+};
+use crate::camt053::format::*;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use std::collections::HashMap;
+
+/// Calendar period of the statements produced by [`merge_streams`](crate::converter::convert_streams::merge_streams).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// One statement per calendar day.
+    Day,
+    /// One statement per ISO week, Monday to Sunday.
+    Week,
+    /// One statement per calendar month.
+    Month,
+}
+
+impl Period {
+    /// The first day of the period `date` falls in.
+    fn start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Period::Day => date,
+            Period::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Period::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+impl std::str::FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "day" | "daily" => Ok(Period::Day),
+            "week" | "weekly" => Ok(Period::Week),
+            "month" | "monthly" => Ok(Period::Month),
+            _ => Err(format!(
+                "Unknown period: {}. Use 'day', 'week' or 'month'",
+                s
+            )),
+        }
+    }
+}
+
+/// How [`split_streams`](crate::converter::convert_streams::split_streams) divides a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// One document per account, keyed by IBAN or other account id.
+    Account,
+    /// One document per calendar day, keyed by the date (`YYYY-MM-DD`).
+    /// Statements spanning several days are cut into daily statements.
+    Day,
+}
+
+impl std::str::FromStr for SplitBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "account" => Ok(SplitBy::Account),
+            "day" => Ok(SplitBy::Day),
+            _ => Err(format!("Unknown split: {}. Use 'account' or 'day'", s)),
+        }
+    }
+}
+
+/// Net amount of `entries`, or `None` if one of them has no valid amount.
+fn net_amount<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Option<SignedAmount> {
+    entries
+        .into_iter()
+        .try_fold(SignedAmount::default(), |net, entry| {
+            Some(net.add(signed_amount(&entry.amt, &entry.cdt_dbt_ind)?))
+        })
+}
+
+fn balance_currency(stmt: &Statement) -> Option<String> {
+    stmt.bal
+        .iter()
+        .find_map(|b| b.amt.as_ref().and_then(|a| a.currency.clone()))
+        .or_else(|| stmt.acct.as_ref().and_then(|a| a.ccy.clone()))
+}
+
+fn day(date: NaiveDate) -> Option<DateAndDateTimeChoice> {
+    Some(DateAndDateTimeChoice {
+        dt: Some(date.format("%Y-%m-%d").to_string()),
+        dt_tm: None,
+    })
+}
+
+/// Merges the statements of `documents` into one statement per account,
+/// currency and `period`.
+///
+/// The merged statement opens with the opening balance of the earliest
+/// statement and closes with that balance plus all entries; other balances,
+/// such as the closing available balance, are taken from the latest
+/// statement. Statements without a date are kept as they are. The group
+/// header is that of the first document.
+pub(crate) fn merge(documents: Vec<Camt053>, period: Period) -> Camt053 {
+    let mut documents = documents.into_iter();
+    let Some(mut merged) = documents.next() else {
+        return Camt053::default();
+    };
+    let mut statements = std::mem::take(&mut merged.bk_to_cstmr_stmt.stmts);
+    statements.extend(documents.flat_map(|document| document.bk_to_cstmr_stmt.stmts));
+
+    // Groups in order of their first statement
+    let mut groups: Vec<Vec<Statement>> = Vec::new();
+    let mut index = HashMap::new();
+    for stmt in statements {
+        let Some(date) = statement_first_day(&stmt) else {
+            groups.push(vec![stmt]);
+            continue;
+        };
+        let key = (
+            account_id(&stmt),
+            stmt.acct.as_ref().and_then(|a| a.ccy.clone()),
+            period.start(date),
+        );
+        let group = *index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(stmt);
+    }
+
+    merged.bk_to_cstmr_stmt.stmts = groups
+        .into_iter()
+        .map(|mut group| {
+            group.sort_by_key(statement_first_day);
+            merge_group(group)
+        })
+        .collect();
+    merged
+}
+
+/// Merges statements of one account, sorted by date, into one.
+fn merge_group(group: Vec<Statement>) -> Statement {
+    let mut group = group.into_iter();
+    let Some(mut merged) = group.next() else {
+        return Statement::default();
+    };
+    if group.len() == 0 {
+        return merged;
+    }
+    for stmt in group {
+        merged.ntry.extend(stmt.ntry);
+        merged.cre_dt_tm = stmt.cre_dt_tm.or(merged.cre_dt_tm);
+        if let Some(to) = stmt.fr_to_dt.and_then(|period| period.to_dt_tm) {
+            merged
+                .fr_to_dt
+                .get_or_insert_with(FromToDate::default)
+                .to_dt_tm = Some(to);
+        }
+        // Balances other than the opening ones describe the end of the period
+        merged
+            .bal
+            .retain(|b| matches!(balance_code(b), Some("OPBD" | "PRCD")));
+        merged.bal.extend(
+            stmt.bal
+                .into_iter()
+                .filter(|b| !matches!(balance_code(b), Some("OPBD" | "PRCD"))),
+        );
+    }
+
+    let opening = find_balance(&merged, "OPBD").and_then(|b| signed_amount(&b.amt, &b.cdt_dbt_ind));
+    if let (Some(opening), Some(net)) = (opening, net_amount(&merged.ntry)) {
+        let currency = balance_currency(&merged);
+        let closing = merged
+            .bal
+            .iter()
+            .position(|b| balance_code(b) == Some("CLBD"));
+        let dt = closing.and_then(|i| merged.bal[i].dt.clone());
+        let balance = new_balance("CLBD", opening.add(net), currency, dt);
+        match closing {
+            Some(i) => merged.bal[i] = balance,
+            None => merged.bal.push(balance),
+        }
+    }
+    merged
+}
+
+/// Splits `document` into documents keyed by account or day, in order of
+/// their first statement. Every document has the group header of `document`.
+pub(crate) fn split(document: Camt053, by: SplitBy) -> Vec<(String, Camt053)> {
+    let grp_hdr = document.bk_to_cstmr_stmt.grp_hdr;
+    let statements = document.bk_to_cstmr_stmt.stmts;
+    let keyed: Vec<(String, Statement)> = match by {
+        SplitBy::Account => statements
+            .into_iter()
+            .map(|stmt| {
+                let key = account_id(&stmt).unwrap_or_else(|| "unknown".to_string());
+                (key, stmt)
+            })
+            .collect(),
+        SplitBy::Day => statements.into_iter().flat_map(split_by_day).collect(),
+    };
+
+    let mut documents: Vec<(String, Camt053)> = Vec::new();
+    for (key, stmt) in keyed {
+        match documents.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, document)) => document.bk_to_cstmr_stmt.stmts.push(stmt),
+            None => documents.push((
+                key,
+                Camt053 {
+                    bk_to_cstmr_stmt: BankToCustomerStatement {
+                        grp_hdr: grp_hdr.clone(),
+                        stmts: vec![stmt],
+                    },
+                },
+            )),
+        }
+    }
+    documents
+}
+
+/// Cuts a statement into one statement per booking day, with balances
+/// carried from day to day.
+///
+/// Entries without a date go into a last, `undated` statement without
+/// balances. The daily balances start from the opening balance, or are
+/// worked back from the closing balance; without either they are left out.
+fn split_by_day(stmt: Statement) -> Vec<(String, Statement)> {
+    let first_day = statement_first_day(&stmt);
+    let mut days: Vec<(Option<NaiveDate>, Vec<Entry>)> = Vec::new();
+    let mut stmt = stmt;
+    for entry in std::mem::take(&mut stmt.ntry) {
+        let date = entry_date(&entry);
+        match days.iter_mut().find(|(d, _)| *d == date) {
+            Some((_, entries)) => entries.push(entry),
+            None => days.push((date, vec![entry])),
+        }
+    }
+    days.sort_by_key(|(date, _)| (date.is_none(), *date));
+
+    let key = |date: Option<NaiveDate>| {
+        date.map_or("undated".to_string(), |d| d.format("%Y-%m-%d").to_string())
+    };
+    if days.len() <= 1 {
+        let (date, entries) = days.pop().unwrap_or((first_day, Vec::new()));
+        stmt.ntry = entries;
+        return vec![(key(date), stmt)];
+    }
+
+    let net = net_amount(days.iter().flat_map(|(_, entries)| entries));
+    let balance =
+        |code: &str| find_balance(&stmt, code).and_then(|b| signed_amount(&b.amt, &b.cdt_dbt_ind));
+    let mut running = balance("OPBD")
+        .or_else(|| Some(balance("CLBD")?.add(net?.negate())))
+        .filter(|_| net.is_some());
+    let currency = balance_currency(&stmt);
+
+    days.into_iter()
+        .map(|(date, entries)| {
+            let mut daily = Statement {
+                id: match (&stmt.id, date) {
+                    (Some(id), Some(date)) => Some(format!("{}-{}", id, date.format("%Y%m%d"))),
+                    (id, _) => id.clone(),
+                },
+                fr_to_dt: date.map(|date| FromToDate {
+                    fr_dt_tm: Some(format!("{}T00:00:00", date)),
+                    to_dt_tm: Some(format!("{}T23:59:59", date)),
+                }),
+                bal: Vec::new(),
+                ntry: Vec::new(),
+                ..stmt.clone()
+            };
+            if let (Some(opening), Some(date)) = (running, date) {
+                let closing = net_amount(&entries).map(|net| opening.add(net));
+                if let Some(closing) = closing {
+                    daily.bal = vec![
+                        new_balance("OPBD", opening, currency.clone(), day(date)),
+                        new_balance("CLBD", closing, currency.clone(), day(date)),
+                    ];
+                }
+                running = closing;
+            }
+            daily.ntry = entries;
+            (key(date), daily)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::FinancialDataRead;
+
+    fn entry(amount: &str, indicator: &str, date: &str) -> Entry {
+        Entry {
+            amt: Some(Amount {
+                currency: Some("EUR".to_string()),
+                value: Some(amount.to_string()),
+            }),
+            cdt_dbt_ind: Some(indicator.to_string()),
+            bookg_dt: Some(DateAndDateTimeChoice {
+                dt: Some(date.to_string()),
+                dt_tm: None,
+            }),
+            ..Entry::default()
+        }
+    }
+
+    fn daily(iban: &str, date: &str, opening: &str, entries: Vec<Entry>) -> Statement {
+        let opening_amount = SignedAmount::parse(opening).unwrap();
+        let closing = opening_amount.add(net_amount(&entries).unwrap());
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Statement {
+            id: Some(format!("{}-{}", iban, date)),
+            acct: Some(Account {
+                id: Some(AccountId {
+                    iban: Some(iban.to_string()),
+                    other: None,
+                }),
+                ccy: Some("EUR".to_string()),
+                ..Account::default()
+            }),
+            bal: vec![
+                new_balance("OPBD", opening_amount, Some("EUR".into()), day(date)),
+                new_balance("CLBD", closing, Some("EUR".into()), day(date)),
+            ],
+            ntry: entries,
+            ..Statement::default()
+        }
+    }
+
+    fn document(stmts: Vec<Statement>) -> Camt053 {
+        Camt053 {
+            bk_to_cstmr_stmt: BankToCustomerStatement {
+                grp_hdr: GroupHeader {
+                    msg_id: Some("MSG1".to_string()),
+                    ..GroupHeader::default()
+                },
+                stmts,
+            },
+        }
+    }
+
+    fn amount_of(stmt: &Statement, code: &str) -> (String, String) {
+        let balance = find_balance(stmt, code).unwrap();
+        (
+            balance.amt.as_ref().unwrap().value.clone().unwrap(),
+            balance.cdt_dbt_ind.clone().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_merge_weekly() {
+        // Monday, Tuesday and the next Monday, given out of order
+        let documents = vec![
+            document(vec![daily(
+                "DE01",
+                "2025-10-14",
+                "150.00",
+                vec![entry("200.00", "DBIT", "2025-10-14")],
+            )]),
+            document(vec![
+                daily(
+                    "DE01",
+                    "2025-10-13",
+                    "100.00",
+                    vec![entry("50.00", "CRDT", "2025-10-13")],
+                ),
+                daily("DE02", "2025-10-13", "0.00", vec![]),
+                daily("DE01", "2025-10-20", "0.00", vec![]),
+            ]),
+        ];
+
+        let merged = merge(documents, Period::Week);
+        let stmts = &merged.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 3);
+        assert_eq!(stmts[0].id.as_deref(), Some("DE01-2025-10-13"));
+        assert_eq!(stmts[0].ntry.len(), 2);
+        assert_eq!(
+            amount_of(&stmts[0], "OPBD"),
+            ("100.00".into(), "CRDT".into())
+        );
+        assert_eq!(
+            amount_of(&stmts[0], "CLBD"),
+            ("50.00".into(), "DBIT".into())
+        );
+        assert_eq!(
+            find_balance(&stmts[0], "CLBD").unwrap().dt,
+            day(NaiveDate::from_ymd_opt(2025, 10, 14).unwrap())
+        );
+        assert_eq!(account_id(&stmts[1]).as_deref(), Some("DE02"));
+        assert_eq!(stmts[2].id.as_deref(), Some("DE01-2025-10-20"));
+
+        let merged = merge(vec![document(vec![])], Period::Month);
+        assert_eq!(
+            merged.bk_to_cstmr_stmt.grp_hdr.msg_id.as_deref(),
+            Some("MSG1")
+        );
+    }
+
+    #[test]
+    fn test_split_by_account_and_day() {
+        let stmt = daily(
+            "DE01",
+            "2025-10-13",
+            "100.00",
+            vec![
+                entry("50.00", "CRDT", "2025-10-13"),
+                entry("30.00", "DBIT", "2025-10-15"),
+                entry("10.00", "DBIT", "2025-10-13"),
+            ],
+        );
+        let other = daily("DE02", "2025-10-13", "0.00", vec![]);
+
+        let accounts = split(
+            document(vec![stmt.clone(), other.clone()]),
+            SplitBy::Account,
+        );
+        let keys: Vec<_> = accounts.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["DE01", "DE02"]);
+        assert_eq!(
+            accounts[0].1.bk_to_cstmr_stmt.grp_hdr.msg_id.as_deref(),
+            Some("MSG1")
+        );
+
+        let days = split(document(vec![stmt, other]), SplitBy::Day);
+        let keys: Vec<_> = days.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["2025-10-13", "2025-10-15"]);
+        let first = &days[0].1.bk_to_cstmr_stmt.stmts;
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].ntry.len(), 2);
+        assert_eq!(
+            amount_of(&first[0], "CLBD"),
+            ("140.00".into(), "CRDT".into())
+        );
+        let second = &days[1].1.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(second.id.as_deref(), Some("DE01-2025-10-13-20251015"));
+        assert_eq!(amount_of(second, "OPBD"), ("140.00".into(), "CRDT".into()));
+        assert_eq!(amount_of(second, "CLBD"), ("110.00".into(), "CRDT".into()));
+    }
+
+    #[test]
+    fn test_split_mt940_by_day() {
+        use crate::Mt940;
+        use crate::test_utils::read_test_file;
+
+        let mt940 = Mt940::from_read(read_test_file("valid1.mt940").as_bytes()).unwrap();
+        let camt053 = Camt053::try_from(&mt940).unwrap();
+        let days = split(camt053, SplitBy::Day);
+        let keys: Vec<_> = days.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["2025-10-21"]);
+        assert_eq!(days[0].1.bk_to_cstmr_stmt.stmts[0].ntry.len(), 3);
+
+        // Entries without a booking or value date are not put on any day
+        let mut undated = entry("5.00", "DBIT", "2025-10-13");
+        undated.bookg_dt = None;
+        let stmt = daily(
+            "DE01",
+            "2025-10-13",
+            "100.00",
+            vec![undated, entry("50.00", "CRDT", "2025-10-14")],
+        );
+        let days = split(document(vec![stmt]), SplitBy::Day);
+        let keys: Vec<_> = days.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["2025-10-14", "undated"]);
+        let first = &days[0].1.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(amount_of(first, "OPBD"), ("100.00".into(), "CRDT".into()));
+        assert_eq!(amount_of(first, "CLBD"), ("150.00".into(), "CRDT".into()));
+        let undated = &days[1].1.bk_to_cstmr_stmt.stmts[0];
+        assert_eq!(undated.ntry.len(), 1);
+        assert!(undated.bal.is_empty());
+    }
+
+    #[test]
+    fn test_merge_valid_statement_is_unchanged() {
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = std::path::PathBuf::from(manifest_dir).join("test_data/valid1.camt053");
+        let camt053 = Camt053::from_read(std::fs::File::open(&path).unwrap()).unwrap();
+        let expected = Camt053::from_read(std::fs::File::open(&path).unwrap()).unwrap();
+
+        assert_eq!(merge(vec![camt053], Period::Month), expected);
+    }
+
+    #[test]
+    fn test_merge_and_split_streams() {
+        use crate::converter::convert_streams::{merge_streams, split_streams};
+        use crate::converter::graph::{ConversionContext, SharedBuffer};
+        use crate::{SupportedFormats, XmlWriteOptions};
+
+        let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = std::path::PathBuf::from(manifest_dir).join("test_data/valid1.camt053");
+        let open = || Box::new(std::fs::File::open(&path).unwrap()) as Box<dyn std::io::Read>;
+
+        let output = SharedBuffer::default();
+        merge_streams(
+            vec![open(), open()],
+            SupportedFormats::Camt053,
+            Box::new(output.clone()),
+            SupportedFormats::Camt053,
+            &XmlWriteOptions::default(),
+            ConversionContext::default(),
+            Period::Month,
+        )
+        .unwrap();
+        let merged = Camt053::from_read(output.take().as_slice()).unwrap();
+        let stmts = &merged.bk_to_cstmr_stmt.stmts;
+        assert_eq!(stmts.len(), 1);
+        assert_eq!(stmts[0].ntry.len(), 4);
+        assert_eq!(
+            amount_of(&stmts[0], "CLBD"),
+            ("1100.00".into(), "CRDT".into())
+        );
+
+        let mut parts = Vec::new();
+        split_streams(
+            open(),
+            SupportedFormats::Camt053,
+            SupportedFormats::Mt940,
+            &XmlWriteOptions::default(),
+            ConversionContext::default(),
+            SplitBy::Day,
+            |key| {
+                let part = SharedBuffer::default();
+                parts.push((key.to_string(), part.clone()));
+                Ok(Box::new(part))
+            },
+        )
+        .unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].0, "2023-10-04");
+        let mt940 = String::from_utf8(parts[1].1.take()).unwrap();
+        assert!(mt940.contains(":60F:C231005EUR950,00"));
+        assert!(mt940.contains(":62F:C231005EUR1050,00"));
+    }
+}
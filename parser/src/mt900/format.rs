@@ -0,0 +1,92 @@
+use crate::mt940::format::{
+    ApplicationHeaderBlock, BasicHeaderBlock, TrailerBlock, UserHeaderBlock,
+};
+use swift_mt_message::fields::{
+    Field13D, Field25AccountIdentification, Field32A, Field52OrderingInstitution, Field72,
+};
+use swift_mt_message::messages;
+
+/// An MT900 confirmation of debit or MT910 confirmation of credit, in the
+/// envelope of [`Mt940`](crate::Mt940).
+///
+/// Both confirm a single booking on the receiver's account; the message
+/// type in block 2 tells which.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Mt900 {
+    pub basic_header: BasicHeaderBlock,
+    pub application_header: ApplicationHeaderBlock,
+    pub user_header: Option<UserHeaderBlock>, // may be skipped
+    pub confirmation: Confirmation,
+    pub footer: Option<TrailerBlock>, // may be skipped
+}
+
+/// The text block of an MT900 or MT910.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Confirmation {
+    Debit(messages::MT900),
+    /// Also names the ordering customer (`:50a:`) and the intermediary (`:56a:`).
+    Credit(messages::MT910),
+}
+
+// The fields both message types have
+macro_rules! common_field {
+    ($confirmation:expr, $field:ident) => {
+        match $confirmation {
+            Confirmation::Debit(message) => &message.$field,
+            Confirmation::Credit(message) => &message.$field,
+        }
+    };
+}
+
+impl Confirmation {
+    /// `900` or `910`.
+    pub(crate) fn message_type(&self) -> &'static str {
+        match self {
+            Confirmation::Debit(_) => "900",
+            Confirmation::Credit(_) => "910",
+        }
+    }
+
+    /// `:20:`, the reference of the confirmation.
+    pub(crate) fn reference(&self) -> &str {
+        &common_field!(self, field_20).reference
+    }
+
+    /// `:21:`, the reference of the booked transaction, `NONREF` if there is none.
+    pub(crate) fn related_reference(&self) -> &str {
+        &common_field!(self, field_21).reference
+    }
+
+    /// `:25:` or `:25P:`, the account booked.
+    pub(crate) fn account(&self) -> &Field25AccountIdentification {
+        common_field!(self, field_25)
+    }
+
+    /// `:13D:`, when the booking was made.
+    pub(crate) fn date_time(&self) -> Option<&Field13D> {
+        common_field!(self, field_13d).as_ref()
+    }
+
+    /// `:32A:`, the value date, currency and amount.
+    pub(crate) fn amount(&self) -> &Field32A {
+        common_field!(self, field_32a)
+    }
+
+    /// `:52a:`, the institution that ordered the payment.
+    pub(crate) fn ordering_institution(&self) -> Option<&Field52OrderingInstitution> {
+        common_field!(self, field_52).as_ref()
+    }
+
+    /// `:72:`, sender to receiver information.
+    pub(crate) fn information(&self) -> Option<&Field72> {
+        common_field!(self, field_72).as_ref()
+    }
+
+    /// The text block as produced by `to_mt_string`.
+    pub(crate) fn to_mt_string(&self) -> String {
+        match self {
+            Confirmation::Debit(message) => message.to_mt_string(),
+            Confirmation::Credit(message) => message.to_mt_string(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan};
+use crate::mt900::format::{Confirmation, Mt900};
+use crate::mt940::format::ApplicationHeaderBlock;
+use crate::mt940::reader::{TextBlockFields, check_field, parse_message, sender_bic};
+use crate::mt940::stream::FinMessage;
+use swift_mt_message::fields::{
+    Field13D, Field20, Field21NoOption, Field25NoOption, Field25P, Field32A, Field50A, Field50F,
+    Field50K, Field52A, Field52D, Field56A, Field56C, Field56D, Field72,
+};
+use swift_mt_message::messages;
+
+/// Validates one field of the MT900 or MT910 text block on its own.
+fn check_text_field(tag: &str, value: &str) -> Result<(), String> {
+    match tag {
+        "20" => check_field::<Field20>(value),
+        "21" => check_field::<Field21NoOption>(value),
+        "25" => check_field::<Field25NoOption>(value),
+        "25P" => check_field::<Field25P>(value),
+        "13D" => check_field::<Field13D>(value),
+        "32A" => check_field::<Field32A>(value),
+        "50A" => check_field::<Field50A>(value),
+        "50F" => check_field::<Field50F>(value),
+        "50K" => check_field::<Field50K>(value),
+        "52A" => check_field::<Field52A>(value),
+        "52D" => check_field::<Field52D>(value),
+        "56A" => check_field::<Field56A>(value),
+        "56C" => check_field::<Field56C>(value),
+        "56D" => check_field::<Field56D>(value),
+        "72" => check_field::<Field72>(value),
+        _ => Ok(()),
+    }
+}
+
+/// Fields of the MT900 and MT910 text blocks.
+const MT900_FIELDS: TextBlockFields = TextBlockFields {
+    error: ParserError::Mt900,
+    check: check_text_field,
+    optional: &[
+        "13D", "50A", "50F", "50K", "52A", "52D", "56A", "56C", "56D", "72",
+    ],
+};
+
+/// Reads the text block as the message type of block 2.
+///
+/// Messages without envelope are MT910 if they name an ordering customer or
+/// an intermediary, which MT900 has not; otherwise the type is unknown.
+fn parse_confirmation(
+    application_header: &ApplicationHeaderBlock,
+    text: &str,
+) -> Result<Confirmation, ParserError> {
+    let credit_only = text
+        .lines()
+        .any(|line| line.starts_with(":50") || line.starts_with(":56"));
+    let credit = match application_header.message_type() {
+        Some("900") => false,
+        Some("910") => true,
+        Some(other) => {
+            return Err(ParserError::Mt900(format!(
+                "Expected an MT900 or MT910 message, found MT{}",
+                other
+            )));
+        }
+        None if credit_only => true,
+        None => {
+            return Err(ParserError::Mt900(
+                "Missing message type: without block 2 an MT900 cannot be told from an MT910"
+                    .to_string(),
+            ));
+        }
+    };
+    let error = |e: swift_mt_message::ParseError| ParserError::Mt900(e.to_string());
+    if credit {
+        messages::MT910::parse_from_block4(text)
+            .map(Confirmation::Credit)
+            .map_err(error)
+    } else {
+        messages::MT900::parse_from_block4(text)
+            .map(Confirmation::Debit)
+            .map_err(error)
+    }
+}
+
+impl Mt900 {
+    /// BIC of the sending institution, see [`sender_bic`].
+    pub(crate) fn sender_bic(&self) -> Option<String> {
+        sender_bic(&self.basic_header, &self.application_header)
+    }
+
+    /// Parses the first SWIFT message in `data`, which starts at `origin` in
+    /// the input. In lenient mode invalid optional fields are left out with
+    /// a warning instead of failing the message.
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let (envelope, confirmation) =
+            parse_message(data, origin, recovery, &MT900_FIELDS, parse_confirmation)?;
+
+        Ok(Mt900 {
+            basic_header: envelope.basic_header,
+            application_header: envelope.application_header,
+            user_header: envelope.user_header,
+            confirmation,
+            footer: envelope.footer,
+        })
+    }
+}
+
+impl FinMessage for Mt900 {
+    fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        Mt900::from_string_recovering(data, origin, recovery)
+    }
+}
+
+impl FinancialDataRead for Mt900 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(mt900, _)| mt900)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Mt900(e.to_string()))?;
+        let mut recovery = Recovery::new(mode);
+        let mt900 = Self::from_string_recovering(&data, &SourceSpan::default(), &mut recovery)?;
+        Ok((mt900, recovery.into_warnings()))
+    }
+}
@@ -0,0 +1,89 @@
+use crate::errors::ParseMode;
+use crate::mt900::format::{Confirmation, Mt900};
+use crate::mt940::stream::SwiftStreamReader;
+use crate::test_utils::read_test_file;
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use swift_mt_message::fields::{Field50OrderingCustomerAFK, Field52OrderingInstitution};
+
+fn read_messages(data: &str) -> Vec<Mt900> {
+    SwiftStreamReader::<_, Mt900>::with_mode(data.as_bytes(), ParseMode::Strict)
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn test_with_file() {
+    let messages = read_messages(&read_test_file("valid1.mt900"));
+    assert_eq!(messages.len(), 2);
+
+    let debit = &messages[0].confirmation;
+    assert!(matches!(debit, Confirmation::Debit(_)));
+    assert_eq!(debit.reference(), "DBT2510210001");
+    assert_eq!(debit.related_reference(), "PAY0001");
+    assert_eq!(debit.amount().currency, "EUR");
+    assert_eq!(debit.amount().amount, 15000.0);
+    assert_eq!(debit.date_time().unwrap().offset, "0100");
+    assert!(matches!(
+        debit.ordering_institution(),
+        Some(Field52OrderingInstitution::A(field)) if field.bic == "BANKFRPPXXX"
+    ));
+    assert_eq!(
+        debit.information().unwrap().information,
+        ["/BNF/SUPPLIER SETTLEMENT"]
+    );
+
+    let Confirmation::Credit(credit) = &messages[1].confirmation else {
+        panic!("expected an MT910");
+    };
+    assert_eq!(credit.field_20.reference, "CDT2510210001");
+    assert!(credit.field_13d.is_none());
+    let Some(Field50OrderingCustomerAFK::K(customer)) = &credit.field_50 else {
+        panic!("expected :50K:");
+    };
+    assert_eq!(
+        customer.account.as_deref(),
+        Some("FR7630006000011234567890189")
+    );
+    assert_eq!(
+        customer.name_and_address,
+        ["ACME SA", "12 RUE DE LA PAIX PARIS"]
+    );
+}
+
+#[test]
+fn test_read_write() {
+    for mt900 in read_messages(&read_test_file("valid1.mt900")) {
+        let mut data = Vec::new();
+        mt900.write_to(&mut data).unwrap();
+        assert_eq!(Mt900::from_read(data.as_slice()).unwrap(), mt900);
+    }
+}
+
+#[test]
+fn test_message_type_without_header() {
+    let data = read_test_file("valid1.mt900");
+    let texts: Vec<&str> = data
+        .split("{4:")
+        .skip(1)
+        .map(|text| text.split("-}").next().unwrap())
+        .collect();
+
+    // An ordering customer is only found in an MT910
+    let credit = Mt900::from_read(format!("{{4:{}-}}", texts[1]).as_bytes()).unwrap();
+    assert_eq!(credit.confirmation.message_type(), "910");
+
+    let error = Mt900::from_read(format!("{{4:{}-}}", texts[0]).as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Missing message type"));
+}
+
+#[test]
+fn test_wrong_message_type() {
+    let data = read_test_file("valid1.mt900").replacen("{2:O900", "{2:O940", 1);
+    let error = Mt900::from_read(data.as_bytes()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Expected an MT900 or MT910 message, found MT940")
+    );
+}
@@ -0,0 +1,18 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::mt900::format::Mt900;
+use crate::mt940::reader::write_message;
+
+impl FinancialDataWrite for Mt900 {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = write_message(
+            &self.basic_header,
+            &self.application_header,
+            self.user_header.as_ref(),
+            &self.confirmation.to_mt_string(),
+            self.footer.as_ref(),
+        )?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
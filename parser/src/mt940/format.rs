@@ -4,10 +4,10 @@ use swift_mt_message::messages;
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Mt940 {
     pub basic_header: BasicHeaderBlock,
-    pub application_header: String,  // depends on implementation
-    pub user_header: Option<String>, // depends on implementation, may be skipped
+    pub application_header: ApplicationHeaderBlock,
+    pub user_header: Option<UserHeaderBlock>, // may be skipped
     pub statement: messages::MT940,
-    pub footer: Option<String>, // depends on implementation, may be skipped
+    pub footer: Option<TrailerBlock>, // may be skipped
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
@@ -39,3 +39,115 @@ impl<'de> Deserialize<'de> for BasicHeaderBlock {
         BasicHeaderBlock::from_string(&s).map_err(serde::de::Error::custom)
     }
 }
+
+/// Block 2, in the input (`I940…`, sent to SWIFT) or output (`O940…`, delivered by SWIFT) form.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ApplicationHeaderBlock {
+    Input(InputApplicationHeader),
+    Output(OutputApplicationHeader),
+    /// Layouts that match neither form (some banks shorten the header), kept verbatim.
+    /// Empty for messages without envelope.
+    Unstructured(String),
+}
+
+impl Default for ApplicationHeaderBlock {
+    fn default() -> Self {
+        ApplicationHeaderBlock::Unstructured(String::new())
+    }
+}
+
+/// `I` + message type + receiver address + priority + delivery monitoring + obsolescence period.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct InputApplicationHeader {
+    pub message_type: String,
+    pub receiver_address: String,
+    pub priority: Option<char>,
+    pub delivery_monitoring: Option<char>,
+    pub obsolescence_period: Option<String>,
+}
+
+/// `O` + message type + input time + MIR + output date and time + priority.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct OutputApplicationHeader {
+    pub message_type: String,
+    pub input_time: String,
+    pub mir: MessageInputReference,
+    pub output_date: String,
+    pub output_time: String,
+    pub priority: Option<char>,
+}
+
+/// Message Input Reference: sending date, sender logical terminal, session and sequence number.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct MessageInputReference {
+    pub date: String,
+    pub lt_address: String,
+    pub session_number: String,
+    pub sequence_number: String,
+}
+
+/// One `{tag:value}` field of block 3 or block 5.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct BlockTag {
+    pub tag: String,
+    pub value: String,
+}
+
+/// Block 3, e.g. `{108:MUR}{119:STP}{121:UETR}`.
+///
+/// Tags are written in the order of the SWIFT standard, so a block read
+/// from a SWIFT message is written back unchanged.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct UserHeaderBlock {
+    /// `108`, message user reference.
+    pub mur: Option<String>,
+    /// `119`, validation flag, e.g. `STP` or `REMIT`.
+    pub validation_flag: Option<String>,
+    /// `121`, unique end-to-end transaction reference.
+    pub uetr: Option<String>,
+    /// Other tags, e.g. `103` (service identifier) or `113` (banking priority).
+    pub other: Vec<BlockTag>,
+}
+
+/// Block 5, e.g. `{MAC:…}{CHK:…}{PDE:}{DLM:}`.
+///
+/// Tags are written in the order of the SWIFT standard, like block 3.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct TrailerBlock {
+    /// `CHK`, checksum.
+    pub chk: Option<String>,
+    /// `MAC`, message authentication code.
+    pub mac: Option<String>,
+    /// `PDE`, possible duplicate emission; empty or the original emission time and MIR.
+    pub pde: Option<String>,
+    /// `DLM`, delayed message; always empty.
+    pub dlm: Option<String>,
+    /// Other tags, e.g. `TNG` (training) or `MRF` (message reference).
+    pub other: Vec<BlockTag>,
+}
+
+// Header blocks serialize as their SWIFT text, like BasicHeaderBlock
+macro_rules! serde_as_block_text {
+    ($($block:ty),*) => {$(
+        impl Serialize for $block {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $block {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let s = String::deserialize(deserializer)?;
+                <$block>::from_string(&s).map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+
+serde_as_block_text!(ApplicationHeaderBlock, UserHeaderBlock, TrailerBlock);
@@ -1,5 +1,7 @@
 pub(crate) mod format;
 pub(crate) mod reader;
+pub(crate) mod stream;
+pub(crate) mod tokenizer;
 pub(crate) mod writer;
 
 #[cfg(test)]
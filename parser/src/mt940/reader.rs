@@ -1,12 +1,27 @@
 use crate::FinancialDataRead;
 use crate::ParserError;
-use crate::mt940::format::{BasicHeaderBlock, Mt940};
-use regex::Regex;
+use crate::errors::{ErrorLocation, ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+use crate::mt940::format::{
+    ApplicationHeaderBlock, BasicHeaderBlock, BlockTag, InputApplicationHeader,
+    MessageInputReference, Mt940, OutputApplicationHeader, TrailerBlock, UserHeaderBlock,
+};
+use crate::mt940::tokenizer::{FinBlocks, blank_narrative_lines, split_to_blocks, swift_text};
 use std::fmt::Write;
+use swift_mt_message::SwiftField;
+use swift_mt_message::errors::ParseError;
+use swift_mt_message::fields::{
+    Field20, Field21NoOption, Field25NoOption, Field28C, Field60F, Field61, Field62F, Field64,
+    Field65, Field86,
+};
 use swift_mt_message::messages;
+use swift_mt_message::parser::MessageParser;
 
 impl BasicHeaderBlock {
     pub(crate) fn from_string(data: &str) -> Result<Self, ParserError> {
+        // An empty {1:} block stands for a message without envelope
+        if data.is_empty() {
+            return Ok(Self::default());
+        }
         // This len is fixed
         if data.len() != 25 {
             return Err(ParserError::Mt940(
@@ -35,75 +50,679 @@ impl BasicHeaderBlock {
     }
 }
 
-// MT940 Can have up to 5 blocks, looking like this: e.g. {1:...}{2:...}{4:...}
-fn split_to_blocks(data: &str) -> Result<Vec<Option<String>>, ParserError> {
-    let mut result = vec![None; 5];
+impl ApplicationHeaderBlock {
+    pub(crate) fn from_string(data: &str) -> Result<Self, ParserError> {
+        let parsed = match data.as_bytes().first() {
+            Some(b'I') => InputApplicationHeader::parse(data).map(Self::Input),
+            Some(b'O') => OutputApplicationHeader::parse(data).map(Self::Output),
+            _ => None,
+        };
+        Ok(parsed.unwrap_or_else(|| Self::Unstructured(data.to_string())))
+    }
 
-    let re = Regex::new(r"\{(\d):([^}]*)\}").map_err(|e| ParserError::Mt940(e.to_string()))?;
+    /// BIC of the correspondent: the receiver of an input message, the sender of an output message.
+    pub(crate) fn bic(&self) -> Option<String> {
+        match self {
+            Self::Input(header) => Some(lt_address_to_bic(&header.receiver_address)),
+            Self::Output(header) => Some(lt_address_to_bic(&header.mir.lt_address)),
+            Self::Unstructured(_) => None,
+        }
+    }
+
+    /// Message type, e.g. `940`, also read from the start of an unstructured
+    /// header such as `I900`.
+    pub(crate) fn message_type(&self) -> Option<&str> {
+        match self {
+            Self::Input(header) => Some(&header.message_type),
+            Self::Output(header) => Some(&header.message_type),
+            Self::Unstructured(data) => data
+                .get(1..4)
+                .filter(|_| data.starts_with(['I', 'O']))
+                .filter(|message_type| message_type.bytes().all(|b| b.is_ascii_digit())),
+        }
+    }
+}
 
-    for caps in re.captures_iter(data) {
-        if let (Some(num), Some(data)) = (caps.get(1), caps.get(2)) {
-            if let Ok(idx) = num.as_str().parse::<usize>() {
-                if (1..=5).contains(&idx) {
-                    result[idx - 1] = Some(data.as_str().to_string());
+impl std::fmt::Display for ApplicationHeaderBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input(header) => {
+                write!(f, "I{}{}", header.message_type, header.receiver_address)?;
+                for c in [header.priority, header.delivery_monitoring]
+                    .into_iter()
+                    .flatten()
+                {
+                    write!(f, "{}", c)?;
                 }
+                write!(
+                    f,
+                    "{}",
+                    header.obsolescence_period.as_deref().unwrap_or_default()
+                )
             }
+            Self::Output(header) => {
+                write!(
+                    f,
+                    "O{}{}{}{}{}{}{}{}",
+                    header.message_type,
+                    header.input_time,
+                    header.mir.date,
+                    header.mir.lt_address,
+                    header.mir.session_number,
+                    header.mir.sequence_number,
+                    header.output_date,
+                    header.output_time
+                )?;
+                match header.priority {
+                    Some(priority) => write!(f, "{}", priority),
+                    None => Ok(()),
+                }
+            }
+            Self::Unstructured(data) => write!(f, "{}", data),
+        }
+    }
+}
+
+impl InputApplicationHeader {
+    // I + type(3) + receiver(12) [+ priority(1) [+ delivery monitoring(1) [+ obsolescence(3)]]]
+    fn parse(data: &str) -> Option<Self> {
+        if !data.is_ascii() || !matches!(data.len(), 16 | 17 | 18 | 21) {
+            return None;
         }
+        let message_type = digits(&data[1..4])?;
+        let receiver_address = alphanumeric(&data[4..16])?;
+        let priority = data[16..].chars().next();
+        let delivery_monitoring = data[16..].chars().nth(1);
+        if priority.is_some_and(|p| !matches!(p, 'S' | 'U' | 'N'))
+            || delivery_monitoring.is_some_and(|d| !matches!(d, '1' | '2' | '3'))
+        {
+            return None;
+        }
+        let obsolescence_period = match data.len() {
+            21 => Some(digits(&data[18..21])?),
+            _ => None,
+        };
+
+        Some(Self {
+            message_type,
+            receiver_address,
+            priority,
+            delivery_monitoring,
+            obsolescence_period,
+        })
+    }
+}
+
+impl OutputApplicationHeader {
+    // O + type(3) + input time(4) + MIR(28) + output date(6) + output time(4) [+ priority(1)]
+    fn parse(data: &str) -> Option<Self> {
+        if !data.is_ascii() || !matches!(data.len(), 46 | 47) {
+            return None;
+        }
+        let priority = data[46..].chars().next();
+        if priority.is_some_and(|p| !matches!(p, 'S' | 'U' | 'N')) {
+            return None;
+        }
+
+        Some(Self {
+            message_type: digits(&data[1..4])?,
+            input_time: digits(&data[4..8])?,
+            mir: MessageInputReference {
+                date: digits(&data[8..14])?,
+                lt_address: alphanumeric(&data[14..26])?,
+                session_number: digits(&data[26..30])?,
+                sequence_number: digits(&data[30..36])?,
+            },
+            output_date: digits(&data[36..42])?,
+            output_time: digits(&data[42..46])?,
+            priority,
+        })
+    }
+}
+
+fn digits(s: &str) -> Option<String> {
+    s.bytes().all(|b| b.is_ascii_digit()).then(|| s.to_string())
+}
+
+fn alphanumeric(s: &str) -> Option<String> {
+    s.bytes()
+        .all(|b| b.is_ascii_alphanumeric())
+        .then(|| s.to_string())
+}
+
+/// A 12-character logical terminal address is the BIC8, a terminal code and the branch code.
+fn lt_address_to_bic(lt_address: &str) -> String {
+    match (lt_address.get(0..8), lt_address.get(9..12)) {
+        (Some(bic), Some(branch)) => format!("{}{}", bic, branch),
+        _ => lt_address.to_string(),
+    }
+}
+
+/// Block 3 tags in the order of the SWIFT standard.
+const USER_HEADER_TAGS: &[&str] = &[
+    "103", "113", "108", "119", "423", "106", "424", "111", "121", "115", "165", "433", "434",
+];
+
+/// Block 5 tags in the order of the SWIFT standard.
+const TRAILER_TAGS: &[&str] = &[
+    "MAC", "PAC", "CHK", "TNG", "PDE", "DLM", "MRF", "PDM", "SYS",
+];
+
+/// Parses a sequence of `{tag:value}` fields, as found in blocks 3 and 5.
+fn parse_block_tags(data: &str, block: &str) -> Result<Vec<BlockTag>, ParserError> {
+    let mut tags: Vec<BlockTag> = vec![];
+    let mut rest = data;
+    while !rest.is_empty() {
+        let invalid = || {
+            ParserError::Mt940(format!(
+                "Invalid {} at position {}: expected {{tag:value}}",
+                block,
+                data.len() - rest.len()
+            ))
+        };
+        let inner = rest.strip_prefix('{').ok_or_else(invalid)?;
+        let close = inner.find('}').ok_or_else(invalid)?;
+        let (tag, value) = inner[..close].split_once(':').ok_or_else(invalid)?;
+        if tag.is_empty() || !tag.bytes().all(|b| b.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+        if tags.iter().any(|t| t.tag == tag) {
+            return Err(ParserError::Mt940(format!(
+                "Duplicate tag {} in {}",
+                tag, block
+            )));
+        }
+        tags.push(BlockTag {
+            tag: tag.to_string(),
+            value: value.to_string(),
+        });
+        rest = &inner[close + 1..];
+    }
+    Ok(tags)
+}
+
+/// Removes the field with `tag` from `tags` and returns its value.
+fn take_block_tag(tags: &mut Vec<BlockTag>, tag: &str) -> Option<String> {
+    let index = tags.iter().position(|t| t.tag == tag)?;
+    Some(tags.remove(index).value)
+}
+
+/// Writes `{tag:value}` fields in the order of `order`; unknown tags follow
+/// in their own order.
+fn write_block_tags<'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    order: &[&str],
+    tags: impl IntoIterator<Item = (&'a str, &'a Option<String>)>,
+    other: &[BlockTag],
+) -> std::fmt::Result {
+    let mut tags: Vec<(&str, &str)> = tags
+        .into_iter()
+        .filter_map(|(tag, value)| Some((tag, value.as_deref()?)))
+        .chain(other.iter().map(|t| (t.tag.as_str(), t.value.as_str())))
+        .collect();
+    tags.sort_by_key(|(tag, _)| {
+        order
+            .iter()
+            .position(|known| known == tag)
+            .unwrap_or(order.len())
+    });
+    for (tag, value) in tags {
+        write!(f, "{{{}:{}}}", tag, value)?;
+    }
+    Ok(())
+}
+
+impl UserHeaderBlock {
+    pub(crate) fn from_string(data: &str) -> Result<Self, ParserError> {
+        let mut tags = parse_block_tags(data, "User Header Block")?;
+        Ok(Self {
+            mur: take_block_tag(&mut tags, "108"),
+            validation_flag: take_block_tag(&mut tags, "119"),
+            uetr: take_block_tag(&mut tags, "121"),
+            other: tags,
+        })
+    }
+}
+
+impl std::fmt::Display for UserHeaderBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tags = [
+            ("108", &self.mur),
+            ("119", &self.validation_flag),
+            ("121", &self.uetr),
+        ];
+        write_block_tags(f, USER_HEADER_TAGS, tags, &self.other)
+    }
+}
+
+impl TrailerBlock {
+    pub(crate) fn from_string(data: &str) -> Result<Self, ParserError> {
+        let mut tags = parse_block_tags(data, "Trailer Block")?;
+        Ok(Self {
+            chk: take_block_tag(&mut tags, "CHK"),
+            mac: take_block_tag(&mut tags, "MAC"),
+            pde: take_block_tag(&mut tags, "PDE"),
+            dlm: take_block_tag(&mut tags, "DLM"),
+            other: tags,
+        })
+    }
+}
+
+impl std::fmt::Display for TrailerBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let tags = [
+            ("CHK", &self.chk),
+            ("MAC", &self.mac),
+            ("PDE", &self.pde),
+            ("DLM", &self.dlm),
+        ];
+        write_block_tags(f, TRAILER_TAGS, tags, &self.other)
+    }
+}
+
+/// BIC of the sending institution of a message: the MIR logical terminal of
+/// an output message, the basic header logical terminal of an input message.
+pub(crate) fn sender_bic(
+    basic_header: &BasicHeaderBlock,
+    application_header: &ApplicationHeaderBlock,
+) -> Option<String> {
+    match application_header {
+        ApplicationHeaderBlock::Output(_) => application_header.bic(),
+        ApplicationHeaderBlock::Input(_) => Some(lt_address_to_bic(&basic_header.lt_identifier)),
+        ApplicationHeaderBlock::Unstructured(_) => None,
     }
+}
 
-    Ok(result)
+impl Mt940 {
+    /// BIC of the sending institution, see [`sender_bic`].
+    pub(crate) fn sender_bic(&self) -> Option<String> {
+        sender_bic(&self.basic_header, &self.application_header)
+    }
+}
+
+/// Writes a SWIFT message: the headers, the text block `text` as produced by
+/// `to_mt_string`, and the trailer.
+pub(crate) fn write_message(
+    basic_header: &BasicHeaderBlock,
+    application_header: &ApplicationHeaderBlock,
+    user_header: Option<&UserHeaderBlock>,
+    text: &str,
+    footer: Option<&TrailerBlock>,
+) -> Result<String, ParserError> {
+    let mut msg = String::new();
+
+    write!(
+        msg,
+        "{{1:{}}}{{2:{}}}\r\n",
+        basic_header.to_string(),
+        application_header
+    )?;
+
+    if let Some(uh) = user_header {
+        write!(msg, "{{3:{}}}\r\n", uh)?;
+    }
+
+    // Lines inside a field, e.g. of the :86: narrative, end with CRLF like the fields
+    let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+    write!(msg, "{{4:{}\r\n", text)?;
+    write!(msg, "-}}\r\n")?;
+
+    if let Some(footer) = footer {
+        write!(msg, "{{5:{}}}", footer)?;
+    }
+
+    Ok(msg)
 }
 
 impl Mt940 {
     pub(crate) fn to_string(&self) -> Result<String, ParserError> {
-        let mut msg = String::new();
+        write_message(
+            &self.basic_header,
+            &self.application_header,
+            self.user_header.as_ref(),
+            &self.statement.to_mt_string(),
+            self.footer.as_ref(),
+        )
+    }
+}
 
-        write!(
-            msg,
-            "{{1:{}}}{{2:{}}}\r\n",
-            self.basic_header.to_string(),
-            self.application_header
-        )?;
+/// The text block fields of a SWIFT message type.
+pub(crate) struct TextBlockFields {
+    /// The error of the message type, e.g. [`ParserError::Mt940`].
+    pub error: fn(String) -> ParserError,
+    /// Validates one field on its own; fields of other tags are accepted.
+    pub check: fn(&str, &str) -> Result<(), String>,
+    /// Tags of the fields lenient mode leaves out when they do not parse.
+    /// A left-out `:61:` statement line takes its `:86:` narrative with it.
+    pub optional: &'static [&'static str],
+}
 
-        if let Some(ref uh) = self.user_header {
-            write!(msg, "{{3:{}}}\r\n", uh)?;
+/// The blocks of a SWIFT message around its text block.
+#[derive(Debug, Default)]
+pub(crate) struct Envelope {
+    pub basic_header: BasicHeaderBlock,
+    pub application_header: ApplicationHeaderBlock,
+    pub user_header: Option<UserHeaderBlock>,
+    pub footer: Option<TrailerBlock>,
+}
+
+/// Parses the first SWIFT message in `data`, which starts at `origin` in
+/// the input, into its envelope and the text block read by `parse_text`.
+///
+/// Errors are located relative to `origin`: header errors point at their
+/// block, text block errors at the first field that does not parse on its
+/// own. In lenient mode invalid optional `fields` are left out with a
+/// warning instead of failing the message.
+pub(crate) fn parse_message<T>(
+    data: &str,
+    origin: &SourceSpan,
+    recovery: &mut Recovery,
+    fields: &TextBlockFields,
+    parse_text: impl FnOnce(&ApplicationHeaderBlock, &str) -> Result<T, ParserError>,
+) -> Result<(Envelope, T), ParserError> {
+    let blocks = split_to_blocks(data, origin)?;
+    let block_error = |blocks: &FinBlocks, index: usize, error: ParserError| {
+        let start = blocks.start(index);
+        let len = blocks[index].as_ref().map_or(1, |block| block.len().max(1));
+        let location = ErrorLocation::Block((index + 1).to_string());
+        error.at_text(data, start, len, origin, Some(location))
+    };
+    let message_error =
+        |message: &str| (fields.error)(message.to_string()).at_text(data, 0, 1, origin, None);
+
+    // Bare `:20:`...`-` messages come without {1:} and {2:} envelope
+    let bare = blocks[0].is_none() && blocks[1].is_none();
+    let basic_header = match blocks[0].as_deref() {
+        Some(block) => {
+            BasicHeaderBlock::from_string(block).map_err(|e| block_error(&blocks, 0, e))?
         }
+        None if bare => BasicHeaderBlock::default(),
+        None => return Err(message_error("Missing Basic Header Block")),
+    };
+    let application_header = match blocks[1].as_deref() {
+        Some(block) => {
+            ApplicationHeaderBlock::from_string(block).map_err(|e| block_error(&blocks, 1, e))?
+        }
+        None if bare => ApplicationHeaderBlock::default(),
+        None => return Err(message_error("Missing Application Header Block")),
+    };
+    if blocks[3].is_none() {
+        return Err(message_error("Missing Text Block"));
+    }
+    let user_header = blocks[2]
+        .as_deref()
+        .map(UserHeaderBlock::from_string)
+        .transpose()
+        .map_err(|e| block_error(&blocks, 2, e))?; // can be skipped
+    let repaired = if recovery.is_lenient() {
+        without_invalid_fields(data, blocks.start(3), origin, recovery, fields)?
+    } else {
+        None
+    };
+    let text = repaired
+        .as_deref()
+        .or(blocks[3].as_deref())
+        .unwrap_or_default();
+    let body = parse_text(&application_header, &swift_text(text))
+        .map_err(|e| locate_text_error(e, data, blocks.start(3), origin, fields))?; // can't be skipped
+    let footer = blocks[4]
+        .as_deref()
+        .map(TrailerBlock::from_string)
+        .transpose()
+        .map_err(|e| block_error(&blocks, 4, e))?; // can be skipped
+
+    let envelope = Envelope {
+        basic_header,
+        application_header,
+        user_header,
+        footer,
+    };
+    Ok((envelope, body))
+}
+
+/// Fields of the MT940 text block.
+const MT940_FIELDS: TextBlockFields = TextBlockFields {
+    error: ParserError::Mt940,
+    check: check_text_field,
+    optional: &["61", "86", "21", "64", "65"],
+};
+
+impl Mt940 {
+    /// Parses the first SWIFT message in `data`.
+    pub(crate) fn from_string(data: &str) -> Result<Self, ParserError> {
+        Self::from_string_at(data, &SourceSpan::default())
+    }
+
+    /// Parses the first SWIFT message in `data`, which starts at `origin` in the input.
+    ///
+    /// Errors are located relative to `origin`: header errors point at their
+    /// block, text block errors at the field the SWIFT parser rejected.
+    pub(crate) fn from_string_at(data: &str, origin: &SourceSpan) -> Result<Self, ParserError> {
+        Self::from_string_recovering(data, origin, &mut Recovery::default())
+    }
 
-        write!(msg, "{{4:{}\r\n", self.statement.to_mt_string())?;
-        write!(msg, "-}}\r\n")?;
+    /// Parses like [`from_string_at`](Self::from_string_at). In lenient mode
+    /// invalid statement lines and optional fields of the text block are left
+    /// out with a warning instead of failing the message.
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let (envelope, statement) =
+            parse_message(data, origin, recovery, &MT940_FIELDS, |_, text| {
+                let mut statement =
+                    parse_statement(text).map_err(|e| ParserError::Mt940(e.to_string()))?;
+                for line in &mut statement.statement_lines {
+                    if let Some(field_86) = &mut line.field_86 {
+                        blank_narrative_lines(&mut field_86.narrative);
+                    }
+                }
+                Ok(statement)
+            })?;
 
-        if let Some(ref footer) = self.footer {
-            write!(msg, "{{5:{}}}", footer)?;
+        Ok(Mt940 {
+            basic_header: envelope.basic_header,
+            application_header: envelope.application_header,
+            user_header: envelope.user_header,
+            statement,
+            footer: envelope.footer,
+        })
+    }
+}
+
+/// Parses the MT940 text block like `MT940::parse_from_block4`, but also
+/// accepts a statement without statement lines, e.g. for a day without
+/// movements, as the SWIFT standard does.
+fn parse_statement(text: &str) -> Result<messages::MT940, ParseError> {
+    let mut parser = MessageParser::new(text, "940");
+    let field_20 = parser.parse_field::<Field20>("20")?;
+    let field_21 = parser.parse_optional_field::<Field21NoOption>("21")?;
+    let field_25 = parser.parse_field::<Field25NoOption>("25")?;
+    let field_28c = parser.parse_field::<Field28C>("28C")?;
+    let field_60f = parser.parse_field::<Field60F>("60F")?;
+
+    parser = parser.with_duplicates(true);
+    let mut statement_lines = Vec::new();
+    while parser.detect_field("61") {
+        statement_lines.push(messages::MT940StatementLine {
+            field_61: parser.parse_field::<Field61>("61")?,
+            field_86: parser.parse_optional_field::<Field86>("86")?,
+        });
+    }
+    parser = parser.with_duplicates(false);
+
+    let field_62f = parser.parse_field::<Field62F>("62F")?;
+    let field_64 = parser.parse_optional_field::<Field64>("64")?;
+    parser = parser.with_duplicates(true);
+    let mut forward_balances = Vec::new();
+    while parser.detect_field("65") {
+        forward_balances.push(parser.parse_field::<Field65>("65")?);
+    }
+
+    Ok(messages::MT940 {
+        field_20,
+        field_21,
+        field_25,
+        field_28c,
+        field_60f,
+        statement_lines,
+        field_62f,
+        field_64,
+        field_65: (!forward_balances.is_empty()).then_some(forward_balances),
+    })
+}
+
+/// One `:tag:value` field of a text block, with continuation lines joined by
+/// `\n`; blank lines are kept inside the field and dropped at its end.
+struct TextField<'a> {
+    tag: &'a str,
+    value: String,
+    // Byte index and length of the `:tag:` line in the message
+    index: usize,
+    len: usize,
+}
+
+/// Splits the raw text block starting at byte `start` of `data` into fields.
+fn text_fields(data: &str, start: usize) -> Vec<TextField<'_>> {
+    let mut fields: Vec<TextField> = Vec::new();
+    let mut index = start;
+    for raw in data[start..].split_inclusive('\n') {
+        let line = raw.trim_end();
+        let line_index = index;
+        index += raw.len();
+        if line == "-" || line.starts_with("-}") {
+            break;
+        }
+        let tagged = line
+            .strip_prefix(':')
+            .and_then(|rest| rest.split_once(':'))
+            .filter(|(tag, _)| !tag.is_empty() && tag.bytes().all(|b| b.is_ascii_alphanumeric()));
+        if let Some((tag, value)) = tagged {
+            fields.push(TextField {
+                tag,
+                value: value.to_string(),
+                index: line_index,
+                len: line.len(),
+            });
+        } else if let Some(field) = fields.last_mut() {
+            field.value.push('\n');
+            field.value.push_str(line);
+        }
+    }
+    for field in &mut fields {
+        field.value.truncate(field.value.trim_end().len());
+    }
+    fields
+}
+
+pub(crate) fn check_field<F: SwiftField>(value: &str) -> Result<(), String> {
+    F::parse(value).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Validates one field of the MT940 text block on its own.
+fn check_text_field(tag: &str, value: &str) -> Result<(), String> {
+    match tag {
+        "20" => check_field::<Field20>(value),
+        "21" => check_field::<Field21NoOption>(value),
+        "25" => check_field::<Field25NoOption>(value),
+        "28C" => check_field::<Field28C>(value),
+        "60F" => check_field::<Field60F>(value),
+        "61" => check_field::<Field61>(value),
+        "86" => check_field::<Field86>(value),
+        "62F" => check_field::<Field62F>(value),
+        "64" => check_field::<Field64>(value),
+        "65" => check_field::<Field65>(value),
+        _ => Ok(()),
+    }
+}
+
+/// Leaves out text block fields that fail to parse but are not required,
+/// e.g. statement lines (`:61:` with its `:86:`), `:86:`, `:21:`, `:64:` and
+/// `:65:` of MT940.
+///
+/// Returns the remaining text block, or `None` if all fields are valid.
+fn without_invalid_fields(
+    data: &str,
+    start: usize,
+    origin: &SourceSpan,
+    recovery: &mut Recovery,
+    fields: &TextBlockFields,
+) -> Result<Option<String>, ParserError> {
+    let mut kept = Vec::new();
+    let mut changed = false;
+    // The narrative of a left-out statement line goes with it
+    let mut skip_narrative = false;
+    for field in text_fields(data, start) {
+        if field.tag == "86" && skip_narrative {
+            continue;
+        }
+        skip_narrative = false;
+        match (fields.check)(field.tag, &field.value) {
+            Err(message) if fields.optional.contains(&field.tag) => {
+                let kind = match field.tag {
+                    "61" => WarningKind::SkippedEntry,
+                    _ => WarningKind::SkippedField,
+                };
+                let location = ErrorLocation::Field(field.tag.to_string());
+                let error = (fields.error)(message).at_text(
+                    data,
+                    field.index,
+                    field.len,
+                    origin,
+                    Some(location),
+                );
+                recovery.recover(kind, error)?;
+                skip_narrative = field.tag == "61";
+                changed = true;
+            }
+            _ => kept.push(format!(":{}:{}", field.tag, field.value)),
         }
+    }
+    Ok(changed.then(|| format!("\n{}\n-", kept.join("\n"))))
+}
 
-        Ok(msg)
+/// Attaches to a text block `error` the position of the first field that
+/// does not parse on its own, or of the text block itself, e.g. when a
+/// mandatory field is missing.
+fn locate_text_error(
+    error: ParserError,
+    data: &str,
+    start: usize,
+    origin: &SourceSpan,
+    fields: &TextBlockFields,
+) -> ParserError {
+    match text_fields(data, start)
+        .iter()
+        .find(|field| (fields.check)(field.tag, &field.value).is_err())
+    {
+        Some(field) => {
+            let location = ErrorLocation::Field(field.tag.to_string());
+            error.at_text(data, field.index, field.len, origin, Some(location))
+        }
+        None => error.at_text(
+            data,
+            start,
+            1,
+            origin,
+            Some(ErrorLocation::Block("4".to_string())),
+        ),
     }
 }
 
 impl FinancialDataRead for Mt940 {
     fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
         let data = Self::read_to_string(reader).map_err(|e| ParserError::Mt940(e.to_string()))?;
-        let mut blocks = split_to_blocks(&data)?;
-
-        let basic_header = BasicHeaderBlock::from_string(
-            &blocks[0]
-                .take()
-                .ok_or(ParserError::Mt940("Missing Basic Header Block".to_string()))?,
-        )?; // can't be skipped
-        let application_header = blocks[1].take().ok_or(ParserError::Mt940(
-            "Missing Application Header Block".to_string(),
-        ))?; // can't be skipped
-        let user_header = blocks[2].take(); // can be skipped
-        let statement = messages::MT940::parse_from_block4(&blocks[3].take().unwrap_or_default())
-            .map_err(|e| ParserError::Mt940(e.to_string()))?; // can't be skipped
-        let footer = blocks[4].take(); // can be skipped
+        Self::from_string(&data)
+    }
 
-        Ok(Mt940 {
-            basic_header: basic_header,
-            application_header: application_header,
-            user_header: user_header,
-            statement: statement,
-            footer: footer,
-        })
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Mt940(e.to_string()))?;
+        let mut recovery = Recovery::new(mode);
+        let mt940 = Self::from_string_recovering(&data, &SourceSpan::default(), &mut recovery)?;
+        Ok((mt940, recovery.into_warnings()))
     }
 }
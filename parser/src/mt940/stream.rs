@@ -0,0 +1,353 @@
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+use crate::mt940::format::Mt940;
+use std::io::BufRead;
+use std::marker::PhantomData;
+use swift_mt_message::messages::MT940StatementLine;
+
+/// A SWIFT message type that [`SwiftStreamReader`] reads one message at a time.
+pub(crate) trait FinMessage: Sized {
+    /// Parses the first message in `data`, which starts at `origin` in the input.
+    fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError>;
+}
+
+impl FinMessage for Mt940 {
+    fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        Mt940::from_string_recovering(data, origin, recovery)
+    }
+}
+
+/// Position of the splitter relative to the blocks of the current message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockState {
+    /// Outside of any block.
+    Between,
+    /// Right after `{`, the next byte is the block number.
+    Number,
+    /// Inside block 4, which ends with `-}` at the start of a line.
+    Text,
+    /// Inside a message without envelope, which ends with a `-` line.
+    BareText,
+    /// Inside block 1, 2, 3 or 5, which may contain nested `{tag:value}` fields.
+    Header { depth: usize },
+}
+
+/// What the current line holds so far, ignoring surrounding whitespace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineState {
+    Blank,
+    /// A single `-`, the line that ends a text block.
+    Dash,
+    Other,
+}
+
+impl LineState {
+    fn push(self, byte: u8) -> Self {
+        match (self, byte) {
+            (_, b'\n') => LineState::Blank,
+            (state, byte) if byte.is_ascii_whitespace() => state,
+            (LineState::Blank, b'-') => LineState::Dash,
+            _ => LineState::Other,
+        }
+    }
+}
+
+/// Splits a byte stream into SWIFT messages without buffering more than one message.
+///
+/// A message ends at a `$` separator, or when a `{1:` block or a bare `:20:`
+/// text follows its completed text block, so boundaries are found regardless
+/// of how the input is chunked. Block contents are tokenized afterwards by
+/// [`split_to_blocks`](crate::mt940::tokenizer::split_to_blocks).
+#[derive(Debug)]
+struct MessageSplitter {
+    message: Vec<u8>,
+    state: BlockState,
+    block_start: usize,
+    line: LineState,
+    has_text_block: bool,
+    // Position of the first byte of `message` in the whole input
+    origin: SourceSpan,
+}
+
+impl MessageSplitter {
+    fn new() -> Self {
+        MessageSplitter {
+            message: Vec::new(),
+            state: BlockState::Between,
+            block_start: 0,
+            line: LineState::Blank,
+            has_text_block: false,
+            origin: SourceSpan::default(),
+        }
+    }
+
+    /// Feeds one byte. Returns the previous message once it is complete.
+    fn push(&mut self, byte: u8) -> Option<(SourceSpan, Vec<u8>)> {
+        self.message.push(byte);
+        let position = self.message.len() - 1;
+        // Whether the line before this byte is a `-` line
+        let current_line_is_dash = self.line == LineState::Dash;
+        self.line = self.line.push(byte);
+
+        match self.state {
+            BlockState::Between => match byte {
+                b'{' => {
+                    self.block_start = position;
+                    self.state = BlockState::Number;
+                }
+                b':' if self.has_text_block => {
+                    self.state = BlockState::BareText;
+                    return self.split_at(position);
+                }
+                b':' => self.state = BlockState::BareText,
+                b'$' if !self.is_blank() => return self.split_at(self.message.len()),
+                _ => {}
+            },
+            BlockState::Number => {
+                self.state = match byte {
+                    b'4' => BlockState::Text,
+                    _ => BlockState::Header { depth: 0 },
+                };
+                if byte == b'1' && self.has_text_block {
+                    return self.split_at(self.block_start);
+                }
+            }
+            BlockState::Text => {
+                if byte == b'}' && current_line_is_dash {
+                    self.state = BlockState::Between;
+                    self.has_text_block = true;
+                }
+            }
+            BlockState::BareText => match byte {
+                b'\n' if current_line_is_dash => {
+                    self.state = BlockState::Between;
+                    self.has_text_block = true;
+                }
+                b'$' => {
+                    self.state = BlockState::Between;
+                    return self.split_at(self.message.len());
+                }
+                _ => {}
+            },
+            BlockState::Header { depth } => match byte {
+                b'{' => self.state = BlockState::Header { depth: depth + 1 },
+                b'}' if depth == 0 => self.state = BlockState::Between,
+                b'}' => self.state = BlockState::Header { depth: depth - 1 },
+                _ => {}
+            },
+        }
+        None
+    }
+
+    /// Completes the message before byte `at` of the buffer; the rest starts the next one.
+    fn split_at(&mut self, at: usize) -> Option<(SourceSpan, Vec<u8>)> {
+        let next = self.message.split_off(at);
+        let complete = std::mem::replace(&mut self.message, next);
+        let origin = self.origin;
+        self.origin.advance(&complete);
+        self.block_start -= at.min(self.block_start);
+        self.has_text_block = false;
+        Some((origin, complete))
+    }
+
+    fn is_blank(&self) -> bool {
+        self.message
+            .iter()
+            .all(|b| b.is_ascii_whitespace() || *b == b'$')
+    }
+
+    /// Returns the last message at end of input.
+    fn finish(&mut self) -> Result<Option<(SourceSpan, Vec<u8>)>, ParserError> {
+        if !matches!(self.state, BlockState::Between | BlockState::BareText) {
+            let mut span = self.origin;
+            span.advance(&self.message[..self.block_start]);
+            span.len = 1;
+            return Err(ParserError::Mt940("Unterminated block".to_string()).at_span(span, None));
+        }
+        if self.is_blank() {
+            return Ok(None);
+        }
+        let message = std::mem::take(&mut self.message);
+        Ok(Some((self.origin, message)))
+    }
+}
+
+/// Incremental reader of SWIFT messages over any buffered source.
+///
+/// Yields one `M` per SWIFT message of a multi-message archive. Only
+/// the message being parsed is kept in memory, and block boundaries may fall
+/// anywhere across the chunks returned by the source.
+///
+/// In lenient mode a message that cannot be parsed is skipped and reading
+/// continues with the next one; see [`take_warnings`](Self::take_warnings).
+pub(crate) struct SwiftStreamReader<R: BufRead, M> {
+    source: R,
+    splitter: MessageSplitter,
+    done: bool,
+    recovery: Recovery,
+    message: PhantomData<M>,
+}
+
+/// Incremental MT940 reader, see [`SwiftStreamReader`].
+pub(crate) type Mt940StreamReader<R> = SwiftStreamReader<R, Mt940>;
+
+impl<R: BufRead, M: FinMessage> SwiftStreamReader<R, M> {
+    /// Creates a reader over a buffered SWIFT source in the given mode.
+    pub(crate) fn with_mode(source: R, mode: ParseMode) -> Self {
+        SwiftStreamReader {
+            source,
+            splitter: MessageSplitter::new(),
+            done: false,
+            recovery: Recovery::new(mode),
+            message: PhantomData,
+        }
+    }
+
+    /// Removes and returns the warnings for the messages read so far.
+    pub(crate) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        self.recovery.take_warnings()
+    }
+
+    fn next_message(&mut self) -> Result<Option<(SourceSpan, Vec<u8>)>, ParserError> {
+        loop {
+            let chunk = self.source.fill_buf()?;
+            if chunk.is_empty() {
+                self.done = true;
+                return match self.splitter.finish() {
+                    Err(e) => self
+                        .recovery
+                        .recover(WarningKind::SkippedMessage, e)
+                        .map(|_| None),
+                    message => message,
+                };
+            }
+
+            let mut consumed = 0;
+            let mut complete = None;
+            for &byte in chunk {
+                consumed += 1;
+                complete = self.splitter.push(byte);
+                if complete.is_some() {
+                    break;
+                }
+            }
+            self.source.consume(consumed);
+
+            if complete.is_some() {
+                return Ok(complete);
+            }
+        }
+    }
+}
+
+impl<R: BufRead, M: FinMessage> SwiftStreamReader<R, M> {
+    fn parse(&mut self, origin: SourceSpan, bytes: Vec<u8>) -> Result<M, ParserError> {
+        let data = String::from_utf8(bytes).map_err(|e| {
+            let mut span = origin;
+            span.advance(&e.as_bytes()[..e.utf8_error().valid_up_to()]);
+            span.len = 1;
+            ParserError::Mt940(e.utf8_error().to_string()).at_span(span, None)
+        })?;
+        M::from_string_recovering(&data, &origin, &mut self.recovery)
+    }
+}
+
+impl<R: BufRead, M: FinMessage> Iterator for SwiftStreamReader<R, M> {
+    type Item = Result<M, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let result = self.next_message().and_then(|message| {
+                let Some((origin, bytes)) = message else {
+                    return Ok(None);
+                };
+                match self.parse(origin, bytes) {
+                    Ok(message) => Ok(Some(message)),
+                    // Lenient mode continues with the next message
+                    Err(e) => self
+                        .recovery
+                        .recover(WarningKind::SkippedMessage, e)
+                        .map(|_| None),
+                }
+            });
+            match result {
+                Ok(Some(message)) => return Some(Ok(message)),
+                Ok(None) => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// One unit of an MT940 stream produced by [`Mt940ItemReader`].
+///
+/// Items arrive in input order: for every message its statement, its
+/// statement lines and a closing marker.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Mt940Item {
+    /// A message with an empty `statement_lines` list.
+    Statement(Box<Mt940>),
+    /// One `:61:` statement line of the current message, with its `:86:`.
+    StatementLine(MT940StatementLine),
+    /// End of the current message.
+    StatementEnd,
+}
+
+/// Incremental MT940 reader yielding one statement line at a time.
+///
+/// Built on [`Mt940StreamReader`], so only the message being read is kept
+/// in memory; errors and lenient mode work the same way.
+pub(crate) struct Mt940ItemReader<R: BufRead> {
+    messages: Mt940StreamReader<R>,
+    lines: std::vec::IntoIter<MT940StatementLine>,
+    in_statement: bool,
+}
+
+impl<R: BufRead> Mt940ItemReader<R> {
+    /// Creates a reader over a buffered MT940 source in the given mode.
+    pub(crate) fn with_mode(source: R, mode: ParseMode) -> Self {
+        Mt940ItemReader {
+            messages: Mt940StreamReader::with_mode(source, mode),
+            lines: Vec::new().into_iter(),
+            in_statement: false,
+        }
+    }
+
+    /// Removes and returns the warnings for the messages read so far.
+    pub(crate) fn take_warnings(&mut self) -> Vec<ParseWarning> {
+        self.messages.take_warnings()
+    }
+}
+
+impl<R: BufRead> Iterator for Mt940ItemReader<R> {
+    type Item = Result<Mt940Item, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(line) = self.lines.next() {
+            return Some(Ok(Mt940Item::StatementLine(line)));
+        }
+        if self.in_statement {
+            self.in_statement = false;
+            return Some(Ok(Mt940Item::StatementEnd));
+        }
+        let mut mt940 = match self.messages.next()? {
+            Ok(mt940) => mt940,
+            Err(e) => return Some(Err(e)),
+        };
+        self.lines = std::mem::take(&mut mt940.statement.statement_lines).into_iter();
+        self.in_statement = true;
+        Some(Ok(Mt940Item::Statement(Box::new(mt940))))
+    }
+}
@@ -1,4 +1,11 @@
-use crate::mt940::format::{BasicHeaderBlock, Mt940};
+use crate::ParserError;
+use crate::errors::{ErrorLocation, ParseMode, SourceSpan, WarningKind};
+use crate::mt940::format::{
+    ApplicationHeaderBlock, BasicHeaderBlock, BlockTag, InputApplicationHeader, Mt940,
+    OutputApplicationHeader, TrailerBlock, UserHeaderBlock,
+};
+use crate::mt940::stream::{Mt940Item, Mt940ItemReader, Mt940StreamReader};
+use crate::mt940::tokenizer::split_to_blocks;
 use crate::traits::{FinancialDataRead, FinancialDataWrite};
 
 #[test]
@@ -68,3 +75,437 @@ fn test_read_write() {
     std::fs::remove_file(&new_file_path).unwrap();
     assert_eq!(read_from_new_file, mt940_valid);
 }
+
+fn archive(names: &[&str]) -> Vec<u8> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let path = PathBuf::from(manifest_dir).join("test_data");
+    let mut data = Vec::new();
+    for name in names {
+        data.extend(std::fs::read(path.join(name)).unwrap());
+        data.extend_from_slice(b"\r\n");
+    }
+    data
+}
+
+#[test]
+fn test_stream_multiple_messages() {
+    let names = ["valid1.mt940", "valid3.mt940"];
+    let data = archive(&names);
+
+    // A tiny buffer puts block boundaries across chunks
+    let source = std::io::BufReader::with_capacity(7, data.as_slice());
+    let messages: Vec<Mt940> = Mt940StreamReader::with_mode(source, ParseMode::Strict)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(messages.len(), names.len());
+    for (message, name) in messages.iter().zip(names) {
+        let expected = Mt940::from_read(archive(&[name]).as_slice()).unwrap();
+        assert_eq!(message, &expected, "{}", name);
+    }
+}
+
+#[test]
+fn test_stream_archive_file() {
+    // valid2.mt940 holds 31 messages; the whole-file reader parses the first one
+    let data = archive(&["valid2.mt940"]);
+    let messages: Vec<Mt940> = Mt940StreamReader::with_mode(data.as_slice(), ParseMode::Strict)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(messages.len(), 31);
+    assert_eq!(
+        messages.first(),
+        Some(&Mt940::from_read(data.as_slice()).unwrap())
+    );
+}
+
+#[test]
+fn test_stream_statement_lines() {
+    let names = ["valid1.mt940", "valid3.mt940"];
+    let data = archive(&names);
+
+    let source = std::io::BufReader::with_capacity(7, data.as_slice());
+    let items: Vec<Mt940Item> = Mt940ItemReader::with_mode(source, ParseMode::Strict)
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let mut expected = Vec::new();
+    for name in names {
+        let mut mt940 = Mt940::from_read(archive(&[name]).as_slice()).unwrap();
+        let lines = std::mem::take(&mut mt940.statement.statement_lines);
+        assert!(!lines.is_empty(), "{}", name);
+        expected.push(Mt940Item::Statement(Box::new(mt940)));
+        expected.extend(lines.into_iter().map(Mt940Item::StatementLine));
+        expected.push(Mt940Item::StatementEnd);
+    }
+    assert_eq!(items, expected);
+}
+
+#[test]
+fn test_stream_unterminated_block() {
+    let mut data = archive(&["valid1.mt940"]);
+    let offset = data.len();
+    data.extend_from_slice(b"{1:F01BANKDEFFAXXX0000000000}{4:\r\n:20:REF\r\n");
+
+    let results: Vec<_> =
+        Mt940StreamReader::with_mode(data.as_slice(), ParseMode::Strict).collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    let error = results[1].as_ref().unwrap_err();
+    assert_eq!(error.diagnostic().unwrap().span.offset, offset + 29);
+}
+
+const BARE_MESSAGE: &str = ":20:STAT202510210001\r\n\
+    :25:NL91ABNA0417164300\r\n\
+    :28C:00001/001\r\n\
+    :60F:C251020EUR10000,00\r\n\
+    :61:2510211021D250,00NTRFNONREF//BKNTRX0001\r\n\
+    :86:Payment {ref} to supplier   \r\n\
+    :62F:C251021EUR9750,00\r\n\
+    -";
+
+#[test]
+fn test_tokenizer_nested_tags_and_braces_in_narrative() {
+    let data = format!(
+        "{{1:F01BANKDEFFAXXX0000000000}}{{2:I940BANKNL2AXXXXN}}{{3:{{108:MUR123}}{{121:UETR}}}}{{4:\r\n{}}}{{5:{{CHK:ABCDEF}}}}\r\n  ",
+        BARE_MESSAGE
+    );
+    let blocks = split_to_blocks(&data, &SourceSpan::default()).unwrap();
+
+    assert_eq!(blocks[2].as_deref(), Some("{108:MUR123}{121:UETR}"));
+    assert_eq!(blocks[4].as_deref(), Some("{CHK:ABCDEF}"));
+    let text = blocks[3].as_deref().unwrap();
+    assert!(text.contains(":86:Payment {ref} to supplier\n:62F:"));
+    assert!(!text.contains('\r'));
+
+    let mt940 = Mt940::from_string(&data).unwrap();
+    let user_header = mt940.user_header.as_ref().unwrap();
+    assert_eq!(user_header.mur.as_deref(), Some("MUR123"));
+    assert_eq!(user_header.uetr.as_deref(), Some("UETR"));
+    assert_eq!(mt940.statement.statement_lines.len(), 1);
+}
+
+#[test]
+fn test_tokenizer_bare_message() {
+    let mt940 = Mt940::from_string(BARE_MESSAGE).unwrap();
+    assert_eq!(mt940.basic_header, BasicHeaderBlock::default());
+    assert_eq!(mt940.application_header, ApplicationHeaderBlock::default());
+    assert_eq!(mt940.statement.field_20.reference, "STAT202510210001");
+
+    // Written with empty envelope blocks, which read back as a bare message
+    let mut written = Vec::new();
+    mt940.write_to(&mut written).unwrap();
+    assert_eq!(Mt940::from_read(written.as_slice()).unwrap(), mt940);
+}
+
+#[test]
+fn test_stream_separators() {
+    let enveloped = "{1:F01BANKDEFFAXXX0000000000}{2:I940BANKNL2AXXXXN}{4:\n:20:REF3\n:25:NL91ABNA0417164300\n:28C:1/1\n:60F:C251020EUR0,00\n:61:2510201020C5,00NTRFNONREF\n:62F:C251020EUR5,00\n-}";
+    let data = format!(
+        "{}\n{}\n$\n{}\r\n$$\n{}\n",
+        BARE_MESSAGE,
+        BARE_MESSAGE.replace("STAT202510210001", "REF2"),
+        enveloped,
+        BARE_MESSAGE.replace("STAT202510210001", "REF4")
+    );
+
+    let source = std::io::BufReader::with_capacity(5, data.as_bytes());
+    let references: Vec<String> = Mt940StreamReader::with_mode(source, ParseMode::Strict)
+        .map(|message| message.unwrap().statement.field_20.reference)
+        .collect();
+    assert_eq!(references, ["STAT202510210001", "REF2", "REF3", "REF4"]);
+}
+
+#[test]
+fn test_blank_narrative_lines() {
+    // Blank and whitespace-only :86: continuation lines, as in ASN bank exports
+    let data = BARE_MESSAGE.replace(
+        ":86:Payment {ref} to supplier   \r\n",
+        ":86:NL47INGB9999999999 hr paulissen\r\n\r\nBetaling sieraden\r\n   \r\n\r\n\r\n",
+    );
+    let mt940 = Mt940::from_string(&data).unwrap();
+
+    let lines = &mt940.statement.statement_lines;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(
+        lines[0].field_86.as_ref().unwrap().narrative,
+        vec![
+            "NL47INGB9999999999 hr paulissen".to_string(),
+            String::new(),
+            "Betaling sieraden".to_string()
+        ]
+    );
+    assert_eq!(mt940.statement.field_62f.amount, 9750.0);
+
+    // An even number of blank lines must not hide the next statement line
+    let second = data.replace(
+        ":62F:",
+        ":61:2510211021C5,00NTRFNONREF\r\n:86:Refund\r\n\r\n\r\n:62F:",
+    );
+    let mt940 = Mt940::from_string(&second).unwrap();
+    assert_eq!(mt940.statement.statement_lines.len(), 2);
+}
+
+#[test]
+fn test_tokenizer_error_offsets() {
+    let origin = SourceSpan {
+        offset: 100,
+        line: 7,
+        column: 1,
+        len: 0,
+    };
+    let unexpected = "{1:F01BANKDEFFAXXX0000000000}\n#{4:\n-}";
+    let error = split_to_blocks(unexpected, &origin).unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert!(matches!(&diagnostic.error, ParserError::Mt940(m) if m == "Unexpected character '#'"));
+    assert_eq!(
+        diagnostic.span,
+        SourceSpan {
+            offset: 130,
+            line: 8,
+            column: 1,
+            len: 1
+        }
+    );
+    assert_eq!(diagnostic.snippet.as_deref(), Some("#{4:"));
+
+    let unterminated = "{1:F01BANKDEFFAXXX0000000000}{2:I940BANKNL2AXXXXN{4:\n-}";
+    let error = split_to_blocks(unterminated, &SourceSpan::default()).unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert_eq!(diagnostic.span.offset, 29);
+    assert_eq!(diagnostic.span.column, 30);
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::Block("2".to_string()))
+    );
+    assert_eq!(
+        error.to_string(),
+        "MT940 parsing error: Unterminated block 2 at line 1, column 30 in block 2"
+    );
+}
+
+#[test]
+fn test_field_error_location() {
+    let data = BARE_MESSAGE.replace(":61:251021", ":61:2510XX");
+    let error = Mt940::from_string(&data).unwrap_err();
+
+    let diagnostic = error.diagnostic().unwrap();
+    assert!(matches!(diagnostic.error, ParserError::Mt940(_)));
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::Field("61".to_string()))
+    );
+    assert_eq!(diagnostic.span.line, 5);
+    assert_eq!(diagnostic.span.column, 1);
+    assert_eq!(
+        diagnostic.snippet.as_deref(),
+        Some(":61:2510XX1021D250,00NTRFNONREF//BKNTRX0001")
+    );
+
+    let rendered = error.render(&data);
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[1], " --> line 5, column 1, field :61:");
+    assert_eq!(lines[3], "5 | :61:2510XX1021D250,00NTRFNONREF//BKNTRX0001");
+    assert_eq!(lines[4], format!("  | {}", "^".repeat(lines[3].len() - 4)));
+    // Without the input the captured line is shown
+    assert_eq!(error.render(""), rendered);
+}
+
+#[test]
+fn test_lenient_skips_invalid_statement_line() {
+    let data = BARE_MESSAGE.replace(
+        ":61:2510211021D250,00",
+        ":61:2510XX1021C10,00NTRFNONREF\r\n:86:Dropped with its line\r\n:61:2510211021D250,00",
+    );
+    assert!(Mt940::from_read(data.as_bytes()).is_err());
+
+    let (mt940, warnings) =
+        Mt940::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    let lines = &mt940.statement.statement_lines;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0].field_61.amount, 250.0);
+    assert_eq!(
+        lines[0].field_86.as_ref().unwrap().narrative,
+        vec!["Payment {ref} to supplier".to_string()]
+    );
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    let diagnostic = warnings[0].error.diagnostic().unwrap();
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::Field("61".to_string()))
+    );
+    assert_eq!(diagnostic.span.line, 5);
+    assert!(
+        warnings[0]
+            .render("")
+            .starts_with("warning (skipped entry): ")
+    );
+}
+
+#[test]
+fn test_lenient_archive_skips_message() {
+    let broken = BARE_MESSAGE.replace(":62F:C251021EUR", ":62F:X251021EUR");
+    let data = format!("{}\r\n{}\r\n{}\r\n", BARE_MESSAGE, broken, BARE_MESSAGE);
+
+    let mut reader = Mt940StreamReader::with_mode(data.as_bytes(), ParseMode::Lenient);
+    let messages: Vec<Mt940> = reader.by_ref().collect::<Result<_, _>>().unwrap();
+    assert_eq!(messages.len(), 2);
+
+    let warnings = reader.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedMessage);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 15);
+}
+
+#[test]
+fn test_error_location_in_archive() {
+    let broken = BARE_MESSAGE.replace(":62F:C251021EUR", ":62F:X251021EUR");
+    let data = format!("{}\r\n{}\r\n", BARE_MESSAGE, broken);
+
+    let source = std::io::BufReader::with_capacity(3, data.as_bytes());
+    let results: Vec<_> = Mt940StreamReader::with_mode(source, ParseMode::Strict).collect();
+    assert!(results[0].is_ok());
+    let error = results[1].as_ref().unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::Field("62F".to_string()))
+    );
+    assert_eq!(diagnostic.span.line, 15);
+    assert_eq!(diagnostic.span.column, 1);
+    assert_eq!(
+        &data[diagnostic.span.offset..diagnostic.span.offset + diagnostic.span.len],
+        ":62F:X251021EUR9750,00"
+    );
+}
+
+#[test]
+fn test_header_error_location() {
+    let data = "{1:F01BANKDEFFAXXX00000}{2:I940BANKNL2AXXXXN}{4:\n:20:REF\n-}";
+    let error = Mt940::from_string(data).unwrap_err();
+    let diagnostic = error.diagnostic().unwrap();
+    assert_eq!(
+        diagnostic.location,
+        Some(ErrorLocation::Block("1".to_string()))
+    );
+    assert_eq!(diagnostic.span.offset, 3);
+    assert_eq!(diagnostic.span.len, 20);
+}
+
+#[test]
+fn test_application_header_input() {
+    let header = ApplicationHeaderBlock::from_string("I940BANKNL2AXXXXU3003").unwrap();
+    assert_eq!(
+        header,
+        ApplicationHeaderBlock::Input(InputApplicationHeader {
+            message_type: "940".to_string(),
+            receiver_address: "BANKNL2AXXXX".to_string(),
+            priority: Some('U'),
+            delivery_monitoring: Some('3'),
+            obsolescence_period: Some("003".to_string()),
+        })
+    );
+    assert_eq!(header.bic(), Some("BANKNL2AXXX".to_string()));
+    assert_eq!(header.to_string(), "I940BANKNL2AXXXXU3003");
+
+    for data in ["I940BANKNL2AXXXX", "I940BANKNL2AXXXXN"] {
+        assert_eq!(
+            ApplicationHeaderBlock::from_string(data)
+                .unwrap()
+                .to_string(),
+            data
+        );
+    }
+}
+
+#[test]
+fn test_application_header_output() {
+    let data = "O9400100230920DEUTDEFFAXXX00000000002309201000N";
+    let header = ApplicationHeaderBlock::from_string(data).unwrap();
+    let ApplicationHeaderBlock::Output(output) = &header else {
+        panic!("expected an output header: {:?}", header);
+    };
+    let expected = OutputApplicationHeader {
+        message_type: "940".to_string(),
+        input_time: "0100".to_string(),
+        output_date: "230920".to_string(),
+        output_time: "1000".to_string(),
+        priority: Some('N'),
+        ..Default::default()
+    };
+    assert_eq!(output.message_type, expected.message_type);
+    assert_eq!(output.input_time, expected.input_time);
+    assert_eq!(output.mir.date, "230920");
+    assert_eq!(output.mir.lt_address, "DEUTDEFFAXXX");
+    assert_eq!(output.mir.session_number, "0000");
+    assert_eq!(output.mir.sequence_number, "000000");
+    assert_eq!(output.output_date, expected.output_date);
+    assert_eq!(output.output_time, expected.output_time);
+    assert_eq!(output.priority, expected.priority);
+    assert_eq!(header.bic(), Some("DEUTDEFFXXX".to_string()));
+    assert_eq!(header.to_string(), data);
+
+    // Shortened bank layouts are kept verbatim
+    let short = ApplicationHeaderBlock::from_string("O940ASNBNL21XXXXN").unwrap();
+    assert_eq!(
+        short,
+        ApplicationHeaderBlock::Unstructured("O940ASNBNL21XXXXN".to_string())
+    );
+    assert_eq!(short.to_string(), "O940ASNBNL21XXXXN");
+}
+
+#[test]
+fn test_user_header_and_trailer_round_trip() {
+    let user_header =
+        "{103:TGT}{108:MUR-2025-0001}{119:STP}{121:e0b5b8c6-3a4b-4c4e-9d5f-6a7b8c9d0e1f}";
+    let parsed = UserHeaderBlock::from_string(user_header).unwrap();
+    assert_eq!(parsed.mur.as_deref(), Some("MUR-2025-0001"));
+    assert_eq!(parsed.validation_flag.as_deref(), Some("STP"));
+    assert_eq!(
+        parsed.uetr.as_deref(),
+        Some("e0b5b8c6-3a4b-4c4e-9d5f-6a7b8c9d0e1f")
+    );
+    assert_eq!(
+        parsed.other,
+        vec![BlockTag {
+            tag: "103".to_string(),
+            value: "TGT".to_string()
+        }]
+    );
+    assert_eq!(parsed.to_string(), user_header);
+
+    let trailer = "{MAC:00000000}{CHK:123456789ABC}{TNG:}{PDE:}{DLM:}";
+    let parsed = TrailerBlock::from_string(trailer).unwrap();
+    assert_eq!(parsed.chk.as_deref(), Some("123456789ABC"));
+    assert_eq!(parsed.mac.as_deref(), Some("00000000"));
+    assert_eq!(parsed.pde.as_deref(), Some(""));
+    assert_eq!(parsed.dlm.as_deref(), Some(""));
+    assert_eq!(parsed.other.len(), 1);
+    assert_eq!(parsed.to_string(), trailer);
+
+    assert!(UserHeaderBlock::from_string("108:MUR").is_err());
+    assert!(UserHeaderBlock::from_string("{108:A}{108:B}").is_err());
+    assert!(TrailerBlock::from_string("{CHK:1").is_err());
+}
+
+#[test]
+fn test_headers_round_trip_through_message() {
+    let data = format!(
+        "{{1:F01BANKDEFFAXXX0000000000}}{{2:O9400100230920DEUTDEFFAXXX00000000002309201000N}}{{3:{{108:MUR}}{{121:UETR-1}}}}{{4:\r\n{}}}{{5:{{CHK:ABCDEF}}{{PDE:}}}}",
+        BARE_MESSAGE
+    );
+    let mt940 = Mt940::from_string(&data).unwrap();
+    assert_eq!(mt940.sender_bic(), Some("DEUTDEFFXXX".to_string()));
+
+    let mut written = Vec::new();
+    mt940.write_to(&mut written).unwrap();
+    let written = String::from_utf8(written).unwrap();
+    assert!(written.starts_with(
+        "{1:F01BANKDEFFAXXX0000000000}{2:O9400100230920DEUTDEFFAXXX00000000002309201000N}\r\n{3:{108:MUR}{121:UETR-1}}\r\n"
+    ));
+    assert!(written.ends_with("{5:{CHK:ABCDEF}{PDE:}}"));
+    assert_eq!(Mt940::from_string(&written).unwrap(), mt940);
+}
@@ -0,0 +1,257 @@
+use crate::ParserError;
+use crate::errors::{ErrorLocation, SourceSpan};
+use std::ops::{Index, IndexMut};
+
+/// Blocks 1–5 of one SWIFT FIN message, index 0 holding block 1.
+#[derive(Debug, Default)]
+pub(crate) struct FinBlocks {
+    contents: [Option<String>; 5],
+    // Byte index of every block's content in the tokenized data
+    starts: [usize; 5],
+}
+
+impl FinBlocks {
+    /// Byte index in the tokenized data where the content of block `index` starts.
+    pub(crate) fn start(&self, index: usize) -> usize {
+        self.starts[index]
+    }
+}
+
+impl Index<usize> for FinBlocks {
+    type Output = Option<String>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.contents[index]
+    }
+}
+
+impl IndexMut<usize> for FinBlocks {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.contents[index]
+    }
+}
+
+/// Splits the first SWIFT FIN message in `data` into its blocks.
+///
+/// `origin` is the position of `data` in the whole input, errors are
+/// located relative to it.
+///
+/// - Header and trailer blocks may contain nested tags, e.g. `{3:{108:REF}}`.
+/// - Block 4 ends only at a `-}` line, so braces in narrative text are allowed.
+/// - A message without envelope (bare `:20:` … `-` text) is returned as block 4.
+/// - Parsing stops at a `$` separator or where the next message begins.
+/// - CRLF and LF line ends are both accepted; block 4 is normalized to LF
+///   with trailing whitespace removed from every line. Blank lines inside a
+///   field, e.g. between `:86:` narrative lines, are kept; blank lines at
+///   the end of a field are dropped.
+pub(crate) fn split_to_blocks(data: &str, origin: &SourceSpan) -> Result<FinBlocks, ParserError> {
+    let bytes = data.as_bytes();
+    let mut blocks = FinBlocks::default();
+    let mut seen_any = false;
+    let mut seen_text = false;
+    let mut i = 0;
+
+    loop {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() {
+            break;
+        }
+
+        match bytes[i] {
+            b'$' if seen_any => break,
+            b'$' => i += 1,
+            b'{' => {
+                let start = i;
+                let colon = bytes[start..]
+                    .iter()
+                    .position(|&b| b == b':')
+                    .map(|p| start + p)
+                    .ok_or_else(|| error(data, start, origin, "Block without identifier", None))?;
+                let id = &data[start + 1..colon];
+                if id.is_empty() || !id.bytes().all(|b| b.is_ascii_alphanumeric()) {
+                    return Err(error(
+                        data,
+                        start,
+                        origin,
+                        &format!("Invalid block identifier '{}'", id),
+                        None,
+                    ));
+                }
+                // Block 1 after the text block opens the next message
+                if id == "1" && seen_text {
+                    break;
+                }
+
+                let content_start = colon + 1;
+                let end = if id == "4" {
+                    find_text_block_end(bytes, content_start)
+                } else {
+                    find_closing_brace(bytes, content_start)
+                }
+                .ok_or_else(|| {
+                    error(
+                        data,
+                        start,
+                        origin,
+                        &format!("Unterminated block {}", id),
+                        Some(id),
+                    )
+                })?;
+
+                let content = &data[content_start..end];
+                if let Ok(number) = id.parse::<usize>()
+                    && (1..=5).contains(&number)
+                {
+                    if blocks[number - 1].is_some() {
+                        return Err(error(
+                            data,
+                            start,
+                            origin,
+                            &format!("Duplicate block {}", id),
+                            Some(id),
+                        ));
+                    }
+                    blocks[number - 1] = Some(if number == 4 {
+                        normalize_text(content)
+                    } else {
+                        content.to_string()
+                    });
+                    blocks.starts[number - 1] = content_start;
+                }
+                // Other blocks, e.g. the system trailer {S:...}, are skipped
+
+                seen_text |= id == "4";
+                seen_any = true;
+                i = end + 1;
+            }
+            b':' if !seen_any => {
+                let (end, next) = find_bare_text_end(bytes, i);
+                blocks[3] = Some(normalize_text(&format!("\n{}", &data[i..end])));
+                blocks.starts[3] = i;
+                seen_any = true;
+                seen_text = true;
+                i = next;
+            }
+            // A bare message after the text block is the next message
+            b':' if seen_text => break,
+            _ => {
+                let c = data[i..].chars().next().unwrap_or_default();
+                return Err(error(
+                    data,
+                    i,
+                    origin,
+                    &format!("Unexpected character '{}'", c),
+                    None,
+                ));
+            }
+        }
+    }
+
+    Ok(blocks)
+}
+
+/// Finds the `}` closing a header block, skipping nested `{tag:value}` pairs.
+fn find_closing_brace(bytes: &[u8], from: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, &b) in bytes.iter().enumerate().skip(from) {
+        match b {
+            b'{' => depth += 1,
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Finds the `}` of a `-}` line closing block 4.
+fn find_text_block_end(bytes: &[u8], from: usize) -> Option<usize> {
+    let mut line_start = from;
+    for (i, &b) in bytes.iter().enumerate().skip(from) {
+        match b {
+            b'\n' => line_start = i + 1,
+            b'}' if bytes[line_start..i].trim_ascii() == b"-" => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the end of a bare text block (after its `-` line) and the position to continue from.
+fn find_bare_text_end(bytes: &[u8], from: usize) -> (usize, usize) {
+    let mut line_start = from;
+    for (i, &b) in bytes.iter().enumerate().skip(from) {
+        match b {
+            b'$' => return (i, i),
+            b'\n' => {
+                if bytes[line_start..i].trim_ascii() == b"-" {
+                    return (i, i + 1);
+                }
+                line_start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    (bytes.len(), bytes.len())
+}
+
+fn normalize_text(content: &str) -> String {
+    let mut lines = content.lines().map(str::trim_end);
+    // The rest of the `{4:` line, empty in an enveloped message
+    let mut text = lines.next().unwrap_or_default().to_string();
+    let mut blank = 0;
+    for line in lines {
+        if line.is_empty() {
+            blank += 1;
+            continue;
+        }
+        // Blank lines before the next field or the `-` line end no field
+        let next_field = line.starts_with(':') || line == "-";
+        if !next_field {
+            text.push_str(&"\n".repeat(blank));
+        }
+        blank = 0;
+        text.push('\n');
+        text.push_str(line);
+    }
+    text
+}
+
+/// Block 4 text as the SWIFT field parser reads it: blank lines inside a
+/// field hold a single space.
+///
+/// The parser finds the end of a field at a line break followed by `:`,
+/// but skips the character after every line break it checks, so after an
+/// even number of line breaks the next field is taken as part of the
+/// current one. Blank lines are turned back into empty narrative lines by
+/// [`blank_narrative_lines`].
+pub(crate) fn swift_text(text: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(index, line)| match line {
+            "" if index > 0 => " ",
+            line => line,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Narrative lines read from [`swift_text`], with blank lines empty again.
+pub(crate) fn blank_narrative_lines(lines: &mut [String]) {
+    for line in lines.iter_mut().filter(|line| line.trim().is_empty()) {
+        line.clear();
+    }
+}
+
+fn error(
+    data: &str,
+    index: usize,
+    origin: &SourceSpan,
+    message: &str,
+    block: Option<&str>,
+) -> ParserError {
+    let location = block.map(|id| ErrorLocation::Block(id.to_string()));
+    ParserError::Mt940(message.to_string()).at_text(data, index, 1, origin, location)
+}
@@ -0,0 +1,18 @@
+use crate::mt940::format::{
+    ApplicationHeaderBlock, BasicHeaderBlock, TrailerBlock, UserHeaderBlock,
+};
+use swift_mt_message::messages;
+
+/// An MT950 statement message, in the envelope of [`Mt940`](crate::Mt940).
+///
+/// Unlike MT940 the statement lines have no `:86:` narrative, there is no
+/// `:21:` related reference, and the balances may be intermediate ones
+/// (`:60M:`, `:62M:`) of a statement sent in several messages.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Mt950 {
+    pub basic_header: BasicHeaderBlock,
+    pub application_header: ApplicationHeaderBlock,
+    pub user_header: Option<UserHeaderBlock>, // may be skipped
+    pub statement: messages::MT950,
+    pub footer: Option<TrailerBlock>, // may be skipped
+}
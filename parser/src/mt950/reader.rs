@@ -0,0 +1,129 @@
+use crate::FinancialDataRead;
+use crate::Mt950;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan};
+use crate::mt940::reader::{TextBlockFields, check_field, parse_message, sender_bic};
+use crate::mt940::stream::FinMessage;
+use swift_mt_message::errors::ParseError;
+use swift_mt_message::fields::{
+    Field20, Field25NoOption, Field28C, Field60, Field60F, Field60M, Field61, Field62, Field62F,
+    Field62M, Field64,
+};
+use swift_mt_message::messages;
+use swift_mt_message::parser::MessageParser;
+
+/// Validates one field of the MT950 text block on its own.
+fn check_text_field(tag: &str, value: &str) -> Result<(), String> {
+    match tag {
+        "20" => check_field::<Field20>(value),
+        "25" => check_field::<Field25NoOption>(value),
+        "28C" => check_field::<Field28C>(value),
+        "60F" => check_field::<Field60F>(value),
+        "60M" => check_field::<Field60M>(value),
+        "61" => check_field::<Field61>(value),
+        "62F" => check_field::<Field62F>(value),
+        "62M" => check_field::<Field62M>(value),
+        "64" => check_field::<Field64>(value),
+        _ => Ok(()),
+    }
+}
+
+/// Parses the MT950 text block like `MT950::parse_from_block4`, but fails
+/// on an invalid statement line instead of ending the statement lines there.
+fn parse_statement(text: &str) -> Result<messages::MT950, ParseError> {
+    let mut parser = MessageParser::new(text, "950");
+    let field_20 = parser.parse_field::<Field20>("20")?;
+    let field_25 = parser.parse_field::<Field25NoOption>("25")?;
+    let field_28c = parser.parse_field::<Field28C>("28C")?;
+    let field_60 = if parser.detect_field("60M") {
+        Field60::M(parser.parse_field::<Field60M>("60M")?)
+    } else {
+        Field60::F(parser.parse_field::<Field60F>("60F")?)
+    };
+
+    parser = parser.with_duplicates(true);
+    let mut statement_lines = Vec::new();
+    while parser.detect_field("61") {
+        statement_lines.push(parser.parse_field::<Field61>("61")?);
+    }
+    parser = parser.with_duplicates(false);
+
+    let field_62 = if parser.detect_field("62M") {
+        Field62::M(parser.parse_field::<Field62M>("62M")?)
+    } else {
+        Field62::F(parser.parse_field::<Field62F>("62F")?)
+    };
+    let field_64 = parser.parse_optional_field::<Field64>("64")?;
+
+    Ok(messages::MT950 {
+        field_20,
+        field_25,
+        field_28c,
+        field_60,
+        field_61: (!statement_lines.is_empty()).then_some(statement_lines),
+        field_62,
+        field_64,
+    })
+}
+
+/// Fields of the MT950 text block.
+const MT950_FIELDS: TextBlockFields = TextBlockFields {
+    error: ParserError::Mt950,
+    check: check_text_field,
+    optional: &["61", "64"],
+};
+
+impl Mt950 {
+    /// BIC of the sending institution, see [`sender_bic`].
+    pub(crate) fn sender_bic(&self) -> Option<String> {
+        sender_bic(&self.basic_header, &self.application_header)
+    }
+
+    /// Parses the first SWIFT message in `data`, which starts at `origin` in
+    /// the input. In lenient mode invalid statement lines and `:64:` are left
+    /// out with a warning instead of failing the message.
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let (envelope, statement) =
+            parse_message(data, origin, recovery, &MT950_FIELDS, |_, text| {
+                parse_statement(text).map_err(|e| ParserError::Mt950(e.to_string()))
+            })?;
+
+        Ok(Mt950 {
+            basic_header: envelope.basic_header,
+            application_header: envelope.application_header,
+            user_header: envelope.user_header,
+            statement,
+            footer: envelope.footer,
+        })
+    }
+}
+
+impl FinMessage for Mt950 {
+    fn from_string_recovering(
+        data: &str,
+        origin: &SourceSpan,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        Mt950::from_string_recovering(data, origin, recovery)
+    }
+}
+
+impl FinancialDataRead for Mt950 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(mt950, _)| mt950)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Mt950(e.to_string()))?;
+        let mut recovery = Recovery::new(mode);
+        let mt950 = Self::from_string_recovering(&data, &SourceSpan::default(), &mut recovery)?;
+        Ok((mt950, recovery.into_warnings()))
+    }
+}
@@ -0,0 +1,81 @@
+use crate::errors::{ParseMode, WarningKind};
+use crate::mt940::stream::SwiftStreamReader;
+use crate::mt950::format::Mt950;
+use crate::test_utils::read_test_file;
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use swift_mt_message::fields::{Field60, Field62};
+
+fn read_messages(data: &str, mode: ParseMode) -> (Vec<Mt950>, Vec<crate::ParseWarning>) {
+    let mut reader = SwiftStreamReader::<_, Mt950>::with_mode(data.as_bytes(), mode);
+    let messages = reader.by_ref().collect::<Result<_, _>>().unwrap();
+    (messages, reader.take_warnings())
+}
+
+#[test]
+fn test_with_file() {
+    let mt950 = Mt950::from_read(read_test_file("valid1.mt950").as_bytes()).unwrap();
+
+    assert_eq!(mt950.application_header.message_type(), Some("950"));
+    assert_eq!(mt950.sender_bic(), Some("BANKGB2LXXX".to_string()));
+    let statement = &mt950.statement;
+    assert_eq!(statement.field_20.reference, "NOSTRO2510210001");
+    assert_eq!(statement.field_25.authorisation, "0012345678");
+    assert!(matches!(statement.field_60, Field60::F(_)));
+    assert!(matches!(statement.field_62, Field62::M(_)));
+    let lines = statement.field_61.as_ref().unwrap();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].amount, 15000.0);
+    assert_eq!(
+        lines[0].supplementary_details.as_deref(),
+        Some("SUPPLIER SETTLEMENT")
+    );
+    assert_eq!(statement.field_64, None);
+}
+
+#[test]
+fn test_read_write() {
+    let mt950 = Mt950::from_read(read_test_file("valid1.mt950").as_bytes()).unwrap();
+
+    let mut data = Vec::new();
+    mt950.write_to(&mut data).unwrap();
+    assert_eq!(Mt950::from_read(data.as_slice()).unwrap(), mt950);
+}
+
+#[test]
+fn test_stream_multiple_messages() {
+    let (messages, warnings) = read_messages(&read_test_file("valid1.mt950"), ParseMode::Strict);
+
+    assert!(warnings.is_empty());
+    assert_eq!(messages.len(), 2);
+    let second = &messages[1].statement;
+    assert_eq!(second.field_28c.sequence_number, Some(2));
+    assert!(matches!(second.field_60, Field60::M(_)));
+    assert!(matches!(second.field_62, Field62::F(_)));
+    assert!(second.field_64.is_some());
+}
+
+#[test]
+fn test_missing_balance() {
+    let data = read_test_file("valid1.mt950").replace(":62M:C251021EUR317500,00\n", "");
+    assert!(Mt950::from_read(data.as_bytes()).is_err());
+
+    let (messages, warnings) = read_messages(&data, ParseMode::Lenient);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].statement.field_20.reference, "NOSTRO2510210002");
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedMessage);
+}
+
+#[test]
+fn test_lenient_skips_statement_line() {
+    let data =
+        read_test_file("valid1.mt950").replace(":61:2510211021C82500", ":61:2510211021X82500");
+    assert!(Mt950::from_read(data.as_bytes()).is_err());
+
+    let (mt950, warnings) =
+        Mt950::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(mt950.statement.field_61.as_ref().unwrap().len(), 1);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+}
@@ -0,0 +1,20 @@
+use crate::FinancialDataWrite;
+use crate::Mt950;
+use crate::ParserError;
+use crate::mt940::reader::write_message;
+
+use swift_mt_message::SwiftMessageBody;
+
+impl FinancialDataWrite for Mt950 {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = write_message(
+            &self.basic_header,
+            &self.application_header,
+            self.user_header.as_ref(),
+            &self.statement.to_mt_string(),
+            self.footer.as_ref(),
+        )?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
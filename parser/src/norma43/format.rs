@@ -0,0 +1,135 @@
+use crate::coda::format::IsoCode;
+
+/// A Norma 43 file: the statements of one or more accounts, each a header
+/// (`11`) to totals (`33`) sequence, and the end of file record (`88`).
+///
+/// The totals and end of file records are not kept: the reader checks
+/// them, and the writer computes them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Norma43 {
+    pub accounts: Vec<Norma43Account>,
+}
+
+/// Records `11` to `33` of one account.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Norma43Account {
+    /// Entity code, 4 digits.
+    pub bank_code: String,
+    /// Office code, 4 digits.
+    pub branch_code: String,
+    /// 10 digits, without the check digits of the CCC.
+    pub number: String,
+    /// `YYMMDD`.
+    pub start_date: String,
+    /// `YYMMDD`.
+    pub end_date: String,
+    /// In cents, negative for a debit (debe) balance.
+    pub initial_balance: i64,
+    /// In cents, negative for a debit (debe) balance.
+    pub final_balance: i64,
+    /// ISO 4217 numeric code, e.g. `978`.
+    pub currency: String,
+    /// Information mode `1`, `2` or `3`.
+    pub information_mode: String,
+    pub name: String,
+    pub movements: Vec<Norma43Movement>,
+}
+
+/// Record `22` with the `23` and `24` records that follow it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Norma43Movement {
+    /// Office where the movement originated.
+    pub branch_code: String,
+    /// `YYMMDD`.
+    pub operation_date: String,
+    /// `YYMMDD`.
+    pub value_date: String,
+    /// Concepto común, 2 digits.
+    pub common_concept: String,
+    /// Concepto propio of the bank, 3 digits.
+    pub own_concept: String,
+    /// In cents, negative for a debit (debe).
+    pub amount: i64,
+    pub document_number: String,
+    pub reference_1: String,
+    pub reference_2: String,
+    /// The 38-character concept texts of the `23` records, two per record.
+    pub concepts: Vec<String>,
+    /// Record `24`: the amount in the currency of origin.
+    pub equivalence: Option<Equivalence>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Equivalence {
+    /// ISO 4217 numeric code.
+    pub currency: String,
+    /// In cents.
+    pub amount: i64,
+}
+
+/// ISO 4217 numeric codes of the currencies Spanish banks report in.
+pub(crate) const CURRENCIES: [(&str, &str); 16] = [
+    ("978", "EUR"),
+    ("840", "USD"),
+    ("826", "GBP"),
+    ("756", "CHF"),
+    ("392", "JPY"),
+    ("752", "SEK"),
+    ("578", "NOK"),
+    ("208", "DKK"),
+    ("124", "CAD"),
+    ("036", "AUD"),
+    ("156", "CNY"),
+    ("484", "MXN"),
+    ("985", "PLN"),
+    ("203", "CZK"),
+    ("348", "HUF"),
+    ("504", "MAD"),
+];
+
+/// Common concepts mapped to ISO 20022 bank transaction codes (domain,
+/// family, sub-family), for credits and for debits.
+///
+/// Concepts without an entry map to nothing; CAMT.053 codes without an
+/// entry are written as concept `99`, various.
+pub(crate) const COMMON_CONCEPTS: [(&str, [IsoCode; 2]); 8] = [
+    ("01", [("PMNT", "RCHQ", "CCHQ"), ("PMNT", "ICHQ", "CCHQ")]),
+    ("02", [("PMNT", "CNTR", "CDPT"), ("PMNT", "CNTR", "CDPT")]),
+    ("03", [("PMNT", "IDDT", "ESDD"), ("PMNT", "RDDT", "ESDD")]),
+    ("04", [("PMNT", "RCDT", "ESCT"), ("PMNT", "ICDT", "ESCT")]),
+    ("11", [("PMNT", "CCRD", "CWDL"), ("PMNT", "CCRD", "CWDL")]),
+    ("12", [("PMNT", "CCRD", "POSD"), ("PMNT", "CCRD", "POSD")]),
+    ("15", [("PMNT", "RCDT", "SALA"), ("PMNT", "ICDT", "SALA")]),
+    ("17", [("ACMT", "MCOP", "INTR"), ("ACMT", "MDOP", "CHRG")]),
+];
+
+/// A check digit of a CCC: the weighted sum of ten digits modulo 11.
+fn ccc_digit(digits: &str) -> Option<u32> {
+    const WEIGHTS: [u32; 10] = [1, 2, 4, 8, 5, 10, 9, 7, 3, 6];
+    let sum = digits
+        .chars()
+        .zip(WEIGHTS)
+        .map(|(c, weight)| Some(c.to_digit(10)? * weight))
+        .sum::<Option<u32>>()?;
+    Some(match 11 - sum % 11 {
+        11 => 0,
+        10 => 1,
+        digit => digit,
+    })
+}
+
+impl Norma43Account {
+    /// The CCC of the account: entity, office, the two check digits, which
+    /// the records leave out, and the number.
+    pub(crate) fn ccc(&self) -> Option<String> {
+        if self.bank_code.len() != 4 || self.branch_code.len() != 4 || self.number.len() != 10 {
+            return None;
+        }
+        let first = ccc_digit(&format!("00{}{}", self.bank_code, self.branch_code))?;
+        let second = ccc_digit(&self.number)?;
+        Some(format!(
+            "{}{}{}{}{}",
+            self.bank_code, self.branch_code, first, second, self.number
+        ))
+    }
+}
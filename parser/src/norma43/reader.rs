@@ -0,0 +1,350 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, WarningKind};
+use crate::fixed_width::{Line, RecordLayout, decode};
+use crate::norma43::format::*;
+
+/// Norma 43 records are 80 characters with `YYMMDD` dates.
+pub(crate) static NORMA43_RECORDS: RecordLayout = RecordLayout {
+    length: 80,
+    error: ParserError::Norma43,
+    concatenated: false,
+    date_format: "YYMMDD",
+    blank_dates: false,
+};
+
+/// A debe/haber key, `1` for a debit and `2` for a credit, followed by
+/// a 14-digit amount in cents.
+fn amount(line: &Line, key: usize, what: &str) -> Result<i64, String> {
+    let amount: i64 = line.number(key + 1, 14, what)?;
+    match line.char_at(key) {
+        '1' => Ok(-amount),
+        '2' => Ok(amount),
+        other => Err(format!(
+            "Invalid debit/credit key '{}' of the {}",
+            other, what
+        )),
+    }
+}
+
+fn header(line: &Line) -> Result<Norma43Account, String> {
+    Ok(Norma43Account {
+        bank_code: line.field(3, 4),
+        branch_code: line.field(7, 4),
+        number: line.field(11, 10),
+        start_date: line.date(21, "start date")?,
+        end_date: line.date(27, "end date")?,
+        initial_balance: amount(line, 33, "initial balance")?,
+        final_balance: 0,
+        currency: line.field(48, 3),
+        information_mode: line.field(51, 1),
+        name: line.field(52, 26),
+        movements: Vec::new(),
+    })
+}
+
+fn movement(line: &Line) -> Result<Norma43Movement, String> {
+    Ok(Norma43Movement {
+        branch_code: line.field(7, 4),
+        operation_date: line.date(11, "operation date")?,
+        value_date: line.date(17, "value date")?,
+        common_concept: line.field(23, 2),
+        own_concept: line.field(25, 3),
+        amount: amount(line, 28, "amount")?,
+        document_number: line.field(43, 10),
+        reference_1: line.field(53, 12),
+        reference_2: line.field(65, 16),
+        concepts: Vec::new(),
+        equivalence: None,
+    })
+}
+
+/// Movement counts and totals of the account being read.
+#[derive(Default)]
+struct Totals {
+    debits: i64,
+    debit: i64,
+    credits: i64,
+    credit: i64,
+}
+
+/// Builds the accounts from their records and checks the totals and end
+/// of file records against them.
+struct Norma43Reader<'a, 'r> {
+    data: &'a str,
+    recovery: &'r mut Recovery,
+    accounts: Vec<Norma43Account>,
+    account: Option<(Norma43Account, Totals)>,
+    /// Records before the end of file record.
+    records: i64,
+    /// Whether the records of a movement that could not be read are being skipped.
+    skipping: bool,
+    ended: bool,
+}
+
+impl Norma43Reader<'_, '_> {
+    fn recover(
+        &mut self,
+        kind: WarningKind,
+        line: &Line,
+        message: impl Into<String>,
+    ) -> Result<(), ParserError> {
+        let error = line.error(self.data, message);
+        self.recovery.recover(kind, error)
+    }
+
+    fn last_movement(&mut self, line: &Line) -> Result<&mut Norma43Movement, ParserError> {
+        let data = self.data;
+        self.account
+            .as_mut()
+            .and_then(|(account, _)| account.movements.last_mut())
+            .ok_or_else(|| {
+                line.error(
+                    data,
+                    format!("Record {} without a movement record 22", line.code()),
+                )
+            })
+    }
+
+    fn record(&mut self, line: &Line) -> Result<(), ParserError> {
+        let data = self.data;
+        let code = line.code();
+        if self.ended {
+            return Err(line.error(data, "Record after the end of file record 88"));
+        }
+        if !matches!(code.as_str(), "23" | "24") {
+            self.skipping = false;
+        }
+        if code != "88" {
+            self.records += 1;
+        }
+        if code != "11" && code != "88" && self.account.is_none() {
+            return Err(line.error(
+                data,
+                format!("Record {} before an account header record 11", code),
+            ));
+        }
+
+        match code.as_str() {
+            "11" => {
+                if self.account.is_some() {
+                    self.recover(
+                        WarningKind::SkippedField,
+                        line,
+                        "Missing totals record 33 before this record",
+                    )?;
+                    self.finish(None);
+                }
+                let account = header(line).map_err(|e| line.error(data, e))?;
+                self.account = Some((account, Totals::default()));
+            }
+            "22" => match movement(line) {
+                Ok(movement) => {
+                    let (account, totals) = self.account.as_mut().unwrap();
+                    match movement.amount < 0 {
+                        true => {
+                            totals.debits += 1;
+                            totals.debit -= movement.amount;
+                        }
+                        false => {
+                            totals.credits += 1;
+                            totals.credit += movement.amount;
+                        }
+                    }
+                    account.movements.push(movement);
+                }
+                Err(e) => {
+                    self.skipping = true;
+                    self.recover(WarningKind::SkippedEntry, line, e)?;
+                }
+            },
+            "23" | "24" if self.skipping => {}
+            "23" => {
+                let movement = self.last_movement(line)?;
+                movement.concepts.push(line.field(5, 38));
+                let second = line.field(43, 38);
+                if !second.is_empty() {
+                    movement.concepts.push(second);
+                }
+            }
+            "24" => {
+                let amount = line.number(8, 14, "equivalent amount");
+                let movement = self.last_movement(line)?;
+                match amount {
+                    Ok(amount) => {
+                        movement.equivalence = Some(Equivalence {
+                            currency: line.field(5, 3),
+                            amount,
+                        })
+                    }
+                    Err(e) => self.recover(WarningKind::SkippedField, line, e)?,
+                }
+            }
+            "33" => self.totals(line)?,
+            "88" => self.end(line)?,
+            other => {
+                self.recover(
+                    WarningKind::SkippedField,
+                    line,
+                    format!("Unknown record type '{}'", other),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the totals record against the movements of the account and closes it.
+    fn totals(&mut self, line: &Line) -> Result<(), ParserError> {
+        let data = self.data;
+        let (account, totals) = self.account.as_ref().unwrap();
+        let number = line.field(11, 10);
+        if number != account.number {
+            return Err(line.error(
+                data,
+                format!(
+                    "Totals record 33 of account {} after the records of account {}",
+                    number, account.number
+                ),
+            ));
+        }
+        let final_balance = amount(line, 59, "final balance").map_err(|e| line.error(data, e))?;
+        let computed = [
+            (
+                "Debit count",
+                totals.debits,
+                line.number::<i64>(21, 5, "debit count"),
+            ),
+            (
+                "Debit total",
+                totals.debit,
+                line.number(26, 14, "debit total"),
+            ),
+            (
+                "Credit count",
+                totals.credits,
+                line.number(40, 5, "credit count"),
+            ),
+            (
+                "Credit total",
+                totals.credit,
+                line.number(45, 14, "credit total"),
+            ),
+            (
+                "Final balance",
+                account.initial_balance + totals.credit - totals.debit,
+                Ok(final_balance),
+            ),
+        ];
+        for (what, computed, record) in computed {
+            let error = match record {
+                Ok(value) if value == computed => continue,
+                Ok(value) => format!(
+                    "{} {} does not match the records, {}",
+                    what, value, computed
+                ),
+                Err(e) => e,
+            };
+            self.recover(WarningKind::SkippedField, line, error)?;
+        }
+        self.finish(Some(final_balance));
+        Ok(())
+    }
+
+    /// Checks the record count of the end of file record.
+    fn end(&mut self, line: &Line) -> Result<(), ParserError> {
+        if self.account.is_some() {
+            self.recover(
+                WarningKind::SkippedField,
+                line,
+                "Missing totals record 33 before this record",
+            )?;
+            self.finish(None);
+        }
+        let error = match line.number::<i64>(21, 6, "record count") {
+            Ok(count) if count == self.records => None,
+            Ok(count) => Some(format!(
+                "Record count {} does not match the records, {}",
+                count, self.records
+            )),
+            Err(e) => Some(e),
+        };
+        if let Some(error) = error {
+            self.recover(WarningKind::SkippedField, line, error)?;
+        }
+        self.ended = true;
+        Ok(())
+    }
+
+    /// Closes the account being read, with the final balance of its totals
+    /// record or, without one, the balance its movements add up to.
+    fn finish(&mut self, final_balance: Option<i64>) {
+        if let Some((mut account, totals)) = self.account.take() {
+            account.final_balance =
+                final_balance.unwrap_or(account.initial_balance + totals.credit - totals.debit);
+            self.accounts.push(account);
+        }
+    }
+}
+
+impl Norma43 {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let lines = NORMA43_RECORDS.lines(data)?;
+        if lines.is_empty() {
+            return Err(ParserError::Norma43("Empty file".to_string()));
+        }
+        let mut reader = Norma43Reader {
+            data,
+            recovery,
+            accounts: Vec::new(),
+            account: None,
+            records: 0,
+            skipping: false,
+            ended: false,
+        };
+        for line in &lines {
+            reader.record(line)?;
+        }
+        let last = lines.last().unwrap();
+        if reader.account.is_some() {
+            reader.recover(
+                WarningKind::SkippedField,
+                last,
+                "Missing totals record 33 after this record",
+            )?;
+            reader.finish(None);
+        }
+        if !reader.ended {
+            reader.recover(
+                WarningKind::SkippedField,
+                last,
+                "Missing end of file record 88 after this record",
+            )?;
+        }
+        Ok(Norma43 {
+            accounts: reader.accounts,
+        })
+    }
+}
+
+impl FinancialDataRead for Norma43 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(norma43, _)| norma43)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        mut reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::Norma43(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let norma43 = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((norma43, recovery.into_warnings()))
+    }
+}
@@ -0,0 +1,129 @@
+use crate::Norma43;
+use crate::errors::{ParseMode, WarningKind};
+use crate::norma43::format::{Equivalence, Norma43Account};
+use crate::test_utils::{patch, read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let norma43 = Norma43::from_read(File::open(test_data_path("valid1.n43")).unwrap()).unwrap();
+
+    assert_eq!(norma43.accounts.len(), 1);
+    let account = &norma43.accounts[0];
+    assert_eq!(account.bank_code, "2100");
+    assert_eq!(account.branch_code, "0418");
+    assert_eq!(account.number, "0200051332");
+    assert_eq!(account.start_date, "251001");
+    assert_eq!(account.end_date, "251031");
+    assert_eq!(account.currency, "978");
+    assert_eq!(account.name, "GLOBEX IBERICA SL");
+    assert_eq!(account.initial_balance, 150_000);
+    assert_eq!(account.final_balance, 377_845);
+    assert_eq!(account.movements.len(), 4);
+
+    let credit = &account.movements[0];
+    assert_eq!(credit.amount, 250_000);
+    assert_eq!(credit.common_concept, "04");
+    assert_eq!(credit.own_concept, "012");
+    assert_eq!(credit.document_number, "0000000001");
+    assert_eq!(credit.reference_1, "E2E000000001");
+    assert_eq!(credit.reference_2, "FRA-2025-118");
+    assert_eq!(credit.concepts, ["TRANSF DE ACME SA", "FACTURA 2025-118"]);
+
+    let card = &account.movements[2];
+    assert_eq!(card.amount, -6_435);
+    assert_eq!(
+        card.equivalence,
+        Some(Equivalence {
+            currency: "840".to_string(),
+            amount: 7_500
+        })
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let data = read_test_file("valid1.n43");
+    let norma43 = Norma43::from_read(data.as_bytes()).unwrap();
+
+    let mut written = Vec::new();
+    norma43.write_to(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), data);
+}
+
+#[test]
+fn test_ccc_check_digits() {
+    let account = Norma43Account {
+        bank_code: "2100".to_string(),
+        branch_code: "0418".to_string(),
+        number: "0200051332".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(account.ccc(), Some("21000418450200051332".to_string()));
+    let letters = Norma43Account {
+        number: "02000513X2".to_string(),
+        ..account
+    };
+    assert_eq!(letters.ccc(), None);
+}
+
+#[test]
+fn test_debit_credit_keys() {
+    // The key of the first movement turned into a debit
+    let data = patch(&read_test_file("valid1.n43"), 2, 28, "1");
+    let error = Norma43::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Debit count 3"));
+
+    let (norma43, warnings) =
+        Norma43::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(norma43.accounts[0].movements[0].amount, -250_000);
+    // Debit count and total, credit count and total, final balance
+    assert_eq!(warnings.len(), 5);
+    assert!(warnings.iter().all(|w| w.kind == WarningKind::SkippedField));
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 11);
+
+    let data = patch(&read_test_file("valid1.n43"), 2, 28, "3");
+    let error = Norma43::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Invalid debit/credit key '3'"));
+}
+
+#[test]
+fn test_record_count() {
+    let data = patch(&read_test_file("valid1.n43"), 12, 21, "000012");
+    let error = Norma43::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Record count 12"));
+
+    let data: String = read_test_file("valid1.n43")
+        .lines()
+        .filter(|line| !line.starts_with("88"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let (norma43, warnings) =
+        Norma43::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(norma43.accounts.len(), 1);
+    assert_eq!(warnings.len(), 1);
+    assert!(
+        warnings[0]
+            .error
+            .to_string()
+            .contains("Missing end of file record 88")
+    );
+}
+
+#[test]
+fn test_lenient_skips_invalid_movement() {
+    // An invalid operation date on the card payment
+    let data = patch(&read_test_file("valid1.n43"), 6, 11, "251315");
+    assert!(Norma43::from_read(data.as_bytes()).is_err());
+
+    let (norma43, warnings) =
+        Norma43::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    let movements = &norma43.accounts[0].movements;
+    assert_eq!(movements.len(), 3);
+    // Its concept and equivalence records are skipped with it
+    assert!(movements.iter().all(|m| m.equivalence.is_none()));
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 6);
+}
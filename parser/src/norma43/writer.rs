@@ -0,0 +1,117 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::fixed_width::{Record, encode};
+use crate::norma43::format::*;
+use crate::norma43::reader::NORMA43_RECORDS;
+
+/// The debe/haber key written before an amount: `1` for a debit, `2` for a credit.
+fn key(amount: i64) -> &'static str {
+    if amount < 0 { "1" } else { "2" }
+}
+
+impl Norma43Movement {
+    /// Writes record `22` and its `23` and `24` records; returns how many.
+    fn write(&self, out: &mut String) -> u64 {
+        Record::new(&NORMA43_RECORDS, "22")
+            .put(7, 4, &self.branch_code)
+            .put(11, 6, &self.operation_date)
+            .put(17, 6, &self.value_date)
+            .put(23, 2, &self.common_concept)
+            .put(25, 3, &self.own_concept)
+            .put(28, 1, key(self.amount))
+            .number(29, 14, self.amount.unsigned_abs())
+            .put(43, 10, &self.document_number)
+            .put(53, 12, &self.reference_1)
+            .put(65, 16, &self.reference_2)
+            .end(out);
+        let mut records = 1;
+        // At most five concept records of two texts each
+        for (i, pair) in self.concepts.chunks(2).take(5).enumerate() {
+            Record::new(&NORMA43_RECORDS, "23")
+                .number(3, 2, i + 1)
+                .put(5, 38, &pair[0])
+                .put(43, 38, pair.get(1).map_or("", String::as_str))
+                .end(out);
+            records += 1;
+        }
+        if let Some(equivalence) = &self.equivalence {
+            Record::new(&NORMA43_RECORDS, "24")
+                .put(3, 2, "01")
+                .put(5, 3, &equivalence.currency)
+                .number(8, 14, equivalence.amount)
+                .end(out);
+            records += 1;
+        }
+        records
+    }
+}
+
+impl Norma43Account {
+    /// Writes records `11` to `33`; returns how many.
+    fn write(&self, out: &mut String) -> u64 {
+        Record::new(&NORMA43_RECORDS, "11")
+            .put(3, 4, &self.bank_code)
+            .put(7, 4, &self.branch_code)
+            .put(11, 10, &self.number)
+            .put(21, 6, &self.start_date)
+            .put(27, 6, &self.end_date)
+            .put(33, 1, key(self.initial_balance))
+            .number(34, 14, self.initial_balance.unsigned_abs())
+            .put(48, 3, &self.currency)
+            .put(51, 1, &self.information_mode)
+            .put(52, 26, &self.name)
+            .end(out);
+        let mut records = 2;
+        let (mut debits, mut debit, mut credits, mut credit) = (0, 0, 0, 0);
+        for movement in &self.movements {
+            match movement.amount < 0 {
+                true => {
+                    debits += 1;
+                    debit -= movement.amount;
+                }
+                false => {
+                    credits += 1;
+                    credit += movement.amount;
+                }
+            }
+            records += movement.write(out);
+        }
+        Record::new(&NORMA43_RECORDS, "33")
+            .put(3, 4, &self.bank_code)
+            .put(7, 4, &self.branch_code)
+            .put(11, 10, &self.number)
+            .number(21, 5, debits)
+            .number(26, 14, debit)
+            .number(40, 5, credits)
+            .number(45, 14, credit)
+            .put(59, 1, key(self.final_balance))
+            .number(60, 14, self.final_balance.unsigned_abs())
+            .put(74, 3, &self.currency)
+            .end(out);
+        records
+    }
+}
+
+impl Norma43 {
+    pub(crate) fn to_records(&self) -> String {
+        let mut out = String::new();
+        let mut records = 0;
+        for account in &self.accounts {
+            records += account.write(&mut out);
+        }
+        Record::new(&NORMA43_RECORDS, "88")
+            .put(3, 18, &"9".repeat(18))
+            .number(21, 6, records)
+            .end(&mut out);
+        out
+    }
+}
+
+impl FinancialDataWrite for Norma43 {
+    /// Writes Latin-1, replacing characters outside it with `?`.
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), ParserError> {
+        writer.write_all(&encode(&self.to_records()))?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+/// An OFX response file with its bank and credit card statements.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Ofx {
+    /// `102`, `103`, `151` or `160` for the SGML versions 1.x, `200` and
+    /// later for the XML versions 2.x.
+    pub version: String,
+    pub signon: SignOn,
+    pub statements: Vec<OfxStatement>,
+}
+
+impl Ofx {
+    /// OFX 1.x: SGML, where elements holding a value have no end tag.
+    pub(crate) fn is_sgml(&self) -> bool {
+        self.version.starts_with('1')
+    }
+}
+
+/// The version of written OFX files, see
+/// [`FormatOptions::ofx_version`](crate::FormatOptions::ofx_version).
+/// Files of either version are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OfxVersion {
+    /// OFX 1.02 in SGML, as imported by older personal finance tools and sold as QFX.
+    V1,
+    /// OFX 2.2 in XML.
+    #[default]
+    V2,
+}
+
+impl OfxVersion {
+    /// The `VERSION` of the header.
+    pub(crate) fn header_version(&self) -> &'static str {
+        match self {
+            OfxVersion::V1 => "102",
+            OfxVersion::V2 => "220",
+        }
+    }
+}
+
+impl std::str::FromStr for OfxVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(OfxVersion::V1),
+            "2" => Ok(OfxVersion::V2),
+            _ => Err(format!("Unknown OFX version: {}. Use '1' or '2'", s)),
+        }
+    }
+}
+
+/// The `SONRS` sign-on response.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SignOn {
+    /// `DTSERVER`, an OFX date-time such as `20251020120000.000[-5:EST]`.
+    pub dt_server: String,
+    pub language: String,
+    /// `FI/ORG` and `FI/FID`.
+    pub fi_org: String,
+    pub fi_id: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) enum StatementKind {
+    /// `STMTTRNRS/STMTRS` with a `BANKACCTFROM` account.
+    #[default]
+    Bank,
+    /// `CCSTMTTRNRS/CCSTMTRS` with a `CCACCTFROM` account.
+    CreditCard,
+}
+
+/// A `STMTRS` or `CCSTMTRS` with the `TRNUID` of its transaction wrapper.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct OfxStatement {
+    pub kind: StatementKind,
+    pub trn_uid: String,
+    /// `CURDEF`, the default currency of the amounts.
+    pub currency: String,
+    pub account: OfxAccount,
+    /// `DTSTART` and `DTEND` of the `BANKTRANLIST`.
+    pub start: String,
+    pub end: String,
+    pub transactions: Vec<OfxTransaction>,
+    pub ledger_balance: Option<OfxBalance>,
+    pub available_balance: Option<OfxBalance>,
+}
+
+/// `BANKACCTFROM` or, with only an account number, `CCACCTFROM`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct OfxAccount {
+    pub bank_id: String,
+    pub branch_id: String,
+    pub acct_id: String,
+    /// `CHECKING`, `SAVINGS`, `MONEYMRKT`, `CREDITLINE` or `CD`.
+    pub acct_type: String,
+}
+
+/// `LEDGERBAL` or `AVAILBAL`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct OfxBalance {
+    /// `BALAMT`, a decimal with `.` and a leading `-` when negative.
+    pub amount: String,
+    /// `DTASOF`.
+    pub date: String,
+}
+
+/// A `STMTTRN`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct OfxTransaction {
+    /// `TRNTYPE`, e.g. `CREDIT`, `DEBIT`, `FEE` or `DIRECTDEBIT`.
+    pub trn_type: String,
+    pub posted: String,
+    /// `DTAVAIL`, when the funds are available; empty when not given.
+    pub available: String,
+    /// `TRNAMT`, negative for debits.
+    pub amount: String,
+    /// `FITID`, unique per account: tools use it to skip transactions
+    /// they have already imported.
+    pub fit_id: String,
+    pub check_num: String,
+    pub ref_num: String,
+    /// `NAME`, or `PAYEE/NAME`.
+    pub name: String,
+    pub memo: String,
+}
+
+/// Transaction types mapped to ISO 20022 bank transaction codes.
+///
+/// `CREDIT`, `DEBIT` and `OTHER` have no entry; CAMT.053 codes without an
+/// entry are written as `CREDIT` or `DEBIT`.
+pub(crate) const TRN_TYPES: [(&str, &str, &str, &str); 14] = [
+    ("INT", "ACMT", "MCOP", "INTR"),
+    ("DIV", "SECU", "CUST", "DVCA"),
+    ("FEE", "ACMT", "MDOP", "CHRG"),
+    ("SRVCHG", "ACMT", "MDOP", "COMM"),
+    ("DEP", "PMNT", "CNTR", "CDPT"),
+    ("ATM", "PMNT", "CCRD", "CWDL"),
+    ("POS", "PMNT", "CCRD", "POSD"),
+    ("XFER", "PMNT", "ICDT", "BOOK"),
+    ("CHECK", "PMNT", "ICHQ", "CCHQ"),
+    ("PAYMENT", "PMNT", "ICDT", "ESCT"),
+    ("CASH", "PMNT", "CNTR", "CWDL"),
+    ("DIRECTDEP", "PMNT", "RCDT", "ESCT"),
+    ("DIRECTDEBIT", "PMNT", "RDDT", "ESDD"),
+    ("REPEATPMT", "PMNT", "ICDT", "STDO"),
+];
@@ -0,0 +1,436 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+use crate::ofx::format::*;
+
+use chrono::NaiveDate;
+
+/// OFX 1.x files are usually Windows-1252 (`CHARSET:1252`); input that is
+/// not UTF-8 is read as Latin-1, which agrees with it on letters.
+fn decode(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    }
+}
+
+/// An element of the `<OFX>` tree: an aggregate with children, or an
+/// element with a value, which in SGML has no end tag.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    text: Option<String>,
+    children: Vec<Element>,
+    /// Byte offset and length of the start tag.
+    offset: usize,
+    len: usize,
+}
+
+impl Element {
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The value of child `name`, empty when there is none.
+    fn value(&self, name: &str) -> String {
+        self.child(name)
+            .and_then(|child| child.text.clone())
+            .unwrap_or_default()
+    }
+
+    fn required(&self, name: &str) -> Result<String, String> {
+        Some(self.value(name))
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| format!("<{}> without <{}>", self.name, name))
+    }
+
+    fn error(&self, data: &str, message: impl Into<String>) -> ParserError {
+        ParserError::Ofx(message.into()).at_text(
+            data,
+            self.offset,
+            self.len,
+            &SourceSpan::default(),
+            None,
+        )
+    }
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let decoded = match entity {
+            Some("amp") => Some('&'),
+            Some("lt") => Some('<'),
+            Some("gt") => Some('>'),
+            Some("quot") => Some('"'),
+            Some("apos") => Some('\''),
+            Some("nbsp") => Some('\u{a0}'),
+            Some(code) if code.starts_with("#x") => u32::from_str_radix(&code[2..], 16)
+                .ok()
+                .and_then(char::from_u32),
+            Some(code) if code.starts_with('#') => code[1..].parse().ok().and_then(char::from_u32),
+            _ => None,
+        };
+        match (decoded, entity) {
+            (Some(c), Some(entity)) => {
+                out.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            // A bare `&`, as SGML files from some banks have
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The version from the header: the `KEY:VALUE` lines of OFX 1.x or the
+/// `<?OFX ...?>` processing instruction of OFX 2.x. Returns it with the
+/// offset of the body.
+fn header(data: &str) -> Result<(String, usize), ParserError> {
+    let body = data
+        .find("<OFX>")
+        .ok_or_else(|| ParserError::Ofx("No <OFX> element".to_string()))?;
+    let head = &data[..body];
+    if let Some(at) = head.find("<?OFX") {
+        let instruction = &head[at..];
+        let version = instruction
+            .split_once("VERSION=\"")
+            .and_then(|(_, rest)| rest.split_once('"'))
+            .map(|(version, _)| version.to_string())
+            .unwrap_or_else(|| "200".to_string());
+        return Ok((version, body));
+    }
+    let fields: Vec<(&str, &str)> = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+    };
+    match (field("OFXHEADER"), field("DATA")) {
+        (Some("100"), Some("OFXSGML")) => Ok((field("VERSION").unwrap_or("102").to_string(), body)),
+        (None, None) if head.trim().is_empty() || head.trim_start().starts_with("<?xml") => {
+            Ok(("200".to_string(), body))
+        }
+        (header, data_type) => Err(ParserError::Ofx(format!(
+            "Unsupported OFX header {} with data {}",
+            header.unwrap_or("none"),
+            data_type.unwrap_or("none")
+        ))),
+    }
+}
+
+/// Builds the element tree of the body. Elements with a value may lack an
+/// end tag, as in SGML; an end tag closes every element opened after the
+/// one it names.
+fn elements(data: &str, body: usize) -> Result<Element, ParserError> {
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+    let mut at = body;
+    let located = |offset: usize, len: usize, message: String| {
+        ParserError::Ofx(message).at_text(data, offset, len, &SourceSpan::default(), None)
+    };
+
+    while let Some(start) = data[at..].find('<').map(|i| at + i) {
+        let text = data[at..start].trim();
+        if !text.is_empty() {
+            match stack.last_mut() {
+                Some(element) if element.children.is_empty() => element.text = Some(unescape(text)),
+                _ => {
+                    return Err(located(
+                        at,
+                        start - at,
+                        format!("Unexpected text '{}'", text),
+                    ));
+                }
+            }
+        }
+        let rest = &data[start..];
+        let skip = [("<!--", "-->"), ("<?", "?>"), ("<!", ">")]
+            .iter()
+            .find(|(open, _)| rest.starts_with(open));
+        if let Some((_, close)) = skip {
+            at = start
+                + rest
+                    .find(close)
+                    .ok_or_else(|| located(start, 2, "Unterminated markup".to_string()))?
+                + close.len();
+            continue;
+        }
+        let end = rest
+            .find('>')
+            .ok_or_else(|| located(start, rest.len(), "Unterminated tag".to_string()))?;
+        let tag = &rest[1..end];
+        at = start + end + 1;
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            if !stack.iter().any(|element| element.name == name) {
+                return Err(located(
+                    start,
+                    end + 1,
+                    format!("End tag </{}> without a start tag", name),
+                ));
+            }
+            while let Some(element) = stack.pop() {
+                let closed = element.name == name;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => root = Some(element),
+                }
+                if closed {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        // A new start tag ends an SGML element with a value
+        if stack.last().is_some_and(|element| element.text.is_some()) {
+            let element = stack.pop().unwrap();
+            stack.last_mut().unwrap().children.push(element);
+        }
+        if root.is_some() {
+            return Err(located(start, end + 1, "Content after </OFX>".to_string()));
+        }
+        let (name, empty) = match tag.strip_suffix('/') {
+            Some(name) => (name.trim(), true),
+            None => (tag.trim(), false),
+        };
+        let element = Element {
+            name: name.to_string(),
+            offset: start,
+            len: end + 1,
+            ..Default::default()
+        };
+        match (empty, stack.last_mut()) {
+            (true, Some(parent)) => parent.children.push(element),
+            _ => stack.push(element),
+        }
+    }
+
+    let text = data[at..].trim();
+    if let Some(element) = stack.last_mut().filter(|_| !text.is_empty()) {
+        element.text = Some(unescape(text));
+    }
+    match (root, stack.first()) {
+        (Some(root), None) => Ok(root),
+        (_, Some(unclosed)) => {
+            Err(unclosed.error(data, format!("<{}> is not closed", unclosed.name)))
+        }
+        (None, None) => Err(ParserError::Ofx("No <OFX> element".to_string())),
+    }
+}
+
+/// An OFX date or date-time, `YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]]`.
+pub(crate) fn ofx_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+fn date(element: &Element, name: &str) -> Result<String, String> {
+    let value = element.required(name)?;
+    ofx_date(&value)
+        .map(|_| value.clone())
+        .ok_or_else(|| format!("Invalid <{}> '{}'", name, value))
+}
+
+/// A decimal amount; a decimal comma, as some European banks write, becomes a point.
+fn amount(element: &Element, name: &str) -> Result<String, String> {
+    let value = element.required(name)?.replace(',', ".");
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(&value);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(format!("Invalid <{}> '{}'", name, value));
+    }
+    Ok(value.strip_prefix('+').map(str::to_string).unwrap_or(value))
+}
+
+fn balance(element: Option<&Element>) -> Result<Option<OfxBalance>, String> {
+    element
+        .map(|balance| {
+            Ok(OfxBalance {
+                amount: amount(balance, "BALAMT")?,
+                date: date(balance, "DTASOF")?,
+            })
+        })
+        .transpose()
+}
+
+fn transaction(element: &Element) -> Result<OfxTransaction, String> {
+    let name = match element.child("PAYEE") {
+        Some(payee) => payee.value("NAME"),
+        None => element.value("NAME"),
+    };
+    let available = element.value("DTAVAIL");
+    if !available.is_empty() && ofx_date(&available).is_none() {
+        return Err(format!("Invalid <DTAVAIL> '{}'", available));
+    }
+    Ok(OfxTransaction {
+        trn_type: element.required("TRNTYPE")?,
+        posted: date(element, "DTPOSTED")?,
+        available,
+        amount: amount(element, "TRNAMT")?,
+        fit_id: element.required("FITID")?,
+        check_num: element.value("CHECKNUM"),
+        ref_num: element.value("REFNUM"),
+        name,
+        memo: element.value("MEMO"),
+    })
+}
+
+fn statement(
+    data: &str,
+    wrapper: &Element,
+    kind: StatementKind,
+    recovery: &mut Recovery,
+) -> Result<Option<OfxStatement>, ParserError> {
+    let (response, account) = match kind {
+        StatementKind::Bank => ("STMTRS", "BANKACCTFROM"),
+        StatementKind::CreditCard => ("CCSTMTRS", "CCACCTFROM"),
+    };
+    let Some(element) = wrapper.child(response) else {
+        // A request the bank could not answer has only its status
+        let status = wrapper.child("STATUS");
+        let message = match status {
+            Some(status) => format!(
+                "<{}> without <{}>, status {} {}",
+                wrapper.name,
+                response,
+                status.value("CODE"),
+                status.value("MESSAGE")
+            ),
+            None => format!("<{}> without <{}>", wrapper.name, response),
+        };
+        recovery.recover(
+            WarningKind::SkippedStatement,
+            wrapper.error(data, message.trim_end()),
+        )?;
+        return Ok(None);
+    };
+
+    let account_element = element
+        .child(account)
+        .ok_or_else(|| element.error(data, format!("<{}> without <{}>", response, account)))?;
+    let account = OfxAccount {
+        bank_id: account_element.value("BANKID"),
+        branch_id: account_element.value("BRANCHID"),
+        acct_id: account_element
+            .required("ACCTID")
+            .map_err(|e| account_element.error(data, e))?,
+        acct_type: account_element.value("ACCTTYPE"),
+    };
+    let list = element.child("BANKTRANLIST");
+    let mut transactions = Vec::new();
+    for item in list.iter().flat_map(|list| list.children("STMTTRN")) {
+        match transaction(item) {
+            Ok(transaction) => transactions.push(transaction),
+            Err(e) => recovery.recover(WarningKind::SkippedEntry, item.error(data, e))?,
+        }
+    }
+    let balance = |name: &str| balance(element.child(name)).map_err(|e| element.error(data, e));
+    Ok(Some(OfxStatement {
+        kind,
+        trn_uid: wrapper.value("TRNUID"),
+        currency: element
+            .required("CURDEF")
+            .map_err(|e| element.error(data, e))?,
+        account,
+        start: list.map(|list| list.value("DTSTART")).unwrap_or_default(),
+        end: list.map(|list| list.value("DTEND")).unwrap_or_default(),
+        transactions,
+        ledger_balance: balance("LEDGERBAL")?,
+        available_balance: balance("AVAILBAL")?,
+    }))
+}
+
+impl Ofx {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let data = data.trim_start_matches('\u{feff}');
+        let (version, body) = header(data)?;
+        let root = elements(data, body)?;
+
+        let signon = root
+            .child("SIGNONMSGSRSV1")
+            .and_then(|messages| messages.child("SONRS"));
+        let signon = SignOn {
+            dt_server: signon.map(|s| s.value("DTSERVER")).unwrap_or_default(),
+            language: signon.map(|s| s.value("LANGUAGE")).unwrap_or_default(),
+            fi_org: signon
+                .and_then(|s| s.child("FI"))
+                .map(|fi| fi.value("ORG"))
+                .unwrap_or_default(),
+            fi_id: signon
+                .and_then(|s| s.child("FI"))
+                .map(|fi| fi.value("FID"))
+                .unwrap_or_default(),
+        };
+
+        let mut statements = Vec::new();
+        for (messages, wrapper, kind) in [
+            ("BANKMSGSRSV1", "STMTTRNRS", StatementKind::Bank),
+            (
+                "CREDITCARDMSGSRSV1",
+                "CCSTMTTRNRS",
+                StatementKind::CreditCard,
+            ),
+        ] {
+            for element in root.children(messages).flat_map(|m| m.children(wrapper)) {
+                if let Some(statement) = statement(data, element, kind, recovery)? {
+                    statements.push(statement);
+                }
+            }
+        }
+        Ok(Ofx {
+            version,
+            signon,
+            statements,
+        })
+    }
+}
+
+impl FinancialDataRead for Ofx {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(ofx, _)| ofx)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        mut reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::Ofx(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let ofx = Self::from_string_recovering(&data, &mut recovery)?;
+        Ok((ofx, recovery.into_warnings()))
+    }
+}
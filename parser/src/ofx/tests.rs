@@ -0,0 +1,142 @@
+use crate::Ofx;
+use crate::ParserError;
+use crate::errors::{ParseMode, WarningKind};
+use crate::ofx::format::StatementKind;
+use crate::test_utils::{read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let ofx = Ofx::from_read(File::open(test_data_path("valid1.ofx")).unwrap()).unwrap();
+
+    assert_eq!(ofx.version, "102");
+    assert!(ofx.is_sgml());
+    assert_eq!(ofx.signon.dt_server, "20251021083000.000[-5:EST]");
+    assert_eq!(ofx.signon.fi_org, "Globex Bank");
+    assert_eq!(ofx.statements.len(), 1);
+
+    let statement = &ofx.statements[0];
+    assert_eq!(statement.kind, StatementKind::Bank);
+    assert_eq!(statement.trn_uid, "1001");
+    assert_eq!(statement.currency, "USD");
+    assert_eq!(statement.account.bank_id, "121000248");
+    assert_eq!(statement.account.acct_id, "4455667788");
+    assert_eq!(statement.account.acct_type, "CHECKING");
+    assert_eq!(
+        (statement.start.as_str(), statement.end.as_str()),
+        ("20251001", "20251020")
+    );
+    assert_eq!(statement.ledger_balance.as_ref().unwrap().amount, "3874.55");
+    assert_eq!(
+        statement.available_balance.as_ref().unwrap().amount,
+        "3800.00"
+    );
+
+    let transactions = &statement.transactions;
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(transactions[0].trn_type, "DIRECTDEP");
+    assert_eq!(transactions[0].posted, "20251003120000");
+    assert_eq!(transactions[0].memo, "SALARY OCTOBER");
+    assert_eq!(transactions[1].amount, "-120.50");
+    assert_eq!(transactions[1].available, "20251009");
+    assert_eq!(transactions[1].check_num, "1042");
+    assert_eq!(transactions[1].name, "SMITH & SONS");
+    assert_eq!(transactions[2].fit_id, "202510200003");
+}
+
+#[test]
+fn test_xml_credit_card() {
+    let ofx = Ofx::from_read(read_test_file("valid2.ofx").as_bytes()).unwrap();
+
+    assert_eq!(ofx.version, "220");
+    assert!(!ofx.is_sgml());
+    let statement = &ofx.statements[0];
+    assert_eq!(statement.kind, StatementKind::CreditCard);
+    assert_eq!(statement.currency, "EUR");
+    assert_eq!(statement.account.acct_id, "4111111111111111");
+    assert_eq!(statement.available_balance, None);
+
+    let pos = &statement.transactions[0];
+    // The payee aggregate gives the name, a decimal comma becomes a point
+    assert_eq!(pos.name, "CAFÉ DU MONDE");
+    assert_eq!(pos.amount, "-45.90");
+    assert_eq!(statement.transactions[1].memo, "THANK YOU");
+}
+
+#[test]
+fn test_read_write() {
+    for name in ["valid1.ofx", "valid2.ofx"] {
+        let ofx = Ofx::from_read(read_test_file(name).as_bytes()).unwrap();
+
+        let mut written = Vec::new();
+        ofx.write_to(&mut written).unwrap();
+        let text = String::from_utf8(written).unwrap();
+        assert_eq!(ofx.is_sgml(), text.starts_with("OFXHEADER:100"), "{}", name);
+        assert_eq!(Ofx::from_read(text.as_bytes()).unwrap(), ofx, "{}", name);
+    }
+
+    // SGML leaves out the end tags of elements with a value
+    let ofx = Ofx::from_read(read_test_file("valid1.ofx").as_bytes()).unwrap();
+    let mut written = Vec::new();
+    ofx.write_to(&mut written).unwrap();
+    let text = String::from_utf8(written).unwrap();
+    assert!(text.contains("\n            <NAME>SMITH &amp; SONS\n"));
+    assert!(!text.contains("</NAME>"));
+}
+
+#[test]
+fn test_sgml_tolerance() {
+    let data = read_test_file("valid1.ofx")
+        // A bare ampersand and a numeric entity
+        .replace("SMITH &amp; SONS", "SMITH & SONS &#233;")
+        // End tags of values, which some banks write in SGML too
+        .replace("<CURDEF>USD", "<CURDEF>USD</CURDEF>");
+    let ofx = Ofx::from_read(data.as_bytes()).unwrap();
+    let statement = &ofx.statements[0];
+    assert_eq!(statement.currency, "USD");
+    assert_eq!(statement.transactions[1].name, "SMITH & SONS \u{e9}");
+
+    let data = read_test_file("valid1.ofx").replace("</STMTRS>", "</STMTTRN>");
+    let error = Ofx::from_read(data.as_bytes()).unwrap_err();
+    let ParserError::Located(diagnostic) = &error else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert!(
+        error
+            .to_string()
+            .contains("End tag </STMTTRN> without a start tag")
+    );
+    assert_eq!(diagnostic.span.line, 76);
+}
+
+#[test]
+fn test_lenient_skips_invalid_transaction() {
+    let data = read_test_file("valid1.ofx").replace("<TRNAMT>-120.50", "<TRNAMT>-12O.50");
+    let error = Ofx::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Invalid <TRNAMT> '-12O.50'"));
+
+    let (ofx, warnings) = Ofx::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(ofx.statements[0].transactions.len(), 2);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+    assert_eq!(warnings[0].error.diagnostic().unwrap().span.line, 51);
+}
+
+#[test]
+fn test_status_without_statement() {
+    let data = "<OFX><BANKMSGSRSV1><STMTTRNRS><TRNUID>7<STATUS><CODE>2000\
+                <SEVERITY>ERROR<MESSAGE>Account unavailable</STATUS></STMTTRNRS>\
+                </BANKMSGSRSV1></OFX>";
+    let error = Ofx::from_read(data.as_bytes()).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("<STMTTRNRS> without <STMTRS>, status 2000 Account unavailable")
+    );
+
+    let (ofx, warnings) = Ofx::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert!(ofx.statements.is_empty());
+    assert_eq!(warnings[0].kind, WarningKind::SkippedStatement);
+}
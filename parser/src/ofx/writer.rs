@@ -0,0 +1,186 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::ofx::format::*;
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Writes the `<OFX>` tree, indented by two spaces per level.
+struct Tags {
+    out: String,
+    sgml: bool,
+    depth: usize,
+}
+
+impl Tags {
+    fn open(&mut self, name: &str) -> &mut Self {
+        self.line(&format!("<{}>", name));
+        self.depth += 1;
+        self
+    }
+
+    fn close(&mut self, name: &str) -> &mut Self {
+        self.depth -= 1;
+        self.line(&format!("</{}>", name));
+        self
+    }
+
+    /// An element with a value; empty values are left out. SGML has no end tag for it.
+    fn value(&mut self, name: &str, value: &str) -> &mut Self {
+        if value.is_empty() {
+            return self;
+        }
+        let value = escape(value);
+        match self.sgml {
+            true => self.line(&format!("<{}>{}", name, value)),
+            false => self.line(&format!("<{0}>{1}</{0}>", name, value)),
+        }
+        self
+    }
+
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// `STATUS` with code `0`: every response this crate writes succeeded.
+    fn status(&mut self) -> &mut Self {
+        self.open("STATUS")
+            .value("CODE", "0")
+            .value("SEVERITY", "INFO")
+            .close("STATUS")
+    }
+
+    fn balance(&mut self, name: &str, balance: &Option<OfxBalance>) -> &mut Self {
+        if let Some(balance) = balance {
+            self.open(name)
+                .value("BALAMT", &balance.amount)
+                .value("DTASOF", &balance.date)
+                .close(name);
+        }
+        self
+    }
+}
+
+impl OfxStatement {
+    fn write(&self, tags: &mut Tags) {
+        let (wrapper, response, account) = match self.kind {
+            StatementKind::Bank => ("STMTTRNRS", "STMTRS", "BANKACCTFROM"),
+            StatementKind::CreditCard => ("CCSTMTTRNRS", "CCSTMTRS", "CCACCTFROM"),
+        };
+        tags.open(wrapper)
+            .value(
+                "TRNUID",
+                if self.trn_uid.is_empty() {
+                    "0"
+                } else {
+                    &self.trn_uid
+                },
+            )
+            .status()
+            .open(response)
+            .value("CURDEF", &self.currency)
+            .open(account);
+        if self.kind == StatementKind::Bank {
+            tags.value("BANKID", &self.account.bank_id)
+                .value("BRANCHID", &self.account.branch_id);
+        }
+        tags.value("ACCTID", &self.account.acct_id);
+        if self.kind == StatementKind::Bank {
+            tags.value("ACCTTYPE", &self.account.acct_type);
+        }
+        tags.close(account)
+            .open("BANKTRANLIST")
+            .value("DTSTART", &self.start)
+            .value("DTEND", &self.end);
+        for transaction in &self.transactions {
+            tags.open("STMTTRN")
+                .value("TRNTYPE", &transaction.trn_type)
+                .value("DTPOSTED", &transaction.posted)
+                .value("DTAVAIL", &transaction.available)
+                .value("TRNAMT", &transaction.amount)
+                .value("FITID", &transaction.fit_id)
+                .value("CHECKNUM", &transaction.check_num)
+                .value("REFNUM", &transaction.ref_num)
+                .value("NAME", &transaction.name)
+                .value("MEMO", &transaction.memo)
+                .close("STMTTRN");
+        }
+        tags.close("BANKTRANLIST")
+            .balance("LEDGERBAL", &self.ledger_balance)
+            .balance("AVAILBAL", &self.available_balance)
+            .close(response)
+            .close(wrapper);
+    }
+}
+
+impl Ofx {
+    /// The header lines of OFX 1.x, or the XML declaration and `<?OFX?>`
+    /// instruction of OFX 2.x.
+    fn header(&self) -> String {
+        if self.is_sgml() {
+            format!(
+                "OFXHEADER:100\nDATA:OFXSGML\nVERSION:{}\nSECURITY:NONE\nENCODING:UTF-8\n\
+                 CHARSET:NONE\nCOMPRESSION:NONE\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n\n",
+                self.version
+            )
+        } else {
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+                 <?OFX OFXHEADER=\"200\" VERSION=\"{}\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n",
+                self.version
+            )
+        }
+    }
+
+    pub(crate) fn to_text(&self) -> String {
+        let mut tags = Tags {
+            out: self.header(),
+            sgml: self.is_sgml(),
+            depth: 0,
+        };
+        tags.open("OFX")
+            .open("SIGNONMSGSRSV1")
+            .open("SONRS")
+            .status()
+            .value("DTSERVER", &self.signon.dt_server)
+            .value("LANGUAGE", &self.signon.language);
+        if !self.signon.fi_org.is_empty() || !self.signon.fi_id.is_empty() {
+            tags.open("FI")
+                .value("ORG", &self.signon.fi_org)
+                .value("FID", &self.signon.fi_id)
+                .close("FI");
+        }
+        tags.close("SONRS").close("SIGNONMSGSRSV1");
+
+        for (messages, kind) in [
+            ("BANKMSGSRSV1", StatementKind::Bank),
+            ("CREDITCARDMSGSRSV1", StatementKind::CreditCard),
+        ] {
+            let mut statements = self.statements.iter().filter(|s| s.kind == kind).peekable();
+            if statements.peek().is_none() {
+                continue;
+            }
+            tags.open(messages);
+            for statement in statements {
+                statement.write(&mut tags);
+            }
+            tags.close(messages);
+        }
+        tags.close("OFX");
+        tags.out
+    }
+}
+
+impl FinancialDataWrite for Ofx {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        Self::write_string(writer, &self.to_text())
+    }
+}
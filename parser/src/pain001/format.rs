@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+
+/// The version of written pain.001 messages, see
+/// [`FormatOptions::pain001_version`](crate::FormatOptions::pain001_version).
+/// Messages of either version are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Pain001Version {
+    /// `pain.001.001.03`, still the most widely accepted by SEPA banks.
+    V03,
+    /// `pain.001.001.09`.
+    #[default]
+    V09,
+}
+
+impl Pain001Version {
+    pub(crate) fn namespace(self) -> &'static str {
+        match self {
+            Pain001Version::V03 => "urn:iso:std:iso:20022:tech:xsd:pain.001.001.03",
+            Pain001Version::V09 => "urn:iso:std:iso:20022:tech:xsd:pain.001.001.09",
+        }
+    }
+
+    /// The version of a document in `namespace`; versions after .03 are
+    /// read like .09.
+    pub(crate) fn of_namespace(namespace: &str) -> Self {
+        if namespace.contains("pain.001.001.03") {
+            Pain001Version::V03
+        } else {
+            Pain001Version::V09
+        }
+    }
+}
+
+impl std::str::FromStr for Pain001Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "03" => Ok(Pain001Version::V03),
+            "09" => Ok(Pain001Version::V09),
+            _ => Err(format!("Unknown pain.001 version: {}. Use '03' or '09'", s)),
+        }
+    }
+}
+
+/// A pain.001 customer credit transfer initiation.
+///
+/// The model follows version .03; the writer renames the elements that
+/// differ in .09 (`BICFI`, `ReqdExctnDt/Dt`) and leaves out empty ones.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename = "Document")]
+pub(crate) struct Pain001 {
+    #[serde(rename = "@xmlns", default, skip_serializing_if = "Option::is_none")]
+    pub xmlns: Option<String>,
+    #[serde(rename = "CstmrCdtTrfInitn")]
+    pub cstmr_cdt_trf_initn: CustomerCreditTransferInitiation,
+    #[serde(skip)]
+    pub version: Pain001Version,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CustomerCreditTransferInitiation {
+    #[serde(rename = "GrpHdr")]
+    pub grp_hdr: Pain001GroupHeader,
+    #[serde(rename = "PmtInf", default)]
+    pub pmt_inf: Vec<PaymentInformation>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001GroupHeader {
+    #[serde(rename = "MsgId")]
+    pub msg_id: Option<String>,
+    #[serde(rename = "CreDtTm")]
+    pub cre_dt_tm: Option<String>,
+    #[serde(rename = "NbOfTxs")]
+    pub nb_of_txs: Option<String>,
+    /// Sum of all instructed amounts, regardless of currency.
+    #[serde(rename = "CtrlSum")]
+    pub ctrl_sum: Option<String>,
+    #[serde(rename = "InitgPty")]
+    pub initg_pty: Option<Pain001Party>,
+}
+
+/// The payments from one debtor account on one requested execution date.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct PaymentInformation {
+    #[serde(rename = "PmtInfId")]
+    pub pmt_inf_id: Option<String>,
+    /// `TRF` for a credit transfer.
+    #[serde(rename = "PmtMtd")]
+    pub pmt_mtd: Option<String>,
+    #[serde(rename = "NbOfTxs")]
+    pub nb_of_txs: Option<String>,
+    #[serde(rename = "CtrlSum")]
+    pub ctrl_sum: Option<String>,
+    #[serde(rename = "PmtTpInf")]
+    pub pmt_tp_inf: Option<PaymentTypeInformation>,
+    #[serde(rename = "ReqdExctnDt")]
+    pub reqd_exctn_dt: Option<RequestedExecutionDate>,
+    #[serde(rename = "Dbtr")]
+    pub dbtr: Option<Pain001Party>,
+    #[serde(rename = "DbtrAcct")]
+    pub dbtr_acct: Option<Pain001Account>,
+    #[serde(rename = "DbtrAgt")]
+    pub dbtr_agt: Option<Pain001Agent>,
+    /// `SLEV` for SEPA: each party pays the charges of its own bank.
+    #[serde(rename = "ChrgBr")]
+    pub chrg_br: Option<String>,
+    #[serde(rename = "CdtTrfTxInf", default)]
+    pub cdt_trf_tx_inf: Vec<CreditTransferTransaction>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct PaymentTypeInformation {
+    #[serde(rename = "SvcLvl")]
+    pub svc_lvl: Option<ServiceLevel>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct ServiceLevel {
+    /// `SEPA` for a SEPA credit transfer.
+    #[serde(rename = "Cd")]
+    pub cd: Option<String>,
+}
+
+/// A plain date in version .03, a `Dt` or `DtTm` element from .09 on.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct RequestedExecutionDate {
+    #[serde(rename = "$text", default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(rename = "Dt", default, skip_serializing_if = "Option::is_none")]
+    pub dt: Option<String>,
+    #[serde(rename = "DtTm", default, skip_serializing_if = "Option::is_none")]
+    pub dt_tm: Option<String>,
+}
+
+impl RequestedExecutionDate {
+    /// The date as `YYYY-MM-DD`, of whichever form the document used.
+    pub(crate) fn value(&self) -> Option<&str> {
+        let date = self
+            .date
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty());
+        date.or(self.dt.as_deref())
+            .or_else(|| self.dt_tm.as_deref()?.get(..10))
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct CreditTransferTransaction {
+    #[serde(rename = "PmtId")]
+    pub pmt_id: Option<PaymentIdentification>,
+    #[serde(rename = "Amt")]
+    pub amt: Option<AmountType>,
+    #[serde(rename = "CdtrAgt")]
+    pub cdtr_agt: Option<Pain001Agent>,
+    #[serde(rename = "Cdtr")]
+    pub cdtr: Option<Pain001Party>,
+    #[serde(rename = "CdtrAcct")]
+    pub cdtr_acct: Option<Pain001Account>,
+    #[serde(rename = "RmtInf")]
+    pub rmt_inf: Option<Pain001RemittanceInformation>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct PaymentIdentification {
+    #[serde(rename = "InstrId")]
+    pub instr_id: Option<String>,
+    #[serde(rename = "EndToEndId")]
+    pub end_to_end_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct AmountType {
+    #[serde(rename = "InstdAmt")]
+    pub instd_amt: Option<Pain001Amount>,
+}
+
+/// `ActiveOrHistoricCurrencyAndAmount`, e.g. `<InstdAmt Ccy="EUR">250.00</InstdAmt>`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001Amount {
+    #[serde(rename = "@Ccy")]
+    pub currency: Option<String>,
+    #[serde(rename = "$value")]
+    pub value: Option<String>,
+}
+
+/// `PartyIdentification`, of which SEPA only uses the name.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001Party {
+    #[serde(rename = "Nm")]
+    pub name: Option<String>,
+}
+
+/// `CashAccount`, identified by its IBAN or another id.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001Account {
+    #[serde(rename = "Id")]
+    pub id: Option<Pain001AccountId>,
+    #[serde(rename = "Ccy")]
+    pub ccy: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001AccountId {
+    #[serde(rename = "IBAN")]
+    pub iban: Option<String>,
+    #[serde(rename = "Othr")]
+    pub other: Option<Pain001OtherAccountId>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001OtherAccountId {
+    #[serde(rename = "Id")]
+    pub id: Option<String>,
+}
+
+/// `BranchAndFinancialInstitutionIdentification`, a bank identified by its BIC.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001Agent {
+    #[serde(rename = "FinInstnId")]
+    pub fin_instn_id: Option<Pain001FinancialInstitution>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001FinancialInstitution {
+    /// `BICFI` from version .09 on.
+    #[serde(rename = "BIC", alias = "BICFI")]
+    pub bic: Option<String>,
+}
+
+/// `RemittanceInformation`; SEPA allows a single unstructured line.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub(crate) struct Pain001RemittanceInformation {
+    #[serde(rename = "Ustrd", default)]
+    pub ustrd: Vec<String>,
+}
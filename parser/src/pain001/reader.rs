@@ -0,0 +1,15 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::pain001::format::{Pain001, Pain001Version};
+
+use quick_xml::de::from_str;
+
+impl FinancialDataRead for Pain001 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Pain001(e.to_string()))?;
+        let mut pain001: Pain001 =
+            from_str(&data).map_err(|e| ParserError::Pain001(e.to_string()))?;
+        pain001.version = Pain001Version::of_namespace(pain001.xmlns.as_deref().unwrap_or(""));
+        Ok(pain001)
+    }
+}
@@ -0,0 +1,49 @@
+use crate::pain001::format::{Pain001, Pain001Version};
+use crate::test_utils::read_test_file;
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+#[test]
+fn test_with_file() {
+    let pain001 = Pain001::from_read(read_test_file("valid1.pain001").as_bytes()).unwrap();
+    assert_eq!(pain001.version, Pain001Version::V09);
+
+    let initiation = &pain001.cstmr_cdt_trf_initn;
+    assert_eq!(initiation.grp_hdr.msg_id, Some("PAY2510210001".to_string()));
+    assert_eq!(initiation.grp_hdr.nb_of_txs, Some("3".to_string()));
+    assert_eq!(initiation.grp_hdr.ctrl_sum, Some("1425.50".to_string()));
+    assert_eq!(initiation.pmt_inf.len(), 2);
+
+    let block = &initiation.pmt_inf[0];
+    assert_eq!(
+        block.reqd_exctn_dt.as_ref().unwrap().value(),
+        Some("2025-10-22")
+    );
+    // Read from BICFI
+    let agent = block.dbtr_agt.as_ref().unwrap();
+    let institution = agent.fin_instn_id.as_ref().unwrap();
+    assert_eq!(institution.bic, Some("COBADEFFXXX".to_string()));
+    assert_eq!(block.cdt_trf_tx_inf.len(), 2);
+    let transaction = &block.cdt_trf_tx_inf[1];
+    assert!(transaction.cdtr_agt.is_none());
+    assert_eq!(
+        transaction.rmt_inf.as_ref().unwrap().ustrd,
+        ["Order 7781 & 7782"]
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let pain001 = Pain001::from_read(read_test_file("valid1.pain001").as_bytes()).unwrap();
+
+    let mut data = Vec::new();
+    pain001.write_to(&mut data).unwrap();
+    let mut result = Pain001::from_read(data.as_slice()).unwrap();
+    result.xmlns = pain001.xmlns.clone();
+    assert_eq!(result, pain001);
+}
+
+#[test]
+fn test_not_a_credit_transfer_initiation() {
+    let data = read_test_file("valid1.pain002");
+    assert!(Pain001::from_read(data.as_bytes()).is_err());
+}
@@ -0,0 +1,98 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::pain001::format::{Pain001, Pain001Version};
+
+use quick_xml::events::{BytesEnd, BytesStart, Event};
+use quick_xml::{Reader, Writer, se};
+
+impl Pain001 {
+    fn to_string(&self) -> Result<String, ParserError> {
+        let mut document = self.clone();
+        document.xmlns = Some(self.version.namespace().to_string());
+        for block in &mut document.cstmr_cdt_trf_initn.pmt_inf {
+            if let Some(date) = &mut block.reqd_exctn_dt {
+                let value = date.value().map(str::to_string);
+                date.date = None;
+                date.dt = None;
+                date.dt_tm = None;
+                match self.version {
+                    Pain001Version::V03 => date.date = value,
+                    Pain001Version::V09 => date.dt = value,
+                }
+            }
+        }
+        let xml = se::to_string(&document).map_err(|e| ParserError::Pain001(e.to_string()))?;
+        finish(&xml, self.version)
+    }
+}
+
+/// Leaves out the empty elements the serializer writes for missing values,
+/// which banks reject, and names the BIC `BICFI` in version .09.
+fn finish(xml: &str, version: Pain001Version) -> Result<String, ParserError> {
+    fn xml_error(e: impl ToString) -> ParserError {
+        ParserError::Pain001(e.to_string())
+    }
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    // Started elements, with whether they have been written yet
+    let mut open: Vec<(BytesStart<'static>, bool)> = Vec::new();
+
+    fn flush(
+        writer: &mut Writer<Vec<u8>>,
+        open: &mut [(BytesStart<'static>, bool)],
+    ) -> std::io::Result<()> {
+        for (start, written) in open.iter_mut().filter(|(_, written)| !*written) {
+            writer.write_event(Event::Start(start.borrow()))?;
+            *written = true;
+        }
+        Ok(())
+    }
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Eof => break,
+            Event::Start(start) => {
+                let start = match (version, start.name().as_ref()) {
+                    (Pain001Version::V09, b"BIC") => BytesStart::new("BICFI"),
+                    _ => start.into_owned(),
+                };
+                open.push((start, false));
+            }
+            Event::End(_) => {
+                let Some((start, written)) = open.pop() else {
+                    continue;
+                };
+                if written {
+                    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+                    writer
+                        .write_event(Event::End(BytesEnd::new(name)))
+                        .map_err(xml_error)?;
+                } else if start.attributes().next().is_some() {
+                    flush(&mut writer, &mut open).map_err(xml_error)?;
+                    writer.write_event(Event::Empty(start)).map_err(xml_error)?;
+                }
+            }
+            Event::Empty(empty) => {
+                if empty.attributes().next().is_some() {
+                    flush(&mut writer, &mut open).map_err(xml_error)?;
+                    writer.write_event(Event::Empty(empty)).map_err(xml_error)?;
+                }
+            }
+            Event::Text(text) if text.iter().all(u8::is_ascii_whitespace) => {}
+            other => {
+                flush(&mut writer, &mut open).map_err(xml_error)?;
+                writer.write_event(other).map_err(xml_error)?;
+            }
+        }
+    }
+
+    String::from_utf8(writer.into_inner()).map_err(xml_error)
+}
+
+impl FinancialDataWrite for Pain001 {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        let data = self.to_string()?;
+        Self::write_string(writer, &data)?;
+        Ok(())
+    }
+}
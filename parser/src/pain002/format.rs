@@ -0,0 +1,93 @@
+use crate::camt053::format::{Amount, GroupHeader};
+use serde::Deserialize;
+
+/// A pain.002 customer payment status report, the answer of the bank to a
+/// pain.001 credit transfer initiation.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(rename = "Document")]
+pub(crate) struct Pain002 {
+    #[serde(rename = "CstmrPmtStsRpt")]
+    pub cstmr_pmt_sts_rpt: CustomerPaymentStatusReport,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct CustomerPaymentStatusReport {
+    #[serde(rename = "GrpHdr")]
+    pub grp_hdr: GroupHeader,
+    #[serde(rename = "OrgnlGrpInfAndSts")]
+    pub orgnl_grp_inf_and_sts: OriginalGroupStatus,
+    #[serde(rename = "OrgnlPmtInfAndSts", default)]
+    pub orgnl_pmt_inf_and_sts: Vec<OriginalPaymentInformationStatus>,
+}
+
+/// The status of the original pain.001 message as a whole.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct OriginalGroupStatus {
+    #[serde(rename = "OrgnlMsgId")]
+    pub orgnl_msg_id: Option<String>,
+    #[serde(rename = "OrgnlMsgNmId")]
+    pub orgnl_msg_nm_id: Option<String>,
+    #[serde(rename = "OrgnlNbOfTxs")]
+    pub orgnl_nb_of_txs: Option<String>,
+    #[serde(rename = "GrpSts")]
+    pub grp_sts: Option<String>,
+    #[serde(rename = "StsRsnInf", default)]
+    pub sts_rsn_inf: Vec<StatusReasonInformation>,
+}
+
+/// The status of one payment information block and its transactions.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct OriginalPaymentInformationStatus {
+    #[serde(rename = "OrgnlPmtInfId")]
+    pub orgnl_pmt_inf_id: Option<String>,
+    #[serde(rename = "PmtInfSts")]
+    pub pmt_inf_sts: Option<String>,
+    #[serde(rename = "StsRsnInf", default)]
+    pub sts_rsn_inf: Vec<StatusReasonInformation>,
+    #[serde(rename = "TxInfAndSts", default)]
+    pub tx_inf_and_sts: Vec<TransactionStatus>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct TransactionStatus {
+    #[serde(rename = "OrgnlInstrId")]
+    pub orgnl_instr_id: Option<String>,
+    #[serde(rename = "OrgnlEndToEndId")]
+    pub orgnl_end_to_end_id: Option<String>,
+    #[serde(rename = "TxSts")]
+    pub tx_sts: Option<String>,
+    #[serde(rename = "StsRsnInf", default)]
+    pub sts_rsn_inf: Vec<StatusReasonInformation>,
+    #[serde(rename = "OrgnlTxRef")]
+    pub orgnl_tx_ref: Option<OriginalTransactionReference>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct StatusReasonInformation {
+    #[serde(rename = "Rsn")]
+    pub rsn: Option<StatusReason>,
+    #[serde(rename = "AddtlInf", default)]
+    pub addtl_inf: Vec<String>,
+}
+
+/// An external status reason code such as `AC04`, or a proprietary one.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct StatusReason {
+    #[serde(rename = "Cd")]
+    pub cd: Option<String>,
+    #[serde(rename = "Prtry")]
+    pub prtry: Option<String>,
+}
+
+/// The parts of the original transaction that the bank repeats.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct OriginalTransactionReference {
+    #[serde(rename = "Amt")]
+    pub amt: Option<AmountType>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub(crate) struct AmountType {
+    #[serde(rename = "InstdAmt")]
+    pub instd_amt: Option<Amount>,
+}
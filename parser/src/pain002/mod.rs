@@ -0,0 +1,5 @@
+pub(crate) mod format;
+pub(crate) mod reader;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,12 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::pain002::format::Pain002;
+
+use quick_xml::de::from_str;
+
+impl FinancialDataRead for Pain002 {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        let data = Self::read_to_string(reader).map_err(|e| ParserError::Pain002(e.to_string()))?;
+        from_str(&data).map_err(|e| ParserError::Pain002(e.to_string()))
+    }
+}
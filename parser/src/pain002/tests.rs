@@ -0,0 +1,61 @@
+use crate::pain002::format::Pain002;
+use crate::payment_status::PaymentStatusReport;
+use crate::test_utils::read_test_file;
+use crate::traits::FinancialDataRead;
+
+#[test]
+fn test_with_file() {
+    let pain002 = Pain002::from_read(read_test_file("valid1.pain002").as_bytes()).unwrap();
+
+    let report = &pain002.cstmr_pmt_sts_rpt;
+    assert_eq!(report.grp_hdr.msg_id, Some("STS2510220001".to_string()));
+    let group = &report.orgnl_grp_inf_and_sts;
+    assert_eq!(group.orgnl_msg_id, Some("PAY2510210001".to_string()));
+    assert_eq!(group.grp_sts, Some("PART".to_string()));
+    assert_eq!(report.orgnl_pmt_inf_and_sts.len(), 2);
+    let tx = &report.orgnl_pmt_inf_and_sts[0].tx_inf_and_sts[1];
+    assert_eq!(tx.orgnl_end_to_end_id, Some("E2E-0002".to_string()));
+    assert_eq!(tx.tx_sts, Some("RJCT".to_string()));
+    let reason = tx.sts_rsn_inf[0].rsn.as_ref().unwrap();
+    assert_eq!(reason.cd, Some("AC04".to_string()));
+}
+
+#[test]
+fn test_payment_statuses() {
+    let report =
+        PaymentStatusReport::from_read(read_test_file("valid1.pain002").as_bytes()).unwrap();
+
+    assert_eq!(report.original_message_id.as_deref(), Some("PAY2510210001"));
+    assert_eq!(
+        report.original_message_type.as_deref(),
+        Some("pain.001.001.09")
+    );
+    assert_eq!(report.payments.len(), 5);
+    let payment = &report.payments[1];
+    assert_eq!(
+        payment.original_message_id.as_deref(),
+        Some("PAY2510210001")
+    );
+    assert_eq!(payment.payment_information_id.as_deref(), Some("PMT-001"));
+    assert_eq!(payment.instruction_id.as_deref(), Some("INSTR-0002"));
+    assert_eq!(payment.amount.as_deref(), Some("50.00"));
+    assert!(payment.is_rejected());
+    assert_eq!(payment.reasons[0].code.as_deref(), Some("AC04"));
+    assert_eq!(
+        payment.reasons[0].additional_information.as_deref(),
+        Some("Account closed")
+    );
+    assert_eq!(report.payments[2].reasons[0].code.as_deref(), Some("LIMIT"));
+
+    // Without a status of its own a transaction has that of its block
+    let inherited = &report.payments[4];
+    assert_eq!(inherited.end_to_end_id.as_deref(), Some("E2E-0005"));
+    assert_eq!(inherited.status.as_deref(), Some("RJCT"));
+    assert_eq!(inherited.reasons[0].code.as_deref(), Some("AM04"));
+}
+
+#[test]
+fn test_not_a_status_report() {
+    let data = read_test_file("valid1.camt053");
+    assert!(Pain002::from_read(data.as_bytes()).is_err());
+}
@@ -0,0 +1,427 @@
+use crate::camt053::balance::{account_id, non_empty, transactions};
+use crate::camt053::format::*;
+use crate::diff::{EntrySummary, summarize};
+use crate::errors::ParserError;
+use crate::pain002::format::*;
+use crate::traits::FinancialDataRead;
+
+use serde::Serialize;
+use std::fmt;
+
+/// A pain.002 customer payment status report: the status of the original
+/// pain.001 message and of each of its payments.
+///
+/// Read with [`from_read`](FinancialDataRead::from_read) and linked to the
+/// entries of a statement by
+/// [`link_payment_statuses`](crate::converter::convert_streams::link_payment_statuses).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PaymentStatusReport {
+    /// `MsgId` of the status report.
+    pub message_id: Option<String>,
+    /// `CreDtTm` of the status report.
+    pub created: Option<String>,
+    /// `MsgId` of the pain.001 message the report answers.
+    pub original_message_id: Option<String>,
+    /// Message name of the original message, e.g. `pain.001.001.09`.
+    pub original_message_type: Option<String>,
+    /// Number of transactions of the original message.
+    pub number_of_transactions: Option<String>,
+    /// Status of the original message as a whole, e.g. `ACCP` or `PART`.
+    pub group_status: Option<String>,
+    /// Reasons given for the group status.
+    pub group_reasons: Vec<StatusReason>,
+    /// The payments with a status, in the order of the report.
+    pub payments: Vec<PaymentStatus>,
+}
+
+/// A status reason: an external code such as `AC04`, or a proprietary one,
+/// with the bank's explanation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StatusReason {
+    /// `Rsn/Cd`, or else `Rsn/Prtry`.
+    pub code: Option<String>,
+    /// `AddtlInf`, lines joined with spaces.
+    pub additional_information: Option<String>,
+}
+
+/// The status of one payment of the original message.
+///
+/// A transaction without a status of its own has the status of its payment
+/// information block, or else of the group, with the reasons given there.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PaymentStatus {
+    /// `MsgId` of the original message.
+    pub original_message_id: Option<String>,
+    /// `PmtInfId` of the original payment information block.
+    pub payment_information_id: Option<String>,
+    /// `InstrId` of the original transaction.
+    pub instruction_id: Option<String>,
+    /// `EndToEndId` of the original transaction.
+    pub end_to_end_id: Option<String>,
+    /// Status code, e.g. `ACSC`, `ACCP`, `PDNG` or `RJCT`.
+    pub status: Option<String>,
+    /// Reasons given for the status.
+    pub reasons: Vec<StatusReason>,
+    /// Instructed amount, as in the original message.
+    pub amount: Option<String>,
+    /// ISO 4217 currency code of the instructed amount.
+    pub currency: Option<String>,
+}
+
+impl PaymentStatus {
+    /// Returns `true` if the payment was rejected (`RJCT`) or cancelled (`CANC`).
+    pub fn is_rejected(&self) -> bool {
+        matches!(self.status.as_deref(), Some("RJCT" | "CANC"))
+    }
+}
+
+fn reasons(information: &[StatusReasonInformation]) -> Vec<StatusReason> {
+    information
+        .iter()
+        .map(|info| {
+            let reason = info.rsn.as_ref();
+            let lines: Vec<&str> = info
+                .addtl_inf
+                .iter()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .collect();
+            StatusReason {
+                code: reason
+                    .and_then(|r| non_empty(&r.cd))
+                    .or_else(|| reason.and_then(|r| non_empty(&r.prtry))),
+                additional_information: Some(lines.join(" ")).filter(|i| !i.is_empty()),
+            }
+        })
+        .collect()
+}
+
+impl From<&Pain002> for PaymentStatusReport {
+    fn from(pain002: &Pain002) -> Self {
+        let report = &pain002.cstmr_pmt_sts_rpt;
+        let group = &report.orgnl_grp_inf_and_sts;
+        let original_message_id = non_empty(&group.orgnl_msg_id);
+
+        let mut payments = Vec::new();
+        for block in &report.orgnl_pmt_inf_and_sts {
+            for tx in &block.tx_inf_and_sts {
+                // The first level that has a status, from the transaction up
+                let (status, level_reasons) = [
+                    (&tx.tx_sts, &tx.sts_rsn_inf),
+                    (&block.pmt_inf_sts, &block.sts_rsn_inf),
+                    (&group.grp_sts, &group.sts_rsn_inf),
+                ]
+                .into_iter()
+                .find_map(|(status, reasons)| non_empty(status).map(|s| (Some(s), reasons)))
+                .unwrap_or((None, &tx.sts_rsn_inf));
+                let status_reasons = if tx.sts_rsn_inf.is_empty() {
+                    reasons(level_reasons)
+                } else {
+                    reasons(&tx.sts_rsn_inf)
+                };
+                let amount = tx
+                    .orgnl_tx_ref
+                    .as_ref()
+                    .and_then(|r| r.amt.as_ref())
+                    .and_then(|a| a.instd_amt.as_ref());
+                payments.push(PaymentStatus {
+                    original_message_id: original_message_id.clone(),
+                    payment_information_id: non_empty(&block.orgnl_pmt_inf_id),
+                    instruction_id: non_empty(&tx.orgnl_instr_id),
+                    end_to_end_id: non_empty(&tx.orgnl_end_to_end_id),
+                    status,
+                    reasons: status_reasons,
+                    amount: amount.and_then(|a| non_empty(&a.value)),
+                    currency: amount.and_then(|a| non_empty(&a.currency)),
+                });
+            }
+        }
+
+        PaymentStatusReport {
+            message_id: non_empty(&report.grp_hdr.msg_id),
+            created: non_empty(&report.grp_hdr.cre_dt_tm),
+            original_message_id,
+            original_message_type: non_empty(&group.orgnl_msg_nm_id),
+            number_of_transactions: non_empty(&group.orgnl_nb_of_txs),
+            group_status: non_empty(&group.grp_sts),
+            group_reasons: reasons(&group.sts_rsn_inf),
+            payments,
+        }
+    }
+}
+
+impl FinancialDataRead for PaymentStatusReport {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Pain002::from_read(reader).map(|pain002| PaymentStatusReport::from(&pain002))
+    }
+}
+
+/// What the statement shows of a payment, given its status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// Rejected or cancelled, yet booked.
+    RejectedButBooked,
+    /// Not rejected, but not booked: accepted payments that never appeared,
+    /// or ones still pending.
+    NotBooked,
+    /// Not rejected and booked.
+    Booked,
+    /// Rejected or cancelled and, as expected, not booked.
+    Rejected,
+    /// Without an end-to-end id to look for in the statement.
+    Unlinked,
+}
+
+impl Outcome {
+    const ALL: [Outcome; 5] = [
+        Outcome::RejectedButBooked,
+        Outcome::NotBooked,
+        Outcome::Booked,
+        Outcome::Rejected,
+        Outcome::Unlinked,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            Outcome::RejectedButBooked => "Rejected but booked",
+            Outcome::NotBooked => "Not booked",
+            Outcome::Booked => "Booked",
+            Outcome::Rejected => "Rejected",
+            Outcome::Unlinked => "Without end-to-end id",
+        }
+    }
+}
+
+/// A statement entry that books a payment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Booking {
+    /// The statement `Id` or `:20:` reference.
+    pub statement_id: Option<String>,
+    /// The IBAN or other id of the account.
+    pub account: Option<String>,
+    /// The entry.
+    pub entry: EntrySummary,
+}
+
+/// A payment with the statement entries that book it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LinkedPayment {
+    /// The payment and its status.
+    pub payment: PaymentStatus,
+    /// What the statement shows of it.
+    pub outcome: Outcome,
+    /// The entries whose `TxDtls/Refs/EndToEndId` is the end-to-end id of the payment.
+    pub bookings: Vec<Booking>,
+}
+
+/// The payments of a status report linked to the entries of a statement
+/// document. Rendered as text by [`Display`](fmt::Display) and as JSON by
+/// [`to_json`](Self::to_json).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PaymentLinks {
+    /// The payments, in the order of the status report.
+    pub payments: Vec<LinkedPayment>,
+}
+
+impl PaymentLinks {
+    /// The payments with the given outcome.
+    pub fn with_outcome(&self, outcome: Outcome) -> impl Iterator<Item = &LinkedPayment> {
+        self.payments.iter().filter(move |p| p.outcome == outcome)
+    }
+
+    /// The links as a pretty-printed JSON document.
+    pub fn to_json(&self) -> String {
+        // Plain data of strings and enums always serializes
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Links every payment of `report` to the entries of `camt053` with its
+/// end-to-end id, in any statement and any transaction of a batch booking.
+/// `NOTPROVIDED` is not an end-to-end id.
+pub(crate) fn link(report: &PaymentStatusReport, camt053: &Camt053) -> PaymentLinks {
+    let payments = report
+        .payments
+        .iter()
+        .map(|payment| {
+            let end_to_end_id = payment
+                .end_to_end_id
+                .as_deref()
+                .filter(|id| *id != "NOTPROVIDED");
+            let bookings: Vec<Booking> = match end_to_end_id {
+                Some(id) => bookings(camt053, id),
+                None => Vec::new(),
+            };
+            let outcome = match (end_to_end_id, payment.is_rejected(), bookings.is_empty()) {
+                (None, _, _) => Outcome::Unlinked,
+                (Some(_), true, false) => Outcome::RejectedButBooked,
+                (Some(_), true, true) => Outcome::Rejected,
+                (Some(_), false, false) => Outcome::Booked,
+                (Some(_), false, true) => Outcome::NotBooked,
+            };
+            LinkedPayment {
+                payment: payment.clone(),
+                outcome,
+                bookings,
+            }
+        })
+        .collect();
+    PaymentLinks { payments }
+}
+
+fn bookings(camt053: &Camt053, end_to_end_id: &str) -> Vec<Booking> {
+    let mut result = Vec::new();
+    for stmt in &camt053.bk_to_cstmr_stmt.stmts {
+        for entry in &stmt.ntry {
+            let linked = transactions(entry)
+                .filter_map(|tx| tx.refs.as_ref())
+                .any(|refs| non_empty(&refs.end_to_end_id).as_deref() == Some(end_to_end_id));
+            if linked {
+                result.push(Booking {
+                    statement_id: non_empty(&stmt.id),
+                    account: account_id(stmt),
+                    entry: summarize(entry),
+                });
+            }
+        }
+    }
+    result
+}
+
+fn show(value: &Option<String>) -> &str {
+    value.as_deref().unwrap_or("-")
+}
+
+impl fmt::Display for StatusReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(show(&self.code))?;
+        if let Some(information) = &self.additional_information {
+            write!(f, " \"{}\"", information)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PaymentStatus {
+    /// One line: end-to-end id, amount, status and reasons.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(show(&self.end_to_end_id))?;
+        if let Some(amount) = &self.amount {
+            write!(f, " {} {}", amount, show(&self.currency))?;
+        }
+        write!(f, " {}", show(&self.status))?;
+        for reason in &self.reasons {
+            write!(f, " {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PaymentLinks {
+    /// A report with one section per outcome, the problems first; booked
+    /// payments are followed by their entries.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.payments.is_empty() {
+            return writeln!(f, "No payment statuses");
+        }
+        for outcome in Outcome::ALL {
+            let payments: Vec<&LinkedPayment> = self.with_outcome(outcome).collect();
+            if payments.is_empty() {
+                continue;
+            }
+            writeln!(f, "{} ({}):", outcome.title(), payments.len())?;
+            for linked in payments {
+                writeln!(f, "  {}", linked.payment)?;
+                for booking in &linked.bookings {
+                    writeln!(
+                        f,
+                        "      statement {} of account {}: {}",
+                        show(&booking.statement_id),
+                        show(&booking.account),
+                        booking.entry
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    fn test_file(name: &str) -> File {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+        let path = PathBuf::from(manifest_dir).join("test_data");
+        File::open(path.join(name)).unwrap()
+    }
+
+    /// The test statement with the fee booked as payment `E2E-0002` and the
+    /// credit as `E2E-0001`.
+    fn statement() -> Camt053 {
+        let mut camt053 = Camt053::from_read(test_file("valid1.camt053")).unwrap();
+        let stmt = &mut camt053.bk_to_cstmr_stmt.stmts[0];
+        for (entry, id) in stmt.ntry.iter_mut().zip(["E2E-0001", "E2E-0002"]) {
+            let refs = entry.ntry_dtls[0].tx_dtls[0].refs.get_or_insert_default();
+            refs.end_to_end_id = Some(id.to_string());
+        }
+        camt053
+    }
+
+    #[test]
+    fn test_link() {
+        let report = PaymentStatusReport::from_read(test_file("valid1.pain002")).unwrap();
+        let links = link(&report, &statement());
+
+        let outcomes: Vec<_> = links.payments.iter().map(|p| p.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [
+                Outcome::Booked,
+                Outcome::RejectedButBooked,
+                Outcome::Rejected,
+                Outcome::NotBooked,
+                Outcome::Rejected,
+            ]
+        );
+        let rejected: Vec<_> = links.with_outcome(Outcome::RejectedButBooked).collect();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(
+            rejected[0].bookings[0].statement_id.as_deref(),
+            Some("STMT001")
+        );
+        assert_eq!(
+            rejected[0].bookings[0].entry.amount.as_deref(),
+            Some("50.00")
+        );
+
+        let text = links.to_string();
+        assert!(text.starts_with(
+            "Rejected but booked (1):\n  E2E-0002 50.00 EUR RJCT AC04 \"Account closed\"\n      \
+             statement STMT001 of account DE89370400440532013000: 2023-10-04 D 50.00 EUR"
+        ));
+        assert!(text.contains("Not booked (1):\n  E2E-0004 ACCP\n"));
+        let json: serde_json::Value = serde_json::from_str(&links.to_json()).unwrap();
+        assert_eq!(json["payments"][1]["outcome"], "rejected_but_booked");
+    }
+
+    #[test]
+    fn test_unlinked_payment() {
+        let mut report = PaymentStatusReport::from_read(test_file("valid1.pain002")).unwrap();
+        report.payments.truncate(1);
+        report.payments[0].end_to_end_id = Some("NOTPROVIDED".to_string());
+
+        let links = link(&report, &statement());
+        assert_eq!(links.payments[0].outcome, Outcome::Unlinked);
+        assert!(links.payments[0].bookings.is_empty());
+        assert_eq!(
+            link(&PaymentStatusReport::default(), &statement()).to_string(),
+            "No payment statuses\n"
+        );
+    }
+}
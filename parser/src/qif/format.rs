@@ -0,0 +1,69 @@
+use chrono::NaiveDate;
+
+/// A QIF file: one `!Type:Bank` list of transactions per account.
+///
+/// QIF has no currency, balances or bank references; the reader takes the
+/// account name from an `!Account` block when the file has one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Qif {
+    /// How dates are written, and read when they are ambiguous.
+    pub date_format: QifDateFormat,
+    pub accounts: Vec<QifAccount>,
+}
+
+/// The order of day and month in the `D` records of QIF files, see
+/// [`FormatOptions::qif_date_format`](crate::FormatOptions::qif_date_format).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum QifDateFormat {
+    /// `MM/DD/YYYY`, as written by Quicken in the US.
+    #[default]
+    Us,
+    /// `DD/MM/YYYY`, as written by European versions of Quicken and Money.
+    Eu,
+}
+
+impl QifDateFormat {
+    pub(crate) fn pattern(&self) -> &'static str {
+        match self {
+            QifDateFormat::Us => "%m/%d/%Y",
+            QifDateFormat::Eu => "%d/%m/%Y",
+        }
+    }
+}
+
+impl std::str::FromStr for QifDateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "us" => Ok(QifDateFormat::Us),
+            "eu" => Ok(QifDateFormat::Eu),
+            _ => Err(format!("Unknown QIF date format: {}. Use 'us' or 'eu'", s)),
+        }
+    }
+}
+
+/// An account and its transactions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct QifAccount {
+    /// `N` of the `!Account` block, empty without one.
+    pub name: String,
+    /// The list type after `!Type:`, e.g. `Bank`, `CCard` or `Cash`.
+    pub list_type: String,
+    pub transactions: Vec<QifTransaction>,
+}
+
+/// A transaction record, ended by `^`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct QifTransaction {
+    /// `D`.
+    pub date: NaiveDate,
+    /// `T`, a decimal with `.` and a leading `-` for payments.
+    pub amount: String,
+    /// `P`.
+    pub payee: String,
+    /// `M`.
+    pub memo: String,
+    /// `N`, a check number or reference.
+    pub number: String,
+}
@@ -0,0 +1,301 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::errors::{ParseMode, ParseWarning, Recovery, SourceSpan, WarningKind};
+use crate::qif::format::*;
+
+use chrono::NaiveDate;
+
+/// Lists whose records have the layout of `!Type:Bank`.
+const TRANSACTION_LISTS: [&str; 5] = ["Bank", "CCard", "Cash", "Oth A", "Oth L"];
+
+/// Lists of categories, classes and memorized transactions, which hold no
+/// statement data and are passed over.
+const OTHER_LISTS: [&str; 4] = ["Cat", "Class", "Memorized", "Prices"];
+
+/// QIF files are usually in the Windows code page of the program that
+/// wrote them; input that is not UTF-8 is read as Latin-1.
+fn decode(bytes: Vec<u8>) -> String {
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    }
+}
+
+fn error(data: &str, offset: usize, len: usize, message: impl Into<String>) -> ParserError {
+    ParserError::Qif(message.into()).at_text(data, offset, len, &SourceSpan::default(), None)
+}
+
+/// A `D` value. Quicken writes `1/ 5/2025`, or `1/ 5'25` with an apostrophe
+/// before the year after 1999; other programs write `01/05/25`, use `-` or
+/// `.` between the parts or the ISO order. A date that is impossible in
+/// `format` is read in the other order.
+pub(crate) fn qif_date(value: &str, format: QifDateFormat) -> Option<NaiveDate> {
+    let parts: Vec<&str> = value.split(['/', '-', '.', '\'']).map(str::trim).collect();
+    let [first, second, third] = parts[..] else {
+        return None;
+    };
+    let number = |part: &str| part.parse::<u32>().ok();
+    if first.len() == 4 {
+        return NaiveDate::from_ymd_opt(first.parse().ok()?, number(second)?, number(third)?);
+    }
+    let year = number(third)? as i32;
+    let year = match (third.len(), value.contains('\'')) {
+        (4, _) => year,
+        (1 | 2, true) => 2000 + year,
+        (1 | 2, false) if year < 70 => 2000 + year,
+        (1 | 2, false) => 1900 + year,
+        _ => return None,
+    };
+    let (month, day) = match format {
+        QifDateFormat::Us => (number(first)?, number(second)?),
+        QifDateFormat::Eu => (number(second)?, number(first)?),
+    };
+    NaiveDate::from_ymd_opt(year, month, day).or_else(|| NaiveDate::from_ymd_opt(year, day, month))
+}
+
+/// A `T` value as a decimal with `.`. Thousands separators are dropped:
+/// of `,` and `.` the last one is the decimal separator, and a single `,`
+/// followed by one or two digits is a decimal comma.
+pub(crate) fn qif_amount(value: &str) -> Option<String> {
+    let value: String = value.chars().filter(|c| !c.is_whitespace()).collect();
+    let (sign, digits) = match value.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", value.strip_prefix('+').unwrap_or(&value)),
+    };
+    let decimal = match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(point), Some(comma)) => Some(point.max(comma)),
+        (Some(point), None) if digits.matches('.').count() == 1 => Some(point),
+        (None, Some(comma)) if digits.matches(',').count() == 1 && digits.len() - comma <= 3 => {
+            Some(comma)
+        }
+        _ => None,
+    };
+    let (whole, fraction) = match decimal {
+        Some(at) => (&digits[..at], &digits[at + 1..]),
+        None => (digits, ""),
+    };
+    let whole: String = whole.chars().filter(|c| !matches!(c, ',' | '.')).collect();
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let whole = if whole.is_empty() { "0" } else { &whole };
+    Some(match fraction.is_empty() {
+        true => format!("{}{}", sign, whole),
+        false => format!("{}{}.{}", sign, whole, fraction),
+    })
+}
+
+/// The lines of one record up to its `^`, with their byte offsets.
+#[derive(Default)]
+struct Record<'a> {
+    lines: Vec<(usize, &'a str)>,
+}
+
+impl<'a> Record<'a> {
+    fn field(&self, code: char) -> Option<(usize, &'a str)> {
+        self.lines
+            .iter()
+            .find(|(_, line)| line.starts_with(code))
+            .map(|(offset, line)| (*offset, line[code.len_utf8()..].trim()))
+    }
+
+    fn value(&self, code: char) -> String {
+        self.field(code)
+            .map(|(_, value)| value.to_string())
+            .unwrap_or_default()
+    }
+
+    fn transaction(
+        &self,
+        data: &str,
+        format: QifDateFormat,
+    ) -> Result<QifTransaction, ParserError> {
+        let (offset, first) = self.lines[0];
+        let (date_offset, date) = self
+            .field('D')
+            .ok_or_else(|| error(data, offset, first.len(), "Transaction without a D date"))?;
+        let date = qif_date(date, format).ok_or_else(|| {
+            error(
+                data,
+                date_offset,
+                date.len() + 1,
+                format!("Invalid date '{}'", date),
+            )
+        })?;
+        // `U` repeats `T` in newer Quicken exports and stands in for it in some others
+        let (amount_offset, amount) = self
+            .field('T')
+            .or_else(|| self.field('U'))
+            .ok_or_else(|| error(data, offset, first.len(), "Transaction without a T amount"))?;
+        let amount = qif_amount(amount).ok_or_else(|| {
+            error(
+                data,
+                amount_offset,
+                amount.len() + 1,
+                format!("Invalid amount '{}'", amount),
+            )
+        })?;
+        Ok(QifTransaction {
+            date,
+            amount,
+            payee: self.value('P'),
+            memo: self.value('M'),
+            number: self.value('N'),
+        })
+    }
+}
+
+/// What the lines being read belong to.
+enum Section {
+    /// Before the first header.
+    None,
+    /// An `!Account` block, whose `N` names the following list.
+    Account,
+    Transactions,
+    /// A list that is passed over.
+    Skipped,
+}
+
+impl Qif {
+    pub(crate) fn from_string_recovering(
+        data: &str,
+        date_format: QifDateFormat,
+        recovery: &mut Recovery,
+    ) -> Result<Self, ParserError> {
+        let data = data.trim_start_matches('\u{feff}');
+        let mut accounts: Vec<QifAccount> = Vec::new();
+        let mut account_name = String::new();
+        let mut section = Section::None;
+        let mut record = Record::default();
+        let mut offset = 0;
+
+        for line in data.split_inclusive('\n') {
+            let start = offset;
+            offset += line.len();
+            let line = line.trim_end();
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('!') {
+                let header = header.trim();
+                let (kind, name) = header.split_once(':').unwrap_or((header, ""));
+                section = match (kind.to_ascii_lowercase().as_str(), name.trim()) {
+                    ("account", _) => {
+                        account_name.clear();
+                        Section::Account
+                    }
+                    ("type", list)
+                        if TRANSACTION_LISTS
+                            .iter()
+                            .any(|t| t.eq_ignore_ascii_case(list)) =>
+                    {
+                        accounts.push(QifAccount {
+                            name: std::mem::take(&mut account_name),
+                            list_type: list.to_string(),
+                            transactions: Vec::new(),
+                        });
+                        Section::Transactions
+                    }
+                    ("type", list) if OTHER_LISTS.iter().any(|t| t.eq_ignore_ascii_case(list)) => {
+                        Section::Skipped
+                    }
+                    // `!Option:AutoSwitch` and `!Clear:AutoSwitch` around the account list
+                    ("option" | "clear", _) => Section::Skipped,
+                    _ => {
+                        recovery.recover(
+                            WarningKind::SkippedStatement,
+                            error(
+                                data,
+                                start,
+                                line.len(),
+                                format!("Unsupported list '!{}'", header),
+                            ),
+                        )?;
+                        Section::Skipped
+                    }
+                };
+                record = Record::default();
+                continue;
+            }
+
+            match section {
+                Section::None => {
+                    return Err(error(
+                        data,
+                        start,
+                        line.len(),
+                        "Record before a !Type header",
+                    ));
+                }
+                Section::Skipped => {}
+                Section::Account if line.starts_with('N') => {
+                    account_name = line[1..].trim().to_string();
+                }
+                Section::Account => {}
+                Section::Transactions if line.starts_with('^') => {
+                    if !record.lines.is_empty() {
+                        let account = accounts.last_mut().expect("a list was started");
+                        match record.transaction(data, date_format) {
+                            Ok(transaction) => account.transactions.push(transaction),
+                            Err(e) => recovery.recover(WarningKind::SkippedEntry, e)?,
+                        }
+                    }
+                    record = Record::default();
+                }
+                Section::Transactions => record.lines.push((start, line)),
+            }
+        }
+        // The last record may lack its `^`
+        if let (Section::Transactions, false) = (&section, record.lines.is_empty()) {
+            let account = accounts.last_mut().expect("a list was started");
+            match record.transaction(data, date_format) {
+                Ok(transaction) => account.transactions.push(transaction),
+                Err(e) => recovery.recover(WarningKind::SkippedEntry, e)?,
+            }
+        }
+
+        if accounts.is_empty() {
+            return Err(ParserError::Qif("No !Type:Bank list".to_string()));
+        }
+        Ok(Qif {
+            date_format,
+            accounts,
+        })
+    }
+
+    /// Reads a QIF file whose dates are in `date_format`.
+    pub(crate) fn from_read_with_format<R: std::io::Read>(
+        mut reader: R,
+        date_format: QifDateFormat,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| ParserError::Qif(e.to_string()))?;
+        let data = decode(bytes);
+        let mut recovery = Recovery::new(mode);
+        let qif = Self::from_string_recovering(&data, date_format, &mut recovery)?;
+        Ok((qif, recovery.into_warnings()))
+    }
+}
+
+/// Reads US dates; see [`Qif::from_read_with_format`] for European ones.
+impl FinancialDataRead for Qif {
+    fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError> {
+        Self::from_read_with_mode(reader, ParseMode::Strict).map(|(qif, _)| qif)
+    }
+
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        Self::from_read_with_format(reader, QifDateFormat::Us, mode)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::ParserError;
+use crate::Qif;
+use crate::errors::{ParseMode, WarningKind};
+use crate::qif::format::QifDateFormat;
+use crate::qif::reader::{qif_amount, qif_date};
+use crate::test_utils::{read_test_file, test_data_path};
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+
+use chrono::NaiveDate;
+use std::fs::File;
+
+#[test]
+fn test_with_file() {
+    let qif = Qif::from_read(File::open(test_data_path("valid1.qif")).unwrap()).unwrap();
+
+    assert_eq!(qif.date_format, QifDateFormat::Us);
+    assert_eq!(qif.accounts.len(), 1);
+    let account = &qif.accounts[0];
+    assert_eq!(account.name, "Checking 4455");
+    assert_eq!(account.list_type, "Bank");
+
+    let transactions = &account.transactions;
+    assert_eq!(transactions.len(), 3);
+    assert_eq!(
+        transactions[0].date,
+        NaiveDate::from_ymd_opt(2025, 10, 3).unwrap()
+    );
+    assert_eq!(transactions[0].amount, "2500.00");
+    assert_eq!(transactions[0].number, "DEP");
+    assert_eq!(transactions[0].payee, "ACME PAYROLL");
+    assert_eq!(transactions[0].memo, "SALARY OCTOBER");
+    assert_eq!(transactions[1].amount, "-120.50");
+    assert_eq!(transactions[1].payee, "SMITH & SONS");
+    assert_eq!(transactions[1].memo, "");
+    assert_eq!(
+        transactions[2].date,
+        NaiveDate::from_ymd_opt(2025, 10, 20).unwrap()
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let qif = Qif::from_read(read_test_file("valid1.qif").as_bytes()).unwrap();
+
+    let mut written = Vec::new();
+    qif.write_to(&mut written).unwrap();
+    let text = String::from_utf8(written).unwrap();
+    assert_eq!(
+        text.lines().take(7).collect::<Vec<_>>(),
+        [
+            "!Type:Bank",
+            "D10/03/2025",
+            "T2500.00",
+            "PACME PAYROLL",
+            "MSALARY OCTOBER",
+            "NDEP",
+            "^"
+        ]
+    );
+
+    // A single list is written without its account name
+    let mut read = Qif::from_read(text.as_bytes()).unwrap();
+    assert_eq!(read.accounts[0].name, "");
+    read.accounts[0].name = qif.accounts[0].name.clone();
+    assert_eq!(read, qif);
+}
+
+#[test]
+fn test_dates() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+    assert_eq!(qif_date("1/ 5'25", QifDateFormat::Us), date(2025, 1, 5));
+    assert_eq!(qif_date("1/ 5/98", QifDateFormat::Us), date(1998, 1, 5));
+    assert_eq!(qif_date("01/05/2025", QifDateFormat::Eu), date(2025, 5, 1));
+    assert_eq!(qif_date("05.01.25", QifDateFormat::Eu), date(2025, 1, 5));
+    assert_eq!(qif_date("2025-01-05", QifDateFormat::Eu), date(2025, 1, 5));
+    // Impossible in the configured order, read in the other one
+    assert_eq!(
+        qif_date("20/10/2025", QifDateFormat::Us),
+        date(2025, 10, 20)
+    );
+    assert_eq!(qif_date("10/20", QifDateFormat::Us), None);
+    assert_eq!(qif_date("13/13/2025", QifDateFormat::Us), None);
+}
+
+#[test]
+fn test_amounts() {
+    assert_eq!(qif_amount("1,234.56").as_deref(), Some("1234.56"));
+    assert_eq!(qif_amount("-1.234,56").as_deref(), Some("-1234.56"));
+    assert_eq!(qif_amount("-4,95").as_deref(), Some("-4.95"));
+    assert_eq!(qif_amount("1,234").as_deref(), Some("1234"));
+    assert_eq!(qif_amount("+.5").as_deref(), Some("0.5"));
+    assert_eq!(qif_amount("12O.50"), None);
+    assert_eq!(qif_amount("-"), None);
+}
+
+#[test]
+fn test_lenient_skips_invalid_transaction() {
+    let data = read_test_file("valid1.qif").replace("D10/8/2025", "D10/32/2025");
+    let error = Qif::from_read(data.as_bytes()).unwrap_err();
+    let ParserError::Located(diagnostic) = &error else {
+        panic!("unexpected error {:?}", error);
+    };
+    assert!(error.to_string().contains("Invalid date '10/32/2025'"));
+    assert_eq!(diagnostic.span.line, 16);
+
+    let (qif, warnings) = Qif::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(qif.accounts[0].transactions.len(), 2);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedEntry);
+}
+
+#[test]
+fn test_other_lists() {
+    // Categories are passed over, investments are not supported
+    let data = format!(
+        "!Type:Cat\nNSalary\nI\n^\n{}!Type:Invst\nD10/21/2025\nNBuy\nYACME\nT-100.00\n^\n",
+        read_test_file("valid1.qif")
+    );
+    let error = Qif::from_read(data.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("Unsupported list '!Type:Invst'"));
+
+    let (qif, warnings) = Qif::from_read_with_mode(data.as_bytes(), ParseMode::Lenient).unwrap();
+    assert_eq!(qif.accounts.len(), 1);
+    assert_eq!(qif.accounts[0].transactions.len(), 3);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, WarningKind::SkippedStatement);
+}
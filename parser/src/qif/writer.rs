@@ -0,0 +1,57 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::qif::format::*;
+
+/// Values are single lines; line breaks in a payee or memo would start new records.
+fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl QifTransaction {
+    fn write(&self, out: &mut String, date_format: QifDateFormat) {
+        out.push_str(&format!("D{}\n", self.date.format(date_format.pattern())));
+        out.push_str(&format!("T{}\n", self.amount));
+        for (code, value) in [('P', &self.payee), ('M', &self.memo), ('N', &self.number)] {
+            let value = single_line(value);
+            if !value.is_empty() {
+                out.push_str(&format!("{}{}\n", code, value));
+            }
+        }
+        out.push_str("^\n");
+    }
+}
+
+impl Qif {
+    /// The lists of all accounts. With more than one account each list
+    /// follows an `!Account` block naming it, so that importers file the
+    /// transactions under the right account; a single list is imported
+    /// into the account chosen by the user.
+    pub(crate) fn to_text(&self) -> String {
+        let mut out = String::new();
+        let named = self.accounts.len() > 1;
+        for account in &self.accounts {
+            let list_type = match account.list_type.as_str() {
+                "" => "Bank",
+                list_type => list_type,
+            };
+            if named {
+                out.push_str(&format!(
+                    "!Account\nN{}\nT{}\n^\n",
+                    single_line(&account.name),
+                    list_type
+                ));
+            }
+            out.push_str(&format!("!Type:{}\n", list_type));
+            for transaction in &account.transactions {
+                transaction.write(&mut out, self.date_format);
+            }
+        }
+        out
+    }
+}
+
+impl FinancialDataWrite for Qif {
+    fn write_to<W: std::io::Write>(&self, writer: W) -> Result<(), ParserError> {
+        Self::write_string(writer, &self.to_text())
+    }
+}
@@ -0,0 +1,352 @@
+use crate::ParserError;
+use crate::SupportedFormats;
+use crate::converter::convert_streams::builtin_converter;
+use crate::converter::graph::Converter;
+use crate::detect::detect_builtin_format;
+
+use std::sync::{Arc, RwLock};
+
+/// A statement format known to the crate: how it is named, recognised,
+/// read and written.
+///
+/// Formats are read and written through [`Converter`]s. The reader converts
+/// data in this format into one of the other formats, usually `"camt053"`,
+/// the canonical model, and the writer converts the other way; conversions
+/// to and from every other format are then routed through them. Either may
+/// be left out for formats that are only read or only written.
+///
+/// ```
+/// use parser::converter::graph::{ConversionContext, Converter};
+/// use parser::{Format, ParserError, SupportedFormats, register_format};
+/// use std::io::{Read, Write};
+///
+/// struct MyBank;
+///
+/// impl Format for MyBank {
+///     fn name(&self) -> &'static str {
+///         "mybank"
+///     }
+///
+///     fn detect(&self, head: &[u8]) -> Option<f32> {
+///         head.starts_with(b"MYBANK").then_some(1.0)
+///     }
+///
+///     fn reader(&self) -> Option<Box<dyn Converter>> {
+///         Some(Box::new(MyBankReader))
+///     }
+/// }
+///
+/// struct MyBankReader;
+///
+/// impl Converter for MyBankReader {
+///     fn source(&self) -> &str {
+///         "mybank"
+///     }
+///
+///     fn target(&self) -> &str {
+///         "csv"
+///     }
+///
+///     fn convert(
+///         &self,
+///         mut input: Box<dyn Read>,
+///         mut output: Box<dyn Write>,
+///         _context: &mut ConversionContext,
+///     ) -> Result<(), ParserError> {
+///         let mut data = String::new();
+///         input.read_to_string(&mut data)?;
+///         writeln!(output, "amount,credit_debit")?;
+///         for line in data.lines().skip(1) {
+///             writeln!(output, "{},CRDT", line)?;
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// register_format(MyBank).unwrap();
+/// assert_eq!(
+///     "mybank".parse::<SupportedFormats>(),
+///     Ok(SupportedFormats::Other("mybank"))
+/// );
+/// ```
+pub trait Format: Send + Sync {
+    /// Lowercase name of the format, e.g. `"mt940"`.
+    fn name(&self) -> &'static str;
+
+    /// Other names the format is accepted under.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Confidence from `0.0` to `1.0` that data starting with `head` is in
+    /// this format, or `None` if it is not.
+    ///
+    /// See [`Detection::confidence`](crate::Detection::confidence) for the
+    /// meaning of the values.
+    fn detect(&self, _head: &[u8]) -> Option<f32> {
+        None
+    }
+
+    /// Converter from this format into another one.
+    fn reader(&self) -> Option<Box<dyn Converter>> {
+        None
+    }
+
+    /// Converter from another format into this one.
+    fn writer(&self) -> Option<Box<dyn Converter>> {
+        None
+    }
+}
+
+/// One of the formats this crate implements.
+struct BuiltinFormat {
+    format: SupportedFormats,
+    aliases: &'static [&'static str],
+}
+
+impl BuiltinFormat {
+    /// The format this one is read into and written from: CAMT.053, or
+    /// CAMT.054 for the MT900 / MT910 confirmations, which have no balances.
+    fn canonical_format(&self) -> SupportedFormats {
+        match self.format {
+            SupportedFormats::Mt900 => SupportedFormats::Camt054,
+            _ => SupportedFormats::Camt053,
+        }
+    }
+}
+
+impl Format for BuiltinFormat {
+    fn name(&self) -> &'static str {
+        self.format.name()
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn detect(&self, head: &[u8]) -> Option<f32> {
+        detect_builtin_format(head)
+            .filter(|detection| detection.format.supported_format() == Some(self.format))
+            .map(|detection| detection.confidence)
+    }
+
+    fn reader(&self) -> Option<Box<dyn Converter>> {
+        builtin_converter(self.name(), self.canonical_format().name())
+    }
+
+    fn writer(&self) -> Option<Box<dyn Converter>> {
+        builtin_converter(self.canonical_format().name(), self.name())
+    }
+}
+
+const BUILTIN_FORMATS: [BuiltinFormat; 18] = [
+    BuiltinFormat {
+        format: SupportedFormats::Mt940,
+        aliases: &["swift"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Camt053,
+        aliases: &["camt.053", "camt"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Xml,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Csv,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Json,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Ndjson,
+        aliases: &["jsonl"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Bai2,
+        aliases: &["bai"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Coda,
+        aliases: &["cod"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Ofx,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Qif,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Cfonb,
+        aliases: &["cfonb120"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Norma43,
+        aliases: &["n43", "aeb43", "csb43"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::ClientBank,
+        aliases: &["1cclientbankexchange", "clientbank", "kl_to_1c"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Mt950,
+        aliases: &[],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Mt900,
+        aliases: &["mt910"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Camt054,
+        aliases: &["camt.054"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Pain001,
+        aliases: &["pain.001"],
+    },
+    BuiltinFormat {
+        format: SupportedFormats::Xlsx,
+        aliases: &["excel"],
+    },
+];
+
+/// Formats added at runtime with [`register_format`].
+static REGISTERED: RwLock<Vec<Arc<dyn Format>>> = RwLock::new(Vec::new());
+
+/// The formats added with [`register_format`].
+pub(crate) fn registered_formats() -> Vec<Arc<dyn Format>> {
+    match REGISTERED.read() {
+        Ok(formats) => formats.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+fn names(format: &dyn Format) -> impl Iterator<Item = &'static str> {
+    std::iter::once(format.name()).chain(format.aliases().iter().copied())
+}
+
+/// Adds a format for the rest of the program.
+///
+/// The format can then be named in [`SupportedFormats`]'s `FromStr`, is
+/// recognised by [`detect_format`](crate::detect_format) and is converted
+/// to and from every other format by
+/// [`convert_streams`](crate::converter::convert_streams::convert_streams).
+/// A format registered under the name of an earlier one replaces it.
+///
+/// # Errors
+///
+/// Returns [`ParserError::GeneralError`] if the name or one of the aliases
+/// is that of a built-in format.
+pub fn register_format(format: impl Format + 'static) -> Result<(), ParserError> {
+    for name in names(&format) {
+        if BUILTIN_FORMATS
+            .iter()
+            .any(|builtin| names(builtin).any(|n| n.eq_ignore_ascii_case(name)))
+        {
+            return Err(ParserError::GeneralError(format!(
+                "Format name '{}' is used by a built-in format",
+                name
+            )));
+        }
+    }
+
+    let mut formats = REGISTERED
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    formats.retain(|f| !f.name().eq_ignore_ascii_case(format.name()));
+    formats.push(Arc::new(format));
+    Ok(())
+}
+
+/// All formats: the built-in ones followed by the registered ones.
+pub fn formats() -> Vec<Arc<dyn Format>> {
+    let builtin = BUILTIN_FORMATS
+        .into_iter()
+        .map(|format| Arc::new(format) as Arc<dyn Format>);
+    builtin.chain(registered_formats()).collect()
+}
+
+/// Finds a format by its name or one of its aliases, ignoring case.
+pub fn find_format(name: &str) -> Option<Arc<dyn Format>> {
+    formats()
+        .into_iter()
+        .find(|format| names(format.as_ref()).any(|n| n.eq_ignore_ascii_case(name)))
+}
+
+/// Readers and writers of the formats added with [`register_format`].
+pub(crate) fn registered_converters() -> Vec<Box<dyn Converter>> {
+    registered_formats()
+        .iter()
+        .flat_map(|format| [format.reader(), format.writer()])
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestFormat(&'static str, &'static [&'static str]);
+
+    impl Format for TestFormat {
+        fn name(&self) -> &'static str {
+            self.0
+        }
+
+        fn aliases(&self) -> &'static [&'static str] {
+            self.1
+        }
+
+        fn detect(&self, head: &[u8]) -> Option<f32> {
+            head.starts_with(b"TESTFORMAT").then_some(0.95)
+        }
+    }
+
+    #[test]
+    fn test_builtin_formats() {
+        let names: Vec<_> = formats().iter().map(|f| f.name()).collect();
+        assert_eq!(
+            &names[..6],
+            &["mt940", "camt053", "xml", "csv", "json", "ndjson"]
+        );
+        assert_eq!(find_format("CAMT.053").unwrap().name(), "camt053");
+        assert!(find_format("mt940").unwrap().reader().is_some());
+        assert!(find_format("camt053").unwrap().reader().is_none());
+        let mt910 = find_format("MT910").unwrap();
+        assert_eq!(mt910.name(), "mt900");
+        assert_eq!(mt910.reader().unwrap().target(), "camt054");
+        assert_eq!(
+            find_format("mt940")
+                .unwrap()
+                .detect(b":20:REF\n:25:ACC\n:60F:C"),
+            Some(0.9)
+        );
+    }
+
+    #[test]
+    fn test_register_format() {
+        assert!(register_format(TestFormat("swift", &[])).is_err());
+        assert!(register_format(TestFormat("testformat", &["Camt"])).is_err());
+
+        register_format(TestFormat("testformat", &["tf"])).unwrap();
+        assert_eq!(find_format("TF").unwrap().name(), "testformat");
+        assert_eq!(
+            "tf".parse::<SupportedFormats>(),
+            Ok(SupportedFormats::Other("testformat"))
+        );
+
+        let detection = crate::detect_format(b"TESTFORMAT 1\n").unwrap();
+        assert_eq!(
+            detection.format,
+            crate::DetectedFormat::Registered("testformat")
+        );
+        assert_eq!(
+            detection.format.supported_format(),
+            Some(SupportedFormats::Other("testformat"))
+        );
+    }
+}
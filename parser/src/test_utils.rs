@@ -0,0 +1,26 @@
+//! Helpers shared by the tests of the format modules.
+
+use std::env;
+use std::path::PathBuf;
+
+/// The path of a file in the `test_data` directory.
+pub(crate) fn test_data_path(name: &str) -> PathBuf {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    PathBuf::from(manifest_dir).join("test_data").join(name)
+}
+
+pub(crate) fn read_test_file(name: &str) -> String {
+    std::fs::read_to_string(test_data_path(name)).unwrap()
+}
+
+pub(crate) fn read_test_bytes(name: &str) -> Vec<u8> {
+    std::fs::read(test_data_path(name)).unwrap()
+}
+
+/// Replaces the characters of line `line` (1-based) at 1-based `position`
+/// of a fixed-width file.
+pub(crate) fn patch(data: &str, line: usize, position: usize, value: &str) -> String {
+    let mut lines: Vec<String> = data.lines().map(str::to_string).collect();
+    lines[line - 1].replace_range(position - 1..position - 1 + value.len(), value);
+    lines.join("\n") + "\n"
+}
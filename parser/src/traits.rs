@@ -1,4 +1,4 @@
-use crate::errors::ParserError;
+use crate::errors::{ParseMode, ParseWarning, ParserError};
 use std::io::{Read, Write};
 
 /// Trait for reading financial data objects from input streams.
@@ -24,6 +24,24 @@ pub trait FinancialDataRead: Sized {
     /// Returns a [`ParserError`] if the input data cannot be parsed successfully.
     fn from_read<R: std::io::Read>(reader: R) -> Result<Self, ParserError>;
 
+    /// Creates a financial data object, handling recoverable problems according to `mode`.
+    ///
+    /// In [`ParseMode::Lenient`] parts of the input that cannot be read, such
+    /// as a malformed entry, are left out and returned as warnings. Formats
+    /// without recoverable problems use the default implementation, which
+    /// behaves like [`from_read`](Self::from_read).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParserError`] if the input cannot be parsed, or in
+    /// [`ParseMode::Strict`] on the first problem.
+    fn from_read_with_mode<R: std::io::Read>(
+        reader: R,
+        _mode: ParseMode,
+    ) -> Result<(Self, Vec<ParseWarning>), ParserError> {
+        Self::from_read(reader).map(|data| (data, Vec::new()))
+    }
+
     /// Reads the entire input stream into a string.
     ///
     /// This helper method provides a convenient way to read data before parsing.
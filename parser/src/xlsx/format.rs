@@ -0,0 +1,173 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+
+/// An XLSX workbook, as the values of the cells of its sheets.
+///
+/// Only values and number formats are modelled; the writer uses one fixed
+/// font and no column widths, and the reader ignores both.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Xlsx {
+    pub sheets: Vec<Sheet>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Sheet {
+    /// Tab name, unique in the workbook and at most 31 characters.
+    pub name: String,
+    /// Rows from the first one on; rows and cells missing in the file are
+    /// empty, and trailing empty cells are left out.
+    pub rows: Vec<Vec<Cell>>,
+}
+
+/// What each sheet of an XLSX workbook written from statements holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SheetLayout {
+    /// One sheet per statement.
+    #[default]
+    Statement,
+    /// One sheet per account, with its statements one after another.
+    Account,
+}
+
+impl std::str::FromStr for SheetLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "statement" => Ok(SheetLayout::Statement),
+            "account" => Ok(SheetLayout::Account),
+            _ => Err(format!(
+                "Unknown XLSX sheet layout: {}. Use 'statement' or 'account'",
+                s
+            )),
+        }
+    }
+}
+
+/// The typed value of a cell.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) enum Cell {
+    #[default]
+    Empty,
+    Text(String),
+    /// A decimal number, shown in the number format of `currency` if it has one.
+    Number {
+        value: String,
+        currency: Option<String>,
+    },
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    /// A formula, with the value it had when last computed.
+    Formula {
+        formula: String,
+        value: String,
+        currency: Option<String>,
+    },
+}
+
+impl Cell {
+    /// An amount cell, or an empty one without an amount.
+    pub(crate) fn amount(value: Option<String>, currency: Option<String>) -> Cell {
+        match value {
+            Some(value) => Cell::Number { value, currency },
+            None => Cell::Empty,
+        }
+    }
+
+    /// A text cell, or an empty one for empty text.
+    pub(crate) fn text(value: impl Into<String>) -> Cell {
+        let value = value.into();
+        if value.is_empty() {
+            Cell::Empty
+        } else {
+            Cell::Text(value)
+        }
+    }
+
+    /// The value as a CSV column would hold it: dates as `YYYY-MM-DD`,
+    /// numbers without the rounding noise of binary floating point.
+    pub(crate) fn csv_value(&self) -> String {
+        match self {
+            Cell::Empty => String::new(),
+            Cell::Text(text) => text.clone(),
+            Cell::Number { value, .. } | Cell::Formula { value, .. } => decimal(value),
+            Cell::Date(date) => date.format("%Y-%m-%d").to_string(),
+            Cell::DateTime(date_time) => date_time.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Five decimals at most, as ISO 20022 amounts have. Plain decimals are
+/// kept as written, others rounded without trailing zeros.
+fn decimal(value: &str) -> String {
+    let value = value.trim();
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    let digits = whole.strip_prefix('-').unwrap_or(whole);
+    if !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+        && fraction.len() <= 5
+    {
+        return value.to_string();
+    }
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() => {
+            let text = format!("{:.5}", number);
+            text.trim_end_matches('0').trim_end_matches('.').to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Day zero of the 1900 date system. Serial numbers from 61 on match
+/// Excel's, which counts a 29 February 1900 that never was.
+fn epoch(date1904: bool) -> NaiveDateTime {
+    let date = if date1904 {
+        NaiveDate::from_ymd_opt(1904, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(1899, 12, 30)
+    };
+    date.unwrap_or_default().and_time(Default::default())
+}
+
+/// The serial number of a date and time in the 1900 date system, days
+/// since the epoch with the time of day as fraction.
+pub(crate) fn date_serial(date_time: NaiveDateTime) -> f64 {
+    let elapsed = date_time - epoch(false);
+    elapsed.num_seconds() as f64 / 86_400.0
+}
+
+/// The date and time of a serial number, to the second.
+pub(crate) fn serial_date(serial: f64, date1904: bool) -> Option<NaiveDateTime> {
+    if !serial.is_finite() || serial < 0.0 {
+        return None;
+    }
+    let seconds = (serial * 86_400.0).round() as i64;
+    epoch(date1904).checked_add_signed(TimeDelta::try_seconds(seconds)?)
+}
+
+/// The letters of the 0-based column `index`, e.g. `AA` for 26.
+pub(crate) fn column_name(index: usize) -> String {
+    let mut name = Vec::new();
+    let mut number = index + 1;
+    while number > 0 {
+        let rest = (number - 1) % 26;
+        name.push(b'A' + rest as u8);
+        number = (number - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// The 0-based column and row of a cell reference such as `AB12`.
+pub(crate) fn cell_position(reference: &str) -> Option<(usize, usize)> {
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() || !letters.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    let column = letters
+        .bytes()
+        .fold(0usize, |column, b| column * 26 + (b - b'A') as usize + 1);
+    let row: usize = digits.parse().ok()?;
+    Some((column - 1, row.checked_sub(1)?))
+}
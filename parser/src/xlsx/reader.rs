@@ -0,0 +1,446 @@
+use crate::FinancialDataRead;
+use crate::ParserError;
+use crate::xlsx::format::{Cell, Sheet, Xlsx, cell_position, serial_date};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use quick_xml::escape::{resolve_predefined_entity, unescape};
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+fn xlsx_error(e: impl ToString) -> ParserError {
+    ParserError::Xlsx(e.to_string())
+}
+
+/// An element of a workbook part, by its local name.
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    /// Text directly inside the element.
+    text: String,
+}
+
+impl Element {
+    fn new(start: &BytesStart) -> Result<Self, ParserError> {
+        let mut attributes = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(xlsx_error)?;
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).into_owned();
+            let raw = String::from_utf8_lossy(&attribute.value).into_owned();
+            let value = unescape(&raw).map_err(xlsx_error)?.into_owned();
+            attributes.push((key, value));
+        }
+        Ok(Element {
+            name: String::from_utf8_lossy(start.local_name().as_ref()).into_owned(),
+            attributes,
+            ..Default::default()
+        })
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The text of a string item: its `t` element or the `t` elements of
+    /// its rich-text runs, without phonetic guides.
+    fn string_item(&self) -> String {
+        match self.child("t") {
+            Some(t) => t.text.clone(),
+            None => self
+                .children("r")
+                .filter_map(|run| run.child("t"))
+                .map(|t| t.text.as_str())
+                .collect(),
+        }
+    }
+}
+
+/// Parses an XML part into its root element.
+fn parse_part(xml: &str) -> Result<Element, ParserError> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut open: Vec<Element> = Vec::new();
+    loop {
+        match reader.read_event().map_err(xlsx_error)? {
+            Event::Start(start) => open.push(Element::new(&start)?),
+            Event::Empty(start) => {
+                let element = Element::new(&start)?;
+                match open.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::End(_) => {
+                let Some(element) = open.pop() else {
+                    continue;
+                };
+                match open.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Ok(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = open.last_mut() {
+                    element
+                        .text
+                        .push_str(&text.xml10_content().map_err(xlsx_error)?);
+                }
+            }
+            Event::CData(text) => {
+                if let Some(element) = open.last_mut() {
+                    element
+                        .text
+                        .push_str(&text.xml10_content().map_err(xlsx_error)?);
+                }
+            }
+            Event::GeneralRef(reference) => {
+                let resolved = match reference.resolve_char_ref().map_err(xlsx_error)? {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = String::from_utf8_lossy(&reference).into_owned();
+                        resolve_predefined_entity(&name)
+                            .ok_or_else(|| xlsx_error(format!("Unknown entity: &{};", name)))?
+                            .to_string()
+                    }
+                };
+                if let Some(element) = open.last_mut() {
+                    element.text.push_str(&resolved);
+                }
+            }
+            Event::Eof => return Err(xlsx_error("Unexpected end of an XML part")),
+            _ => {}
+        }
+    }
+}
+
+/// The parts of the workbook package.
+struct Package(ZipArchive<Cursor<Vec<u8>>>);
+
+impl Package {
+    fn part(&mut self, name: &str) -> Result<Option<Element>, ParserError> {
+        let mut file = match self.0.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(xlsx_error(e)),
+        };
+        let mut xml = String::new();
+        file.read_to_string(&mut xml).map_err(xlsx_error)?;
+        parse_part(&xml).map(Some)
+    }
+
+    fn required_part(&mut self, name: &str) -> Result<Element, ParserError> {
+        self.part(name)?
+            .ok_or_else(|| xlsx_error(format!("Missing part {}", name)))
+    }
+
+    /// The relationships of part `name`: id, type and resolved target.
+    fn relationships(&mut self, name: &str) -> Result<Vec<(String, String, String)>, ParserError> {
+        let (directory, file) = name.rsplit_once('/').unwrap_or(("", name));
+        let path = if directory.is_empty() {
+            format!("_rels/{}.rels", file)
+        } else {
+            format!("{}/_rels/{}.rels", directory, file)
+        };
+        let Some(relationships) = self.part(&path)? else {
+            return Ok(Vec::new());
+        };
+        Ok(relationships
+            .children("Relationship")
+            .filter_map(|relationship| {
+                let id = relationship.attribute("Id")?;
+                let kind = relationship.attribute("Type")?;
+                let target = resolve(directory, relationship.attribute("Target")?);
+                Some((id.to_string(), kind.to_string(), target))
+            })
+            .collect())
+    }
+}
+
+/// The part a relationship target of a part in `directory` refers to.
+fn resolve(directory: &str, target: &str) -> String {
+    let mut path: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => directory.split('/').filter(|s| !s.is_empty()).collect(),
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                path.pop();
+            }
+            segment => path.push(segment),
+        }
+    }
+    path.join("/")
+}
+
+/// How the number of a cell is shown.
+#[derive(Debug, Clone, PartialEq)]
+enum NumberFormat {
+    Number { currency: Option<String> },
+    Date,
+    DateTime,
+}
+
+impl NumberFormat {
+    fn built_in(id: u32) -> Self {
+        match id {
+            14..=17 => NumberFormat::Date,
+            18..=22 | 45..=47 => NumberFormat::DateTime,
+            _ => NumberFormat::Number { currency: None },
+        }
+    }
+
+    /// The kind of a format code, leaving out quoted text, `[...]` sections
+    /// and escaped characters; a quoted or `[$...]` currency code is kept.
+    fn of_code(code: &str) -> Self {
+        let mut plain = String::new();
+        let mut currency = None;
+        let mut chars = code.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => {
+                    let quoted: String = chars.by_ref().take_while(|c| *c != '"').collect();
+                    let quoted = quoted.trim();
+                    if quoted.len() == 3 && quoted.bytes().all(|b| b.is_ascii_uppercase()) {
+                        currency = Some(quoted.to_string());
+                    }
+                }
+                '[' => {
+                    let section: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                    let code = section.strip_prefix('$').and_then(|s| s.get(..3));
+                    if let Some(code) = code.filter(|c| c.bytes().all(|b| b.is_ascii_uppercase())) {
+                        currency = Some(code.to_string());
+                    }
+                }
+                '\\' => {
+                    chars.next();
+                }
+                c => plain.push(c.to_ascii_lowercase()),
+            }
+        }
+        if plain.contains(['h', 's']) {
+            NumberFormat::DateTime
+        } else if plain.contains(['y', 'd']) {
+            NumberFormat::Date
+        } else {
+            NumberFormat::Number { currency }
+        }
+    }
+}
+
+/// The number formats of the cell styles, by style index.
+fn cell_formats(styles: &Element) -> Vec<NumberFormat> {
+    let codes: HashMap<&str, &str> = styles
+        .child("numFmts")
+        .into_iter()
+        .flat_map(|formats| formats.children("numFmt"))
+        .filter_map(|format| {
+            Some((
+                format.attribute("numFmtId")?,
+                format.attribute("formatCode")?,
+            ))
+        })
+        .collect();
+    styles
+        .child("cellXfs")
+        .into_iter()
+        .flat_map(|formats| formats.children("xf"))
+        .map(|xf| {
+            let id = xf.attribute("numFmtId").unwrap_or("0");
+            match codes.get(id) {
+                Some(code) => NumberFormat::of_code(code),
+                None => NumberFormat::built_in(id.parse().unwrap_or(0)),
+            }
+        })
+        .collect()
+}
+
+/// What the cells of a sheet refer to: shared strings and styles.
+struct Context {
+    strings: Vec<String>,
+    formats: Vec<NumberFormat>,
+    date1904: bool,
+}
+
+impl Context {
+    fn cell(&self, c: &Element) -> Cell {
+        let value = c.child("v").map(|v| v.text.as_str()).unwrap_or_default();
+        match c.attribute("t").unwrap_or("n") {
+            "s" => {
+                let string = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| self.strings.get(i));
+                Cell::text(string.cloned().unwrap_or_default())
+            }
+            "inlineStr" => Cell::text(c.child("is").map(Element::string_item).unwrap_or_default()),
+            "b" => Cell::text(if value.trim() == "1" { "TRUE" } else { "FALSE" }),
+            "d" => date_text(value),
+            "str" | "e" => Cell::text(value),
+            _ if value.trim().is_empty() => Cell::Empty,
+            _ => {
+                let style = c.attribute("s").and_then(|s| s.parse::<usize>().ok());
+                let format = style.and_then(|s| self.formats.get(s));
+                let formula = c
+                    .child("f")
+                    .map(|f| f.text.clone())
+                    .filter(|f| !f.is_empty());
+                let serial = || serial_date(value.trim().parse().ok()?, self.date1904);
+                match (format, formula) {
+                    (Some(NumberFormat::Date), _) => serial()
+                        .map(|date_time| Cell::Date(date_time.date()))
+                        .unwrap_or_else(|| Cell::text(value)),
+                    (Some(NumberFormat::DateTime), _) => serial()
+                        .map(Cell::DateTime)
+                        .unwrap_or_else(|| Cell::text(value)),
+                    (format, formula) => {
+                        let currency = match format {
+                            Some(NumberFormat::Number { currency }) => currency.clone(),
+                            _ => None,
+                        };
+                        let value = value.trim().to_string();
+                        match formula {
+                            Some(formula) => Cell::Formula {
+                                formula,
+                                value,
+                                currency,
+                            },
+                            None => Cell::Number { value, currency },
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn sheet(&self, name: String, worksheet: &Element) -> Sheet {
+        let mut rows: Vec<Vec<Cell>> = Vec::new();
+        let rows_in_file = worksheet
+            .child("sheetData")
+            .into_iter()
+            .flat_map(|data| data.children("row"));
+        for row in rows_in_file {
+            let index = row
+                .attribute("r")
+                .and_then(|r| r.parse::<usize>().ok())
+                .and_then(|r| r.checked_sub(1))
+                .unwrap_or(rows.len());
+            if rows.len() <= index {
+                rows.resize(index + 1, Vec::new());
+            }
+            let cells = &mut rows[index];
+            for c in row.children("c") {
+                let column = c
+                    .attribute("r")
+                    .and_then(cell_position)
+                    .map_or(cells.len(), |(column, _)| column);
+                if cells.len() <= column {
+                    cells.resize(column + 1, Cell::Empty);
+                }
+                cells[column] = self.cell(c);
+            }
+            while cells.last() == Some(&Cell::Empty) {
+                cells.pop();
+            }
+        }
+        while rows.last().is_some_and(Vec::is_empty) {
+            rows.pop();
+        }
+        Sheet { name, rows }
+    }
+}
+
+/// A `t="d"` cell, an ISO 8601 date or date and time.
+fn date_text(value: &str) -> Cell {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Cell::Date(date);
+    }
+    let date_time = value.trim_end_matches('Z');
+    NaiveDateTime::parse_from_str(date_time, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(Cell::DateTime)
+        .unwrap_or_else(|_| Cell::text(value))
+}
+
+impl FinancialDataRead for Xlsx {
+    fn from_read<R: std::io::Read>(mut reader: R) -> Result<Self, ParserError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(xlsx_error)?;
+        let archive = ZipArchive::new(Cursor::new(bytes))
+            .map_err(|e| xlsx_error(format!("Not an XLSX workbook: {}", e)))?;
+        let mut package = Package(archive);
+
+        let workbook_name = package
+            .relationships("")?
+            .into_iter()
+            .find(|(_, kind, _)| kind.ends_with("/officeDocument"))
+            .map_or("xl/workbook.xml".to_string(), |(_, _, target)| target);
+        let workbook = package.required_part(&workbook_name)?;
+        let relationships = package.relationships(&workbook_name)?;
+        let target = |suffix: &str| {
+            relationships
+                .iter()
+                .find(|(_, kind, _)| kind.ends_with(suffix))
+                .map(|(_, _, target)| target.clone())
+        };
+
+        let strings = match target("/sharedStrings") {
+            Some(name) => package
+                .required_part(&name)?
+                .children("si")
+                .map(Element::string_item)
+                .collect(),
+            None => Vec::new(),
+        };
+        let formats = match target("/styles") {
+            Some(name) => cell_formats(&package.required_part(&name)?),
+            None => Vec::new(),
+        };
+        let date1904 = workbook
+            .child("workbookPr")
+            .and_then(|properties| properties.attribute("date1904"))
+            .is_some_and(|value| matches!(value, "1" | "true"));
+        let context = Context {
+            strings,
+            formats,
+            date1904,
+        };
+
+        let mut sheets = Vec::new();
+        let entries = workbook
+            .child("sheets")
+            .into_iter()
+            .flat_map(|sheets| sheets.children("sheet"));
+        for entry in entries {
+            // Chart sheets have no cells
+            let worksheet = relationships.iter().find(|(id, kind, _)| {
+                Some(id.as_str()) == entry.attribute("id") && kind.ends_with("/worksheet")
+            });
+            let Some((_, _, name)) = worksheet else {
+                continue;
+            };
+            let worksheet = package.required_part(name)?;
+            let name = entry.attribute("name").unwrap_or_default().to_string();
+            sheets.push(context.sheet(name, &worksheet));
+        }
+        if sheets.is_empty() {
+            return Err(xlsx_error("The workbook has no worksheets"));
+        }
+        Ok(Xlsx { sheets })
+    }
+}
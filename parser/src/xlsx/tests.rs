@@ -0,0 +1,159 @@
+use crate::ParserError;
+use crate::test_utils::read_test_bytes;
+use crate::traits::{FinancialDataRead, FinancialDataWrite};
+use crate::xlsx::format::{
+    Cell, Sheet, Xlsx, cell_position, column_name, date_serial, serial_date,
+};
+
+use chrono::NaiveDate;
+
+fn text(value: &str) -> Cell {
+    Cell::Text(value.to_string())
+}
+
+#[test]
+fn test_with_file() {
+    let xlsx = Xlsx::from_read(read_test_bytes("valid1.xlsx").as_slice()).unwrap();
+
+    assert_eq!(xlsx.sheets.len(), 2);
+    assert_eq!(xlsx.sheets[0].name, "Notes");
+    assert_eq!(xlsx.sheets[1].name, "October & more");
+    let rows = &xlsx.sheets[1].rows;
+    assert_eq!(rows.len(), 7);
+    assert_eq!(
+        rows[0],
+        vec![text("Account"), text("DE89370400440532013000")]
+    );
+    assert!(rows[1].is_empty());
+    assert_eq!(rows[2][0], text("booking_date"));
+    let date = NaiveDate::from_ymd_opt(2025, 10, 21).unwrap();
+    assert_eq!(rows[3][0], Cell::Date(date));
+    assert_eq!(
+        rows[3][2],
+        Cell::Number {
+            value: "1500".to_string(),
+            currency: Some("EUR".to_string()),
+        }
+    );
+    assert_eq!(rows[3][8], text("Invoice 4711"));
+    assert_eq!(rows[4][1], Cell::Empty);
+    assert_eq!(rows[4][2].csv_value(), "850.3");
+    assert_eq!(
+        rows[6][2],
+        Cell::Formula {
+            formula: "SUM(C4:C5)".to_string(),
+            value: "2350.3".to_string(),
+            currency: Some("EUR".to_string()),
+        }
+    );
+}
+
+#[test]
+fn test_read_write() {
+    let date = NaiveDate::from_ymd_opt(2025, 10, 21).unwrap();
+    let xlsx = Xlsx {
+        sheets: vec![
+            Sheet {
+                name: "Statement".to_string(),
+                rows: vec![
+                    vec![text("Account"), text(" Müller & Söhne <GmbH> ")],
+                    vec![],
+                    vec![
+                        Cell::Date(date),
+                        Cell::DateTime(date.and_hms_opt(14, 30, 5).unwrap()),
+                        Cell::Empty,
+                        Cell::Number {
+                            value: "-1234.56".to_string(),
+                            currency: Some("EUR".to_string()),
+                        },
+                        Cell::Number {
+                            value: "1000".to_string(),
+                            currency: Some("JPY".to_string()),
+                        },
+                        Cell::Number {
+                            value: "0.5".to_string(),
+                            currency: None,
+                        },
+                    ],
+                    vec![Cell::Formula {
+                        formula: "SUM(D3:D3)".to_string(),
+                        value: "-1234.56".to_string(),
+                        currency: Some("EUR".to_string()),
+                    }],
+                ],
+            },
+            Sheet {
+                name: "Empty".to_string(),
+                rows: Vec::new(),
+            },
+        ],
+    };
+
+    let mut data = Vec::new();
+    xlsx.write_to(&mut data).unwrap();
+    assert!(data.starts_with(b"PK\x03\x04"));
+    let result = Xlsx::from_read(data.as_slice()).unwrap();
+    assert_eq!(result, xlsx);
+}
+
+#[test]
+fn test_write_no_sheets() {
+    let mut data = Vec::new();
+    let result = Xlsx::default().write_to(&mut data);
+    assert!(matches!(result, Err(ParserError::Xlsx(_))));
+}
+
+#[test]
+fn test_not_an_xlsx() {
+    let result = Xlsx::from_read("booking_date,amount\n2025-10-21,1.00\n".as_bytes());
+    assert!(matches!(result, Err(ParserError::Xlsx(message)) if message.contains("Not an XLSX")));
+}
+
+#[test]
+fn test_csv_value() {
+    let number = |value: &str| Cell::Number {
+        value: value.to_string(),
+        currency: None,
+    };
+    assert_eq!(number("1500").csv_value(), "1500");
+    assert_eq!(number("-12.50").csv_value(), "-12.50");
+    assert_eq!(number("850.30000000000007").csv_value(), "850.3");
+    assert_eq!(number("1E-3").csv_value(), "0.001");
+    assert_eq!(number("abc").csv_value(), "abc");
+    let date = NaiveDate::from_ymd_opt(2025, 10, 21).unwrap();
+    assert_eq!(Cell::Date(date).csv_value(), "2025-10-21");
+    assert_eq!(
+        Cell::DateTime(date.and_hms_opt(8, 0, 0).unwrap()).csv_value(),
+        "2025-10-21T08:00:00"
+    );
+    assert_eq!(Cell::Empty.csv_value(), "");
+}
+
+#[test]
+fn test_date_serial() {
+    let date_time = NaiveDate::from_ymd_opt(2025, 10, 21)
+        .unwrap()
+        .and_hms_opt(12, 0, 0)
+        .unwrap();
+    assert_eq!(date_serial(date_time), 45951.5);
+    assert_eq!(serial_date(45951.5, false), Some(date_time));
+    let date1904 = NaiveDate::from_ymd_opt(1904, 1, 2).unwrap();
+    assert_eq!(
+        serial_date(1.0, true),
+        Some(date1904.and_hms_opt(0, 0, 0).unwrap())
+    );
+    assert_eq!(serial_date(-1.0, false), None);
+}
+
+#[test]
+fn test_cell_references() {
+    assert_eq!(column_name(0), "A");
+    assert_eq!(column_name(25), "Z");
+    assert_eq!(column_name(26), "AA");
+    assert_eq!(column_name(701), "ZZ");
+    assert_eq!(column_name(702), "AAA");
+    assert_eq!(cell_position("A1"), Some((0, 0)));
+    assert_eq!(cell_position("AB12"), Some((27, 11)));
+    assert_eq!(cell_position("12"), None);
+    assert_eq!(cell_position("A0"), None);
+}
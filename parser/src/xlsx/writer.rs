@@ -0,0 +1,466 @@
+use crate::FinancialDataWrite;
+use crate::ParserError;
+use crate::camt053::balance::currency_decimals;
+use crate::xlsx::format::{Cell, Sheet, Xlsx, column_name, date_serial};
+
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+use std::io::{Cursor, Write};
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+const MAIN_NAMESPACE: &str = "http://schemas.openxmlformats.org/spreadsheetml/2006/main";
+const RELATIONSHIPS_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const PACKAGE_RELATIONSHIPS_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/package/2006/relationships";
+const CONTENT_TYPES_NAMESPACE: &str =
+    "http://schemas.openxmlformats.org/package/2006/content-types";
+const CONTENT_TYPE_PREFIX: &str = "application/vnd.openxmlformats-officedocument.spreadsheetml";
+
+/// Cell styles of the workbook: the built-in ones, then one per currency.
+struct Styles {
+    currencies: Vec<String>,
+}
+
+impl Styles {
+    const DATE: usize = 1;
+    const DATE_TIME: usize = 2;
+    const NUMBER: usize = 3;
+    /// Number formats from 164 on are the workbook's own.
+    const FIRST_CUSTOM_FORMAT: usize = 164;
+
+    fn new(xlsx: &Xlsx) -> Self {
+        let mut currencies: Vec<String> = Vec::new();
+        let cells = xlsx
+            .sheets
+            .iter()
+            .flat_map(|sheet| sheet.rows.iter().flatten());
+        for cell in cells {
+            if let Cell::Number {
+                currency: Some(currency),
+                ..
+            }
+            | Cell::Formula {
+                currency: Some(currency),
+                ..
+            } = cell
+                && !currencies.contains(currency)
+            {
+                currencies.push(currency.clone());
+            }
+        }
+        Styles { currencies }
+    }
+
+    fn style(&self, cell: &Cell) -> usize {
+        match cell {
+            Cell::Date(_) => Self::DATE,
+            Cell::DateTime(_) => Self::DATE_TIME,
+            Cell::Number { currency, .. } | Cell::Formula { currency, .. } => currency
+                .as_ref()
+                .and_then(|currency| self.currencies.iter().position(|c| c == currency))
+                .map_or(Self::NUMBER, |index| Self::NUMBER + 1 + index),
+            Cell::Empty | Cell::Text(_) => 0,
+        }
+    }
+
+    /// The custom number formats: dates, then amounts in each currency
+    /// with the decimals of its minor unit, e.g. `#,##0.00 "EUR"`.
+    fn number_formats(&self) -> Vec<String> {
+        let currencies = self.currencies.iter().map(|currency| {
+            let decimals = "0".repeat(currency_decimals(currency) as usize);
+            let point = if decimals.is_empty() { "" } else { "." };
+            format!("#,##0{}{} \"{}\"", point, decimals, currency)
+        });
+        ["yyyy-mm-dd".to_string(), "yyyy-mm-dd hh:mm:ss".to_string()]
+            .into_iter()
+            .chain(currencies)
+            .collect()
+    }
+
+    fn to_xml(&self) -> std::io::Result<Vec<u8>> {
+        let formats = self.number_formats();
+        // General, the two date formats, #,##0.00, then the currencies
+        let cell_formats: Vec<usize> = [
+            0,
+            Self::FIRST_CUSTOM_FORMAT,
+            Self::FIRST_CUSTOM_FORMAT + 1,
+            4,
+        ]
+        .into_iter()
+        .chain((2..formats.len()).map(|index| Self::FIRST_CUSTOM_FORMAT + index))
+        .collect();
+
+        let mut writer = xml_writer()?;
+        writer
+            .create_element("styleSheet")
+            .with_attribute(("xmlns", MAIN_NAMESPACE))
+            .write_inner_content(|writer| {
+                writer
+                    .create_element("numFmts")
+                    .with_attribute(("count", formats.len().to_string().as_str()))
+                    .write_inner_content(|writer| {
+                        for (index, code) in formats.iter().enumerate() {
+                            let id = (Self::FIRST_CUSTOM_FORMAT + index).to_string();
+                            writer
+                                .create_element("numFmt")
+                                .with_attribute(("numFmtId", id.as_str()))
+                                .with_attribute(("formatCode", code.as_str()))
+                                .write_empty()?;
+                        }
+                        Ok(())
+                    })?;
+                writer
+                    .create_element("fonts")
+                    .with_attribute(("count", "1"))
+                    .write_inner_content(|writer| {
+                        writer
+                            .create_element("font")
+                            .write_inner_content(|writer| {
+                                writer
+                                    .create_element("sz")
+                                    .with_attribute(("val", "11"))
+                                    .write_empty()?;
+                                writer
+                                    .create_element("name")
+                                    .with_attribute(("val", "Calibri"))
+                                    .write_empty()?;
+                                Ok(())
+                            })?;
+                        Ok(())
+                    })?;
+                // The first two fills are reserved
+                writer
+                    .create_element("fills")
+                    .with_attribute(("count", "2"))
+                    .write_inner_content(|writer| {
+                        for pattern in ["none", "gray125"] {
+                            writer
+                                .create_element("fill")
+                                .write_inner_content(|writer| {
+                                    writer
+                                        .create_element("patternFill")
+                                        .with_attribute(("patternType", pattern))
+                                        .write_empty()?;
+                                    Ok(())
+                                })?;
+                        }
+                        Ok(())
+                    })?;
+                writer
+                    .create_element("borders")
+                    .with_attribute(("count", "1"))
+                    .write_inner_content(|writer| {
+                        writer.create_element("border").write_empty()?;
+                        Ok(())
+                    })?;
+                writer
+                    .create_element("cellStyleXfs")
+                    .with_attribute(("count", "1"))
+                    .write_inner_content(|writer| {
+                        writer
+                            .create_element("xf")
+                            .with_attribute(("numFmtId", "0"))
+                            .write_empty()?;
+                        Ok(())
+                    })?;
+                writer
+                    .create_element("cellXfs")
+                    .with_attribute(("count", cell_formats.len().to_string().as_str()))
+                    .write_inner_content(|writer| {
+                        for format in &cell_formats {
+                            let mut xf = writer
+                                .create_element("xf")
+                                .with_attribute(("numFmtId", format.to_string().as_str()))
+                                .with_attribute(("xfId", "0"));
+                            if *format != 0 {
+                                xf = xf.with_attribute(("applyNumberFormat", "1"));
+                            }
+                            xf.write_empty()?;
+                        }
+                        Ok(())
+                    })?;
+                Ok(())
+            })?;
+        Ok(writer.into_inner())
+    }
+}
+
+fn xml_writer() -> std::io::Result<Writer<Vec<u8>>> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Event::Decl(BytesDecl::new(
+        "1.0",
+        Some("UTF-8"),
+        Some("yes"),
+    )))?;
+    Ok(writer)
+}
+
+fn write_cell(
+    writer: &mut Writer<Vec<u8>>,
+    reference: &str,
+    style: usize,
+    cell: &Cell,
+) -> std::io::Result<()> {
+    let element = writer.create_element("c").with_attribute(("r", reference));
+    let element = if style == 0 {
+        element
+    } else {
+        element.with_attribute(("s", style.to_string().as_str()))
+    };
+    let serial = |serial: f64| serial.to_string();
+    match cell {
+        Cell::Empty => Ok(()),
+        Cell::Text(text) => {
+            element
+                .with_attribute(("t", "inlineStr"))
+                .write_inner_content(|writer| {
+                    writer.create_element("is").write_inner_content(|writer| {
+                        let t = writer.create_element("t");
+                        // Leading and trailing spaces and line breaks are otherwise dropped
+                        let t = if text.trim() != text || text.contains('\n') {
+                            t.with_attribute(("xml:space", "preserve"))
+                        } else {
+                            t
+                        };
+                        t.write_text_content(BytesText::new(text))?;
+                        Ok(())
+                    })?;
+                    Ok(())
+                })?;
+            Ok(())
+        }
+        Cell::Number { value, .. } => {
+            element.write_inner_content(|writer| {
+                writer
+                    .create_element("v")
+                    .write_text_content(BytesText::new(value))?;
+                Ok(())
+            })?;
+            Ok(())
+        }
+        Cell::Date(date) => {
+            let value = serial(date_serial(date.and_time(Default::default())));
+            element.write_inner_content(|writer| {
+                writer
+                    .create_element("v")
+                    .write_text_content(BytesText::new(&value))?;
+                Ok(())
+            })?;
+            Ok(())
+        }
+        Cell::DateTime(date_time) => {
+            let value = serial(date_serial(*date_time));
+            element.write_inner_content(|writer| {
+                writer
+                    .create_element("v")
+                    .write_text_content(BytesText::new(&value))?;
+                Ok(())
+            })?;
+            Ok(())
+        }
+        Cell::Formula { formula, value, .. } => {
+            element.write_inner_content(|writer| {
+                writer
+                    .create_element("f")
+                    .write_text_content(BytesText::new(formula))?;
+                writer
+                    .create_element("v")
+                    .write_text_content(BytesText::new(value))?;
+                Ok(())
+            })?;
+            Ok(())
+        }
+    }
+}
+
+fn worksheet(sheet: &Sheet, styles: &Styles) -> std::io::Result<Vec<u8>> {
+    let mut writer = xml_writer()?;
+    writer
+        .create_element("worksheet")
+        .with_attribute(("xmlns", MAIN_NAMESPACE))
+        .write_inner_content(|writer| {
+            writer
+                .create_element("sheetData")
+                .write_inner_content(|writer| {
+                    for (row_index, row) in sheet.rows.iter().enumerate() {
+                        if row.iter().all(|cell| *cell == Cell::Empty) {
+                            continue;
+                        }
+                        let number = (row_index + 1).to_string();
+                        writer
+                            .create_element("row")
+                            .with_attribute(("r", number.as_str()))
+                            .write_inner_content(|writer| {
+                                for (column, cell) in row.iter().enumerate() {
+                                    let reference = format!("{}{}", column_name(column), number);
+                                    write_cell(writer, &reference, styles.style(cell), cell)?;
+                                }
+                                Ok(())
+                            })?;
+                    }
+                    Ok(())
+                })?;
+            Ok(())
+        })?;
+    Ok(writer.into_inner())
+}
+
+fn workbook(xlsx: &Xlsx) -> std::io::Result<Vec<u8>> {
+    let mut writer = xml_writer()?;
+    writer
+        .create_element("workbook")
+        .with_attribute(("xmlns", MAIN_NAMESPACE))
+        .with_attribute(("xmlns:r", RELATIONSHIPS_NAMESPACE))
+        .write_inner_content(|writer| {
+            writer
+                .create_element("sheets")
+                .write_inner_content(|writer| {
+                    for (index, sheet) in xlsx.sheets.iter().enumerate() {
+                        let id = (index + 1).to_string();
+                        writer
+                            .create_element("sheet")
+                            .with_attribute(("name", sheet.name.as_str()))
+                            .with_attribute(("sheetId", id.as_str()))
+                            .with_attribute(("r:id", format!("rId{}", id).as_str()))
+                            .write_empty()?;
+                    }
+                    Ok(())
+                })?;
+            Ok(())
+        })?;
+    Ok(writer.into_inner())
+}
+
+/// Relationships of `(type, target)`, with ids `rId1` on.
+fn relationships(targets: &[(String, String)]) -> std::io::Result<Vec<u8>> {
+    let mut writer = xml_writer()?;
+    writer
+        .create_element("Relationships")
+        .with_attribute(("xmlns", PACKAGE_RELATIONSHIPS_NAMESPACE))
+        .write_inner_content(|writer| {
+            for (index, (kind, target)) in targets.iter().enumerate() {
+                writer
+                    .create_element("Relationship")
+                    .with_attribute(("Id", format!("rId{}", index + 1).as_str()))
+                    .with_attribute((
+                        "Type",
+                        format!("{}/{}", RELATIONSHIPS_NAMESPACE, kind).as_str(),
+                    ))
+                    .with_attribute(("Target", target.as_str()))
+                    .write_empty()?;
+            }
+            Ok(())
+        })?;
+    Ok(writer.into_inner())
+}
+
+fn content_types(sheets: usize) -> std::io::Result<Vec<u8>> {
+    let parts = [
+        ("/xl/workbook.xml".to_string(), "sheet.main+xml"),
+        ("/xl/styles.xml".to_string(), "styles+xml"),
+    ]
+    .into_iter()
+    .chain((1..=sheets).map(|index| {
+        (
+            format!("/xl/worksheets/sheet{}.xml", index),
+            "worksheet+xml",
+        )
+    }));
+
+    let mut writer = xml_writer()?;
+    writer
+        .create_element("Types")
+        .with_attribute(("xmlns", CONTENT_TYPES_NAMESPACE))
+        .write_inner_content(|writer| {
+            writer
+                .create_element("Default")
+                .with_attribute(("Extension", "rels"))
+                .with_attribute((
+                    "ContentType",
+                    "application/vnd.openxmlformats-package.relationships+xml",
+                ))
+                .write_empty()?;
+            writer
+                .create_element("Default")
+                .with_attribute(("Extension", "xml"))
+                .with_attribute(("ContentType", "application/xml"))
+                .write_empty()?;
+            for (part, kind) in parts {
+                writer
+                    .create_element("Override")
+                    .with_attribute(("PartName", part.as_str()))
+                    .with_attribute((
+                        "ContentType",
+                        format!("{}.{}", CONTENT_TYPE_PREFIX, kind).as_str(),
+                    ))
+                    .write_empty()?;
+            }
+            Ok(())
+        })?;
+    Ok(writer.into_inner())
+}
+
+impl Xlsx {
+    fn to_bytes(&self) -> Result<Vec<u8>, ParserError> {
+        let xlsx_error = |e: std::io::Error| ParserError::Xlsx(e.to_string());
+        if self.sheets.is_empty() {
+            return Err(ParserError::Xlsx(
+                "A workbook needs at least one sheet".to_string(),
+            ));
+        }
+        let styles = Styles::new(self);
+
+        let mut workbook_relationships: Vec<(String, String)> = (1..=self.sheets.len())
+            .map(|index| {
+                let target = format!("worksheets/sheet{}.xml", index);
+                ("worksheet".to_string(), target)
+            })
+            .collect();
+        workbook_relationships.push(("styles".to_string(), "styles.xml".to_string()));
+        let package_relationships = [("officeDocument".to_string(), "xl/workbook.xml".to_string())];
+
+        let mut parts = vec![
+            (
+                "[Content_Types].xml".to_string(),
+                content_types(self.sheets.len()),
+            ),
+            (
+                "_rels/.rels".to_string(),
+                relationships(&package_relationships),
+            ),
+            ("xl/workbook.xml".to_string(), workbook(self)),
+            (
+                "xl/_rels/workbook.xml.rels".to_string(),
+                relationships(&workbook_relationships),
+            ),
+            ("xl/styles.xml".to_string(), styles.to_xml()),
+        ];
+        for (index, sheet) in self.sheets.iter().enumerate() {
+            let name = format!("xl/worksheets/sheet{}.xml", index + 1);
+            parts.push((name, worksheet(sheet, &styles)));
+        }
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        for (name, data) in parts {
+            let data = data.map_err(xlsx_error)?;
+            zip.start_file(name, options)
+                .map_err(|e| ParserError::Xlsx(e.to_string()))?;
+            zip.write_all(&data).map_err(xlsx_error)?;
+        }
+        let cursor = zip.finish().map_err(|e| ParserError::Xlsx(e.to_string()))?;
+        Ok(cursor.into_inner())
+    }
+}
+
+impl FinancialDataWrite for Xlsx {
+    fn write_to<W: std::io::Write>(&self, mut writer: W) -> Result<(), ParserError> {
+        let data = self.to_bytes()?;
+        writer.write_all(&data)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use crate::ParserError;
+use crate::xml::options::is_namespace_declaration;
+use quick_xml::Reader;
+use quick_xml::escape::{resolve_predefined_entity, unescape};
+use quick_xml::events::{BytesStart, Event};
+use std::collections::HashMap;
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// Namespace declarations of one element: prefix (`""` for the default namespace) → URI.
+type Scope = HashMap<String, String>;
+
+/// Serializes `xml` in Exclusive XML Canonicalization form without comments
+/// (<https://www.w3.org/TR/xml-exc-c14n/>).
+///
+/// - no XML declaration or DOCTYPE, comments are dropped;
+/// - empty elements are written as start/end tag pairs;
+/// - character and entity references are expanded, CDATA becomes text;
+/// - namespace declarations are rendered only where visibly utilized
+///   and not already in effect on an output ancestor;
+/// - namespace declarations are sorted by prefix, attributes by (namespace URI, local name).
+pub(crate) fn exclusive_c14n(xml: &str) -> Result<String, ParserError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().expand_empty_elements = true;
+
+    let mut output = String::with_capacity(xml.len());
+    // Declarations in scope in the input document
+    let mut input_scopes: Vec<Scope> = vec![];
+    // Declarations already rendered on output ancestors
+    let mut rendered_scopes: Vec<Scope> = vec![];
+    let mut depth = 0usize;
+    let mut root_closed = false;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| ParserError::Xml(e.to_string()))?;
+        match event {
+            Event::Eof => break,
+            Event::Start(e) => {
+                write_start_tag(&e, &mut input_scopes, &mut rendered_scopes, &mut output)?;
+                depth += 1;
+            }
+            Event::End(e) => {
+                output.push_str("</");
+                output.push_str(&utf8(e.name().as_ref())?);
+                output.push('>');
+                input_scopes.pop();
+                rendered_scopes.pop();
+                depth = depth.saturating_sub(1);
+                root_closed = depth == 0;
+            }
+            Event::Text(e) if depth > 0 => {
+                let text = e
+                    .xml10_content()
+                    .map_err(|e| ParserError::Xml(e.to_string()))?;
+                escape_text(&text, &mut output);
+            }
+            Event::CData(e) if depth > 0 => {
+                let text = e
+                    .xml10_content()
+                    .map_err(|e| ParserError::Xml(e.to_string()))?;
+                escape_text(&text, &mut output);
+            }
+            Event::GeneralRef(e) if depth > 0 => {
+                let resolved = match e
+                    .resolve_char_ref()
+                    .map_err(|e| ParserError::Xml(e.to_string()))?
+                {
+                    Some(c) => c.to_string(),
+                    None => {
+                        let name = utf8(&e)?;
+                        resolve_predefined_entity(&name)
+                            .ok_or_else(|| {
+                                ParserError::Xml(format!("Unknown entity reference: &{};", name))
+                            })?
+                            .to_string()
+                    }
+                };
+                escape_text(&resolved, &mut output);
+            }
+            Event::PI(e) => {
+                if depth == 0 && root_closed {
+                    output.push('\n');
+                }
+                output.push_str("<?");
+                output.push_str(&utf8(&e)?);
+                output.push_str("?>");
+                if depth == 0 && !root_closed {
+                    output.push('\n');
+                }
+            }
+            // Declaration, DOCTYPE, comments and whitespace outside the root element
+            _ => {}
+        }
+    }
+
+    Ok(output)
+}
+
+fn write_start_tag(
+    start: &BytesStart,
+    input_scopes: &mut Vec<Scope>,
+    rendered_scopes: &mut Vec<Scope>,
+    output: &mut String,
+) -> Result<(), ParserError> {
+    let name = utf8(start.name().as_ref())?;
+
+    let mut declared = Scope::new();
+    let mut attributes: Vec<(String, String)> = vec![];
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| ParserError::Xml(e.to_string()))?;
+        let key = utf8(attr.key.as_ref())?;
+        let value = normalize_attribute_value(&utf8(&attr.value)?)?;
+        if is_namespace_declaration(attr.key.as_ref()) {
+            let prefix = key.strip_prefix("xmlns:").unwrap_or("").to_string();
+            declared.insert(prefix, value);
+        } else {
+            attributes.push((key, value));
+        }
+    }
+    input_scopes.push(declared);
+
+    // Prefixes visibly utilized by the element and its attributes
+    let mut utilized = vec![prefix_of(&name).unwrap_or("").to_string()];
+    for (key, _) in &attributes {
+        if let Some(prefix) = prefix_of(key)
+            && prefix != "xml"
+            && !utilized.iter().any(|p| p == prefix)
+        {
+            utilized.push(prefix.to_string());
+        }
+    }
+
+    let mut rendered = Scope::new();
+    for prefix in utilized {
+        let uri = lookup(input_scopes, &prefix);
+        if uri.is_none() && !prefix.is_empty() {
+            return Err(ParserError::Xml(format!(
+                "Unbound namespace prefix: {}",
+                prefix
+            )));
+        }
+        let uri = uri.unwrap_or_default();
+        let in_effect = lookup(rendered_scopes, &prefix).unwrap_or_default();
+        if uri != in_effect {
+            rendered.insert(prefix, uri);
+        }
+    }
+
+    let mut namespaces: Vec<(&String, &String)> = rendered.iter().collect();
+    namespaces.sort();
+
+    let mut sorted_attributes: Vec<(String, &str, &String, &String)> = attributes
+        .iter()
+        .map(|(key, value)| {
+            let (uri, local) = match key.split_once(':') {
+                Some(("xml", local)) => (XML_NAMESPACE.to_string(), local),
+                Some((prefix, local)) => (lookup(input_scopes, prefix).unwrap_or_default(), local),
+                None => (String::new(), key.as_str()),
+            };
+            (uri, local, key, value)
+        })
+        .collect();
+    sorted_attributes.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+
+    output.push('<');
+    output.push_str(&name);
+    for (prefix, uri) in namespaces {
+        if prefix.is_empty() {
+            output.push_str(" xmlns=\"");
+        } else {
+            output.push_str(" xmlns:");
+            output.push_str(prefix);
+            output.push_str("=\"");
+        }
+        escape_attribute(uri, output);
+        output.push('"');
+    }
+    for (_, _, key, value) in sorted_attributes {
+        output.push(' ');
+        output.push_str(key);
+        output.push_str("=\"");
+        escape_attribute(value, output);
+        output.push('"');
+    }
+    output.push('>');
+
+    rendered_scopes.push(rendered);
+    Ok(())
+}
+
+fn lookup(scopes: &[Scope], prefix: &str) -> Option<String> {
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| scope.get(prefix))
+        .cloned()
+}
+
+fn prefix_of(qname: &str) -> Option<&str> {
+    qname.split_once(':').map(|(prefix, _)| prefix)
+}
+
+fn utf8(bytes: &[u8]) -> Result<String, ParserError> {
+    std::str::from_utf8(bytes)
+        .map(str::to_string)
+        .map_err(|e| ParserError::Xml(e.to_string()))
+}
+
+// Attribute-value normalization of an XML 1.0 parser: literal whitespace
+// characters become spaces, references are expanded afterwards.
+fn normalize_attribute_value(raw: &str) -> Result<String, ParserError> {
+    let normalized = raw.replace("\r\n", " ").replace(['\r', '\n', '\t'], " ");
+    unescape(&normalized)
+        .map(|v| v.into_owned())
+        .map_err(|e| ParserError::Xml(e.to_string()))
+}
+
+fn escape_text(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '\r' => output.push_str("&#xD;"),
+            other => output.push(other),
+        }
+    }
+}
+
+fn escape_attribute(value: &str, output: &mut String) {
+    for c in value.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '"' => output.push_str("&quot;"),
+            '\t' => output.push_str("&#x9;"),
+            '\n' => output.push_str("&#xA;"),
+            '\r' => output.push_str("&#xD;"),
+            other => output.push(other),
+        }
+    }
+}
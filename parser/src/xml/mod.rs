@@ -1,4 +1,6 @@
+pub(crate) mod canonical;
 pub(crate) mod format;
+pub(crate) mod options;
 pub(crate) mod reader;
 pub(crate) mod writer;
 
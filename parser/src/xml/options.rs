@@ -0,0 +1,191 @@
+use crate::ParserError;
+use crate::xml::canonical::exclusive_c14n;
+use quick_xml::Reader;
+use quick_xml::Writer;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesDecl, BytesStart, Event};
+use std::io::Write;
+
+/// Formatting options for XML output.
+///
+/// Applies to every XML-producing format: **CAMT.053**, the MT940 XML
+/// representation and generic **XML**. The default leaves the serializer
+/// output untouched.
+///
+/// # Example
+///
+/// ```
+/// use parser::XmlWriteOptions;
+///
+/// let options = XmlWriteOptions::new().indent(2).declaration(true);
+/// let pretty = options.apply("<Document><GrpHdr/></Document>")?;
+/// assert_eq!(
+///     pretty,
+///     "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Document>\n  <GrpHdr/>\n</Document>"
+/// );
+/// # Ok::<(), parser::ParserError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmlWriteOptions {
+    indent: usize,
+    trim_text: bool,
+    declaration: bool,
+    sort_attributes: bool,
+    canonical: bool,
+}
+
+impl XmlWriteOptions {
+    /// Creates options that leave the XML output unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indents nested elements by `size` spaces. `0` disables indentation.
+    ///
+    /// Whitespace-only text between elements is replaced by the
+    /// indentation; other text is kept as it is, see [`trim_text`](Self::trim_text).
+    pub fn indent(mut self, size: usize) -> Self {
+        self.indent = size;
+        self
+    }
+
+    /// Removes leading and trailing whitespace from text nodes.
+    ///
+    /// This changes element content, e.g. of a padded remittance line, so
+    /// it is off unless asked for, also with [`indent`](Self::indent).
+    pub fn trim_text(mut self, enabled: bool) -> Self {
+        self.trim_text = enabled;
+        self
+    }
+
+    /// Prepends `<?xml version="1.0" encoding="UTF-8"?>`, replacing any existing declaration.
+    pub fn declaration(mut self, enabled: bool) -> Self {
+        self.declaration = enabled;
+        self
+    }
+
+    /// Orders attributes by name, with namespace declarations first.
+    pub fn sort_attributes(mut self, enabled: bool) -> Self {
+        self.sort_attributes = enabled;
+        self
+    }
+
+    /// Produces Exclusive XML Canonicalization (without comments).
+    ///
+    /// Canonical output has no XML declaration and keeps whitespace as it is,
+    /// so [`indent`](Self::indent) and [`declaration`](Self::declaration) are ignored.
+    pub fn canonical(mut self, enabled: bool) -> Self {
+        self.canonical = enabled;
+        self
+    }
+
+    /// Returns `true` if the options do not change the XML output.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Reformats an XML document according to the options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParserError::Xml`] if the input is not well-formed XML.
+    pub fn apply(&self, xml: &str) -> Result<String, ParserError> {
+        if self.canonical {
+            return exclusive_c14n(xml);
+        }
+        if self.is_default() {
+            return Ok(xml.to_string());
+        }
+        self.reformat(xml)
+    }
+
+    fn reformat(&self, xml: &str) -> Result<String, ParserError> {
+        let mut reader = Reader::from_str(xml);
+        reader.config_mut().trim_text(self.trim_text);
+
+        let mut writer = if self.indent > 0 {
+            Writer::new_with_indent(Vec::new(), b' ', self.indent)
+        } else {
+            Writer::new(Vec::new())
+        };
+
+        if self.declaration {
+            writer
+                .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+                .map_err(|e| ParserError::Xml(e.to_string()))?;
+            if self.indent == 0 {
+                writer.get_mut().write_all(b"\n")?;
+            }
+        }
+
+        // Whitespace-only text is held back until the next event shows
+        // whether it is formatting between elements or the content of one
+        let mut pending_space: Option<Event> = None;
+        let mut after_markup = true;
+        let mut after_start = false;
+        loop {
+            let event = reader
+                .read_event()
+                .map_err(|e| ParserError::Xml(e.to_string()))?;
+            let is_content = matches!(
+                event,
+                Event::Text(_) | Event::GeneralRef(_) | Event::CData(_)
+            );
+            if let Event::Text(text) = &event
+                && self.indent > 0
+                && after_markup
+                && text.iter().all(u8::is_ascii_whitespace)
+            {
+                pending_space = Some(event.into_owned());
+                continue;
+            }
+            if let Some(space) = pending_space.take()
+                && (is_content || (after_start && matches!(event, Event::End(_))))
+            {
+                writer
+                    .write_event(space)
+                    .map_err(|e| ParserError::Xml(e.to_string()))?;
+            }
+            after_markup = !is_content;
+            after_start = matches!(event, Event::Start(_));
+            let event = match event {
+                Event::Eof => break,
+                Event::Decl(_) if self.declaration => continue,
+                Event::Start(e) if self.sort_attributes => Event::Start(sorted_attributes(&e)?),
+                Event::Empty(e) if self.sort_attributes => Event::Empty(sorted_attributes(&e)?),
+                other => other,
+            };
+            writer
+                .write_event(event)
+                .map_err(|e| ParserError::Xml(e.to_string()))?;
+        }
+
+        String::from_utf8(writer.into_inner()).map_err(|e| ParserError::Xml(e.to_string()))
+    }
+}
+
+fn sorted_attributes(start: &BytesStart) -> Result<BytesStart<'static>, ParserError> {
+    let mut attributes = start
+        .attributes()
+        .collect::<Result<Vec<Attribute>, _>>()
+        .map_err(|e| ParserError::Xml(e.to_string()))?;
+
+    // xmlns and xmlns:* go first, everything else by qualified name
+    attributes.sort_by(|a, b| {
+        let a_ns = is_namespace_declaration(a.key.as_ref());
+        let b_ns = is_namespace_declaration(b.key.as_ref());
+        b_ns.cmp(&a_ns)
+            .then_with(|| a.key.as_ref().cmp(b.key.as_ref()))
+    });
+
+    let name = std::str::from_utf8(start.name().as_ref())
+        .map_err(|e| ParserError::Xml(e.to_string()))?
+        .to_string();
+    Ok(BytesStart::new(name)
+        .with_attributes(attributes)
+        .into_owned())
+}
+
+pub(crate) fn is_namespace_declaration(key: &[u8]) -> bool {
+    key == b"xmlns" || key.starts_with(b"xmlns:")
+}
@@ -49,3 +49,87 @@ fn test_read_write() {
     std::fs::remove_file(&new_file_path).unwrap();
     assert_eq!(xml, read_xml);
 }
+
+#[test]
+fn test_write_options_indent_and_declaration() {
+    let xml = "<Document><GrpHdr><MsgId>1</MsgId></GrpHdr></Document>";
+    let options = XmlWriteOptions::new().indent(2).declaration(true);
+
+    let result = options.apply(xml).unwrap();
+    assert_eq!(
+        result,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <Document>\n  <GrpHdr>\n    <MsgId>1</MsgId>\n  </GrpHdr>\n</Document>"
+    );
+}
+
+#[test]
+fn test_write_options_indent_keeps_text() {
+    let xml = "<Document>\n <Ustrd>  Invoice &amp; fee </Ustrd> <Nm> </Nm>\n</Document>";
+
+    let result = XmlWriteOptions::new().indent(2).apply(xml).unwrap();
+    assert_eq!(
+        result,
+        "<Document>\n  <Ustrd>  Invoice &amp; fee </Ustrd>\n  <Nm> </Nm>\n</Document>"
+    );
+
+    let options = XmlWriteOptions::new().indent(2).trim_text(true);
+    let result = options.apply(xml).unwrap();
+    assert_eq!(
+        result,
+        "<Document>\n  <Ustrd>Invoice&amp;fee</Ustrd>\n  <Nm>\n  </Nm>\n</Document>"
+    );
+}
+
+#[test]
+fn test_write_options_sort_attributes() {
+    let xml = r#"<Amt b="2" Ccy="EUR" xmlns="urn:x" a="1">10</Amt>"#;
+    let options = XmlWriteOptions::new().sort_attributes(true);
+
+    let result = options.apply(xml).unwrap();
+    assert_eq!(
+        result,
+        r#"<Amt xmlns="urn:x" Ccy="EUR" a="1" b="2">10</Amt>"#
+    );
+}
+
+#[test]
+fn test_write_options_default_is_identity() {
+    let xml = "<a  x='1'><b/></a>";
+    assert_eq!(XmlWriteOptions::new().apply(xml).unwrap(), xml);
+}
+
+#[test]
+fn test_exclusive_canonicalization() {
+    let xml = "<?xml version=\"1.0\"?>\n\
+        <!-- comment -->\n\
+        <doc xmlns=\"urn:a\" xmlns:unused=\"urn:u\" xmlns:p=\"urn:p\">\
+        <e2 b='2' p:a=\"&#x31;\" a=\"x&amp;y\"/>\
+        <p:e3><![CDATA[<1>]]> &amp; &#65;</p:e3>\
+        <e4 xmlns=\"urn:a\"/>\
+        </doc>";
+    let options = XmlWriteOptions::new().canonical(true).indent(4);
+
+    let result = options.apply(xml).unwrap();
+    assert_eq!(
+        result,
+        "<doc xmlns=\"urn:a\">\
+        <e2 xmlns:p=\"urn:p\" a=\"x&amp;y\" b=\"2\" p:a=\"1\"></e2>\
+        <p:e3 xmlns:p=\"urn:p\">&lt;1&gt; &amp; A</p:e3>\
+        <e4></e4>\
+        </doc>"
+    );
+}
+
+#[test]
+fn test_exclusive_canonicalization_resets_default_namespace() {
+    let xml = r#"<a xmlns="urn:a"><b xmlns=""><c/></b></a>"#;
+    let result = XmlWriteOptions::new().canonical(true).apply(xml).unwrap();
+    assert_eq!(result, r#"<a xmlns="urn:a"><b xmlns=""><c></c></b></a>"#);
+}
+
+#[test]
+fn test_exclusive_canonicalization_invalid_xml() {
+    let result = XmlWriteOptions::new().canonical(true).apply("<a><b></a>");
+    assert!(result.is_err());
+}